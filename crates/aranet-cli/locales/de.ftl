@@ -0,0 +1,130 @@
+# German messages for the TUI and GUI.
+#
+# Messages missing here fall back to en.ftl.
+
+## Tabs
+
+tab-dashboard = Übersicht
+tab-history = Verlauf
+tab-settings = Einstellungen
+tab-service = Dienst
+
+## TUI header bar
+
+header-online = { $connected }/{ $total } online
+header-alerts = Warnungen { $count }
+
+## GUI navigation bar
+
+nav-press-key = Taste { $key }
+nav-toggle-theme = T drücken, um das Design zu wechseln
+nav-toggle-auto-refresh = A drücken, um die automatische Aktualisierung umzuschalten
+nav-auto-on = Auto an
+nav-auto-off = Auto aus
+
+## TUI keyboard shortcut overlay
+
+help-section-navigation = Navigation
+help-section-views = Ansichten
+help-section-devices = Geräte
+help-section-charts = Diagramme
+help-section-alerts = Warnungen
+help-section-settings = Einstellungen
+help-section-other = Sonstiges
+help-next-prev-tab = Nächster/vorheriger Tab
+help-next-prev-device = Nächstes/vorheriges Gerät
+help-connect-disconnect = Verbinden/Trennen
+help-scroll-history = Verlauf blättern
+help-toggle-help = Hilfe ein/aus
+help-full-screen-chart = Diagramm im Vollbild
+help-comparison-view = Vergleichsansicht
+help-alert-history = Warnungsverlauf
+help-toggle-sidebar = Seitenleiste ein/aus
+help-toggle-sidebar-width = Seitenleistenbreite ändern
+help-scan-for-devices = Nach Geräten suchen
+help-refresh-reading = Messwert aktualisieren
+help-sync-history = Verlauf synchronisieren
+help-connect-all = Alle verbinden
+help-set-device-alias = Gerätealias festlegen
+help-cycle-device-filter = Gerätefilter wechseln
+help-toggle-device-group = Gerätegruppe ein-/ausklappen
+help-dismiss-alert = Warnung schließen
+help-do-not-disturb = Nicht stören
+help-adjust-thresholds = Grenzwerte anpassen
+help-toggle-theme = Design wechseln
+help-export-history = Verlauf exportieren
+help-copy-history-summary = Verlaufsübersicht kopieren
+help-show-error-details = Fehlerdetails anzeigen
+help-quit = Beenden
+help-close = { $key } oder Esc zum Schließen
+help-title = Tastenkürzel
+
+## TUI settings panel
+
+tui-settings-title = Einstellungen
+tui-settings-select-device = Gerät auswählen, um Einstellungen anzuzeigen
+tui-settings-device-id = Geräte-ID
+tui-settings-name = Name
+tui-settings-type = Typ
+tui-settings-signal = Signal
+tui-settings-connected = Verbunden
+tui-settings-interval = Intervall
+tui-settings-battery = Batterie
+tui-settings-enabled = Aktiviert
+tui-settings-disabled = Deaktiviert
+tui-settings-alert-thresholds = Warngrenzen
+tui-settings-device-settings = Geräteeinstellungen
+tui-settings-enter-to-change = Enter zum Ändern
+tui-settings-adjust = +/- zum Anpassen
+tui-settings-connect-to-view = Verbinden, um Geräteeinstellungen anzuzeigen
+
+## GUI menu bar
+
+menu-file = Ablage
+menu-scan-for-devices = Nach Geräten suchen
+menu-refresh-all = Alle aktualisieren
+menu-export-history = Verlauf exportieren
+menu-export-as-csv = Als CSV exportieren …
+menu-export-as-json = Als JSON exportieren …
+menu-edit = Bearbeiten
+menu-view = Darstellung
+menu-dashboard = Übersicht
+menu-history = Verlauf
+menu-settings = Einstellungen
+menu-service = Dienst
+menu-appearance = Erscheinungsbild
+menu-display = Anzeige
+menu-temperature = Temperatur
+menu-humidity = Luftfeuchtigkeit
+menu-pressure = Luftdruck
+menu-device = Gerät
+menu-no-devices = Keine Geräte
+menu-window = Fenster
+menu-help = Hilfe
+
+## GUI application settings
+
+settings-title = Programmeinstellungen
+settings-theme = Design
+settings-theme-hint = Helles, dunkles oder System-Erscheinungsbild wählen
+settings-compact-mode = Kompaktmodus
+settings-desktop-notifications = Desktop-Mitteilungen
+settings-do-not-disturb = Nicht stören
+settings-display-units = Anzeigeeinheiten
+settings-temperature = Temperatur
+settings-pressure = Luftdruck
+settings-radon = Radon
+settings-override-units = Geräteeinheiten überschreiben
+settings-alert-thresholds = Warngrenzen
+settings-behavior = Verhalten
+settings-on = An
+settings-off = Aus
+settings-dark = Dunkel
+settings-light = Hell
+settings-system = System
+settings-accent-color = Akzentfarbe
+settings-accent-color-hint = Farbe von Schaltflächen und Hervorhebungen
+settings-chart-colors = Diagrammfarben
+settings-chart-colors-hint = Linienfarben für CO2, Temperatur, Luftfeuchtigkeit und Luftdruck
+settings-language = Sprache
+settings-language-hint = Menüs werden nach einem Neustart aktualisiert
@@ -0,0 +1,218 @@
+# English messages for the TUI and GUI.
+#
+# This is the reference language: every message ID used in the code must be
+# defined here. Other languages may leave messages out; they fall back to these.
+
+## Tabs
+
+tab-dashboard = Dashboard
+tab-history = History
+tab-settings = Settings
+tab-service = Service
+
+## TUI header bar
+
+header-online = { $connected }/{ $total } online
+header-alerts = Alerts { $count }
+
+## GUI navigation bar
+
+nav-press-key = Press { $key }
+nav-toggle-theme = Press T to toggle theme
+nav-toggle-auto-refresh = Press A to toggle auto-refresh
+nav-auto-on = Auto On
+nav-auto-off = Auto Off
+
+## TUI keyboard shortcut overlay
+
+help-section-navigation = Navigation
+help-section-views = Views
+help-section-devices = Devices
+help-section-charts = Charts
+help-section-alerts = Alerts
+help-section-settings = Settings
+help-section-other = Other
+help-next-prev-tab = Next/Prev tab
+help-next-prev-device = Next/Prev device
+help-connect-disconnect = Connect/Disconnect
+help-scroll-history = Scroll history
+help-toggle-help = Toggle help
+help-full-screen-chart = Full-screen chart
+help-comparison-view = Comparison view
+help-alert-history = Alert history
+help-toggle-sidebar = Toggle sidebar
+help-toggle-sidebar-width = Toggle sidebar width
+help-scan-for-devices = Scan for devices
+help-refresh-reading = Refresh reading
+help-sync-history = Sync history
+help-connect-all = Connect all
+help-set-device-alias = Set device alias
+help-cycle-device-filter = Cycle device filter
+help-toggle-device-group = Collapse/expand device group
+help-toggle-temp-on-chart = Toggle temp on chart
+help-toggle-humidity-on-chart = Toggle humidity on chart
+help-time-filter-history = Time filter (History)
+help-dismiss-alert = Dismiss alert
+help-toggle-sticky-alerts = Toggle sticky alerts
+help-toggle-bell = Toggle bell
+help-do-not-disturb = Do Not Disturb
+help-adjust-thresholds = Adjust thresholds
+help-toggle-theme = Toggle theme
+help-toggle-ble-range = Toggle BLE range
+help-toggle-smart-home-mode = Toggle Smart Home mode
+help-passive-mode-advertisements = Passive mode (advertisements)
+help-change-interval-settings = Change interval (Settings)
+help-export-history = Export history
+help-copy-history-summary = Copy history summary
+help-toggle-export-format = Toggle export format
+help-show-error-details = Show error details
+help-quit = Quit
+help-close = Press { $key } or Esc to close
+help-title = Keyboard Shortcuts
+
+## TUI settings panel
+
+tui-settings-title = Settings
+tui-settings-select-device = Select a device to view settings
+tui-settings-device-id = Device ID
+tui-settings-name = Name
+tui-settings-type = Type
+tui-settings-signal = Signal
+tui-settings-connected = Connected
+tui-settings-interval = Interval
+tui-settings-battery = Battery
+tui-settings-co2-alert = CO2 Alert
+tui-settings-radon-alert = Radon Alert
+tui-settings-temp-unit = Temp Unit
+tui-settings-radon-unit = Radon Unit
+tui-settings-smart-home = Smart Home
+tui-settings-ble-range = BLE Range
+tui-settings-buzzer = Buzzer
+tui-settings-auto-calib = Auto Calib
+tui-settings-enabled = Enabled
+tui-settings-disabled = Disabled
+tui-settings-standard = Standard
+tui-settings-extended = Extended
+tui-settings-celsius = Celsius
+tui-settings-fahrenheit = Fahrenheit
+tui-settings-alert-thresholds = Alert Thresholds
+tui-settings-device-settings = Device Settings
+tui-settings-enter-to-change = Enter to change
+tui-settings-adjust = +/- to adjust
+tui-settings-connect-to-view = Connect to view device settings
+
+## GUI menu bar
+
+menu-file = File
+menu-scan-for-devices = Scan for Devices
+menu-refresh-all = Refresh All
+menu-export-history = Export History
+menu-export-as-csv = Export as CSV...
+menu-export-as-json = Export as JSON...
+menu-edit = Edit
+menu-view = View
+menu-dashboard = Dashboard
+menu-history = History
+menu-settings = Settings
+menu-service = Service
+menu-appearance = Appearance
+menu-system = System
+menu-light = Light
+menu-dark = Dark
+menu-dark-mode = Dark Mode
+menu-display = Display
+menu-co2-level = CO2 Level
+menu-temperature = Temperature
+menu-humidity = Humidity
+menu-pressure = Pressure
+menu-auto-refresh = Auto Refresh
+menu-refresh-interval = Refresh Interval
+menu-30-seconds = 30 seconds
+menu-1-minute = 1 minute
+menu-5-minutes = 5 minutes
+menu-10-minutes = 10 minutes
+menu-enable-notifications = Enable Notifications
+menu-alerts = Alerts
+menu-do-not-disturb = Do Not Disturb
+menu-sticky-alerts = Sticky Alerts
+menu-log-data-to-csv = Log Data to CSV
+menu-device = Device
+menu-no-devices = No devices
+menu-manage-aliases = Manage Aliases...
+menu-window = Window
+menu-help = Help
+menu-aranet-documentation = Aranet Documentation
+menu-report-an-issue = Report an Issue...
+menu-check-for-updates = Check for Updates...
+menu-about-aranet = About Aranet
+
+## GUI application settings
+
+settings-title = Application Settings
+settings-theme = Theme
+settings-theme-hint = Choose light, dark or system appearance
+settings-compact-mode = Compact Mode
+settings-compact-mode-hint = Denser layout for smaller screens
+settings-colored-menu-bar-icon = Colored Menu Bar Icon
+settings-colored-menu-bar-icon-hint = Show colored icon when CO2 is elevated
+settings-desktop-notifications = Desktop Notifications
+settings-desktop-notifications-hint = Alert when CO2 reaches threshold levels
+settings-notification-sound = Notification Sound
+settings-notification-sound-hint = Play sound with notifications
+settings-do-not-disturb = Do Not Disturb
+settings-do-not-disturb-hint = Temporarily silence all notifications
+settings-close-to-menu-bar = Close to Menu Bar
+settings-close-to-menu-bar-hint = Keep running in background when window closes
+settings-start-minimized = Start Minimized
+settings-start-minimized-hint = Launch hidden in menu bar
+settings-display-units = Display Units
+settings-temperature = Temperature
+settings-temperature-hint = Used when device preference is unavailable
+settings-pressure = Pressure
+settings-pressure-hint = Atmospheric pressure display unit
+settings-radon = Radon
+settings-radon-hint = Used when device preference is unavailable
+settings-override-units = Override Device Units
+settings-override-units-hint = Show every device in these units, whatever its screen shows
+settings-alert-thresholds = Alert Thresholds
+settings-co2-warning = CO2 Warning
+settings-co2-warning-hint = Amber indicator threshold (ppm)
+settings-co2-danger = CO2 Danger
+settings-co2-danger-hint = Red indicator threshold (ppm)
+settings-radon-warning = Radon Warning
+settings-radon-warning-hint = Amber indicator threshold (Bq/m³)
+settings-radon-danger = Radon Danger
+settings-radon-danger-hint = Red indicator threshold (Bq/m³)
+settings-radiation-warning = Radiation Warning
+settings-radiation-warning-hint = Amber indicator threshold (µSv/h)
+settings-radiation-alarm = Radiation Alarm
+settings-radiation-alarm-hint = Red indicator threshold (µSv/h)
+settings-data-export = Data Export
+settings-default-format = Default Format
+settings-default-format-hint = Format used for history exports
+settings-export-location = Export Location
+settings-reset = Reset
+settings-behavior = Behavior
+settings-auto-connect = Auto-Connect
+settings-auto-connect-hint = Connect to known devices on startup
+settings-auto-sync-history = Auto-Sync History
+settings-auto-sync-history-hint = Download history when connecting to device
+settings-remember-devices = Remember Devices
+settings-remember-devices-hint = Save connected devices to database
+settings-load-cached-data = Load Cached Data
+settings-load-cached-data-hint = Load devices and readings from database on startup
+settings-on = On
+settings-off = Off
+settings-dark = Dark
+settings-light = Light
+settings-system = System
+settings-accent-color = Accent Color
+settings-accent-color-hint = Color of buttons and highlights
+settings-chart-colors = Chart Colors
+settings-chart-colors-hint = Line colors for CO2, temperature, humidity and pressure
+settings-dnd-enabled = Do Not Disturb enabled
+settings-dnd-disabled = Do Not Disturb disabled
+settings-downloads-folder = Downloads folder
+settings-reset-export-location = Reset to Downloads folder
+settings-language = Language
+settings-language-hint = Menus update after restarting
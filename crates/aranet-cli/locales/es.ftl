@@ -0,0 +1,87 @@
+# Spanish messages for the TUI and GUI.
+#
+# Messages missing here fall back to en.ftl.
+
+## Tabs
+
+tab-dashboard = Panel
+tab-history = Historial
+tab-settings = Ajustes
+tab-service = Servicio
+
+## TUI header bar
+
+header-online = { $connected }/{ $total } en línea
+header-alerts = Alertas { $count }
+
+## GUI navigation bar
+
+nav-press-key = Pulsa { $key }
+nav-toggle-theme = Pulsa T para cambiar el tema
+nav-toggle-auto-refresh = Pulsa A para activar o desactivar la actualización automática
+
+## TUI keyboard shortcut overlay
+
+help-section-navigation = Navegación
+help-section-views = Vistas
+help-section-devices = Dispositivos
+help-section-charts = Gráficos
+help-section-alerts = Alertas
+help-section-settings = Ajustes
+help-section-other = Otros
+help-next-prev-tab = Pestaña siguiente/anterior
+help-next-prev-device = Dispositivo siguiente/anterior
+help-connect-disconnect = Conectar/Desconectar
+help-scan-for-devices = Buscar dispositivos
+help-refresh-reading = Actualizar lectura
+help-sync-history = Sincronizar historial
+help-export-history = Exportar historial
+help-copy-history-summary = Copiar resumen del historial
+help-quit = Salir
+help-close = Pulsa { $key } o Esc para cerrar
+help-title = Atajos de teclado
+
+## TUI settings panel
+
+tui-settings-title = Ajustes
+tui-settings-select-device = Selecciona un dispositivo para ver sus ajustes
+tui-settings-name = Nombre
+tui-settings-type = Tipo
+tui-settings-signal = Señal
+tui-settings-battery = Batería
+tui-settings-enabled = Activado
+tui-settings-disabled = Desactivado
+
+## GUI menu bar
+
+menu-file = Archivo
+menu-edit = Edición
+menu-view = Ver
+menu-dashboard = Panel
+menu-history = Historial
+menu-settings = Ajustes
+menu-service = Servicio
+menu-device = Dispositivo
+menu-no-devices = Sin dispositivos
+menu-window = Ventana
+menu-help = Ayuda
+
+## GUI application settings
+
+settings-title = Ajustes de la aplicación
+settings-theme = Tema
+settings-temperature = Temperatura
+settings-pressure = Presión
+settings-radon = Radón
+settings-override-units = Ignorar unidades del dispositivo
+settings-on = Sí
+settings-off = No
+settings-dark = Oscuro
+settings-light = Claro
+settings-system = Sistema
+settings-accent-color = Color de acento
+settings-accent-color-hint = Color de botones y resaltados
+settings-chart-colors = Colores de gráficos
+settings-chart-colors-hint = Colores de línea para CO2, temperatura, humedad y presión
+settings-language = Idioma
+settings-language-hint = Los menús se actualizan al reiniciar
@@ -0,0 +1,307 @@
+//! Baselines for `watch --compare` and `status --compare`.
+//!
+//! A baseline is a CSV of readings from an earlier period, such as last
+//! week's `aranet history --format csv` export or a `watch --log-file` log.
+//! Its rows are averaged per hour of the day (local time), and a current
+//! reading is compared with the averages for the hour it was taken in.
+
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use aranet_types::CurrentReading;
+use serde::Serialize;
+use time::format_description::well_known::Rfc3339;
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::format::FormatOptions;
+
+/// Running mean of one metric.
+#[derive(Debug, Clone, Copy, Default)]
+struct Mean {
+    sum: f64,
+    count: u32,
+}
+
+impl Mean {
+    fn add(&mut self, value: f64) {
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn value(&self) -> Option<f32> {
+        (self.count > 0).then(|| (self.sum / f64::from(self.count)) as f32)
+    }
+}
+
+/// Baseline means for one hour of the day, in ppm, °C, % and Bq/m³.
+#[derive(Debug, Clone, Copy, Default)]
+struct HourMeans {
+    co2: Mean,
+    temperature: Mean,
+    humidity: Mean,
+    radon: Mean,
+}
+
+/// Hour-of-day averages of a recorded baseline period.
+#[derive(Debug, Clone)]
+pub struct Baseline {
+    hours: [HourMeans; 24],
+    offset: UtcOffset,
+}
+
+impl Baseline {
+    /// Load a baseline CSV, bucketing rows by local hour.
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+        let offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+        Self::parse(&data, offset).with_context(|| format!("Invalid baseline {}", path.display()))
+    }
+
+    /// Parse baseline CSV data, bucketing rows by hour at `offset`.
+    ///
+    /// A `timestamp` column (RFC 3339) is required. `co2`, `humidity`,
+    /// `temperature`/`temperature_c`/`temperature_f` and
+    /// `radon`/`radon_bq`/`radon_pci` are used when present, so history
+    /// exports, cache exports and watch logs all work. Rows without a valid
+    /// timestamp are skipped.
+    pub fn parse(data: &str, offset: UtcOffset) -> Result<Self> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(data.as_bytes());
+        let headers = reader.headers()?.clone();
+        let column = |names: &[&str]| headers.iter().position(|h| names.contains(&h));
+        let Some(timestamp_col) = column(&["timestamp"]) else {
+            bail!("missing a timestamp column");
+        };
+        let co2_col = column(&["co2"]);
+        let celsius_col = column(&["temperature", "temperature_c"]);
+        let fahrenheit_col = column(&["temperature_f"]);
+        let humidity_col = column(&["humidity"]);
+        let bq_col = column(&["radon", "radon_bq"]);
+        let pci_col = column(&["radon_pci"]);
+
+        let mut hours = [HourMeans::default(); 24];
+        let mut rows = 0;
+        for record in reader.records() {
+            let record = record?;
+            let Some(at) = record
+                .get(timestamp_col)
+                .and_then(|ts| OffsetDateTime::parse(ts, &Rfc3339).ok())
+            else {
+                continue;
+            };
+            let value = |col: Option<usize>| {
+                col.and_then(|c| record.get(c))
+                    .and_then(|v| v.parse::<f64>().ok())
+            };
+
+            let means = &mut hours[usize::from(at.to_offset(offset).hour())];
+            if let Some(co2) = value(co2_col).filter(|&co2| co2 > 0.0) {
+                means.co2.add(co2);
+            }
+            if let Some(celsius) =
+                value(celsius_col).or_else(|| value(fahrenheit_col).map(|f| (f - 32.0) * 5.0 / 9.0))
+            {
+                means.temperature.add(celsius);
+            }
+            if let Some(humidity) = value(humidity_col).filter(|&h| h > 0.0) {
+                means.humidity.add(humidity);
+            }
+            if let Some(bq) = value(bq_col).or_else(|| value(pci_col).map(|pci| pci / 0.027)) {
+                means.radon.add(bq);
+            }
+            rows += 1;
+        }
+        if rows == 0 {
+            bail!("no rows with a valid timestamp");
+        }
+
+        Ok(Self { hours, offset })
+    }
+
+    /// Compare `reading`, taken at `at`, with the baseline for that hour.
+    ///
+    /// Values are in the units `opts` selects for output. Returns `None`
+    /// when the baseline has nothing to compare for that hour.
+    pub fn compare(
+        &self,
+        reading: &CurrentReading,
+        at: OffsetDateTime,
+        opts: &FormatOptions,
+    ) -> Option<Comparison> {
+        let hour = at.to_offset(self.offset).hour();
+        let means = &self.hours[usize::from(hour)];
+        let delta = |mean: &Mean, current: Option<f32>, convert: &dyn Fn(f32) -> f32| {
+            let baseline = convert(mean.value()?);
+            Some(MetricDelta {
+                baseline,
+                delta: convert(current?) - baseline,
+            })
+        };
+        let radon_scale = if opts.bq { 1.0 } else { 0.027 };
+
+        let comparison = Comparison {
+            hour,
+            co2: delta(
+                &means.co2,
+                (reading.co2 > 0).then_some(f32::from(reading.co2)),
+                &|v| v,
+            ),
+            temperature: delta(&means.temperature, Some(reading.temperature), &|c| {
+                opts.convert_temp(c)
+            }),
+            humidity: delta(
+                &means.humidity,
+                (reading.humidity > 0).then_some(f32::from(reading.humidity)),
+                &|v| v,
+            ),
+            radon: delta(&means.radon, reading.radon.map(|r| r as f32), &|bq| {
+                bq * radon_scale
+            }),
+        };
+        let any = comparison.co2.is_some()
+            || comparison.temperature.is_some()
+            || comparison.humidity.is_some()
+            || comparison.radon.is_some();
+        any.then_some(comparison)
+    }
+}
+
+/// A current value against its baseline average.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MetricDelta {
+    /// Baseline average for the hour.
+    pub baseline: f32,
+    /// Current value minus the baseline.
+    pub delta: f32,
+}
+
+/// How a reading compares with the baseline for its hour.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Comparison {
+    /// Local hour of day (0-23) the baseline was taken from.
+    pub hour: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub co2: Option<MetricDelta>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<MetricDelta>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub humidity: Option<MetricDelta>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radon: Option<MetricDelta>,
+}
+
+impl Comparison {
+    /// One-line summary, e.g. `vs 14:00 baseline: CO2 +320 ppm, +0.5°C, -3% RH`.
+    pub fn summary(&self, opts: &FormatOptions) -> String {
+        let mut parts = Vec::new();
+        if let Some(co2) = self.co2 {
+            parts.push(format!("CO2 {:+.0} ppm", co2.delta));
+        }
+        if let Some(temperature) = self.temperature {
+            let unit = if opts.fahrenheit { "F" } else { "C" };
+            let degree = if opts.is_plain() { "" } else { "°" };
+            parts.push(format!("{:+.1}{}{}", temperature.delta, degree, unit));
+        }
+        if let Some(humidity) = self.humidity {
+            parts.push(format!("{:+.0}% RH", humidity.delta));
+        }
+        if let Some(radon) = self.radon {
+            let precision = if opts.bq { 0 } else { 2 };
+            parts.push(format!(
+                "radon {:+.*} {}",
+                precision,
+                radon.delta,
+                opts.radon_display_unit()
+            ));
+        }
+        format!("vs {:02}:00 baseline: {}", self.hour, parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::StyleMode;
+    use aranet_types::Status;
+    use time::macros::datetime;
+
+    const HISTORY_CSV: &str = "\
+timestamp,co2,temperature_f,humidity,pressure_hpa,radon_pci
+2026-01-05T14:10:00Z,600,68.0,40,1013.00,
+2026-01-06T14:40:00Z,800,71.6,50,1012.00,
+2026-01-06T15:00:00Z,1500,77.0,60,1012.00,
+not-a-time,9999,0.0,0,0.00,
+";
+
+    fn reading(co2: u16, temperature: f32, humidity: u8) -> CurrentReading {
+        CurrentReading::builder()
+            .co2(co2)
+            .temperature(temperature)
+            .humidity(humidity)
+            .pressure(1013.0)
+            .battery(80)
+            .status(Status::Green)
+            .build()
+    }
+
+    #[test]
+    fn test_compare_with_hour_average() {
+        let baseline = Baseline::parse(HISTORY_CSV, UtcOffset::UTC).unwrap();
+        let opts = FormatOptions::new(true, false, StyleMode::Plain);
+
+        let comparison = baseline
+            .compare(
+                &reading(1000, 23.0, 42),
+                datetime!(2026-01-12 14:30 UTC),
+                &opts,
+            )
+            .unwrap();
+        assert_eq!(comparison.hour, 14);
+        let co2 = comparison.co2.unwrap();
+        assert_eq!((co2.baseline, co2.delta), (700.0, 300.0));
+        assert!((comparison.temperature.unwrap().delta - 2.0).abs() < 1e-4);
+        assert_eq!(comparison.humidity.unwrap().delta, -3.0);
+        assert_eq!(comparison.radon, None);
+        assert_eq!(
+            comparison.summary(&opts),
+            "vs 14:00 baseline: CO2 +300 ppm, +2.0C, -3% RH"
+        );
+
+        // Nothing was recorded at 03:00
+        assert_eq!(
+            baseline.compare(
+                &reading(1000, 23.0, 42),
+                datetime!(2026-01-12 03:00 UTC),
+                &opts
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_uses_local_hour() {
+        let offset = UtcOffset::from_hms(2, 0, 0).unwrap();
+        let baseline = Baseline::parse(HISTORY_CSV, offset).unwrap();
+        let opts = FormatOptions::new(true, true, StyleMode::Plain);
+
+        // 14:xx UTC is 16:xx at +02:00
+        let comparison = baseline
+            .compare(
+                &reading(1000, 23.0, 42),
+                datetime!(2026-01-12 16:30 +02:00),
+                &opts,
+            )
+            .unwrap();
+        assert_eq!(comparison.hour, 16);
+        assert!((comparison.temperature.unwrap().baseline - 69.8).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_parse_rejects_unusable_csv() {
+        assert!(Baseline::parse("co2,humidity\n800,40\n", UtcOffset::UTC).is_err());
+        assert!(Baseline::parse("timestamp,co2\n", UtcOffset::UTC).is_err());
+    }
+}
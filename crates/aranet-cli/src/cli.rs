@@ -21,6 +21,18 @@ pub enum ReportFormat {
     Json,
 }
 
+/// How often `watch --log-file` starts a new file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogRotation {
+    /// Keep appending to the given file
+    #[default]
+    Never,
+    /// One file per hour, e.g. readings-2026-01-15-09.csv
+    Hourly,
+    /// One file per day, e.g. readings-2026-01-15.csv
+    Daily,
+}
+
 /// Visual styling mode for output
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
 pub enum StyleMode {
@@ -258,6 +270,11 @@ pub enum Commands {
         /// Interactively save aliases for discovered devices
         #[arg(short, long)]
         alias: bool,
+
+        /// Record raw advertisements in the local database for offline analysis
+        /// (export with `aranet cache adverts export`)
+        #[arg(long)]
+        log_adverts: bool,
     },
 
     /// Read current sensor values from one or more devices
@@ -271,6 +288,26 @@ pub enum Commands {
         /// Read from BLE advertisements without connecting (requires Smart Home enabled)
         #[arg(long)]
         passive: bool,
+
+        /// Show all devices in one table (JSON: a bare array), sorted by alias
+        #[arg(long, conflicts_with = "passive")]
+        combined: bool,
+
+        /// Keep reading until the device has taken a measurement since the
+        /// command started, giving up after SECS (default: 600)
+        #[arg(
+            long,
+            value_name = "SECS",
+            num_args = 0..=1,
+            default_missing_value = "600",
+            conflicts_with = "passive"
+        )]
+        retry_until_fresh: Option<u64>,
+
+        /// Show the latest reading from the local database or the running
+        /// service, with its age and whether it is stale; never uses Bluetooth
+        #[arg(long, conflicts_with_all = ["passive", "combined", "retry_until_fresh"])]
+        cached: bool,
     },
 
     /// Quick one-line status from a device
@@ -284,6 +321,14 @@ pub enum Commands {
         /// Super-compact single-line output for scripting
         #[arg(long)]
         brief: bool,
+
+        /// Add ventilation advice based on the recent CO2 trend
+        #[arg(long, conflicts_with = "brief")]
+        advice: bool,
+
+        /// Compare with the same hour of a recorded baseline CSV (e.g. last week's history export)
+        #[arg(long, value_name = "CSV", conflicts_with = "brief")]
+        compare: Option<PathBuf>,
     },
 
     /// Retrieve historical data from a device
@@ -334,8 +379,9 @@ pub enum Commands {
         #[arg(long, short = 'f')]
         force: bool,
 
+        /// Setting to change; omit it to pick settings from an interactive menu
         #[command(subcommand)]
-        setting: DeviceSetting,
+        setting: Option<DeviceSetting>,
     },
 
     /// Continuously monitor a device
@@ -357,6 +403,85 @@ pub enum Commands {
         /// Watch from BLE advertisements without connecting (requires Smart Home enabled)
         #[arg(long)]
         passive: bool,
+
+        /// Also append readings to this file (.csv or .jsonl) for unattended logging
+        #[arg(long, value_name = "PATH")]
+        log_file: Option<PathBuf>,
+
+        /// Start a new date-stamped log file every hour or day
+        #[arg(long, value_enum, requires = "log_file")]
+        rotate: Option<LogRotation>,
+
+        /// Gzip log files once they have been rotated out
+        #[arg(long, requires = "rotate")]
+        gzip: bool,
+
+        /// Compare with the same hour of a recorded baseline CSV (e.g. last week's history export)
+        #[arg(long, value_name = "CSV", conflicts_with = "passive")]
+        compare: Option<PathBuf>,
+    },
+
+    /// Generate readings from simulated devices, for development without Bluetooth
+    Simulate {
+        #[command(flatten)]
+        output: OutputArgs,
+
+        /// Number of simulated devices
+        #[arg(short, long, default_value = "1", value_parser = clap::value_parser!(u8).range(1..=16))]
+        devices: u8,
+
+        /// Kind of device to simulate
+        #[arg(short = 't', long, value_enum, default_value = "aranet4")]
+        device_type: SimulatedDeviceType,
+
+        /// Seconds between readings
+        #[arg(short, long, default_value = "5")]
+        interval: u64,
+
+        /// Number of readings per device before exiting (0 for unlimited)
+        #[arg(short = 'n', long, default_value = "0")]
+        count: u32,
+
+        /// Save readings to the local database
+        #[arg(long)]
+        store: bool,
+
+        /// Send readings to a running aranet-service through its ingest endpoint
+        #[arg(long)]
+        push: bool,
+
+        /// Service URL for --push (defaults to the configured service URL)
+        #[arg(long, value_name = "URL", requires = "push")]
+        service_url: Option<String>,
+
+        /// API key for --push (defaults to the configured service API key)
+        #[arg(long, requires = "push")]
+        api_key: Option<String>,
+    },
+
+    /// Live table of all configured devices, refreshed from BLE advertisements
+    Top {
+        #[command(flatten)]
+        device: MultiDeviceArgs,
+
+        #[command(flatten)]
+        output: ReportOutputArgs,
+
+        /// Refresh interval in seconds
+        #[arg(short, long, default_value = "10")]
+        interval: u64,
+
+        /// Column to sort rows by
+        #[arg(short, long, value_enum, default_value = "device")]
+        sort: TopSortColumn,
+
+        /// Reverse the sort order
+        #[arg(short, long)]
+        reverse: bool,
+
+        /// Number of refreshes before exiting (0 for unlimited)
+        #[arg(short = 'n', long, default_value = "0")]
+        count: u32,
     },
 
     /// Manage configuration
@@ -379,6 +504,8 @@ pub enum Commands {
     },
 
     /// Run BLE diagnostics and permission checks
+    ///
+    /// With --json, also includes the recent BLE errors and operation timings.
     Doctor,
 
     /// Show common usage examples
@@ -400,6 +527,17 @@ pub enum Commands {
         /// Sync all known devices from the database
         #[arg(long, conflicts_with = "device")]
         all: bool,
+
+        /// Instead of syncing, re-download N random cached records and compare
+        /// them with the device (default: 20)
+        #[arg(
+            long,
+            value_name = "N",
+            num_args = 0..=1,
+            default_missing_value = "20",
+            conflicts_with_all = ["full", "all"]
+        )]
+        verify: Option<u32>,
     },
 
     /// Query cached data from local database
@@ -430,6 +568,12 @@ pub enum Commands {
         output: ReportOutputArgs,
     },
 
+    /// Add, list or delete notes on cached data (e.g., "window opened")
+    Annotate {
+        #[command(subcommand)]
+        action: AnnotateAction,
+    },
+
     /// Start the HTTP API server
     Server {
         /// Path to the aranet-service configuration file
@@ -549,6 +693,10 @@ pub enum CacheAction {
         /// Filter records until this date/time
         #[arg(long)]
         until: Option<String>,
+
+        /// Add an `annotation` column (CSV) or field (JSON) with matching annotations
+        #[arg(long)]
+        annotations: bool,
     },
 
     /// Delete old data from the cache
@@ -570,6 +718,32 @@ pub enum CacheAction {
         vacuum: bool,
     },
 
+    /// Check the database for corruption, orphaned rows and duplicate history
+    Verify {
+        /// Delete rows whose device no longer exists and duplicated history records
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Vacuum the database and truncate the write-ahead log
+    Compact,
+
+    /// Delete cached data for a device, or data older than a date
+    Purge {
+        /// Device address (all devices when omitted)
+        #[arg(short, long, required_unless_present = "before")]
+        device: Option<String>,
+
+        /// Only delete history and readings before this date/time; without
+        /// it, the device and everything stored for it are removed
+        #[arg(long)]
+        before: Option<String>,
+
+        /// Skip confirmation prompt
+        #[arg(long, short = 'f')]
+        force: bool,
+    },
+
     /// Show database path and info
     Info,
 
@@ -577,12 +751,165 @@ pub enum CacheAction {
     Import {
         /// Input format
         #[arg(short, long, value_enum, default_value = "csv")]
-        format: ExportFormat,
+        format: ImportFormat,
 
         /// Input file path (uses stdin if not specified)
         #[arg(short, long)]
         input: Option<std::path::PathBuf>,
     },
+
+    /// Raw advertisements recorded with `aranet scan --log-adverts`
+    Adverts {
+        #[command(subcommand)]
+        action: AdvertsAction,
+    },
+
+    /// Run a read-only SQL query over the cache's views
+    Sql {
+        /// SELECT statement over the views shown by --views
+        #[arg(required_unless_present = "views")]
+        query: Option<String>,
+
+        /// Value for the next `?` placeholder (repeatable); numbers are bound as numbers
+        #[arg(
+            short,
+            long = "param",
+            value_name = "VALUE",
+            allow_hyphen_values = true
+        )]
+        params: Vec<String>,
+
+        /// List the queryable views and their columns
+        #[arg(long, conflicts_with = "query")]
+        views: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+}
+
+/// Advertisement log subcommands
+#[derive(Debug, Clone, Subcommand)]
+pub enum AdvertsAction {
+    /// Export logged advertisements, oldest first
+    Export {
+        /// Device address (all devices when omitted)
+        #[arg(short, long)]
+        device: Option<String>,
+
+        /// Output format (csv or json)
+        #[arg(short, long, value_enum, default_value = "csv")]
+        format: ExportFormat,
+
+        /// Output file path (uses stdout if not specified)
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+
+        /// Only advertisements received at or after this date/time
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only advertisements received at or before this date/time
+        #[arg(long)]
+        until: Option<String>,
+    },
+}
+
+/// Annotation subcommands
+#[derive(Debug, Clone, Subcommand)]
+pub enum AnnotateAction {
+    /// Annotate a point in time, or a range with --until
+    Add {
+        /// Device address or alias (uses the configured or remembered device when omitted)
+        #[arg(short, long)]
+        device: Option<String>,
+
+        /// When the event happened (RFC3339, "YYYY-MM-DD HH:MM" in local time, YYYY-MM-DD or "now")
+        #[arg(long, default_value = "now")]
+        at: String,
+
+        /// End of the annotated range (omit for a single point in time)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Tag for grouping annotations (e.g., "ventilation")
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Annotation text (e.g., "HVAC filter replaced")
+        text: String,
+    },
+
+    /// List annotations
+    #[command(alias = "ls")]
+    List {
+        /// Device address or alias (all devices when omitted)
+        #[arg(short, long)]
+        device: Option<String>,
+
+        /// Only annotations ending at or after this date/time
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only annotations starting at or before this date/time
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Only annotations with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Delete an annotation by ID
+    #[command(alias = "rm")]
+    Delete {
+        /// Annotation ID (shown by `aranet annotate list`)
+        id: i64,
+    },
+}
+
+/// Device kind for `aranet simulate`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SimulatedDeviceType {
+    /// CO2, temperature, humidity and pressure
+    #[default]
+    Aranet4,
+    /// Temperature and humidity
+    Aranet2,
+    /// Radon, temperature, humidity and pressure
+    Radon,
+    /// Radiation dose rate and total dose
+    Radiation,
+}
+
+/// Sort column for `aranet top`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum TopSortColumn {
+    /// Device name (A-Z)
+    #[default]
+    Device,
+    /// CO2 concentration (highest first)
+    Co2,
+    /// Temperature (highest first)
+    #[value(alias = "temperature")]
+    Temp,
+    /// Relative humidity (highest first)
+    #[value(alias = "rh")]
+    Humidity,
+    /// Radon concentration (highest first)
+    Radon,
+    /// Battery level (lowest first)
+    Battery,
+    /// Reading age (stalest first)
+    Age,
+    /// Signal strength (strongest first)
+    #[value(alias = "signal")]
+    Rssi,
 }
 
 /// Export format options
@@ -592,6 +919,21 @@ pub enum ExportFormat {
     Csv,
     /// JavaScript Object Notation
     Json,
+    /// Apple Health `export.xml` records
+    AppleHealth,
+    /// Apple Health samples as CSV, for importer apps and Shortcuts
+    AppleHealthCsv,
+    /// Google Fit data points as CSV
+    GoogleFit,
+}
+
+/// Import format options
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    /// Comma-separated values
+    Csv,
+    /// JavaScript Object Notation
+    Json,
 }
 
 /// Alias subcommands
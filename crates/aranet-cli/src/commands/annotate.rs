@@ -0,0 +1,234 @@
+//! Annotate command - notes on cached device data.
+//!
+//! Annotations mark events such as "window opened" or "HVAC filter replaced"
+//! at a point in time or over a range. They are stored in the local database
+//! and can be included in `aranet cache export` with `--annotations`.
+
+use anyhow::{Context, Result, bail};
+use aranet_store::{AnnotationQuery, Store, StoredAnnotation};
+use tabled::{builder::Builder, settings::Style};
+use time::OffsetDateTime;
+
+use crate::cli::{AnnotateAction, OutputFormat};
+use crate::config::{Config, resolve_alias};
+use crate::format::csv_escape;
+
+/// Execute the annotate command.
+///
+/// The device for `add` is expected to be resolved by the caller (aliases and
+/// the configured default device); `list` resolves aliases itself.
+pub fn cmd_annotate(action: AnnotateAction, config: &Config) -> Result<()> {
+    let store = Store::open_default().context("Failed to open database")?;
+
+    match action {
+        AnnotateAction::Add {
+            device,
+            at,
+            until,
+            tag,
+            text,
+        } => {
+            let Some(device) = device else {
+                bail!("No device specified. Use --device <ADDRESS|ALIAS>.");
+            };
+            let start = parse_datetime(&at)?;
+            let end = until.as_deref().map(parse_datetime).transpose()?;
+            let annotation = store.add_annotation(
+                &device,
+                start..=end.unwrap_or(start),
+                &text,
+                tag.as_deref(),
+            )?;
+            println!(
+                "Added annotation #{} for {} at {}",
+                annotation.id,
+                device,
+                format_span(&annotation)
+            );
+            Ok(())
+        }
+        AnnotateAction::List {
+            device,
+            since,
+            until,
+            tag,
+            format,
+        } => {
+            let mut query = AnnotationQuery::new();
+            if let Some(device) = device {
+                query = query.device(&resolve_alias(&device, config));
+            }
+            if let Some(since) = since {
+                query = query.since(parse_datetime(&since)?);
+            }
+            if let Some(until) = until {
+                query = query.until(parse_datetime(&until)?);
+            }
+            if let Some(tag) = tag {
+                query = query.tag(&tag);
+            }
+
+            let annotations = store.query_annotations(&query)?;
+            match format.unwrap_or(OutputFormat::Text) {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&annotations)?);
+                }
+                OutputFormat::Csv => print!("{}", format_annotations_csv(&annotations)?),
+                OutputFormat::Text => print_annotations(&annotations),
+            }
+            Ok(())
+        }
+        AnnotateAction::Delete { id } => {
+            if !store.delete_annotation(id)? {
+                bail!("No annotation with ID {}", id);
+            }
+            println!("Deleted annotation #{}", id);
+            Ok(())
+        }
+    }
+}
+
+fn print_annotations(annotations: &[StoredAnnotation]) {
+    if annotations.is_empty() {
+        println!("No annotations found.");
+        println!();
+        println!("Add one with: aranet annotate add --device <DEVICE> --at <TIME> \"<TEXT>\"");
+        return;
+    }
+
+    let mut builder = Builder::default();
+    builder.push_record(["ID", "Device", "When", "Tag", "Text"]);
+    for annotation in annotations {
+        builder.push_record([
+            annotation.id.to_string(),
+            annotation.device_id.clone(),
+            format_span(annotation),
+            annotation.tag.clone().unwrap_or_default(),
+            annotation.text.clone(),
+        ]);
+    }
+
+    let mut table = builder.build();
+    table.with(Style::rounded());
+    println!("{}", table);
+}
+
+fn format_annotations_csv(annotations: &[StoredAnnotation]) -> Result<String> {
+    let rfc3339 = &time::format_description::well_known::Rfc3339;
+    let mut output = String::from("id,device_id,start,end,tag,text\n");
+    for annotation in annotations {
+        let end = match annotation.end {
+            Some(end) => end.format(rfc3339)?,
+            None => String::new(),
+        };
+        output.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            annotation.id,
+            csv_escape(&annotation.device_id),
+            annotation.start.format(rfc3339)?,
+            end,
+            csv_escape(annotation.tag.as_deref().unwrap_or_default()),
+            csv_escape(&annotation.text)
+        ));
+    }
+    Ok(output)
+}
+
+/// Format an annotation's time or range in local time.
+fn format_span(annotation: &StoredAnnotation) -> String {
+    let format = time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]");
+    let local = |t: OffsetDateTime| {
+        let t = time::UtcOffset::current_local_offset()
+            .map(|offset| t.to_offset(offset))
+            .unwrap_or(t);
+        t.format(&format).unwrap_or_default()
+    };
+    match annotation.end {
+        Some(end) => format!("{} - {}", local(annotation.start), local(end)),
+        None => local(annotation.start),
+    }
+}
+
+/// Parse a date/time string:
+/// - "now"
+/// - RFC3339: "2024-05-02T14:00:00Z"
+/// - Local date and time: "2024-05-02 14:00" or "2024-05-02 14:00:30"
+/// - YYYY-MM-DD: "2024-05-02" (start of day, UTC)
+fn parse_datetime(s: &str) -> Result<OffsetDateTime> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("now") {
+        return Ok(OffsetDateTime::now_utc());
+    }
+
+    if let Ok(dt) = OffsetDateTime::parse(s, &time::format_description::well_known::Rfc3339) {
+        return Ok(dt);
+    }
+
+    let with_minutes = time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]");
+    let with_seconds =
+        time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+    if let Ok(dt) = time::PrimitiveDateTime::parse(s, &with_minutes)
+        .or_else(|_| time::PrimitiveDateTime::parse(s, &with_seconds))
+    {
+        let offset = time::UtcOffset::current_local_offset().unwrap_or(time::UtcOffset::UTC);
+        return Ok(dt.assume_offset(offset));
+    }
+
+    let date_only = time::macros::format_description!("[year]-[month]-[day]");
+    if let Ok(date) = time::Date::parse(s, &date_only) {
+        return Ok(date.midnight().assume_utc());
+    }
+
+    bail!(
+        "Invalid date/time format: {}. Use RFC3339, \"YYYY-MM-DD HH:MM\", YYYY-MM-DD or \"now\"",
+        s
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_datetime_formats() {
+        let dt = parse_datetime("2024-05-02T14:00:00Z").unwrap();
+        assert_eq!((dt.hour(), dt.minute()), (14, 0));
+
+        let dt = parse_datetime("2024-05-02 14:00").unwrap();
+        assert_eq!((dt.day(), dt.hour(), dt.minute()), (2, 14, 0));
+
+        let dt = parse_datetime("2024-05-02 14:00:30").unwrap();
+        assert_eq!(dt.second(), 30);
+
+        let dt = parse_datetime("2024-05-02").unwrap();
+        assert_eq!((dt.hour(), dt.offset()), (0, time::UtcOffset::UTC));
+
+        assert!(parse_datetime("now").is_ok());
+    }
+
+    #[test]
+    fn test_parse_datetime_invalid() {
+        let err = parse_datetime("02/05/2024").unwrap_err();
+        assert!(err.to_string().contains("Invalid date/time format"));
+        assert!(parse_datetime("").is_err());
+    }
+
+    #[test]
+    fn test_format_annotations_csv() {
+        let annotation = StoredAnnotation {
+            id: 3,
+            device_id: "AA:BB".to_string(),
+            start: time::macros::datetime!(2024-05-02 14:00 UTC),
+            end: None,
+            text: "HVAC filter replaced".to_string(),
+            tag: Some("hvac".to_string()),
+            created_at: time::macros::datetime!(2024-05-02 15:00 UTC),
+        };
+        let csv = format_annotations_csv(&[annotation]).unwrap();
+        assert_eq!(
+            csv,
+            "id,device_id,start,end,tag,text\n\
+             3,AA:BB,2024-05-02T14:00:00Z,,hvac,HVAC filter replaced\n"
+        );
+    }
+}
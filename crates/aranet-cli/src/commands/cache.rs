@@ -3,12 +3,16 @@
 use std::io::{Read, Write};
 
 use anyhow::{Context, Result};
-use aranet_store::{HistoryQuery, Store};
+use aranet_store::{AdvertisementQuery, HealthExportFormat, HistoryQuery, Store};
 use time::OffsetDateTime;
 
-use crate::cli::{CacheAction, ExportFormat, OutputArgs, OutputFormat};
+use crate::cli::{
+    AdvertsAction, CacheAction, ExportFormat, ImportFormat, OutputArgs, OutputFormat,
+};
 use crate::config::Config;
-use crate::format::{FormatOptions, format_history_csv, format_history_json, format_history_text};
+use crate::format::{
+    FormatOptions, csv_escape, format_history_csv, format_history_json, format_history_text,
+};
 
 /// Execute the cache command.
 pub fn cmd_cache(action: CacheAction, config: &Config) -> Result<()> {
@@ -41,15 +45,42 @@ pub fn cmd_cache(action: CacheAction, config: &Config) -> Result<()> {
             output,
             since,
             until,
-        } => export_history(&store, &device, format, output, since, until),
+            annotations,
+        } => export_history(&store, &device, format, output, since, until, annotations),
         CacheAction::Prune {
             older_than,
             history_only,
             force,
             vacuum,
         } => prune_data(&store, &older_than, history_only, force, vacuum),
+        CacheAction::Verify { fix } => verify_database(&store, fix),
+        CacheAction::Compact => compact_database(&store),
+        CacheAction::Purge {
+            device,
+            before,
+            force,
+        } => purge_data(&store, device.as_deref(), before.as_deref(), force),
         CacheAction::Info => unreachable!("Handled above"),
         CacheAction::Import { format, input } => import_history(&store, format, input),
+        CacheAction::Adverts {
+            action:
+                AdvertsAction::Export {
+                    device,
+                    format,
+                    output,
+                    since,
+                    until,
+                },
+        } => export_adverts(&store, device.as_deref(), format, output, since, until),
+        CacheAction::Sql {
+            query,
+            params,
+            views,
+            format,
+        } => match query {
+            Some(query) if !views => run_sql(&store, &query, &params, format),
+            _ => list_sql_views(&store),
+        },
     }
 }
 
@@ -108,15 +139,60 @@ fn show_stats(store: &Store, device_id: Option<&str>) -> Result<()> {
         }
         None => {
             println!("Cache statistics (all devices):");
+            let size = store.database_size()?;
+            println!(
+                "  Database size: {} KB ({} KB reclaimable)",
+                size.bytes / 1024,
+                size.free_bytes / 1024
+            );
         }
     }
 
     println!("  Readings: {}", total_readings);
     println!("  History records: {}", total_history);
 
+    for stats in store.device_cache_stats()? {
+        if device_id.is_some_and(|id| id != stats.device_id) {
+            continue;
+        }
+        println!();
+        match &stats.name {
+            Some(name) => println!("  {} ({})", stats.device_id, name),
+            None => println!("  {}", stats.device_id),
+        }
+        println!(
+            "    Readings:         {}{}",
+            stats.counts.readings,
+            format_range(stats.readings_range)?
+        );
+        println!(
+            "    History records:  {}{}",
+            stats.counts.history,
+            format_range(stats.history_range)?
+        );
+        println!("    Daily stats:      {}", stats.counts.daily_stats);
+        println!("    Settings changes: {}", stats.counts.settings_history);
+        println!("    Alerts:           {}", stats.counts.alerts);
+        println!("    Annotations:      {}", stats.counts.annotations);
+        println!("    Channel values:   {}", stats.counts.channel_values);
+    }
+
     Ok(())
 }
 
+/// Format an oldest/newest pair as ` (from .. to)`, or nothing when empty.
+fn format_range(range: Option<(OffsetDateTime, OffsetDateTime)>) -> Result<String> {
+    let Some((oldest, newest)) = range else {
+        return Ok(String::new());
+    };
+    let rfc3339 = &time::format_description::well_known::Rfc3339;
+    Ok(format!(
+        " ({} to {})",
+        oldest.format(rfc3339)?,
+        newest.format(rfc3339)?
+    ))
+}
+
 fn query_history(
     store: &Store,
     device_id: &str,
@@ -309,6 +385,7 @@ fn export_history(
     output: Option<std::path::PathBuf>,
     since: Option<String>,
     until: Option<String>,
+    annotations: bool,
 ) -> Result<()> {
     let mut query = HistoryQuery::new().device(device_id);
 
@@ -322,9 +399,24 @@ fn export_history(
         query = query.until(ts);
     }
 
+    if annotations && !matches!(format, ExportFormat::Csv | ExportFormat::Json) {
+        anyhow::bail!("--annotations is only supported for CSV and JSON exports");
+    }
+
     let content = match format {
+        ExportFormat::Csv if annotations => store.export_history_csv_annotated(&query)?,
         ExportFormat::Csv => store.export_history_csv(&query)?,
+        ExportFormat::Json if annotations => store.export_history_json_annotated(&query)?,
         ExportFormat::Json => store.export_history_json(&query)?,
+        ExportFormat::AppleHealth => {
+            store.export_history_health(&query, HealthExportFormat::AppleHealthXml)?
+        }
+        ExportFormat::AppleHealthCsv => {
+            store.export_history_health(&query, HealthExportFormat::AppleHealthCsv)?
+        }
+        ExportFormat::GoogleFit => {
+            store.export_history_health(&query, HealthExportFormat::GoogleFitCsv)?
+        }
     };
 
     match output {
@@ -342,9 +434,159 @@ fn export_history(
     Ok(())
 }
 
-fn import_history(
+fn export_adverts(
     store: &Store,
+    device_id: Option<&str>,
     format: ExportFormat,
+    output: Option<std::path::PathBuf>,
+    since: Option<String>,
+    until: Option<String>,
+) -> Result<()> {
+    let mut query = AdvertisementQuery::new();
+    if let Some(device_id) = device_id {
+        query = query.device(device_id);
+    }
+    if let Some(since_str) = since {
+        query = query.since(parse_datetime(&since_str)?);
+    }
+    if let Some(until_str) = until {
+        query = query.until(parse_datetime(&until_str)?);
+    }
+
+    let adverts = store.query_advertisements(&query)?;
+    let content = match format {
+        ExportFormat::Csv => {
+            let mut csv = String::from("seen_at,device_id,rssi,payload\n");
+            for advert in &adverts {
+                csv.push_str(&format!(
+                    "{},{},{},{}\n",
+                    advert
+                        .seen_at
+                        .format(&time::format_description::well_known::Rfc3339)?,
+                    csv_escape(&advert.device_id),
+                    advert.rssi.map(|r| r.to_string()).unwrap_or_default(),
+                    advert.payload_hex()
+                ));
+            }
+            csv
+        }
+        ExportFormat::Json => serde_json::to_string_pretty(&adverts)? + "\n",
+        _ => anyhow::bail!("Advertisements can only be exported as CSV or JSON"),
+    };
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, content)
+                .with_context(|| format!("Failed to create file: {}", path.display()))?;
+            println!(
+                "Exported {} advertisement(s) to {}",
+                adverts.len(),
+                path.display()
+            );
+        }
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
+
+fn run_sql(store: &Store, query: &str, params: &[String], format: OutputFormat) -> Result<()> {
+    let params: Vec<serde_json::Value> = params.iter().map(|p| parse_sql_param(p)).collect();
+    let result = store.query_readonly(query, &params)?;
+
+    match format {
+        OutputFormat::Json => {
+            let rows: Vec<serde_json::Map<String, serde_json::Value>> = result
+                .rows
+                .iter()
+                .map(|row| {
+                    result
+                        .columns
+                        .iter()
+                        .cloned()
+                        .zip(row.iter().cloned())
+                        .collect()
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+        }
+        OutputFormat::Csv => {
+            println!(
+                "{}",
+                result
+                    .columns
+                    .iter()
+                    .map(|c| csv_escape(c))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            );
+            for row in &result.rows {
+                let values: Vec<String> =
+                    row.iter().map(|v| csv_escape(&sql_value_text(v))).collect();
+                println!("{}", values.join(","));
+            }
+        }
+        OutputFormat::Text => {
+            let mut builder = tabled::builder::Builder::default();
+            builder.push_record(result.columns.iter().map(String::as_str));
+            for row in &result.rows {
+                builder.push_record(row.iter().map(sql_value_text));
+            }
+            let mut table = builder.build();
+            crate::style::apply_table_style(&mut table, crate::cli::StyleMode::Rich);
+            println!("{}", table);
+            println!("{} row(s)", result.rows.len());
+        }
+    }
+
+    if result.truncated {
+        eprintln!(
+            "Warning: only the first {} rows are shown; add a LIMIT or aggregate further",
+            aranet_store::QUERY_MAX_ROWS
+        );
+    }
+    Ok(())
+}
+
+fn list_sql_views(store: &Store) -> Result<()> {
+    println!("Queryable views:\n");
+    for view in aranet_store::QUERY_VIEWS {
+        let columns = store
+            .query_readonly(&format!("SELECT * FROM {} LIMIT 0", view.name), &[])?
+            .columns;
+        println!("  {} - {}", view.name, view.description);
+        println!("    {}", columns.join(", "));
+        println!();
+    }
+    println!("Times are Unix seconds, with a UTC text copy in `time`.");
+    Ok(())
+}
+
+/// Bind a `--param` as a number when it looks like one, otherwise as text.
+fn parse_sql_param(param: &str) -> serde_json::Value {
+    if let Ok(i) = param.parse::<i64>() {
+        return i.into();
+    }
+    param
+        .parse::<f64>()
+        .ok()
+        .filter(|f| f.is_finite())
+        .and_then(serde_json::Number::from_f64)
+        .map_or_else(|| param.into(), serde_json::Value::Number)
+}
+
+/// A query value as plain text: strings unquoted, `NULL` empty.
+fn sql_value_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn import_history(
+    store: &Store,
+    format: ImportFormat,
     input: Option<std::path::PathBuf>,
 ) -> Result<()> {
     // Read input data
@@ -362,8 +604,8 @@ fn import_history(
 
     // Import based on format
     let result = match format {
-        ExportFormat::Csv => store.import_history_csv(&data)?,
-        ExportFormat::Json => store.import_history_json(&data)?,
+        ImportFormat::Csv => store.import_history_csv(&data)?,
+        ImportFormat::Json => store.import_history_json(&data)?,
     };
 
     // Report results
@@ -431,12 +673,7 @@ fn prune_data(
             },
             cutoff_str
         );
-        print!("Continue? [y/N] ");
-        std::io::Write::flush(&mut std::io::stdout())?;
-
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input)?;
-        if !input.trim().eq_ignore_ascii_case("y") {
+        if !confirm()? {
             println!("Aborted.");
             return Ok(());
         }
@@ -459,6 +696,124 @@ fn prune_data(
     Ok(())
 }
 
+/// Ask for a y/N confirmation on stdin.
+fn confirm() -> Result<bool> {
+    print!("Continue? [y/N] ");
+    std::io::stdout().flush()?;
+
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+fn verify_database(store: &Store, fix: bool) -> Result<()> {
+    println!("Checking database integrity...");
+    let report = store.verify()?;
+
+    for problem in &report.problems {
+        println!("  Corruption: {}", problem);
+    }
+    for (table, count) in &report.orphans {
+        println!("  Orphaned rows in {}: {}", table, count);
+    }
+    for (device, count) in &report.duplicates {
+        println!("  Duplicate history records for {}: {}", device, count);
+    }
+    if report.checksum_mismatches > 0 {
+        println!(
+            "  History records not matching their checksum: {}",
+            report.checksum_mismatches
+        );
+    }
+
+    if report.is_ok() {
+        println!("No problems found.");
+        return Ok(());
+    }
+
+    if fix {
+        if report.orphan_count() > 0 {
+            let deleted = store.delete_orphans()?;
+            println!("Deleted {} orphaned rows", deleted);
+        }
+        if report.duplicate_count() > 0 {
+            let deleted = store.delete_history_duplicates()?;
+            println!("Deleted {} duplicate history records", deleted);
+        }
+    } else if report.orphan_count() > 0 || report.duplicate_count() > 0 {
+        println!("Run with --fix to delete orphaned rows and duplicate history records.");
+    }
+    if report.checksum_mismatches > 0 {
+        println!("Records not matching their checksum were modified after they were synced.");
+    }
+
+    if !report.problems.is_empty() {
+        anyhow::bail!(
+            "Database integrity check failed with {} problem(s); restore from a backup or re-sync",
+            report.problems.len()
+        );
+    }
+
+    Ok(())
+}
+
+fn compact_database(store: &Store) -> Result<()> {
+    let before = store.database_size()?;
+    println!("Compacting database...");
+    store.compact()?;
+    let after = store.database_size()?;
+    println!(
+        "Done. {} KB -> {} KB",
+        before.bytes / 1024,
+        after.bytes / 1024
+    );
+    Ok(())
+}
+
+fn purge_data(
+    store: &Store,
+    device_id: Option<&str>,
+    before: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let cutoff = before.map(parse_datetime).transpose()?;
+    let target = device_id.unwrap_or("all devices");
+
+    if !force {
+        match cutoff {
+            Some(cutoff) => println!(
+                "This will delete history and readings for {} before {}",
+                target,
+                cutoff.format(&time::format_description::well_known::Rfc3339)?
+            ),
+            None => println!("This will delete {} and everything cached for it", target),
+        }
+        if !confirm()? {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    match (device_id, cutoff) {
+        (_, Some(cutoff)) => {
+            let counts = store.purge_before(device_id, cutoff)?;
+            println!("Deleted {} history records", counts.history);
+            println!("Deleted {} readings", counts.readings);
+            println!("Deleted {} daily summaries", counts.daily_stats);
+        }
+        (Some(id), None) => {
+            let counts = store.purge_device(id)?;
+            if !counts.device {
+                anyhow::bail!("Device not found in cache: {}", id);
+            }
+            println!("Deleted {} and {} cached rows", id, counts.total() - 1);
+        }
+        (None, None) => unreachable!("clap requires --device or --before"),
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -467,6 +822,19 @@ mod tests {
     // parse_datetime tests
     // ========================================================================
 
+    #[test]
+    fn test_parse_sql_param() {
+        assert_eq!(parse_sql_param("1000"), serde_json::json!(1000));
+        assert_eq!(parse_sql_param("-2.5"), serde_json::json!(-2.5));
+        assert_eq!(
+            parse_sql_param("Aranet4 17C3C"),
+            serde_json::json!("Aranet4 17C3C")
+        );
+        assert_eq!(parse_sql_param("NaN"), serde_json::json!("NaN"));
+        assert_eq!(sql_value_text(&serde_json::Value::Null), "");
+        assert_eq!(sql_value_text(&serde_json::json!("a")), "a");
+    }
+
     #[test]
     fn test_parse_datetime_rfc3339() {
         let result = parse_datetime("2024-01-15T10:30:00Z").unwrap();
@@ -1,23 +1,38 @@
 //! Doctor command implementation.
 //!
 //! Performs BLE diagnostics and permission checks to help troubleshoot
-//! connectivity issues.
+//! connectivity issues. With `--json`, prints the check results together
+//! with the recent BLE errors and operation timings recorded during the run.
+//! Aranet4 devices found by the scan also get their advertised CO₂
+//! calibration state checked.
 
 use anyhow::Result;
 use aranet_core::scan::{self, ScanOptions};
+use aranet_core::{BluetoothDiagnostics, DiscoveredDevice, SensorHealth, global_diagnostics};
 use owo_colors::OwoColorize;
+use serde::Serialize;
 
 use crate::style;
 
 /// Check result with status and message.
+#[derive(Serialize)]
 struct Check {
-    #[allow(dead_code)]
     name: &'static str,
     passed: bool,
     warning: bool,
     message: String,
 }
 
+/// Machine-readable doctor output.
+#[derive(Serialize)]
+struct DoctorReport {
+    checks: Vec<Check>,
+    passed: usize,
+    warnings: usize,
+    failed: usize,
+    diagnostics: BluetoothDiagnostics,
+}
+
 impl Check {
     fn pass(name: &'static str, message: impl Into<String>) -> Self {
         Self {
@@ -47,7 +62,11 @@ impl Check {
     }
 }
 
-pub async fn cmd_doctor(verbose: bool, no_color: bool) -> Result<()> {
+pub async fn cmd_doctor(verbose: bool, json: bool, compact: bool, no_color: bool) -> Result<()> {
+    if json {
+        return print_json_report(compact).await;
+    }
+
     println!(
         "{}",
         style::format_title("Aranet Doctor - BLE Diagnostics", no_color)
@@ -76,9 +95,16 @@ pub async fn cmd_doctor(verbose: bool, no_color: bool) -> Result<()> {
     if adapter_ok {
         check_num += 1;
         print_check_start(check_num, "Device Scan", no_color);
-        let scan_check = check_scan().await;
+        let (scan_check, devices) = check_scan().await;
         print_check_result(&scan_check, no_color);
         checks.push(scan_check);
+
+        if let Some(health_check) = check_sensor_health(&devices) {
+            check_num += 1;
+            print_check_start(check_num, "Sensor Health", no_color);
+            print_check_result(&health_check, no_color);
+            checks.push(health_check);
+        }
     }
 
     // Check 4: Config file validity
@@ -126,6 +152,31 @@ pub async fn cmd_doctor(verbose: bool, no_color: bool) -> Result<()> {
     Ok(())
 }
 
+async fn print_json_report(compact: bool) -> Result<()> {
+    let mut checks = vec![check_adapter().await, check_permissions().await];
+    if checks[0].passed {
+        let (scan_check, devices) = check_scan().await;
+        checks.push(scan_check);
+        checks.extend(check_sensor_health(&devices));
+    }
+    checks.push(check_config());
+
+    let report = DoctorReport {
+        passed: checks.iter().filter(|c| c.passed && !c.warning).count(),
+        warnings: checks.iter().filter(|c| c.warning).count(),
+        failed: checks.iter().filter(|c| !c.passed).count(),
+        checks,
+        diagnostics: global_diagnostics().collect().await,
+    };
+    let output = if compact {
+        serde_json::to_string(&report)?
+    } else {
+        serde_json::to_string_pretty(&report)?
+    };
+    println!("{}", output);
+    Ok(())
+}
+
 fn print_check_start(num: usize, name: &str, no_color: bool) {
     // Use simple static output instead of a spinner that can't animate during sync blocking
     use std::io::{Write, stdout};
@@ -175,14 +226,14 @@ async fn check_adapter() -> Check {
     }
 }
 
-async fn check_scan() -> Check {
+async fn check_scan() -> (Check, Vec<DiscoveredDevice>) {
     let options = ScanOptions::default()
         .duration_secs(3)
         .filter_aranet_only(true);
 
     match scan::scan_with_options(options).await {
         Ok(devices) => {
-            if devices.is_empty() {
+            let check = if devices.is_empty() {
                 Check::warn("BLE Scanning", "No Aranet devices found nearby")
             } else {
                 let names: Vec<String> = devices.iter().filter_map(|d| d.name.clone()).collect();
@@ -190,9 +241,44 @@ async fn check_scan() -> Check {
                     "BLE Scanning",
                     format!("Found {} device(s): {}", devices.len(), names.join(", ")),
                 )
-            }
+            };
+            (check, devices)
+        }
+        Err(e) => (
+            Check::fail("BLE Scanning", format!("Failed ({})", e)),
+            Vec::new(),
+        ),
+    }
+}
+
+/// Check the advertised CO₂ calibration state of scanned Aranet4 devices.
+///
+/// Returns `None` if no scanned device has a CO₂ sensor.
+fn check_sensor_health(devices: &[DiscoveredDevice]) -> Option<Check> {
+    let mut sensors = 0;
+    let mut problems = Vec::new();
+    for device in devices {
+        let Some(health) = device
+            .manufacturer_data
+            .as_deref()
+            .and_then(|data| SensorHealth::from_manufacturer_data(data, device.name.as_deref()))
+        else {
+            continue;
+        };
+        sensors += 1;
+        if let Some(warning) = health.warnings().first() {
+            let name = device.name.as_deref().unwrap_or(&device.identifier);
+            problems.push(format!("{}: {}", name, warning));
         }
-        Err(e) => Check::fail("BLE Scanning", format!("Failed ({})", e)),
+    }
+
+    match (sensors, problems.is_empty()) {
+        (0, _) => None,
+        (_, true) => Some(Check::pass(
+            "Sensor Health",
+            format!("{} CO₂ sensor(s), no calibration issues", sensors),
+        )),
+        (_, false) => Some(Check::warn("Sensor Health", problems.join("; "))),
     }
 }
 
@@ -252,11 +338,11 @@ fn check_config() -> Check {
     use crate::config::Config;
 
     let config_path = Config::path();
-    if !config_path.exists() {
+    if !config_path.exists() && Config::env_overrides().is_empty() {
         return Check::pass("Configuration", "No config file (using defaults)");
     }
 
-    match Config::load() {
+    match Config::load_effective() {
         Ok(config) => {
             let alias_count = config.aliases.len();
             let default_device = config.device.is_some();
@@ -270,7 +356,7 @@ fn check_config() -> Check {
                 ),
             )
         }
-        Err(err) => Check::warn("Configuration", format!("Invalid config file ({err})")),
+        Err(err) => Check::warn("Configuration", format!("Invalid config ({err})")),
     }
 }
 
@@ -4,14 +4,26 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::{Context, Result, bail};
-use aranet_core::HistoryOptions;
+use aranet_core::{Device, HistoryOptions};
 use aranet_store::{HistoryQuery, Store};
+use aranet_types::HistoryRecord;
+use futures::TryStreamExt;
+use indicatif::ProgressBar;
 use time::OffsetDateTime;
 
 use crate::cli::OutputFormat;
-use crate::format::{FormatOptions, format_history_csv, format_history_json, format_history_text};
+use crate::format::{
+    FormatOptions, HISTORY_TEXT_MAX_ROWS, format_history_csv, format_history_csv_header,
+    format_history_csv_rows, format_history_json, format_history_json_elements,
+    format_history_text, format_history_text_with_total,
+};
 use crate::style;
-use crate::util::{require_device_interactive, write_output};
+use crate::util::{append_output, require_device_interactive, write_output};
+
+/// Records downloaded, stored and written at a time.
+///
+/// Bounds memory for devices holding tens of thousands of records.
+const HISTORY_CHUNK: u16 = 1024;
 
 /// Options for querying history from the cache.
 struct CacheQueryOptions<'a> {
@@ -162,79 +174,205 @@ pub async fn cmd_history(args: HistoryArgs<'_>) -> Result<()> {
         None
     };
 
-    // Clone progress bar for callback (ProgressBar uses Arc internally)
-    let pb_for_callback = pb.clone();
-
-    let history_options = if let Some(pb_callback) = pb_for_callback {
-        HistoryOptions::default().with_progress(move |progress| {
-            let percent = (progress.overall_progress * 100.0) as u64;
-            pb_callback.set_position(percent);
-            pb_callback.set_message(format!(
-                "Downloading {:?} ({}/{})",
-                progress.current_param, progress.param_index, progress.total_params
-            ));
-        })
-    } else {
-        HistoryOptions::default()
-    };
-
     let device_id = device.address().to_string();
-    let history_result = device
-        .download_history_with_options(history_options)
-        .await
-        .context("Failed to download history");
+    let mut writer = HistoryWriter::new(format, output, opts);
+    let filter = HistoryFilter {
+        since: since_dt,
+        until: until_dt,
+        count: count as usize,
+    };
+    let result = stream_history(&device, &device_id, &filter, pb.as_ref(), &mut writer).await;
 
     crate::util::disconnect_device(&device).await;
-    let history = history_result?;
+    result?;
 
     if let Some(pb) = pb {
         pb.finish_with_message("Download complete");
     }
 
+    if !quiet && matches!(format, OutputFormat::Text) {
+        eprintln!("Downloaded {} records.", writer.written);
+    }
+
+    writer.finish()
+}
+
+/// Which downloaded records are written.
+struct HistoryFilter {
+    since: Option<OffsetDateTime>,
+    until: Option<OffsetDateTime>,
+    /// Newest records to write; 0 writes all.
+    count: usize,
+}
+
+/// Download history newest chunk first, saving each chunk to the store and
+/// writing the records that pass `filter` before fetching the next.
+///
+/// Only one chunk is held at a time, and the download stops as soon as the
+/// records are older than `since` or `count` have been written.
+async fn stream_history(
+    device: &Device,
+    device_id: &str,
+    filter: &HistoryFilter,
+    pb: Option<&ProgressBar>,
+    writer: &mut HistoryWriter<'_>,
+) -> Result<()> {
+    let info = device
+        .get_history_info()
+        .await
+        .context("Failed to read history info")?;
+    let lowest = match filter.since {
+        Some(since) => info.first_index_since(since, OffsetDateTime::now_utc()),
+        None => (info.total_readings > 0).then_some(1),
+    };
+    let Some(lowest) = lowest else {
+        return Ok(());
+    };
+    let highest = info.total_readings;
+
+    // V1 devices cannot download a range, so fetch everything at once
+    let chunk = if device.quirks().history_v1 {
+        highest
+    } else {
+        HISTORY_CHUNK
+    };
+
     // Save history to store (unified data architecture)
-    crate::util::save_history_to_store(&device_id, &history);
-
-    // Apply date filters
-    let history: Vec<_> = history
-        .into_iter()
-        .filter(|r| {
-            if let Some(since) = since_dt
-                && r.timestamp < since
-            {
-                return false;
+    let mut store = crate::util::open_store();
+    let mut end = highest;
+    loop {
+        let start = end.saturating_sub(chunk - 1).max(lowest);
+        let options = HistoryOptions::default().start_index(start).end_index(end);
+        let mut records: Vec<HistoryRecord> = device
+            .history_stream(options)
+            .try_collect()
+            .await
+            .context("Failed to download history")?;
+        records.reverse();
+
+        if let Some(s) = &store
+            && let Err(e) = s.insert_history(device_id, &records)
+        {
+            tracing::warn!("Failed to save history to store: {}", e);
+            eprintln!("Warning: could not save history to local database: {e}");
+            store = None;
+        }
+        if let Some(pb) = pb {
+            let done = u64::from(highest - start + 1);
+            pb.set_position(done * 100 / u64::from(highest - lowest + 1));
+        }
+
+        let mut finished = false;
+        let mut selected = Vec::with_capacity(records.len());
+        for record in records {
+            if filter.until.is_some_and(|until| record.timestamp > until) {
+                continue;
+            }
+            if filter.since.is_some_and(|since| record.timestamp < since) {
+                finished = true;
+                break;
             }
-            if let Some(until) = until_dt
-                && r.timestamp > until
-            {
-                return false;
+            selected.push(record);
+            if filter.count > 0 && writer.written + selected.len() >= filter.count {
+                finished = true;
+                break;
             }
-            true
-        })
-        .collect();
+        }
+        writer.write(&selected)?;
 
-    // Reverse to show newest first (device sends oldest first)
-    let mut history = history;
-    history.reverse();
+        if finished || start <= lowest {
+            return Ok(());
+        }
+        end = start - 1;
+    }
+}
 
-    // Apply count limit if specified (0 means all)
-    let history: Vec<_> = if count > 0 {
-        history.into_iter().take(count as usize).collect()
-    } else {
-        history
-    };
+/// Writes history newest record first, a chunk at a time.
+///
+/// CSV and JSON go to the output as each chunk arrives. The text table only
+/// shows the newest records, so those are kept and printed by
+/// [`finish`](Self::finish).
+struct HistoryWriter<'a> {
+    format: OutputFormat,
+    output: Option<&'a PathBuf>,
+    opts: &'a FormatOptions,
+    /// Records written so far.
+    written: usize,
+    /// Whether the output has been started (and truncated).
+    started: bool,
+    table: Vec<HistoryRecord>,
+}
 
-    if !quiet && matches!(format, OutputFormat::Text) {
-        eprintln!("Downloaded {} records.", history.len());
+impl<'a> HistoryWriter<'a> {
+    fn new(format: OutputFormat, output: Option<&'a PathBuf>, opts: &'a FormatOptions) -> Self {
+        Self {
+            format,
+            output,
+            opts,
+            written: 0,
+            started: false,
+            table: Vec::new(),
+        }
     }
 
-    let content = match format {
-        OutputFormat::Json => format_history_json(&history, opts)?,
-        OutputFormat::Text => format_history_text(&history, opts),
-        OutputFormat::Csv => format_history_csv(&history, opts),
-    };
+    fn write(&mut self, records: &[HistoryRecord]) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        match self.format {
+            OutputFormat::Text => {
+                let room = HISTORY_TEXT_MAX_ROWS.saturating_sub(self.table.len());
+                self.table.extend(records.iter().take(room).cloned());
+            }
+            OutputFormat::Csv => {
+                let mut content = String::new();
+                if self.written == 0 {
+                    content.push_str(&format_history_csv_header(self.opts));
+                }
+                content.push_str(&format_history_csv_rows(records, self.opts));
+                self.emit(&content)?;
+            }
+            OutputFormat::Json => {
+                let separator = if self.opts.compact { "," } else { ",\n" };
+                let mut content = String::new();
+                for element in format_history_json_elements(records, self.opts)? {
+                    if self.written == 0 && content.is_empty() {
+                        content.push_str(if self.opts.compact { "[" } else { "[\n" });
+                    } else {
+                        content.push_str(separator);
+                    }
+                    content.push_str(&element);
+                }
+                self.emit(&content)?;
+            }
+        }
+        self.written += records.len();
+        Ok(())
+    }
 
-    write_output(output, &content)?;
-    Ok(())
+    /// Write whatever completes the output.
+    fn finish(mut self) -> Result<()> {
+        let content = match self.format {
+            OutputFormat::Text => {
+                format_history_text_with_total(&self.table, self.written, self.opts)
+            }
+            OutputFormat::Csv if self.written == 0 => format_history_csv_header(self.opts),
+            OutputFormat::Csv => return Ok(()),
+            OutputFormat::Json if self.written == 0 => "[]\n".to_string(),
+            OutputFormat::Json if self.opts.compact => "]\n".to_string(),
+            OutputFormat::Json => "\n]\n".to_string(),
+        };
+        self.emit(&content)
+    }
+
+    fn emit(&mut self, content: &str) -> Result<()> {
+        if self.started {
+            append_output(self.output, content)
+        } else {
+            self.started = true;
+            write_output(self.output, content)
+        }
+    }
 }
 
 /// Read history from local cache instead of connecting to the device.
@@ -545,4 +683,89 @@ mod tests {
         assert!(err.to_string().contains("Invalid date format"));
         assert!(err.to_string().contains("invalid"));
     }
+
+    // ========================================================================
+    // HistoryWriter tests
+    // ========================================================================
+
+    fn history(count: usize) -> Vec<HistoryRecord> {
+        let start = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        (0..count)
+            .map(|i| HistoryRecord {
+                timestamp: start - time::Duration::minutes(i as i64),
+                co2: 400 + i as u16,
+                temperature: 21.5,
+                pressure: 1013.0,
+                humidity: 40,
+                radon: None,
+                radiation_rate: None,
+                radiation_total: None,
+            })
+            .collect()
+    }
+
+    fn write_in_chunks(
+        format: OutputFormat,
+        opts: &FormatOptions,
+        records: &[HistoryRecord],
+    ) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.out");
+        let mut writer = HistoryWriter::new(format, Some(&path), opts);
+        for chunk in records.chunks(2) {
+            writer.write(chunk).unwrap();
+        }
+        writer.finish().unwrap();
+        std::fs::read_to_string(&path).unwrap()
+    }
+
+    #[test]
+    fn test_history_writer_matches_whole_output() {
+        let records = history(5);
+        let pretty = FormatOptions::default();
+        let compact = FormatOptions {
+            compact: true,
+            ..FormatOptions::default()
+        };
+
+        for opts in [&pretty, &compact] {
+            assert_eq!(
+                write_in_chunks(OutputFormat::Json, opts, &records),
+                format_history_json(&records, opts).unwrap()
+            );
+            assert_eq!(
+                write_in_chunks(OutputFormat::Json, opts, &[]),
+                format_history_json(&[], opts).unwrap()
+            );
+        }
+        assert_eq!(
+            write_in_chunks(OutputFormat::Csv, &pretty, &records),
+            format_history_csv(&records, &pretty)
+        );
+        assert_eq!(
+            write_in_chunks(OutputFormat::Csv, &pretty, &[]),
+            format_history_csv(&[], &pretty)
+        );
+    }
+
+    #[test]
+    fn test_history_writer_text_keeps_only_table_rows() {
+        let records = history(HISTORY_TEXT_MAX_ROWS + 7);
+        let opts = FormatOptions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.txt");
+
+        let mut writer = HistoryWriter::new(OutputFormat::Text, Some(&path), &opts);
+        for chunk in records.chunks(4) {
+            writer.write(chunk).unwrap();
+        }
+        assert_eq!(writer.table.len(), HISTORY_TEXT_MAX_ROWS);
+        assert_eq!(writer.written, records.len());
+        writer.finish().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            format_history_text(&records, &opts)
+        );
+    }
 }
@@ -4,6 +4,9 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
+use aranet_core::{HistoryInfo, SensorHealth, SensorHealthWarning};
+use aranet_types::DeviceInfo;
+use serde::Serialize;
 
 use crate::cli::OutputFormat;
 use crate::format::{FormatOptions, format_info_csv, format_info_text};
@@ -27,15 +30,86 @@ pub async fn cmd_info(
         .read_device_info()
         .await
         .context("Failed to read device info");
+    // Storage usage is extra; devices that cannot report it still get info
+    let history_info = if info_result.is_ok() {
+        device.get_history_info().await.ok()
+    } else {
+        None
+    };
+    // Likewise calibration state, which only CO₂ sensors report
+    let health = if info_result.is_ok() {
+        device.read_sensor_health().await.ok()
+    } else {
+        None
+    };
     crate::util::disconnect_device(&device).await;
     let info = info_result?;
 
     let content = match format {
-        OutputFormat::Json => opts.as_json(&info)?,
-        OutputFormat::Text => format_info_text(&info, opts),
+        OutputFormat::Json => {
+            format_info_json(&info, history_info.as_ref(), health.as_ref(), opts)?
+        }
+        OutputFormat::Text => format_info_text(&info, history_info.as_ref(), health.as_ref(), opts),
         OutputFormat::Csv => format_info_csv(&info, opts),
     };
 
     write_output(output, &content)?;
     Ok(())
 }
+
+/// Format device info as JSON, with a `storage` object when the history
+/// memory usage is known and a `sensor_health` object for CO₂ sensors.
+fn format_info_json(
+    info: &DeviceInfo,
+    history_info: Option<&HistoryInfo>,
+    health: Option<&SensorHealth>,
+    opts: &FormatOptions,
+) -> Result<String> {
+    #[derive(Serialize)]
+    struct StorageJson {
+        stored_readings: u16,
+        capacity: u16,
+        percent_full: f32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        hours_until_overwrite: Option<f64>,
+    }
+
+    #[derive(Serialize)]
+    struct InfoJson<'a> {
+        #[serde(flatten)]
+        info: &'a DeviceInfo,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        storage: Option<StorageJson>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sensor_health: Option<SensorHealthJson<'a>>,
+    }
+
+    #[derive(Serialize)]
+    struct SensorHealthJson<'a> {
+        #[serde(flatten)]
+        health: &'a SensorHealth,
+        warnings: Vec<SensorHealthWarning>,
+    }
+
+    let storage = history_info.and_then(|h| {
+        Some(StorageJson {
+            stored_readings: h.total_readings,
+            capacity: h.capacity?,
+            percent_full: h.fill_ratio()? * 100.0,
+            hours_until_overwrite: h
+                .time_until_overwrite()
+                .map(|d| d.as_seconds_f64() / 3_600.0),
+        })
+    });
+
+    let sensor_health = health.map(|health| SensorHealthJson {
+        health,
+        warnings: health.warnings(),
+    });
+
+    opts.as_json(&InfoJson {
+        info,
+        storage,
+        sensor_health,
+    })
+}
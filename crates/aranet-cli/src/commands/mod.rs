@@ -1,6 +1,7 @@
 //! Command implementations for the CLI.
 
 mod alias;
+mod annotate;
 mod cache;
 mod doctor;
 mod history;
@@ -10,20 +11,25 @@ pub mod report;
 mod scan;
 mod server;
 mod set;
+mod simulate;
 mod status;
 mod sync;
+mod top;
 mod watch;
 
 pub use alias::{AliasAction, cmd_alias};
+pub use annotate::cmd_annotate;
 pub use cache::cmd_cache;
 pub use doctor::cmd_doctor;
 pub use history::{HistoryArgs, cmd_history};
 pub use info::cmd_info;
-pub use read::{DeviceReading, cmd_read};
+pub use read::{DeviceReading, ReadArgs, cmd_read};
 pub use report::cmd_report;
 pub use scan::cmd_scan;
 pub use server::{ServerArgs, cmd_server};
 pub use set::cmd_set;
-pub use status::cmd_status;
+pub use simulate::{SimulateArgs, cmd_simulate};
+pub use status::{StatusArgs, cmd_status};
 pub use sync::{SyncArgs, cmd_sync};
+pub use top::{TopArgs, cmd_top};
 pub use watch::{WatchArgs, cmd_watch};
@@ -1,37 +1,105 @@
 //! Read command implementation.
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::cli::OutputFormat;
 use crate::format::{
-    FormatOptions, format_multi_reading_csv, format_multi_reading_json, format_multi_reading_text,
+    FormatOptions, format_cached_reading_json, format_multi_reading_csv, format_multi_reading_json,
+    format_multi_reading_json_array, format_multi_reading_table, format_multi_reading_text,
     format_reading_csv, format_reading_json, format_reading_text, format_reading_text_with_name,
 };
 use crate::util::{require_device_interactive, write_output};
 use anyhow::{Context, Result, bail};
 use aranet_core::advertisement::parse_advertisement_with_name;
 use aranet_core::scan::{ScanOptions, scan_with_options};
+use aranet_core::service_client::{CurrentReadingResponse, ServiceClient};
+use aranet_core::{Device, platform_config};
+use aranet_store::StoredReading;
 use aranet_types::CurrentReading;
-use futures::future::join_all;
+use futures::stream::{self, StreamExt};
+use time::OffsetDateTime;
 
 /// Result of reading from a device
 pub struct DeviceReading {
     pub identifier: String,
+    /// Configured alias for the device, if any.
+    pub alias: Option<String>,
+    /// Name the device advertises.
+    pub name: Option<String>,
     pub reading: CurrentReading,
 }
 
-pub async fn cmd_read(
-    devices: Vec<String>,
-    timeout: Duration,
-    format: OutputFormat,
-    output: Option<&PathBuf>,
-    quiet: bool,
-    passive: bool,
-    opts: &FormatOptions,
-) -> Result<()> {
+impl DeviceReading {
+    /// Best human-readable label: alias, then device name, then identifier.
+    pub fn label(&self) -> &str {
+        self.alias
+            .as_deref()
+            .or(self.name.as_deref())
+            .unwrap_or(&self.identifier)
+    }
+}
+
+/// Arguments for the read command.
+pub struct ReadArgs<'a> {
+    pub devices: Vec<String>,
+    pub timeout: Duration,
+    pub format: OutputFormat,
+    pub output: Option<&'a PathBuf>,
+    pub quiet: bool,
+    pub passive: bool,
+    /// Print one combined table (or JSON array) for all devices.
+    pub combined: bool,
+    /// Re-read until the reading was measured after the command started,
+    /// for at most this long.
+    pub retry_until_fresh: Option<Duration>,
+    /// Show the latest stored reading instead of using Bluetooth.
+    pub cached: bool,
+    /// Service to ask when the local database has no fresh reading.
+    pub service_url: &'a str,
+    pub service_api_key: Option<&'a str>,
+    /// Configured aliases (alias -> address), used to label devices.
+    pub aliases: &'a HashMap<String, String>,
+    pub opts: &'a FormatOptions,
+}
+
+pub async fn cmd_read(args: ReadArgs<'_>) -> Result<()> {
+    let started = Instant::now();
+    let ReadArgs {
+        devices,
+        timeout,
+        format,
+        output,
+        quiet,
+        passive,
+        combined,
+        retry_until_fresh,
+        cached,
+        service_url,
+        service_api_key,
+        aliases,
+        opts,
+    } = args;
+    let freshness = retry_until_fresh.map(|limit| Freshness { started, limit });
+
+    if cached {
+        let device = match devices.as_slice() {
+            [device] => device,
+            [] => bail!(
+                "No device specified. Use --device <ADDRESS> or set a default with \
+                 'aranet config set device <ADDRESS>'."
+            ),
+            _ => bail!(
+                "Cached mode only supports one device, but {} were specified.",
+                devices.len()
+            ),
+        };
+        return cmd_read_cached(device, service_url, service_api_key, format, output, opts).await;
+    }
+
     if passive {
         if devices.len() > 1 {
             bail!(
@@ -52,12 +120,81 @@ pub async fn cmd_read(
     };
 
     // Single device: use simple output
-    if devices.len() == 1 {
-        return cmd_read_single(&devices[0], timeout, format, output, quiet, opts).await;
+    if devices.len() == 1 && !combined {
+        return cmd_read_single(&devices[0], timeout, format, output, quiet, freshness, opts).await;
     }
 
     // Multiple devices: read in parallel
-    cmd_read_multi(devices, timeout, format, output, quiet, opts).await
+    cmd_read_multi(
+        devices, timeout, format, output, quiet, combined, freshness, aliases, opts,
+    )
+    .await
+}
+
+/// Requirement that a reading was measured after the command started.
+#[derive(Debug, Clone, Copy)]
+struct Freshness {
+    started: Instant,
+    limit: Duration,
+}
+
+impl Freshness {
+    /// Whether `reading` was measured after `started`.
+    ///
+    /// The age is reported in whole seconds, so one second is added to stay
+    /// on the safe side.
+    fn is_fresh(&self, reading: &CurrentReading) -> bool {
+        Duration::from_secs(u64::from(reading.age) + 1) <= self.started.elapsed()
+    }
+}
+
+/// How long to wait before the device should have taken its next measurement.
+fn until_next_measurement(reading: &CurrentReading) -> Duration {
+    if reading.interval == 0 {
+        return Duration::from_secs(5);
+    }
+    let remaining = reading.interval.saturating_sub(reading.age);
+    // Give the device a moment to publish the new measurement
+    Duration::from_secs(u64::from(remaining) + 2)
+}
+
+/// Read current values, re-reading until fresh if requested.
+async fn read_until_fresh(
+    device: &Device,
+    freshness: Option<Freshness>,
+    show_progress: bool,
+) -> Result<CurrentReading> {
+    loop {
+        let reading = device
+            .read_current()
+            .await
+            .context("Failed to read current values")?;
+        let Some(freshness) = freshness else {
+            return Ok(reading);
+        };
+        if freshness.is_fresh(&reading) {
+            return Ok(reading);
+        }
+
+        let remaining = freshness.limit.saturating_sub(freshness.started.elapsed());
+        if remaining.is_zero() {
+            bail!(
+                "No new measurement within {}s (last one was {}s old, interval {}s)",
+                freshness.limit.as_secs(),
+                reading.age,
+                reading.interval
+            );
+        }
+        let wait = until_next_measurement(&reading).min(remaining);
+        if show_progress {
+            eprintln!(
+                "Waiting {}s for a new measurement (last one is {}s old)...",
+                wait.as_secs(),
+                reading.age
+            );
+        }
+        tokio::time::sleep(wait).await;
+    }
 }
 
 /// Read from a single device
@@ -67,6 +204,7 @@ async fn cmd_read_single(
     format: OutputFormat,
     output: Option<&PathBuf>,
     quiet: bool,
+    freshness: Option<Freshness>,
     opts: &FormatOptions,
 ) -> Result<()> {
     // Use connect_device_with_progress which has its own spinner
@@ -76,10 +214,7 @@ async fn cmd_read_single(
         crate::util::connect_device_with_progress(identifier, timeout, show_progress).await?;
     let device_id = device.address().to_string();
     let device_name = device.name().map(|s| s.to_string());
-    let reading_result = device
-        .read_current()
-        .await
-        .context("Failed to read current values");
+    let reading_result = read_until_fresh(&device, freshness, show_progress).await;
     crate::util::disconnect_device(&device).await;
     let reading = reading_result?;
 
@@ -97,16 +232,24 @@ async fn cmd_read_single(
 }
 
 /// Read from multiple devices in parallel
+///
+/// At most as many devices as the platform's BLE adapter can comfortably
+/// hold are connected at once; results are sorted by alias or device name.
+#[allow(clippy::too_many_arguments)]
 async fn cmd_read_multi(
     devices: Vec<String>,
     timeout: Duration,
     format: OutputFormat,
     output: Option<&PathBuf>,
     quiet: bool,
+    combined: bool,
+    freshness: Option<Freshness>,
+    aliases: &HashMap<String, String>,
     opts: &FormatOptions,
 ) -> Result<()> {
     let total_devices = devices.len();
     let show_progress = !quiet && matches!(format, OutputFormat::Text);
+    let max_concurrent = platform_config().max_concurrent_connections.max(1);
 
     if show_progress {
         eprintln!("Reading from {} devices...", total_devices);
@@ -116,26 +259,28 @@ async fn cmd_read_multi(
     let completed = Arc::new(AtomicUsize::new(0));
 
     // Read from all devices in parallel with progress updates
-    let futures = devices.iter().map(|id| {
-        let completed = Arc::clone(&completed);
-        let id = id.clone();
-        async move {
-            let result = read_device(id.clone(), timeout).await;
-            let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
-            if show_progress {
-                match &result {
-                    Ok(reading) => {
-                        eprintln!("  [{}/{}] {} - OK", done, total_devices, reading.identifier);
-                    }
-                    Err((id, _)) => {
-                        eprintln!("  [{}/{}] {} - FAILED", done, total_devices, id);
+    let results: Vec<Result<DeviceReading, (String, anyhow::Error)>> = stream::iter(devices)
+        .map(|id| {
+            let completed = Arc::clone(&completed);
+            async move {
+                let result = read_device(id, timeout, freshness).await;
+                let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                if show_progress {
+                    match &result {
+                        Ok(reading) => {
+                            eprintln!("  [{}/{}] {} - OK", done, total_devices, reading.identifier);
+                        }
+                        Err((id, _)) => {
+                            eprintln!("  [{}/{}] {} - FAILED", done, total_devices, id);
+                        }
                     }
                 }
+                result
             }
-            result
-        }
-    });
-    let results: Vec<Result<DeviceReading, (String, anyhow::Error)>> = join_all(futures).await;
+        })
+        .buffer_unordered(max_concurrent)
+        .collect()
+        .await;
 
     // Collect successful readings and errors
     let mut readings = Vec::new();
@@ -143,10 +288,14 @@ async fn cmd_read_multi(
 
     for result in results {
         match result {
-            Ok(reading) => readings.push(reading),
+            Ok(mut reading) => {
+                reading.alias = alias_for(&reading.identifier, aliases);
+                readings.push(reading);
+            }
             Err((id, err)) => errors.push((id, err)),
         }
     }
+    readings.sort_by_cached_key(|r| r.label().to_lowercase());
 
     // Report detailed errors
     if !quiet && !errors.is_empty() {
@@ -160,20 +309,32 @@ async fn cmd_read_multi(
         bail!("Failed to read from any device");
     }
 
-    let content = match format {
-        OutputFormat::Json => format_multi_reading_json(&readings, opts)?,
-        OutputFormat::Text => format_multi_reading_text(&readings, opts),
-        OutputFormat::Csv => format_multi_reading_csv(&readings, opts),
+    let content = match (format, combined) {
+        (OutputFormat::Json, true) => format_multi_reading_json_array(&readings, opts)?,
+        (OutputFormat::Json, false) => format_multi_reading_json(&readings, opts)?,
+        (OutputFormat::Text, true) => format_multi_reading_table(&readings, opts),
+        (OutputFormat::Text, false) => format_multi_reading_text(&readings, opts),
+        (OutputFormat::Csv, _) => format_multi_reading_csv(&readings, opts),
     };
 
     write_output(output, &content)?;
     Ok(())
 }
 
+/// Find the alias configured for a device address.
+fn alias_for(identifier: &str, aliases: &HashMap<String, String>) -> Option<String> {
+    aliases
+        .iter()
+        .filter(|(_, address)| address.eq_ignore_ascii_case(identifier))
+        .map(|(alias, _)| alias.clone())
+        .min()
+}
+
 /// Read from a single device, returning the identifier with the result
 async fn read_device(
     identifier: String,
     timeout: Duration,
+    freshness: Option<Freshness>,
 ) -> Result<DeviceReading, (String, anyhow::Error)> {
     // Don't show progress for individual devices in multi-read mode
     // to avoid multiple spinners running in parallel
@@ -182,10 +343,9 @@ async fn read_device(
         .map_err(|e| (identifier.clone(), e))?;
 
     let device_id = device.address().to_string();
-    let reading_result = device
-        .read_current()
+    let name = device.name().map(|s| s.to_string());
+    let reading_result = read_until_fresh(&device, freshness, false)
         .await
-        .context("Failed to read current values")
         .map_err(|e| (identifier.clone(), e));
     crate::util::disconnect_device(&device).await;
     let reading = reading_result?;
@@ -195,11 +355,182 @@ async fn read_device(
 
     Ok(DeviceReading {
         identifier,
+        alias: None,
+        name,
         reading,
     })
 }
 
 /// Read sensor data from BLE advertisements without connecting.
+/// Readings older than this are stale when there is no poll interval to go by.
+///
+/// Matches the threshold the service uses for devices it does not poll.
+const CACHED_STALE_AFTER_SECS: i64 = 180;
+
+/// Where a cached reading came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CachedSource {
+    Store,
+    Service,
+}
+
+impl CachedSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            CachedSource::Store => "store",
+            CachedSource::Service => "service",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            CachedSource::Store => "local database",
+            CachedSource::Service => "aranet service",
+        }
+    }
+}
+
+/// Latest known reading of a device, with its age.
+#[derive(Debug, Clone)]
+struct CachedReading {
+    source: CachedSource,
+    name: Option<String>,
+    reading: CurrentReading,
+    age_seconds: i64,
+    stale: bool,
+}
+
+impl CachedReading {
+    fn from_store(stored: &StoredReading, name: Option<String>, now: OffsetDateTime) -> Self {
+        let age_seconds = (now - stored.captured_at).whole_seconds().max(0);
+        let mut reading = stored.to_reading();
+        reading.age = u16::try_from(age_seconds).unwrap_or(u16::MAX);
+        Self {
+            source: CachedSource::Store,
+            name,
+            reading,
+            age_seconds,
+            stale: age_seconds > CACHED_STALE_AFTER_SECS,
+        }
+    }
+
+    fn from_service(response: &CurrentReadingResponse) -> Self {
+        Self {
+            source: CachedSource::Service,
+            name: None,
+            reading: response.to_reading(),
+            age_seconds: response.age_seconds.max(0),
+            stale: response.stale,
+        }
+    }
+}
+
+/// Pick the newer of the local and the service reading.
+fn newer_reading(
+    local: Option<CachedReading>,
+    remote: Option<CachedReading>,
+) -> Option<CachedReading> {
+    match (local, remote) {
+        (Some(local), Some(remote)) if remote.age_seconds < local.age_seconds => Some(remote),
+        (Some(local), _) => Some(local),
+        (None, remote) => remote,
+    }
+}
+
+/// Latest reading from the local database, if it has one.
+fn latest_stored_reading(device: &str) -> Option<CachedReading> {
+    let store = match aranet_store::Store::open_default() {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::debug!("Failed to open store: {}", e);
+            return None;
+        }
+    };
+    let stored = store
+        .get_latest_reading(device)
+        .map_err(|e| tracing::debug!("Failed to read latest reading: {}", e))
+        .ok()
+        .flatten()?;
+    let name = store.get_device(device).ok().flatten().and_then(|d| d.name);
+    Some(CachedReading::from_store(
+        &stored,
+        name,
+        OffsetDateTime::now_utc(),
+    ))
+}
+
+/// Latest reading stored by the service, if it is running and has one.
+async fn latest_service_reading(
+    device: &str,
+    service_url: &str,
+    service_api_key: Option<&str>,
+) -> Option<CachedReading> {
+    let client = ServiceClient::new_with_api_key(service_url, service_api_key.map(String::from))
+        .map_err(|e| tracing::debug!("Invalid service URL: {}", e))
+        .ok()?;
+    match client.current_reading(device).await {
+        Ok(response) => Some(CachedReading::from_service(&response)),
+        Err(e) => {
+            tracing::debug!("No reading from service: {}", e);
+            None
+        }
+    }
+}
+
+/// Show the latest stored reading without touching Bluetooth.
+///
+/// A fresh reading in the local database is used as is; otherwise the
+/// service is asked too and the newer reading wins.
+async fn cmd_read_cached(
+    device: &str,
+    service_url: &str,
+    service_api_key: Option<&str>,
+    format: OutputFormat,
+    output: Option<&PathBuf>,
+    opts: &FormatOptions,
+) -> Result<()> {
+    let local = latest_stored_reading(device);
+    let cached = if local.as_ref().is_some_and(|r| !r.stale) {
+        local
+    } else {
+        let remote = latest_service_reading(device, service_url, service_api_key).await;
+        newer_reading(local, remote)
+    };
+
+    let Some(cached) = cached else {
+        bail!(
+            "No cached reading for '{}' in the local database or at {}. \
+             Read the device once with 'aranet read', or keep 'aranet server' running.",
+            device,
+            service_url
+        );
+    };
+
+    let content = match format {
+        OutputFormat::Json => format_cached_reading_json(
+            &cached.reading,
+            cached.source.as_str(),
+            cached.age_seconds,
+            cached.stale,
+            opts,
+        )?,
+        OutputFormat::Text => {
+            let mut text =
+                format_reading_text_with_name(&cached.reading, opts, cached.name.as_deref());
+            text.push_str(&format!(
+                "Source:      {}{}\n",
+                cached.source.label(),
+                if cached.stale { " (STALE)" } else { "" }
+            ));
+            text
+        }
+        OutputFormat::Csv => format_reading_csv(&cached.reading, opts),
+    };
+
+    write_output(output, &content)?;
+    Ok(())
+}
+
 async fn cmd_read_passive(
     device: Option<String>,
     timeout: Duration,
@@ -258,26 +589,7 @@ async fn cmd_read_passive(
     let adv = parse_advertisement_with_name(mfr_data, device_name)
         .context("Failed to parse advertisement data")?;
 
-    // Convert AdvertisementData to CurrentReading using builder
-    let mut builder = CurrentReading::builder()
-        .co2(adv.co2.unwrap_or(0))
-        .temperature(adv.temperature.unwrap_or(0.0))
-        .pressure(adv.pressure.unwrap_or(0.0))
-        .humidity(adv.humidity.unwrap_or(0))
-        .battery(adv.battery)
-        .status(adv.status)
-        .interval(adv.interval)
-        .age(adv.age);
-
-    // Add device-specific fields
-    if let Some(radon) = adv.radon {
-        builder = builder.radon(radon);
-    }
-    if let Some(rate) = adv.radiation_dose_rate {
-        builder = builder.radiation_rate(rate);
-    }
-
-    let reading = builder.build();
+    let reading = adv.to_reading();
 
     if !quiet && matches!(format, OutputFormat::Text) {
         let name = discovered.name.as_deref().unwrap_or(&discovered.identifier);
@@ -293,3 +605,89 @@ async fn cmd_read_passive(
     write_output(output, &content)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(age: u16, interval: u16) -> CurrentReading {
+        CurrentReading::builder()
+            .age(age)
+            .interval(interval)
+            .build()
+    }
+
+    #[test]
+    fn test_freshness_compares_age_with_elapsed_time() {
+        let freshness = Freshness {
+            started: Instant::now() - Duration::from_secs(30),
+            limit: Duration::from_secs(600),
+        };
+        assert!(freshness.is_fresh(&reading(10, 60)));
+        assert!(!freshness.is_fresh(&reading(30, 60)));
+        assert!(!freshness.is_fresh(&reading(45, 60)));
+    }
+
+    fn cached(source: CachedSource, age_seconds: i64) -> CachedReading {
+        CachedReading {
+            source,
+            name: None,
+            reading: reading(0, 0),
+            age_seconds,
+            stale: false,
+        }
+    }
+
+    #[test]
+    fn test_cached_reading_from_store_is_stale_after_threshold() {
+        let now = OffsetDateTime::now_utc();
+        let mut stored = StoredReading::from_reading("AA:BB", &reading(0, 60));
+        stored.captured_at = now - time::Duration::seconds(CACHED_STALE_AFTER_SECS);
+        let fresh = CachedReading::from_store(&stored, None, now);
+        assert_eq!(fresh.reading.age, CACHED_STALE_AFTER_SECS as u16);
+        assert!(!fresh.stale);
+
+        stored.captured_at = now - time::Duration::days(2);
+        let old = CachedReading::from_store(&stored, None, now);
+        assert_eq!(old.age_seconds, 2 * 86_400);
+        assert_eq!(old.reading.age, u16::MAX);
+        assert!(old.stale);
+    }
+
+    #[test]
+    fn test_newer_reading_prefers_youngest() {
+        let local = cached(CachedSource::Store, 600);
+        let remote = cached(CachedSource::Service, 30);
+        assert_eq!(
+            newer_reading(Some(local.clone()), Some(remote)).map(|r| r.source),
+            Some(CachedSource::Service)
+        );
+        let remote = cached(CachedSource::Service, 900);
+        assert_eq!(
+            newer_reading(Some(local), Some(remote.clone())).map(|r| r.source),
+            Some(CachedSource::Store)
+        );
+        assert_eq!(
+            newer_reading(None, Some(remote)).map(|r| r.source),
+            Some(CachedSource::Service)
+        );
+        assert!(newer_reading(None, None).is_none());
+    }
+
+    #[test]
+    fn test_until_next_measurement() {
+        assert_eq!(
+            until_next_measurement(&reading(45, 60)),
+            Duration::from_secs(17)
+        );
+        // Overdue measurements are retried shortly
+        assert_eq!(
+            until_next_measurement(&reading(70, 60)),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            until_next_measurement(&reading(0, 0)),
+            Duration::from_secs(5)
+        );
+    }
+}
@@ -1,7 +1,7 @@
 //! Report command - generate data summaries.
 
 use anyhow::{Context, Result, bail};
-use aranet_store::{HistoryQuery, Store};
+use aranet_store::{ExposureStats, HistoryQuery, MeasurementCoverage, Store};
 use time::{Duration, OffsetDateTime};
 
 use crate::cli::{ReportFormat, ReportOutputArgs, ReportPeriod};
@@ -93,12 +93,70 @@ fn resolve_report_devices(store: &Store, device: Option<String>, all: bool) -> R
 #[derive(serde::Serialize)]
 struct DeviceReport {
     device_id: String,
-    record_count: usize,
+    record_count: u64,
     co2: Option<MetricSummary>,
     temperature: Option<MetricSummary>,
     humidity: Option<MetricSummary>,
     pressure: Option<MetricSummary>,
     radon: Option<MetricSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    co2_exposure: Option<ExposureSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coverage: Option<CoverageSummary>,
+}
+
+/// Share of the device's measurements received over the report period,
+/// from the counters of passively collected readings.
+#[derive(serde::Serialize)]
+struct CoverageSummary {
+    expected: u64,
+    received: u64,
+    missed: u64,
+    percent: f64,
+}
+
+impl From<MeasurementCoverage> for CoverageSummary {
+    fn from(coverage: MeasurementCoverage) -> Self {
+        Self {
+            expected: coverage.expected,
+            received: coverage.received,
+            missed: coverage.missed(),
+            percent: coverage.percent(),
+        }
+    }
+}
+
+/// CO₂ exposure over the report period, with the per-day breakdown.
+#[derive(serde::Serialize)]
+struct ExposureSummary {
+    hours_above_800: f64,
+    hours_above_1000: f64,
+    ppm_hours_above_800: f64,
+    ppm_hours_above_1000: f64,
+    /// Highest single-day ppm-hours above 800 ppm.
+    max_daily_ppm_hours_above_800: f64,
+    /// Highest single-day ppm-hours above 1000 ppm.
+    max_daily_ppm_hours_above_1000: f64,
+    daily: Vec<ExposureStats>,
+}
+
+impl ExposureSummary {
+    fn from_days(daily: Vec<ExposureStats>) -> Option<Self> {
+        if daily.is_empty() {
+            return None;
+        }
+        let sum = |f: fn(&ExposureStats) -> f64| daily.iter().map(f).sum::<f64>();
+        let max = |f: fn(&ExposureStats) -> f64| daily.iter().map(f).fold(0.0, f64::max);
+        Some(Self {
+            hours_above_800: sum(|d| d.hours_above_800),
+            hours_above_1000: sum(|d| d.hours_above_1000),
+            ppm_hours_above_800: sum(|d| d.ppm_hours_above_800),
+            ppm_hours_above_1000: sum(|d| d.ppm_hours_above_1000),
+            max_daily_ppm_hours_above_800: max(|d| d.ppm_hours_above_800),
+            max_daily_ppm_hours_above_1000: max(|d| d.ppm_hours_above_1000),
+            daily,
+        })
+    }
 }
 
 #[derive(serde::Serialize)]
@@ -109,112 +167,184 @@ struct MetricSummary {
     time_above_threshold: Option<f64>,
 }
 
+/// Running totals for one metric, merged from cached days and raw records.
+#[derive(Default)]
+struct MetricTotals {
+    count: u64,
+    min: f64,
+    max: f64,
+    sum: f64,
+    above_threshold: u64,
+}
+
+impl MetricTotals {
+    fn add(&mut self, value: f64, above_threshold: bool) {
+        self.merge(1, value, value, value, above_threshold as u64);
+    }
+
+    /// Add a cached day; `avg` is expanded back into a sum over `count` values.
+    fn add_day(
+        &mut self,
+        count: u64,
+        min: Option<f64>,
+        max: Option<f64>,
+        avg: Option<f64>,
+        above_threshold: u64,
+    ) {
+        if let (Some(min), Some(max), Some(avg)) = (min, max, avg) {
+            self.merge(count, min, max, avg * count as f64, above_threshold);
+        }
+    }
+
+    fn merge(&mut self, count: u64, min: f64, max: f64, sum: f64, above_threshold: u64) {
+        if count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            (self.min, self.max) = (min, max);
+        } else {
+            self.min = self.min.min(min);
+            self.max = self.max.max(max);
+        }
+        self.count += count;
+        self.sum += sum;
+        self.above_threshold += above_threshold;
+    }
+
+    /// Summary over `record_count` records, with the share of them above the
+    /// threshold if the metric has one.
+    fn summary(&self, record_count: u64, has_threshold: bool) -> Option<MetricSummary> {
+        (self.count > 0).then(|| MetricSummary {
+            min: self.min,
+            max: self.max,
+            avg: self.sum / self.count as f64,
+            time_above_threshold: has_threshold
+                .then(|| self.above_threshold as f64 / record_count as f64 * 100.0),
+        })
+    }
+}
+
+/// Start of the UTC day after the one containing `ts`.
+fn next_utc_midnight(ts: OffsetDateTime) -> OffsetDateTime {
+    ts.to_offset(time::UtcOffset::UTC)
+        .date()
+        .midnight()
+        .assume_utc()
+        + Duration::days(1)
+}
+
+/// Summarize a device's history since `since`.
+///
+/// Whole UTC days come from the store's daily statistics cache, so a monthly
+/// report reads about 30 rows; only the partial day at the start of the
+/// period is aggregated from raw records.
 fn generate_device_report(
     store: &Store,
     device_id: &str,
     since: OffsetDateTime,
 ) -> Result<Option<DeviceReport>> {
     let query = HistoryQuery::new().device(device_id).since(since);
-    let records = store.query_history(&query)?;
-
-    if records.is_empty() {
-        return Ok(None);
+    let first_cached_day = next_utc_midnight(since);
+
+    let mut count = 0u64;
+    let mut co2 = MetricTotals::default();
+    let mut temperature = MetricTotals::default();
+    let mut humidity = MetricTotals::default();
+    let mut pressure = MetricTotals::default();
+    let mut radon = MetricTotals::default();
+
+    let cached_query = HistoryQuery::new()
+        .device(device_id)
+        .since(first_cached_day);
+    for day in store.daily_stats(&cached_query)? {
+        count += day.count;
+        co2.add_day(
+            day.co2_count,
+            day.min.co2,
+            day.max.co2,
+            day.avg.co2,
+            day.co2_buckets.moderate + day.co2_buckets.poor,
+        );
+        temperature.add_day(
+            day.count,
+            day.min.temperature,
+            day.max.temperature,
+            day.avg.temperature,
+            0,
+        );
+        humidity.add_day(
+            day.count,
+            day.min.humidity,
+            day.max.humidity,
+            day.avg.humidity,
+            0,
+        );
+        pressure.add_day(
+            day.pressure_count,
+            day.min.pressure,
+            day.max.pressure,
+            day.avg.pressure,
+            0,
+        );
+        radon.add_day(
+            day.radon_count,
+            day.min.radon,
+            day.max.radon,
+            day.avg.radon,
+            day.radon_buckets.poor,
+        );
     }
 
-    let count = records.len();
-
-    // CO2
-    let co2_vals: Vec<f64> = records
-        .iter()
-        .map(|r| r.co2 as f64)
-        .filter(|&v| v > 0.0)
-        .collect();
-    let co2 = if !co2_vals.is_empty() {
-        let above_1000 = co2_vals.iter().filter(|&&v| v >= 1000.0).count();
-        Some(MetricSummary {
-            min: co2_vals.iter().copied().reduce(f64::min).unwrap_or(0.0),
-            max: co2_vals.iter().copied().reduce(f64::max).unwrap_or(0.0),
-            avg: co2_vals.iter().sum::<f64>() / co2_vals.len() as f64,
-            time_above_threshold: Some((above_1000 as f64 / count as f64) * 100.0),
-        })
-    } else {
-        None
-    };
-
-    // Temperature
-    let temp_vals: Vec<f64> = records.iter().map(|r| r.temperature as f64).collect();
-    let temperature = if !temp_vals.is_empty() {
-        Some(MetricSummary {
-            min: temp_vals.iter().copied().reduce(f64::min).unwrap_or(0.0),
-            max: temp_vals.iter().copied().reduce(f64::max).unwrap_or(0.0),
-            avg: temp_vals.iter().sum::<f64>() / temp_vals.len() as f64,
-            time_above_threshold: None,
-        })
-    } else {
-        None
-    };
-
-    // Humidity
-    let hum_vals: Vec<f64> = records.iter().map(|r| r.humidity as f64).collect();
-    let humidity = if !hum_vals.is_empty() {
-        Some(MetricSummary {
-            min: hum_vals.iter().copied().reduce(f64::min).unwrap_or(0.0),
-            max: hum_vals.iter().copied().reduce(f64::max).unwrap_or(0.0),
-            avg: hum_vals.iter().sum::<f64>() / hum_vals.len() as f64,
-            time_above_threshold: None,
-        })
-    } else {
-        None
-    };
+    let partial_day = HistoryQuery::new()
+        .device(device_id)
+        .since(since)
+        .until(first_cached_day - Duration::seconds(1));
+    for record in store.query_history(&partial_day)? {
+        count += 1;
+        if record.co2 > 0 {
+            co2.add(record.co2 as f64, record.co2 >= 1000);
+        }
+        temperature.add(record.temperature as f64, false);
+        humidity.add(record.humidity as f64, false);
+        if record.pressure > 0.0 {
+            pressure.add(record.pressure as f64, false);
+        }
+        if let Some(value) = record.radon {
+            radon.add(value as f64, value >= 300);
+        }
+    }
 
-    // Pressure
-    let press_vals: Vec<f64> = records
-        .iter()
-        .map(|r| r.pressure as f64)
-        .filter(|&v| v > 0.0)
-        .collect();
-    let pressure = if !press_vals.is_empty() {
-        Some(MetricSummary {
-            min: press_vals.iter().copied().reduce(f64::min).unwrap_or(0.0),
-            max: press_vals.iter().copied().reduce(f64::max).unwrap_or(0.0),
-            avg: press_vals.iter().sum::<f64>() / press_vals.len() as f64,
-            time_above_threshold: None,
-        })
-    } else {
-        None
-    };
+    if count == 0 {
+        return Ok(None);
+    }
 
-    // Radon
-    let radon_vals: Vec<f64> = records
-        .iter()
-        .filter_map(|r| r.radon.map(|v| v as f64))
-        .collect();
-    let radon = if !radon_vals.is_empty() {
-        let above_300 = radon_vals.iter().filter(|&&v| v >= 300.0).count();
-        Some(MetricSummary {
-            min: radon_vals.iter().copied().reduce(f64::min).unwrap_or(0.0),
-            max: radon_vals.iter().copied().reduce(f64::max).unwrap_or(0.0),
-            avg: radon_vals.iter().sum::<f64>() / radon_vals.len() as f64,
-            time_above_threshold: Some((above_300 as f64 / count as f64) * 100.0),
-        })
-    } else {
-        None
-    };
+    let co2_exposure = ExposureSummary::from_days(store.exposure_stats(&query)?);
+    let coverage = store
+        .measurement_coverage(device_id, Some(since))?
+        .map(CoverageSummary::from);
 
     Ok(Some(DeviceReport {
         device_id: device_id.to_string(),
         record_count: count,
-        co2,
-        temperature,
-        humidity,
-        pressure,
-        radon,
+        co2: co2.summary(count, true),
+        temperature: temperature.summary(count, false),
+        humidity: humidity.summary(count, false),
+        pressure: pressure.summary(count, false),
+        radon: radon.summary(count, true),
+        co2_exposure,
+        coverage,
     }))
 }
 
 fn print_device_report(report: &DeviceReport, fahrenheit: bool, inhg: bool, bq: bool) {
     println!("Device: {}", report.device_id);
     println!("  Records: {}", report.record_count);
+    if let Some(ref coverage) = report.coverage {
+        println!(
+            "  Coverage: {:.1}% ({} of {} measurements missed)",
+            coverage.percent, coverage.missed, coverage.expected
+        );
+    }
 
     if let Some(ref co2) = report.co2 {
         println!("  CO\u{2082}:");
@@ -227,6 +357,22 @@ fn print_device_report(report: &DeviceReport, fahrenheit: bool, inhg: bool, bq:
         }
     }
 
+    if let Some(ref exposure) = report.co2_exposure {
+        println!("  CO\u{2082} exposure:");
+        println!(
+            "    Above 800 ppm: {:.1} h, {:.0} ppm\u{b7}h (worst day {:.0} ppm\u{b7}h)",
+            exposure.hours_above_800,
+            exposure.ppm_hours_above_800,
+            exposure.max_daily_ppm_hours_above_800
+        );
+        println!(
+            "    Above 1000 ppm: {:.1} h, {:.0} ppm\u{b7}h (worst day {:.0} ppm\u{b7}h)",
+            exposure.hours_above_1000,
+            exposure.ppm_hours_above_1000,
+            exposure.max_daily_ppm_hours_above_1000
+        );
+    }
+
     if let Some(ref temp) = report.temperature {
         if fahrenheit {
             println!("  Temperature:");
@@ -326,6 +472,139 @@ mod tests {
         assert_eq!(devices, vec!["device-1", "device-2"]);
     }
 
+    #[test]
+    fn test_report_includes_co2_exposure() {
+        let store = seed_store(&["device-1"]);
+        let start = OffsetDateTime::now_utc() - Duration::hours(2);
+        let records: Vec<_> = [900u16, 1100, 1200]
+            .into_iter()
+            .enumerate()
+            .map(|(i, co2)| aranet_types::HistoryRecord {
+                timestamp: start + Duration::minutes(10 * i as i64),
+                co2,
+                temperature: 21.0,
+                pressure: 1010.0,
+                humidity: 40,
+                radon: None,
+                radiation_rate: None,
+                radiation_total: None,
+            })
+            .collect();
+        store.insert_history("device-1", &records).unwrap();
+
+        let report = generate_device_report(&store, "device-1", start - Duration::hours(1))
+            .unwrap()
+            .unwrap();
+        let exposure = report.co2_exposure.unwrap();
+        assert!((exposure.hours_above_800 - 0.5).abs() < 1e-9);
+        assert!((exposure.hours_above_1000 - 20.0 / 60.0).abs() < 1e-9);
+        assert!(!exposure.daily.is_empty());
+        // No passively collected readings, so no counters
+        assert!(report.coverage.is_none());
+    }
+
+    #[test]
+    fn test_report_includes_measurement_coverage() {
+        let store = seed_store(&["device-1"]);
+        let start = OffsetDateTime::now_utc() - Duration::hours(2);
+        store
+            .insert_history(
+                "device-1",
+                &[aranet_types::HistoryRecord {
+                    timestamp: start,
+                    co2: 800,
+                    temperature: 21.0,
+                    pressure: 1010.0,
+                    humidity: 40,
+                    radon: None,
+                    radiation_rate: None,
+                    radiation_total: None,
+                }],
+            )
+            .unwrap();
+        for (minute, counter) in [(0, 7), (1, 8), (3, 10)] {
+            let reading = aranet_types::CurrentReading::builder()
+                .co2(800)
+                .interval(60)
+                .captured_at(start + Duration::minutes(minute))
+                .build();
+            store
+                .insert_reading_with_counter("device-1", &reading, counter)
+                .unwrap();
+        }
+
+        let report = generate_device_report(&store, "device-1", start - Duration::hours(1))
+            .unwrap()
+            .unwrap();
+        let coverage = report.coverage.unwrap();
+        assert_eq!((coverage.expected, coverage.received), (4, 3));
+        assert_eq!(coverage.missed, 1);
+    }
+
+    fn history_at(timestamp: OffsetDateTime, co2: u16) -> aranet_types::HistoryRecord {
+        aranet_types::HistoryRecord {
+            timestamp,
+            co2,
+            temperature: 21.0,
+            pressure: 1010.0,
+            humidity: 40,
+            radon: None,
+            radiation_rate: None,
+            radiation_total: None,
+        }
+    }
+
+    #[test]
+    fn test_report_reads_whole_days_from_daily_stats() {
+        let store = seed_store(&["device-1"]);
+        let today = OffsetDateTime::now_utc().date().midnight().assume_utc();
+        let records = [
+            history_at(today - Duration::days(3) + Duration::hours(9), 600),
+            history_at(today - Duration::days(3) + Duration::hours(10), 1200),
+            history_at(today - Duration::days(2) + Duration::hours(9), 900),
+        ];
+        store.insert_history("device-1", &records).unwrap();
+
+        // Raw rows are gone, but the cached days still cover them
+        store.prune_history(today).unwrap();
+        assert_eq!(store.count_history(Some("device-1")).unwrap(), 0);
+
+        let report = generate_device_report(&store, "device-1", today - Duration::days(7))
+            .unwrap()
+            .unwrap();
+        assert_eq!(report.record_count, 3);
+        let co2 = report.co2.unwrap();
+        assert_eq!((co2.min, co2.max, co2.avg), (600.0, 1200.0, 900.0));
+        assert!((co2.time_above_threshold.unwrap() - 100.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_report_merges_partial_first_day_from_raw_records() {
+        let store = seed_store(&["device-1"]);
+        let today = OffsetDateTime::now_utc().date().midnight().assume_utc();
+        let yesterday = today - Duration::days(1);
+        store
+            .insert_history(
+                "device-1",
+                &[
+                    // Before the period start on the first day: excluded
+                    history_at(yesterday + Duration::hours(1), 2000),
+                    history_at(yesterday + Duration::hours(20), 500),
+                    history_at(today + Duration::minutes(1), 700),
+                ],
+            )
+            .unwrap();
+
+        let report = generate_device_report(&store, "device-1", yesterday + Duration::hours(12))
+            .unwrap()
+            .unwrap();
+        assert_eq!(report.record_count, 2);
+        let co2 = report.co2.unwrap();
+        assert_eq!((co2.min, co2.max, co2.avg), (500.0, 700.0, 600.0));
+        assert_eq!(co2.time_above_threshold, Some(0.0));
+        assert!(report.radon.is_none());
+    }
+
     #[test]
     fn test_resolve_report_devices_errors_on_ambiguous_none() {
         let store = seed_store(&["device-1", "device-2"]);
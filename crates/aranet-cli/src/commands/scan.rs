@@ -4,7 +4,9 @@ use std::io::{self, Write};
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
-use aranet_core::{ScanOptions, scan};
+use aranet_core::{DeviceName, ScanOptions, scan};
+use aranet_store::{ADVERTISEMENT_LOG_MAX_ROWS, Store};
+use time::OffsetDateTime;
 
 use crate::cli::OutputFormat;
 use crate::config::Config;
@@ -14,12 +16,14 @@ use crate::format::{
 use crate::style;
 use crate::util::write_output;
 
+#[allow(clippy::too_many_arguments)]
 pub async fn cmd_scan(
     timeout: u64,
     format: OutputFormat,
     output: Option<&PathBuf>,
     quiet: bool,
     save_alias: bool,
+    log_adverts: bool,
     opts: &FormatOptions,
     config: &Config,
 ) -> Result<()> {
@@ -43,6 +47,13 @@ pub async fn cmd_scan(
         sp.finish_and_clear();
     }
 
+    if log_adverts {
+        let logged = log_advertisements(&devices)?;
+        if !quiet {
+            eprintln!("Logged {} advertisement(s) to the local database.", logged);
+        }
+    }
+
     // For text format, show aliases and tips
     let content = match format {
         OutputFormat::Json => format_scan_json(&devices, opts)?,
@@ -55,6 +66,10 @@ pub async fn cmd_scan(
 
     write_output(output, &content)?;
 
+    if matches!(format, OutputFormat::Text) && !quiet {
+        report_moved_devices(&devices);
+    }
+
     // Handle --alias flag for interactive alias saving
     if save_alias && !devices.is_empty() && matches!(format, OutputFormat::Text) {
         save_aliases_interactive(&devices, config)?;
@@ -63,9 +78,28 @@ pub async fn cmd_scan(
     Ok(())
 }
 
+/// Record the raw manufacturer data of discovered devices in the store,
+/// keeping the log at [`ADVERTISEMENT_LOG_MAX_ROWS`].
+fn log_advertisements(devices: &[aranet_core::scan::DiscoveredDevice]) -> Result<usize> {
+    let store = Store::open_default().context("Failed to open database")?;
+    let now = OffsetDateTime::now_utc();
+    let mut logged = 0;
+    for device in devices {
+        if let Some(payload) = &device.manufacturer_data {
+            store.log_advertisement(&device.identifier, device.rssi, payload, now)?;
+            logged += 1;
+        }
+    }
+    store.trim_advertisements(ADVERTISEMENT_LOG_MAX_ROWS)?;
+    Ok(logged)
+}
+
 /// Generate a suggested alias from a device name.
 /// Converts "Aranet4 12ABC" to "aranet4-12abc" style.
 fn suggest_alias(device_name: &str) -> String {
+    if let Some(name) = DeviceName::parse(device_name) {
+        return name.default_alias();
+    }
     device_name
         .to_lowercase()
         .chars()
@@ -77,6 +111,33 @@ fn suggest_alias(device_name: &str) -> String {
         .join("-")
 }
 
+/// Point out discovered devices whose cached data is stored under a
+/// different identifier (e.g. after macOS assigned a new UUID).
+///
+/// Does nothing if there is no local database yet.
+fn report_moved_devices(devices: &[aranet_core::scan::DiscoveredDevice]) {
+    if !aranet_store::default_db_path().exists() {
+        return;
+    }
+    let Ok(store) = Store::open_default() else {
+        return;
+    };
+
+    for device in devices {
+        let Some(name) = device.name.as_deref() else {
+            continue;
+        };
+        if let Ok(Some(stored)) = store.find_device_by_name(name)
+            && !stored.id.eq_ignore_ascii_case(&device.identifier)
+        {
+            eprintln!(
+                "{} was previously cached as {}; its history stays under that ID.",
+                name, stored.id
+            );
+        }
+    }
+}
+
 /// Interactively prompt user to save aliases for discovered devices.
 fn save_aliases_interactive(
     devices: &[aranet_core::scan::DiscoveredDevice],
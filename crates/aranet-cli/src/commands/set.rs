@@ -1,13 +1,24 @@
 //! Set command implementation.
+//!
+//! Without a setting argument, `aranet set` reads the device's settings and
+//! offers a menu to change them, applies the changes with read-back
+//! verification and prints what changed.
 
 use std::io::{self, IsTerminal, Write};
 use std::time::Duration;
 
-use anyhow::Result;
-use aranet_core::{BluetoothRange, MeasurementInterval};
+use anyhow::{Context, Result, bail};
+use aranet_core::{
+    BluetoothRange, DeviceSettings, MeasurementInterval, RadonUnit, SettingChange, TemperatureUnit,
+};
+use aranet_types::DeviceType;
+use dialoguer::{Confirm, Select, theme::ColorfulTheme};
 
 use crate::cli::{BluetoothRangeSetting, DeviceSetting};
-use crate::util::{connect_device_with_progress, require_device_interactive};
+use crate::util::{
+    connect_device_with_progress, disconnect_device, require_device_interactive,
+    save_settings_to_store,
+};
 
 /// Prompt user for confirmation before making changes.
 /// Returns true if user confirms, false otherwise.
@@ -60,10 +71,13 @@ fn describe_setting_change(setting: &DeviceSetting) -> String {
 pub async fn cmd_set(
     device: Option<String>,
     timeout: Duration,
-    setting: DeviceSetting,
+    setting: Option<DeviceSetting>,
     quiet: bool,
     force: bool,
 ) -> Result<()> {
+    let Some(setting) = setting else {
+        return cmd_set_interactive(device, timeout, quiet).await;
+    };
     let identifier = require_device_interactive(device).await?;
 
     // Confirm before making changes (unless --force is used)
@@ -86,6 +100,7 @@ pub async fn cmd_set(
                 )
             })?;
             device.set_interval(interval).await?;
+            save_settings_to_store(&identifier, &[SettingChange::Interval(interval)]);
             if !quiet {
                 println!("Measurement interval set to {} minute(s)", minutes);
             }
@@ -97,6 +112,7 @@ pub async fn cmd_set(
                 BluetoothRangeSetting::Extended => BluetoothRange::Extended,
             };
             device.set_bluetooth_range(bt_range).await?;
+            save_settings_to_store(&identifier, &[SettingChange::BluetoothRange(bt_range)]);
             if !quiet {
                 println!("Bluetooth range set to {:?}", bt_range);
             }
@@ -104,6 +120,7 @@ pub async fn cmd_set(
         }
         DeviceSetting::SmartHome { enabled } => {
             device.set_smart_home(enabled).await?;
+            save_settings_to_store(&identifier, &[SettingChange::SmartHome(enabled)]);
             if !quiet {
                 println!(
                     "Smart Home integration {}",
@@ -116,3 +133,277 @@ pub async fn cmd_set(
     crate::util::disconnect_device(&device).await;
     update_result
 }
+
+/// Settings shown to the user, as (label, value) rows.
+///
+/// The display units and buzzer cannot be changed over Bluetooth, so they
+/// are shown but never offered in the menu.
+fn settings_rows(
+    settings: &DeviceSettings,
+    device_type: Option<DeviceType>,
+) -> Vec<(&'static str, String)> {
+    let mut rows = vec![
+        (
+            "Interval",
+            settings.interval.map_or("unknown".to_string(), |i| {
+                format!("{} min", i.as_seconds() / 60)
+            }),
+        ),
+        (
+            "Bluetooth range",
+            range_label(settings.bluetooth_range).to_string(),
+        ),
+        (
+            "Smart Home",
+            on_off(settings.smart_home_enabled).to_string(),
+        ),
+    ];
+    if device_type != Some(DeviceType::AranetRadiation) {
+        rows.push((
+            "Temperature unit",
+            temperature_unit_label(settings.temperature_unit).to_string(),
+        ));
+    }
+    if device_type == Some(DeviceType::AranetRadon) {
+        rows.push((
+            "Radon unit",
+            radon_unit_label(settings.radon_unit).to_string(),
+        ));
+    }
+    rows.push(("Buzzer", on_off(settings.buzzer_enabled).to_string()));
+    rows
+}
+
+/// Settings as an aligned list, marking values that differ from `before`
+/// as `before -> after`.
+fn format_settings_diff(
+    before: &DeviceSettings,
+    after: &DeviceSettings,
+    device_type: Option<DeviceType>,
+) -> String {
+    settings_rows(before, device_type)
+        .into_iter()
+        .zip(settings_rows(after, device_type))
+        .map(|((label, old), (_, new))| {
+            if old == new {
+                format!("  {:<18}{}\n", label, new)
+            } else {
+                format!("  {:<18}{} -> {}\n", label, old, new)
+            }
+        })
+        .collect()
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled { "on" } else { "off" }
+}
+
+fn range_label(range: BluetoothRange) -> &'static str {
+    match range {
+        BluetoothRange::Standard => "Standard",
+        BluetoothRange::Extended => "Extended",
+    }
+}
+
+fn temperature_unit_label(unit: TemperatureUnit) -> &'static str {
+    match unit {
+        TemperatureUnit::Celsius => "Celsius",
+        TemperatureUnit::Fahrenheit => "Fahrenheit",
+    }
+}
+
+fn radon_unit_label(unit: RadonUnit) -> &'static str {
+    match unit {
+        RadonUnit::BqM3 => "Bq/m³",
+        RadonUnit::PciL => "pCi/L",
+    }
+}
+
+/// Entries of the interactive settings menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuItem {
+    Interval,
+    Range,
+    SmartHome,
+    Apply,
+    Cancel,
+}
+
+/// Ask the user to pick one of `options`, starting at the current value.
+fn choose<T: Copy + PartialEq>(prompt: &str, options: &[(T, &str)], current: T) -> Result<T> {
+    let labels: Vec<&str> = options.iter().map(|(_, label)| *label).collect();
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(&labels)
+        .default(options.iter().position(|(v, _)| *v == current).unwrap_or(0))
+        .interact()
+        .context("Failed to get user selection")?;
+    Ok(options[selection].0)
+}
+
+/// Let the user edit `current` through a menu.
+///
+/// Returns the edited settings, or `None` if the user cancelled.
+fn edit_settings(
+    current: &DeviceSettings,
+    device_type: Option<DeviceType>,
+) -> Result<Option<DeviceSettings>> {
+    let mut target = current.clone();
+    loop {
+        let rows = settings_rows(&target, device_type);
+        let value = |label: &str| {
+            rows.iter()
+                .find(|(l, _)| *l == label)
+                .map_or(String::new(), |(_, v)| v.clone())
+        };
+        let mut items = vec![
+            (
+                MenuItem::Interval,
+                format!("Interval: {}", value("Interval")),
+            ),
+            (
+                MenuItem::Range,
+                format!("Bluetooth range: {}", value("Bluetooth range")),
+            ),
+            (
+                MenuItem::SmartHome,
+                format!("Smart Home: {}", value("Smart Home")),
+            ),
+        ];
+        let pending = current.diff(&target).len();
+        items.push((MenuItem::Apply, format!("Apply changes ({})", pending)));
+        items.push((MenuItem::Cancel, "Cancel".to_string()));
+
+        let labels: Vec<&str> = items.iter().map(|(_, label)| label.as_str()).collect();
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select a setting to change")
+            .items(&labels)
+            .default(0)
+            .interact()
+            .context("Failed to get user selection")?;
+
+        match items[selection].0 {
+            MenuItem::Interval => {
+                let minutes = choose(
+                    "Measurement interval",
+                    &[(1, "1 min"), (2, "2 min"), (5, "5 min"), (10, "10 min")],
+                    target.interval.map_or(0, |i| i.as_seconds() / 60),
+                )?;
+                target.interval = MeasurementInterval::from_minutes(minutes as u8);
+            }
+            MenuItem::Range => {
+                target.bluetooth_range = choose(
+                    "Bluetooth range",
+                    &[
+                        (BluetoothRange::Standard, "Standard (lower power)"),
+                        (BluetoothRange::Extended, "Extended (higher power)"),
+                    ],
+                    target.bluetooth_range,
+                )?;
+            }
+            MenuItem::SmartHome => {
+                target.smart_home_enabled = choose(
+                    "Smart Home integration",
+                    &[(true, "on"), (false, "off")],
+                    target.smart_home_enabled,
+                )?;
+            }
+            MenuItem::Apply => return Ok(Some(target)),
+            MenuItem::Cancel => return Ok(None),
+        }
+    }
+}
+
+/// Interactive `aranet set`: read, edit, confirm, apply and verify.
+async fn cmd_set_interactive(device: Option<String>, timeout: Duration, quiet: bool) -> Result<()> {
+    if !io::stdin().is_terminal() {
+        bail!(
+            "Interactive mode needs a terminal. Pass a setting instead, e.g. `aranet set interval 5`."
+        );
+    }
+    let identifier = require_device_interactive(device).await?;
+
+    // Read the current settings, then disconnect while the user decides
+    let device = connect_device_with_progress(&identifier, timeout, !quiet).await?;
+    let device_type = device.device_type();
+    let read = device.get_settings_with_interval().await;
+    disconnect_device(&device).await;
+    let before = read.context("Failed to read device settings")?;
+    print!(
+        "Current settings:\n{}",
+        format_settings_diff(&before, &before, device_type)
+    );
+
+    let Some(target) = edit_settings(&before, device_type)? else {
+        eprintln!("Cancelled.");
+        return Ok(());
+    };
+    let diff = before.diff(&target);
+    if diff.is_empty() {
+        println!("No changes.");
+        return Ok(());
+    }
+    print!(
+        "Changes:\n{}",
+        format_settings_diff(&before, &target, device_type)
+    );
+    let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Apply {} change(s)?", diff.len()))
+        .default(false)
+        .interact()
+        .context("Failed to get confirmation")?;
+    if !confirmed {
+        eprintln!("Cancelled.");
+        return Ok(());
+    }
+
+    // Each write is read back by apply_settings; read everything once more
+    // for the summary
+    let device = connect_device_with_progress(&identifier, timeout, !quiet).await?;
+    let result = async {
+        let applied = device.apply_settings(&target).await?;
+        let after = device.get_settings_with_interval().await?;
+        Ok::<_, aranet_core::Error>((applied, after))
+    }
+    .await;
+    disconnect_device(&device).await;
+    let (applied, after) = result.context("Failed to apply settings")?;
+    save_settings_to_store(&identifier, &applied.applied);
+
+    for (change, reason) in &applied.skipped {
+        eprintln!("Skipped {}: {}", change, reason);
+    }
+    print!(
+        "Applied {} change(s):\n{}",
+        applied.applied.len(),
+        format_settings_diff(&before, &after, device_type)
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_settings_diff() {
+        let before = DeviceSettings {
+            interval: Some(MeasurementInterval::FiveMinutes),
+            ..DeviceSettings::default()
+        };
+        let after = DeviceSettings {
+            interval: Some(MeasurementInterval::OneMinute),
+            smart_home_enabled: true,
+            ..before.clone()
+        };
+
+        let diff = format_settings_diff(&before, &after, Some(DeviceType::Aranet4));
+        assert!(diff.contains("  Interval          5 min -> 1 min\n"));
+        assert!(diff.contains("  Smart Home        off -> on\n"));
+        assert!(diff.contains("  Bluetooth range   Standard\n"));
+        assert!(!diff.contains("Radon unit"));
+
+        let radon = format_settings_diff(&before, &before, Some(DeviceType::AranetRadon));
+        assert!(radon.contains("  Radon unit        Bq/m³\n"));
+    }
+}
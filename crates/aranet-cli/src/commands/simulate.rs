@@ -0,0 +1,302 @@
+//! Simulate command implementation.
+//!
+//! Runs one or more [`MockDevice`]s whose readings follow a slow random walk
+//! around a daily pattern, and feeds them through the same paths as real
+//! devices: watch-style output, the local database and a running
+//! aranet-service's ingest endpoint. This lets integrations be developed on
+//! machines without Bluetooth.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use aranet_core::{MockDevice, MockDeviceBuilder};
+use aranet_service::api::IngestReading;
+use aranet_service::forward::ForwardClient;
+use aranet_store::{Store, StoredReading};
+use aranet_types::{CurrentReading, DeviceType, Status};
+use time::OffsetDateTime;
+
+use crate::cli::{OutputFormat, SimulatedDeviceType};
+use crate::format::{
+    FormatOptions, format_reading_json_with_device, format_watch_csv_header_with_device,
+    format_watch_csv_line_with_device, format_watch_line_with_device,
+};
+use crate::util::append_output;
+
+/// Arguments for the simulate command.
+pub struct SimulateArgs<'a> {
+    pub devices: u8,
+    pub device_type: SimulatedDeviceType,
+    pub interval: u64,
+    pub count: u32,
+    pub store: bool,
+    pub push: Option<ForwardClient>,
+    pub format: OutputFormat,
+    pub output: Option<&'a PathBuf>,
+    pub opts: &'a FormatOptions,
+}
+
+pub async fn cmd_simulate(args: SimulateArgs<'_>) -> Result<()> {
+    let SimulateArgs {
+        devices,
+        device_type,
+        interval,
+        count,
+        store,
+        push,
+        format,
+        output,
+        opts,
+    } = args;
+
+    let mut sensors: Vec<SimulatedSensor> = (1..=devices)
+        .map(|index| SimulatedSensor::new(device_type, index, interval))
+        .collect();
+
+    let store = if store {
+        let store = Store::open_default().context("Failed to open database")?;
+        for sensor in &sensors {
+            store.upsert_device(sensor.device.name(), Some(sensor.device.name()))?;
+        }
+        Some(store)
+    } else {
+        None
+    };
+
+    let names: Vec<&str> = sensors.iter().map(|s| s.device.name()).collect();
+    eprintln!("Simulating: {}", names.join(", "));
+    if count > 0 {
+        eprintln!(
+            "Interval: {}s | Count: {} | Press Ctrl+C to stop",
+            interval, count
+        );
+    } else {
+        eprintln!("Interval: {}s | Press Ctrl+C to stop", interval);
+    }
+    eprintln!("{}", "-".repeat(60));
+
+    let mut header_written = opts.no_header;
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval.max(1)));
+    let mut ticks: u32 = 0;
+
+    loop {
+        ticker.tick().await;
+        let now = OffsetDateTime::now_utc();
+        let mut batch = Vec::with_capacity(sensors.len());
+
+        for sensor in &mut sensors {
+            let reading = sensor.next_reading(now).await?;
+            let name = sensor.device.name();
+
+            let content = match format {
+                OutputFormat::Json => format_reading_json_with_device(&reading, name, opts)?,
+                OutputFormat::Csv => {
+                    let mut out = String::new();
+                    if !header_written {
+                        out.push_str(&format_watch_csv_header_with_device(opts));
+                        header_written = true;
+                    }
+                    out.push_str(&format_watch_csv_line_with_device(&reading, name, opts));
+                    out
+                }
+                OutputFormat::Text => format_watch_line_with_device(&reading, name, opts),
+            };
+            append_output(output, &content)?;
+
+            if let Some(store) = &store
+                && let Err(e) = store.insert_reading(name, &reading)
+            {
+                eprintln!("Failed to store reading for {}: {}", name, e);
+            }
+            batch.push(IngestReading {
+                name: Some(name.to_string()),
+                reading: StoredReading::from_reading(name, &reading),
+            });
+        }
+
+        // The service may not be running yet; keep simulating either way
+        if let Some(client) = &push
+            && let Err(e) = client.send(&batch).await
+        {
+            eprintln!("Failed to send readings to service: {}", e);
+        }
+
+        ticks += 1;
+        if count > 0 && ticks >= count {
+            eprintln!("Completed {} readings.", ticks);
+            return Ok(());
+        }
+    }
+}
+
+/// A mock device plus the state of its simulated environment.
+struct SimulatedSensor {
+    device: MockDevice,
+    kind: SimulatedDeviceType,
+    interval: u64,
+    rng: u64,
+    co2: f32,
+    temperature: f32,
+    humidity: f32,
+    pressure: f32,
+    radon: f32,
+    radiation_total: f64,
+    battery_ticks: u32,
+}
+
+impl SimulatedSensor {
+    /// Readings taken per 1% of battery drained.
+    const TICKS_PER_BATTERY_PERCENT: u32 = 500;
+
+    fn new(kind: SimulatedDeviceType, index: u8, interval: u64) -> Self {
+        let (model, device_type) = match kind {
+            SimulatedDeviceType::Aranet4 => ("Aranet4", DeviceType::Aranet4),
+            SimulatedDeviceType::Aranet2 => ("Aranet2", DeviceType::Aranet2),
+            SimulatedDeviceType::Radon => ("AranetRn+", DeviceType::AranetRadon),
+            SimulatedDeviceType::Radiation => ("Aranet Radiation", DeviceType::AranetRadiation),
+        };
+        let device = MockDeviceBuilder::new()
+            .name(&format!("{} SIM{:02}", model, index))
+            .device_type(device_type)
+            .build();
+
+        // Offset each device so they don't move in lockstep
+        let offset = f32::from(index - 1);
+        Self {
+            device,
+            kind,
+            interval,
+            rng: 0x9E37_79B9_7F4A_7C15 ^ u64::from(index),
+            co2: 550.0 + offset * 60.0,
+            temperature: 21.0 + offset * 0.3,
+            humidity: 45.0 + offset,
+            pressure: 1013.0,
+            radon: 60.0 + offset * 15.0,
+            radiation_total: 0.0,
+            battery_ticks: 0,
+        }
+    }
+
+    /// Advance the simulation and read the new values back from the device.
+    async fn next_reading(&mut self, now: OffsetDateTime) -> aranet_core::Result<CurrentReading> {
+        // Occupied during the day, empty at night
+        let co2_target = if (8..22).contains(&now.hour()) {
+            950.0
+        } else {
+            480.0
+        };
+        self.co2 =
+            (self.co2 + (co2_target - self.co2) * 0.05 + self.noise() * 30.0).clamp(400.0, 3000.0);
+        self.temperature += (21.5 - self.temperature) * 0.05 + self.noise() * 0.1;
+        self.humidity =
+            (self.humidity + (45.0 - self.humidity) * 0.05 + self.noise()).clamp(15.0, 85.0);
+        self.pressure += (1013.0 - self.pressure) * 0.02 + self.noise() * 0.2;
+        self.radon =
+            (self.radon + (80.0 - self.radon) * 0.05 + self.noise() * 8.0).clamp(1.0, 600.0);
+        let radiation_rate = (0.1 + self.noise() * 0.02).max(0.01);
+        // µSv/h over one interval, in mSv
+        self.radiation_total += f64::from(radiation_rate) * self.interval as f64 / 3600.0 / 1000.0;
+        self.battery_ticks += 1;
+        let battery = 95u32
+            .saturating_sub(self.battery_ticks / Self::TICKS_PER_BATTERY_PERCENT)
+            .max(1) as u8;
+
+        let mut reading = CurrentReading {
+            co2: 0,
+            temperature: self.temperature,
+            pressure: self.pressure,
+            humidity: self.humidity.round() as u8,
+            battery,
+            status: Status::Green,
+            interval: self.interval.min(u64::from(u16::MAX)) as u16,
+            age: 0,
+            captured_at: Some(now),
+            radon: None,
+            radiation_rate: None,
+            radiation_total: None,
+            radon_avg_24h: None,
+            radon_avg_7d: None,
+            radon_avg_30d: None,
+        };
+        match self.kind {
+            SimulatedDeviceType::Aranet4 => {
+                reading.co2 = self.co2.round() as u16;
+                reading.status = level_status(reading.co2.into(), 1000, 1400);
+            }
+            SimulatedDeviceType::Aranet2 => reading.pressure = 0.0,
+            SimulatedDeviceType::Radon => {
+                let radon = self.radon.round() as u32;
+                reading.radon = Some(radon);
+                reading.status = level_status(radon, 150, 300);
+            }
+            SimulatedDeviceType::Radiation => {
+                reading.temperature = 0.0;
+                reading.pressure = 0.0;
+                reading.humidity = 0;
+                reading.radiation_rate = Some(radiation_rate);
+                reading.radiation_total = Some(self.radiation_total);
+            }
+        }
+
+        self.device.set_reading(reading).await;
+        self.device.read_current().await
+    }
+
+    /// Uniform noise in [-1, 1] from a xorshift generator.
+    fn noise(&mut self) -> f32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+}
+
+fn level_status(value: u32, yellow: u32, red: u32) -> Status {
+    if value >= red {
+        Status::Red
+    } else if value >= yellow {
+        Status::Yellow
+    } else {
+        Status::Green
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_simulated_readings_stay_in_range() {
+        let mut sensor = SimulatedSensor::new(SimulatedDeviceType::Aranet4, 1, 5);
+        let now = OffsetDateTime::now_utc();
+
+        for _ in 0..1000 {
+            let reading = sensor.next_reading(now).await.unwrap();
+            assert!((400..=3000).contains(&reading.co2));
+            assert!((15..=85).contains(&reading.humidity));
+            assert_eq!(reading.captured_at, Some(now));
+        }
+        assert_eq!(sensor.device.name(), "Aranet4 SIM01");
+    }
+
+    #[tokio::test]
+    async fn test_simulated_device_types_fill_their_fields() {
+        let now = OffsetDateTime::now_utc();
+
+        let mut radon = SimulatedSensor::new(SimulatedDeviceType::Radon, 2, 5);
+        let reading = radon.next_reading(now).await.unwrap();
+        assert_eq!(reading.co2, 0);
+        assert!(reading.radon.is_some());
+        assert_eq!(
+            DeviceType::from_name(radon.device.name()),
+            Some(DeviceType::AranetRadon)
+        );
+
+        let mut radiation = SimulatedSensor::new(SimulatedDeviceType::Radiation, 1, 3600);
+        let first = radiation.next_reading(now).await.unwrap();
+        let second = radiation.next_reading(now).await.unwrap();
+        assert!(first.radiation_rate.is_some());
+        assert!(second.radiation_total > first.radiation_total);
+    }
+}
@@ -4,22 +4,41 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
+use aranet_core::advice::{self, Advice};
+use aranet_core::readings::ExtendedReading;
+use aranet_store::{HistoryQuery, ReadingQuery};
 use owo_colors::OwoColorize;
 use serde::Serialize;
 
+use crate::baseline::{Baseline, Comparison};
 use crate::cli::OutputFormat;
 use crate::format::{FormatOptions, bq_to_pci, csv_escape, format_status};
 use crate::style;
 use crate::util::{connect_device_with_progress, require_device_interactive, write_output};
 
-pub async fn cmd_status(
-    device: Option<String>,
-    timeout: Duration,
-    format: OutputFormat,
-    output: Option<&PathBuf>,
-    opts: &FormatOptions,
-    brief: bool,
-) -> Result<()> {
+/// Arguments for the status command.
+pub struct StatusArgs<'a> {
+    pub device: Option<String>,
+    pub timeout: Duration,
+    pub format: OutputFormat,
+    pub output: Option<&'a PathBuf>,
+    pub opts: &'a FormatOptions,
+    pub brief: bool,
+    pub advice: bool,
+    pub baseline: Option<Baseline>,
+}
+
+pub async fn cmd_status(args: StatusArgs<'_>) -> Result<()> {
+    let StatusArgs {
+        device,
+        timeout,
+        format,
+        output,
+        opts,
+        brief,
+        advice,
+        baseline,
+    } = args;
     let identifier = require_device_interactive(device).await?;
 
     // Use connect_device_with_progress which has its own spinner
@@ -38,15 +57,33 @@ pub async fn cmd_status(
     crate::util::save_reading_to_store(&device_id, &reading);
 
     let device_name = name.clone().unwrap_or_else(|| identifier.clone());
+    let advice = if advice {
+        reading_advice(&device_id, &reading)
+    } else {
+        None
+    };
+    let comparison = baseline.as_ref().and_then(|b| {
+        let at = reading
+            .captured_at
+            .unwrap_or_else(time::OffsetDateTime::now_utc);
+        b.compare(&reading, at, opts)
+    });
 
     let content = match format {
-        OutputFormat::Json => format_status_json(&device_name, &reading, opts)?,
+        OutputFormat::Json => format_status_json(&device_name, &reading, advice, comparison, opts)?,
         OutputFormat::Csv => format_status_csv(&device_name, &reading, opts),
         OutputFormat::Text => {
             if brief {
                 format_status_brief(&reading, opts)
             } else {
-                format_status_text(&device_name, &reading, opts)
+                let mut text = format_status_text(&device_name, &reading, opts);
+                if let Some(advice) = advice {
+                    text.push_str(&format_advice(advice, opts.no_color));
+                }
+                if let Some(comparison) = comparison {
+                    text.push_str(&format!("  {}\n", comparison.summary(opts)));
+                }
+                text
             }
         }
     };
@@ -55,6 +92,45 @@ pub async fn cmd_status(
     Ok(())
 }
 
+/// How far back stored readings and history are used for the CO2 slope.
+const ADVICE_WINDOW: time::Duration = time::Duration::minutes(30);
+
+/// Ventilation advice for `reading`, with the CO2 slope taken from the
+/// readings and history stored for the device over the last half hour.
+fn reading_advice(device_id: &str, reading: &aranet_types::CurrentReading) -> Option<Advice> {
+    let now = time::OffsetDateTime::now_utc();
+    let since = now - ADVICE_WINDOW;
+    let mut samples = vec![(reading.captured_at.unwrap_or(now), reading.co2)];
+    if let Some(store) = crate::util::open_store() {
+        if let Ok(readings) =
+            store.query_readings(&ReadingQuery::new().device(device_id).since(since))
+        {
+            samples.extend(readings.iter().map(|r| (r.captured_at, r.co2)));
+        }
+        if let Ok(history) =
+            store.query_history(&HistoryQuery::new().device(device_id).since(since))
+        {
+            samples.extend(history.iter().map(|r| (r.timestamp, r.co2)));
+        }
+    }
+
+    ExtendedReading {
+        reading: *reading,
+        radiation_duration: None,
+    }
+    .advice(advice::co2_slope(samples))
+}
+
+/// Format an advice line to follow the status line
+fn format_advice(advice: Advice, no_color: bool) -> String {
+    let text = advice.to_string();
+    if no_color || !advice.needs_action() {
+        format!("  Advice: {}\n", text)
+    } else {
+        format!("  Advice: {}\n", text.yellow())
+    }
+}
+
 /// Format status as one-line text output with colored values
 fn format_status_text(
     device_name: &str,
@@ -128,6 +204,8 @@ fn format_status_brief(reading: &aranet_types::CurrentReading, opts: &FormatOpti
 fn format_status_json(
     device_name: &str,
     reading: &aranet_types::CurrentReading,
+    advice: Option<Advice>,
+    baseline: Option<Comparison>,
     opts: &FormatOptions,
 ) -> Result<String> {
     #[derive(Serialize)]
@@ -146,6 +224,10 @@ fn format_status_json(
         radon_pci: Option<f32>,
         #[serde(skip_serializing_if = "Option::is_none")]
         radiation_rate: Option<f32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        advice: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        baseline: Option<Comparison>,
     }
 
     let json = StatusJson {
@@ -160,6 +242,8 @@ fn format_status_json(
         radon_bq: reading.radon,
         radon_pci: reading.radon.map(bq_to_pci),
         radiation_rate: reading.radiation_rate,
+        advice: advice.map(|a| a.to_string()),
+        baseline,
     };
 
     opts.as_json(&json)
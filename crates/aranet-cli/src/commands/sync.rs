@@ -4,9 +4,12 @@ use std::time::Duration;
 
 use anyhow::{Context, Result};
 use aranet_core::HistoryOptions;
-use aranet_store::Store;
+use aranet_service::collector::correct_clock_drift;
+use aranet_store::{HistoryQuery, Store, StoredHistoryRecord};
+use aranet_types::HistoryRecord;
 use indicatif::ProgressBar;
 use serde::Serialize;
+use time::OffsetDateTime;
 use tracing::info;
 
 use crate::cli::{DeviceArgs, OutputFormat};
@@ -20,6 +23,8 @@ pub struct SyncArgs {
     pub format: OutputFormat,
     pub full: bool,
     pub all: bool,
+    /// Spot-check this many cached records against the device instead of syncing.
+    pub verify: Option<u32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -147,6 +152,13 @@ pub async fn cmd_sync(args: SyncArgs, config: &Config) -> Result<()> {
 
     // Connect to device
     let device = crate::util::connect_device_with_progress(&device_address, timeout, true).await?;
+
+    if let Some(samples) = args.verify {
+        let verify_result = verify_device(&store, &device, &device_address, samples).await;
+        crate::util::disconnect_device(&device).await;
+        return print_verify_summary(args.format, &verify_result?);
+    }
+
     let sync_result: Result<SingleDeviceSyncSummary> = async {
         // Get device info for display
         let device_info = device.read_device_info().await?;
@@ -217,7 +229,8 @@ pub async fn cmd_sync(args: SyncArgs, config: &Config) -> Result<()> {
             }
         }
 
-        let history = history_result?;
+        let mut history = history_result?;
+        correct_clock_drift(&store, &device_address, &mut history)?;
 
         // Store history records
         let inserted = store.insert_history(&device_address, &history)?;
@@ -383,10 +396,11 @@ async fn sync_single_device(
 
         // Download history (without progress bar to keep output clean for multiple devices)
         let history_opts = build_history_options(start_index, None);
-        let history = device
+        let mut history = device
             .download_history_with_options(history_opts)
             .await
             .context("Failed to download history")?;
+        correct_clock_drift(store, device_address, &mut history)?;
 
         // Store history records
         let inserted = store.insert_history(device_address, &history)?;
@@ -402,10 +416,285 @@ async fn sync_single_device(
     sync_result
 }
 
+/// Result of checking cached history against the device.
+#[derive(Debug, Serialize)]
+struct VerifySummary {
+    device: String,
+    /// Cached records compared against the device.
+    checked: usize,
+    /// Records whose values all matched.
+    matched: usize,
+    /// Cached records with no device record at the same time.
+    missing_on_device: usize,
+    mismatches: Vec<VerifyMismatch>,
+}
+
+/// A single value that differs between the store and the device.
+#[derive(Debug, Serialize)]
+struct VerifyMismatch {
+    #[serde(with = "time::serde::rfc3339")]
+    timestamp: OffsetDateTime,
+    field: &'static str,
+    stored: String,
+    device: String,
+}
+
+/// Re-download a random sample of cached records and compare their values.
+///
+/// Only records still within the device's memory are sampled. Downloaded
+/// records get the same clock drift correction as a sync, so timestamps line
+/// up with what was stored.
+async fn verify_device(
+    store: &Store,
+    device: &aranet_core::Device,
+    device_address: &str,
+    samples: u32,
+) -> Result<VerifySummary> {
+    let info = device.get_history_info().await?;
+    let mut summary = VerifySummary {
+        device: device_address.to_string(),
+        checked: 0,
+        matched: 0,
+        missing_on_device: 0,
+        mismatches: Vec::new(),
+    };
+    if info.total_readings == 0 || info.interval_seconds == 0 {
+        return Ok(summary);
+    }
+
+    // Time span the device still holds
+    let interval = time::Duration::seconds(i64::from(info.interval_seconds));
+    let newest =
+        OffsetDateTime::now_utc() - time::Duration::seconds(i64::from(info.seconds_since_update));
+    let oldest = newest - interval * (i32::from(info.total_readings) - 1);
+
+    let query = HistoryQuery::new()
+        .device(device_address)
+        .since(oldest - interval / 2)
+        .until(newest + interval / 2);
+    let stored = store.sample_history(&query, samples)?;
+
+    if stored.is_empty() {
+        return Ok(summary);
+    }
+
+    // Download only a few records around each sampled one; the estimated
+    // index can be off by a step or two of clock drift
+    let index_of = |timestamp: OffsetDateTime| {
+        let steps = ((newest - timestamp) / interval).round() as i64;
+        (i64::from(info.total_readings) - steps).clamp(1, i64::from(info.total_readings)) as u16
+    };
+    let windows = sample_windows(
+        stored.iter().map(|r| index_of(r.timestamp)),
+        VERIFY_WINDOW_RADIUS,
+        info.total_readings,
+    );
+    eprintln!(
+        "Verifying {} cached records against {} device records...",
+        stored.len(),
+        windows
+            .iter()
+            .map(|(start, end)| usize::from(end - start) + 1)
+            .sum::<usize>()
+    );
+    let mut downloaded = Vec::new();
+    for (start, end) in windows {
+        let options = HistoryOptions::default().start_index(start).end_index(end);
+        downloaded.extend(
+            device
+                .download_history_with_options(options)
+                .await
+                .context("Failed to download history")?,
+        );
+    }
+    correct_clock_drift(store, device_address, &mut downloaded)?;
+
+    for record in &stored {
+        summary.checked += 1;
+        let Some(device_record) = nearest_record(&downloaded, record.timestamp, interval / 2)
+        else {
+            summary.missing_on_device += 1;
+            continue;
+        };
+        let mismatches = compare_history_record(record, device_record);
+        if mismatches.is_empty() {
+            summary.matched += 1;
+        }
+        summary.mismatches.extend(mismatches);
+    }
+
+    Ok(summary)
+}
+
+/// Records downloaded on either side of each sampled record.
+const VERIFY_WINDOW_RADIUS: u16 = 2;
+
+/// Merge the device indices within `radius` of each of `indices` into
+/// inclusive ranges within `1..=total`, in ascending order.
+fn sample_windows(indices: impl Iterator<Item = u16>, radius: u16, total: u16) -> Vec<(u16, u16)> {
+    let mut indices: Vec<u16> = indices.collect();
+    indices.sort_unstable();
+
+    let mut windows: Vec<(u16, u16)> = Vec::new();
+    for index in indices {
+        let start = index.saturating_sub(radius).max(1);
+        let end = index.saturating_add(radius).min(total);
+        match windows.last_mut() {
+            Some((_, last_end)) if start <= last_end.saturating_add(1) => {
+                *last_end = (*last_end).max(end);
+            }
+            _ => windows.push((start, end)),
+        }
+    }
+    windows
+}
+
+/// The downloaded record closest to `timestamp`, if one is within `tolerance`.
+fn nearest_record(
+    records: &[HistoryRecord],
+    timestamp: OffsetDateTime,
+    tolerance: time::Duration,
+) -> Option<&HistoryRecord> {
+    records
+        .iter()
+        .min_by_key(|r| (r.timestamp - timestamp).abs())
+        .filter(|r| (r.timestamp - timestamp).abs() <= tolerance)
+}
+
+/// Compare a cached record with the device's copy, field by field.
+fn compare_history_record(
+    stored: &StoredHistoryRecord,
+    device: &HistoryRecord,
+) -> Vec<VerifyMismatch> {
+    let mut mismatches = Vec::new();
+    let mut check = |field: &'static str, stored_value: String, device_value: String| {
+        if stored_value != device_value {
+            mismatches.push(VerifyMismatch {
+                timestamp: stored.timestamp,
+                field,
+                stored: stored_value,
+                device: device_value,
+            });
+        }
+    };
+
+    check("co2", stored.co2.to_string(), device.co2.to_string());
+    // Compare at display precision; the store keeps what the parser produced
+    check(
+        "temperature",
+        format!("{:.1}", stored.temperature),
+        format!("{:.1}", device.temperature),
+    );
+    check(
+        "pressure",
+        format!("{:.1}", stored.pressure),
+        format!("{:.1}", device.pressure),
+    );
+    check(
+        "humidity",
+        stored.humidity.to_string(),
+        device.humidity.to_string(),
+    );
+    check(
+        "radon",
+        format!("{:?}", stored.radon),
+        format!("{:?}", device.radon),
+    );
+
+    mismatches
+}
+
+fn print_verify_summary(format: OutputFormat, summary: &VerifySummary) -> Result<()> {
+    if matches!(format, OutputFormat::Json) {
+        println!("{}", serde_json::to_string_pretty(summary)?);
+        return Ok(());
+    }
+
+    if summary.checked == 0 {
+        println!("No cached records within the device's history to verify");
+        return Ok(());
+    }
+
+    println!("Checked: {} records", summary.checked);
+    println!("Matched: {}", summary.matched);
+    println!("Missing on device: {}", summary.missing_on_device);
+    println!("Mismatched values: {}", summary.mismatches.len());
+    let rfc3339 = time::format_description::well_known::Rfc3339;
+    for mismatch in &summary.mismatches {
+        println!(
+            "  {} {}: stored {} / device {}",
+            mismatch.timestamp.format(&rfc3339)?,
+            mismatch.field,
+            mismatch.stored,
+            mismatch.device
+        );
+    }
+    if summary.missing_on_device > 0 {
+        println!(
+            "Records missing on the device may point to clock drift or a device reset since the sync"
+        );
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn device_record(timestamp: OffsetDateTime, co2: u16) -> HistoryRecord {
+        HistoryRecord {
+            timestamp,
+            co2,
+            temperature: 21.5,
+            pressure: 1013.2,
+            humidity: 40,
+            radon: None,
+            radiation_rate: None,
+            radiation_total: None,
+        }
+    }
+
+    #[test]
+    fn test_nearest_record_respects_tolerance() {
+        let t0 = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let records = vec![
+            device_record(t0, 400),
+            device_record(t0 + time::Duration::minutes(5), 410),
+        ];
+        let tolerance = time::Duration::seconds(150);
+
+        let found = nearest_record(&records, t0 + time::Duration::seconds(280), tolerance);
+        assert_eq!(found.map(|r| r.co2), Some(410));
+        assert!(nearest_record(&records, t0 + time::Duration::minutes(10), tolerance).is_none());
+    }
+
+    #[test]
+    fn test_sample_windows_merge_and_clamp() {
+        assert_eq!(
+            sample_windows([500, 3, 10, 12, 1000].into_iter(), 2, 1000),
+            vec![(1, 5), (8, 14), (498, 502), (998, 1000)]
+        );
+        assert!(sample_windows(std::iter::empty(), 2, 1000).is_empty());
+    }
+
+    #[test]
+    fn test_compare_history_record() {
+        let t0 = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let device = device_record(t0, 800);
+        let mut stored = StoredHistoryRecord::from_history("dev", &device);
+
+        assert!(compare_history_record(&stored, &device).is_empty());
+
+        stored.co2 = 850;
+        stored.temperature = 22.0;
+        let mismatches = compare_history_record(&stored, &device);
+        let fields: Vec<_> = mismatches.iter().map(|m| m.field).collect();
+        assert_eq!(fields, ["co2", "temperature"]);
+        assert_eq!(mismatches[0].stored, "850");
+        assert_eq!(mismatches[0].device, "800");
+    }
+
     #[test]
     fn test_build_history_options_sets_start_index_without_progress() {
         let options = build_history_options(42, None);
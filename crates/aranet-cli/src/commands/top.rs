@@ -0,0 +1,377 @@
+//! Top command implementation.
+//!
+//! Shows a `top`-style table of every configured device, refreshed from BLE
+//! advertisements so no connections are held open. Devices that drop out of a
+//! scan keep their last values and simply age until they are seen again.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use aranet_core::advertisement::{AdvertisementData, parse_advertisement_with_name};
+use aranet_core::scan::{DiscoveredDevice, ScanOptions, scan_with_options};
+use clap::ValueEnum;
+use owo_colors::OwoColorize;
+use tabled::{Table, Tabled};
+
+use crate::cli::TopSortColumn;
+use crate::format::{FormatOptions, format_age};
+use crate::style;
+
+/// Arguments for the top command.
+pub struct TopArgs<'a> {
+    /// Devices to show; empty means every Aranet device in range.
+    pub devices: Vec<String>,
+    pub interval: u64,
+    pub count: u32,
+    pub timeout: Duration,
+    pub sort: TopSortColumn,
+    pub reverse: bool,
+    pub opts: &'a FormatOptions,
+}
+
+/// Latest advertisement seen for a device.
+struct TopRow {
+    name: String,
+    rssi: Option<i16>,
+    adv: AdvertisementData,
+    seen: Instant,
+}
+
+impl TopRow {
+    /// Seconds since the sensor took this measurement.
+    fn age_secs(&self) -> u64 {
+        u64::from(self.adv.age) + self.seen.elapsed().as_secs()
+    }
+}
+
+pub async fn cmd_top(args: TopArgs<'_>) -> Result<()> {
+    let TopArgs {
+        devices,
+        interval,
+        count,
+        timeout,
+        sort,
+        reverse,
+        opts,
+    } = args;
+
+    let redraw = std::io::stdout().is_terminal();
+    let mut rows: HashMap<String, TopRow> = HashMap::new();
+    let mut refreshes: u32 = 0;
+
+    loop {
+        let options = ScanOptions::default()
+            .duration(timeout)
+            .filter_aranet_only(true);
+
+        let scan_error = match scan_with_options(options).await {
+            Ok(discovered) => {
+                for device in discovered
+                    .iter()
+                    .filter(|d| devices.is_empty() || matches_target(d, &devices))
+                {
+                    let Some(mfr_data) = &device.manufacturer_data else {
+                        continue;
+                    };
+                    let Ok(adv) = parse_advertisement_with_name(mfr_data, device.name.as_deref())
+                    else {
+                        continue;
+                    };
+                    rows.insert(
+                        device.identifier.clone(),
+                        TopRow {
+                            name: device
+                                .name
+                                .clone()
+                                .unwrap_or_else(|| device.address.clone()),
+                            rssi: device.rssi,
+                            adv,
+                            seen: Instant::now(),
+                        },
+                    );
+                }
+                None
+            }
+            Err(e) => Some(e),
+        };
+
+        let mut sorted: Vec<&TopRow> = rows.values().collect();
+        sort_rows(&mut sorted, sort, reverse);
+
+        let mut screen = String::new();
+        if redraw {
+            // Clear the screen and move the cursor home before each frame
+            screen.push_str("\x1b[2J\x1b[H");
+        }
+        screen.push_str(&format_top_header(
+            sorted.len(),
+            sort,
+            interval,
+            opts.no_color,
+        ));
+        screen.push_str(&format_top_table(&sorted, opts));
+        if let Some(err) = &scan_error {
+            screen.push_str(&format!("Scan failed: {}. Retrying...\n", err));
+        }
+        print!("{}", screen);
+
+        refreshes += 1;
+        if count > 0 && refreshes >= count {
+            return Ok(());
+        }
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                eprintln!("\nShutting down...");
+                return Ok(());
+            }
+            _ = tokio::time::sleep(Duration::from_secs(interval)) => {}
+        }
+    }
+}
+
+/// Check whether a discovered device matches any of the requested identifiers.
+fn matches_target(device: &DiscoveredDevice, targets: &[String]) -> bool {
+    targets.iter().any(|t| {
+        device
+            .name
+            .as_deref()
+            .is_some_and(|n| n.eq_ignore_ascii_case(t))
+            || device.address.eq_ignore_ascii_case(t)
+            || device.identifier.eq_ignore_ascii_case(t)
+    })
+}
+
+/// Sort rows by the chosen column.
+///
+/// Numeric columns put the most interesting value first (highest CO2, lowest
+/// battery, stalest reading); rows without a value always sink to the bottom.
+/// Ties fall back to the device name so the table does not jitter.
+fn sort_rows(rows: &mut [&TopRow], sort: TopSortColumn, reverse: bool) {
+    rows.sort_by(|a, b| {
+        let primary = match sort {
+            TopSortColumn::Device => Some(a.name.to_lowercase().cmp(&b.name.to_lowercase())),
+            TopSortColumn::Co2 => cmp_desc(non_zero(a.adv.co2), non_zero(b.adv.co2)),
+            TopSortColumn::Temp => cmp_desc(a.adv.temperature, b.adv.temperature),
+            TopSortColumn::Humidity => cmp_desc(a.adv.humidity, b.adv.humidity),
+            TopSortColumn::Radon => cmp_desc(a.adv.radon, b.adv.radon),
+            TopSortColumn::Battery => Some(a.adv.battery.cmp(&b.adv.battery)),
+            TopSortColumn::Age => Some(b.age_secs().cmp(&a.age_secs())),
+            TopSortColumn::Rssi => cmp_desc(a.rssi, b.rssi),
+        };
+        let ordering = match primary {
+            Some(ordering) if reverse => ordering.reverse(),
+            Some(ordering) => ordering,
+            // Missing values stay at the bottom regardless of direction
+            None => missing_last(a, b, sort),
+        };
+        ordering.then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+/// Compare two optional values in descending order.
+///
+/// Returns `None` when either side is missing so the caller can keep those
+/// rows at the bottom regardless of direction.
+fn cmp_desc<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<Ordering> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(b.partial_cmp(&a).unwrap_or(Ordering::Equal)),
+        _ => None,
+    }
+}
+
+/// Order rows where at least one side lacks a value for the sort column.
+fn missing_last(a: &TopRow, b: &TopRow, sort: TopSortColumn) -> Ordering {
+    let has_value = |row: &TopRow| match sort {
+        TopSortColumn::Co2 => non_zero(row.adv.co2).is_some(),
+        TopSortColumn::Temp => row.adv.temperature.is_some(),
+        TopSortColumn::Humidity => row.adv.humidity.is_some(),
+        TopSortColumn::Radon => row.adv.radon.is_some(),
+        TopSortColumn::Rssi => row.rssi.is_some(),
+        TopSortColumn::Device | TopSortColumn::Battery | TopSortColumn::Age => true,
+    };
+    has_value(b).cmp(&has_value(a))
+}
+
+/// Aranet advertisements report 0 ppm for devices without a CO2 sensor.
+fn non_zero(co2: Option<u16>) -> Option<u16> {
+    co2.filter(|&v| v > 0)
+}
+
+fn format_top_header(devices: usize, sort: TopSortColumn, interval: u64, no_color: bool) -> String {
+    let time = aranet_cli::local_now_fmt("[hour]:[minute]:[second]");
+    let title = if no_color {
+        "aranet top".to_string()
+    } else {
+        format!("{}", "aranet top".bold())
+    };
+    let sort = sort
+        .to_possible_value()
+        .map(|v| v.get_name().to_string())
+        .unwrap_or_default();
+    format!(
+        "{} - {} | {} device(s) | sort: {} | refresh: {}s | Ctrl+C to quit\n\n",
+        title, time, devices, sort, interval
+    )
+}
+
+fn format_top_table(rows: &[&TopRow], opts: &FormatOptions) -> String {
+    #[derive(Tabled)]
+    struct Row {
+        #[tabled(rename = "Device")]
+        device: String,
+        #[tabled(rename = "CO2")]
+        co2: String,
+        #[tabled(rename = "Temp")]
+        temp: String,
+        #[tabled(rename = "RH")]
+        humidity: String,
+        #[tabled(rename = "Radon")]
+        radon: String,
+        #[tabled(rename = "Battery")]
+        battery: String,
+        #[tabled(rename = "Age")]
+        age: String,
+        #[tabled(rename = "RSSI")]
+        rssi: String,
+    }
+
+    if rows.is_empty() {
+        return "Waiting for advertisements (Smart Home integration must be enabled)...\n"
+            .to_string();
+    }
+
+    let dash = || "-".to_string();
+    let table_rows: Vec<Row> = rows
+        .iter()
+        .map(|r| Row {
+            device: if opts.no_color {
+                r.name.clone()
+            } else {
+                format!("{}", r.name.cyan())
+            },
+            co2: non_zero(r.adv.co2)
+                .map(|v| format!("{} ppm", style::format_co2_colored(v, opts.no_color)))
+                .unwrap_or_else(dash),
+            temp: r
+                .adv
+                .temperature
+                .map(|t| opts.format_temp(t))
+                .unwrap_or_else(dash),
+            humidity: r
+                .adv
+                .humidity
+                .map(|h| style::format_humidity_colored(h, opts.no_color))
+                .unwrap_or_else(dash),
+            radon: r
+                .adv
+                .radon
+                .map(|v| opts.format_radon(v))
+                .unwrap_or_else(dash),
+            battery: style::format_battery_colored(r.adv.battery, opts.no_color),
+            age: format_age(r.age_secs().min(u64::from(u16::MAX)) as u16),
+            rssi: if opts.is_plain() {
+                r.rssi.map(|v| v.to_string()).unwrap_or_else(dash)
+            } else {
+                style::format_signal_bar(r.rssi, opts.no_color)
+            },
+        })
+        .collect();
+
+    let mut table = Table::new(table_rows);
+    style::apply_table_style(&mut table, opts.style);
+    format!("{}\n", table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aranet_types::{DeviceType, Status};
+
+    fn row(name: &str, co2: u16, battery: u8, rssi: Option<i16>) -> TopRow {
+        TopRow {
+            name: name.to_string(),
+            rssi,
+            adv: AdvertisementData {
+                device_type: DeviceType::Aranet4,
+                co2: Some(co2),
+                temperature: Some(21.0),
+                pressure: Some(1013.0),
+                humidity: Some(40),
+                battery,
+                status: Status::Green,
+                interval: 60,
+                age: 0,
+                radon: None,
+                radiation_dose_rate: None,
+                counter: None,
+                flags: 0,
+            },
+            seen: Instant::now(),
+        }
+    }
+
+    fn names(rows: &[&TopRow]) -> Vec<String> {
+        rows.iter().map(|r| r.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_sort_by_device_name() {
+        let (a, b, c) = (
+            row("office", 500, 80, None),
+            row("Bedroom", 900, 50, None),
+            row("kitchen", 700, 20, None),
+        );
+        let mut rows = vec![&a, &b, &c];
+        sort_rows(&mut rows, TopSortColumn::Device, false);
+        assert_eq!(names(&rows), ["Bedroom", "kitchen", "office"]);
+    }
+
+    #[test]
+    fn test_sort_by_co2_highest_first_and_reverse() {
+        let (a, b, c) = (
+            row("a", 500, 80, None),
+            row("b", 900, 50, None),
+            row("c", 700, 20, None),
+        );
+        let mut rows = vec![&a, &b, &c];
+        sort_rows(&mut rows, TopSortColumn::Co2, false);
+        assert_eq!(names(&rows), ["b", "c", "a"]);
+
+        sort_rows(&mut rows, TopSortColumn::Co2, true);
+        assert_eq!(names(&rows), ["a", "c", "b"]);
+    }
+
+    #[test]
+    fn test_sort_by_battery_lowest_first() {
+        let (a, b, c) = (
+            row("a", 500, 80, None),
+            row("b", 900, 50, None),
+            row("c", 700, 20, None),
+        );
+        let mut rows = vec![&a, &b, &c];
+        sort_rows(&mut rows, TopSortColumn::Battery, false);
+        assert_eq!(names(&rows), ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn test_sort_missing_values_last_in_both_directions() {
+        let (a, b, c) = (
+            row("a", 0, 80, Some(-80)),
+            row("b", 900, 50, None),
+            row("c", 700, 20, Some(-40)),
+        );
+        let mut rows = vec![&a, &b, &c];
+        sort_rows(&mut rows, TopSortColumn::Co2, false);
+        assert_eq!(names(&rows), ["b", "c", "a"]);
+
+        sort_rows(&mut rows, TopSortColumn::Rssi, false);
+        assert_eq!(names(&rows), ["c", "a", "b"]);
+
+        sort_rows(&mut rows, TopSortColumn::Rssi, true);
+        assert_eq!(names(&rows), ["a", "c", "b"]);
+    }
+}
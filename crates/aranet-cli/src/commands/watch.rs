@@ -3,16 +3,20 @@
 //! Uses a persistent BLE connection to reduce overhead. The connection is only
 //! re-established when a read fails, indicating the device has disconnected.
 //! Implements exponential backoff for reconnection attempts to reduce resource usage.
+//! Readings can additionally be appended to a rotating log file (see [`RollingLog`]),
+//! and compared with a recorded [`Baseline`].
 
 use std::path::PathBuf;
 use std::time::Duration;
 
+use crate::baseline::{Baseline, Comparison};
 use crate::cli::OutputFormat;
 use crate::format::{
-    FormatOptions, bq_to_pci, format_reading_json, format_reading_json_with_device,
+    FormatOptions, bq_to_pci, format_reading_json_with_baseline, format_reading_json_with_device,
     format_watch_csv_header, format_watch_csv_header_with_device, format_watch_csv_line,
     format_watch_csv_line_with_device, format_watch_line_with_device,
 };
+use crate::log_file::RollingLog;
 use crate::style;
 use crate::util::{append_output, require_device_interactive};
 use anyhow::Result;
@@ -36,6 +40,8 @@ pub struct WatchArgs<'a> {
     pub format: OutputFormat,
     pub output: Option<&'a PathBuf>,
     pub passive: bool,
+    pub log: Option<RollingLog>,
+    pub baseline: Option<Baseline>,
     pub opts: &'a FormatOptions,
 }
 
@@ -48,11 +54,23 @@ pub async fn cmd_watch(args: WatchArgs<'_>) -> Result<()> {
         format,
         output,
         passive,
+        mut log,
+        baseline,
         opts,
     } = args;
 
     if passive {
-        return cmd_watch_passive(device, interval, count, timeout, format, output, opts).await;
+        return cmd_watch_passive(
+            device,
+            interval,
+            count,
+            timeout,
+            format,
+            output,
+            log.as_mut(),
+            opts,
+        )
+        .await;
     }
 
     let identifier = require_device_interactive(device).await?;
@@ -150,8 +168,16 @@ pub async fn cmd_watch(args: WatchArgs<'_>) -> Result<()> {
                 readings_taken += 1;
                 // Save reading to store (unified data architecture)
                 crate::util::save_reading_to_store(&device_id, &reading);
+                let comparison = baseline.as_ref().and_then(|b| {
+                    let at = reading
+                        .captured_at
+                        .unwrap_or_else(time::OffsetDateTime::now_utc);
+                    b.compare(&reading, at, opts)
+                });
                 let content = match format {
-                    OutputFormat::Json => format_reading_json(&reading, opts)?,
+                    OutputFormat::Json => {
+                        format_reading_json_with_baseline(&reading, comparison, opts)?
+                    }
                     OutputFormat::Csv => {
                         let mut out = String::new();
                         if !header_written {
@@ -161,11 +187,18 @@ pub async fn cmd_watch(args: WatchArgs<'_>) -> Result<()> {
                         out.push_str(&format_watch_csv_line(&reading, opts));
                         out
                     }
-                    OutputFormat::Text => {
-                        format_watch_line_with_trend(&reading, previous_reading.as_ref(), opts)
-                    }
+                    OutputFormat::Text => format_watch_line_with_trend(
+                        &reading,
+                        previous_reading.as_ref(),
+                        comparison.as_ref(),
+                        opts,
+                    ),
                 };
                 append_output(output, &content)?;
+                if let Some(log) = log.as_mut() {
+                    let name = device.name().unwrap_or(&device_id);
+                    log.write(name, &reading, local_now())?;
+                }
                 previous_reading = Some(reading);
             }
             Err(e) => {
@@ -198,6 +231,7 @@ pub async fn cmd_watch(args: WatchArgs<'_>) -> Result<()> {
 }
 
 /// Watch sensor data from BLE advertisements without connecting.
+#[allow(clippy::too_many_arguments)]
 async fn cmd_watch_passive(
     device: Option<String>,
     interval: u64,
@@ -205,6 +239,7 @@ async fn cmd_watch_passive(
     timeout: Duration,
     format: OutputFormat,
     output: Option<&PathBuf>,
+    mut log: Option<&mut RollingLog>,
     opts: &FormatOptions,
 ) -> Result<()> {
     let target = device.as_deref();
@@ -271,25 +306,7 @@ async fn cmd_watch_passive(
                             let device_name = discovered.name.as_deref();
                             match parse_advertisement_with_name(mfr_data, device_name) {
                                 Ok(adv) => {
-                                    // Convert to CurrentReading
-                                    let mut builder = CurrentReading::builder()
-                                        .co2(adv.co2.unwrap_or(0))
-                                        .temperature(adv.temperature.unwrap_or(0.0))
-                                        .pressure(adv.pressure.unwrap_or(0.0))
-                                        .humidity(adv.humidity.unwrap_or(0))
-                                        .battery(adv.battery)
-                                        .status(adv.status)
-                                        .interval(adv.interval)
-                                        .age(adv.age);
-
-                                    if let Some(radon) = adv.radon {
-                                        builder = builder.radon(radon);
-                                    }
-                                    if let Some(rate) = adv.radiation_dose_rate {
-                                        builder = builder.radiation_rate(rate);
-                                    }
-
-                                    let reading = builder.build();
+                                    let reading = adv.to_reading();
                                     readings_taken += 1;
 
                                     // Get a short device name for display
@@ -323,6 +340,9 @@ async fn cmd_watch_passive(
                                         ),
                                     };
                                     append_output(output, &content)?;
+                                    if let Some(log) = log.as_mut() {
+                                        log.write(display_name, &reading, local_now())?;
+                                    }
                                 }
                                 Err(e) => {
                                     eprintln!(
@@ -357,8 +377,36 @@ async fn cmd_watch_passive(
     }
 }
 
-/// Format a watch line with trend indicators comparing to previous reading.
+/// Current local time (UTC if the offset is unknown), used to pick the log file.
+fn local_now() -> time::OffsetDateTime {
+    time::OffsetDateTime::now_local().unwrap_or_else(|_| time::OffsetDateTime::now_utc())
+}
+
+/// Format a watch line with trend indicators comparing to previous reading,
+/// followed by the baseline comparison if there is one.
 fn format_watch_line_with_trend(
+    reading: &CurrentReading,
+    previous: Option<&CurrentReading>,
+    comparison: Option<&Comparison>,
+    opts: &FormatOptions,
+) -> String {
+    let line = format_watch_values(reading, previous, opts);
+    match comparison {
+        Some(comparison) => {
+            let summary = comparison.summary(opts);
+            let summary = if opts.no_color {
+                summary
+            } else {
+                summary.dimmed().to_string()
+            };
+            format!("{} | {}\n", line.trim_end(), summary)
+        }
+        None => line,
+    }
+}
+
+/// Format the values of a watch line with trend indicators.
+fn format_watch_values(
     reading: &CurrentReading,
     previous: Option<&CurrentReading>,
     opts: &FormatOptions,
@@ -1,10 +1,12 @@
 //! Configuration file management.
 
-use std::collections::HashMap;
-use std::fs;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::Result;
+use aranet_core::RadiationThresholds;
+use aranet_core::config::{ConfigLoader, EnvOverride};
+use aranet_core::settings::DeviceSettings;
 use serde::{Deserialize, Serialize};
 
 /// Configuration file structure
@@ -42,6 +44,11 @@ pub struct Config {
     #[serde(default)]
     pub aliases: HashMap<String, String>,
 
+    /// Device groups for the TUI sidebar (group name -> device IDs, names or
+    /// aliases)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub groups: BTreeMap<String, Vec<String>>,
+
     /// Last successfully connected device (auto-updated)
     #[serde(default)]
     pub last_device: Option<String>,
@@ -50,6 +57,15 @@ pub struct Config {
     #[serde(default)]
     pub last_device_name: Option<String>,
 
+    /// TUI and GUI language code (e.g. "en", "de"); unset or "auto" follows
+    /// the system locale
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// TUI key bindings
+    #[serde(default, skip_serializing_if = "KeymapConfig::is_empty")]
+    pub keymap: KeymapConfig,
+
     /// Behavior settings for unified data architecture
     #[serde(default)]
     pub behavior: BehaviorConfig,
@@ -68,6 +84,14 @@ pub struct GuiConfig {
     #[serde(default = "default_theme")]
     pub theme: String,
 
+    /// Accent color as "#RRGGBB", replacing the theme's default blue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent_color: Option<String>,
+
+    /// Chart line colors, overriding the theme's defaults.
+    #[serde(default, skip_serializing_if = "ChartPalette::is_default")]
+    pub chart_palette: ChartPalette,
+
     /// Show colored tray icon for elevated CO2 levels.
     /// When false, always uses native template icon (auto dark/light).
     /// When true, shows colored icons (yellow/orange/red) for elevated CO2.
@@ -100,6 +124,16 @@ pub struct GuiConfig {
     #[serde(default = "default_hpa")]
     pub pressure_unit: String,
 
+    /// Radon unit preference: "bq" or "pci".
+    /// Used when device settings are not available.
+    #[serde(default = "default_bq")]
+    pub radon_unit: String,
+
+    /// Display every device in the units above instead of the units set on
+    /// each device. Only affects display; device settings are not changed.
+    #[serde(default)]
+    pub override_device_units: bool,
+
     /// Whether the sidebar is collapsed.
     #[serde(default)]
     pub sidebar_collapsed: bool,
@@ -140,6 +174,14 @@ pub struct GuiConfig {
     #[serde(default = "default_radon_danger")]
     pub radon_danger_threshold: u32,
 
+    /// Radiation dose rate warning threshold in µSv/h (Aranet Radiation).
+    #[serde(default = "default_radiation_warning")]
+    pub radiation_warning_threshold: f32,
+
+    /// Radiation dose rate alarm threshold in µSv/h (Aranet Radiation).
+    #[serde(default = "default_radiation_alarm")]
+    pub radiation_alarm_threshold: f32,
+
     /// Default export format: "csv" or "json".
     #[serde(default = "default_export_format")]
     pub default_export_format: String,
@@ -176,6 +218,52 @@ pub struct GuiConfig {
     /// Do Not Disturb mode - suppress all notifications.
     #[serde(default)]
     pub do_not_disturb: bool,
+    /// Per-device auto-refresh overrides, keyed by device ID.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub device_refresh: HashMap<String, DeviceRefreshConfig>,
+}
+
+/// Chart line colors for the GUI, each as "#RRGGBB".
+///
+/// Unset colors use the theme's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChartPalette {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub co2: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub humidity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<String>,
+}
+
+impl ChartPalette {
+    /// Whether no color is overridden.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Parse a "#RRGGBB" (or "RRGGBB") color into its RGB components.
+pub fn parse_hex_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Per-device auto-refresh settings for the GUI.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceRefreshConfig {
+    /// Refresh interval in seconds, overriding the device's measurement interval.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u64>,
+    /// Skip this device during auto-refresh.
+    #[serde(default)]
+    pub paused: bool,
 }
 
 fn default_service_url() -> String {
@@ -194,6 +282,10 @@ fn default_hpa() -> String {
     "hpa".to_string()
 }
 
+fn default_bq() -> String {
+    "bq".to_string()
+}
+
 fn default_co2_warning() -> u16 {
     1000
 }
@@ -210,6 +302,14 @@ fn default_radon_danger() -> u32 {
     150
 }
 
+fn default_radiation_warning() -> f32 {
+    RadiationThresholds::default().warning_usv
+}
+
+fn default_radiation_alarm() -> f32 {
+    RadiationThresholds::default().alarm_usv
+}
+
 fn default_export_format() -> String {
     "csv".to_string()
 }
@@ -218,6 +318,8 @@ impl Default for GuiConfig {
     fn default() -> Self {
         Self {
             theme: default_theme(),
+            accent_color: None,
+            chart_palette: ChartPalette::default(),
             colored_tray_icon: true,
             notifications_enabled: true,
             notification_sound: true,
@@ -225,6 +327,8 @@ impl Default for GuiConfig {
             close_to_tray: true,
             temperature_unit: default_celsius(),
             pressure_unit: default_hpa(),
+            radon_unit: default_bq(),
+            override_device_units: false,
             sidebar_collapsed: false,
             compact_mode: false,
             window_width: None,
@@ -235,6 +339,8 @@ impl Default for GuiConfig {
             co2_danger_threshold: default_co2_danger(),
             radon_warning_threshold: default_radon_warning(),
             radon_danger_threshold: default_radon_danger(),
+            radiation_warning_threshold: default_radiation_warning(),
+            radiation_alarm_threshold: default_radiation_alarm(),
             default_export_format: default_export_format(),
             export_directory: String::new(),
             service_url: default_service_url(),
@@ -244,10 +350,72 @@ impl Default for GuiConfig {
             show_humidity: true,
             show_pressure: true,
             do_not_disturb: false,
+            device_refresh: HashMap::new(),
         }
     }
 }
 
+impl GuiConfig {
+    /// Auto-refresh settings for a device (defaults if none are saved).
+    pub fn device_refresh(&self, device_id: &str) -> DeviceRefreshConfig {
+        self.device_refresh
+            .get(device_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Save auto-refresh settings for a device, dropping the entry when it
+    /// matches the defaults.
+    pub fn set_device_refresh(&mut self, device_id: &str, refresh: DeviceRefreshConfig) {
+        if refresh == DeviceRefreshConfig::default() {
+            self.device_refresh.remove(device_id);
+        } else {
+            self.device_refresh.insert(device_id.to_string(), refresh);
+        }
+    }
+
+    /// The device settings that decide how a device's readings are
+    /// displayed: the device's own, or `None` when
+    /// [`override_device_units`](Self::override_device_units) is set so the
+    /// app's unit preferences apply.
+    pub fn unit_settings<'a>(
+        &self,
+        device: Option<&'a DeviceSettings>,
+    ) -> Option<&'a DeviceSettings> {
+        device.filter(|_| !self.override_device_units)
+    }
+
+    /// Radiation dose rate thresholds used for badges and alerts.
+    pub fn radiation_thresholds(&self) -> RadiationThresholds {
+        RadiationThresholds::new(
+            self.radiation_warning_threshold,
+            self.radiation_alarm_threshold,
+        )
+    }
+}
+
+/// TUI key binding configuration.
+///
+/// Starts from a preset and replaces the keys of individual actions, e.g.
+/// `bindings = { sync_history = ["S"], toggle_theme = [] }`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeymapConfig {
+    /// Base bindings: "default", "vim" or "emacs"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+
+    /// Keys per action name; an empty list unbinds the action
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub bindings: BTreeMap<String, Vec<String>>,
+}
+
+impl KeymapConfig {
+    /// Whether neither a preset nor any bindings are configured.
+    pub fn is_empty(&self) -> bool {
+        self.preset.is_none() && self.bindings.is_empty()
+    }
+}
+
 /// Behavior configuration for unified data architecture.
 ///
 /// Controls automatic connection, sync, and device memory across all tools.
@@ -299,33 +467,50 @@ impl Config {
 
     /// Load config from the default path.
     ///
-    /// Returns an error if the file exists but cannot be read or parsed.
+    /// Reads the file only; use [`Config::load_effective`] for the settings a
+    /// command should run with. Returns an error if the file exists but
+    /// cannot be read or parsed.
     pub fn load() -> Result<Self> {
         Self::load_from_path(&Self::path())
     }
 
     /// Load config from the default path, returning defaults when the file is absent.
     ///
-    /// Returns an error if the file exists but cannot be read or parsed.
+    /// Reads the file only, so it is safe to modify and [`save`](Self::save)
+    /// the result without persisting environment overrides. Returns an error
+    /// if the file exists but cannot be read or parsed.
     pub fn load_or_default() -> Result<Self> {
         Self::load_from_path_or_default(&Self::path())
     }
 
+    /// Load the effective config: defaults, then the config file, then
+    /// `ARANET_*` environment variables.
+    ///
+    /// Command-line flags are applied on top by the caller. Variables use
+    /// `__` between table levels, e.g. `ARANET_TIMEOUT=30` or
+    /// `ARANET_GUI__SERVICE_URL=http://nas:8080`.
+    pub fn load_effective() -> Result<Self> {
+        Ok(Self::env_loader().load_or_default(Self::path())?)
+    }
+
+    /// The `ARANET_*` variables that override config keys.
+    pub fn env_overrides() -> Vec<EnvOverride> {
+        Self::env_loader().overrides().to_vec()
+    }
+
+    fn env_loader() -> ConfigLoader {
+        // `--style` reads ARANET_STYLE itself; it is not a config key
+        ConfigLoader::from_env().ignore("ARANET_STYLE")
+    }
+
     /// Load config from an explicit path.
     pub fn load_from_path(path: &Path) -> Result<Self> {
-        let content = fs::read_to_string(path)
-            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
-        toml::from_str(&content)
-            .with_context(|| format!("Failed to parse config file: {}", path.display()))
+        Ok(ConfigLoader::file_only().load(path)?)
     }
 
     /// Load config from an explicit path, returning defaults when the file is absent.
     pub fn load_from_path_or_default(path: &Path) -> Result<Self> {
-        if !path.exists() {
-            return Ok(Self::default());
-        }
-
-        Self::load_from_path(path)
+        Ok(ConfigLoader::file_only().load_or_default(path)?)
     }
 
     /// Load config from the default path, logging a warning and falling back to defaults on error.
@@ -343,17 +528,10 @@ impl Config {
     }
 
     /// Save config to file
+    ///
+    /// The file is made owner-readable only since it may hold a service API key.
     pub fn save(&self) -> Result<()> {
-        let path = Self::path();
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).with_context(|| {
-                format!("Failed to create config directory: {}", parent.display())
-            })?;
-        }
-        let content = toml::to_string_pretty(self).context("Failed to serialize config")?;
-        fs::write(&path, content)
-            .with_context(|| format!("Failed to write config: {}", path.display()))?;
-        Ok(())
+        Ok(aranet_core::config::save(self, Self::path())?)
     }
 }
 
@@ -462,6 +640,7 @@ pub fn resolve_timeout(cmd_timeout: Option<u64>, config: &Config, default: u64)
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
     use tempfile::tempdir;
 
     #[test]
@@ -554,6 +733,36 @@ office = "Aranet4 12345"
         );
     }
 
+    #[test]
+    fn test_env_overrides_file_values() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "timeout = 10\nfahrenheit = true\n").unwrap();
+
+        let loader = ConfigLoader::with_vars([
+            ("ARANET_TIMEOUT", "45"),
+            ("ARANET_DEVICE", "Aranet4 12345"),
+            ("ARANET_GUI__SERVICE_URL", "http://nas:8080"),
+        ]);
+        let config: Config = loader.load_or_default(&path).unwrap();
+        assert_eq!(config.timeout, Some(45));
+        assert!(config.fahrenheit);
+        assert_eq!(config.device.as_deref(), Some("Aranet4 12345"));
+        assert_eq!(config.gui.service_url, "http://nas:8080");
+    }
+
+    #[test]
+    fn test_env_override_error_names_key() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("missing.toml");
+
+        let loader = ConfigLoader::with_vars([("ARANET_GUI__CO2_WARNING_THRESHOLD", "high")]);
+        let err = loader.load_or_default::<Config>(&path).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("`gui.co2_warning_threshold`"));
+        assert!(message.contains("ARANET_GUI__CO2_WARNING_THRESHOLD"));
+    }
+
     #[test]
     fn test_behavior_config_serialization() {
         let behavior = BehaviorConfig {
@@ -574,6 +783,49 @@ office = "Aranet4 12345"
         assert!(parsed.load_cache);
     }
 
+    #[test]
+    fn test_device_refresh_overrides_round_trip() {
+        let mut gui = GuiConfig::default();
+        let bedroom = DeviceRefreshConfig {
+            interval_secs: Some(1800),
+            paused: false,
+        };
+        gui.set_device_refresh("AA:BB:CC:DD:EE:FF", bedroom.clone());
+        gui.set_device_refresh("11:22:33:44:55:66", DeviceRefreshConfig::default());
+        assert_eq!(gui.device_refresh.len(), 1);
+
+        let toml_str = toml::to_string(&gui).unwrap();
+        let parsed: GuiConfig = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed.device_refresh("AA:BB:CC:DD:EE:FF"), bedroom);
+        assert_eq!(
+            parsed.device_refresh("11:22:33:44:55:66"),
+            DeviceRefreshConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_custom_colors_round_trip() {
+        let mut gui = GuiConfig::default();
+        assert!(!toml::to_string(&gui).unwrap().contains("chart_palette"));
+
+        gui.accent_color = Some("#E11D48".to_string());
+        gui.chart_palette.co2 = Some("#10b981".to_string());
+        let toml_str = toml::to_string(&gui).unwrap();
+        let parsed: GuiConfig = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed.accent_color.as_deref(), Some("#E11D48"));
+        assert_eq!(parsed.chart_palette.co2.as_deref(), Some("#10b981"));
+        assert_eq!(parsed.chart_palette.temperature, None);
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#E11D48"), Some([0xE1, 0x1D, 0x48]));
+        assert_eq!(parse_hex_color("10b981"), Some([0x10, 0xB9, 0x81]));
+        assert_eq!(parse_hex_color("#fff"), None);
+        assert_eq!(parse_hex_color("#GG0000"), None);
+        assert_eq!(parse_hex_color("#ééé"), None);
+    }
+
     // ========================================================================
     // resolve_alias tests
     // ========================================================================
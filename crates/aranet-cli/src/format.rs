@@ -6,6 +6,7 @@ use aranet_types::{CurrentReading, DeviceInfo, HistoryRecord, Status};
 use owo_colors::OwoColorize;
 use serde::Serialize;
 
+use crate::baseline::Comparison;
 use crate::cli::StyleMode;
 use crate::style;
 
@@ -797,6 +798,15 @@ impl ReadingJsonCore {
 
 /// Format reading as JSON with temperature and pressure unit conversion applied.
 pub fn format_reading_json(reading: &CurrentReading, opts: &FormatOptions) -> Result<String> {
+    format_reading_json_with_baseline(reading, None, opts)
+}
+
+/// Format a reading as JSON with an optional `baseline` comparison.
+pub fn format_reading_json_with_baseline(
+    reading: &CurrentReading,
+    baseline: Option<Comparison>,
+    opts: &FormatOptions,
+) -> Result<String> {
     #[derive(Serialize)]
     struct ReadingJson {
         #[serde(flatten)]
@@ -809,6 +819,8 @@ pub fn format_reading_json(reading: &CurrentReading, opts: &FormatOptions) -> Re
         radon_avg_7d_bq: Option<u32>,
         #[serde(skip_serializing_if = "Option::is_none")]
         radon_avg_30d_bq: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        baseline: Option<Comparison>,
     }
 
     let json = ReadingJson {
@@ -818,6 +830,50 @@ pub fn format_reading_json(reading: &CurrentReading, opts: &FormatOptions) -> Re
         radon_avg_24h_bq: reading.radon_avg_24h,
         radon_avg_7d_bq: reading.radon_avg_7d,
         radon_avg_30d_bq: reading.radon_avg_30d,
+        baseline,
+    };
+
+    opts.as_json(&json)
+}
+
+/// Format a cached reading as JSON with where it came from, when it was
+/// captured and whether it is stale.
+pub fn format_cached_reading_json(
+    reading: &CurrentReading,
+    source: &str,
+    age_seconds: i64,
+    stale: bool,
+    opts: &FormatOptions,
+) -> Result<String> {
+    #[derive(Serialize)]
+    struct CachedReadingJson<'a> {
+        #[serde(flatten)]
+        core: ReadingJsonCore,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        radon_avg_24h_bq: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        radon_avg_7d_bq: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        radon_avg_30d_bq: Option<u32>,
+        source: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        captured_at: Option<String>,
+        age_seconds: i64,
+        stale: bool,
+    }
+
+    let json = CachedReadingJson {
+        core: ReadingJsonCore::from_reading(reading, opts),
+        radon_avg_24h_bq: reading.radon_avg_24h,
+        radon_avg_7d_bq: reading.radon_avg_7d,
+        radon_avg_30d_bq: reading.radon_avg_30d,
+        source,
+        captured_at: reading.captured_at.and_then(|t| {
+            t.format(&time::format_description::well_known::Rfc3339)
+                .ok()
+        }),
+        age_seconds,
+        stale,
     };
 
     opts.as_json(&json)
@@ -889,6 +945,103 @@ pub fn format_multi_reading_json(
     opts.as_json(&json)
 }
 
+/// Format multiple device readings as a single table, one row per device.
+#[must_use]
+pub fn format_multi_reading_table(readings: &[DeviceReading], opts: &FormatOptions) -> String {
+    use tabled::{Table, Tabled};
+
+    #[derive(Tabled)]
+    struct Row {
+        #[tabled(rename = "Device")]
+        device: String,
+        #[tabled(rename = "CO2")]
+        co2: String,
+        #[tabled(rename = "Temp")]
+        temp: String,
+        #[tabled(rename = "RH")]
+        humidity: String,
+        #[tabled(rename = "Pressure")]
+        pressure: String,
+        #[tabled(rename = "Radon")]
+        radon: String,
+        #[tabled(rename = "Battery")]
+        battery: String,
+        #[tabled(rename = "Age")]
+        age: String,
+    }
+
+    let dash = || "-".to_string();
+    let rows: Vec<Row> = readings
+        .iter()
+        .map(|dr| {
+            let r = &dr.reading;
+            Row {
+                device: if opts.no_color {
+                    dr.label().to_string()
+                } else {
+                    format!("{}", dr.label().cyan())
+                },
+                co2: if r.co2 > 0 {
+                    format!("{} ppm", style::format_co2_colored(r.co2, opts.no_color))
+                } else {
+                    dash()
+                },
+                temp: opts.format_temp(r.temperature),
+                humidity: if r.humidity > 0 {
+                    style::format_humidity_colored(r.humidity, opts.no_color)
+                } else {
+                    dash()
+                },
+                pressure: if r.pressure > 0.0 {
+                    opts.format_pressure(r.pressure)
+                } else {
+                    dash()
+                },
+                radon: r.radon.map(|v| opts.format_radon(v)).unwrap_or_else(dash),
+                battery: style::format_battery_colored(r.battery, opts.no_color),
+                age: format_age(r.age),
+            }
+        })
+        .collect();
+
+    let mut table = Table::new(rows);
+    style::apply_table_style(&mut table, opts.style);
+    format!("{}\n", table)
+}
+
+/// Format multiple device readings as a bare JSON array.
+pub fn format_multi_reading_json_array(
+    readings: &[DeviceReading],
+    opts: &FormatOptions,
+) -> Result<String> {
+    #[derive(Serialize)]
+    struct DeviceReadingJson {
+        device: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        alias: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(flatten)]
+        core: ReadingJsonCore,
+        age: u16,
+        interval: u16,
+    }
+
+    let json: Vec<DeviceReadingJson> = readings
+        .iter()
+        .map(|dr| DeviceReadingJson {
+            device: dr.identifier.clone(),
+            alias: dr.alias.clone(),
+            name: dr.name.clone(),
+            core: ReadingJsonCore::from_reading(&dr.reading, opts),
+            age: dr.reading.age,
+            interval: dr.reading.interval,
+        })
+        .collect();
+
+    opts.as_json(&json)
+}
+
 /// Format multiple device readings as CSV
 #[must_use]
 pub fn format_multi_reading_csv(readings: &[DeviceReading], opts: &FormatOptions) -> String {
@@ -951,7 +1104,12 @@ pub fn format_multi_reading_csv(readings: &[DeviceReading], opts: &FormatOptions
 // ============================================================================
 
 #[must_use]
-pub fn format_info_text(info: &DeviceInfo, opts: &FormatOptions) -> String {
+pub fn format_info_text(
+    info: &DeviceInfo,
+    history_info: Option<&aranet_core::HistoryInfo>,
+    health: Option<&aranet_core::SensorHealth>,
+    opts: &FormatOptions,
+) -> String {
     use tabled::builder::Builder;
 
     let mut builder = Builder::default();
@@ -963,6 +1121,28 @@ pub fn format_info_text(info: &DeviceInfo, opts: &FormatOptions) -> String {
     builder.push_record(["Hardware", &info.hardware]);
     builder.push_record(["Software", &info.software]);
     builder.push_record(["Manufacturer", &info.manufacturer]);
+    if let Some(history_info) = history_info {
+        let stored = match history_info.capacity {
+            Some(capacity) => format!("{} of {}", history_info.total_readings, capacity),
+            None => history_info.total_readings.to_string(),
+        };
+        builder.push_record(["Stored readings", &stored]);
+        if let Some(summary) = history_info.storage_summary() {
+            builder.push_record(["Storage", &summary]);
+        }
+    }
+    if let Some(health) = health {
+        if let Some(state) = health.calibration_state {
+            builder.push_record(["Calibration".to_string(), state.to_string()]);
+        }
+        if let Some(enabled) = health.auto_calibration_enabled {
+            let value = if enabled { "on" } else { "off" };
+            builder.push_record(["Auto-calibration", value]);
+        }
+        if let Some(offset) = health.co2_offset {
+            builder.push_record(["CO₂ offset".to_string(), format!("{} ppm", offset)]);
+        }
+    }
 
     let mut table = builder.build();
     style::apply_table_style(&mut table, opts.style);
@@ -973,7 +1153,12 @@ pub fn format_info_text(info: &DeviceInfo, opts: &FormatOptions) -> String {
         format!("{}", "Device Information".bold())
     };
 
-    format!("{}\n{}\n", title, table)
+    let mut output = format!("{}\n{}\n", title, table);
+    for warning in health.map(|h| h.warnings()).unwrap_or_default() {
+        output.push_str(&style::format_warning(&warning.to_string(), opts.no_color));
+        output.push('\n');
+    }
+    output
 }
 
 #[must_use]
@@ -1008,11 +1193,25 @@ pub fn format_info_csv(info: &DeviceInfo, opts: &FormatOptions) -> String {
 // History formatting
 // ============================================================================
 
+/// Most rows the history table shows; the rest are summarized.
+pub const HISTORY_TEXT_MAX_ROWS: usize = 20;
+
 #[must_use]
 pub fn format_history_text(history: &[HistoryRecord], opts: &FormatOptions) -> String {
+    format_history_text_with_total(history, history.len(), opts)
+}
+
+/// Format the history table for `total` records, of which `history` holds
+/// the first (at least [`HISTORY_TEXT_MAX_ROWS`] unless that is all of them).
+#[must_use]
+pub fn format_history_text_with_total(
+    history: &[HistoryRecord],
+    total: usize,
+    opts: &FormatOptions,
+) -> String {
     use tabled::builder::Builder;
 
-    if history.is_empty() {
+    if total == 0 {
         return "No history records found.\n".to_string();
     }
 
@@ -1028,9 +1227,13 @@ pub fn format_history_text(history: &[HistoryRecord], opts: &FormatOptions) -> S
     // Determine how many records to show based on terminal width
     // Narrow terminals get fewer records to avoid wrapping issues
     let term_width = style::terminal_width();
-    let max_records = if term_width < 80 { 10 } else { 20 };
+    let max_records = if term_width < 80 {
+        10
+    } else {
+        HISTORY_TEXT_MAX_ROWS
+    };
 
-    let mut output = format!("History ({} records):\n\n", history.len());
+    let mut output = format!("History ({} records):\n\n", total);
 
     // Build table with dynamic headers
     let mut builder = Builder::default();
@@ -1083,11 +1286,8 @@ pub fn format_history_text(history: &[HistoryRecord], opts: &FormatOptions) -> S
     output.push_str(&table.to_string());
     output.push('\n');
 
-    if history.len() > max_records {
-        output.push_str(&format!(
-            "... and {} more records\n",
-            history.len() - max_records
-        ));
+    if total > max_records {
+        output.push_str(&format!("... and {} more records\n", total - max_records));
         output.push_str("(Use --format csv or --format json for full data)\n");
     }
 
@@ -1096,21 +1296,34 @@ pub fn format_history_text(history: &[HistoryRecord], opts: &FormatOptions) -> S
 
 #[must_use]
 pub fn format_history_csv(history: &[HistoryRecord], opts: &FormatOptions) -> String {
+    let mut output = format_history_csv_header(opts);
+    output.push_str(&format_history_csv_rows(history, opts));
+    output
+}
+
+/// The history CSV header line, or nothing with `--no-header`.
+#[must_use]
+pub fn format_history_csv_header(opts: &FormatOptions) -> String {
+    if opts.no_header {
+        return String::new();
+    }
     let temp_header = if opts.fahrenheit {
         "temperature_f"
     } else {
         "temperature_c"
     };
-    let mut output = if opts.no_header {
-        String::new()
-    } else {
-        format!(
-            "timestamp,co2,{},humidity,{},{}\n",
-            temp_header,
-            opts.pressure_csv_header(),
-            opts.radon_csv_header()
-        )
-    };
+    format!(
+        "timestamp,co2,{},humidity,{},{}\n",
+        temp_header,
+        opts.pressure_csv_header(),
+        opts.radon_csv_header()
+    )
+}
+
+/// History CSV rows without a header.
+#[must_use]
+pub fn format_history_csv_rows(history: &[HistoryRecord], opts: &FormatOptions) -> String {
+    let mut output = String::new();
     for record in history {
         let ts = record
             .timestamp
@@ -1134,47 +1347,76 @@ pub fn format_history_csv(history: &[HistoryRecord], opts: &FormatOptions) -> St
     output
 }
 
-/// Format history as JSON with temperature and pressure unit conversion applied.
-pub fn format_history_json(history: &[HistoryRecord], opts: &FormatOptions) -> Result<String> {
-    #[derive(Serialize)]
-    struct HistoryRecordJson {
-        timestamp: String,
-        co2: u16,
-        temperature: f32,
-        temperature_unit: &'static str,
-        humidity: u8,
-        pressure: f32,
-        pressure_unit: &'static str,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        radon_bq: Option<u32>,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        radon_pci: Option<f32>,
+#[derive(Serialize)]
+struct HistoryRecordJson {
+    timestamp: String,
+    co2: u16,
+    temperature: f32,
+    temperature_unit: &'static str,
+    humidity: u8,
+    pressure: f32,
+    pressure_unit: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    radon_bq: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    radon_pci: Option<f32>,
+}
+
+impl HistoryRecordJson {
+    fn new(r: &HistoryRecord, opts: &FormatOptions) -> Self {
+        let ts = r
+            .timestamp
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_else(|_| String::new());
+        Self {
+            timestamp: ts,
+            co2: r.co2,
+            temperature: opts.convert_temp(r.temperature),
+            temperature_unit: if opts.fahrenheit { "F" } else { "C" },
+            humidity: r.humidity,
+            pressure: opts.convert_pressure(r.pressure),
+            pressure_unit: if opts.inhg { "inHg" } else { "hPa" },
+            radon_bq: r.radon,
+            radon_pci: r.radon.map(bq_to_pci),
+        }
     }
+}
 
+/// Format history as JSON with temperature and pressure unit conversion applied.
+pub fn format_history_json(history: &[HistoryRecord], opts: &FormatOptions) -> Result<String> {
     let records: Vec<HistoryRecordJson> = history
         .iter()
-        .map(|r| {
-            let ts = r
-                .timestamp
-                .format(&time::format_description::well_known::Rfc3339)
-                .unwrap_or_else(|_| String::new());
-            HistoryRecordJson {
-                timestamp: ts,
-                co2: r.co2,
-                temperature: opts.convert_temp(r.temperature),
-                temperature_unit: if opts.fahrenheit { "F" } else { "C" },
-                humidity: r.humidity,
-                pressure: opts.convert_pressure(r.pressure),
-                pressure_unit: if opts.inhg { "inHg" } else { "hPa" },
-                radon_bq: r.radon,
-                radon_pci: r.radon.map(bq_to_pci),
-            }
-        })
+        .map(|r| HistoryRecordJson::new(r, opts))
         .collect();
 
     opts.as_json(&records)
 }
 
+/// Format each history record as an element of the array
+/// [`format_history_json`] produces, indented to nest inside it.
+///
+/// Joining the elements with `,` (compact) or `,\n` (pretty) inside the
+/// array brackets gives the same output without holding every record.
+pub fn format_history_json_elements(
+    history: &[HistoryRecord],
+    opts: &FormatOptions,
+) -> Result<Vec<String>> {
+    history
+        .iter()
+        .map(|r| {
+            let record = HistoryRecordJson::new(r, opts);
+            Ok(if opts.compact {
+                serde_json::to_string(&record)?
+            } else {
+                format!(
+                    "  {}",
+                    serde_json::to_string_pretty(&record)?.replace('\n', "\n  ")
+                )
+            })
+        })
+        .collect()
+}
+
 // ============================================================================
 // Watch formatting
 // ============================================================================
@@ -1679,6 +1921,39 @@ mod tests {
         assert!(result.starts_with("co2,temperature_c,humidity,pressure_hpa,battery,status,age,interval,radon_pci,radiation_usvh,radiation_msv\n"));
     }
 
+    #[test]
+    fn test_format_multi_reading_combined() {
+        let readings = vec![
+            DeviceReading {
+                identifier: "AA:BB:CC:DD:EE:01".to_string(),
+                alias: Some("bedroom".to_string()),
+                name: Some("Aranet4 12345".to_string()),
+                reading: make_aranet4_reading(),
+            },
+            DeviceReading {
+                identifier: "AA:BB:CC:DD:EE:02".to_string(),
+                alias: None,
+                name: None,
+                reading: make_aranet4_reading(),
+            },
+        ];
+        let opts = test_opts();
+
+        let table = format_multi_reading_table(&readings, &opts);
+        assert!(table.contains("bedroom"));
+        assert!(table.contains("AA:BB:CC:DD:EE:02"));
+        assert!(table.contains("800 ppm"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&format_multi_reading_json_array(&readings, &opts).unwrap())
+                .unwrap();
+        let array = json.as_array().unwrap();
+        assert_eq!(array.len(), 2);
+        assert_eq!(array[0]["alias"], "bedroom");
+        assert_eq!(array[0]["co2"], 800);
+        assert!(array[1].get("alias").is_none());
+    }
+
     #[test]
     fn test_format_reading_json() {
         let reading = make_aranet4_reading();
@@ -1709,11 +1984,39 @@ mod tests {
     fn test_format_info_text_contains_all_fields() {
         let info = make_test_device_info();
         let opts = test_opts();
-        let result = format_info_text(&info, &opts);
+        let result = format_info_text(&info, None, None, &opts);
         assert!(result.contains("Device Information"));
         assert!(result.contains("Aranet4 12345"));
         assert!(result.contains("SN12345678"));
         assert!(result.contains("SAF Tehnika"));
+        assert!(!result.contains("Storage"));
+    }
+
+    #[test]
+    fn test_format_info_text_storage() {
+        let info = make_test_device_info();
+        let history_info = aranet_core::HistoryInfo {
+            total_readings: 1371,
+            interval_seconds: 300,
+            seconds_since_update: 60,
+            capacity: Some(2016),
+        };
+        let result = format_info_text(&info, Some(&history_info), None, &test_opts());
+        assert!(result.contains("1371 of 2016"));
+        assert!(result.contains("storage 68% full, ~2.2 days until overwrite"));
+    }
+
+    #[test]
+    fn test_format_info_text_sensor_health() {
+        let info = make_test_device_info();
+        let health = aranet_core::SensorHealth {
+            calibration_state: Some(aranet_core::CalibrationState::Idle),
+            auto_calibration_enabled: Some(false),
+            ..Default::default()
+        };
+        let result = format_info_text(&info, None, Some(&health), &test_opts());
+        assert!(result.contains("Auto-calibration"));
+        assert!(result.contains("[!!] Automatic calibration is off"));
     }
 
     #[test]
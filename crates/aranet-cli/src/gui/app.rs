@@ -3,28 +3,32 @@
 //! This module contains the [`AranetApp`] struct which implements the egui application,
 //! handling user input, rendering, and coordinating with the background BLE worker.
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::mpsc as std_mpsc;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
-use aranet_core::messages::{Command, SensorEvent};
+use aranet_core::messages::{Command, ImportPreview, SensorEvent};
 use aranet_core::service_client::DeviceCollectionStats;
 use eframe::egui::{self, RichText, UserData, ViewportCommand};
 use tokio::sync::mpsc;
 use tracing::{debug, info};
 
 use crate::config::{Config, GuiConfig};
+use crate::i18n::{tr, tr_args};
 
 use super::components;
 use super::export;
-use super::helpers::{SCAN_DURATION, TOAST_DURATION, Toast, ToastType};
+use super::helpers::{
+    SCAN_DURATION, SYSTEM_THEME_POLL_INTERVAL, TOAST_DURATION, Toast, ToastType, display_file_name,
+};
 use super::theme::{Theme, ThemeMode};
 use super::tray::{
     TrayCommand, TrayManager, TrayState, check_co2_threshold, hide_dock_icon, show_dock_icon,
 };
 use super::types::{
-    ConnectionFilter, ConnectionState, DeviceState, DeviceTypeFilter, HistoryFilter, Tab,
+    AlertEntry, AlertFilter, AlertSeverity, AlertType, ConnectionFilter, ConnectionState,
+    DeviceState, DeviceTypeFilter, HistoryFilter, PlacementState, RadiationLevel, Tab,
 };
 
 /// State of the aranet-service.
@@ -71,12 +75,14 @@ pub struct AranetApp {
     pub(crate) connection_filter: ConnectionFilter,
     /// Whether a settings update is in progress.
     pub(crate) updating_settings: bool,
-    /// When the last auto-refresh was triggered.
-    pub(crate) last_auto_refresh: Option<Instant>,
+    /// When auto-refresh last ran for each device, keyed by device ID.
+    pub(crate) last_auto_refresh: HashMap<String, Instant>,
     /// Whether auto-refresh is enabled.
     pub(crate) auto_refresh_enabled: bool,
-    /// Current theme mode (dark/light).
+    /// Current theme mode (dark/light/follow system).
     pub(crate) theme_mode: ThemeMode,
+    /// When the system appearance was last checked while following it.
+    pub(crate) last_system_theme_check: Instant,
     /// Current theme colors.
     pub(crate) theme: Theme,
     /// Active toast notifications.
@@ -136,12 +142,18 @@ pub struct AranetApp {
     // -------------------------------------------------------------------------
     // Alert History
     // -------------------------------------------------------------------------
-    /// History of alerts for the current session (newest first).
-    pub(crate) alert_history: VecDeque<super::types::AlertEntry>,
+    /// Alert history, loaded from the store on startup (newest first).
+    pub(crate) alert_history: VecDeque<AlertEntry>,
     /// Maximum number of alerts to keep in history.
     pub(crate) alert_history_max: usize,
     /// Whether the alert history popup is visible.
     pub(crate) alert_history_visible: bool,
+    /// Filters applied in the alert history popup.
+    pub(crate) alert_filter: AlertFilter,
+    /// Severity of the last low-battery alert per device, cleared once the battery recovers.
+    battery_alerts: HashMap<String, AlertSeverity>,
+    /// Last alerted radiation level per device, cleared once the dose rate is normal again.
+    radiation_alerts: HashMap<String, RadiationLevel>,
     /// Do Not Disturb mode - temporarily suppresses all notifications (per-session).
     pub(crate) do_not_disturb: bool,
     /// Whether to show combined Temperature & Humidity overlay chart.
@@ -150,6 +162,11 @@ pub struct AranetApp {
     pub(crate) comparison_mode: bool,
     /// Indices of devices selected for comparison.
     pub(crate) comparison_devices: Vec<usize>,
+    /// Sensor placement assistant session, while its window is open.
+    pub(crate) placement: Option<PlacementState>,
+    /// A dropped file and what importing it would add, while the import
+    /// preview dialog is open.
+    pub(crate) import_preview: Option<(std::path::PathBuf, ImportPreview)>,
     // -------------------------------------------------------------------------
     // Data Logging
     // -------------------------------------------------------------------------
@@ -167,6 +184,8 @@ pub struct AranetApp {
     // -------------------------------------------------------------------------
     /// Texture handle for the app logo displayed in the header.
     pub(crate) logo_texture: Option<egui::TextureHandle>,
+    /// Scripted demo scenario being played (demo mode only).
+    pub(crate) demo_player: Option<super::demo::DemoPlayer>,
 }
 
 impl AranetApp {
@@ -210,12 +229,8 @@ impl AranetApp {
         let gui_config = config.gui.clone();
 
         // Initialize theme based on saved preferences (including compact mode)
-        let theme_mode = match gui_config.theme.as_str() {
-            "light" => ThemeMode::Light,
-            "system" => super::theme::detect_system_theme(),
-            _ => ThemeMode::Dark,
-        };
-        let theme = Theme::for_mode_with_options(theme_mode, gui_config.compact_mode);
+        let theme_mode = ThemeMode::from_config(&gui_config.theme);
+        let theme = Theme::from_config(theme_mode, &gui_config);
         cc.egui_ctx.set_style(theme.to_style());
 
         // Close-to-tray is enabled only when tray is available and config allows it
@@ -230,7 +245,7 @@ impl AranetApp {
 
         // Sync menu state with initial app state
         if let Some(ref menu) = menu_manager {
-            menu.set_dark_mode(theme_mode == ThemeMode::Dark);
+            menu.set_dark_mode(theme.is_dark);
             menu.set_auto_refresh(!demo_mode);
         }
 
@@ -268,9 +283,10 @@ impl AranetApp {
             device_type_filter: DeviceTypeFilter::All,
             connection_filter: ConnectionFilter::All,
             updating_settings: false,
-            last_auto_refresh: None,
+            last_auto_refresh: HashMap::new(),
             auto_refresh_enabled: !demo_mode, // Disable auto-refresh in demo mode
             theme_mode,
+            last_system_theme_check: Instant::now(),
             theme,
             toasts: Vec::new(),
             // Read initial window visibility from tray state (for start_minimized support)
@@ -304,11 +320,16 @@ impl AranetApp {
             alert_history: VecDeque::new(),
             alert_history_max: 100, // Keep last 100 alerts
             alert_history_visible: false,
+            alert_filter: AlertFilter::default(),
+            battery_alerts: HashMap::new(),
+            radiation_alerts: HashMap::new(),
             // Temperature & Humidity overlay chart (off by default)
             show_temp_humidity_overlay: false,
             // Comparison mode (off by default)
             comparison_mode: false,
             comparison_devices: Vec::new(),
+            placement: None,
+            import_preview: None,
             // Data logging (off by default)
             log_file: None,
             logging_enabled: false,
@@ -316,6 +337,36 @@ impl AranetApp {
             sticky_alerts: false,
             // Logo texture (loaded on first frame)
             logo_texture: None,
+            demo_player: None,
+        }
+    }
+
+    /// Start playing a scripted demo scenario.
+    ///
+    /// Its events go through the same handling as events from the sensor
+    /// worker, so alerts, toasts and connection states behave as with real
+    /// devices.
+    pub fn start_demo_scenario(&mut self, scenario: super::demo::DemoScenario) {
+        self.status = format!("Demo Mode - playing {:?} scenario", scenario);
+        self.demo_player = Some(super::demo::DemoPlayer::new(scenario));
+    }
+
+    /// Handle demo scenario events that are due.
+    fn play_demo_scenario(&mut self) {
+        let Some(player) = self.demo_player.as_mut() else {
+            return;
+        };
+        let events = player.due_events(Instant::now());
+        let finished = player.is_finished().then(|| player.scenario());
+        for event in events {
+            self.handle_event(event);
+        }
+        if let Some(scenario) = finished {
+            self.demo_player = None;
+            self.add_toast(
+                format!("Demo scenario {:?} finished", scenario),
+                ToastType::Info,
+            );
         }
     }
 
@@ -358,13 +409,35 @@ impl AranetApp {
         self.toasts.push(Toast::new(message, toast_type));
     }
 
+    /// Open the placement assistant for a device and start probing it.
+    pub(crate) fn start_placement(&mut self, device_id: String) {
+        self.send_command(Command::StartPlacementProbe {
+            device_id: device_id.clone(),
+        });
+        self.placement = Some(PlacementState::new(device_id));
+    }
+
+    /// Close the placement assistant.
+    pub(crate) fn stop_placement(&mut self) {
+        if self.placement.take().is_some() {
+            self.send_command(Command::StopPlacementProbe);
+        }
+    }
+
     /// Remove expired toasts.
     fn cleanup_toasts(&mut self) {
         self.toasts.retain(|t| !t.is_expired());
     }
 
-    /// Add an alert to the history log.
-    fn log_alert(&mut self, alert: super::types::AlertEntry) {
+    /// Add an alert to the history log and persist it to the store.
+    fn log_alert(&mut self, alert: AlertEntry) {
+        self.send_command(Command::RecordAlert {
+            device_id: alert.device_id.clone(),
+            kind: alert.alert_type.kind().to_string(),
+            severity: alert.severity.as_str().to_string(),
+            value: alert.value,
+            message: alert.message.clone(),
+        });
         self.alert_history.push_front(alert); // Add to front (most recent first), O(1)
         // Trim to max size by removing from back (oldest)
         while self.alert_history.len() > self.alert_history_max {
@@ -373,15 +446,16 @@ impl AranetApp {
     }
 
     /// Check CO2 level and log alert if threshold exceeded.
-    fn check_and_log_co2_alert(&mut self, device_name: &str, co2_ppm: u16) {
-        use super::types::{AlertEntry, Co2Level};
+    fn check_and_log_co2_alert(&mut self, device_id: &str, device_name: &str, co2_ppm: u16) {
+        use super::types::Co2Level;
 
         let level = Co2Level::from_ppm(co2_ppm);
 
         // Get the last alert level for this specific check to avoid duplicate alerts
-        let last_co2_alert = self.alert_history.iter().find(|a| {
-            a.device_name == device_name && matches!(a.alert_type, super::types::AlertType::Co2)
-        });
+        let last_co2_alert = self
+            .alert_history
+            .iter()
+            .find(|a| a.device_id == device_id && a.alert_type == AlertType::Co2);
 
         let should_log = match last_co2_alert {
             None => matches!(level, Co2Level::Poor | Co2Level::Bad),
@@ -408,11 +482,89 @@ impl AranetApp {
         };
 
         if should_log {
-            let alert = AlertEntry::co2(device_name, co2_ppm, level);
+            let alert = AlertEntry::co2(device_id, device_name, co2_ppm, level);
             self.log_alert(alert);
         }
     }
 
+    /// Log a low-battery alert when a device drops below 20% (and again below 10%).
+    fn check_and_log_battery_alert(&mut self, device_id: &str, device_name: &str, battery: u8) {
+        if battery == 0 {
+            return;
+        }
+        if battery >= 20 {
+            self.battery_alerts.remove(device_id);
+            return;
+        }
+
+        let alert = AlertEntry::battery_low(device_id, device_name, battery);
+        let last = self.battery_alerts.get(device_id).copied();
+        if last == Some(alert.severity) || last == Some(AlertSeverity::Critical) {
+            return;
+        }
+        self.battery_alerts
+            .insert(device_id.to_string(), alert.severity);
+        self.log_alert(alert);
+    }
+
+    /// Log a radiation alert when the dose rate crosses the configured
+    /// warning or alarm threshold, and once when it returns to normal.
+    fn check_and_log_radiation_alert(&mut self, device_id: &str, device_name: &str, usv: f32) {
+        let level = RadiationLevel::from_usv(usv, &self.gui_config.radiation_thresholds());
+        let last = self.radiation_alerts.get(device_id).copied();
+
+        let should_log = match (last, level) {
+            (None, RadiationLevel::Normal) => false,
+            (None, _) => true,
+            (Some(RadiationLevel::Elevated), RadiationLevel::High) => true,
+            (Some(_), RadiationLevel::Normal) => true,
+            _ => false,
+        };
+        if !should_log {
+            return;
+        }
+
+        if level == RadiationLevel::Normal {
+            self.radiation_alerts.remove(device_id);
+        } else {
+            self.radiation_alerts.insert(device_id.to_string(), level);
+        }
+        self.log_alert(AlertEntry::radiation(device_id, device_name, usv, level));
+    }
+
+    /// Mark alerts as acknowledged, locally and in the store.
+    pub(crate) fn acknowledge_alerts(&mut self, indices: &[usize]) {
+        let mut ids = Vec::new();
+        for &index in indices {
+            if let Some(alert) = self.alert_history.get_mut(index)
+                && !alert.acknowledged
+            {
+                alert.acknowledged = true;
+                ids.extend(alert.id);
+            }
+        }
+        if !ids.is_empty() {
+            self.send_command(Command::AcknowledgeAlerts { ids });
+        }
+    }
+
+    /// Remove alerts from the history, locally and in the store.
+    pub(crate) fn clear_alerts(&mut self, indices: &[usize]) {
+        let mut ids = Vec::new();
+        let mut index = 0;
+        self.alert_history.retain(|alert| {
+            let remove = indices.contains(&index);
+            index += 1;
+            if remove {
+                ids.extend(alert.id);
+            }
+            !remove
+        });
+        if !ids.is_empty() {
+            self.send_command(Command::ClearAlerts { ids });
+        }
+    }
+
     /// Process system tray events and handle commands.
     fn process_tray_events(&mut self, ctx: &egui::Context) {
         let Some(ref tray_manager) = self.tray_manager else {
@@ -534,23 +686,13 @@ impl AranetApp {
                     self.apply_theme_change(ctx);
                 }
                 super::MenuCommand::ThemeSystem => {
-                    // Detect system preference using platform APIs
-                    self.theme_mode = super::theme::detect_system_theme();
-                    self.gui_config.theme = "system".to_string();
-                    self.apply_theme_change(ctx);
-                    self.save_gui_config();
+                    self.set_theme_mode(ctx, ThemeMode::FollowSystem);
                 }
                 super::MenuCommand::ThemeLight => {
-                    self.theme_mode = ThemeMode::Light;
-                    self.gui_config.theme = "light".to_string();
-                    self.apply_theme_change(ctx);
-                    self.save_gui_config();
+                    self.set_theme_mode(ctx, ThemeMode::Light);
                 }
                 super::MenuCommand::ThemeDark => {
-                    self.theme_mode = ThemeMode::Dark;
-                    self.gui_config.theme = "dark".to_string();
-                    self.apply_theme_change(ctx);
-                    self.save_gui_config();
+                    self.set_theme_mode(ctx, ThemeMode::Dark);
                 }
 
                 // === View menu - auto refresh ===
@@ -710,11 +852,35 @@ impl AranetApp {
     }
 
     /// Apply theme changes to the UI and sync with menu.
-    fn apply_theme_change(&mut self, ctx: &egui::Context) {
-        self.theme = Theme::for_mode_with_options(self.theme_mode, self.gui_config.compact_mode);
+    pub(crate) fn apply_theme_change(&mut self, ctx: &egui::Context) {
+        self.theme = Theme::from_config(self.theme_mode, &self.gui_config);
         ctx.set_style(self.theme.to_style());
         if let Some(ref menu) = self.menu_manager {
-            menu.set_dark_mode(self.theme_mode == ThemeMode::Dark);
+            menu.set_dark_mode(self.theme.is_dark);
+        }
+    }
+
+    /// Switch to a theme mode, apply it and save it as the preference.
+    pub(crate) fn set_theme_mode(&mut self, ctx: &egui::Context, mode: ThemeMode) {
+        self.theme_mode = mode;
+        self.gui_config.theme = mode.as_config_str().to_string();
+        self.apply_theme_change(ctx);
+        self.save_gui_config();
+    }
+
+    /// Re-apply the theme when following the system and its appearance
+    /// changed since the last check.
+    fn check_system_theme(&mut self, ctx: &egui::Context) {
+        if self.theme_mode != ThemeMode::FollowSystem
+            || self.last_system_theme_check.elapsed() < SYSTEM_THEME_POLL_INTERVAL
+        {
+            return;
+        }
+        self.last_system_theme_check = Instant::now();
+        let is_dark = super::theme::detect_system_theme() == ThemeMode::Dark;
+        if is_dark != self.theme.is_dark {
+            info!("System appearance changed, switching theme");
+            self.apply_theme_change(ctx);
         }
     }
 
@@ -782,13 +948,17 @@ impl AranetApp {
     }
 
     /// Check if auto-refresh is due and refresh connected devices.
+    ///
+    /// Each device runs on its own schedule: its configured override, or else
+    /// the shortest measurement interval among connected devices. Paused
+    /// devices are skipped.
     fn check_auto_refresh(&mut self) {
         if !self.auto_refresh_enabled {
             return;
         }
 
         // Get the shortest interval from connected devices (or default 60s)
-        let interval_secs = self
+        let default_secs = self
             .devices
             .iter()
             .filter(|d| matches!(d.connection, ConnectionState::Connected))
@@ -798,29 +968,30 @@ impl AranetApp {
             .min()
             .unwrap_or(60) as u64;
 
-        let interval = Duration::from_secs(interval_secs);
         let now = Instant::now();
-
-        let should_refresh = match self.last_auto_refresh {
-            Some(last) => now.duration_since(last) >= interval,
-            None => {
-                self.last_auto_refresh = Some(now);
-                false
+        let mut due = Vec::new();
+        for device in &self.devices {
+            if !matches!(device.connection, ConnectionState::Connected) {
+                continue;
             }
-        };
-
-        if should_refresh {
-            self.last_auto_refresh = Some(now);
-            let connected_ids: Vec<_> = self
-                .devices
-                .iter()
-                .filter(|d| matches!(d.connection, ConnectionState::Connected))
-                .map(|d| d.id.clone())
-                .collect();
-
-            for device_id in connected_ids {
-                self.send_command(Command::RefreshReading { device_id });
+            let refresh = self.gui_config.device_refresh(&device.id);
+            if refresh.paused {
+                continue;
             }
+            let interval = Duration::from_secs(refresh.interval_secs.unwrap_or(default_secs));
+            match self.last_auto_refresh.get(&device.id) {
+                Some(last) if now.duration_since(*last) >= interval => due.push(device.id.clone()),
+                Some(_) => {}
+                None => {
+                    // Newly connected: the reading is fresh, start the clock
+                    self.last_auto_refresh.insert(device.id.clone(), now);
+                }
+            }
+        }
+
+        for device_id in due {
+            self.last_auto_refresh.insert(device_id.clone(), now);
+            self.send_command(Command::RefreshReading { device_id });
         }
     }
 
@@ -886,9 +1057,16 @@ impl AranetApp {
                 error,
                 context,
             } => {
+                let mut went_offline = false;
+                let mut device_name = device_id.clone();
                 if let Some(device) = self.devices.iter_mut().find(|d| d.id == device_id) {
+                    went_offline = !matches!(device.connection, ConnectionState::Error(_));
+                    device_name = device.display_name().to_string();
                     device.connection = ConnectionState::Error(error.clone());
                 }
+                if went_offline {
+                    self.log_alert(AlertEntry::offline(&device_id, &device_name, &error));
+                }
                 // Show suggestion if available
                 let msg = if let Some(ctx) = context
                     && let Some(suggestion) = ctx.suggestion
@@ -913,6 +1091,9 @@ impl AranetApp {
                     .map(|d| d.display_name().to_string())
                     .unwrap_or_else(|| device_id.clone());
 
+                let battery = reading.battery;
+                let radiation_rate = reading.radiation_rate;
+
                 // Log reading to file if logging is enabled
                 self.log_reading(&device_id, &reading);
 
@@ -925,7 +1106,11 @@ impl AranetApp {
 
                 // Log alert if CO2 threshold exceeded
                 if let Some(co2) = co2_ppm {
-                    self.check_and_log_co2_alert(&device_name, co2);
+                    self.check_and_log_co2_alert(&device_id, &device_name, co2);
+                }
+                self.check_and_log_battery_alert(&device_id, &device_name, battery);
+                if let Some(rate) = radiation_rate {
+                    self.check_and_log_radiation_alert(&device_id, &device_name, rate);
                 }
 
                 self.status = "Reading updated".to_string();
@@ -1114,6 +1299,43 @@ impl AranetApp {
                         self.selected_device = Some(0);
                     }
                 }
+                // Device names are known now, so load the persisted alert history
+                self.send_command(Command::LoadAlerts {
+                    limit: self.alert_history_max as u32,
+                });
+            }
+            SensorEvent::AlertsLoaded { alerts } => {
+                // Keep alerts raised before the load finished that are not yet stored
+                let pending: Vec<AlertEntry> = self
+                    .alert_history
+                    .drain(..)
+                    .filter(|a| a.id.is_none())
+                    .collect();
+                self.alert_history = pending
+                    .into_iter()
+                    .chain(alerts.into_iter().filter_map(|alert| {
+                        let mut entry = AlertEntry::from_cached(alert)?;
+                        if let Some(device) = self.devices.iter().find(|d| d.id == entry.device_id)
+                        {
+                            entry.device_name = device.display_name().to_string();
+                        }
+                        Some(entry)
+                    }))
+                    .take(self.alert_history_max)
+                    .collect();
+            }
+            SensorEvent::AlertRecorded { alert } => {
+                if let Some(entry) = self.alert_history.iter_mut().rev().find(|a| {
+                    a.id.is_none() && a.device_id == alert.device_id && a.message == alert.message
+                }) {
+                    entry.id = Some(alert.id);
+                    // Acknowledged before the store write completed
+                    if entry.acknowledged {
+                        self.send_command(Command::AcknowledgeAlerts {
+                            ids: vec![alert.id],
+                        });
+                    }
+                }
             }
             // Service events
             SensorEvent::ServiceStatusRefreshed {
@@ -1279,6 +1501,11 @@ impl AranetApp {
                     ToastType::Error,
                 );
             }
+            SensorEvent::HistoryInfoLoaded { device_id, info } => {
+                if let Some(device) = self.devices.iter_mut().find(|d| d.id == device_id) {
+                    device.history_info = Some(info);
+                }
+            }
             SensorEvent::HistorySyncProgress {
                 device_id,
                 downloaded,
@@ -1330,6 +1557,56 @@ impl AranetApp {
                     device.signal_quality = Some(quality);
                 }
             }
+            SensorEvent::PlacementSample {
+                device_id,
+                rssi,
+                advertisements,
+            } => {
+                if let Some(placement) =
+                    self.placement.as_mut().filter(|p| p.device_id == device_id)
+                {
+                    placement.push(rssi, advertisements);
+                }
+            }
+            SensorEvent::PlacementError { device_id, error } => {
+                if let Some(placement) =
+                    self.placement.as_mut().filter(|p| p.device_id == device_id)
+                {
+                    placement.error = Some(error.clone());
+                }
+                self.add_toast(
+                    format!("Placement assistant stopped: {}", error),
+                    ToastType::Error,
+                );
+            }
+            SensorEvent::ImportPreviewReady { path, preview } => {
+                self.import_preview = Some((path, preview));
+            }
+            SensorEvent::ImportCompleted {
+                path,
+                imported,
+                skipped,
+            } => {
+                self.add_toast(
+                    format!(
+                        "Imported {} rows from {} ({} skipped)",
+                        imported,
+                        display_file_name(&path),
+                        skipped
+                    ),
+                    ToastType::Success,
+                );
+            }
+            SensorEvent::ImportFailed { path, error } => {
+                self.add_toast(
+                    format!("Import of {} failed: {}", display_file_name(&path), error),
+                    ToastType::Error,
+                );
+            }
+            // Passive monitoring is only driven by the TUI
+            SensorEvent::PassiveMonitoringStarted
+            | SensorEvent::PassiveMonitoringStopped
+            | SensorEvent::PassiveReadingUpdated { .. } => {}
         }
     }
 }
@@ -1351,10 +1628,13 @@ impl eframe::App for AranetApp {
         });
 
         self.process_events();
+        self.process_dropped_files(ctx);
+        self.play_demo_scenario();
         self.check_auto_refresh();
         self.cleanup_toasts();
         self.process_tray_events(ctx);
         self.process_menu_events(ctx);
+        self.check_system_theme(ctx);
 
         // Load logo texture on first frame
         if self.logo_texture.is_none()
@@ -1500,9 +1780,7 @@ impl eframe::App for AranetApp {
         // Apply deferred actions
         if toggle_theme {
             self.theme_mode.toggle();
-            self.theme =
-                Theme::for_mode_with_options(self.theme_mode, self.gui_config.compact_mode);
-            ctx.set_style(self.theme.to_style());
+            self.apply_theme_change(ctx);
         }
         if toggle_sidebar {
             self.sidebar_collapsed = !self.sidebar_collapsed;
@@ -1687,15 +1965,19 @@ impl eframe::App for AranetApp {
                     ui.add_space(self.theme.spacing.sm);
 
                     // Tab navigation
-                    for (tab, label, shortcut) in [
-                        (Tab::Dashboard, "Dashboard", "1"),
-                        (Tab::History, "History", "2"),
-                        (Tab::Settings, "Settings", "3"),
-                        (Tab::Service, "Service", "4"),
+                    for (tab, label_id, shortcut) in [
+                        (Tab::Dashboard, "tab-dashboard", "1"),
+                        (Tab::History, "tab-history", "2"),
+                        (Tab::Settings, "tab-settings", "3"),
+                        (Tab::Service, "tab-service", "4"),
                     ] {
                         let is_selected = self.active_tab == tab;
-                        let response = components::nav_tab(ui, &self.theme, label, is_selected)
-                            .on_hover_text(format!("Press {}", shortcut));
+                        let response =
+                            components::nav_tab(ui, &self.theme, &tr(label_id), is_selected)
+                                .on_hover_text(tr_args(
+                                    "nav-press-key",
+                                    &[("key", shortcut.into())],
+                                ));
 
                         if response.clicked() {
                             self.active_tab = tab;
@@ -1708,19 +1990,15 @@ impl eframe::App for AranetApp {
                         if components::themed_button(
                             ui,
                             &self.theme,
-                            self.theme_mode.icon(),
+                            if self.theme.is_dark { "Light" } else { "Dark" },
                             self.theme.button_secondary(),
                             self.theme.typography.caption,
                         )
-                        .on_hover_text("Press T to toggle theme")
+                        .on_hover_text(tr("nav-toggle-theme"))
                         .clicked()
                         {
                             self.theme_mode.toggle();
-                            self.theme = Theme::for_mode_with_options(
-                                self.theme_mode,
-                                self.gui_config.compact_mode,
-                            );
-                            ctx.set_style(self.theme.to_style());
+                            self.apply_theme_change(ctx);
                         }
 
                         ui.add_space(self.theme.spacing.sm);
@@ -1729,15 +2007,15 @@ impl eframe::App for AranetApp {
                         if components::toggle_chip(
                             ui,
                             &self.theme,
-                            if self.auto_refresh_enabled {
-                                "Auto On"
+                            &if self.auto_refresh_enabled {
+                                tr("nav-auto-on")
                             } else {
-                                "Auto Off"
+                                tr("nav-auto-off")
                             },
                             self.auto_refresh_enabled,
                             self.theme.success,
                         )
-                        .on_hover_text("Press A to toggle auto-refresh")
+                        .on_hover_text(tr("nav-toggle-auto-refresh"))
                         .clicked()
                         {
                             self.auto_refresh_enabled = !self.auto_refresh_enabled;
@@ -1805,13 +2083,17 @@ impl eframe::App for AranetApp {
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         // Alert history button
-                        let alert_count = self.alert_history.len();
+                        let alert_count = self
+                            .alert_history
+                            .iter()
+                            .filter(|a| !a.acknowledged)
+                            .count();
                         let has_recent_alerts = self.alert_history.iter().any(|a| {
-                            a.timestamp.elapsed() < Duration::from_secs(300) // 5 minutes
+                            !a.acknowledged
+                                && a.age() < Duration::from_secs(300) // 5 minutes
                                 && matches!(
                                     a.severity,
-                                    super::types::AlertSeverity::Warning
-                                        | super::types::AlertSeverity::Critical
+                                    AlertSeverity::Warning | AlertSeverity::Critical
                                 )
                         });
 
@@ -1837,7 +2119,7 @@ impl eframe::App for AranetApp {
                                 )
                                 .fill(ghost_style.fill),
                             )
-                            .on_hover_text("View alert history")
+                            .on_hover_text("View alert history (unacknowledged count)")
                             .clicked()
                         {
                             self.alert_history_visible = !self.alert_history_visible;
@@ -1906,6 +2188,16 @@ impl eframe::App for AranetApp {
             self.render_alert_history_popup(ctx);
         }
 
+        // Placement assistant window
+        if self.placement.is_some() {
+            self.render_placement_window(ctx);
+        }
+
+        // Import preview dialog for a dropped file
+        if self.import_preview.is_some() {
+            self.render_import_window(ctx);
+        }
+
         // Left panel with device list
         self.render_device_list(ctx);
 
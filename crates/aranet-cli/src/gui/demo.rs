@@ -1,15 +1,122 @@
 //! Demo mode for generating screenshots with mock data.
 //!
-//! This module provides deterministic mock data for reproducible screenshots.
+//! This module provides deterministic mock data for reproducible screenshots,
+//! and scripted [`DemoScenario`]s that replay sensor events on a timeline so
+//! alerts and connection states can be previewed without hardware.
 
-use aranet_core::BluetoothRange;
-use aranet_core::messages::SignalQuality;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use aranet_core::messages::{ErrorContext, SensorEvent, SignalQuality};
 use aranet_core::settings::DeviceSettings;
+use aranet_core::{BluetoothRange, HistoryInfo};
 use aranet_types::{CurrentReading, DeviceType, HistoryRecord, Status};
 use time::{Duration, OffsetDateTime};
 
 use super::types::{ConnectionState, DeviceState};
 
+/// Identifier of the demo Aranet4 device.
+const ARANET4_ID: &str = "921df903-d89b-9c97-6ffa-bb80d7c8e471";
+
+/// Identifier of the demo AranetRn+ device.
+const RADON_ID: &str = "387c18c7-299f-cc32-d01c-6cf29a8d3ca5";
+
+/// Time between scripted steps.
+const STEP_SECS: u64 = 4;
+
+/// A scripted sequence of events played out over time in demo mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DemoScenario {
+    /// CO₂ on the Aranet4 climbs through the moderate, poor and dangerous
+    /// thresholds, then falls back after ventilation.
+    Co2Rise,
+    /// The Aranet4 stops responding, goes offline and reconnects.
+    Disconnect,
+    /// The AranetRn+ battery drains through the low and critical levels.
+    BatteryDrain,
+    /// All scenarios, one after the other.
+    All,
+}
+
+impl DemoScenario {
+    /// The events of this scenario and when they fire, relative to its start.
+    pub fn timeline(self) -> Vec<DemoStep> {
+        match self {
+            Self::Co2Rise => co2_rise_timeline(),
+            Self::Disconnect => disconnect_timeline(),
+            Self::BatteryDrain => battery_drain_timeline(),
+            Self::All => {
+                let mut steps = Vec::new();
+                for scenario in [Self::Co2Rise, Self::Disconnect, Self::BatteryDrain] {
+                    let offset = steps
+                        .last()
+                        .map(|s: &DemoStep| s.at + std::time::Duration::from_secs(STEP_SECS))
+                        .unwrap_or_default();
+                    steps.extend(scenario.timeline().into_iter().map(|mut step| {
+                        step.at += offset;
+                        step
+                    }));
+                }
+                steps
+            }
+        }
+    }
+}
+
+/// A single scripted event.
+#[derive(Debug, Clone)]
+pub struct DemoStep {
+    /// When the event fires, relative to the start of the scenario.
+    pub at: std::time::Duration,
+    /// The event, handled exactly like one sent by the sensor worker.
+    pub event: SensorEvent,
+}
+
+/// Plays a [`DemoScenario`] back against the wall clock.
+#[derive(Debug)]
+pub struct DemoPlayer {
+    scenario: DemoScenario,
+    started: Instant,
+    steps: VecDeque<DemoStep>,
+}
+
+impl DemoPlayer {
+    /// Start playing `scenario` now.
+    pub fn new(scenario: DemoScenario) -> Self {
+        Self::starting_at(scenario, Instant::now())
+    }
+
+    fn starting_at(scenario: DemoScenario, started: Instant) -> Self {
+        Self {
+            scenario,
+            started,
+            steps: scenario.timeline().into(),
+        }
+    }
+
+    /// The scenario being played.
+    pub fn scenario(&self) -> DemoScenario {
+        self.scenario
+    }
+
+    /// Take the events that are due at `now`, in order.
+    pub fn due_events(&mut self, now: Instant) -> Vec<SensorEvent> {
+        let elapsed = now.saturating_duration_since(self.started);
+        let mut events = Vec::new();
+        while self.steps.front().is_some_and(|step| step.at <= elapsed) {
+            if let Some(step) = self.steps.pop_front() {
+                events.push(step.event);
+            }
+        }
+        events
+    }
+
+    /// Whether every step has been played.
+    pub fn is_finished(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
 /// Generate demo devices with realistic mock data for screenshots.
 /// Matches the real devices: Aranet4 17C3C (office) and AranetRn+ 306B8 (radon).
 pub fn create_demo_devices() -> Vec<DeviceState> {
@@ -43,12 +150,13 @@ fn create_aranet4_device() -> DeviceState {
         radon_unit: aranet_core::settings::RadonUnit::BqM3,
         buzzer_enabled: true,
         auto_calibration_enabled: true,
+        interval: Some(aranet_core::settings::MeasurementInterval::FiveMinutes),
     };
 
     let history = generate_co2_history(24 * 12); // 24 hours at 5-min intervals
 
     DeviceState {
-        id: "921df903-d89b-9c97-6ffa-bb80d7c8e471".to_string(),
+        id: ARANET4_ID.to_string(),
         name: Some("Aranet4 17C3C".to_string()),
         device_type: Some(DeviceType::Aranet4),
         rssi: Some(-75),
@@ -62,6 +170,12 @@ fn create_aranet4_device() -> DeviceState {
         settings: Some(settings),
         reading_from_cache: false,
         last_sync: Some(OffsetDateTime::now_utc() - Duration::minutes(5)),
+        history_info: Some(HistoryInfo {
+            total_readings: 1371,
+            interval_seconds: 300,
+            seconds_since_update: 120,
+            capacity: Some(2016),
+        }),
         background_polling: None,
         session_stats: Default::default(),
         connected_at: Some(std::time::Instant::now()),
@@ -90,7 +204,7 @@ fn create_radon_device() -> DeviceState {
     };
 
     DeviceState {
-        id: "387c18c7-299f-cc32-d01c-6cf29a8d3ca5".to_string(),
+        id: RADON_ID.to_string(),
         name: Some("AranetRn+ 306B8".to_string()),
         device_type: Some(DeviceType::AranetRadon),
         rssi: Some(-77),
@@ -104,12 +218,98 @@ fn create_radon_device() -> DeviceState {
         settings: None,
         reading_from_cache: false,
         last_sync: Some(OffsetDateTime::now_utc() - Duration::minutes(3)),
+        history_info: None,
         background_polling: None,
         session_stats: Default::default(),
         connected_at: Some(std::time::Instant::now()),
     }
 }
 
+/// Build a step every [`STEP_SECS`], starting one step in.
+fn steps(events: impl IntoIterator<Item = SensorEvent>) -> Vec<DemoStep> {
+    events
+        .into_iter()
+        .zip(1u64..)
+        .map(|(event, n)| DemoStep {
+            at: std::time::Duration::from_secs(n * STEP_SECS),
+            event,
+        })
+        .collect()
+}
+
+/// A fresh demo reading with the given CO₂ level.
+fn co2_reading(co2: u16) -> CurrentReading {
+    let status = if co2 < 1000 {
+        Status::Green
+    } else if co2 < 1400 {
+        Status::Yellow
+    } else {
+        Status::Red
+    };
+    CurrentReading {
+        co2,
+        status,
+        age: 0,
+        captured_at: Some(OffsetDateTime::now_utc()),
+        ..create_aranet4_device().reading.unwrap_or_default()
+    }
+}
+
+fn co2_rise_timeline() -> Vec<DemoStep> {
+    steps(
+        [850, 1050, 1300, 1650, 2100, 1200, 700]
+            .into_iter()
+            .map(|co2| SensorEvent::ReadingUpdated {
+                device_id: ARANET4_ID.to_string(),
+                reading: co2_reading(co2),
+            }),
+    )
+}
+
+fn disconnect_timeline() -> Vec<DemoStep> {
+    let device_id = ARANET4_ID.to_string();
+    steps([
+        SensorEvent::ConnectionError {
+            device_id: device_id.clone(),
+            error: "Device not responding".to_string(),
+            context: Some(ErrorContext::transient(
+                "Device not responding",
+                "Move closer to the device or check its battery",
+            )),
+        },
+        SensorEvent::DeviceConnecting {
+            device_id: device_id.clone(),
+        },
+        SensorEvent::DeviceConnected {
+            device_id: device_id.clone(),
+            name: Some("Aranet4 17C3C".to_string()),
+            device_type: Some(DeviceType::Aranet4),
+            rssi: Some(-82),
+        },
+        SensorEvent::ReadingUpdated {
+            device_id,
+            reading: co2_reading(640),
+        },
+    ])
+}
+
+fn battery_drain_timeline() -> Vec<DemoStep> {
+    let base = create_radon_device().reading.unwrap_or_default();
+    steps(
+        [60, 25, 18, 12, 8, 4]
+            .into_iter()
+            .map(|battery| SensorEvent::ReadingUpdated {
+                device_id: RADON_ID.to_string(),
+                reading: CurrentReading {
+                    battery,
+                    age: 0,
+                    captured_at: Some(OffsetDateTime::now_utc()),
+                    ..base
+                },
+            }),
+    )
+}
+
 /// Generate realistic CO2 history with daily patterns.
 fn generate_co2_history(count: usize) -> Vec<HistoryRecord> {
     let mut history = Vec::with_capacity(count);
@@ -176,3 +376,58 @@ fn generate_radon_history(count: usize) -> Vec<HistoryRecord> {
 
     history
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn co2_values(steps: &[DemoStep]) -> Vec<u16> {
+        steps
+            .iter()
+            .filter_map(|step| match &step.event {
+                SensorEvent::ReadingUpdated { reading, .. } => Some(reading.co2),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_co2_rise_crosses_thresholds() {
+        let co2 = co2_values(&DemoScenario::Co2Rise.timeline());
+        assert!(co2.iter().any(|&c| (1000..1500).contains(&c)));
+        assert!(co2.iter().any(|&c| c >= 1500));
+        assert!(co2.last().is_some_and(|&c| c < 800));
+    }
+
+    #[test]
+    fn test_all_scenarios_play_in_order() {
+        let steps = DemoScenario::All.timeline();
+        let single: usize = [
+            DemoScenario::Co2Rise,
+            DemoScenario::Disconnect,
+            DemoScenario::BatteryDrain,
+        ]
+        .iter()
+        .map(|s| s.timeline().len())
+        .sum();
+        assert_eq!(steps.len(), single);
+        assert!(steps.windows(2).all(|w| w[0].at < w[1].at));
+    }
+
+    #[test]
+    fn test_player_releases_due_events() {
+        let start = Instant::now();
+        let mut player = DemoPlayer::starting_at(DemoScenario::Disconnect, start);
+
+        assert!(player.due_events(start).is_empty());
+        let first = player.due_events(start + std::time::Duration::from_secs(STEP_SECS));
+        assert!(matches!(
+            first.as_slice(),
+            [SensorEvent::ConnectionError { .. }]
+        ));
+
+        let rest = player.due_events(start + std::time::Duration::from_secs(60));
+        assert_eq!(rest.len(), 3);
+        assert!(player.is_finished());
+    }
+}
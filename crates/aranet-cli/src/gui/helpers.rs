@@ -3,6 +3,7 @@
 //! This module contains utility types like [`Toast`] notifications and
 //! unit conversion functions for temperature, pressure, and radon measurements.
 
+use std::path::Path;
 use std::time::{Duration, Instant};
 
 use aranet_core::settings::{DeviceSettings, RadonUnit, TemperatureUnit};
@@ -13,6 +14,9 @@ pub const TOAST_DURATION: Duration = Duration::from_secs(4);
 /// Default scan duration.
 pub const SCAN_DURATION: Duration = Duration::from_secs(5);
 
+/// How often to check the system appearance when following it.
+pub const SYSTEM_THEME_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 /// Available measurement intervals in seconds.
 pub const INTERVAL_OPTIONS: &[(u16, &str)] = &[
     (60, "1 min"),
@@ -21,6 +25,17 @@ pub const INTERVAL_OPTIONS: &[(u16, &str)] = &[
     (600, "10 min"),
 ];
 
+/// Per-device auto-refresh interval choices in seconds (`None` follows the
+/// measurement interval).
+pub const REFRESH_INTERVAL_OPTIONS: &[(Option<u64>, &str)] = &[
+    (None, "Auto"),
+    (Some(30), "30 s"),
+    (Some(60), "1 min"),
+    (Some(300), "5 min"),
+    (Some(900), "15 min"),
+    (Some(1800), "30 min"),
+];
+
 /// Toast notification type.
 #[derive(Debug, Clone)]
 #[allow(dead_code)] // Info may be used later
@@ -72,6 +87,24 @@ pub fn hpa_to_inhg(hpa: f32) -> f32 {
     hpa * 0.02953
 }
 
+/// Whether to display temperatures in Fahrenheit.
+///
+/// Priority: device settings > app_preference > Celsius
+pub fn uses_fahrenheit(settings: Option<&DeviceSettings>, app_preference: Option<&str>) -> bool {
+    settings
+        .map(|s| s.temperature_unit == TemperatureUnit::Fahrenheit)
+        .unwrap_or_else(|| app_preference == Some("fahrenheit"))
+}
+
+/// Whether to display radon in pCi/L.
+///
+/// Priority: device settings > app_preference > Bq/m³
+pub fn uses_pci(settings: Option<&DeviceSettings>, app_preference: Option<&str>) -> bool {
+    settings
+        .map(|s| s.radon_unit == RadonUnit::PciL)
+        .unwrap_or_else(|| app_preference == Some("pci"))
+}
+
 /// Format temperature value and unit based on device settings or app preference.
 ///
 /// Priority: device settings > app_preference > Celsius
@@ -81,11 +114,7 @@ pub fn format_temperature(
     settings: Option<&DeviceSettings>,
     app_preference: Option<&str>,
 ) -> (String, &'static str) {
-    let use_fahrenheit = settings
-        .map(|s| s.temperature_unit == TemperatureUnit::Fahrenheit)
-        .unwrap_or_else(|| app_preference == Some("fahrenheit"));
-
-    if use_fahrenheit {
+    if uses_fahrenheit(settings, app_preference) {
         (format!("{:.1}", celsius_to_fahrenheit(celsius)), "°F")
     } else {
         (format!("{:.1}", celsius), "°C")
@@ -103,15 +132,16 @@ pub fn format_pressure(hpa: f32, app_preference: &str) -> (String, &'static str)
     }
 }
 
-/// Format radon value and unit based on device settings.
+/// Format radon value and unit based on device settings or app preference.
 ///
+/// Priority: device settings > app_preference > Bq/m³
 /// Returns (value_string, unit_string) tuple.
-pub fn format_radon(bq: u32, settings: Option<&DeviceSettings>) -> (String, &'static str) {
-    let use_pci = settings
-        .map(|s| s.radon_unit == RadonUnit::PciL)
-        .unwrap_or(false);
-
-    if use_pci {
+pub fn format_radon(
+    bq: u32,
+    settings: Option<&DeviceSettings>,
+    app_preference: Option<&str>,
+) -> (String, &'static str) {
+    if uses_pci(settings, app_preference) {
         (format!("{:.2}", bq_to_pci(bq)), "pCi/L")
     } else {
         (format!("{}", bq), "Bq/m3")
@@ -135,6 +165,14 @@ pub fn format_uptime(seconds: u64) -> String {
     }
 }
 
+/// File name of `path` for display, or the whole path if it has none.
+pub fn display_file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,6 +297,43 @@ mod tests {
         assert_eq!(unit, "°C");
     }
 
+    #[test]
+    fn test_format_radon_app_preference() {
+        let settings = DeviceSettings {
+            radon_unit: RadonUnit::BqM3,
+            ..Default::default()
+        };
+        assert_eq!(format_radon(100, None, Some("pci")).1, "pCi/L");
+        assert_eq!(format_radon(100, Some(&settings), Some("pci")).1, "Bq/m3");
+        assert_eq!(format_radon(100, None, None).1, "Bq/m3");
+    }
+
+    #[test]
+    fn test_override_device_units() {
+        let settings = DeviceSettings {
+            temperature_unit: TemperatureUnit::Celsius,
+            ..Default::default()
+        };
+        let mut config = crate::config::GuiConfig {
+            temperature_unit: "fahrenheit".to_string(),
+            ..Default::default()
+        };
+        let (_, unit) = format_temperature(
+            20.0,
+            config.unit_settings(Some(&settings)),
+            Some(&config.temperature_unit),
+        );
+        assert_eq!(unit, "°C");
+
+        config.override_device_units = true;
+        let (_, unit) = format_temperature(
+            20.0,
+            config.unit_settings(Some(&settings)),
+            Some(&config.temperature_unit),
+        );
+        assert_eq!(unit, "°F");
+    }
+
     // ========================================================================
     // format_pressure tests
     // ========================================================================
@@ -293,7 +368,7 @@ mod tests {
 
     #[test]
     fn test_format_radon_no_settings_defaults_bq() {
-        let (value, unit) = format_radon(100, None);
+        let (value, unit) = format_radon(100, None, None);
         assert_eq!(value, "100");
         assert_eq!(unit, "Bq/m3");
     }
@@ -304,7 +379,7 @@ mod tests {
             radon_unit: RadonUnit::BqM3,
             ..Default::default()
         };
-        let (value, unit) = format_radon(100, Some(&settings));
+        let (value, unit) = format_radon(100, Some(&settings), None);
         assert_eq!(value, "100");
         assert_eq!(unit, "Bq/m3");
     }
@@ -315,7 +390,7 @@ mod tests {
             radon_unit: RadonUnit::PciL,
             ..Default::default()
         };
-        let (value, unit) = format_radon(100, Some(&settings));
+        let (value, unit) = format_radon(100, Some(&settings), None);
         assert_eq!(value, "2.70");
         assert_eq!(unit, "pCi/L");
     }
@@ -326,7 +401,7 @@ mod tests {
             radon_unit: RadonUnit::PciL,
             ..Default::default()
         };
-        let (value, unit) = format_radon(0, Some(&settings));
+        let (value, unit) = format_radon(0, Some(&settings), None);
         assert_eq!(value, "0.00");
         assert_eq!(unit, "pCi/L");
     }
@@ -337,7 +412,7 @@ mod tests {
             radon_unit: RadonUnit::PciL,
             ..Default::default()
         };
-        let (value, unit) = format_radon(300, Some(&settings));
+        let (value, unit) = format_radon(300, Some(&settings), None);
         // 300 Bq/m³ = 8.1 pCi/L
         assert_eq!(value, "8.10");
         assert_eq!(unit, "pCi/L");
@@ -17,6 +17,8 @@ use tray_icon::menu::{
     accelerator::{Accelerator, Code, Modifiers},
 };
 
+use crate::i18n::tr;
+
 /// Commands that can be triggered from the native menu bar.
 #[derive(Debug, Clone)]
 pub enum MenuCommand {
@@ -179,15 +181,15 @@ impl MenuManager {
         }
 
         // === File menu ===
-        let file_menu = Submenu::new("File", true);
+        let file_menu = Submenu::new(tr("menu-file"), true);
 
         let scan_item = MenuItem::new(
-            "Scan for Devices",
+            tr("menu-scan-for-devices"),
             true,
             Some(Accelerator::new(None, Code::F5)),
         );
         let refresh_item = MenuItem::new(
-            "Refresh All",
+            tr("menu-refresh-all"),
             true,
             Some(Accelerator::new(Some(cmd), Code::KeyR)),
         );
@@ -197,14 +199,14 @@ impl MenuManager {
         file_menu.append(&PredefinedMenuItem::separator())?;
 
         // Export submenu
-        let export_menu = Submenu::new("Export History", true);
+        let export_menu = Submenu::new(tr("menu-export-history"), true);
         let export_csv_item = MenuItem::new(
-            "Export as CSV...",
+            tr("menu-export-as-csv"),
             true,
             Some(Accelerator::new(Some(cmd), Code::KeyE)),
         );
         let export_json_item = MenuItem::new(
-            "Export as JSON...",
+            tr("menu-export-as-json"),
             true,
             Some(Accelerator::new(Some(cmd | Modifiers::SHIFT), Code::KeyE)),
         );
@@ -224,7 +226,7 @@ impl MenuManager {
         menu.append(&file_menu)?;
 
         // === Edit menu (standard system items) ===
-        let edit_menu = Submenu::new("Edit", true);
+        let edit_menu = Submenu::new(tr("menu-edit"), true);
         edit_menu.append(&PredefinedMenuItem::undo(None))?;
         edit_menu.append(&PredefinedMenuItem::redo(None))?;
         edit_menu.append(&PredefinedMenuItem::separator())?;
@@ -235,26 +237,26 @@ impl MenuManager {
         menu.append(&edit_menu)?;
 
         // === View menu ===
-        let view_menu = Submenu::new("View", true);
+        let view_menu = Submenu::new(tr("menu-view"), true);
 
         // Navigation section
         let dashboard_item = MenuItem::new(
-            "Dashboard",
+            tr("menu-dashboard"),
             true,
             Some(Accelerator::new(Some(cmd), Code::Digit1)),
         );
         let history_item = MenuItem::new(
-            "History",
+            tr("menu-history"),
             true,
             Some(Accelerator::new(Some(cmd), Code::Digit2)),
         );
         let settings_item = MenuItem::new(
-            "Settings",
+            tr("menu-settings"),
             true,
             Some(Accelerator::new(Some(cmd), Code::Digit3)),
         );
         let service_item = MenuItem::new(
-            "Service",
+            tr("menu-service"),
             true,
             Some(Accelerator::new(Some(cmd), Code::Digit4)),
         );
@@ -266,10 +268,10 @@ impl MenuManager {
         view_menu.append(&PredefinedMenuItem::separator())?;
 
         // Appearance submenu
-        let appearance_menu = Submenu::new("Appearance", true);
-        let theme_system_item = CheckMenuItem::new("System", true, false, None);
-        let theme_light_item = CheckMenuItem::new("Light", true, false, None);
-        let theme_dark_item = CheckMenuItem::new("Dark", true, true, None); // Default to dark
+        let appearance_menu = Submenu::new(tr("menu-appearance"), true);
+        let theme_system_item = CheckMenuItem::new(tr("menu-system"), true, false, None);
+        let theme_light_item = CheckMenuItem::new(tr("menu-light"), true, false, None);
+        let theme_dark_item = CheckMenuItem::new(tr("menu-dark"), true, true, None); // Default to dark
         appearance_menu.append(&theme_system_item)?;
         appearance_menu.append(&theme_light_item)?;
         appearance_menu.append(&theme_dark_item)?;
@@ -277,7 +279,7 @@ impl MenuManager {
 
         // Hidden theme toggle for keyboard shortcut compatibility
         let theme_toggle_item = CheckMenuItem::new(
-            "Dark Mode",
+            tr("menu-dark-mode"),
             false, // Hidden
             false,
             Some(Accelerator::new(None, Code::KeyT)),
@@ -286,11 +288,11 @@ impl MenuManager {
         view_menu.append(&PredefinedMenuItem::separator())?;
 
         // Display options submenu
-        let display_menu = Submenu::new("Display", true);
-        let show_co2_item = CheckMenuItem::new("CO2 Level", true, true, None);
-        let show_temp_item = CheckMenuItem::new("Temperature", true, true, None);
-        let show_humidity_item = CheckMenuItem::new("Humidity", true, true, None);
-        let show_pressure_item = CheckMenuItem::new("Pressure", true, true, None);
+        let display_menu = Submenu::new(tr("menu-display"), true);
+        let show_co2_item = CheckMenuItem::new(tr("menu-co2-level"), true, true, None);
+        let show_temp_item = CheckMenuItem::new(tr("menu-temperature"), true, true, None);
+        let show_humidity_item = CheckMenuItem::new(tr("menu-humidity"), true, true, None);
+        let show_pressure_item = CheckMenuItem::new(tr("menu-pressure"), true, true, None);
         display_menu.append(&show_co2_item)?;
         display_menu.append(&show_temp_item)?;
         display_menu.append(&show_humidity_item)?;
@@ -301,18 +303,18 @@ impl MenuManager {
 
         // Auto-refresh and interval
         let auto_refresh_item = CheckMenuItem::new(
-            "Auto Refresh",
+            tr("menu-auto-refresh"),
             true,
             true,
             Some(Accelerator::new(None, Code::KeyA)),
         );
         view_menu.append(&auto_refresh_item)?;
 
-        let interval_menu = Submenu::new("Refresh Interval", true);
-        let interval_30s_item = CheckMenuItem::new("30 seconds", true, false, None);
-        let interval_1m_item = CheckMenuItem::new("1 minute", true, true, None); // Default
-        let interval_5m_item = CheckMenuItem::new("5 minutes", true, false, None);
-        let interval_10m_item = CheckMenuItem::new("10 minutes", true, false, None);
+        let interval_menu = Submenu::new(tr("menu-refresh-interval"), true);
+        let interval_30s_item = CheckMenuItem::new(tr("menu-30-seconds"), true, false, None);
+        let interval_1m_item = CheckMenuItem::new(tr("menu-1-minute"), true, true, None); // Default
+        let interval_5m_item = CheckMenuItem::new(tr("menu-5-minutes"), true, false, None);
+        let interval_10m_item = CheckMenuItem::new(tr("menu-10-minutes"), true, false, None);
         interval_menu.append(&interval_30s_item)?;
         interval_menu.append(&interval_1m_item)?;
         interval_menu.append(&interval_5m_item)?;
@@ -322,18 +324,19 @@ impl MenuManager {
         view_menu.append(&PredefinedMenuItem::separator())?;
 
         // Notifications
-        let notifications_item = CheckMenuItem::new("Enable Notifications", true, true, None);
+        let notifications_item =
+            CheckMenuItem::new(tr("menu-enable-notifications"), true, true, None);
         view_menu.append(&notifications_item)?;
 
         // Alerts submenu
-        let alerts_menu = Submenu::new("Alerts", true);
+        let alerts_menu = Submenu::new(tr("menu-alerts"), true);
         let do_not_disturb_item = CheckMenuItem::new(
-            "Do Not Disturb",
+            tr("menu-do-not-disturb"),
             true,
             false,
             Some(Accelerator::new(Some(cmd | Modifiers::SHIFT), Code::KeyD)),
         );
-        let sticky_alerts_item = CheckMenuItem::new("Sticky Alerts", true, false, None);
+        let sticky_alerts_item = CheckMenuItem::new(tr("menu-sticky-alerts"), true, false, None);
         alerts_menu.append(&do_not_disturb_item)?;
         alerts_menu.append(&sticky_alerts_item)?;
         view_menu.append(&alerts_menu)?;
@@ -342,7 +345,7 @@ impl MenuManager {
 
         // Data logging
         let data_logging_item = CheckMenuItem::new(
-            "Log Data to CSV",
+            tr("menu-log-data-to-csv"),
             true,
             false,
             Some(Accelerator::new(Some(cmd | Modifiers::SHIFT), Code::KeyL)),
@@ -355,11 +358,11 @@ impl MenuManager {
         menu.append(&view_menu)?;
 
         // === Device menu ===
-        let device_menu = Submenu::new("Device", true);
+        let device_menu = Submenu::new(tr("menu-device"), true);
 
         // Scan is duplicated here for discoverability
         let device_scan_item = MenuItem::new(
-            "Scan for Devices",
+            tr("menu-scan-for-devices"),
             true,
             None, // No accelerator, F5 is on File menu
         );
@@ -367,18 +370,18 @@ impl MenuManager {
         device_menu.append(&PredefinedMenuItem::separator())?;
 
         // Placeholder for dynamic device list - will be populated at runtime
-        let no_devices_item = MenuItem::new("No devices", false, None);
+        let no_devices_item = MenuItem::new(tr("menu-no-devices"), false, None);
         device_menu.append(&no_devices_item)?;
 
         device_menu.append(&PredefinedMenuItem::separator())?;
 
-        let manage_aliases_item = MenuItem::new("Manage Aliases...", true, None);
+        let manage_aliases_item = MenuItem::new(tr("menu-manage-aliases"), true, None);
         device_menu.append(&manage_aliases_item)?;
 
         menu.append(&device_menu)?;
 
         // === Window menu ===
-        let window_menu = Submenu::new("Window", true);
+        let window_menu = Submenu::new(tr("menu-window"), true);
         window_menu.append(&PredefinedMenuItem::minimize(None))?;
         window_menu.append(&PredefinedMenuItem::maximize(None))?;
         window_menu.append(&PredefinedMenuItem::separator())?;
@@ -393,11 +396,11 @@ impl MenuManager {
         menu.append(&window_menu)?;
 
         // === Help menu ===
-        let help_menu = Submenu::new("Help", true);
+        let help_menu = Submenu::new(tr("menu-help"), true);
 
-        let documentation_item = MenuItem::new("Aranet Documentation", true, None);
-        let report_issue_item = MenuItem::new("Report an Issue...", true, None);
-        let check_updates_item = MenuItem::new("Check for Updates...", true, None);
+        let documentation_item = MenuItem::new(tr("menu-aranet-documentation"), true, None);
+        let report_issue_item = MenuItem::new(tr("menu-report-an-issue"), true, None);
+        let check_updates_item = MenuItem::new(tr("menu-check-for-updates"), true, None);
 
         help_menu.append(&documentation_item)?;
         help_menu.append(&report_issue_item)?;
@@ -408,7 +411,7 @@ impl MenuManager {
         #[cfg(not(target_os = "macos"))]
         let about_item = {
             help_menu.append(&PredefinedMenuItem::separator())?;
-            let item = MenuItem::new("About Aranet", true, None);
+            let item = MenuItem::new(tr("menu-about-aranet"), true, None);
             help_menu.append(&item)?;
             item
         };
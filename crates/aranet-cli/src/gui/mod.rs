@@ -40,6 +40,7 @@ use tracing::{debug, info, warn};
 use aranet_core::messages::{Command, SensorEvent};
 
 use crate::config::Config;
+use crate::i18n::{self, Language};
 
 /// Embedded icon PNG data (64x64 RGBA)
 pub(crate) const ICON_PNG: &[u8] = include_bytes!("../../assets/aranet-icon.png");
@@ -77,6 +78,8 @@ pub struct GuiOptions {
     pub screenshot: Option<PathBuf>,
     /// Number of frames to wait before taking screenshot (default: 3).
     pub screenshot_delay_frames: u32,
+    /// Scripted scenario to play in demo mode.
+    pub demo_scenario: Option<demo::DemoScenario>,
 }
 
 impl GuiOptions {
@@ -88,6 +91,13 @@ impl GuiOptions {
         }
     }
 
+    /// Play a scripted scenario (implies demo mode).
+    pub fn with_scenario(mut self, scenario: demo::DemoScenario) -> Self {
+        self.demo = true;
+        self.demo_scenario = Some(scenario);
+        self
+    }
+
     /// Set screenshot output path.
     pub fn with_screenshot(mut self, path: impl Into<PathBuf>) -> Self {
         self.screenshot = Some(path.into());
@@ -106,7 +116,8 @@ pub fn run() -> Result<()> {
     tracing_subscriber::fmt::init();
 
     // Load config to get service URL
-    let config = Config::load_or_default()?;
+    let config = Config::load_effective()?;
+    i18n::set_language(Language::resolve(config.language.as_deref()));
     let service_url = config.gui.service_url.clone();
     let service_api_key = config.gui.service_api_key.clone();
 
@@ -283,7 +294,8 @@ pub fn run_with_options(options: GuiOptions) -> Result<()> {
     }
 
     // Load config to get service URL and GUI settings
-    let config = Config::load_or_default()?;
+    let config = Config::load_effective()?;
+    i18n::set_language(Language::resolve(config.language.as_deref()));
     let service_url = config.gui.service_url.clone();
     let service_api_key = config.gui.service_api_key.clone();
 
@@ -434,6 +446,7 @@ pub fn run_with_options(options: GuiOptions) -> Result<()> {
     let screenshot_path = options.screenshot.clone();
     let screenshot_delay = options.screenshot_delay_frames;
     let demo_mode = options.demo;
+    let demo_scenario = options.demo_scenario;
 
     eframe::run_native(
         "Aranet",
@@ -480,6 +493,10 @@ pub fn run_with_options(options: GuiOptions) -> Result<()> {
             // Set the menu manager on the app
             app.set_menu_manager(menu_manager);
 
+            if let Some(scenario) = demo_scenario {
+                app.start_demo_scenario(scenario);
+            }
+
             Ok(Box::new(app))
         }),
     )
@@ -1,85 +1,145 @@
 //! Alert history popup rendering.
 //!
 //! This module contains the alert history popup rendering logic,
-//! displaying past alerts with severity levels and timestamps.
+//! displaying persisted alerts with severity levels and timestamps,
+//! filters, and acknowledge/clear actions.
 
 use eframe::egui::{self, RichText};
 
 use crate::gui::app::AranetApp;
-use crate::gui::types::AlertSeverity;
+use crate::gui::types::{AlertSeverity, AlertType};
 
 impl AranetApp {
     /// Render the alert history popup.
     pub(crate) fn render_alert_history_popup(&mut self, ctx: &egui::Context) {
+        // Indices into alert_history of the alerts passing the current filters
+        let visible: Vec<usize> = self
+            .alert_history
+            .iter()
+            .enumerate()
+            .filter(|(_, alert)| self.alert_filter.matches(alert))
+            .map(|(index, _)| index)
+            .collect();
+        let unacknowledged = visible
+            .iter()
+            .filter(|&&i| !self.alert_history[i].acknowledged)
+            .count();
+
+        let mut acknowledge: Vec<usize> = Vec::new();
+        let mut clear: Vec<usize> = Vec::new();
+
         egui::Window::new("Alert History")
             .collapsible(false)
             .resizable(true)
-            .default_width(400.0)
-            .default_height(300.0)
+            .default_width(480.0)
+            .default_height(360.0)
             .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
             .show(ctx, |ui| {
-                // Header with close button
+                // Header with actions and close button
                 ui.horizontal(|ui| {
                     ui.label(
-                        RichText::new(format!("{} alerts", self.alert_history.len()))
-                            .color(self.theme.text_muted)
-                            .size(self.theme.typography.caption),
+                        RichText::new(format!(
+                            "{} alerts, {} unacknowledged",
+                            visible.len(),
+                            unacknowledged
+                        ))
+                        .color(self.theme.text_muted)
+                        .size(self.theme.typography.caption),
                     );
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         if ui
                             .add(
                                 egui::Button::new(
-                                    RichText::new("Clear All")
+                                    RichText::new("Close")
+                                        .size(self.theme.typography.caption)
+                                        .color(self.theme.text_on_accent),
+                                )
+                                .fill(self.theme.accent),
+                            )
+                            .clicked()
+                        {
+                            self.alert_history_visible = false;
+                        }
+
+                        ui.add_space(self.theme.spacing.sm);
+
+                        let clear_label = if self.alert_filter.is_active() {
+                            "Clear Shown"
+                        } else {
+                            "Clear All"
+                        };
+                        if ui
+                            .add_enabled(
+                                !visible.is_empty(),
+                                egui::Button::new(
+                                    RichText::new(clear_label)
                                         .size(self.theme.typography.caption)
                                         .color(self.theme.text_secondary),
                                 )
                                 .fill(self.theme.bg_secondary),
                             )
+                            .on_hover_text("Delete these alerts from the history")
                             .clicked()
                         {
-                            self.alert_history.clear();
+                            clear.extend(&visible);
                         }
 
                         ui.add_space(self.theme.spacing.sm);
 
                         if ui
-                            .add(
+                            .add_enabled(
+                                unacknowledged > 0,
                                 egui::Button::new(
-                                    RichText::new("Close")
+                                    RichText::new("Acknowledge All")
                                         .size(self.theme.typography.caption)
-                                        .color(self.theme.text_on_accent),
+                                        .color(self.theme.text_secondary),
                                 )
-                                .fill(self.theme.accent),
+                                .fill(self.theme.bg_secondary),
                             )
                             .clicked()
                         {
-                            self.alert_history_visible = false;
+                            acknowledge.extend(&visible);
                         }
                     });
                 });
 
+                self.render_alert_filters(ui);
+
                 ui.separator();
 
-                if self.alert_history.is_empty() {
+                if visible.is_empty() {
                     ui.vertical_centered(|ui| {
                         ui.add_space(self.theme.spacing.xl);
+                        let (title, hint) = if self.alert_history.is_empty() {
+                            (
+                                "No alerts yet",
+                                "Alerts will appear when thresholds are exceeded, \
+                                 devices go offline, or batteries run low",
+                            )
+                        } else {
+                            (
+                                "No matching alerts",
+                                "Adjust the filters to see more alerts",
+                            )
+                        };
                         ui.label(
-                            RichText::new("No alerts yet")
+                            RichText::new(title)
                                 .color(self.theme.text_muted)
                                 .size(self.theme.typography.body),
                         );
                         ui.label(
-                            RichText::new("Alerts will appear when thresholds are exceeded")
+                            RichText::new(hint)
                                 .color(self.theme.text_muted)
                                 .size(self.theme.typography.caption),
                         );
                     });
                 } else {
                     egui::ScrollArea::vertical()
-                        .max_height(250.0)
+                        .max_height(280.0)
                         .show(ui, |ui| {
-                            for alert in &self.alert_history {
+                            for &index in &visible {
+                                let alert = &self.alert_history[index];
                                 let (severity_color, severity_bg) = match alert.severity {
                                     AlertSeverity::Info => {
                                         (self.theme.info, self.theme.tint_bg(self.theme.info, 15))
@@ -93,6 +153,12 @@ impl AranetApp {
                                         self.theme.tint_bg(self.theme.danger, 15),
                                     ),
                                 };
+                                // Acknowledged alerts are drawn muted
+                                let (severity_color, severity_bg) = if alert.acknowledged {
+                                    (self.theme.text_muted, self.theme.bg_secondary)
+                                } else {
+                                    (severity_color, severity_bg)
+                                };
 
                                 egui::Frame::new()
                                     .fill(severity_bg)
@@ -116,7 +182,7 @@ impl AranetApp {
 
                                             ui.vertical(|ui| {
                                                 ui.horizontal(|ui| {
-                                                    // Time and device
+                                                    // Time, device and type
                                                     ui.label(
                                                         RichText::new(&alert.time_str)
                                                             .color(self.theme.text_secondary)
@@ -133,12 +199,34 @@ impl AranetApp {
                                                             .size(self.theme.typography.caption)
                                                             .strong(),
                                                     );
+                                                    ui.label(
+                                                        RichText::new(alert.alert_type.label())
+                                                            .color(self.theme.text_muted)
+                                                            .size(self.theme.typography.caption),
+                                                    );
 
                                                     ui.with_layout(
                                                         egui::Layout::right_to_left(
                                                             egui::Align::Center,
                                                         ),
                                                         |ui| {
+                                                            if ui
+                                                                .small_button("Clear")
+                                                                .on_hover_text("Delete this alert")
+                                                                .clicked()
+                                                            {
+                                                                clear.push(index);
+                                                            }
+                                                            if !alert.acknowledged
+                                                                && ui
+                                                                    .small_button("Ack")
+                                                                    .on_hover_text(
+                                                                        "Acknowledge this alert",
+                                                                    )
+                                                                    .clicked()
+                                                            {
+                                                                acknowledge.push(index);
+                                                            }
                                                             ui.label(
                                                                 RichText::new(alert.age_str())
                                                                     .color(self.theme.text_muted)
@@ -166,5 +254,66 @@ impl AranetApp {
                         });
                 }
             });
+
+        if !acknowledge.is_empty() {
+            self.acknowledge_alerts(&acknowledge);
+        }
+        if !clear.is_empty() {
+            self.clear_alerts(&clear);
+        }
+    }
+
+    /// Render the type, severity and device filters for the alert history.
+    fn render_alert_filters(&mut self, ui: &mut egui::Ui) {
+        // Devices that appear in the history, for the device filter
+        let mut devices: Vec<(String, String)> = Vec::new();
+        for alert in &self.alert_history {
+            if !devices.iter().any(|(id, _)| *id == alert.device_id) {
+                devices.push((alert.device_id.clone(), alert.device_name.clone()));
+            }
+        }
+
+        ui.horizontal(|ui| {
+            let filter = &mut self.alert_filter;
+
+            egui::ComboBox::from_id_salt("alert_type_filter")
+                .selected_text(filter.alert_type.map_or("All types", |t| t.label()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut filter.alert_type, None, "All types");
+                    for alert_type in AlertType::ALL {
+                        ui.selectable_value(
+                            &mut filter.alert_type,
+                            Some(alert_type),
+                            alert_type.label(),
+                        );
+                    }
+                });
+
+            egui::ComboBox::from_id_salt("alert_severity_filter")
+                .selected_text(filter.severity.map_or("All severities", |s| s.label()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut filter.severity, None, "All severities");
+                    for severity in AlertSeverity::ALL {
+                        ui.selectable_value(&mut filter.severity, Some(severity), severity.label());
+                    }
+                });
+
+            let device_label = filter
+                .device_id
+                .as_ref()
+                .and_then(|id| devices.iter().find(|(d, _)| d == id))
+                .map_or("All devices", |(_, name)| name.as_str())
+                .to_string();
+            egui::ComboBox::from_id_salt("alert_device_filter")
+                .selected_text(device_label)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut filter.device_id, None, "All devices");
+                    for (id, name) in &devices {
+                        ui.selectable_value(&mut filter.device_id, Some(id.clone()), name);
+                    }
+                });
+
+            ui.checkbox(&mut filter.unacknowledged_only, "Unacknowledged only");
+        });
     }
 }
@@ -5,16 +5,17 @@
 
 use eframe::egui::{self, Color32, RichText};
 
-use crate::config::Config;
+use crate::config::{ChartPalette, Config};
 use crate::gui::app::AranetApp;
 use crate::gui::components;
 use crate::gui::helpers::ToastType;
-use crate::gui::theme::{Theme, ThemeMode};
+use crate::gui::theme::ThemeMode;
+use crate::i18n::{self, Language, tr};
 
 impl AranetApp {
     /// Render the application settings section.
     pub(crate) fn render_app_settings_section(&mut self, ui: &mut egui::Ui) {
-        components::section_header(ui, &self.theme, "Application Settings");
+        components::section_header(ui, &self.theme, &tr("settings-title"));
 
         egui::Frame::new()
             .fill(self.theme.bg_card)
@@ -28,20 +29,24 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Theme")
+                            RichText::new(tr("settings-theme"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Choose light or dark appearance")
+                            RichText::new(tr("settings-theme-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_secondary),
                         );
                     });
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        for (mode, label) in [("dark", "Dark"), ("light", "Light")] {
-                            let is_selected = self.gui_config.theme == mode;
+                        for (mode, label) in [
+                            (ThemeMode::FollowSystem, tr("settings-system")),
+                            (ThemeMode::Light, tr("settings-light")),
+                            (ThemeMode::Dark, tr("settings-dark")),
+                        ] {
+                            let is_selected = self.theme_mode == mode;
                             let (bg, text_color) = if is_selected {
                                 (self.theme.accent, self.theme.text_on_accent)
                             } else {
@@ -57,18 +62,9 @@ impl AranetApp {
                             .corner_radius(egui::CornerRadius::same(self.theme.rounding.sm as u8));
 
                             if ui.add(btn).clicked() && !is_selected {
-                                self.gui_config.theme = mode.to_string();
-                                self.theme_mode = match mode {
-                                    "light" => ThemeMode::Light,
-                                    _ => ThemeMode::Dark,
-                                };
-                                self.theme = Theme::for_mode_with_options(
-                                    self.theme_mode,
-                                    self.gui_config.compact_mode,
-                                );
-                                if let Some(ref menu) = self.menu_manager {
-                                    menu.set_dark_mode(self.theme_mode == ThemeMode::Dark);
-                                }
+                                self.theme_mode = mode;
+                                self.gui_config.theme = mode.as_config_str().to_string();
+                                self.apply_theme_change(ui.ctx());
                                 config_changed = true;
                             }
                         }
@@ -81,19 +77,20 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Compact Mode")
+                            RichText::new(tr("settings-compact-mode"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Denser layout for smaller screens")
+                            RichText::new(tr("settings-compact-mode-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_secondary),
                         );
                     });
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        for (val, text) in [(true, "On"), (false, "Off")] {
+                        for (val, text) in [(true, tr("settings-on")), (false, tr("settings-off"))]
+                        {
                             let is_selected = self.gui_config.compact_mode == val;
                             let (bg, text_color) = if is_selected {
                                 (self.theme.accent, self.theme.text_on_accent)
@@ -112,8 +109,7 @@ impl AranetApp {
                             if ui.add(btn).clicked() && !is_selected {
                                 self.gui_config.compact_mode = val;
                                 // Rebuild theme with new compact setting
-                                self.theme = Theme::for_mode_with_options(self.theme_mode, val);
-                                ui.ctx().set_style(self.theme.to_style());
+                                self.apply_theme_change(ui.ctx());
                                 config_changed = true;
                             }
                         }
@@ -122,23 +118,162 @@ impl AranetApp {
 
                 ui.add_space(self.theme.spacing.md);
 
+                // Accent color
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        ui.label(
+                            RichText::new(tr("settings-accent-color"))
+                                .size(self.theme.typography.body)
+                                .color(self.theme.text_primary),
+                        );
+                        ui.label(
+                            RichText::new(tr("settings-accent-color-hint"))
+                                .size(self.theme.typography.caption)
+                                .color(self.theme.text_secondary),
+                        );
+                    });
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if self.gui_config.accent_color.is_some()
+                            && ui.button(tr("settings-reset")).clicked()
+                        {
+                            self.gui_config.accent_color = None;
+                            self.apply_theme_change(ui.ctx());
+                            config_changed = true;
+                        }
+                        let accent = self.theme.accent;
+                        let mut rgb = [accent.r(), accent.g(), accent.b()];
+                        if egui::color_picker::color_edit_button_srgb(ui, &mut rgb).changed() {
+                            self.gui_config.accent_color = Some(hex_color(rgb));
+                            self.apply_theme_change(ui.ctx());
+                            config_changed = true;
+                        }
+                    });
+                });
+
+                ui.add_space(self.theme.spacing.md);
+
+                // Chart palette
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        ui.label(
+                            RichText::new(tr("settings-chart-colors"))
+                                .size(self.theme.typography.body)
+                                .color(self.theme.text_primary),
+                        );
+                        ui.label(
+                            RichText::new(tr("settings-chart-colors-hint"))
+                                .size(self.theme.typography.caption)
+                                .color(self.theme.text_secondary),
+                        );
+                    });
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if !self.gui_config.chart_palette.is_default()
+                            && ui.button(tr("settings-reset")).clicked()
+                        {
+                            self.gui_config.chart_palette = ChartPalette::default();
+                            self.apply_theme_change(ui.ctx());
+                            config_changed = true;
+                        }
+                        let palette = &mut self.gui_config.chart_palette;
+                        let mut changed = false;
+                        // Right-to-left, so the last entry is shown first
+                        for (custom, current, label) in [
+                            (
+                                &mut palette.pressure,
+                                self.theme.chart_pressure,
+                                tr("menu-pressure"),
+                            ),
+                            (
+                                &mut palette.humidity,
+                                self.theme.chart_humidity,
+                                tr("menu-humidity"),
+                            ),
+                            (
+                                &mut palette.temperature,
+                                self.theme.chart_temperature,
+                                tr("menu-temperature"),
+                            ),
+                            (&mut palette.co2, self.theme.chart_co2, tr("menu-co2-level")),
+                        ] {
+                            let mut rgb = [current.r(), current.g(), current.b()];
+                            if egui::color_picker::color_edit_button_srgb(ui, &mut rgb)
+                                .on_hover_text(label)
+                                .changed()
+                            {
+                                *custom = Some(hex_color(rgb));
+                                changed = true;
+                            }
+                        }
+                        if changed {
+                            self.apply_theme_change(ui.ctx());
+                            config_changed = true;
+                        }
+                    });
+                });
+
+                ui.add_space(self.theme.spacing.md);
+
+                // Language selection
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        ui.label(
+                            RichText::new(tr("settings-language"))
+                                .size(self.theme.typography.body)
+                                .color(self.theme.text_primary),
+                        );
+                        ui.label(
+                            RichText::new(tr("settings-language-hint"))
+                                .size(self.theme.typography.caption)
+                                .color(self.theme.text_secondary),
+                        );
+                    });
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let current = i18n::language();
+                        let mut selected = current;
+                        egui::ComboBox::from_id_salt("language")
+                            .selected_text(current.native_name())
+                            .show_ui(ui, |ui| {
+                                for language in Language::ALL {
+                                    ui.selectable_value(
+                                        &mut selected,
+                                        language,
+                                        language.native_name(),
+                                    );
+                                }
+                            });
+
+                        if selected != current {
+                            i18n::set_language(selected);
+                            let mut full_config = Config::load_or_default_logged();
+                            full_config.language = Some(selected.code().to_string());
+                            let _ = full_config.save();
+                        }
+                    });
+                });
+
+                ui.add_space(self.theme.spacing.md);
+
                 // Colored tray icon toggle
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Colored Menu Bar Icon")
+                            RichText::new(tr("settings-colored-menu-bar-icon"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Show colored icon when CO2 is elevated")
+                            RichText::new(tr("settings-colored-menu-bar-icon-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_secondary),
                         );
                     });
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        for (val, text) in [(true, "On"), (false, "Off")] {
+                        for (val, text) in [(true, tr("settings-on")), (false, tr("settings-off"))]
+                        {
                             let is_selected = self.gui_config.colored_tray_icon == val;
                             let (bg, text_color) = if is_selected {
                                 (self.theme.accent, self.theme.text_on_accent)
@@ -176,19 +311,20 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Desktop Notifications")
+                            RichText::new(tr("settings-desktop-notifications"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Alert when CO2 reaches threshold levels")
+                            RichText::new(tr("settings-desktop-notifications-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_secondary),
                         );
                     });
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        for (val, text) in [(true, "On"), (false, "Off")] {
+                        for (val, text) in [(true, tr("settings-on")), (false, tr("settings-off"))]
+                        {
                             let is_selected = self.gui_config.notifications_enabled == val;
                             let (bg, text_color) = if is_selected {
                                 (self.theme.accent, self.theme.text_on_accent)
@@ -223,19 +359,21 @@ impl AranetApp {
                     ui.horizontal(|ui| {
                         ui.vertical(|ui| {
                             ui.label(
-                                RichText::new("Notification Sound")
+                                RichText::new(tr("settings-notification-sound"))
                                     .size(self.theme.typography.body)
                                     .color(self.theme.text_primary),
                             );
                             ui.label(
-                                RichText::new("Play sound with notifications")
+                                RichText::new(tr("settings-notification-sound-hint"))
                                     .size(self.theme.typography.caption)
                                     .color(self.theme.text_secondary),
                             );
                         });
 
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            for (val, text) in [(true, "On"), (false, "Off")] {
+                            for (val, text) in
+                                [(true, tr("settings-on")), (false, tr("settings-off"))]
+                            {
                                 let is_selected = self.gui_config.notification_sound == val;
                                 let (bg, text_color) = if is_selected {
                                     (self.theme.accent, self.theme.text_on_accent)
@@ -271,19 +409,21 @@ impl AranetApp {
                     ui.horizontal(|ui| {
                         ui.vertical(|ui| {
                             ui.label(
-                                RichText::new("Do Not Disturb")
+                                RichText::new(tr("settings-do-not-disturb"))
                                     .size(self.theme.typography.body)
                                     .color(self.theme.text_primary),
                             );
                             ui.label(
-                                RichText::new("Temporarily silence all notifications")
+                                RichText::new(tr("settings-do-not-disturb-hint"))
                                     .size(self.theme.typography.caption)
                                     .color(self.theme.text_secondary),
                             );
                         });
 
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            for (val, text) in [(true, "On"), (false, "Off")] {
+                            for (val, text) in
+                                [(true, tr("settings-on")), (false, tr("settings-off"))]
+                            {
                                 let is_selected = self.do_not_disturb == val;
                                 let (bg, text_color) = if is_selected {
                                     if val {
@@ -321,13 +461,10 @@ impl AranetApp {
                                     }
                                     // Show toast to confirm
                                     if val {
-                                        self.add_toast(
-                                            "Do Not Disturb enabled".to_string(),
-                                            ToastType::Info,
-                                        );
+                                        self.add_toast(tr("settings-dnd-enabled"), ToastType::Info);
                                     } else {
                                         self.add_toast(
-                                            "Do Not Disturb disabled".to_string(),
+                                            tr("settings-dnd-disabled"),
                                             ToastType::Info,
                                         );
                                     }
@@ -344,19 +481,21 @@ impl AranetApp {
                     ui.horizontal(|ui| {
                         ui.vertical(|ui| {
                             ui.label(
-                                RichText::new("Close to Menu Bar")
+                                RichText::new(tr("settings-close-to-menu-bar"))
                                     .size(self.theme.typography.body)
                                     .color(self.theme.text_primary),
                             );
                             ui.label(
-                                RichText::new("Keep running in background when window closes")
+                                RichText::new(tr("settings-close-to-menu-bar-hint"))
                                     .size(self.theme.typography.caption)
                                     .color(self.theme.text_secondary),
                             );
                         });
 
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            for (val, text) in [(true, "On"), (false, "Off")] {
+                            for (val, text) in
+                                [(true, tr("settings-on")), (false, tr("settings-off"))]
+                            {
                                 let is_selected = self.gui_config.close_to_tray == val;
                                 let (bg, text_color) = if is_selected {
                                     (self.theme.accent, self.theme.text_on_accent)
@@ -389,19 +528,21 @@ impl AranetApp {
                     ui.horizontal(|ui| {
                         ui.vertical(|ui| {
                             ui.label(
-                                RichText::new("Start Minimized")
+                                RichText::new(tr("settings-start-minimized"))
                                     .size(self.theme.typography.body)
                                     .color(self.theme.text_primary),
                             );
                             ui.label(
-                                RichText::new("Launch hidden in menu bar")
+                                RichText::new(tr("settings-start-minimized-hint"))
                                     .size(self.theme.typography.caption)
                                     .color(self.theme.text_secondary),
                             );
                         });
 
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            for (val, text) in [(true, "On"), (false, "Off")] {
+                            for (val, text) in
+                                [(true, tr("settings-on")), (false, tr("settings-off"))]
+                            {
                                 let is_selected = self.gui_config.start_minimized == val;
                                 let (bg, text_color) = if is_selected {
                                     (self.theme.accent, self.theme.text_on_accent)
@@ -434,7 +575,7 @@ impl AranetApp {
 
                 // Units section header
                 ui.label(
-                    RichText::new("Display Units")
+                    RichText::new(tr("settings-display-units"))
                         .size(self.theme.typography.body)
                         .strong()
                         .color(self.theme.text_primary),
@@ -445,12 +586,12 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Temperature")
+                            RichText::new(tr("settings-temperature"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Used when device preference is unavailable")
+                            RichText::new(tr("settings-temperature-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_secondary),
                         );
@@ -487,12 +628,12 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Pressure")
+                            RichText::new(tr("settings-pressure"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Atmospheric pressure display unit")
+                            RichText::new(tr("settings-pressure-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_secondary),
                         );
@@ -523,13 +664,98 @@ impl AranetApp {
                     });
                 });
 
+                ui.add_space(self.theme.spacing.md);
+
+                // Radon unit toggle
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        ui.label(
+                            RichText::new(tr("settings-radon"))
+                                .size(self.theme.typography.body)
+                                .color(self.theme.text_primary),
+                        );
+                        ui.label(
+                            RichText::new(tr("settings-radon-hint"))
+                                .size(self.theme.typography.caption)
+                                .color(self.theme.text_secondary),
+                        );
+                    });
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        for (unit, label) in [("pci", "pCi/L"), ("bq", "Bq/m³")] {
+                            let is_selected = self.gui_config.radon_unit == unit;
+                            let (bg, text_color) = if is_selected {
+                                (self.theme.accent, self.theme.text_on_accent)
+                            } else {
+                                (self.theme.bg_secondary, self.theme.text_secondary)
+                            };
+
+                            let btn = egui::Button::new(
+                                RichText::new(label)
+                                    .size(self.theme.typography.caption)
+                                    .color(text_color),
+                            )
+                            .fill(bg)
+                            .corner_radius(egui::CornerRadius::same(self.theme.rounding.sm as u8));
+
+                            if ui.add(btn).clicked() && !is_selected {
+                                self.gui_config.radon_unit = unit.to_string();
+                                config_changed = true;
+                            }
+                        }
+                    });
+                });
+
+                ui.add_space(self.theme.spacing.md);
+
+                // Override device units toggle
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        ui.label(
+                            RichText::new(tr("settings-override-units"))
+                                .size(self.theme.typography.body)
+                                .color(self.theme.text_primary),
+                        );
+                        ui.label(
+                            RichText::new(tr("settings-override-units-hint"))
+                                .size(self.theme.typography.caption)
+                                .color(self.theme.text_secondary),
+                        );
+                    });
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        for (val, text) in [(true, tr("settings-on")), (false, tr("settings-off"))]
+                        {
+                            let is_selected = self.gui_config.override_device_units == val;
+                            let (bg, text_color) = if is_selected {
+                                (self.theme.accent, self.theme.text_on_accent)
+                            } else {
+                                (self.theme.bg_secondary, self.theme.text_secondary)
+                            };
+
+                            let btn = egui::Button::new(
+                                RichText::new(text)
+                                    .size(self.theme.typography.caption)
+                                    .color(text_color),
+                            )
+                            .fill(bg)
+                            .corner_radius(egui::CornerRadius::same(self.theme.rounding.sm as u8));
+
+                            if ui.add(btn).clicked() && !is_selected {
+                                self.gui_config.override_device_units = val;
+                                config_changed = true;
+                            }
+                        }
+                    });
+                });
+
                 ui.add_space(self.theme.spacing.lg);
                 ui.separator();
                 ui.add_space(self.theme.spacing.md);
 
                 // Alert Thresholds section
                 ui.label(
-                    RichText::new("Alert Thresholds")
+                    RichText::new(tr("settings-alert-thresholds"))
                         .size(self.theme.typography.body)
                         .strong()
                         .color(self.theme.text_primary),
@@ -540,12 +766,12 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("CO2 Warning")
+                            RichText::new(tr("settings-co2-warning"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Amber indicator threshold (ppm)")
+                            RichText::new(tr("settings-co2-warning-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_muted),
                         );
@@ -595,12 +821,12 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("CO2 Danger")
+                            RichText::new(tr("settings-co2-danger"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Red indicator threshold (ppm)")
+                            RichText::new(tr("settings-co2-danger-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_muted),
                         );
@@ -653,12 +879,12 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Radon Warning")
+                            RichText::new(tr("settings-radon-warning"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Amber indicator threshold (Bq/m³)")
+                            RichText::new(tr("settings-radon-warning-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_muted),
                         );
@@ -711,12 +937,12 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Radon Danger")
+                            RichText::new(tr("settings-radon-danger"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Red indicator threshold (Bq/m³)")
+                            RichText::new(tr("settings-radon-danger-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_muted),
                         );
@@ -766,13 +992,129 @@ impl AranetApp {
                     });
                 });
 
+                ui.add_space(self.theme.spacing.sm);
+
+                // Radiation Warning Threshold slider
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        ui.label(
+                            RichText::new(tr("settings-radiation-warning"))
+                                .size(self.theme.typography.body)
+                                .color(self.theme.text_primary),
+                        );
+                        ui.label(
+                            RichText::new(tr("settings-radiation-warning-hint"))
+                                .size(self.theme.typography.caption)
+                                .color(self.theme.text_muted),
+                        );
+                    });
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let mut radiation_warning = self.gui_config.radiation_warning_threshold;
+                        // Current value (rightmost)
+                        ui.label(
+                            RichText::new(format!(
+                                "{:.2} µSv/h",
+                                self.gui_config.radiation_warning_threshold
+                            ))
+                            .size(self.theme.typography.caption)
+                            .color(self.theme.warning),
+                        );
+                        ui.add_space(self.theme.spacing.sm);
+                        // Max label
+                        ui.label(
+                            RichText::new("1.0")
+                                .size(self.theme.typography.caption)
+                                .color(self.theme.text_muted),
+                        );
+                        let slider = egui::Slider::new(&mut radiation_warning, 0.1..=1.0)
+                            .show_value(false)
+                            .step_by(0.05);
+                        if ui.add(slider).changed() {
+                            self.gui_config.radiation_warning_threshold = radiation_warning;
+                            // Ensure warning < alarm (maintain at least 0.1 µSv/h gap)
+                            if self.gui_config.radiation_warning_threshold
+                                >= self.gui_config.radiation_alarm_threshold
+                            {
+                                self.gui_config.radiation_alarm_threshold =
+                                    (self.gui_config.radiation_warning_threshold + 0.1).min(5.0);
+                            }
+                            config_changed = true;
+                        }
+                        // Min label (leftmost)
+                        ui.label(
+                            RichText::new("0.1")
+                                .size(self.theme.typography.caption)
+                                .color(self.theme.text_muted),
+                        );
+                    });
+                });
+
+                ui.add_space(self.theme.spacing.sm);
+
+                // Radiation Alarm Threshold slider
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        ui.label(
+                            RichText::new(tr("settings-radiation-alarm"))
+                                .size(self.theme.typography.body)
+                                .color(self.theme.text_primary),
+                        );
+                        ui.label(
+                            RichText::new(tr("settings-radiation-alarm-hint"))
+                                .size(self.theme.typography.caption)
+                                .color(self.theme.text_muted),
+                        );
+                    });
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let mut radiation_alarm = self.gui_config.radiation_alarm_threshold;
+                        // Current value (rightmost)
+                        ui.label(
+                            RichText::new(format!(
+                                "{:.2} µSv/h",
+                                self.gui_config.radiation_alarm_threshold
+                            ))
+                            .size(self.theme.typography.caption)
+                            .color(self.theme.danger),
+                        );
+                        ui.add_space(self.theme.spacing.sm);
+                        // Max label
+                        ui.label(
+                            RichText::new("5.0")
+                                .size(self.theme.typography.caption)
+                                .color(self.theme.text_muted),
+                        );
+                        let slider = egui::Slider::new(&mut radiation_alarm, 0.5..=5.0)
+                            .show_value(false)
+                            .step_by(0.1);
+                        if ui.add(slider).changed() {
+                            self.gui_config.radiation_alarm_threshold = radiation_alarm;
+                            // Ensure alarm > warning (maintain at least 0.1 µSv/h gap)
+                            if self.gui_config.radiation_alarm_threshold
+                                <= self.gui_config.radiation_warning_threshold
+                            {
+                                self.gui_config.radiation_warning_threshold =
+                                    (self.gui_config.radiation_alarm_threshold - 0.1).max(0.1);
+                            }
+                            config_changed = true;
+                        }
+                        // Min label (leftmost)
+                        ui.label(
+                            RichText::new("0.5")
+                                .size(self.theme.typography.caption)
+                                .color(self.theme.text_muted),
+                        );
+                    });
+                });
+
                 ui.add_space(self.theme.spacing.lg);
                 ui.separator();
                 ui.add_space(self.theme.spacing.md);
 
                 // Data Export section
                 ui.label(
-                    RichText::new("Data Export")
+                    RichText::new(tr("settings-data-export"))
                         .size(self.theme.typography.body)
                         .strong()
                         .color(self.theme.text_primary),
@@ -783,12 +1125,12 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Default Format")
+                            RichText::new(tr("settings-default-format"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Format used for history exports")
+                            RichText::new(tr("settings-default-format-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_secondary),
                         );
@@ -825,14 +1167,14 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Export Location")
+                            RichText::new(tr("settings-export-location"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         let display_path = if self.gui_config.export_directory.is_empty() {
                             dirs::download_dir()
                                 .map(|p| p.display().to_string())
-                                .unwrap_or_else(|| "Downloads folder".to_string())
+                                .unwrap_or_else(|| tr("settings-downloads-folder"))
                         } else {
                             self.gui_config.export_directory.clone()
                         };
@@ -847,7 +1189,7 @@ impl AranetApp {
                         // Reset to default button (only show if custom path is set)
                         if !self.gui_config.export_directory.is_empty() {
                             let reset_btn = egui::Button::new(
-                                RichText::new("Reset")
+                                RichText::new(tr("settings-reset"))
                                     .size(self.theme.typography.caption)
                                     .color(self.theme.text_secondary),
                             )
@@ -856,7 +1198,7 @@ impl AranetApp {
 
                             if ui
                                 .add(reset_btn)
-                                .on_hover_text("Reset to Downloads folder")
+                                .on_hover_text(tr("settings-reset-export-location"))
                                 .clicked()
                             {
                                 self.gui_config.export_directory = String::new();
@@ -872,7 +1214,7 @@ impl AranetApp {
 
                 // Behavior section
                 ui.label(
-                    RichText::new("Behavior")
+                    RichText::new(tr("settings-behavior"))
                         .size(self.theme.typography.body)
                         .strong()
                         .color(self.theme.text_primary),
@@ -886,19 +1228,20 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Auto-Connect")
+                            RichText::new(tr("settings-auto-connect"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Connect to known devices on startup")
+                            RichText::new(tr("settings-auto-connect-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_secondary),
                         );
                     });
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        for (val, text) in [(true, "On"), (false, "Off")] {
+                        for (val, text) in [(true, tr("settings-on")), (false, tr("settings-off"))]
+                        {
                             let is_selected = behavior_config.auto_connect == val;
                             let (bg, text_color) = if is_selected {
                                 (self.theme.accent, self.theme.text_on_accent)
@@ -930,19 +1273,20 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Auto-Sync History")
+                            RichText::new(tr("settings-auto-sync-history"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Download history when connecting to device")
+                            RichText::new(tr("settings-auto-sync-history-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_secondary),
                         );
                     });
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        for (val, text) in [(true, "On"), (false, "Off")] {
+                        for (val, text) in [(true, tr("settings-on")), (false, tr("settings-off"))]
+                        {
                             let is_selected = behavior_config.auto_sync == val;
                             let (bg, text_color) = if is_selected {
                                 (self.theme.accent, self.theme.text_on_accent)
@@ -973,19 +1317,20 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Remember Devices")
+                            RichText::new(tr("settings-remember-devices"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Save connected devices to database")
+                            RichText::new(tr("settings-remember-devices-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_secondary),
                         );
                     });
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        for (val, text) in [(true, "On"), (false, "Off")] {
+                        for (val, text) in [(true, tr("settings-on")), (false, tr("settings-off"))]
+                        {
                             let is_selected = behavior_config.remember_devices == val;
                             let (bg, text_color) = if is_selected {
                                 (self.theme.accent, self.theme.text_on_accent)
@@ -1016,19 +1361,20 @@ impl AranetApp {
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
                         ui.label(
-                            RichText::new("Load Cached Data")
+                            RichText::new(tr("settings-load-cached-data"))
                                 .size(self.theme.typography.body)
                                 .color(self.theme.text_primary),
                         );
                         ui.label(
-                            RichText::new("Load devices and readings from database on startup")
+                            RichText::new(tr("settings-load-cached-data-hint"))
                                 .size(self.theme.typography.caption)
                                 .color(self.theme.text_secondary),
                         );
                     });
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        for (val, text) in [(true, "On"), (false, "Off")] {
+                        for (val, text) in [(true, tr("settings-on")), (false, tr("settings-off"))]
+                        {
                             let is_selected = behavior_config.load_cache == val;
                             let (bg, text_color) = if is_selected {
                                 (self.theme.accent, self.theme.text_on_accent)
@@ -1060,3 +1406,8 @@ impl AranetApp {
             });
     }
 }
+
+/// Format an RGB color as stored in the GUI config.
+fn hex_color([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
@@ -5,7 +5,7 @@ use eframe::egui::{self, Color32, RichText};
 use crate::gui::app::AranetApp;
 use crate::gui::components;
 use crate::gui::helpers::{format_pressure, format_radon, format_temperature};
-use crate::gui::types::ConnectionState;
+use crate::gui::types::{ConnectionState, RadiationLevel};
 
 impl AranetApp {
     pub(crate) fn render_comparison_panel(&mut self, ui: &mut egui::Ui) {
@@ -120,8 +120,11 @@ impl AranetApp {
 
                                 // Radon (if available)
                                 if let Some(radon) = reading.radon {
-                                    let (value, unit) =
-                                        format_radon(radon, device.settings.as_ref());
+                                    let (value, unit) = format_radon(
+                                        radon,
+                                        self.gui_config.unit_settings(device.settings.as_ref()),
+                                        Some(&self.gui_config.radon_unit),
+                                    );
                                     self.render_comparison_metric(
                                         ui,
                                         "Radon",
@@ -138,14 +141,17 @@ impl AranetApp {
                                         "Radiation",
                                         &format!("{:.2}", rate),
                                         "µSv/h",
-                                        self.theme.radiation_color(rate),
+                                        self.theme.radiation_color(RadiationLevel::from_usv(
+                                            rate,
+                                            &self.gui_config.radiation_thresholds(),
+                                        )),
                                     );
                                 }
 
                                 // Temperature
                                 let (temp_val, temp_unit) = format_temperature(
                                     reading.temperature,
-                                    device.settings.as_ref(),
+                                    self.gui_config.unit_settings(device.settings.as_ref()),
                                     Some(&self.gui_config.temperature_unit),
                                 );
                                 self.render_comparison_metric(
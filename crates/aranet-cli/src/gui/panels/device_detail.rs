@@ -13,7 +13,12 @@ use crate::gui::types::{ConnectionState, DeviceState};
 
 impl AranetApp {
     /// Render the device detail panel.
-    pub(crate) fn render_device_panel(&self, ui: &mut egui::Ui, device: &DeviceState, idx: usize) {
+    pub(crate) fn render_device_panel(
+        &mut self,
+        ui: &mut egui::Ui,
+        device: &DeviceState,
+        idx: usize,
+    ) {
         // Device header
         ui.horizontal(|ui| {
             ui.vertical(|ui| {
@@ -106,6 +111,16 @@ impl AranetApp {
                                 device_id: device.id.clone(),
                             });
                         }
+                        ui.add_space(self.theme.spacing.sm);
+                        if ui
+                            .add(egui::Button::new(
+                                RichText::new("Placement").size(self.theme.typography.body),
+                            ))
+                            .on_hover_text("Find a spot with reliable signal")
+                            .clicked()
+                        {
+                            self.start_placement(device.id.clone());
+                        }
                     }
                     ConnectionState::Connecting => {
                         components::loading_indicator(ui, &self.theme, Some("Connecting..."));
@@ -150,13 +165,7 @@ impl AranetApp {
 
         // Readings content
         if device.reading.is_some() {
-            readings::render_readings(
-                ui,
-                &self.theme,
-                device,
-                &self.gui_config.temperature_unit,
-                &self.gui_config.pressure_unit,
-            );
+            readings::render_readings(ui, &self.theme, device, &self.gui_config);
         } else if device.connection == ConnectionState::Connected {
             components::loading_indicator(ui, &self.theme, Some("Waiting for readings..."));
         } else {
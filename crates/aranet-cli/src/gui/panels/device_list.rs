@@ -11,7 +11,7 @@ use aranet_core::messages::Command;
 use crate::gui::app::AranetApp;
 use crate::gui::components;
 use crate::gui::helpers::{format_radon, format_temperature};
-use crate::gui::types::{ConnectionFilter, ConnectionState, DeviceTypeFilter};
+use crate::gui::types::{ConnectionFilter, ConnectionState, DeviceTypeFilter, RadiationLevel};
 
 impl AranetApp {
     /// Render the device list side panel.
@@ -451,7 +451,10 @@ impl AranetApp {
                                                         // AranetRadon: Show radon
                                                         let (value, unit) = format_radon(
                                                             radon,
-                                                            device.settings.as_ref(),
+                                                            self.gui_config.unit_settings(
+                                                                device.settings.as_ref(),
+                                                            ),
+                                                            Some(&self.gui_config.radon_unit),
                                                         );
                                                         let color = self.theme.radon_color(radon);
                                                         ui.label(
@@ -467,8 +470,12 @@ impl AranetApp {
                                                         reading.radiation_rate
                                                     {
                                                         // AranetRadiation: Show radiation rate
+                                                        let level = RadiationLevel::from_usv(
+                                                            rate,
+                                                            &self.gui_config.radiation_thresholds(),
+                                                        );
                                                         let color =
-                                                            self.theme.radiation_color(rate);
+                                                            self.theme.radiation_color(level);
                                                         ui.label(
                                                             RichText::new(format!(
                                                                 "{:.2} uSv/h",
@@ -483,7 +490,9 @@ impl AranetApp {
                                                         let (temp_val, temp_unit) =
                                                             format_temperature(
                                                                 reading.temperature,
-                                                                device.settings.as_ref(),
+                                                                self.gui_config.unit_settings(
+                                                                    device.settings.as_ref(),
+                                                                ),
                                                                 Some(
                                                                     &self
                                                                         .gui_config
@@ -4,13 +4,13 @@
 //! including time-series charts for CO2, radon, radiation, temperature, and humidity.
 
 use aranet_core::messages::Command;
-use aranet_core::settings::{RadonUnit, TemperatureUnit};
+use aranet_store::ExposureStats;
 use eframe::egui::{self, Color32, RichText};
 use egui_plot::{HLine, Legend, Line, Plot, PlotPoints};
 
 use crate::gui::app::AranetApp;
 use crate::gui::components;
-use crate::gui::helpers::{bq_to_pci, celsius_to_fahrenheit};
+use crate::gui::helpers::{bq_to_pci, celsius_to_fahrenheit, uses_fahrenheit, uses_pci};
 use crate::gui::types::{DeviceState, HistoryFilter};
 
 impl AranetApp {
@@ -111,8 +111,31 @@ impl AranetApp {
                 }
             }
 
-            // Sync status indicator (right-aligned)
+            // Sync status and storage indicators (right-aligned)
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if let Some(summary) = device
+                    .history_info
+                    .as_ref()
+                    .and_then(|info| info.storage_summary())
+                {
+                    let full = device
+                        .history_info
+                        .as_ref()
+                        .and_then(|info| info.fill_ratio())
+                        .is_some_and(|ratio| ratio >= 0.9);
+                    let color = if full {
+                        self.theme.warning
+                    } else {
+                        self.theme.text_muted
+                    };
+                    let summary = format!("Device {}", summary);
+                    ui.label(
+                        RichText::new(summary)
+                            .size(self.theme.typography.caption)
+                            .color(color),
+                    );
+                    ui.separator();
+                }
                 if let Some(last_sync) = device.last_sync {
                     let now = time::OffsetDateTime::now_utc();
                     let duration = now - last_sync;
@@ -302,6 +325,11 @@ impl AranetApp {
         let has_radon = filtered.iter().any(|r| r.radon.is_some());
         let has_radiation = filtered.iter().any(|r| r.radiation_rate.is_some());
 
+        if has_co2 {
+            self.render_exposure_summary(ui, &device.id, &filtered);
+            ui.add_space(self.theme.spacing.md);
+        }
+
         let now_secs = time::OffsetDateTime::now_utc().unix_timestamp() as f64;
         let to_hours_ago = |ts: time::OffsetDateTime| -> f64 {
             let secs = ts.unix_timestamp() as f64;
@@ -358,12 +386,11 @@ impl AranetApp {
             }
 
             if has_radon {
-                // Use device settings for radon unit
-                let use_pci = device
-                    .settings
-                    .as_ref()
-                    .map(|s| s.radon_unit == RadonUnit::PciL)
-                    .unwrap_or(false);
+                // Use device settings for radon unit, unless the app overrides them
+                let use_pci = uses_pci(
+                    self.gui_config.unit_settings(device.settings.as_ref()),
+                    Some(&self.gui_config.radon_unit),
+                );
                 let radon_unit_label = if use_pci { "pCi/L" } else { "Bq/m3" };
                 // Threshold lines (convert if using pCi/L)
                 let thresholds = if use_pci {
@@ -426,12 +453,11 @@ impl AranetApp {
                 );
             }
 
-            // Use device settings for temperature unit
-            let use_fahrenheit = device
-                .settings
-                .as_ref()
-                .map(|s| s.temperature_unit == TemperatureUnit::Fahrenheit)
-                .unwrap_or(false);
+            // Use device settings for temperature unit, unless the app overrides them
+            let use_fahrenheit = uses_fahrenheit(
+                self.gui_config.unit_settings(device.settings.as_ref()),
+                Some(&self.gui_config.temperature_unit),
+            );
             let temp_unit_label = if use_fahrenheit { "F" } else { "C" };
 
             // Toggle for overlay mode
@@ -524,6 +550,67 @@ impl AranetApp {
         });
     }
 
+    /// Show cumulative CO₂ exposure (ppm-hours above 800 and 1000 ppm) for
+    /// the filtered records.
+    fn render_exposure_summary(
+        &self,
+        ui: &mut egui::Ui,
+        device_id: &str,
+        records: &[&aranet_types::HistoryRecord],
+    ) {
+        let days =
+            ExposureStats::from_samples(device_id, records.iter().map(|r| (r.timestamp, r.co2)));
+        if days.is_empty() {
+            return;
+        }
+        let sum = |f: fn(&ExposureStats) -> f64| days.iter().map(f).sum::<f64>();
+        let per_day = sum(|d| d.ppm_hours_above_1000) / days.len() as f64;
+
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new("CO\u{2082} exposure:")
+                    .size(self.theme.typography.caption)
+                    .color(self.theme.text_secondary),
+            );
+            for (threshold, hours, ppm_hours) in [
+                (
+                    800,
+                    sum(|d| d.hours_above_800),
+                    sum(|d| d.ppm_hours_above_800),
+                ),
+                (
+                    1000,
+                    sum(|d| d.hours_above_1000),
+                    sum(|d| d.ppm_hours_above_1000),
+                ),
+            ] {
+                let color = if ppm_hours > 0.0 {
+                    self.theme.warning
+                } else {
+                    self.theme.text_muted
+                };
+                components::status_badge(
+                    ui,
+                    &self.theme,
+                    &format!(
+                        ">{} ppm: {:.1} h, {:.0} ppm\u{b7}h",
+                        threshold, hours, ppm_hours
+                    ),
+                    color,
+                );
+            }
+            ui.label(
+                RichText::new(format!("avg {:.0} ppm\u{b7}h/day above 1000 ppm", per_day))
+                    .size(self.theme.typography.caption)
+                    .color(self.theme.text_muted),
+            )
+            .on_hover_text(
+                "Time-weighted CO\u{2082} above each threshold: one hour at 1200 ppm \
+                 adds 200 ppm\u{b7}h above 1000 ppm",
+            );
+        });
+    }
+
     /// Render a chart section with consistent styling.
     ///
     /// # Arguments
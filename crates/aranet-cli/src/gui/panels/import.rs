@@ -0,0 +1,175 @@
+//! Import of files dropped onto the window.
+//!
+//! Dropping an exported history CSV or device bundle asks the worker for a
+//! preview, which is shown here for confirmation before anything is written
+//! to the local store.
+
+use aranet_core::messages::{Command, ImportFormat};
+use eframe::egui::{self, RichText};
+
+use crate::gui::app::AranetApp;
+use crate::gui::helpers::display_file_name;
+
+/// Format an import time range bound in local time.
+fn format_bound(timestamp: Option<time::OffsetDateTime>) -> String {
+    let Some(timestamp) = timestamp else {
+        return "-".to_string();
+    };
+    let offset = time::UtcOffset::current_local_offset().unwrap_or(time::UtcOffset::UTC);
+    let local = timestamp.to_offset(offset);
+    format!("{} {:02}:{:02}", local.date(), local.hour(), local.minute())
+}
+
+impl AranetApp {
+    /// Request a preview for a file dropped onto the window, and highlight
+    /// the window while one is dragged over it.
+    pub(crate) fn process_dropped_files(&mut self, ctx: &egui::Context) {
+        let (hovering, dropped) = ctx.input(|i| {
+            (
+                !i.raw.hovered_files.is_empty(),
+                i.raw.dropped_files.iter().find_map(|f| f.path.clone()),
+            )
+        });
+
+        if hovering {
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("file_drop_overlay"),
+            ));
+            let rect = ctx.content_rect();
+            painter.rect_filled(rect, 0.0, egui::Color32::from_black_alpha(160));
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop a history CSV or device bundle to import",
+                egui::FontId::proportional(self.theme.typography.heading),
+                egui::Color32::WHITE,
+            );
+        }
+
+        if let Some(path) = dropped {
+            self.send_command(Command::PreviewImport { path });
+        }
+    }
+
+    /// Render the import preview dialog.
+    pub(crate) fn render_import_window(&mut self, ctx: &egui::Context) {
+        let Some((path, preview)) = &self.import_preview else {
+            return;
+        };
+        let file_name = display_file_name(path);
+        let format = match preview.format {
+            ImportFormat::HistoryCsv => "History CSV",
+            ImportFormat::DeviceBundle => "Device bundle",
+        };
+        let devices = if preview.devices.is_empty() {
+            "-".to_string()
+        } else {
+            preview
+                .devices
+                .iter()
+                .map(|id| {
+                    self.devices
+                        .iter()
+                        .find(|d| &d.id == id)
+                        .map_or_else(|| id.clone(), |d| d.display_name().to_string())
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut rows = vec![
+            ("File", file_name),
+            ("Format", format.to_string()),
+            ("Device", devices),
+        ];
+        for (label, count) in [
+            ("Readings", preview.readings),
+            ("History records", preview.history),
+            ("Annotations", preview.annotations),
+            ("Invalid rows", preview.invalid_rows),
+        ] {
+            if count > 0 {
+                rows.push((label, count.to_string()));
+            }
+        }
+        rows.push(("From", format_bound(preview.start)));
+        rows.push(("To", format_bound(preview.end)));
+        let importable = preview.readings + preview.history + preview.annotations > 0;
+
+        let mut open = true;
+        let mut import = false;
+        let mut cancel = ctx.input(|i| i.key_pressed(egui::Key::Escape));
+        egui::Window::new("Import Data")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Grid::new("import_preview")
+                    .num_columns(2)
+                    .spacing([self.theme.spacing.lg, self.theme.spacing.sm])
+                    .show(ui, |ui| {
+                        for (label, value) in &rows {
+                            ui.label(
+                                RichText::new(*label)
+                                    .size(self.theme.typography.body)
+                                    .color(self.theme.text_muted),
+                            );
+                            ui.label(
+                                RichText::new(value)
+                                    .size(self.theme.typography.body)
+                                    .color(self.theme.text_primary),
+                            );
+                            ui.end_row();
+                        }
+                    });
+
+                ui.add_space(self.theme.spacing.md);
+                ui.label(
+                    RichText::new("Rows already in the local store are skipped.")
+                        .size(self.theme.typography.caption)
+                        .color(self.theme.text_muted),
+                );
+                ui.add_space(self.theme.spacing.md);
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            importable,
+                            egui::Button::new(
+                                RichText::new("Import")
+                                    .size(self.theme.typography.body)
+                                    .color(self.theme.text_on_accent),
+                            )
+                            .fill(self.theme.accent),
+                        )
+                        .clicked()
+                    {
+                        import = true;
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(
+                                RichText::new("Cancel")
+                                    .size(self.theme.typography.body)
+                                    .color(self.theme.text_secondary),
+                            )
+                            .fill(self.theme.bg_secondary),
+                        )
+                        .clicked()
+                    {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if import {
+            if let Some((path, _)) = self.import_preview.take() {
+                self.status = format!("Importing {}...", display_file_name(&path));
+                self.send_command(Command::ImportFile { path });
+            }
+        } else if cancel || !open {
+            self.import_preview = None;
+        }
+    }
+}
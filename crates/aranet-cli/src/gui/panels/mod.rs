@@ -10,6 +10,8 @@ mod comparison;
 mod device_detail;
 mod device_list;
 mod history;
+mod import;
+mod placement;
 mod service;
 mod settings;
 
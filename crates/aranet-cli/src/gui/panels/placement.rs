@@ -0,0 +1,178 @@
+//! Sensor placement assistant window.
+//!
+//! Shows a live signal-quality meter and RSSI history for one device while
+//! the user moves it around, from the advertisements the worker's placement
+//! probe reports every second.
+
+use aranet_core::SignalQuality;
+use eframe::egui::{self, RichText};
+use egui_plot::{HLine, Legend, Line, Plot, PlotPoints};
+
+use crate::gui::app::AranetApp;
+use crate::gui::types::PlacementState;
+
+/// RSSI shown as an empty meter.
+const METER_MIN_DBM: f64 = -100.0;
+/// RSSI shown as a full meter.
+const METER_MAX_DBM: f64 = -40.0;
+
+impl AranetApp {
+    /// Render the placement assistant window.
+    pub(crate) fn render_placement_window(&mut self, ctx: &egui::Context) {
+        let Some(placement) = &self.placement else {
+            return;
+        };
+        let device_name = self
+            .devices
+            .iter()
+            .find(|d| d.id == placement.device_id)
+            .map(|d| d.display_name().to_string())
+            .unwrap_or_else(|| placement.device_id.clone());
+        let rssi = placement.recent_rssi();
+        let reception_rate = placement.reception_rate();
+        let quality = rssi.quality();
+        let history: Vec<[f64; 2]> = placement
+            .points
+            .iter()
+            .filter_map(|p| p.rssi.map(|rssi| [p.elapsed_secs, f64::from(rssi)]))
+            .collect();
+        let error = placement.error.clone();
+
+        let quality_color = match quality {
+            Some(SignalQuality::Excellent | SignalQuality::Good) => self.theme.success,
+            Some(SignalQuality::Fair) => self.theme.warning,
+            Some(SignalQuality::Poor) => self.theme.danger,
+            None => self.theme.text_muted,
+        };
+
+        let mut open = true;
+        egui::Window::new(format!("Placement Assistant: {}", device_name))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(480.0)
+            .default_height(420.0)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(
+                        "Move the sensor around and watch the meter. Keep it disconnected: \
+                         most sensors stop advertising while connected.",
+                    )
+                    .size(self.theme.typography.caption)
+                    .color(self.theme.text_muted),
+                );
+                ui.add_space(self.theme.spacing.md);
+
+                // Live meter
+                let (headline, detail) = match (rssi.mean(), quality) {
+                    (Some(mean), Some(quality)) => (
+                        format!("{:.0} dBm", mean),
+                        quality.description().to_string(),
+                    ),
+                    _ => (
+                        "No signal".to_string(),
+                        format!(
+                            "No advertisements received in the last {} seconds",
+                            PlacementState::WINDOW_LEN
+                        ),
+                    ),
+                };
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(headline)
+                            .size(self.theme.typography.heading)
+                            .strong()
+                            .color(quality_color),
+                    );
+                    ui.add_space(self.theme.spacing.md);
+                    ui.label(
+                        RichText::new(detail)
+                            .size(self.theme.typography.body)
+                            .color(self.theme.text_secondary),
+                    );
+                });
+                let fill = rssi
+                    .mean()
+                    .map(|mean| (mean - METER_MIN_DBM) / (METER_MAX_DBM - METER_MIN_DBM))
+                    .unwrap_or(0.0)
+                    .clamp(0.0, 1.0) as f32;
+                ui.add(egui::ProgressBar::new(fill).fill(quality_color));
+                ui.add_space(self.theme.spacing.sm);
+
+                ui.horizontal(|ui| {
+                    let stat = |ui: &mut egui::Ui, label: &str, value: String| {
+                        ui.label(
+                            RichText::new(format!("{}: {}", label, value))
+                                .size(self.theme.typography.caption)
+                                .color(self.theme.text_secondary),
+                        );
+                        ui.add_space(self.theme.spacing.md);
+                    };
+                    stat(
+                        ui,
+                        "Variation",
+                        rssi.stddev()
+                            .map_or("-".to_string(), |sd| format!("± {:.1} dB", sd)),
+                    );
+                    stat(
+                        ui,
+                        "Weakest",
+                        rssi.min()
+                            .map_or("-".to_string(), |min| format!("{} dBm", min)),
+                    );
+                    stat(
+                        ui,
+                        "Advertisements",
+                        reception_rate.map_or("-".to_string(), |rate| format!("{:.0}/min", rate)),
+                    );
+                });
+
+                if let Some(error) = &error {
+                    ui.add_space(self.theme.spacing.sm);
+                    ui.label(
+                        RichText::new(format!("Stopped: {}", error))
+                            .size(self.theme.typography.caption)
+                            .color(self.theme.danger),
+                    );
+                }
+
+                ui.add_space(self.theme.spacing.md);
+
+                // RSSI history
+                Plot::new("placement_rssi")
+                    .height(200.0)
+                    .show_axes(true)
+                    .show_grid(true)
+                    .allow_scroll(false)
+                    .allow_drag(false)
+                    .allow_zoom(false)
+                    .include_y(METER_MIN_DBM)
+                    .include_y(METER_MAX_DBM)
+                    .x_axis_label("Seconds")
+                    .y_axis_label("dBm")
+                    .legend(Legend::default())
+                    .show(ui, |plot_ui| {
+                        for (label, value, color) in [
+                            ("Good", -75.0, self.theme.success),
+                            ("Poor", -85.0, self.theme.danger),
+                        ] {
+                            plot_ui.hline(
+                                HLine::new(label, value)
+                                    .color(color)
+                                    .style(egui_plot::LineStyle::dashed_dense()),
+                            );
+                        }
+                        plot_ui.line(
+                            Line::new("RSSI", PlotPoints::new(history))
+                                .color(self.theme.accent)
+                                .width(2.0),
+                        );
+                    });
+            });
+
+        if !open {
+            self.stop_placement();
+        }
+    }
+}
@@ -8,7 +8,7 @@ use eframe::egui::{self, RichText};
 
 use crate::gui::app::AranetApp;
 use crate::gui::components;
-use crate::gui::helpers::INTERVAL_OPTIONS;
+use crate::gui::helpers::{INTERVAL_OPTIONS, REFRESH_INTERVAL_OPTIONS};
 use crate::gui::theme::Theme;
 use crate::gui::types::DeviceState;
 
@@ -212,6 +212,54 @@ impl AranetApp {
                 ui.add_space(self.theme.spacing.lg);
             }
 
+            // Auto-Refresh Section (GUI-only, stored in the config file)
+            components::section_header(ui, &self.theme, "Auto-Refresh");
+
+            egui::Frame::new()
+                .fill(self.theme.bg_card)
+                .inner_margin(egui::Margin::same(self.theme.spacing.lg as i8))
+                .corner_radius(egui::CornerRadius::same(self.theme.rounding.md as u8))
+                .stroke(egui::Stroke::new(1.0, self.theme.border_subtle))
+                .show(ui, |ui| {
+                    let mut refresh = self.gui_config.device_refresh(&device.id);
+                    let mut changed = false;
+
+                    ui.horizontal(|ui| {
+                        for &(secs, label) in REFRESH_INTERVAL_OPTIONS {
+                            let is_selected = refresh.interval_secs == secs;
+                            if self.settings_choice_button(ui, label, is_selected) && !is_selected
+                            {
+                                refresh.interval_secs = secs;
+                                changed = true;
+                            }
+                        }
+                    });
+
+                    ui.add_space(self.theme.spacing.sm);
+                    if ui
+                        .checkbox(&mut refresh.paused, "Pause auto-refresh for this device")
+                        .changed()
+                    {
+                        changed = true;
+                    }
+
+                    ui.add_space(self.theme.spacing.sm);
+                    ui.label(
+                        RichText::new(
+                            "How often the app polls this device. Auto follows the measurement interval.",
+                        )
+                        .size(self.theme.typography.caption)
+                        .color(self.theme.text_muted),
+                    );
+
+                    if changed {
+                        self.gui_config.set_device_refresh(&device.id, refresh);
+                        self.save_gui_config();
+                    }
+                });
+
+            ui.add_space(self.theme.spacing.lg);
+
             // Device Configuration Section
             if let Some(settings) = &device.settings {
                 components::section_header(ui, &self.theme, "Device Configuration");
@@ -453,6 +501,25 @@ impl AranetApp {
         }
     }
 
+    /// Render a selectable choice button; returns true if it was clicked.
+    fn settings_choice_button(&self, ui: &mut egui::Ui, label: &str, is_selected: bool) -> bool {
+        let (bg, text_color) = if is_selected {
+            (self.theme.accent, self.theme.text_on_accent)
+        } else {
+            (self.theme.bg_secondary, self.theme.text_secondary)
+        };
+
+        let btn = egui::Button::new(
+            RichText::new(label)
+                .size(self.theme.typography.caption)
+                .color(text_color),
+        )
+        .fill(bg)
+        .corner_radius(egui::CornerRadius::same(self.theme.rounding.sm as u8));
+
+        ui.add(btn).clicked()
+    }
+
     fn render_settings_row_static(ui: &mut egui::Ui, theme: &Theme, label: &str, value: &str) {
         ui.label(
             RichText::new(label)
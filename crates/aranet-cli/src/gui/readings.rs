@@ -2,6 +2,7 @@
 //!
 //! This module provides rendering for current sensor readings with styled cards.
 
+use aranet_types::CurrentReading;
 use eframe::egui::{self, RichText};
 
 use super::components;
@@ -10,18 +11,14 @@ use super::theme::Theme;
 use super::types::{
     Co2Level, DeviceState, RadiationLevel, RadonLevel, Trend, calculate_radon_averages,
 };
+use crate::config::GuiConfig;
 
 /// Render sensor readings with styled cards.
 ///
 /// Displays the current readings from a device including CO2, radon, radiation,
 /// temperature, humidity, pressure, and battery levels with appropriate color coding.
-pub fn render_readings(
-    ui: &mut egui::Ui,
-    theme: &Theme,
-    device: &DeviceState,
-    temperature_unit: &str,
-    pressure_unit: &str,
-) {
+/// Units and radiation thresholds come from `config`.
+pub fn render_readings(ui: &mut egui::Ui, theme: &Theme, device: &DeviceState, config: &GuiConfig) {
     let reading = match device.reading.as_ref() {
         Some(r) => r,
         None => return,
@@ -45,13 +42,20 @@ pub fn render_readings(
 
         // Radon with color-coded card (only for AranetRadon)
         if let Some(radon) = reading.radon {
-            render_radon_card(ui, theme, device, radon);
+            render_radon_card(ui, theme, device, config, reading, radon);
             ui.add_space(theme.spacing.lg);
         }
 
         // Radiation with color-coded card (only for AranetRadiation)
         if let Some(rate) = reading.radiation_rate {
-            render_radiation_card(ui, theme, rate, reading.radiation_total.map(|t| t as f32));
+            let level = RadiationLevel::from_usv(rate, &config.radiation_thresholds());
+            render_radiation_card(
+                ui,
+                theme,
+                rate,
+                level,
+                reading.radiation_total.map(|t| t as f32),
+            );
             ui.add_space(theme.spacing.lg);
         }
 
@@ -62,8 +66,8 @@ pub fn render_readings(
             // Temperature (use device settings for unit, fall back to app preference)
             let (temp_value, temp_unit) = format_temperature(
                 reading.temperature,
-                device.settings.as_ref(),
-                Some(temperature_unit),
+                config.unit_settings(device.settings.as_ref()),
+                Some(&config.temperature_unit),
             );
             components::metric_card(
                 ui,
@@ -89,7 +93,7 @@ pub fn render_readings(
             // Pressure (if available)
             if reading.pressure > 0.0 {
                 let (pressure_value, pressure_unit_str) =
-                    format_pressure(reading.pressure, pressure_unit);
+                    format_pressure(reading.pressure, &config.pressure_unit);
                 components::metric_card(
                     ui,
                     theme,
@@ -178,6 +182,15 @@ fn render_co2_card(ui: &mut egui::Ui, theme: &Theme, device: &DeviceState, co2:
                 ui.add_space(theme.spacing.md);
                 components::co2_gauge(ui, theme, co2);
 
+                if let Some(advice) = device.co2_advice().filter(|a| a.needs_action()) {
+                    ui.add_space(theme.spacing.sm);
+                    ui.label(
+                        RichText::new(advice.to_string())
+                            .color(theme.caution)
+                            .size(theme.typography.caption),
+                    );
+                }
+
                 // Session statistics (if we have any readings tracked)
                 if device.session_stats.co2_count > 0 {
                     ui.add_space(theme.spacing.md);
@@ -232,11 +245,20 @@ fn render_co2_card(ui: &mut egui::Ui, theme: &Theme, device: &DeviceState, co2:
 }
 
 /// Render radon reading card.
-fn render_radon_card(ui: &mut egui::Ui, theme: &Theme, device: &DeviceState, radon: u32) {
+fn render_radon_card(
+    ui: &mut egui::Ui,
+    theme: &Theme,
+    device: &DeviceState,
+    config: &GuiConfig,
+    reading: &CurrentReading,
+    radon: u32,
+) {
     let level = RadonLevel::from_bq(radon);
     let color = theme.radon_color(radon);
     let bg_color = theme.radon_bg_color(radon);
-    let (radon_value, radon_unit) = format_radon(radon, device.settings.as_ref());
+    let settings = config.unit_settings(device.settings.as_ref());
+    let radon_unit_preference = Some(config.radon_unit.as_str());
+    let (radon_value, radon_unit) = format_radon(radon, settings, radon_unit_preference);
 
     egui::Frame::new()
         .fill(bg_color)
@@ -330,68 +352,55 @@ fn render_radon_card(ui: &mut egui::Ui, theme: &Theme, device: &DeviceState, rad
                     });
                 }
 
-                // Historical radon averages (24h, 7d, 30d) if history is available
-                if !device.history.is_empty() {
-                    let (day_avg, week_avg, month_avg) = calculate_radon_averages(&device.history);
-                    if day_avg.is_some() || week_avg.is_some() || month_avg.is_some() {
-                        ui.add_space(theme.spacing.sm);
-                        ui.horizontal(|ui| {
+                // Radon averages (24h, 7d, 30d), device-reported or from history
+                let (day_avg, week_avg, month_avg) =
+                    calculate_radon_averages(reading, &device.history);
+                if day_avg.is_some() || week_avg.is_some() || month_avg.is_some() {
+                    ui.add_space(theme.spacing.md);
+                    ui.separator();
+                    ui.add_space(theme.spacing.sm);
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            RichText::new("Averages:")
+                                .color(theme.text_muted)
+                                .size(theme.typography.caption),
+                        );
+                        for (label, avg) in [("24h", day_avg), ("7d", week_avg), ("30d", month_avg)]
+                        {
+                            let Some(avg) = avg else {
+                                continue;
+                            };
+                            let (value, unit) = format_radon(avg, settings, radon_unit_preference);
+                            ui.add_space(theme.spacing.md);
                             ui.label(
-                                RichText::new("Averages:")
+                                RichText::new(format!("{}:", label))
                                     .color(theme.text_muted)
                                     .size(theme.typography.caption),
                             );
-                            ui.add_space(theme.spacing.sm);
-                            if let Some(avg) = day_avg {
-                                ui.label(
-                                    RichText::new("24h:")
-                                        .color(theme.text_muted)
-                                        .size(theme.typography.caption),
-                                );
-                                ui.label(
-                                    RichText::new(format!("{}", avg))
-                                        .color(theme.radon_color(avg))
-                                        .size(theme.typography.caption),
-                                );
-                                ui.add_space(theme.spacing.sm);
-                            }
-                            if let Some(avg) = week_avg {
-                                ui.label(
-                                    RichText::new("7d:")
-                                        .color(theme.text_muted)
-                                        .size(theme.typography.caption),
-                                );
-                                ui.label(
-                                    RichText::new(format!("{}", avg))
-                                        .color(theme.radon_color(avg))
-                                        .size(theme.typography.caption),
-                                );
-                                ui.add_space(theme.spacing.sm);
-                            }
-                            if let Some(avg) = month_avg {
-                                ui.label(
-                                    RichText::new("30d:")
-                                        .color(theme.text_muted)
-                                        .size(theme.typography.caption),
-                                );
-                                ui.label(
-                                    RichText::new(format!("{}", avg))
-                                        .color(theme.radon_color(avg))
-                                        .size(theme.typography.caption),
-                                );
-                            }
-                        });
-                    }
+                            ui.label(
+                                RichText::new(format!("{} {}", value, unit))
+                                    .color(theme.radon_color(avg))
+                                    .size(theme.typography.caption)
+                                    .strong(),
+                            )
+                            .on_hover_text(RadonLevel::from_bq(avg).status_text());
+                        }
+                    });
                 }
             });
         });
 }
 
 /// Render radiation reading card.
-fn render_radiation_card(ui: &mut egui::Ui, theme: &Theme, rate: f32, total: Option<f32>) {
-    let level = RadiationLevel::from_usv(rate);
-    let color = theme.radiation_color(rate);
-    let bg_color = theme.radiation_bg_color(rate);
+fn render_radiation_card(
+    ui: &mut egui::Ui,
+    theme: &Theme,
+    rate: f32,
+    level: RadiationLevel,
+    total: Option<f32>,
+) {
+    let color = theme.radiation_color(level);
+    let bg_color = theme.radiation_bg_color(level);
 
     egui::Frame::new()
         .fill(bg_color)
@@ -5,80 +5,74 @@
 
 use eframe::egui::{Color32, CornerRadius, Margin, Shadow, Stroke, Style, Visuals};
 
+use crate::config::{GuiConfig, parse_hex_color};
+
+use super::types::RadiationLevel;
+
 /// Theme mode for the application.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ThemeMode {
     #[default]
     Dark,
     Light,
+    /// Follow the operating system's appearance, including changes while
+    /// the app is running.
+    FollowSystem,
 }
 
 impl ThemeMode {
-    /// Toggle between light and dark mode.
-    pub fn toggle(&mut self) {
-        *self = match self {
-            ThemeMode::Dark => ThemeMode::Light,
-            ThemeMode::Light => ThemeMode::Dark,
-        };
+    /// Parse the `theme` value from the GUI config ("dark", "light" or
+    /// "system"), falling back to dark.
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            "light" => ThemeMode::Light,
+            "system" => ThemeMode::FollowSystem,
+            _ => ThemeMode::Dark,
+        }
+    }
+
+    /// The value stored in the GUI config for this mode.
+    pub fn as_config_str(&self) -> &'static str {
+        match self {
+            ThemeMode::Dark => "dark",
+            ThemeMode::Light => "light",
+            ThemeMode::FollowSystem => "system",
+        }
     }
 
-    /// Get the icon/label for the current theme.
-    pub fn icon(&self) -> &'static str {
+    /// Resolve to the concrete dark or light mode to render with.
+    pub fn resolve(&self) -> ThemeMode {
         match self {
-            ThemeMode::Dark => "Light",
-            ThemeMode::Light => "Dark",
+            ThemeMode::FollowSystem => detect_system_theme(),
+            mode => *mode,
         }
     }
+
+    /// Toggle between light and dark mode.
+    ///
+    /// Following the system switches to the opposite of its current
+    /// appearance.
+    pub fn toggle(&mut self) {
+        *self = match self.resolve() {
+            ThemeMode::Light => ThemeMode::Dark,
+            _ => ThemeMode::Light,
+        };
+    }
 }
 
 /// Detect the system's current appearance (dark or light mode).
 ///
-/// On macOS, this queries the system's effective appearance setting.
-/// On other platforms, this returns Dark as a fallback.
-#[cfg(target_os = "macos")]
+/// Returns Dark when the platform doesn't report a preference.
 pub fn detect_system_theme() -> ThemeMode {
-    use objc2_app_kit::NSApplication;
-    use objc2_foundation::MainThreadMarker;
-
-    // We need to be on the main thread to access NSApp
-    let Some(mtm) = MainThreadMarker::new() else {
-        tracing::warn!("Cannot detect system theme: not on main thread");
-        return ThemeMode::Dark;
-    };
-
-    let app = NSApplication::sharedApplication(mtm);
-
-    // Get the effective appearance name
-    // NSApp.effectiveAppearance.name contains the resolved appearance
-    // Common values: "NSAppearanceNameAqua" (light), "NSAppearanceNameDarkAqua" (dark)
-    let appearance = app.effectiveAppearance();
-    // SAFETY: We're on the main thread (checked above), and the appearance object
-    // is valid for the duration of this call. The name property returns a string
-    // that describes the appearance.
-    // SAFETY: We're on the main thread (checked above via MainThreadMarker),
-    // and the appearance object is valid for the duration of this call.
-    #[allow(unsafe_code)]
-    let name = unsafe { appearance.name() };
-    let name_str = name.to_string();
-
-    // Check if the appearance name contains "Dark"
-    if name_str.contains("Dark") {
-        tracing::debug!("System theme detected: Dark ({})", name_str);
-        ThemeMode::Dark
-    } else {
-        tracing::debug!("System theme detected: Light ({})", name_str);
-        ThemeMode::Light
+    match dark_light::detect() {
+        dark_light::Mode::Light => ThemeMode::Light,
+        dark_light::Mode::Dark | dark_light::Mode::Default => ThemeMode::Dark,
     }
 }
 
-/// Detect the system's current appearance (dark or light mode).
-///
-/// On non-macOS platforms, this returns Dark as a fallback.
-#[cfg(not(target_os = "macos"))]
-pub fn detect_system_theme() -> ThemeMode {
-    // On other platforms, we don't have a reliable way to detect system theme
-    // Default to dark mode
-    ThemeMode::Dark
+/// Parse a "#RRGGBB" config color.
+fn parse_color(color: &str) -> Option<Color32> {
+    parse_hex_color(color).map(|[r, g, b]| Color32::from_rgb(r, g, b))
 }
 
 /// Opacity levels for consistent transparency across the UI.
@@ -377,12 +371,40 @@ impl Theme {
 
     /// Get theme for the specified mode.
     pub fn for_mode(mode: ThemeMode) -> Self {
-        match mode {
-            ThemeMode::Dark => Self::dark(),
+        match mode.resolve() {
             ThemeMode::Light => Self::light(),
+            _ => Self::dark(),
         }
     }
 
+    /// Get theme for the specified mode with the layout and custom colors
+    /// from the GUI config.
+    pub fn from_config(mode: ThemeMode, config: &GuiConfig) -> Self {
+        let mut theme = Self::for_mode_with_options(mode, config.compact_mode);
+        if let Some(accent) = config.accent_color.as_deref().and_then(parse_color) {
+            theme.accent = accent;
+            theme.focus_ring = accent;
+            // Lighter on dark backgrounds, darker on light ones
+            theme.accent_hover = if theme.is_dark {
+                accent.lerp_to_gamma(Color32::WHITE, 0.2)
+            } else {
+                accent.lerp_to_gamma(Color32::BLACK, 0.2)
+            };
+        }
+        let palette = &config.chart_palette;
+        for (color, custom) in [
+            (&mut theme.chart_co2, &palette.co2),
+            (&mut theme.chart_temperature, &palette.temperature),
+            (&mut theme.chart_humidity, &palette.humidity),
+            (&mut theme.chart_pressure, &palette.pressure),
+        ] {
+            if let Some(custom) = custom.as_deref().and_then(parse_color) {
+                *color = custom;
+            }
+        }
+        theme
+    }
+
     /// Get theme for the specified mode with optional compact layout.
     pub fn for_mode_with_options(mode: ThemeMode, compact: bool) -> Self {
         let mut theme = Self::for_mode(mode);
@@ -451,20 +473,18 @@ impl Theme {
         self.tint_bg(self.radon_color(bq), self.opacity.light)
     }
 
-    /// Get radiation color based on level (µSv/h).
-    pub fn radiation_color(&self, usv: f32) -> Color32 {
-        if usv < 0.3 {
-            self.success
-        } else if usv < 1.0 {
-            self.warning
-        } else {
-            self.danger
+    /// Get radiation color for a dose rate level.
+    pub fn radiation_color(&self, level: RadiationLevel) -> Color32 {
+        match level {
+            RadiationLevel::Normal => self.success,
+            RadiationLevel::Elevated => self.warning,
+            RadiationLevel::High => self.danger,
         }
     }
 
     /// Get radiation background color (subtle for card backgrounds).
-    pub fn radiation_bg_color(&self, usv: f32) -> Color32 {
-        self.tint_bg(self.radiation_color(usv), self.opacity.light)
+    pub fn radiation_bg_color(&self, level: RadiationLevel) -> Color32 {
+        self.tint_bg(self.radiation_color(level), self.opacity.light)
     }
 
     // -------------------------------------------------------------------------
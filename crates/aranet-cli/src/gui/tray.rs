@@ -286,25 +286,24 @@ impl TrayManager {
 
         for event in tray_events {
             match event {
+                // Only respond to button Up (click completed), not Down
+                // Otherwise we get two toggles per click
                 TrayIconEvent::Click {
                     button,
                     button_state,
                     ..
-                } => {
-                    // Only respond to button Up (click completed), not Down
-                    // Otherwise we get two toggles per click
-                    if button == tray_icon::MouseButton::Left
-                        && button_state == tray_icon::MouseButtonState::Up
-                    {
-                        debug!("Tray: Left click - toggle window");
-                        commands.push(TrayCommand::ToggleWindow);
-                    }
+                } if button == tray_icon::MouseButton::Left
+                    && button_state == tray_icon::MouseButtonState::Up =>
+                {
+                    debug!("Tray: Left click - toggle window");
+                    commands.push(TrayCommand::ToggleWindow);
                 }
-                TrayIconEvent::DoubleClick { button, .. } => {
-                    if button == tray_icon::MouseButton::Left {
-                        debug!("Tray: Double click - show window");
-                        commands.push(TrayCommand::ShowWindow);
-                    }
+                TrayIconEvent::DoubleClick {
+                    button: tray_icon::MouseButton::Left,
+                    ..
+                } => {
+                    debug!("Tray: Double click - show window");
+                    commands.push(TrayCommand::ShowWindow);
                 }
                 _ => {}
             }
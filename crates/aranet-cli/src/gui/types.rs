@@ -1,10 +1,14 @@
 //! Type definitions for the GUI module.
 
+use std::collections::VecDeque;
 use std::time::Instant;
 
-use aranet_core::messages::{CachedDevice, SignalQuality};
+use aranet_core::advice::{self, Advice};
+use aranet_core::messages::{CachedAlert, CachedDevice, SignalQuality};
+use aranet_core::readings::ExtendedReading;
 use aranet_core::scan::DiscoveredDevice;
 use aranet_core::settings::DeviceSettings;
+use aranet_core::{HistoryInfo, PLACEMENT_SAMPLE_INTERVAL, RadiationThresholds, RssiSamples};
 use aranet_types::{CurrentReading, DeviceType, HistoryRecord};
 
 /// Connection state for a device.
@@ -225,8 +229,13 @@ impl SessionStats {
     }
 }
 
-/// Calculate radon averages from history records (24h, 7d, 30d).
+/// Radon averages (24h, 7d, 30d) to display for a device.
+///
+/// Prefers the averages the AranetRn+ reports itself, which cover the full
+/// period even when little history has been downloaded, and falls back to
+/// averages computed from history records for any the device did not report.
 pub fn calculate_radon_averages(
+    reading: &CurrentReading,
     history: &[HistoryRecord],
 ) -> (Option<u32>, Option<u32>, Option<u32>) {
     use time::OffsetDateTime;
@@ -280,7 +289,11 @@ pub fn calculate_radon_averages(
         None
     };
 
-    (day_avg, week_avg, month_avg)
+    (
+        reading.radon_avg_24h.or(day_avg),
+        reading.radon_avg_7d.or(week_avg),
+        reading.radon_avg_30d.or(month_avg),
+    )
 }
 
 /// Trend direction for a value.
@@ -336,6 +349,8 @@ pub struct DeviceState {
     pub reading_from_cache: bool,
     /// When history was last synced from the device.
     pub last_sync: Option<time::OffsetDateTime>,
+    /// History memory usage, read when history is synced.
+    pub history_info: Option<HistoryInfo>,
     /// Background polling interval in seconds (None if not polling).
     pub background_polling: Option<u64>,
     /// Session statistics for this device (min/max/avg values).
@@ -362,6 +377,7 @@ impl DeviceState {
             settings: None,
             reading_from_cache: false,
             last_sync: None,
+            history_info: None,
             background_polling: None,
             session_stats: SessionStats::default(),
             connected_at: None,
@@ -385,6 +401,7 @@ impl DeviceState {
             settings: None,
             reading_from_cache: cached.reading.is_some(), // Mark as cached if reading exists
             last_sync: cached.last_sync,
+            history_info: None,
             background_polling: None,
             session_stats: SessionStats::default(),
             connected_at: None,
@@ -441,6 +458,31 @@ impl DeviceState {
         Some(Trend::from_delta(current as f64, previous as f64, 20.0))
     }
 
+    /// Ventilation advice from the current CO2 reading and its slope over the
+    /// last half hour of history and readings.
+    pub fn co2_advice(&self) -> Option<Advice> {
+        let reading = self.reading?;
+        let now = time::OffsetDateTime::now_utc();
+        let since = now - time::Duration::minutes(30);
+        let samples = self
+            .history
+            .iter()
+            .map(|r| (r.timestamp, r.co2))
+            .chain(
+                [Some(reading), self.previous_reading]
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|r| r.captured_at.map(|at| (at, r.co2))),
+            )
+            .filter(|&(at, _)| at >= since);
+
+        ExtendedReading {
+            reading,
+            radiation_duration: None,
+        }
+        .advice(advice::co2_slope(samples))
+    }
+
     /// Get temperature trend.
     pub fn temperature_trend(&self) -> Option<Trend> {
         let current = self.reading.as_ref()?.temperature;
@@ -515,15 +557,17 @@ impl RadonLevel {
 }
 
 /// Radiation level for color coding.
-/// Based on typical background radiation levels:
+/// Boundaries come from the configured dose rate thresholds
+/// (`gui.radiation_warning_threshold` and `gui.radiation_alarm_threshold`),
+/// which default to typical background radiation levels:
 /// - < 0.3 µSv/h: Normal background (green)
 /// - 0.3-1.0 µSv/h: Elevated (yellow)
-/// - > 1.0 µSv/h: High, investigate (red)
+/// - >= 1.0 µSv/h: High, investigate (red)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RadiationLevel {
-    Normal,   // < 0.3 µSv/h (green)
-    Elevated, // 0.3-1.0 µSv/h (yellow)
-    High,     // > 1.0 µSv/h (red)
+    Normal,   // below warning threshold (green)
+    Elevated, // warning to alarm threshold (yellow)
+    High,     // at or above alarm threshold (red)
 }
 
 /// Alert severity level for categorizing alerts.
@@ -538,6 +582,13 @@ pub enum AlertSeverity {
 }
 
 impl AlertSeverity {
+    /// All severities, for filter menus.
+    pub const ALL: [AlertSeverity; 3] = [
+        AlertSeverity::Info,
+        AlertSeverity::Warning,
+        AlertSeverity::Critical,
+    ];
+
     /// Get display label for the severity.
     pub fn label(&self) -> &'static str {
         match self {
@@ -555,6 +606,20 @@ impl AlertSeverity {
             AlertSeverity::Critical => "[!!]",
         }
     }
+
+    /// Name stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertSeverity::Info => "info",
+            AlertSeverity::Warning => "warning",
+            AlertSeverity::Critical => "critical",
+        }
+    }
+
+    /// Parse a severity name stored in the database.
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == name)
+    }
 }
 
 /// Type of measurement that triggered an alert.
@@ -568,9 +633,20 @@ pub enum AlertType {
     Radiation,
     /// Battery low alert
     BatteryLow,
+    /// Device stopped responding
+    Offline,
 }
 
 impl AlertType {
+    /// All alert types, for filter menus.
+    pub const ALL: [AlertType; 5] = [
+        AlertType::Co2,
+        AlertType::Radon,
+        AlertType::Radiation,
+        AlertType::BatteryLow,
+        AlertType::Offline,
+    ];
+
     /// Get display label for the alert type.
     pub fn label(&self) -> &'static str {
         match self {
@@ -578,32 +654,78 @@ impl AlertType {
             AlertType::Radon => "Radon",
             AlertType::Radiation => "Radiation",
             AlertType::BatteryLow => "Battery",
+            AlertType::Offline => "Offline",
+        }
+    }
+
+    /// Alert kind stored in the database.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AlertType::Co2 => aranet_store::ALERT_CO2,
+            AlertType::Radon => aranet_store::ALERT_RADON,
+            AlertType::Radiation => aranet_store::ALERT_RADIATION,
+            AlertType::BatteryLow => aranet_store::ALERT_BATTERY_LOW,
+            AlertType::Offline => aranet_store::ALERT_OFFLINE,
         }
     }
+
+    /// Parse an alert kind stored in the database.
+    pub fn from_kind(kind: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.kind() == kind)
+    }
 }
 
 /// An entry in the alert history log.
 #[derive(Debug, Clone)]
 pub struct AlertEntry {
+    /// Store row ID, once the alert has been persisted.
+    pub id: Option<i64>,
     /// When the alert was triggered.
-    pub timestamp: std::time::Instant,
+    pub timestamp: time::OffsetDateTime,
     /// Human-readable timestamp for display.
     pub time_str: String,
-    /// Device name that triggered the alert.
+    /// Device that triggered the alert.
+    pub device_id: String,
+    /// Device name at the time the alert was raised.
     pub device_name: String,
     /// Type of alert (CO2, Radon, etc.).
     pub alert_type: AlertType,
     /// Severity of the alert.
     pub severity: AlertSeverity,
-    /// The measurement value that triggered the alert.
-    pub value: String,
+    /// The measurement value that triggered the alert, if any.
+    pub value: Option<f64>,
     /// Alert message/description.
     pub message: String,
+    /// Whether the user has acknowledged the alert.
+    pub acknowledged: bool,
 }
 
 impl AlertEntry {
+    fn new(
+        device_id: &str,
+        device_name: &str,
+        alert_type: AlertType,
+        severity: AlertSeverity,
+        value: Option<f64>,
+        message: String,
+    ) -> Self {
+        let timestamp = time::OffsetDateTime::now_utc();
+        Self {
+            id: None,
+            timestamp,
+            time_str: format_alert_time(timestamp),
+            device_id: device_id.to_string(),
+            device_name: device_name.to_string(),
+            alert_type,
+            severity,
+            value,
+            message,
+            acknowledged: false,
+        }
+    }
+
     /// Create a new CO2 alert entry.
-    pub fn co2(device_name: &str, co2_ppm: u16, level: Co2Level) -> Self {
+    pub fn co2(device_id: &str, device_name: &str, co2_ppm: u16, level: Co2Level) -> Self {
         let (severity, message) = match level {
             Co2Level::Good => (
                 AlertSeverity::Info,
@@ -629,19 +751,18 @@ impl AlertEntry {
             ),
         };
 
-        Self {
-            timestamp: std::time::Instant::now(),
-            time_str: format_current_time(),
-            device_name: device_name.to_string(),
-            alert_type: AlertType::Co2,
+        Self::new(
+            device_id,
+            device_name,
+            AlertType::Co2,
             severity,
-            value: format!("{} ppm", co2_ppm),
+            Some(f64::from(co2_ppm)),
             message,
-        }
+        )
     }
 
     /// Create a new radon alert entry.
-    pub fn radon(device_name: &str, bq: u32, level: RadonLevel) -> Self {
+    pub fn radon(device_id: &str, device_name: &str, bq: u32, level: RadonLevel) -> Self {
         let (severity, message) = match level {
             RadonLevel::Low => (
                 AlertSeverity::Info,
@@ -657,34 +778,109 @@ impl AlertEntry {
             ),
         };
 
-        Self {
-            timestamp: std::time::Instant::now(),
-            time_str: format_current_time(),
-            device_name: device_name.to_string(),
-            alert_type: AlertType::Radon,
+        Self::new(
+            device_id,
+            device_name,
+            AlertType::Radon,
             severity,
-            value: format!("{} Bq/m³", bq),
+            Some(f64::from(bq)),
             message,
-        }
+        )
     }
 
-    /// Create a battery low alert entry.
-    pub fn battery_low(device_name: &str, battery_pct: u8) -> Self {
-        Self {
-            timestamp: std::time::Instant::now(),
-            time_str: format_current_time(),
-            device_name: device_name.to_string(),
-            alert_type: AlertType::BatteryLow,
-            severity: AlertSeverity::Warning,
-            value: format!("{}%", battery_pct),
-            message: format!("Battery low ({}%) - consider charging", battery_pct),
-        }
+    /// Create a new radiation dose rate alert entry.
+    pub fn radiation(device_id: &str, device_name: &str, usv: f32, level: RadiationLevel) -> Self {
+        let (severity, message) = match level {
+            RadiationLevel::Normal => (
+                AlertSeverity::Info,
+                format!("Radiation dose rate returned to normal ({:.2} µSv/h)", usv),
+            ),
+            RadiationLevel::Elevated => (
+                AlertSeverity::Warning,
+                format!("Radiation dose rate elevated ({:.2} µSv/h)", usv),
+            ),
+            RadiationLevel::High => (
+                AlertSeverity::Critical,
+                format!(
+                    "Radiation dose rate high ({:.2} µSv/h) - investigate the source",
+                    usv
+                ),
+            ),
+        };
+
+        Self::new(
+            device_id,
+            device_name,
+            AlertType::Radiation,
+            severity,
+            Some(f64::from(usv)),
+            message,
+        )
+    }
+
+    /// Create a battery low alert entry (critical below 10%).
+    pub fn battery_low(device_id: &str, device_name: &str, battery_pct: u8) -> Self {
+        let (severity, message) = if battery_pct < 10 {
+            (
+                AlertSeverity::Critical,
+                format!("Battery critically low ({}%) - replace soon", battery_pct),
+            )
+        } else {
+            (
+                AlertSeverity::Warning,
+                format!("Battery low ({}%) - consider charging", battery_pct),
+            )
+        };
+
+        Self::new(
+            device_id,
+            device_name,
+            AlertType::BatteryLow,
+            severity,
+            Some(f64::from(battery_pct)),
+            message,
+        )
+    }
+
+    /// Create an alert for a device that could not be reached.
+    pub fn offline(device_id: &str, device_name: &str, error: &str) -> Self {
+        Self::new(
+            device_id,
+            device_name,
+            AlertType::Offline,
+            AlertSeverity::Warning,
+            None,
+            format!("Device offline: {}", error),
+        )
+    }
+
+    /// Rebuild an entry from an alert loaded from the store.
+    ///
+    /// Returns `None` for alert kinds or severities this version does not know.
+    pub fn from_cached(alert: CachedAlert) -> Option<Self> {
+        Some(Self {
+            id: Some(alert.id),
+            timestamp: alert.triggered_at,
+            time_str: format_alert_time(alert.triggered_at),
+            device_name: alert.device_id.clone(),
+            device_id: alert.device_id,
+            alert_type: AlertType::from_kind(&alert.kind)?,
+            severity: AlertSeverity::parse(&alert.severity)?,
+            value: alert.value,
+            message: alert.message,
+            acknowledged: alert.acknowledged,
+        })
+    }
+
+    /// Time elapsed since the alert was raised.
+    pub fn age(&self) -> std::time::Duration {
+        let elapsed = time::OffsetDateTime::now_utc() - self.timestamp;
+        elapsed.try_into().unwrap_or_default()
     }
 
     /// Get the age of this alert as a human-readable string.
     pub fn age_str(&self) -> String {
-        let elapsed = self.timestamp.elapsed();
-        let secs = elapsed.as_secs();
+        let secs = self.age().as_secs();
         if secs < 60 {
             "just now".to_string()
         } else if secs < 3600 {
@@ -697,21 +893,142 @@ impl AlertEntry {
     }
 }
 
-/// Format the current local time as HH:MM:SS.
-fn format_current_time() -> String {
-    let now = time::OffsetDateTime::now_local().unwrap_or_else(|_| time::OffsetDateTime::now_utc());
-    format!("{:02}:{:02}:{:02}", now.hour(), now.minute(), now.second())
+/// Format an alert time in local time: HH:MM:SS for today, with the date otherwise.
+fn format_alert_time(timestamp: time::OffsetDateTime) -> String {
+    let offset = time::UtcOffset::current_local_offset().unwrap_or(time::UtcOffset::UTC);
+    let local = timestamp.to_offset(offset);
+    let today = time::OffsetDateTime::now_utc().to_offset(offset).date();
+    if local.date() == today {
+        format!(
+            "{:02}:{:02}:{:02}",
+            local.hour(),
+            local.minute(),
+            local.second()
+        )
+    } else {
+        format!("{} {:02}:{:02}", local.date(), local.hour(), local.minute())
+    }
+}
+
+/// One second of placement assistant measurements.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacementPoint {
+    /// Seconds since the placement assistant started.
+    pub elapsed_secs: f64,
+    /// Last advertisement RSSI in dBm, if any advertisement was received.
+    pub rssi: Option<i16>,
+    /// Number of advertisements received.
+    pub advertisements: u32,
+}
+
+/// State of the sensor placement assistant for one device.
+#[derive(Debug, Clone)]
+pub struct PlacementState {
+    /// Device being placed.
+    pub device_id: String,
+    /// When the assistant started.
+    pub started_at: Instant,
+    /// Measurements, oldest first, capped at [`Self::HISTORY_LEN`].
+    pub points: VecDeque<PlacementPoint>,
+    /// Error that stopped the probe, if any.
+    pub error: Option<String>,
+}
+
+impl PlacementState {
+    /// Number of measurements kept for the history plot (five minutes).
+    pub const HISTORY_LEN: usize = 300;
+    /// Number of recent measurements the live meter is computed from.
+    pub const WINDOW_LEN: usize = 10;
+
+    /// Start a placement session for a device.
+    pub fn new(device_id: String) -> Self {
+        Self {
+            device_id,
+            started_at: Instant::now(),
+            points: VecDeque::with_capacity(Self::HISTORY_LEN),
+            error: None,
+        }
+    }
+
+    /// Record a measurement.
+    pub fn push(&mut self, rssi: Option<i16>, advertisements: u32) {
+        if self.points.len() == Self::HISTORY_LEN {
+            self.points.pop_front();
+        }
+        self.points.push_back(PlacementPoint {
+            elapsed_secs: self.started_at.elapsed().as_secs_f64(),
+            rssi,
+            advertisements,
+        });
+    }
+
+    /// The measurements the live meter is computed from.
+    fn window(&self) -> impl Iterator<Item = &PlacementPoint> {
+        self.points
+            .iter()
+            .skip(self.points.len().saturating_sub(Self::WINDOW_LEN))
+    }
+
+    /// RSSI statistics over the recent window.
+    pub fn recent_rssi(&self) -> RssiSamples {
+        RssiSamples::new(
+            PLACEMENT_SAMPLE_INTERVAL,
+            self.window().filter_map(|p| p.rssi).collect(),
+        )
+    }
+
+    /// Advertisements received per minute over the recent window.
+    ///
+    /// `None` until the first measurement arrives.
+    pub fn reception_rate(&self) -> Option<f64> {
+        let count = self.window().count();
+        if count == 0 {
+            return None;
+        }
+        let received: u32 = self.window().map(|p| p.advertisements).sum();
+        let window_secs = count as f64 * PLACEMENT_SAMPLE_INTERVAL.as_secs_f64();
+        Some(f64::from(received) * 60.0 / window_secs)
+    }
+}
+
+/// Filters applied to the alert history pane.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AlertFilter {
+    /// Only show alerts of this type.
+    pub alert_type: Option<AlertType>,
+    /// Only show alerts of this severity.
+    pub severity: Option<AlertSeverity>,
+    /// Only show alerts from this device.
+    pub device_id: Option<String>,
+    /// Hide acknowledged alerts.
+    pub unacknowledged_only: bool,
+}
+
+impl AlertFilter {
+    /// Whether an alert passes all active filters.
+    pub fn matches(&self, alert: &AlertEntry) -> bool {
+        self.alert_type.is_none_or(|t| t == alert.alert_type)
+            && self.severity.is_none_or(|s| s == alert.severity)
+            && self
+                .device_id
+                .as_ref()
+                .is_none_or(|id| *id == alert.device_id)
+            && !(self.unacknowledged_only && alert.acknowledged)
+    }
+
+    /// Whether any filter is active.
+    pub fn is_active(&self) -> bool {
+        *self != Self::default()
+    }
 }
 
 impl RadiationLevel {
-    /// Evaluate radiation level from µSv/h.
-    pub fn from_usv(usv: f32) -> Self {
-        if usv < 0.3 {
-            Self::Normal
-        } else if usv < 1.0 {
-            Self::Elevated
-        } else {
-            Self::High
+    /// Evaluate radiation level from µSv/h against the given thresholds.
+    pub fn from_usv(usv: f32, thresholds: &RadiationThresholds) -> Self {
+        match thresholds.evaluate(usv) {
+            aranet_core::RadiationLevel::Normal => Self::Normal,
+            aranet_core::RadiationLevel::Elevated => Self::Elevated,
+            aranet_core::RadiationLevel::High => Self::High,
         }
     }
 
@@ -724,3 +1041,58 @@ impl RadiationLevel {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placement_recent_window() {
+        let mut placement = PlacementState::new("AA:BB".to_string());
+        placement.push(Some(-90), 0);
+        for rssi in [-60, -64, -68, -72] {
+            placement.push(Some(rssi), 3);
+        }
+        for _ in 0..PlacementState::WINDOW_LEN - 4 {
+            placement.push(None, 0);
+        }
+
+        // The first measurement has left the window
+        let rssi = placement.recent_rssi();
+        assert_eq!(rssi.samples, vec![-60, -64, -68, -72]);
+        assert_eq!(rssi.mean(), Some(-66.0));
+        assert_eq!(rssi.min(), Some(-72));
+        assert_eq!(placement.reception_rate(), Some(72.0));
+    }
+
+    #[test]
+    fn test_calculate_radon_averages() {
+        let now = time::OffsetDateTime::now_utc();
+        let record = |hours_ago: i64, radon: u32| {
+            HistoryRecord::builder()
+                .timestamp(now - time::Duration::hours(hours_ago))
+                .radon(radon)
+                .build()
+        };
+        let history = vec![record(1, 100), record(48, 200), record(24 * 20, 300)];
+
+        // Computed from history when the device reports nothing
+        let reading = CurrentReading::builder().radon(100).build();
+        assert_eq!(
+            calculate_radon_averages(&reading, &history),
+            (Some(100), Some(150), Some(200))
+        );
+        assert_eq!(calculate_radon_averages(&reading, &[]), (None, None, None));
+
+        // Device-reported averages win over history
+        let reading = CurrentReading::builder()
+            .radon(100)
+            .radon_avg_24h(90)
+            .radon_avg_30d(120)
+            .build();
+        assert_eq!(
+            calculate_radon_averages(&reading, &history),
+            (Some(90), Some(150), Some(120))
+        );
+    }
+}
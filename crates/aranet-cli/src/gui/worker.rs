@@ -16,15 +16,15 @@ async fn disconnect_quietly(device: &aranet_core::Device) {
 }
 
 use aranet_core::messages::{
-    CachedDevice, Command, ErrorContext, SensorEvent, ServiceDeviceStats, ServiceMonitoredDevice,
-    SignalQuality,
+    CachedAlert, CachedDevice, Command, CommandThrottle, ErrorContext, ImportFormat, ImportPreview,
+    SensorEvent, ServiceDeviceStats, ServiceMonitoredDevice, SignalQuality,
 };
 use aranet_core::retry::{RetryConfig, with_retry};
 use aranet_core::scan::scan_with_options;
 use aranet_core::service_client::ServiceClient;
-use aranet_core::settings::{DeviceSettings, MeasurementInterval};
-use aranet_core::{BluetoothRange, Device, ScanOptions};
-use aranet_store::Store;
+use aranet_core::settings::{DeviceSettings, MeasurementInterval, SettingChange};
+use aranet_core::{BluetoothRange, Device, PlacementProbe, ScanOptions};
+use aranet_store::{AlertQuery, DeviceBundle, Store, StoredAlert};
 use aranet_types::{CurrentReading, DeviceType};
 use futures::future::join_all;
 use tokio::sync::mpsc;
@@ -38,9 +38,82 @@ const CONNECT_READ_TIMEOUT: Duration = Duration::from_secs(30);
 /// Maximum time to wait for history download (5 minutes for large histories).
 const HISTORY_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
 
+/// How often to poll the service for the status of a delegated history sync.
+const SERVICE_SYNC_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Default URL for the aranet-service.
 const DEFAULT_SERVICE_URL: &str = "http://localhost:8080";
 
+/// Convert a stored alert into the message type sent to the UI.
+fn cached_alert(alert: StoredAlert) -> CachedAlert {
+    CachedAlert {
+        id: alert.id,
+        device_id: alert.device_id,
+        kind: alert.kind,
+        severity: alert.severity,
+        value: alert.value,
+        message: alert.message,
+        triggered_at: alert.triggered_at,
+        acknowledged: alert.acknowledged,
+    }
+}
+
+/// The contents of a file dropped for import.
+enum ImportData {
+    HistoryCsv(String),
+    /// The raw JSON, which the store reads itself on import, and the
+    /// parsed bundle for the preview.
+    DeviceBundle(String, Box<DeviceBundle>),
+}
+
+impl ImportData {
+    /// Read `path`, detecting a JSON device bundle by its `.json` extension
+    /// or leading `{` and treating anything else as history CSV.
+    fn read(path: &std::path::Path) -> Result<Self, String> {
+        let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+            || data.trim_start().starts_with('{');
+        if is_json {
+            let bundle =
+                serde_json::from_str(&data).map_err(|e| format!("Not a device bundle: {}", e))?;
+            return Ok(Self::DeviceBundle(data, Box::new(bundle)));
+        }
+        Ok(Self::HistoryCsv(data))
+    }
+
+    fn preview(&self) -> ImportPreview {
+        let (format, preview) = match self {
+            Self::HistoryCsv(data) => (
+                ImportFormat::HistoryCsv,
+                aranet_store::ImportPreview::history_csv(data),
+            ),
+            Self::DeviceBundle(_, bundle) => (
+                ImportFormat::DeviceBundle,
+                aranet_store::ImportPreview::device_bundle(bundle),
+            ),
+        };
+        ImportPreview {
+            format,
+            devices: preview.devices,
+            readings: preview.readings,
+            history: preview.history,
+            annotations: preview.annotations,
+            invalid_rows: preview.invalid,
+            start: preview.start,
+            end: preview.end,
+        }
+    }
+}
+
+/// Wait until a throttled command is due.
+async fn sleep_until_due(due: Option<std::time::Instant>) {
+    if let Some(due) = due {
+        tokio::time::sleep_until(due.into()).await;
+    }
+}
+
 /// Retry configuration for BLE operations.
 fn default_retry_config() -> RetryConfig {
     RetryConfig {
@@ -172,6 +245,10 @@ pub struct SensorWorker {
     polling_tasks: HashMap<String, PollingTask>,
     /// Circuit breaker for service calls.
     service_circuit_breaker: CircuitBreaker,
+    /// Collapses duplicate refreshes and rapid reconnects.
+    throttle: CommandThrottle,
+    /// Cancels the running placement probe, if any.
+    placement_probe: Option<CancellationToken>,
 }
 
 impl SensorWorker {
@@ -229,6 +306,8 @@ impl SensorWorker {
             cancel_token: CancellationToken::new(),
             polling_tasks: HashMap::new(),
             service_circuit_breaker: CircuitBreaker::new(),
+            throttle: CommandThrottle::default(),
+            placement_probe: None,
         }
     }
 
@@ -249,6 +328,20 @@ impl SensorWorker {
         self.store.as_ref()
     }
 
+    /// Record a settings write in the store's settings history.
+    fn save_setting_change(&mut self, device_id: &str, change: SettingChange) {
+        if let Some(store) = self.get_store()
+            && let Err(e) = aranet_service::collector::record_setting_changes(
+                store,
+                device_id,
+                &[change],
+                time::OffsetDateTime::now_utc(),
+            )
+        {
+            warn!(error = %e, "Failed to save settings history");
+        }
+    }
+
     /// Get a mutable reference to the store.
     fn get_store_mut(&mut self) -> Option<&mut Store> {
         if self.store.is_none() {
@@ -277,11 +370,19 @@ impl SensorWorker {
     pub async fn run(mut self) {
         info!("GUI SensorWorker started");
         loop {
+            let next_due = self.throttle.next_due();
             tokio::select! {
                 cmd = self.command_rx.recv() => {
                     match cmd {
                         Some(Command::Shutdown) | None => break,
-                        Some(cmd) => self.handle_command(cmd).await,
+                        Some(cmd) => self.dispatch(cmd).await,
+                    }
+                }
+                _ = sleep_until_due(next_due), if next_due.is_some() => {
+                    for cmd in self.throttle.take_due() {
+                        debug!(?cmd, "Running throttled command");
+                        self.handle_command(cmd.clone()).await;
+                        self.throttle.finish(&cmd);
                     }
                 }
             }
@@ -296,6 +397,16 @@ impl SensorWorker {
         info!("GUI SensorWorker stopped");
     }
 
+    /// Run a command unless the throttle holds it back as a duplicate.
+    async fn dispatch(&mut self, cmd: Command) {
+        if !self.throttle.admit(&cmd) {
+            debug!(?cmd, "Deferring throttled command");
+            return;
+        }
+        self.handle_command(cmd.clone()).await;
+        self.throttle.finish(&cmd);
+    }
+
     async fn handle_command(&mut self, cmd: Command) {
         match cmd {
             Command::LoadCachedData => self.handle_load_cached_data().await,
@@ -348,6 +459,28 @@ impl SensorWorker {
             Command::StopBackgroundPolling { device_id } => {
                 self.handle_stop_background_polling(&device_id).await;
             }
+            Command::StartPassiveMonitoring | Command::StopPassiveMonitoring => {
+                warn!("Passive monitoring is not supported in the GUI");
+            }
+            Command::StartPlacementProbe { device_id } => {
+                self.handle_start_placement_probe(device_id);
+            }
+            Command::StopPlacementProbe => self.handle_stop_placement_probe(),
+            Command::LoadAlerts { limit } => self.handle_load_alerts(limit).await,
+            Command::RecordAlert {
+                device_id,
+                kind,
+                severity,
+                value,
+                message,
+            } => {
+                self.handle_record_alert(&device_id, &kind, &severity, value, &message)
+                    .await;
+            }
+            Command::AcknowledgeAlerts { ids } => self.handle_acknowledge_alerts(&ids),
+            Command::ClearAlerts { ids } => self.handle_clear_alerts(&ids),
+            Command::PreviewImport { path } => self.handle_preview_import(path).await,
+            Command::ImportFile { path } => self.handle_import_file(path).await,
             Command::Shutdown => {} // Handled in run() loop
             Command::InstallSystemService { user_level } => {
                 self.handle_install_system_service(user_level).await;
@@ -494,6 +627,133 @@ impl SensorWorker {
         }
     }
 
+    /// Load the most recent persisted alerts and send them to the UI.
+    async fn handle_load_alerts(&mut self, limit: u32) {
+        let Some(store) = self.get_store() else {
+            return;
+        };
+
+        match store.query_alerts(&AlertQuery::new().limit(limit)) {
+            Ok(alerts) => {
+                info!(count = alerts.len(), "Loaded alert history from store");
+                let alerts = alerts.into_iter().map(cached_alert).collect();
+                self.send_event(SensorEvent::AlertsLoaded { alerts }).await;
+            }
+            Err(e) => warn!(error = %e, "Failed to load alert history"),
+        }
+    }
+
+    /// Persist an alert and report its store ID back to the UI.
+    async fn handle_record_alert(
+        &mut self,
+        device_id: &str,
+        kind: &str,
+        severity: &str,
+        value: Option<f64>,
+        message: &str,
+    ) {
+        let Some(store) = self.get_store() else {
+            return;
+        };
+
+        let now = time::OffsetDateTime::now_utc();
+        match store.insert_alert(device_id, kind, severity, value, message, now) {
+            Ok(alert) => {
+                self.send_event(SensorEvent::AlertRecorded {
+                    alert: cached_alert(alert),
+                })
+                .await;
+            }
+            Err(e) => warn!(device_id, error = %e, "Failed to save alert"),
+        }
+    }
+
+    fn handle_acknowledge_alerts(&mut self, ids: &[i64]) {
+        let Some(store) = self.get_store() else {
+            return;
+        };
+        for &id in ids {
+            if let Err(e) = store.acknowledge_alert(id) {
+                warn!(id, error = %e, "Failed to acknowledge alert");
+            }
+        }
+    }
+
+    fn handle_clear_alerts(&mut self, ids: &[i64]) {
+        let Some(store) = self.get_store() else {
+            return;
+        };
+        for &id in ids {
+            if let Err(e) = store.delete_alert(id) {
+                warn!(id, error = %e, "Failed to delete alert");
+            }
+        }
+    }
+
+    async fn handle_preview_import(&mut self, path: PathBuf) {
+        let preview = ImportData::read(&path).map(|data| data.preview());
+        let event = match preview {
+            Ok(preview)
+                if preview.readings + preview.history + preview.annotations == 0
+                    && preview.invalid_rows == 0 =>
+            {
+                SensorEvent::ImportFailed {
+                    path,
+                    error: "No readings or history records found".to_string(),
+                }
+            }
+            Ok(preview) => SensorEvent::ImportPreviewReady { path, preview },
+            Err(error) => SensorEvent::ImportFailed { path, error },
+        };
+        self.send_event(event).await;
+    }
+
+    /// Import a previewed file, then reload cached devices and history so
+    /// the imported data shows up.
+    async fn handle_import_file(&mut self, path: PathBuf) {
+        let result = ImportData::read(&path).and_then(|data| {
+            let store = self
+                .get_store()
+                .ok_or_else(|| "Failed to open the local store".to_string())?;
+            match data {
+                ImportData::HistoryCsv(data) => store
+                    .import_history_csv(&data)
+                    .map(|result| (result.imported, result.skipped))
+                    .map_err(|e| e.to_string()),
+                ImportData::DeviceBundle(data, bundle) => {
+                    let total =
+                        bundle.readings.len() + bundle.history.len() + bundle.annotations.len();
+                    store
+                        .import_device_bundle(data.as_bytes())
+                        .map(|counts| {
+                            let imported =
+                                (counts.readings + counts.history + counts.annotations) as usize;
+                            (imported, total.saturating_sub(imported))
+                        })
+                        .map_err(|e| e.to_string())
+                }
+            }
+        });
+
+        match result {
+            Ok((imported, skipped)) => {
+                info!(path = %path.display(), imported, skipped, "Imported file into store");
+                self.send_event(SensorEvent::ImportCompleted {
+                    path,
+                    imported,
+                    skipped,
+                })
+                .await;
+                self.handle_load_cached_data().await;
+            }
+            Err(error) => {
+                warn!(path = %path.display(), %error, "Import failed");
+                self.send_event(SensorEvent::ImportFailed { path, error })
+                    .await;
+            }
+        }
+    }
+
     async fn handle_scan(&mut self, duration: Duration) {
         self.send_event(SensorEvent::ScanStarted).await;
 
@@ -763,6 +1023,10 @@ impl SensorWorker {
     async fn handle_sync_history(&mut self, device_id: &str) {
         use aranet_core::history::HistoryOptions;
 
+        if self.sync_history_via_service(device_id).await {
+            return;
+        }
+
         info!(device_id, "Syncing history from device");
 
         let cancel_token = self.cancel_token.clone();
@@ -823,6 +1087,11 @@ impl SensorWorker {
         };
 
         let total_on_device = history_info.total_readings;
+        self.send_event(SensorEvent::HistoryInfoLoaded {
+            device_id: device_id.to_string(),
+            info: history_info,
+        })
+        .await;
 
         // Calculate start index for incremental sync
         let start_index = {
@@ -994,6 +1263,111 @@ impl SensorWorker {
         self.load_and_send_history(device_id).await;
     }
 
+    /// Sync history through aranet-service if it monitors the device.
+    ///
+    /// The service downloads the history into the shared database, so the GUI
+    /// does not open a second BLE connection that competes with its
+    /// collector. Returns `false` if the service is unreachable or does not
+    /// monitor the device, in which case the caller syncs directly.
+    async fn sync_history_via_service(&mut self, device_id: &str) -> bool {
+        let Some(client) = self.service_client.clone() else {
+            return false;
+        };
+        if !self.service_circuit_breaker.should_allow() {
+            return false;
+        }
+
+        let mut job = match client.sync_device_history(device_id).await {
+            Ok(job) => job,
+            Err(e) => {
+                if matches!(
+                    e,
+                    aranet_core::service_client::ServiceClientError::NotReachable { .. }
+                ) {
+                    self.service_circuit_breaker.record_failure();
+                }
+                debug!(device_id, error = %e, "Service cannot sync history, syncing directly");
+                return false;
+            }
+        };
+        info!(
+            device_id,
+            job_id = job.id,
+            "History sync delegated to service"
+        );
+
+        self.send_event(SensorEvent::HistorySyncStarted {
+            device_id: device_id.to_string(),
+            total_records: job.total_records,
+        })
+        .await;
+
+        let cancel_token = self.cancel_token.clone();
+        while !job.status.is_finished() {
+            tokio::select! {
+                _ = tokio::time::sleep(SERVICE_SYNC_POLL_INTERVAL) => {}
+                _ = cancel_token.cancelled() => {
+                    // The service finishes the job; we only stop waiting for it
+                    self.send_event(SensorEvent::OperationCancelled {
+                        operation: format!("History sync for {}", device_id),
+                    })
+                    .await;
+                    return true;
+                }
+            }
+
+            job = match client.sync_job(job.id).await {
+                Ok(job) => job,
+                Err(e) => {
+                    let message = Self::format_service_error(&e);
+                    self.send_event(SensorEvent::HistorySyncError {
+                        device_id: device_id.to_string(),
+                        error: message.clone(),
+                        context: Some(ErrorContext::transient(
+                            message,
+                            "Check that aranet-service is still running.",
+                        )),
+                    })
+                    .await;
+                    return true;
+                }
+            };
+
+            if let Some(total) = job.total_records {
+                self.send_event(SensorEvent::HistorySyncProgress {
+                    device_id: device_id.to_string(),
+                    downloaded: (job.progress * f32::from(total)) as usize,
+                    total: usize::from(total),
+                })
+                .await;
+            }
+        }
+
+        if job.status == aranet_core::service_client::SyncJobStatus::Failed {
+            let message = job
+                .error
+                .unwrap_or_else(|| "History sync failed".to_string());
+            self.send_event(SensorEvent::HistorySyncError {
+                device_id: device_id.to_string(),
+                error: message.clone(),
+                context: Some(ErrorContext::transient(
+                    message,
+                    "The service could not reach the device. Try again later.",
+                )),
+            })
+            .await;
+            return true;
+        }
+
+        self.send_event(SensorEvent::HistorySynced {
+            device_id: device_id.to_string(),
+            count: job.downloaded,
+        })
+        .await;
+        self.load_and_send_history(device_id).await;
+        true
+    }
+
     /// Connect to device and read data with automatic retry on transient failures.
     async fn connect_and_read_with_retry(
         &self,
@@ -1117,6 +1491,7 @@ impl SensorWorker {
         }
 
         disconnect_quietly(&device).await;
+        self.save_setting_change(device_id, SettingChange::Interval(interval));
 
         info!("Measurement interval set successfully for {}", device_id);
         self.send_event(SensorEvent::IntervalChanged {
@@ -1168,6 +1543,7 @@ impl SensorWorker {
         }
 
         disconnect_quietly(&device).await;
+        self.save_setting_change(device_id, SettingChange::BluetoothRange(range));
 
         info!("Bluetooth range set successfully for {}", device_id);
         self.send_event(SensorEvent::BluetoothRangeChanged {
@@ -1210,6 +1586,7 @@ impl SensorWorker {
         }
 
         disconnect_quietly(&device).await;
+        self.save_setting_change(device_id, SettingChange::SmartHome(enabled));
 
         info!("Smart Home set successfully for {}", device_id);
         self.send_event(SensorEvent::SmartHomeChanged {
@@ -1219,6 +1596,46 @@ impl SensorWorker {
         .await;
     }
 
+    // -------------------------------------------------------------------------
+    // Placement Assistant Methods
+    // -------------------------------------------------------------------------
+
+    /// Start the placement assistant's probe for a device, replacing any running one.
+    fn handle_start_placement_probe(&mut self, device_id: String) {
+        self.handle_stop_placement_probe();
+
+        let cancel_token = CancellationToken::new();
+        let mut samples = PlacementProbe::new(device_id.clone()).start(cancel_token.clone());
+        self.placement_probe = Some(cancel_token);
+
+        let event_tx = self.event_tx.clone();
+        tokio::spawn(async move {
+            while let Some(result) = samples.recv().await {
+                let event = match result {
+                    Ok(sample) => SensorEvent::PlacementSample {
+                        device_id: device_id.clone(),
+                        rssi: sample.rssi,
+                        advertisements: sample.advertisements,
+                    },
+                    Err(e) => SensorEvent::PlacementError {
+                        device_id: device_id.clone(),
+                        error: e.to_string(),
+                    },
+                };
+                if event_tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Stop the placement assistant's probe.
+    fn handle_stop_placement_probe(&mut self) {
+        if let Some(cancel_token) = self.placement_probe.take() {
+            cancel_token.cancel();
+        }
+    }
+
     // -------------------------------------------------------------------------
     // Background Polling Methods
     // -------------------------------------------------------------------------
@@ -2301,4 +2718,117 @@ mod tests {
 
         drop(command_tx);
     }
+
+    #[tokio::test]
+    async fn alerts_round_trip_through_store() {
+        let (_command_tx, command_rx) = mpsc::channel(1);
+        let (event_tx, mut event_rx) = mpsc::channel(4);
+        let store_path = test_store_path("alerts");
+        let mut worker = SensorWorker::new(command_rx, event_tx, store_path.clone());
+
+        worker
+            .handle_record_alert("dev", "offline", "warning", None, "Device offline: timeout")
+            .await;
+        let id = match event_rx.recv().await.unwrap() {
+            SensorEvent::AlertRecorded { alert } => {
+                assert_eq!(alert.device_id, "dev");
+                assert!(!alert.acknowledged);
+                alert.id
+            }
+            other => panic!("unexpected event: {other:?}"),
+        };
+
+        worker.handle_acknowledge_alerts(&[id]);
+        worker.handle_load_alerts(10).await;
+        match event_rx.recv().await.unwrap() {
+            SensorEvent::AlertsLoaded { alerts } => {
+                assert_eq!(alerts.len(), 1);
+                assert!(alerts[0].acknowledged);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+
+        worker.handle_clear_alerts(&[id]);
+        worker.handle_load_alerts(10).await;
+        match event_rx.recv().await.unwrap() {
+            SensorEvent::AlertsLoaded { alerts } => assert!(alerts.is_empty()),
+            other => panic!("unexpected event: {other:?}"),
+        }
+
+        drop(worker);
+        let _ = std::fs::remove_file(store_path);
+    }
+
+    const IMPORT_CSV: &str = "timestamp,device_id,co2,temperature,pressure,humidity,radon
+2024-01-15T10:30:00Z,kitchen,800,22.5,1013.25,45,
+2024-01-15T11:30:00Z,kitchen,850,23.0,1014.00,48,
+";
+
+    #[test]
+    fn import_data_detects_format() {
+        let csv = test_store_path("import").with_extension("csv");
+        std::fs::write(&csv, IMPORT_CSV).unwrap();
+        let preview = ImportData::read(&csv).unwrap().preview();
+        assert_eq!(preview.format, ImportFormat::HistoryCsv);
+        assert_eq!(preview.devices, vec!["kitchen".to_string()]);
+        assert_eq!((preview.history, preview.invalid_rows), (2, 0));
+
+        // JSON is recognised by content as well as by extension
+        let json = test_store_path("import").with_extension("txt");
+        std::fs::write(&json, "{\"device\": 1}").unwrap();
+        let error = ImportData::read(&json).err().unwrap();
+        assert!(error.starts_with("Not a device bundle"));
+
+        let _ = std::fs::remove_file(csv);
+        let _ = std::fs::remove_file(json);
+    }
+
+    #[tokio::test]
+    async fn import_previews_then_imports_file() {
+        let (_command_tx, command_rx) = mpsc::channel(1);
+        let (event_tx, mut event_rx) = mpsc::channel(8);
+        let store_path = test_store_path("import-store");
+        let mut worker = SensorWorker::new(command_rx, event_tx, store_path.clone());
+        let csv = test_store_path("import-file").with_extension("csv");
+
+        std::fs::write(&csv, "timestamp,device_id,co2\n").unwrap();
+        worker.handle_preview_import(csv.clone()).await;
+        match event_rx.recv().await.unwrap() {
+            SensorEvent::ImportFailed { error, .. } => {
+                assert_eq!(error, "No readings or history records found");
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+
+        std::fs::write(&csv, IMPORT_CSV).unwrap();
+        worker.handle_preview_import(csv.clone()).await;
+        match event_rx.recv().await.unwrap() {
+            SensorEvent::ImportPreviewReady { path, preview } => {
+                assert_eq!(path, csv);
+                assert_eq!(preview.history, 2);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+
+        worker.handle_import_file(csv.clone()).await;
+        match event_rx.recv().await.unwrap() {
+            SensorEvent::ImportCompleted {
+                imported, skipped, ..
+            } => assert_eq!((imported, skipped), (2, 0)),
+            other => panic!("unexpected event: {other:?}"),
+        }
+
+        // Importing again skips the records the store already has
+        worker.handle_import_file(csv.clone()).await;
+        let skipped = loop {
+            if let SensorEvent::ImportCompleted { skipped, .. } = event_rx.recv().await.unwrap() {
+                break skipped;
+            }
+        };
+        assert_eq!(skipped, 2);
+
+        drop(worker);
+        let _ = std::fs::remove_file(csv);
+        let _ = std::fs::remove_file(store_path);
+    }
 }
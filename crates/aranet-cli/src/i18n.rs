@@ -0,0 +1,247 @@
+//! Translations for the TUI and GUI.
+//!
+//! User-facing strings are looked up by message ID in [Fluent] resources
+//! embedded from `locales/<code>.ftl`. English is the reference language:
+//! every ID must exist in `en.ftl`, and IDs missing from another language fall
+//! back to English, so partial translations can be shipped as they grow.
+//!
+//! The language comes from the `language` config key, or from the
+//! `LC_ALL`/`LC_MESSAGES`/`LANG` environment variables when that is unset.
+//!
+//! [Fluent]: https://projectfluent.org/
+
+use std::sync::{Arc, RwLock};
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use tracing::{debug, warn};
+use unic_langid::LanguageIdentifier;
+
+/// A supported interface language.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    Spanish,
+}
+
+impl Language {
+    /// All supported languages, in the order they are offered in settings.
+    pub const ALL: [Language; 3] = [Language::English, Language::German, Language::Spanish];
+
+    /// ISO 639-1 code, as used in the config file.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::Spanish => "es",
+        }
+    }
+
+    /// The language's name in that language, for the language picker.
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+            Language::Spanish => "Español",
+        }
+    }
+
+    /// Parse a language code or locale such as `de`, `de-AT` or `de_DE.UTF-8`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let primary = code
+            .split(['-', '_', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Self::ALL.into_iter().find(|lang| lang.code() == primary)
+    }
+
+    /// The language requested by the environment, if it is supported.
+    ///
+    /// Follows the POSIX precedence of `LC_ALL`, `LC_MESSAGES` and `LANG`.
+    pub fn from_env() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_code(&value))
+    }
+
+    /// Resolve the configured language: an explicit code wins, otherwise the
+    /// environment, otherwise English.
+    pub fn resolve(configured: Option<&str>) -> Self {
+        match configured.filter(|code| !code.is_empty() && *code != "auto") {
+            Some(code) => Self::from_code(code).unwrap_or_else(|| {
+                warn!("Unsupported language '{}', using English", code);
+                Language::English
+            }),
+            None => Self::from_env().unwrap_or_default(),
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locales/en.ftl"),
+            Language::German => include_str!("../locales/de.ftl"),
+            Language::Spanish => include_str!("../locales/es.ftl"),
+        }
+    }
+}
+
+/// Message bundles for the active language and the English fallback.
+struct Localizer {
+    language: Language,
+    bundle: FluentBundle<FluentResource>,
+    fallback: Option<FluentBundle<FluentResource>>,
+}
+
+impl Localizer {
+    fn new(language: Language) -> Self {
+        let fallback = (language != Language::English).then(|| build_bundle(Language::English));
+        Self {
+            language,
+            bundle: build_bundle(language),
+            fallback,
+        }
+    }
+
+    fn format(&self, id: &str, args: Option<&FluentArgs>) -> String {
+        for bundle in std::iter::once(&self.bundle).chain(&self.fallback) {
+            let Some(pattern) = bundle.get_message(id).and_then(|msg| msg.value()) else {
+                continue;
+            };
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, args, &mut errors);
+            if !errors.is_empty() {
+                debug!("Errors formatting message '{}': {:?}", id, errors);
+            }
+            return text.into_owned();
+        }
+        debug!("Missing translation for '{}'", id);
+        id.to_string()
+    }
+}
+
+fn build_bundle(language: Language) -> FluentBundle<FluentResource> {
+    let langid: LanguageIdentifier = language.code().parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Unicode isolation marks show up as stray glyphs in terminals and egui
+    bundle.set_use_isolating(false);
+    let resource = FluentResource::try_new(language.source().to_string()).unwrap_or_else(
+        |(resource, errors)| {
+            warn!(
+                "Errors parsing {} translations: {:?}",
+                language.code(),
+                errors
+            );
+            resource
+        },
+    );
+    if let Err(errors) = bundle.add_resource(resource) {
+        warn!("Duplicate {} translations: {:?}", language.code(), errors);
+    }
+    bundle
+}
+
+static LOCALIZER: RwLock<Option<Arc<Localizer>>> = RwLock::new(None);
+
+fn localizer() -> Arc<Localizer> {
+    if let Some(localizer) = LOCALIZER.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return Arc::clone(localizer);
+    }
+    let mut slot = LOCALIZER.write().unwrap_or_else(|e| e.into_inner());
+    Arc::clone(slot.get_or_insert_with(|| Arc::new(Localizer::new(Language::default()))))
+}
+
+/// Switch the interface language. Strings looked up afterwards use it.
+pub fn set_language(language: Language) {
+    if localizer().language == language {
+        return;
+    }
+    *LOCALIZER.write().unwrap_or_else(|e| e.into_inner()) =
+        Some(Arc::new(Localizer::new(language)));
+}
+
+/// The active interface language.
+pub fn language() -> Language {
+    localizer().language
+}
+
+/// Look up a translated string by message ID.
+///
+/// Returns the ID itself if no language defines it.
+pub fn tr(id: &str) -> String {
+    localizer().format(id, None)
+}
+
+/// Look up a translated string with `{ $name }` placeholders filled in.
+pub fn tr_args<'a>(id: &str, args: &[(&'a str, FluentValue<'a>)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    localizer().format(id, Some(&fluent_args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_ids(language: Language) -> Vec<String> {
+        language
+            .source()
+            .lines()
+            .filter(|line| !line.starts_with(['#', ' ', '-']) && line.contains(" ="))
+            .filter_map(|line| line.split(" =").next())
+            .map(|id| id.trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_language_from_code() {
+        assert_eq!(Language::from_code("de"), Some(Language::German));
+        assert_eq!(Language::from_code("de_AT.UTF-8"), Some(Language::German));
+        assert_eq!(Language::from_code("es-MX"), Some(Language::Spanish));
+        assert_eq!(Language::from_code("EN"), Some(Language::English));
+        assert_eq!(Language::from_code("C"), None);
+        assert_eq!(Language::resolve(Some("de")), Language::German);
+        assert_eq!(Language::resolve(Some("xx")), Language::English);
+    }
+
+    #[test]
+    fn test_translations_parse_and_are_in_english() {
+        let english = message_ids(Language::English);
+        assert!(english.contains(&"tab-dashboard".to_string()));
+        for language in Language::ALL {
+            assert!(
+                FluentResource::try_new(language.source().to_string()).is_ok(),
+                "{} translations do not parse",
+                language.code()
+            );
+            for id in message_ids(language) {
+                assert!(
+                    english.contains(&id),
+                    "{} defines '{}', which is missing from en.ftl",
+                    language.code(),
+                    id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup_falls_back_to_english() {
+        let german = Localizer::new(Language::German);
+        assert_eq!(german.format("tab-history", None), "Verlauf");
+        assert_eq!(german.format("no-such-message", None), "no-such-message");
+
+        let mut args = FluentArgs::new();
+        args.set("connected", 2);
+        args.set("total", 3);
+        let english = Localizer::new(Language::English);
+        assert_eq!(english.format("header-online", Some(&args)), "2/3 online");
+        assert_eq!(german.format("header-online", Some(&args)), "2/3 online");
+    }
+}
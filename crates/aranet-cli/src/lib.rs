@@ -106,6 +106,10 @@ pub fn local_now_fmt(fmt: &str) -> String {
 // Config module - needed by both TUI and GUI
 pub mod config;
 
+// Translations - shared by TUI and GUI
+#[cfg(any(feature = "tui", feature = "gui"))]
+pub mod i18n;
+
 // TUI module - publicly exposed for aranet-tui crate to use
 #[cfg(feature = "tui")]
 pub mod tui;
@@ -0,0 +1,271 @@
+//! Rolling log files for long-running `watch` sessions.
+//!
+//! Readings are appended to a CSV or JSON Lines file chosen by the extension
+//! of `--log-file`. With `--rotate`, each day (or hour) gets its own
+//! date-stamped file next to the given path, e.g. `readings-2026-01-15.csv`,
+//! and files that have been rotated out can be gzipped.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use aranet_types::CurrentReading;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use time::OffsetDateTime;
+use time::macros::format_description;
+
+use crate::cli::LogRotation;
+use crate::format::{
+    FormatOptions, format_reading_json_with_device, format_watch_csv_header_with_device,
+    format_watch_csv_line_with_device,
+};
+
+/// Record format of a log file, taken from its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogFormat {
+    Csv,
+    JsonLines,
+}
+
+impl LogFormat {
+    fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => Ok(Self::Csv),
+            Some(ext)
+                if ext.eq_ignore_ascii_case("jsonl") || ext.eq_ignore_ascii_case("ndjson") =>
+            {
+                Ok(Self::JsonLines)
+            }
+            _ => bail!(
+                "Unsupported log file '{}': use a .csv or .jsonl extension",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// The file currently being appended to.
+struct OpenLog {
+    period: String,
+    path: PathBuf,
+    file: File,
+}
+
+/// Appends readings to a log file, starting a new file when the rotation
+/// period changes.
+pub struct RollingLog {
+    base: PathBuf,
+    format: LogFormat,
+    rotation: LogRotation,
+    gzip: bool,
+    opts: FormatOptions,
+    current: Option<OpenLog>,
+}
+
+impl RollingLog {
+    /// Create a log writing to `base` (or date-stamped files next to it).
+    ///
+    /// No file is created until the first reading is written.
+    pub fn new(
+        base: PathBuf,
+        rotation: LogRotation,
+        gzip: bool,
+        opts: &FormatOptions,
+    ) -> Result<Self> {
+        let format = LogFormat::from_path(&base)?;
+        Ok(Self {
+            base,
+            format,
+            rotation,
+            gzip,
+            // One JSON object per line
+            opts: opts.with_compact(true),
+            current: None,
+        })
+    }
+
+    /// Append a reading, rotating first if `now` is in a new period.
+    pub fn write(
+        &mut self,
+        device: &str,
+        reading: &CurrentReading,
+        now: OffsetDateTime,
+    ) -> Result<()> {
+        let period = self.period(now);
+        if self
+            .current
+            .as_ref()
+            .is_none_or(|current| current.period != period)
+        {
+            self.rotate(period)?;
+        }
+
+        let content = match self.format {
+            LogFormat::Csv => format_watch_csv_line_with_device(reading, device, &self.opts),
+            LogFormat::JsonLines => format_reading_json_with_device(reading, device, &self.opts)?,
+        };
+        if let Some(current) = self.current.as_mut() {
+            current
+                .file
+                .write_all(content.as_bytes())
+                .with_context(|| format!("Failed to write to {}", current.path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Path of the file for a period.
+    fn path_for(&self, period: &str) -> PathBuf {
+        if period.is_empty() {
+            return self.base.clone();
+        }
+        let stem = self
+            .base
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut name = format!("{stem}-{period}");
+        if let Some(ext) = self.base.extension() {
+            name.push('.');
+            name.push_str(&ext.to_string_lossy());
+        }
+        self.base.with_file_name(name)
+    }
+
+    /// Period key of `now`: empty without rotation.
+    fn period(&self, now: OffsetDateTime) -> String {
+        let formatted = match self.rotation {
+            LogRotation::Never => return String::new(),
+            LogRotation::Daily => now.format(format_description!("[year]-[month]-[day]")),
+            LogRotation::Hourly => now.format(format_description!("[year]-[month]-[day]-[hour]")),
+        };
+        formatted.unwrap_or_default()
+    }
+
+    /// Close the current file (gzipping it if enabled) and open the one for `period`.
+    fn rotate(&mut self, period: String) -> Result<()> {
+        if let Some(previous) = self.current.take() {
+            drop(previous.file);
+            if self.gzip {
+                gzip_file(&previous.path)
+                    .with_context(|| format!("Failed to compress {}", previous.path.display()))?;
+            }
+        }
+
+        let path = self.path_for(&period);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open log file {}", path.display()))?;
+        let is_empty = file.metadata().map(|m| m.len() == 0).unwrap_or(true);
+        if is_empty && self.format == LogFormat::Csv && !self.opts.no_header {
+            file.write_all(format_watch_csv_header_with_device(&self.opts).as_bytes())
+                .with_context(|| format!("Failed to write to {}", path.display()))?;
+        }
+
+        self.current = Some(OpenLog { period, path, file });
+        Ok(())
+    }
+}
+
+/// Compress `path` to `path.gz` and remove the original.
+fn gzip_file(path: &Path) -> io::Result<()> {
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let mut input = File::open(path)?;
+    let mut encoder = GzEncoder::new(File::create(&gz_name)?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn reading(co2: u16) -> CurrentReading {
+        CurrentReading::builder().co2(co2).battery(90).build()
+    }
+
+    #[test]
+    fn test_log_format_from_extension() {
+        assert_eq!(
+            LogFormat::from_path(Path::new("readings.csv")).unwrap(),
+            LogFormat::Csv
+        );
+        assert_eq!(
+            LogFormat::from_path(Path::new("readings.JSONL")).unwrap(),
+            LogFormat::JsonLines
+        );
+        assert!(LogFormat::from_path(Path::new("readings.txt")).is_err());
+    }
+
+    #[test]
+    fn test_daily_rotation_with_gzip() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("readings.csv");
+        let mut log = RollingLog::new(
+            base.clone(),
+            LogRotation::Daily,
+            true,
+            &FormatOptions::default(),
+        )
+        .unwrap();
+
+        log.write("office", &reading(600), datetime!(2026-01-15 23:50 UTC))
+            .unwrap();
+        log.write("office", &reading(610), datetime!(2026-01-15 23:55 UTC))
+            .unwrap();
+        log.write("office", &reading(620), datetime!(2026-01-16 00:00 UTC))
+            .unwrap();
+
+        assert!(!base.exists());
+        let closed = dir.path().join("readings-2026-01-15.csv.gz");
+        let mut csv = String::new();
+        GzDecoder::new(File::open(&closed).unwrap())
+            .read_to_string(&mut csv)
+            .unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("timestamp,device,co2"));
+        assert!(lines[2].contains(",office,610,"));
+
+        let current = fs::read_to_string(dir.path().join("readings-2026-01-16.csv")).unwrap();
+        assert_eq!(current.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_jsonl_without_rotation_appends() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("readings.jsonl");
+        let opts = FormatOptions::default();
+
+        for co2 in [600, 700] {
+            // A restarted watch keeps appending to the same file
+            let mut log = RollingLog::new(base.clone(), LogRotation::Never, false, &opts).unwrap();
+            log.write("office", &reading(co2), OffsetDateTime::now_utc())
+                .unwrap();
+        }
+
+        let content = fs::read_to_string(&base).unwrap();
+        let co2: Vec<u64> = content
+            .lines()
+            .map(|line| {
+                let json: serde_json::Value = serde_json::from_str(line).unwrap();
+                json["co2"].as_u64().unwrap()
+            })
+            .collect();
+        assert_eq!(co2, [600, 700]);
+    }
+}
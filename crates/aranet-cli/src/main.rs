@@ -12,12 +12,16 @@ compile_error!("At least one of 'cli' or 'tui' features must be enabled");
 
 // CLI modules (conditionally compiled)
 #[cfg(feature = "cli")]
+mod baseline;
+#[cfg(feature = "cli")]
 mod cli;
 #[cfg(feature = "cli")]
 mod commands;
 #[cfg(feature = "cli")]
 mod format;
 #[cfg(feature = "cli")]
+mod log_file;
+#[cfg(feature = "cli")]
 mod style;
 #[cfg(feature = "cli")]
 mod util;
@@ -30,23 +34,35 @@ use aranet_cli::config;
 #[cfg(feature = "tui")]
 mod tui;
 
+// Translations are defined in lib.rs and shared with the TUI
+#[cfg(feature = "tui")]
+use aranet_cli::i18n;
+
 use anyhow::Result;
 
+#[cfg(feature = "cli")]
+use baseline::Baseline;
 #[cfg(feature = "cli")]
 use clap::{CommandFactory, Parser};
 #[cfg(feature = "cli")]
-use cli::{AliasSubcommand, Cli, Commands, ConfigAction, ConfigKey, OutputFormat, ReportFormat};
+use cli::{
+    AliasSubcommand, AnnotateAction, Cli, Commands, ConfigAction, ConfigKey, OutputFormat,
+    ReportFormat,
+};
 #[cfg(feature = "cli")]
 use commands::{
-    AliasAction, HistoryArgs, ServerArgs, SyncArgs, WatchArgs, cmd_alias, cmd_cache, cmd_doctor,
-    cmd_history, cmd_info, cmd_read, cmd_report, cmd_scan, cmd_server, cmd_set, cmd_status,
-    cmd_sync, cmd_watch,
+    AliasAction, HistoryArgs, ReadArgs, ServerArgs, SimulateArgs, StatusArgs, SyncArgs, TopArgs,
+    WatchArgs, cmd_alias, cmd_annotate, cmd_cache, cmd_doctor, cmd_history, cmd_info, cmd_read,
+    cmd_report, cmd_scan, cmd_server, cmd_set, cmd_simulate, cmd_status, cmd_sync, cmd_top,
+    cmd_watch,
 };
 #[cfg(feature = "cli")]
 use config::{Config, get_device_source, resolve_alias_with_info, resolve_timeout};
 #[cfg(feature = "cli")]
 use format::FormatOptions;
 #[cfg(feature = "cli")]
+use log_file::RollingLog;
+#[cfg(feature = "cli")]
 use std::io;
 #[cfg(feature = "cli")]
 use std::time::Duration;
@@ -118,8 +134,9 @@ async fn main() -> Result<()> {
         return aranet_cli::gui::run();
     }
 
-    // Load config for device resolution
-    let config = Config::load_or_default()?;
+    // Load config for device resolution (file, then ARANET_* overrides;
+    // command-line flags take precedence below)
+    let config = Config::load_effective()?;
 
     // Initialize tracing (write to stderr so stdout is clean for data)
     let filter = if cli.quiet {
@@ -155,13 +172,24 @@ async fn main() -> Result<()> {
             format,
             no_header,
             alias,
+            log_adverts,
         } => {
             let format = resolve_format_with_config(cli.json, format, config_format);
             let timeout = resolve_timeout(timeout, &config, 10);
             let opts = FormatOptions::new(no_color, config_fahrenheit, style)
                 .with_no_header(no_header)
                 .with_compact(compact);
-            cmd_scan(timeout, format, output, quiet, alias, &opts, &config).await?;
+            cmd_scan(
+                timeout,
+                format,
+                output,
+                quiet,
+                alias,
+                log_adverts,
+                &opts,
+                &config,
+            )
+            .await?;
         }
         Commands::Examples => {
             print_examples();
@@ -170,6 +198,9 @@ async fn main() -> Result<()> {
             device,
             output: out,
             passive,
+            combined,
+            retry_until_fresh,
+            cached,
         } => {
             let format = resolve_format_with_config(cli.json, out.format, config_format);
             // If no devices specified, try last device before falling back to interactive
@@ -189,12 +220,29 @@ async fn main() -> Result<()> {
                     .with_compact(compact)
                     .with_bq(out.resolve_bq(config_bq))
                     .with_inhg(out.resolve_inhg(config_inhg));
-            cmd_read(devices, timeout, format, output, quiet, passive, &opts).await?;
+            cmd_read(ReadArgs {
+                devices,
+                timeout,
+                format,
+                output,
+                quiet,
+                passive,
+                combined,
+                retry_until_fresh: retry_until_fresh.map(Duration::from_secs),
+                cached,
+                service_url: &config.gui.service_url,
+                service_api_key: config.gui.service_api_key.as_deref(),
+                aliases: &config.aliases,
+                opts: &opts,
+            })
+            .await?;
         }
         Commands::Status {
             device,
             output: out,
             brief,
+            advice,
+            compare,
         } => {
             let format = resolve_format_with_config(cli.json, out.format, config_format);
             let dev = resolve_device_with_hint(device.device, &config, quiet);
@@ -205,7 +253,18 @@ async fn main() -> Result<()> {
                     .with_compact(compact)
                     .with_bq(out.resolve_bq(config_bq))
                     .with_inhg(out.resolve_inhg(config_inhg));
-            cmd_status(dev, timeout, format, output, &opts, brief).await?;
+            let baseline = compare.as_deref().map(Baseline::load).transpose()?;
+            cmd_status(StatusArgs {
+                device: dev,
+                timeout,
+                format,
+                output,
+                opts: &opts,
+                brief,
+                advice,
+                baseline,
+            })
+            .await?;
         }
         Commands::History {
             device,
@@ -267,6 +326,10 @@ async fn main() -> Result<()> {
             interval,
             count,
             passive,
+            log_file,
+            rotate,
+            gzip,
+            compare,
         } => {
             let format = resolve_format_with_config(cli.json, out.format, config_format);
             // For passive mode without explicit device, don't resolve to last device
@@ -283,6 +346,10 @@ async fn main() -> Result<()> {
                     .with_compact(compact)
                     .with_bq(out.resolve_bq(config_bq))
                     .with_inhg(out.resolve_inhg(config_inhg));
+            let log = log_file
+                .map(|path| RollingLog::new(path, rotate.unwrap_or_default(), gzip, &opts))
+                .transpose()?;
+            let baseline = compare.as_deref().map(Baseline::load).transpose()?;
             cmd_watch(WatchArgs {
                 device: dev,
                 interval,
@@ -291,18 +358,96 @@ async fn main() -> Result<()> {
                 format,
                 output,
                 passive,
+                log,
+                baseline,
+                opts: &opts,
+            })
+            .await?;
+        }
+        Commands::Simulate {
+            output: out,
+            devices,
+            device_type,
+            interval,
+            count,
+            store,
+            push,
+            service_url,
+            api_key,
+        } => {
+            let format = resolve_format_with_config(cli.json, out.format, config_format);
+            let opts =
+                FormatOptions::new(no_color, out.resolve_fahrenheit(config_fahrenheit), style)
+                    .with_no_header(out.no_header)
+                    .with_compact(compact)
+                    .with_bq(out.resolve_bq(config_bq))
+                    .with_inhg(out.resolve_inhg(config_inhg));
+            let push = if push {
+                let url = service_url.unwrap_or_else(|| config.gui.service_url.clone());
+                let api_key = api_key.or_else(|| config.gui.service_api_key.clone());
+                Some(aranet_service::forward::ForwardClient::new(&url, api_key)?)
+            } else {
+                None
+            };
+            cmd_simulate(SimulateArgs {
+                devices,
+                device_type,
+                interval,
+                count,
+                store,
+                push,
+                format,
+                output,
+                opts: &opts,
+            })
+            .await?;
+        }
+        Commands::Top {
+            device,
+            output: out,
+            interval,
+            sort,
+            reverse,
+            count,
+        } => {
+            // Explicit devices win; otherwise show every aliased/default device,
+            // or everything in range when nothing is configured
+            let devices = if device.device.is_empty() {
+                let mut configured: Vec<String> = config.aliases.values().cloned().collect();
+                if let Some(dev) = &config.device
+                    && !configured.iter().any(|d| d.eq_ignore_ascii_case(dev))
+                {
+                    configured.push(dev.clone());
+                }
+                configured
+            } else {
+                resolve_devices_with_feedback(device.device, &config, quiet)
+            };
+            let timeout = Duration::from_secs(resolve_timeout(device.timeout, &config, 5));
+            let opts =
+                FormatOptions::new(no_color, out.resolve_fahrenheit(config_fahrenheit), style)
+                    .with_bq(out.resolve_bq(config_bq))
+                    .with_inhg(out.resolve_inhg(config_inhg));
+            cmd_top(TopArgs {
+                devices,
+                interval,
+                count,
+                timeout,
+                sort,
+                reverse,
                 opts: &opts,
             })
             .await?;
         }
         Commands::Doctor => {
-            cmd_doctor(cli.verbose, no_color).await?;
+            cmd_doctor(cli.verbose, cli.json, compact, no_color).await?;
         }
         Commands::Sync {
             device,
             format,
             full,
             all,
+            verify,
         } => {
             let format = resolve_format_with_config(cli.json, format, config_format);
             cmd_sync(
@@ -311,6 +456,7 @@ async fn main() -> Result<()> {
                     format,
                     full,
                     all,
+                    verify,
                 },
                 &config,
             )
@@ -338,6 +484,12 @@ async fn main() -> Result<()> {
             };
             cmd_report(device, all, period, format, out, &config)?;
         }
+        Commands::Annotate { mut action } => {
+            if let AnnotateAction::Add { device, .. } = &mut action {
+                *device = resolve_device_with_hint(device.take(), &config, quiet);
+            }
+            cmd_annotate(action, &config)?;
+        }
         Commands::Server { .. } => unreachable!(), // Handled above
         Commands::Config { .. } => unreachable!(),
         Commands::Alias { .. } => unreachable!(),
@@ -371,7 +523,10 @@ fn handle_config_command(action: &ConfigAction) -> Result<()> {
             println!("{}", Config::path().display());
         }
         ConfigAction::Show => {
-            let config = Config::load_or_default()?;
+            let config = Config::load_effective()?;
+            for o in Config::env_overrides() {
+                println!("# {} overridden by {}", o.key, o.var);
+            }
             println!("{}", toml::to_string_pretty(&config)?);
         }
         ConfigAction::Init => {
@@ -384,7 +539,7 @@ fn handle_config_command(action: &ConfigAction) -> Result<()> {
             }
         }
         ConfigAction::Get { key } => {
-            let config = Config::load_or_default()?;
+            let config = Config::load_effective()?;
             let value = match key {
                 ConfigKey::Device => config.device.unwrap_or_default(),
                 ConfigKey::Format => config.format.unwrap_or_else(|| "text".to_string()),
@@ -587,11 +742,14 @@ fn print_examples() {
     println!("  aranet read -d living-room       # Read using device alias");
     println!("  aranet status                    # Quick one-line status");
     println!("  aranet status --brief            # Super-compact status for scripting");
+    println!("  aranet status --advice           # Status with ventilation advice");
     println!();
     println!("{}", "Monitoring:".bold());
     println!("  aranet watch                     # Continuously monitor (60s intervals)");
     println!("  aranet watch -i 30               # Monitor every 30 seconds");
     println!("  aranet watch -n 5                # Take 5 readings then exit");
+    println!("  aranet watch --compare week.csv  # Show deltas against a recorded baseline");
+    println!("  aranet top --sort co2            # Live table of all configured devices");
     println!();
     println!("{}", "History & Export:".bold());
     println!("  aranet history                   # Show all stored readings");
@@ -599,6 +757,7 @@ fn print_examples() {
     println!("  aranet history -f csv > data.csv # Export to CSV file");
     println!("  aranet history -f json           # Export as JSON");
     println!("  aranet report --period weekly    # Summarize cached history");
+    println!("  aranet annotate add \"Filter swap\" # Mark an event on the default device");
     println!();
     println!("{}", "Device Management:".bold());
     println!("  aranet alias list                # Show saved aliases");
@@ -3,19 +3,23 @@
 //! This module contains the core state management for the terminal user interface,
 //! including device tracking, connection status, and UI navigation.
 
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::time::{Duration, Instant};
 
 use tokio::sync::mpsc;
 
 use aranet_core::settings::DeviceSettings;
-use aranet_types::{CurrentReading, DeviceType, HistoryRecord};
+use aranet_types::{CurrentReading, DeviceType, HistoryRecord, Status};
 
+use super::keymap::Keymap;
 use super::messages::{CachedDevice, Command, SensorEvent};
 
 /// Maximum number of alert history entries to retain.
 const MAX_ALERT_HISTORY: usize = 1000;
 
+/// Sidebar group holding devices that are not in any configured group.
+pub const UNGROUPED: &str = "Ungrouped";
+
 /// Bluetooth range mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum BleRange {
@@ -74,6 +78,33 @@ pub enum ConnectionStatus {
     Error(String),
 }
 
+/// A row in the device sidebar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SidebarRow {
+    /// Group header, followed by the group's devices unless collapsed.
+    Group {
+        /// Group name from the config, or [`UNGROUPED`].
+        name: String,
+        /// Indices of the group's devices that pass the current filter.
+        devices: Vec<usize>,
+        /// Whether the group's devices are hidden.
+        collapsed: bool,
+    },
+    /// A device, by index into [`App::devices`].
+    Device(usize),
+}
+
+/// Summary of the latest readings of a device group.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GroupSummary {
+    /// Worst CO2 status among devices with a CO2 reading.
+    pub worst_co2_status: Option<Status>,
+    /// Average temperature in Celsius among devices with a reading.
+    pub avg_temperature: Option<f32>,
+    /// Number of connected devices.
+    pub connected: usize,
+}
+
 /// State for a single Aranet device.
 #[derive(Debug, Clone)]
 pub struct DeviceState {
@@ -107,6 +138,8 @@ pub struct DeviceState {
     pub connected_at: Option<std::time::Instant>,
     /// Device settings read from the device.
     pub settings: Option<DeviceSettings>,
+    /// When the last BLE advertisement was received (passive mode).
+    pub last_advertisement: Option<Instant>,
 }
 
 impl DeviceState {
@@ -128,6 +161,7 @@ impl DeviceState {
             rssi: None,
             connected_at: None,
             settings: None,
+            last_advertisement: None,
         }
     }
 
@@ -139,6 +173,32 @@ impl DeviceState {
             .unwrap_or(&self.id)
     }
 
+    /// Whether the device is being updated from advertisements, i.e. one was
+    /// received within two measurement intervals.
+    pub fn is_advertising(&self) -> bool {
+        let Some(received) = self.last_advertisement else {
+            return false;
+        };
+        let interval = self
+            .reading
+            .as_ref()
+            .map(|r| u64::from(r.interval.max(60)))
+            .unwrap_or(300);
+        received.elapsed() < Duration::from_secs(interval * 2)
+    }
+
+    /// Time since the last advertisement as a short string (e.g. "12s").
+    pub fn advertisement_age(&self) -> Option<String> {
+        let secs = self.last_advertisement?.elapsed().as_secs();
+        Some(if secs < 60 {
+            format!("{}s", secs)
+        } else if secs < 3600 {
+            format!("{}m", secs / 60)
+        } else {
+            format!("{}h", secs / 3600)
+        })
+    }
+
     /// Get uptime as formatted string if connected.
     pub fn uptime(&self) -> Option<String> {
         let connected_at = self.connected_at?;
@@ -387,29 +447,44 @@ impl SessionStats {
     }
 }
 
-/// Calculate radon averages from history records.
-pub fn calculate_radon_averages(history: &[HistoryRecord]) -> (Option<u32>, Option<u32>) {
+/// Radon averages (24h, 7d, 30d) to display for a device.
+///
+/// Prefers the averages the AranetRn+ reports itself, which cover the full
+/// period even when little history has been downloaded, and falls back to
+/// averages computed from history records for any the device did not report.
+pub fn calculate_radon_averages(
+    reading: &CurrentReading,
+    history: &[HistoryRecord],
+) -> (Option<u32>, Option<u32>, Option<u32>) {
     use time::OffsetDateTime;
 
     let now = OffsetDateTime::now_utc();
     let day_ago = now - time::Duration::days(1);
     let week_ago = now - time::Duration::days(7);
+    let month_ago = now - time::Duration::days(30);
 
     let mut day_sum: u64 = 0;
     let mut day_count: u32 = 0;
     let mut week_sum: u64 = 0;
     let mut week_count: u32 = 0;
+    let mut month_sum: u64 = 0;
+    let mut month_count: u32 = 0;
 
     for record in history {
         if let Some(radon) = record.radon
-            && record.timestamp >= week_ago
+            && record.timestamp >= month_ago
         {
-            week_sum += radon as u64;
-            week_count += 1;
+            month_sum += radon as u64;
+            month_count += 1;
+
+            if record.timestamp >= week_ago {
+                week_sum += radon as u64;
+                week_count += 1;
 
-            if record.timestamp >= day_ago {
-                day_sum += radon as u64;
-                day_count += 1;
+                if record.timestamp >= day_ago {
+                    day_sum += radon as u64;
+                    day_count += 1;
+                }
             }
         }
     }
@@ -426,7 +501,17 @@ pub fn calculate_radon_averages(history: &[HistoryRecord]) -> (Option<u32>, Opti
         None
     };
 
-    (day_avg, week_avg)
+    let month_avg = if month_count > 0 {
+        Some((month_sum / month_count as u64) as u32)
+    } else {
+        None
+    };
+
+    (
+        reading.radon_avg_24h.or(day_avg),
+        reading.radon_avg_7d.or(week_avg),
+        reading.radon_avg_30d.or(month_avg),
+    )
 }
 
 /// Actions that require user confirmation.
@@ -496,6 +581,12 @@ pub struct App {
     pub bell_enabled: bool,
     /// Device list filter.
     pub device_filter: DeviceFilter,
+    /// Sidebar groups from the config (group name -> device IDs, names or aliases).
+    pub device_groups: BTreeMap<String, Vec<String>>,
+    /// Names of sidebar groups whose devices are hidden.
+    pub collapsed_groups: HashSet<String>,
+    /// Active key bindings.
+    pub keymap: Keymap,
     /// Pending confirmation action.
     pub pending_confirmation: Option<PendingAction>,
     /// Whether to show the device sidebar (can be hidden on narrow terminals).
@@ -524,12 +615,19 @@ pub struct App {
     pub chart_metrics: u8,
     /// Whether Smart Home integration mode is enabled.
     pub smart_home_enabled: bool,
+    /// Whether passive (advertisement-based) monitoring is enabled.
+    pub passive_mode: bool,
     /// Bluetooth range setting.
     pub ble_range: BleRange,
     /// Whether a history sync is in progress.
     pub syncing: bool,
     /// Export format for history (CSV or JSON).
     pub export_format: ExportFormat,
+    /// System clipboard, opened on first copy.
+    ///
+    /// Kept open because on X11 the copied text is only available while the
+    /// clipboard that set it is alive.
+    clipboard: Option<arboard::Clipboard>,
     /// Do Not Disturb mode - temporarily suppresses all alert notifications.
     pub do_not_disturb: bool,
     /// Service client for aranet-service communication.
@@ -601,6 +699,9 @@ impl App {
             radon_alert_threshold: 300,
             bell_enabled: true,
             device_filter: DeviceFilter::default(),
+            device_groups: BTreeMap::new(),
+            collapsed_groups: HashSet::new(),
+            keymap: Keymap::default(),
             pending_confirmation: None,
             show_sidebar: true,
             show_fullscreen_chart: false,
@@ -615,9 +716,11 @@ impl App {
             theme: Theme::default(),
             chart_metrics: Self::METRIC_PRIMARY, // Primary metric only by default
             smart_home_enabled: false,
+            passive_mode: false,
             ble_range: BleRange::default(),
             syncing: false,
             export_format: ExportFormat::default(),
+            clipboard: None,
             do_not_disturb: false,
             service_client: aranet_core::service_client::ServiceClient::new_with_api_key(
                 &service_url,
@@ -686,6 +789,20 @@ impl App {
         self.push_status_message(format!("Smart Home mode {}", status));
     }
 
+    /// Toggle passive monitoring and return the command for the worker.
+    pub fn toggle_passive_mode(&mut self) -> Command {
+        self.passive_mode = !self.passive_mode;
+        if self.passive_mode {
+            self.push_status_message(
+                "Passive mode: listening for Smart Home advertisements".to_string(),
+            );
+            Command::StartPassiveMonitoring
+        } else {
+            self.push_status_message("Passive mode disabled".to_string());
+            Command::StopPassiveMonitoring
+        }
+    }
+
     /// Toggle full-screen chart view.
     pub fn toggle_fullscreen_chart(&mut self) {
         self.show_fullscreen_chart = !self.show_fullscreen_chart;
@@ -733,14 +850,17 @@ impl App {
             | SensorEvent::DeviceForgotten { .. }
             | SensorEvent::SignalStrengthUpdate { .. }
             | SensorEvent::BackgroundPollingStarted { .. }
-            | SensorEvent::BackgroundPollingStopped { .. } => self.handle_device_event(event),
+            | SensorEvent::BackgroundPollingStopped { .. }
+            | SensorEvent::PassiveMonitoringStarted
+            | SensorEvent::PassiveMonitoringStopped => self.handle_device_event(event),
 
             // Reading updates and history
             SensorEvent::ReadingUpdated { .. }
             | SensorEvent::HistoryLoaded { .. }
             | SensorEvent::HistorySyncStarted { .. }
             | SensorEvent::HistorySynced { .. }
-            | SensorEvent::HistorySyncProgress { .. } => self.handle_reading_event(event),
+            | SensorEvent::HistorySyncProgress { .. }
+            | SensorEvent::PassiveReadingUpdated { .. } => self.handle_reading_event(event),
 
             // Device settings changes
             SensorEvent::IntervalChanged { .. }
@@ -793,7 +913,15 @@ impl App {
             | SensorEvent::ServiceDeviceAdded { .. }
             | SensorEvent::ServiceDeviceUpdated { .. }
             | SensorEvent::ServiceDeviceRemoved { .. }
-            | SensorEvent::ServiceDeviceError { .. } => Vec::new(),
+            | SensorEvent::ServiceDeviceError { .. }
+            | SensorEvent::AlertsLoaded { .. }
+            | SensorEvent::AlertRecorded { .. }
+            | SensorEvent::ImportPreviewReady { .. }
+            | SensorEvent::ImportCompleted { .. }
+            | SensorEvent::ImportFailed { .. }
+            | SensorEvent::PlacementSample { .. }
+            | SensorEvent::PlacementError { .. }
+            | SensorEvent::HistoryInfoLoaded { .. } => Vec::new(),
         }
     }
 
@@ -902,6 +1030,15 @@ impl App {
             SensorEvent::BackgroundPollingStopped { device_id: _ } => {
                 self.push_status_message("Background polling stopped".to_string());
             }
+            SensorEvent::PassiveMonitoringStarted => {
+                self.passive_mode = true;
+            }
+            SensorEvent::PassiveMonitoringStopped => {
+                self.passive_mode = false;
+                for device in &mut self.devices {
+                    device.last_advertisement = None;
+                }
+            }
             _ => {}
         }
 
@@ -926,6 +1063,46 @@ impl App {
                     device.error = None;
                 }
             }
+            SensorEvent::PassiveReadingUpdated {
+                device_id,
+                name,
+                device_type,
+                reading,
+                rssi,
+            } => {
+                // Advertisements carry the address, which may differ from the
+                // peripheral ID a device was discovered under; match on name too
+                let index = self
+                    .devices
+                    .iter()
+                    .position(|d| d.id == device_id)
+                    .or_else(|| {
+                        name.as_deref().and_then(|name| {
+                            self.devices
+                                .iter()
+                                .position(|d| d.name.as_deref() == Some(name))
+                        })
+                    });
+                let index = index.unwrap_or_else(|| {
+                    self.devices.push(DeviceState::new(device_id));
+                    self.devices.len() - 1
+                });
+                let device_id = self.devices[index].id.clone();
+
+                self.check_thresholds(&device_id, &reading);
+                self.log_reading(&device_id, &reading);
+
+                let device = &mut self.devices[index];
+                device.name = device.name.take().or(name);
+                device.device_type = Some(device_type);
+                device.rssi = rssi.or(device.rssi);
+                device.session_stats.update(&reading);
+                device.previous_reading = device.reading.take();
+                device.reading = Some(reading);
+                device.last_updated = Some(Instant::now());
+                device.last_advertisement = Some(Instant::now());
+                device.error = None;
+            }
             SensorEvent::HistoryLoaded { device_id, records } => {
                 if let Some(device) = self.devices.iter_mut().find(|d| d.id == device_id) {
                     device.history = records;
@@ -1255,39 +1432,163 @@ impl App {
         }
     }
 
-    /// Select a device by its current filtered row.
-    pub fn select_filtered_row(&mut self, row: usize) {
-        if let Some(index) = self.filtered_device_indices().get(row).copied() {
-            self.selected_device = index;
-            self.reset_history_scroll();
+    /// Name of the configured group a device belongs to, if any.
+    ///
+    /// Members match the device ID, name or alias, ignoring case. A device
+    /// listed in several groups goes to the first one by name.
+    #[must_use]
+    pub fn device_group(&self, device: &DeviceState) -> Option<&str> {
+        let matches = |member: &String| {
+            [
+                Some(device.id.as_str()),
+                device.name.as_deref(),
+                device.alias.as_deref(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|key| key.eq_ignore_ascii_case(member))
+        };
+        self.device_groups
+            .iter()
+            .find(|(_, members)| members.iter().any(matches))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Rows of the device sidebar for the current filter.
+    ///
+    /// Without configured groups this is a flat device list. Otherwise each
+    /// non-empty group gets a header row, with ungrouped devices last.
+    #[must_use]
+    pub fn sidebar_rows(&self) -> Vec<SidebarRow> {
+        let filtered = self.filtered_device_indices();
+        if self.device_groups.is_empty() {
+            return filtered.into_iter().map(SidebarRow::Device).collect();
+        }
+
+        let mut groups: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        let mut ungrouped = Vec::new();
+        for index in filtered {
+            match self.device_group(&self.devices[index]) {
+                Some(name) => groups.entry(name).or_default().push(index),
+                None => ungrouped.push(index),
+            }
+        }
+
+        let mut rows = Vec::new();
+        let groups = groups
+            .into_iter()
+            .chain((!ungrouped.is_empty()).then_some((UNGROUPED, ungrouped)));
+        for (name, devices) in groups {
+            let collapsed = self.collapsed_groups.contains(name);
+            let members = if collapsed {
+                Vec::new()
+            } else {
+                devices.clone()
+            };
+            rows.push(SidebarRow::Group {
+                name: name.to_string(),
+                devices,
+                collapsed,
+            });
+            rows.extend(members.into_iter().map(SidebarRow::Device));
+        }
+        rows
+    }
+
+    /// Summarize the latest readings of the given devices.
+    #[must_use]
+    pub fn group_summary(&self, devices: &[usize]) -> GroupSummary {
+        let members = || devices.iter().filter_map(|&index| self.devices.get(index));
+        let readings = || members().filter_map(|d| d.reading.as_ref());
+
+        let temperatures: Vec<f32> = readings().map(|r| r.temperature).collect();
+        GroupSummary {
+            worst_co2_status: readings()
+                .filter(|r| r.co2 > 0 && r.status != Status::Error)
+                .map(|r| r.status)
+                .max(),
+            avg_temperature: (!temperatures.is_empty())
+                .then(|| temperatures.iter().sum::<f32>() / temperatures.len() as f32),
+            connected: members()
+                .filter(|d| matches!(d.status, ConnectionStatus::Connected))
+                .count(),
+        }
+    }
+
+    /// Devices the selection steps through, one entry per visible sidebar
+    /// entry. A collapsed group is a single entry holding all its devices.
+    fn selection_stops(&self) -> Vec<Vec<usize>> {
+        self.sidebar_rows()
+            .into_iter()
+            .filter_map(|row| match row {
+                SidebarRow::Group {
+                    devices,
+                    collapsed: true,
+                    ..
+                } => Some(devices),
+                SidebarRow::Group { .. } => None,
+                SidebarRow::Device(index) => Some(vec![index]),
+            })
+            .collect()
+    }
+
+    /// Handle a click on a sidebar row: select a device or toggle a group.
+    pub fn select_sidebar_row(&mut self, row: usize) {
+        match self.sidebar_rows().into_iter().nth(row) {
+            Some(SidebarRow::Device(index)) => {
+                self.selected_device = index;
+                self.reset_history_scroll();
+            }
+            Some(SidebarRow::Group { name, .. }) => self.toggle_group(&name),
+            None => {}
+        }
+    }
+
+    /// Collapse or expand the group of the selected device.
+    pub fn toggle_selected_group(&mut self) {
+        if self.device_groups.is_empty() {
+            self.push_status_message("No device groups configured".to_string());
+            return;
+        }
+        let Some(device) = self.selected_device() else {
+            return;
+        };
+        let name = self.device_group(device).unwrap_or(UNGROUPED).to_string();
+        self.toggle_group(&name);
+    }
+
+    /// Collapse or expand a sidebar group by name.
+    pub fn toggle_group(&mut self, name: &str) {
+        if self.collapsed_groups.remove(name) {
+            self.push_status_message(format!("Expanded group: {}", name));
+        } else {
+            self.collapsed_groups.insert(name.to_string());
+            self.push_status_message(format!("Collapsed group: {}", name));
         }
     }
 
     /// Select the next device in the list.
     pub fn select_next_device(&mut self) {
-        let filtered = self.filtered_device_indices();
-        if !filtered.is_empty() {
-            let current = filtered
+        let stops = self.selection_stops();
+        if !stops.is_empty() {
+            let current = stops
                 .iter()
-                .position(|&idx| idx == self.selected_device)
+                .position(|stop| stop.contains(&self.selected_device))
                 .unwrap_or(0);
-            self.selected_device = filtered[(current + 1) % filtered.len()];
+            self.selected_device = stops[(current + 1) % stops.len()][0];
             self.reset_history_scroll();
         }
     }
 
     /// Select the previous device in the list.
     pub fn select_previous_device(&mut self) {
-        let filtered = self.filtered_device_indices();
-        if !filtered.is_empty() {
-            let current = filtered
+        let stops = self.selection_stops();
+        if !stops.is_empty() {
+            let current = stops
                 .iter()
-                .position(|&idx| idx == self.selected_device)
+                .position(|stop| stop.contains(&self.selected_device))
                 .unwrap_or(0);
-            self.selected_device = filtered
-                .get(current.checked_sub(1).unwrap_or(filtered.len() - 1))
-                .copied()
-                .unwrap_or(self.selected_device);
+            self.selected_device = stops[current.checked_sub(1).unwrap_or(stops.len() - 1)][0];
             self.reset_history_scroll();
         }
     }
@@ -1779,6 +2080,30 @@ impl App {
         Some(path.to_string_lossy().to_string())
     }
 
+    /// Summarize the visible history as a tab-separated table.
+    pub fn history_summary(&self) -> Option<String> {
+        let device = self.selected_device()?;
+        let filtered: Vec<_> = device
+            .history
+            .iter()
+            .filter(|r| self.filter_matches_record(r))
+            .collect();
+        history_summary_table(
+            device.name.as_deref().unwrap_or(&device.id),
+            self.history_filter.label(),
+            &filtered,
+        )
+    }
+
+    /// Copy text to the system clipboard.
+    pub fn copy_to_clipboard(&mut self, text: String) -> Result<(), arboard::Error> {
+        let clipboard = match &mut self.clipboard {
+            Some(clipboard) => clipboard,
+            None => self.clipboard.insert(arboard::Clipboard::new()?),
+        };
+        clipboard.set_text(text)
+    }
+
     /// Toggle export format between CSV and JSON.
     pub fn toggle_export_format(&mut self) {
         self.export_format = self.export_format.toggle();
@@ -1868,10 +2193,12 @@ impl App {
 
         if should_refresh {
             self.last_auto_refresh = Some(now);
-            // Return IDs of all connected devices
+            // Return IDs of all connected devices, except those already kept
+            // up to date by advertisements
             self.devices
                 .iter()
                 .filter(|d| d.status == ConnectionStatus::Connected)
+                .filter(|d| !(self.passive_mode && d.is_advertising()))
                 .map(|d| d.id.clone())
                 .collect()
         } else {
@@ -2064,3 +2391,114 @@ impl App {
             .and_then(|i| self.devices.get(i))
     }
 }
+
+/// Build a min/avg/max table of `records`, tab-separated so it pastes into
+/// spreadsheets as cells.
+///
+/// Metrics the device does not measure are left out. Returns `None` when
+/// there are no records.
+pub(crate) fn history_summary_table(
+    device: &str,
+    filter: &str,
+    records: &[&HistoryRecord],
+) -> Option<String> {
+    use std::fmt::Write;
+
+    let first = records.iter().map(|r| r.timestamp).min()?;
+    let last = records.iter().map(|r| r.timestamp).max()?;
+    let format = time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]");
+
+    let mut table = format!(
+        "{device} - {filter} ({} records, {} to {})\nMetric\tMin\tAvg\tMax\n",
+        records.len(),
+        first.format(format).unwrap_or_default(),
+        last.format(format).unwrap_or_default(),
+    );
+    let mut row = |name: &str, precision: usize, values: Vec<f64>| {
+        if values.is_empty() || values.iter().all(|&v| v == 0.0) {
+            return;
+        }
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let avg = values.iter().sum::<f64>() / values.len() as f64;
+        let _ = writeln!(
+            table,
+            "{name}\t{min:.precision$}\t{avg:.precision$}\t{max:.precision$}"
+        );
+    };
+
+    row(
+        "CO2 (ppm)",
+        0,
+        records.iter().map(|r| f64::from(r.co2)).collect(),
+    );
+    row(
+        "Temperature (°C)",
+        1,
+        records.iter().map(|r| f64::from(r.temperature)).collect(),
+    );
+    row(
+        "Humidity (%)",
+        0,
+        records.iter().map(|r| f64::from(r.humidity)).collect(),
+    );
+    row(
+        "Pressure (hPa)",
+        1,
+        records.iter().map(|r| f64::from(r.pressure)).collect(),
+    );
+    row(
+        "Radon (Bq/m³)",
+        0,
+        records
+            .iter()
+            .filter_map(|r| r.radon.map(f64::from))
+            .collect(),
+    );
+    row(
+        "Radiation (µSv/h)",
+        3,
+        records
+            .iter()
+            .filter_map(|r| r.radiation_rate.map(f64::from))
+            .collect(),
+    );
+
+    Some(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_radon_averages() {
+        let now = time::OffsetDateTime::now_utc();
+        let record = |hours_ago: i64, radon: u32| {
+            HistoryRecord::builder()
+                .timestamp(now - time::Duration::hours(hours_ago))
+                .radon(radon)
+                .build()
+        };
+        let history = vec![record(1, 100), record(48, 200), record(24 * 20, 300)];
+
+        // Computed from history when the device reports nothing
+        let reading = CurrentReading::builder().radon(100).build();
+        assert_eq!(
+            calculate_radon_averages(&reading, &history),
+            (Some(100), Some(150), Some(200))
+        );
+        assert_eq!(calculate_radon_averages(&reading, &[]), (None, None, None));
+
+        // Device-reported averages win over history
+        let reading = CurrentReading::builder()
+            .radon(100)
+            .radon_avg_24h(90)
+            .radon_avg_30d(120)
+            .build();
+        assert_eq!(
+            calculate_radon_averages(&reading, &history),
+            (Some(90), Some(150), Some(120))
+        );
+    }
+}
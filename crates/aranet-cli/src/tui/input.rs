@@ -4,30 +4,16 @@
 //! user interface. It translates keyboard events into high-level actions
 //! and applies those actions to the application state.
 //!
-//! # Key Bindings
-//!
-//! | Key       | Action            |
-//! |-----------|-------------------|
-//! | `q`       | Quit              |
-//! | `s`       | Scan              |
-//! | `r`       | Refresh           |
-//! | `c`       | Connect           |
-//! | `d`       | Disconnect        |
-//! | `y`       | Sync history      |
-//! | `↓` / `j` | Select next       |
-//! | `↑` / `k` | Select previous   |
-//! | `Tab` / `l` | Next tab        |
-//! | `BackTab` / `h` | Previous tab |
-//! | `?`       | Toggle help       |
-//! | `D`       | Do Not Disturb    |
-//! | `F`       | Toggle export fmt |
+//! Key bindings come from the active [`Keymap`]; see the
+//! [`keymap`](super::keymap) module for the defaults and presets.
 
 use std::time::Duration;
 
-use crossterm::event::{KeyCode, MouseButton, MouseEvent, MouseEventKind};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use tokio::sync::mpsc;
 
 use super::app::{App, ConnectionStatus, HistoryFilter, PendingAction, Tab, Theme};
+use super::keymap::Keymap;
 use super::messages::Command;
 
 /// User actions that can be triggered by keyboard input.
@@ -77,10 +63,14 @@ pub enum Action {
     ChangeSetting,
     /// Export history to CSV file.
     ExportHistory,
+    /// Copy a summary of the visible history to the clipboard.
+    CopyHistorySummary,
     /// Toggle alert history view.
     ToggleAlertHistory,
     /// Cycle device filter.
     CycleDeviceFilter,
+    /// Collapse or expand the selected device's sidebar group.
+    ToggleGroup,
     /// Toggle sidebar visibility.
     ToggleSidebar,
     /// Toggle sidebar width.
@@ -125,17 +115,20 @@ pub enum Action {
     ToggleSmartHome,
     /// Toggle Do Not Disturb mode.
     ToggleDoNotDisturb,
+    /// Toggle passive (advertisement-based) monitoring.
+    TogglePassiveMode,
     /// Toggle export format (CSV/JSON).
     ToggleExportFormat,
     /// No action (unrecognized key).
     None,
 }
 
-/// Map a key code to an action.
+/// Map a key event to an action.
 ///
 /// # Arguments
 ///
-/// * `key` - The key code from a keyboard event
+/// * `keymap` - The active key bindings
+/// * `key` - The keyboard event
 /// * `editing_text` - Whether the user is currently editing text input
 /// * `has_pending_confirmation` - Whether there is a pending confirmation dialog
 ///
@@ -143,10 +136,15 @@ pub enum Action {
 ///
 /// The corresponding action for the key, or [`Action::None`] if the key
 /// is not mapped to any action.
-pub fn handle_key(key: KeyCode, editing_text: bool, has_pending_confirmation: bool) -> Action {
+pub fn handle_key(
+    keymap: &Keymap,
+    key: KeyEvent,
+    editing_text: bool,
+    has_pending_confirmation: bool,
+) -> Action {
     // If editing text, handle text input specially
     if editing_text {
-        return match key {
+        return match key.code {
             KeyCode::Enter => Action::TextSubmit,
             KeyCode::Esc => Action::TextCancel,
             KeyCode::Backspace => Action::TextBackspace,
@@ -157,60 +155,14 @@ pub fn handle_key(key: KeyCode, editing_text: bool, has_pending_confirmation: bo
 
     // When a confirmation dialog is active, only handle Y/N keys
     if has_pending_confirmation {
-        return match key {
+        return match key.code {
             KeyCode::Char('y') | KeyCode::Char('Y') => Action::Confirm,
             KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => Action::Cancel,
             _ => Action::None,
         };
     }
 
-    match key {
-        KeyCode::Char('q') => Action::Quit,
-        KeyCode::Char('s') => Action::Scan,
-        KeyCode::Char('r') => Action::Refresh,
-        KeyCode::Char('c') => Action::Connect,
-        KeyCode::Char('C') => Action::ConnectAll,
-        KeyCode::Char('d') => Action::Disconnect,
-        KeyCode::Char('S') | KeyCode::Char('y') => Action::SyncHistory,
-        KeyCode::Down | KeyCode::Char('j') => Action::SelectNext,
-        KeyCode::Up | KeyCode::Char('k') => Action::SelectPrevious,
-        KeyCode::Tab | KeyCode::Char('l') => Action::NextTab,
-        KeyCode::BackTab | KeyCode::Char('h') => Action::PreviousTab,
-        KeyCode::Char('?') => Action::ToggleHelp,
-        KeyCode::Char('L') => Action::ToggleLogging,
-        KeyCode::Char('b') => Action::ToggleBell,
-        KeyCode::Char('n') => Action::EditAlias,
-        KeyCode::Esc => Action::DismissAlert,
-        KeyCode::PageUp => Action::ScrollUp,
-        KeyCode::PageDown => Action::ScrollDown,
-        KeyCode::Char('0') => Action::SetHistoryFilter(HistoryFilter::All),
-        KeyCode::Char('1') => Action::SetHistoryFilter(HistoryFilter::Today),
-        KeyCode::Char('2') => Action::SetHistoryFilter(HistoryFilter::Last24Hours),
-        KeyCode::Char('3') => Action::SetHistoryFilter(HistoryFilter::Last7Days),
-        KeyCode::Char('4') => Action::SetHistoryFilter(HistoryFilter::Last30Days),
-        KeyCode::Char('+') | KeyCode::Char('=') => Action::IncreaseThreshold,
-        KeyCode::Char('-') | KeyCode::Char('_') => Action::DecreaseThreshold,
-        KeyCode::Enter => Action::ChangeSetting,
-        KeyCode::Char('e') => Action::ExportHistory,
-        KeyCode::Char('a') => Action::ToggleAlertHistory,
-        KeyCode::Char('f') => Action::CycleDeviceFilter,
-        KeyCode::Char('[') => Action::ToggleSidebar,
-        KeyCode::Char(']') => Action::ToggleSidebarWidth,
-        KeyCode::Char('g') => Action::ToggleChart,
-        KeyCode::Char('A') => Action::ToggleStickyAlerts,
-        KeyCode::Char('v') => Action::ToggleComparison,
-        KeyCode::Char('<') => Action::PrevComparisonDevice,
-        KeyCode::Char('>') => Action::NextComparisonDevice,
-        KeyCode::Char('E') => Action::ShowErrorDetails,
-        KeyCode::Char('t') => Action::ToggleTheme,
-        KeyCode::Char('T') => Action::ToggleChartTemp,
-        KeyCode::Char('H') => Action::ToggleChartHumidity,
-        KeyCode::Char('B') => Action::ToggleBleRange,
-        KeyCode::Char('I') => Action::ToggleSmartHome,
-        KeyCode::Char('D') => Action::ToggleDoNotDisturb,
-        KeyCode::Char('F') => Action::ToggleExportFormat,
-        _ => Action::None,
-    }
+    keymap.action(&key)
 }
 
 /// Handle mouse events and return corresponding action.
@@ -305,7 +257,7 @@ fn apply_navigation_action(app: &mut App, action: Action) -> Option<Command> {
             // Device list is in the left sidebar (x < ~25, y > 4)
             else if x < 25 && y > 4 {
                 let device_row = (y as usize).saturating_sub(5);
-                app.select_filtered_row(device_row);
+                app.select_sidebar_row(device_row);
             }
             None
         }
@@ -412,10 +364,25 @@ fn apply_device_action(app: &mut App, action: Action) -> Option<Command> {
             }
             None
         }
+        Action::CopyHistorySummary => {
+            let message = match app.history_summary() {
+                Some(summary) => match app.copy_to_clipboard(summary) {
+                    Ok(()) => "Copied history summary to clipboard".to_string(),
+                    Err(e) => format!("Clipboard unavailable: {}", e),
+                },
+                None => "No history to copy".to_string(),
+            };
+            app.push_status_message(message);
+            None
+        }
         Action::CycleDeviceFilter => {
             app.cycle_device_filter();
             None
         }
+        Action::ToggleGroup => {
+            app.toggle_selected_group();
+            None
+        }
         _ => None,
     }
 }
@@ -464,9 +431,10 @@ fn apply_settings_action(app: &mut App, action: Action) -> Option<Command> {
                         app.push_status_message("Service not reachable".to_string());
                     }
                 } else {
-                    app.push_status_message(
-                        "Service status unknown - press 'r' to refresh".to_string(),
-                    );
+                    app.push_status_message(format!(
+                        "Service status unknown - press '{}' to refresh",
+                        app.keymap.label(Action::Refresh)
+                    ));
                 }
                 None
             } else if app.active_tab == Tab::Settings && app.selected_setting == 0 {
@@ -517,6 +485,7 @@ fn apply_settings_action(app: &mut App, action: Action) -> Option<Command> {
             app.toggle_do_not_disturb();
             None
         }
+        Action::TogglePassiveMode => Some(app.toggle_passive_mode()),
         Action::ToggleExportFormat => {
             app.toggle_export_format();
             None
@@ -667,7 +636,9 @@ pub fn apply_action(
         | Action::TextSubmit
         | Action::TextCancel
         | Action::ExportHistory
-        | Action::CycleDeviceFilter => apply_device_action(app, action),
+        | Action::CopyHistorySummary
+        | Action::CycleDeviceFilter
+        | Action::ToggleGroup => apply_device_action(app, action),
 
         // Settings: thresholds, intervals, toggles for logging/bell/alerts/BLE/smart home
         Action::IncreaseThreshold
@@ -680,6 +651,7 @@ pub fn apply_action(
         | Action::ToggleBleRange
         | Action::ToggleSmartHome
         | Action::ToggleDoNotDisturb
+        | Action::TogglePassiveMode
         | Action::ToggleExportFormat => apply_settings_action(app, action),
 
         // View: theme, help, sidebar, chart, comparison, error details
@@ -0,0 +1,466 @@
+//! Configurable key bindings for the TUI.
+//!
+//! A [`Keymap`] maps key presses to [`Action`]s. It starts from a preset
+//! (`default`, `vim` or `emacs`) and applies per-action overrides from the
+//! `[keymap]` table of the config file:
+//!
+//! ```toml
+//! [keymap]
+//! preset = "emacs"
+//!
+//! [keymap.bindings]
+//! sync_history = ["S"]
+//! toggle_theme = []
+//! ```
+//!
+//! The help overlay and status bar hints take their key labels from the
+//! active keymap, so they always show what the keys actually do.
+
+use std::fmt;
+
+use anyhow::{Result, bail};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::app::HistoryFilter;
+use super::input::Action;
+use crate::config::KeymapConfig;
+
+/// Bindable actions with their config names and default keys.
+const ACTIONS: &[(&str, Action, &[&str])] = &[
+    ("quit", Action::Quit, &["q", "Ctrl+c"]),
+    ("scan", Action::Scan, &["s"]),
+    ("refresh", Action::Refresh, &["r"]),
+    ("connect", Action::Connect, &["c"]),
+    ("connect_all", Action::ConnectAll, &["C"]),
+    ("disconnect", Action::Disconnect, &["d"]),
+    ("sync_history", Action::SyncHistory, &["S", "y"]),
+    ("select_next", Action::SelectNext, &["j", "Down"]),
+    ("select_previous", Action::SelectPrevious, &["k", "Up"]),
+    ("next_tab", Action::NextTab, &["Tab", "l"]),
+    ("previous_tab", Action::PreviousTab, &["Shift+Tab", "h"]),
+    ("scroll_up", Action::ScrollUp, &["PgUp"]),
+    ("scroll_down", Action::ScrollDown, &["PgDn"]),
+    ("toggle_help", Action::ToggleHelp, &["?"]),
+    ("toggle_logging", Action::ToggleLogging, &["L"]),
+    ("toggle_bell", Action::ToggleBell, &["b"]),
+    ("edit_alias", Action::EditAlias, &["n"]),
+    ("dismiss_alert", Action::DismissAlert, &["Esc"]),
+    (
+        "filter_all",
+        Action::SetHistoryFilter(HistoryFilter::All),
+        &["0"],
+    ),
+    (
+        "filter_today",
+        Action::SetHistoryFilter(HistoryFilter::Today),
+        &["1"],
+    ),
+    (
+        "filter_24h",
+        Action::SetHistoryFilter(HistoryFilter::Last24Hours),
+        &["2"],
+    ),
+    (
+        "filter_7d",
+        Action::SetHistoryFilter(HistoryFilter::Last7Days),
+        &["3"],
+    ),
+    (
+        "filter_30d",
+        Action::SetHistoryFilter(HistoryFilter::Last30Days),
+        &["4"],
+    ),
+    ("increase_threshold", Action::IncreaseThreshold, &["+", "="]),
+    ("decrease_threshold", Action::DecreaseThreshold, &["-", "_"]),
+    ("change_setting", Action::ChangeSetting, &["Enter"]),
+    ("export_history", Action::ExportHistory, &["e"]),
+    ("copy_history_summary", Action::CopyHistorySummary, &["Y"]),
+    ("toggle_alert_history", Action::ToggleAlertHistory, &["a"]),
+    ("cycle_device_filter", Action::CycleDeviceFilter, &["f"]),
+    ("toggle_group", Action::ToggleGroup, &["z"]),
+    ("toggle_sidebar", Action::ToggleSidebar, &["["]),
+    ("toggle_sidebar_width", Action::ToggleSidebarWidth, &["]"]),
+    ("toggle_chart", Action::ToggleChart, &["g"]),
+    ("toggle_sticky_alerts", Action::ToggleStickyAlerts, &["A"]),
+    ("toggle_comparison", Action::ToggleComparison, &["v"]),
+    (
+        "previous_comparison_device",
+        Action::PrevComparisonDevice,
+        &["<"],
+    ),
+    (
+        "next_comparison_device",
+        Action::NextComparisonDevice,
+        &[">"],
+    ),
+    ("show_error_details", Action::ShowErrorDetails, &["E"]),
+    ("toggle_theme", Action::ToggleTheme, &["t"]),
+    ("toggle_chart_temp", Action::ToggleChartTemp, &["T"]),
+    ("toggle_chart_humidity", Action::ToggleChartHumidity, &["H"]),
+    ("toggle_ble_range", Action::ToggleBleRange, &["B"]),
+    ("toggle_smart_home", Action::ToggleSmartHome, &["I"]),
+    ("toggle_do_not_disturb", Action::ToggleDoNotDisturb, &["D"]),
+    ("toggle_passive_mode", Action::TogglePassiveMode, &["p"]),
+    ("toggle_export_format", Action::ToggleExportFormat, &["F"]),
+];
+
+/// Vim preset: the defaults plus half-page scrolling.
+const VIM: &[(&str, &[&str])] = &[
+    ("scroll_up", &["Ctrl+u", "PgUp"]),
+    ("scroll_down", &["Ctrl+d", "PgDn"]),
+];
+
+/// Emacs preset: Ctrl/Alt motion keys instead of `hjkl`.
+const EMACS: &[(&str, &[&str])] = &[
+    ("select_next", &["Ctrl+n", "Down"]),
+    ("select_previous", &["Ctrl+p", "Up"]),
+    ("next_tab", &["Ctrl+f", "Tab"]),
+    ("previous_tab", &["Ctrl+b", "Shift+Tab"]),
+    ("scroll_up", &["Alt+v", "PgUp"]),
+    ("scroll_down", &["Ctrl+v", "PgDn"]),
+    ("dismiss_alert", &["Ctrl+g", "Esc"]),
+];
+
+/// A key press with the modifiers that matter for bindings.
+///
+/// Shift is part of the character for letters and symbols (`S`, not
+/// `Shift+s`), and `Shift+Tab` is [`KeyCode::BackTab`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    /// The key itself.
+    pub code: KeyCode,
+    /// Whether Ctrl is held.
+    pub ctrl: bool,
+    /// Whether Alt is held.
+    pub alt: bool,
+}
+
+impl KeyChord {
+    /// The chord for a key event.
+    pub fn from_event(event: &KeyEvent) -> Self {
+        let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = event.modifiers.contains(KeyModifiers::ALT);
+        // Terminals disagree on the case of Ctrl+letter
+        let code = match event.code {
+            KeyCode::Char(c) if ctrl => KeyCode::Char(c.to_ascii_lowercase()),
+            code => code,
+        };
+        Self { code, ctrl, alt }
+    }
+
+    /// Parse a key as written in the config, e.g. `q`, `Ctrl+n`, `PgDn`.
+    ///
+    /// Returns `None` for unknown key names.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut rest = spec.trim();
+        let mut ctrl = false;
+        let mut alt = false;
+        let mut shift = false;
+        // A lone `+` is the plus key, not a separator
+        while rest.len() > 1 {
+            let Some((modifier, key)) = rest.split_once('+') else {
+                break;
+            };
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => ctrl = true,
+                "alt" | "meta" => alt = true,
+                "shift" => shift = true,
+                _ => return None,
+            }
+            rest = key;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if ctrl => KeyCode::Char(c.to_ascii_lowercase()),
+            (Some(c), None) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "tab" if shift => KeyCode::BackTab,
+                _ if shift => return None,
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "del" | "delete" => KeyCode::Delete,
+                "ins" | "insert" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pgup" | "pageup" => KeyCode::PageUp,
+                "pgdn" | "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+            },
+        };
+        Some(Self { code, ctrl, alt })
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            f.write_str("Ctrl+")?;
+        }
+        if self.alt {
+            f.write_str("Alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) if self.ctrl || self.alt => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::BackTab => f.write_str("Shift+Tab"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Delete => f.write_str("Del"),
+            KeyCode::Insert => f.write_str("Ins"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// Key bindings for every bindable action.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    /// Keys per action, in the order of [`ACTIONS`].
+    keys: Vec<Vec<KeyChord>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let keys = ACTIONS
+            .iter()
+            .map(|(_, _, keys)| keys.iter().filter_map(|k| KeyChord::parse(k)).collect())
+            .collect();
+        Self { keys }
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from the `[keymap]` config table.
+    pub fn from_config(config: &KeymapConfig) -> Result<Self> {
+        let mut keymap = Self::default();
+        let preset = match config.preset.as_deref().map(str::to_ascii_lowercase) {
+            None => &[][..],
+            Some(name) => match name.as_str() {
+                "default" => &[][..],
+                "vim" => VIM,
+                "emacs" => EMACS,
+                _ => bail!(
+                    "Unknown keymap preset `{}`; expected default, vim or emacs",
+                    name
+                ),
+            },
+        };
+        for (name, keys) in preset {
+            let keys: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+            keymap.bind(name, &keys)?;
+        }
+        for (name, keys) in &config.bindings {
+            keymap.bind(name, keys)?;
+        }
+        Ok(keymap)
+    }
+
+    /// Replace the keys of the action called `name`.
+    ///
+    /// The keys are taken away from any other action they were bound to.
+    fn bind(&mut self, name: &str, keys: &[String]) -> Result<()> {
+        let Some(index) = ACTIONS.iter().position(|(n, _, _)| *n == name) else {
+            bail!("Unknown action `{}` in keymap.bindings", name);
+        };
+        let mut chords = Vec::with_capacity(keys.len());
+        for key in keys {
+            match KeyChord::parse(key) {
+                Some(chord) => chords.push(chord),
+                None => bail!("Invalid key `{}` for `{}` in keymap.bindings", key, name),
+            }
+        }
+        for bound in &mut self.keys {
+            bound.retain(|chord| !chords.contains(chord));
+        }
+        self.keys[index] = chords;
+        Ok(())
+    }
+
+    /// The action bound to a key event, or [`Action::None`].
+    pub fn action(&self, event: &KeyEvent) -> Action {
+        let chord = KeyChord::from_event(event);
+        ACTIONS
+            .iter()
+            .zip(&self.keys)
+            .find(|(_, keys)| keys.contains(&chord))
+            .map_or(Action::None, |((_, action, _), _)| *action)
+    }
+
+    /// The keys bound to an action, in binding order.
+    pub fn keys(&self, action: Action) -> &[KeyChord] {
+        ACTIONS
+            .iter()
+            .position(|(_, a, _)| *a == action)
+            .map_or(&[], |index| &self.keys[index])
+    }
+
+    /// All keys of an action, e.g. `S/y`, or `-` when it is unbound.
+    pub fn label(&self, action: Action) -> String {
+        join_keys(self.keys(action).iter())
+    }
+
+    /// The first key of each action, e.g. `j/k` for next/previous.
+    pub fn primary_label(&self, actions: &[Action]) -> String {
+        join_keys(actions.iter().filter_map(|a| self.keys(*a).first()))
+    }
+}
+
+fn join_keys<'a>(keys: impl Iterator<Item = &'a KeyChord>) -> String {
+    let label = keys.map(|k| k.to_string()).collect::<Vec<_>>().join("/");
+    if label.is_empty() {
+        "-".to_string()
+    } else {
+        label
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_default_keys_parse() {
+        for (name, _, keys) in ACTIONS {
+            for k in *keys {
+                assert!(KeyChord::parse(k).is_some(), "{name}: {k}");
+            }
+        }
+        for (name, keys) in VIM.iter().chain(EMACS) {
+            assert!(ACTIONS.iter().any(|(n, _, _)| n == name), "{name}");
+            for k in *keys {
+                assert!(KeyChord::parse(k).is_some(), "{name}: {k}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let chord = KeyChord::parse("Ctrl+N").unwrap();
+        assert_eq!(chord.code, KeyCode::Char('n'));
+        assert!(chord.ctrl);
+        assert_eq!(chord.to_string(), "Ctrl+N");
+        assert_eq!(KeyChord::parse("+").unwrap().code, KeyCode::Char('+'));
+        assert_eq!(KeyChord::parse("Alt++").unwrap().to_string(), "Alt++");
+        assert_eq!(KeyChord::parse("shift+tab").unwrap().code, KeyCode::BackTab);
+        assert_eq!(KeyChord::parse("Shift+s").unwrap().code, KeyCode::Char('S'));
+        assert_eq!(KeyChord::parse("F5").unwrap().code, KeyCode::F(5));
+        assert_eq!(KeyChord::parse("pgdn").unwrap().to_string(), "PgDn");
+        assert!(KeyChord::parse("Hyper+x").is_none());
+        assert!(KeyChord::parse("Shift+Enter").is_none());
+        assert!(KeyChord::parse("nope").is_none());
+    }
+
+    #[test]
+    fn test_default_keymap() {
+        let keymap = Keymap::default();
+        let none = KeyModifiers::NONE;
+        assert_eq!(keymap.action(&key(KeyCode::Char('q'), none)), Action::Quit);
+        assert_eq!(
+            keymap.action(&key(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Action::Quit
+        );
+        assert_eq!(
+            keymap.action(&key(KeyCode::Char('S'), KeyModifiers::SHIFT)),
+            Action::SyncHistory
+        );
+        assert_eq!(
+            keymap.action(&key(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            Action::PreviousTab
+        );
+        assert_eq!(keymap.action(&key(KeyCode::Char('x'), none)), Action::None);
+        assert_eq!(keymap.label(Action::SyncHistory), "S/y");
+        assert_eq!(
+            keymap.primary_label(&[Action::SelectNext, Action::SelectPrevious]),
+            "j/k"
+        );
+    }
+
+    #[test]
+    fn test_presets_and_overrides() {
+        let config = KeymapConfig {
+            preset: Some("emacs".to_string()),
+            bindings: BTreeMap::from([
+                ("toggle_theme".to_string(), vec![]),
+                ("scan".to_string(), vec!["t".to_string()]),
+                ("refresh".to_string(), vec!["s".to_string()]),
+            ]),
+        };
+        let keymap = Keymap::from_config(&config).unwrap();
+        let ctrl = KeyModifiers::CONTROL;
+        let none = KeyModifiers::NONE;
+        assert_eq!(
+            keymap.action(&key(KeyCode::Char('n'), ctrl)),
+            Action::SelectNext
+        );
+        assert_eq!(keymap.action(&key(KeyCode::Char('j'), none)), Action::None);
+        assert_eq!(keymap.action(&key(KeyCode::Char('t'), none)), Action::Scan);
+        assert_eq!(
+            keymap.action(&key(KeyCode::Char('s'), none)),
+            Action::Refresh
+        );
+        assert_eq!(keymap.label(Action::ToggleTheme), "-");
+
+        let vim = Keymap::from_config(&KeymapConfig {
+            preset: Some("vim".to_string()),
+            ..KeymapConfig::default()
+        })
+        .unwrap();
+        assert_eq!(
+            vim.action(&key(KeyCode::Char('d'), ctrl)),
+            Action::ScrollDown
+        );
+        assert_eq!(
+            vim.action(&key(KeyCode::Char('d'), none)),
+            Action::Disconnect
+        );
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let preset = KeymapConfig {
+            preset: Some("nano".to_string()),
+            ..KeymapConfig::default()
+        };
+        assert!(Keymap::from_config(&preset).is_err());
+
+        let action = KeymapConfig {
+            bindings: BTreeMap::from([("fly".to_string(), vec!["f".to_string()])]),
+            ..KeymapConfig::default()
+        };
+        let err = Keymap::from_config(&action).unwrap_err().to_string();
+        assert!(err.contains("`fly`"));
+
+        let key = KeymapConfig {
+            bindings: BTreeMap::from([("quit".to_string(), vec!["Ctrl+Nope".to_string()])]),
+            ..KeymapConfig::default()
+        };
+        assert!(Keymap::from_config(&key).is_err());
+    }
+}
@@ -11,6 +11,7 @@
 pub mod app;
 pub mod errors;
 pub mod input;
+pub mod keymap;
 pub mod messages;
 pub mod ui;
 pub mod worker;
@@ -34,7 +35,9 @@ use tracing::info;
 
 use aranet_store::default_db_path;
 
-use crate::config::Config;
+use crate::config::{Config, resolve_alias};
+use crate::i18n::{self, Language};
+use keymap::Keymap;
 
 /// Set up the terminal for TUI rendering.
 ///
@@ -67,7 +70,8 @@ pub fn restore_terminal() -> Result<()> {
 /// 4. Runs the main event loop
 /// 5. Ensures graceful shutdown
 pub async fn run() -> Result<()> {
-    let config = Config::load_or_default()?;
+    let config = Config::load_effective()?;
+    i18n::set_language(Language::resolve(config.language.as_deref()));
     let service_url = config.gui.service_url.clone();
     let service_api_key = config.gui.service_api_key.clone();
 
@@ -95,6 +99,15 @@ pub async fn run() -> Result<()> {
 
     // Create the application
     let mut app = App::new(cmd_tx.clone(), event_rx, service_url, service_api_key);
+    app.keymap = Keymap::from_config(&config.keymap)?;
+    app.device_groups = config
+        .groups
+        .iter()
+        .map(|(name, members)| {
+            let members = members.iter().map(|m| resolve_alias(m, &config)).collect();
+            (name.clone(), members)
+        })
+        .collect();
 
     // Set up terminal
     let mut terminal = setup_terminal()?;
@@ -139,16 +152,15 @@ async fn run_event_loop(
         // Poll for keyboard and mouse events with timeout
         if event::poll(Duration::from_millis(100))? {
             match event::read()? {
-                Event::Key(key) => {
-                    if key.kind == KeyEventKind::Press {
-                        let action = input::handle_key(
-                            key.code,
-                            app.editing_alias,
-                            app.pending_confirmation.is_some(),
-                        );
-                        if let Some(cmd) = input::apply_action(app, action, command_tx) {
-                            let _ = command_tx.try_send(cmd);
-                        }
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    let action = input::handle_key(
+                        &app.keymap,
+                        key,
+                        app.editing_alias,
+                        app.pending_confirmation.is_some(),
+                    );
+                    if let Some(cmd) = input::apply_action(app, action, command_tx) {
+                        let _ = command_tx.try_send(cmd);
                     }
                 }
                 Event::Mouse(mouse_event) => {
@@ -195,52 +207,149 @@ mod tests {
 
     #[test]
     fn test_input_handling_quit() {
-        let action = input::handle_key(KeyCode::Char('q'), false, false);
+        let action = input::handle_key(&Keymap::default(), KeyCode::Char('q').into(), false, false);
         assert_eq!(action, input::Action::Quit);
     }
 
     #[test]
     fn test_input_handling_scan() {
-        let action = input::handle_key(KeyCode::Char('s'), false, false);
+        let action = input::handle_key(&Keymap::default(), KeyCode::Char('s').into(), false, false);
         assert_eq!(action, input::Action::Scan);
     }
 
     #[test]
     fn test_input_handling_connect_all() {
         // Lowercase 'c' connects selected device
-        let action = input::handle_key(KeyCode::Char('c'), false, false);
+        let action = input::handle_key(&Keymap::default(), KeyCode::Char('c').into(), false, false);
         assert_eq!(action, input::Action::Connect);
 
         // Uppercase 'C' connects all devices
-        let action = input::handle_key(KeyCode::Char('C'), false, false);
+        let action = input::handle_key(&Keymap::default(), KeyCode::Char('C').into(), false, false);
         assert_eq!(action, input::Action::ConnectAll);
     }
 
     #[test]
     fn test_input_handling_other_keys() {
-        let action = input::handle_key(KeyCode::Char('a'), false, false);
+        let action = input::handle_key(&Keymap::default(), KeyCode::Char('a').into(), false, false);
         // 'a' is now mapped to ToggleAlertHistory
         assert_eq!(action, input::Action::ToggleAlertHistory);
 
         // Enter is now mapped to ChangeSetting
-        let action = input::handle_key(KeyCode::Enter, false, false);
+        let action = input::handle_key(&Keymap::default(), KeyCode::Enter.into(), false, false);
         assert_eq!(action, input::Action::ChangeSetting);
     }
 
+    #[test]
+    fn test_input_handling_copy_history_summary() {
+        let action = input::handle_key(&Keymap::default(), KeyCode::Char('Y').into(), false, false);
+        assert_eq!(action, input::Action::CopyHistorySummary);
+    }
+
+    #[test]
+    fn test_history_summary_table() {
+        let record = |minutes: i64, co2: u16, temperature: f32| aranet_types::HistoryRecord {
+            timestamp: time::macros::datetime!(2026-01-15 08:00 UTC)
+                + time::Duration::minutes(minutes),
+            co2,
+            temperature,
+            pressure: 1010.0,
+            humidity: 40,
+            radon: None,
+            radiation_rate: None,
+            radiation_total: None,
+        };
+        let records = [
+            record(0, 400, 20.0),
+            record(5, 800, 22.0),
+            record(10, 600, 24.0),
+        ];
+        let refs: Vec<_> = records.iter().collect();
+
+        let table = app::history_summary_table("Office", "24h", &refs).unwrap();
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(
+            lines[0],
+            "Office - 24h (3 records, 2026-01-15 08:00 to 2026-01-15 08:10)"
+        );
+        assert_eq!(lines[1], "Metric\tMin\tAvg\tMax");
+        assert_eq!(lines[2], "CO2 (ppm)\t400\t600\t800");
+        assert_eq!(lines[3], "Temperature (°C)\t20.0\t22.0\t24.0");
+        // Radon and radiation are not measured, so they are left out
+        assert_eq!(lines.len(), 6);
+
+        assert!(app::history_summary_table("Office", "24h", &[]).is_none());
+    }
+
+    #[test]
+    fn test_input_handling_passive_mode() {
+        let action = input::handle_key(&Keymap::default(), KeyCode::Char('p').into(), false, false);
+        assert_eq!(action, input::Action::TogglePassiveMode);
+    }
+
     #[test]
     fn test_input_handling_confirmation() {
         // When confirmation is pending, only Y/N keys work
-        let action = input::handle_key(KeyCode::Char('y'), false, true);
+        let action = input::handle_key(&Keymap::default(), KeyCode::Char('y').into(), false, true);
         assert_eq!(action, input::Action::Confirm);
 
-        let action = input::handle_key(KeyCode::Char('n'), false, true);
+        let action = input::handle_key(&Keymap::default(), KeyCode::Char('n').into(), false, true);
         assert_eq!(action, input::Action::Cancel);
 
-        let action = input::handle_key(KeyCode::Esc, false, true);
+        let action = input::handle_key(&Keymap::default(), KeyCode::Esc.into(), false, true);
         assert_eq!(action, input::Action::Cancel);
 
         // Other keys are ignored during confirmation
-        let action = input::handle_key(KeyCode::Char('q'), false, true);
+        let action = input::handle_key(&Keymap::default(), KeyCode::Char('q').into(), false, true);
         assert_eq!(action, input::Action::None);
     }
+
+    #[test]
+    fn test_sidebar_groups_collapse_and_summary() {
+        use app::{DeviceState, SidebarRow, UNGROUPED};
+        use aranet_types::{CurrentReading, Status};
+
+        let (cmd_tx, _cmd_rx) = mpsc::channel(1);
+        let (_event_tx, event_rx) = mpsc::channel(1);
+        let mut app = App::new(cmd_tx, event_rx, "http://localhost:8080".into(), None);
+        for (id, co2, temperature, status) in [
+            ("AA:01", 600, 20.0, Status::Green),
+            ("AA:02", 1600, 22.0, Status::Red),
+            ("AA:03", 700, 19.0, Status::Green),
+        ] {
+            let mut device = DeviceState::new(id.to_string());
+            device.reading = Some(
+                CurrentReading::builder()
+                    .co2(co2)
+                    .temperature(temperature)
+                    .status(status)
+                    .build(),
+            );
+            app.devices.push(device);
+        }
+        app.devices[1].alias = Some("Kitchen".to_string());
+        app.device_groups
+            .insert("Office".to_string(), vec!["aa:01".into(), "kitchen".into()]);
+
+        let rows = app.sidebar_rows();
+        assert_eq!(rows.len(), 5);
+        assert!(matches!(&rows[0], SidebarRow::Group { name, devices, .. }
+            if name == "Office" && devices == &[0, 1]));
+        assert!(matches!(&rows[3], SidebarRow::Group { name, .. } if name == UNGROUPED));
+
+        let summary = app.group_summary(&[0, 1]);
+        assert_eq!(summary.worst_co2_status, Some(Status::Red));
+        assert_eq!(summary.avg_temperature, Some(21.0));
+
+        // A collapsed group is a single stop when navigating
+        app.toggle_selected_group();
+        assert_eq!(app.sidebar_rows().len(), 3);
+        app.select_next_device();
+        assert_eq!(app.selected_device, 2);
+        app.select_next_device();
+        assert_eq!(app.selected_device, 0);
+
+        // Clicking the header expands the group again
+        app.select_sidebar_row(0);
+        assert_eq!(app.sidebar_rows().len(), 5);
+    }
 }
@@ -70,8 +70,6 @@ pub fn radon_color(theme: &AppTheme, bq_m3: u32) -> Color {
 /// - Yellow status → Yellow color
 /// - Red status → Red color
 /// - Error status → DarkGray color
-// Kept for future use: displaying sensor status indicators in the TUI
-#[allow(dead_code)]
 #[must_use]
 pub fn status_color(theme: &AppTheme, status: &Status) -> Color {
     theme.sensor_status_color(status)
@@ -6,15 +6,16 @@
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Sparkline};
 
+use aranet_core::settings::DeviceSettings;
 use aranet_types::DeviceType;
 
-use super::colors::{battery_color, co2_color, radon_color, signal_strength_display};
+use super::colors::{battery_color, co2_color, radon_color, signal_strength_display, status_color};
 use super::theme::{AppTheme, BORDER_TYPE};
 use super::widgets::{
     co2_trend, convert_radon_for_device, format_radon_for_device, format_temp_for_device,
     radon_unit_for_device, resample_sparkline_data, sparkline_data,
 };
-use crate::tui::app::{App, ConnectionStatus, DeviceFilter, calculate_radon_averages};
+use crate::tui::app::{App, ConnectionStatus, DeviceFilter, SidebarRow, calculate_radon_averages};
 
 /// Create a bordered reading card with status-aware border color.
 fn reading_card(
@@ -388,14 +389,16 @@ fn render_sparkline(
 }
 
 /// Render radon averages line for radon devices.
+///
+/// Each average is colored against the radon action levels.
 fn render_radon_averages(
     frame: &mut Frame,
     area: Rect,
-    device: &crate::tui::app::DeviceState,
+    averages: (Option<u32>, Option<u32>, Option<u32>),
+    settings: Option<&DeviceSettings>,
     theme: &AppTheme,
 ) {
-    let settings = device.settings.as_ref();
-    let (day_avg, week_avg) = calculate_radon_averages(&device.history);
+    let (day_avg, week_avg, month_avg) = averages;
     let radon_unit = radon_unit_for_device(settings);
 
     let mut avg_spans = vec![Span::styled(
@@ -403,27 +406,24 @@ fn render_radon_averages(
         Style::default().fg(theme.text_muted),
     )];
 
-    if let Some(avg) = day_avg {
+    for (label, avg) in [("24h", day_avg), ("7d", week_avg), ("30d", month_avg)] {
+        let Some(avg) = avg else {
+            continue;
+        };
         let avg_display = convert_radon_for_device(avg, settings);
-        avg_spans.push(Span::styled("24h: ", Style::default().fg(theme.text_muted)));
         avg_spans.push(Span::styled(
-            format!("{:.1}", avg_display),
-            Style::default().fg(radon_color(theme, avg)),
+            format!("{}: ", label),
+            Style::default().fg(theme.text_muted),
         ));
-        avg_spans.push(Span::raw("  "));
-    }
-
-    if let Some(avg) = week_avg {
-        let avg_display = convert_radon_for_device(avg, settings);
-        avg_spans.push(Span::styled("7d: ", Style::default().fg(theme.text_muted)));
         avg_spans.push(Span::styled(
             format!("{:.1}", avg_display),
             Style::default().fg(radon_color(theme, avg)),
         ));
+        avg_spans.push(Span::raw("  "));
     }
 
     avg_spans.push(Span::styled(
-        format!(" {}", radon_unit),
+        radon_unit,
         Style::default().fg(theme.text_muted),
     ));
 
@@ -474,9 +474,18 @@ pub(super) fn draw_device_list(frame: &mut Frame, area: Rect, app: &App) {
     };
     let title = format!(" Devices ({}){}  ", filtered.len(), filter_label);
 
-    let items: Vec<ListItem> = filtered
+    let items: Vec<ListItem> = app
+        .sidebar_rows()
         .iter()
-        .map(|device_index| {
+        .map(|row| {
+            let device_index = match row {
+                SidebarRow::Device(index) => index,
+                SidebarRow::Group {
+                    name,
+                    devices,
+                    collapsed,
+                } => return group_header_item(app, &theme, name, devices, *collapsed),
+            };
             let device = &app.devices[*device_index];
             let name = device.display_name().chars().take(18).collect::<String>();
 
@@ -523,6 +532,22 @@ pub(super) fn draw_device_list(frame: &mut Frame, area: Rect, app: &App) {
                 }
             }
 
+            // Advertisement badge and age for passively updated devices
+            if app.passive_mode
+                && let Some(age) = device.advertisement_age()
+            {
+                let color = if device.is_advertising() {
+                    theme.info
+                } else {
+                    theme.text_muted
+                };
+                spans.push(Span::styled(" adv", Style::default().fg(color)));
+                spans.push(Span::styled(
+                    format!(" {}", age),
+                    Style::default().fg(theme.text_muted),
+                ));
+            }
+
             let line = Line::from(spans);
 
             let style = if is_selected {
@@ -571,6 +596,60 @@ pub(super) fn draw_device_list(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(list, area);
 }
 
+/// Sidebar header row for a device group: name, device count, worst CO2
+/// status and average temperature.
+fn group_header_item<'a>(
+    app: &App,
+    theme: &AppTheme,
+    name: &str,
+    devices: &[usize],
+    collapsed: bool,
+) -> ListItem<'a> {
+    let summary = app.group_summary(devices);
+    // A collapsed group stands in for its selected device
+    let is_selected = collapsed && devices.contains(&app.selected_device);
+    let prefix = if is_selected { "> " } else { "  " };
+    let marker = if collapsed { "+" } else { "-" };
+
+    let mut spans = vec![
+        Span::styled(prefix, Style::default().fg(theme.primary)),
+        Span::styled(marker, Style::default().fg(theme.text_muted)),
+        Span::raw(" "),
+        Span::styled(
+            name.chars().take(12).collect::<String>(),
+            Style::default()
+                .fg(theme.text_primary)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(" ({})", devices.len()),
+            Style::default().fg(theme.text_muted),
+        ),
+    ];
+    if let Some(status) = summary.worst_co2_status {
+        spans.push(Span::styled(
+            " ●",
+            Style::default().fg(status_color(theme, &status)),
+        ));
+    }
+    if let Some(temperature) = summary.avg_temperature {
+        let settings = devices
+            .iter()
+            .find_map(|&index| app.devices[index].settings.as_ref());
+        spans.push(Span::styled(
+            format!(" {}", format_temp_for_device(temperature, settings)),
+            Style::default().fg(theme.text_muted),
+        ));
+    }
+
+    let style = if is_selected {
+        theme.selected_style()
+    } else {
+        Style::default()
+    };
+    ListItem::new(Line::from(spans)).style(style)
+}
+
 /// Draw the readings panel for the selected device.
 pub(super) fn draw_readings_panel(frame: &mut Frame, area: Rect, app: &App) {
     let theme = app.app_theme();
@@ -707,7 +786,11 @@ pub(super) fn draw_readings_panel(frame: &mut Frame, area: Rect, app: &App) {
 
     // Device name header with uptime
     let name = device.display_name();
-    let header_text = if let Some(uptime) = device.uptime() {
+    let header_text = if app.passive_mode
+        && let Some(age) = device.advertisement_age()
+    {
+        format!("{} (adv {} ago)", name, age)
+    } else if let Some(uptime) = device.uptime() {
         format!("{} ({})", name, uptime)
     } else {
         name.to_string()
@@ -740,9 +823,18 @@ pub(super) fn draw_readings_panel(frame: &mut Frame, area: Rect, app: &App) {
         render_sparkline(frame, readings_layout[5], device, reading, &theme);
     }
 
-    // Radon averages (for radon devices with history)
-    if matches!(device.device_type, Some(DeviceType::AranetRadon)) && !device.history.is_empty() {
-        render_radon_averages(frame, readings_layout[6], device, &theme);
+    // Radon averages (device-reported, or computed from history)
+    if matches!(device.device_type, Some(DeviceType::AranetRadon)) {
+        let averages = calculate_radon_averages(reading, &device.history);
+        if averages != (None, None, None) {
+            render_radon_averages(
+                frame,
+                readings_layout[6],
+                averages,
+                device.settings.as_ref(),
+                &theme,
+            );
+        }
     }
 
     // Session statistics (if available)
@@ -20,7 +20,9 @@ mod settings;
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph};
 
-use super::app::{App, Tab, Theme};
+use super::app::{App, HistoryFilter, Tab, Theme};
+use super::input::Action;
+use crate::i18n::{tr, tr_args};
 use colors::co2_color;
 use theme::BORDER_TYPE;
 
@@ -100,7 +102,7 @@ pub fn draw(frame: &mut Frame, app: &App) {
 
     // Draw help overlay if active
     if app.show_help {
-        overlays::draw_help_overlay(frame);
+        overlays::draw_help_overlay(frame, app);
     }
 
     // Alert history overlay
@@ -145,7 +147,13 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
     };
     if width >= 22 {
         spans.push(Span::styled(
-            format!(" {}/{} online ", connected, total),
+            format!(
+                " {} ",
+                tr_args(
+                    "header-online",
+                    &[("connected", connected.into()), ("total", total.into())]
+                )
+            ),
             Style::default().fg(conn_color),
         ));
     }
@@ -165,7 +173,10 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
     let alert_count = app.alerts.len();
     if width >= 50 && alert_count > 0 {
         spans.push(Span::styled(
-            format!(" Alerts {} ", alert_count),
+            format!(
+                " {} ",
+                tr_args("header-alerts", &[("count", alert_count.into())])
+            ),
             Style::default()
                 .fg(theme.danger)
                 .add_modifier(Modifier::BOLD),
@@ -195,6 +206,9 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
         if app.smart_home_enabled {
             spans.push(Span::styled(" Home ", Style::default().fg(theme.success)));
         }
+        if app.passive_mode {
+            spans.push(Span::styled(" Passive ", Style::default().fg(theme.info)));
+        }
     }
 
     let header = Paragraph::new(Line::from(spans)).style(theme.header_style());
@@ -203,52 +217,69 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 /// Get context-sensitive help hints based on current state.
-fn context_hints(app: &App) -> Vec<(&'static str, &'static str)> {
+///
+/// Keys are taken from the active keymap.
+fn context_hints(app: &App) -> Vec<(String, &'static str)> {
+    let keys = &app.keymap;
+    let select = keys.primary_label(&[Action::SelectNext, Action::SelectPrevious]);
     let mut hints = Vec::new();
 
     // Always show help key
-    hints.push(("?", "help"));
+    hints.push((keys.label(Action::ToggleHelp), "help"));
 
     match app.active_tab {
         Tab::Dashboard => {
             if app.devices.is_empty() {
-                hints.push(("s", "scan"));
+                hints.push((keys.label(Action::Scan), "scan"));
             } else {
-                hints.push(("j/k", "select"));
+                hints.push((select, "select"));
                 if app.selected_device().is_some() {
                     if app
                         .selected_device()
                         .map(|d| matches!(d.status, super::app::ConnectionStatus::Connected))
                         .unwrap_or(false)
                     {
-                        hints.push(("r", "refresh"));
-                        hints.push(("d", "disconnect"));
-                        hints.push(("S", "sync"));
+                        hints.push((keys.label(Action::Refresh), "refresh"));
+                        hints.push((keys.label(Action::Disconnect), "disconnect"));
+                        hints.push((keys.label(Action::SyncHistory), "sync"));
                     } else {
-                        hints.push(("c", "connect"));
+                        hints.push((keys.label(Action::Connect), "connect"));
                     }
                 }
-                hints.push(("s", "scan"));
+                hints.push((keys.label(Action::Scan), "scan"));
             }
         }
         Tab::History => {
-            hints.push(("S", "sync"));
-            hints.push(("0-4", "filter"));
-            hints.push(("PgUp/Dn", "scroll"));
-            hints.push(("g", "fullscreen"));
+            hints.push((keys.label(Action::SyncHistory), "sync"));
+            hints.push((
+                format!(
+                    "{}-{}",
+                    keys.primary_label(&[Action::SetHistoryFilter(HistoryFilter::All)]),
+                    keys.primary_label(&[Action::SetHistoryFilter(HistoryFilter::Last30Days)])
+                ),
+                "filter",
+            ));
+            hints.push((
+                keys.primary_label(&[Action::ScrollUp, Action::ScrollDown]),
+                "scroll",
+            ));
+            hints.push((keys.label(Action::ToggleChart), "fullscreen"));
         }
         Tab::Settings => {
-            hints.push(("+/-", "adjust"));
-            hints.push(("n", "alias"));
+            hints.push((
+                keys.primary_label(&[Action::IncreaseThreshold, Action::DecreaseThreshold]),
+                "adjust",
+            ));
+            hints.push((keys.label(Action::EditAlias), "alias"));
         }
         Tab::Service => {
-            hints.push(("r", "refresh"));
-            hints.push(("Enter", "start/stop"));
-            hints.push(("j/k", "select"));
+            hints.push((keys.label(Action::Refresh), "refresh"));
+            hints.push((keys.label(Action::ChangeSetting), "start/stop"));
+            hints.push((select, "select"));
         }
     }
 
-    hints.push(("q", "quit"));
+    hints.push((keys.primary_label(&[Action::Quit]), "quit"));
     hints
 }
 
@@ -297,23 +328,23 @@ fn draw_status_bar(frame: &mut Frame, area: Rect, app: &App) {
         // Context-sensitive hints with styled keys
         let hints = context_hints(app);
         let hints: Vec<_> = if width < 46 {
-            let mut compact = vec![hints[0]];
+            let mut compact = vec![hints[0].clone()];
             if hints.len() > 2 {
-                compact.push(hints[1]);
+                compact.push(hints[1].clone());
             }
-            if let Some(last) = hints.last().copied()
-                && compact.last().copied() != Some(last)
+            if let Some(last) = hints.last()
+                && compact.last() != Some(last)
             {
-                compact.push(last);
+                compact.push(last.clone());
             }
             compact
         } else if width < 72 {
-            let mut compact = vec![hints[0]];
-            compact.extend(hints.iter().skip(1).take(2).copied());
-            if let Some(last) = hints.last().copied()
-                && compact.last().copied() != Some(last)
+            let mut compact = vec![hints[0].clone()];
+            compact.extend(hints.iter().skip(1).take(2).cloned());
+            if let Some(last) = hints.last()
+                && compact.last() != Some(last)
             {
-                compact.push(last);
+                compact.push(last.clone());
             }
             compact
         } else {
@@ -325,7 +356,7 @@ fn draw_status_bar(frame: &mut Frame, area: Rect, app: &App) {
                 spans.push(Span::styled(" | ", Style::default().fg(theme.text_muted)));
             }
             spans.push(Span::styled(
-                *key,
+                key.clone(),
                 Style::default()
                     .fg(theme.primary)
                     .add_modifier(Modifier::BOLD),
@@ -374,10 +405,10 @@ fn draw_tab_bar(frame: &mut Frame, area: Rect, app: &App) {
     let theme = app.app_theme();
 
     let tabs = [
-        ("Dashboard", Tab::Dashboard),
-        ("History", Tab::History),
-        ("Settings", Tab::Settings),
-        ("Service", Tab::Service),
+        (tr("tab-dashboard"), Tab::Dashboard),
+        (tr("tab-history"), Tab::History),
+        (tr("tab-settings"), Tab::Settings),
+        (tr("tab-service"), Tab::Service),
     ];
 
     // Build custom tab line with underline indicator for active tab
@@ -17,11 +17,126 @@ use aranet_types::DeviceType;
 use super::colors::{battery_color, co2_color, radon_color};
 use super::theme::{AppTheme, BORDER_TYPE};
 use super::widgets::{resample_sparkline_data, sparkline_data};
-use crate::tui::app::{App, DeviceState, PendingAction};
+use crate::i18n::{tr, tr_args};
+use crate::tui::app::{App, DeviceState, HistoryFilter, PendingAction};
 use crate::tui::errors::format_error_with_guidance;
-
-/// Draw help overlay with keyboard shortcuts.
-pub(super) fn draw_help_overlay(frame: &mut Frame) {
+use crate::tui::input::Action;
+use crate::tui::keymap::Keymap;
+
+/// A help overlay section: title message ID and (actions, description ID)
+/// entries.
+type HelpSection = (&'static str, &'static [(&'static [Action], &'static str)]);
+
+/// Help overlay contents, left column.
+const HELP_LEFT: &[HelpSection] = &[
+    (
+        "help-section-navigation",
+        &[
+            (
+                &[Action::NextTab, Action::PreviousTab],
+                "help-next-prev-tab",
+            ),
+            (
+                &[Action::SelectNext, Action::SelectPrevious],
+                "help-next-prev-device",
+            ),
+            (
+                &[Action::Connect, Action::Disconnect],
+                "help-connect-disconnect",
+            ),
+            (
+                &[Action::ScrollUp, Action::ScrollDown],
+                "help-scroll-history",
+            ),
+        ],
+    ),
+    (
+        "help-section-views",
+        &[
+            (&[Action::ToggleHelp], "help-toggle-help"),
+            (&[Action::ToggleChart], "help-full-screen-chart"),
+            (&[Action::ToggleComparison], "help-comparison-view"),
+            (&[Action::ToggleAlertHistory], "help-alert-history"),
+            (&[Action::ToggleSidebar], "help-toggle-sidebar"),
+            (&[Action::ToggleSidebarWidth], "help-toggle-sidebar-width"),
+        ],
+    ),
+    (
+        "help-section-devices",
+        &[
+            (&[Action::Scan], "help-scan-for-devices"),
+            (&[Action::Refresh], "help-refresh-reading"),
+            (&[Action::SyncHistory], "help-sync-history"),
+            (&[Action::ConnectAll], "help-connect-all"),
+            (&[Action::EditAlias], "help-set-device-alias"),
+            (&[Action::CycleDeviceFilter], "help-cycle-device-filter"),
+            (&[Action::ToggleGroup], "help-toggle-device-group"),
+        ],
+    ),
+];
+
+/// Help overlay contents, right column.
+const HELP_RIGHT: &[HelpSection] = &[
+    (
+        "help-section-charts",
+        &[
+            (&[Action::ToggleChartTemp], "help-toggle-temp-on-chart"),
+            (
+                &[Action::ToggleChartHumidity],
+                "help-toggle-humidity-on-chart",
+            ),
+            (
+                &[
+                    Action::SetHistoryFilter(HistoryFilter::All),
+                    Action::SetHistoryFilter(HistoryFilter::Today),
+                    Action::SetHistoryFilter(HistoryFilter::Last24Hours),
+                    Action::SetHistoryFilter(HistoryFilter::Last7Days),
+                    Action::SetHistoryFilter(HistoryFilter::Last30Days),
+                ],
+                "help-time-filter-history",
+            ),
+        ],
+    ),
+    (
+        "help-section-alerts",
+        &[
+            (&[Action::DismissAlert], "help-dismiss-alert"),
+            (&[Action::ToggleStickyAlerts], "help-toggle-sticky-alerts"),
+            (&[Action::ToggleBell], "help-toggle-bell"),
+            (&[Action::ToggleDoNotDisturb], "help-do-not-disturb"),
+            (
+                &[Action::IncreaseThreshold, Action::DecreaseThreshold],
+                "help-adjust-thresholds",
+            ),
+        ],
+    ),
+    (
+        "help-section-settings",
+        &[
+            (&[Action::ToggleTheme], "help-toggle-theme"),
+            (&[Action::ToggleBleRange], "help-toggle-ble-range"),
+            (&[Action::ToggleSmartHome], "help-toggle-smart-home-mode"),
+            (
+                &[Action::TogglePassiveMode],
+                "help-passive-mode-advertisements",
+            ),
+            (&[Action::ChangeSetting], "help-change-interval-settings"),
+        ],
+    ),
+    (
+        "help-section-other",
+        &[
+            (&[Action::ExportHistory], "help-export-history"),
+            (&[Action::CopyHistorySummary], "help-copy-history-summary"),
+            (&[Action::ToggleExportFormat], "help-toggle-export-format"),
+            (&[Action::ShowErrorDetails], "help-show-error-details"),
+            (&[Action::Quit], "help-quit"),
+        ],
+    ),
+];
+
+/// Draw help overlay with the keys of the active keymap.
+pub(super) fn draw_help_overlay(frame: &mut Frame, app: &App) {
     let theme = AppTheme::dark(); // Help overlay uses dark theme for consistency
 
     let area = frame.area();
@@ -45,106 +160,16 @@ pub(super) fn draw_help_overlay(frame: &mut Frame) {
         .margin(1)
         .split(help_area);
 
-    // Left column
-    let left_lines = vec![
-        Line::from(Span::styled(
-            "--- Navigation ---",
-            Style::default()
-                .fg(theme.primary)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from(""),
-        shortcut_line("Tab/Shift+Tab", "Next/Prev tab", &theme),
-        shortcut_line("j/k", "Next/Prev device", &theme),
-        shortcut_line("l/h", "Next/Prev device", &theme),
-        shortcut_line("Enter", "Connect/Disconnect", &theme),
-        shortcut_line("PgUp/PgDn", "Scroll history", &theme),
-        Line::from(""),
-        Line::from(Span::styled(
-            "--- Views ---",
-            Style::default()
-                .fg(theme.primary)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from(""),
-        shortcut_line("?", "Toggle help", &theme),
-        shortcut_line("g", "Full-screen chart", &theme),
-        shortcut_line("v", "Comparison view", &theme),
-        shortcut_line("a", "Alert history", &theme),
-        shortcut_line("[", "Toggle sidebar", &theme),
-        shortcut_line("]", "Toggle sidebar width", &theme),
-        Line::from(""),
-        Line::from(Span::styled(
-            "--- Devices ---",
-            Style::default()
-                .fg(theme.primary)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from(""),
-        shortcut_line("s", "Scan for devices", &theme),
-        shortcut_line("r", "Refresh reading", &theme),
-        shortcut_line("S", "Sync history", &theme),
-        shortcut_line("C", "Connect all", &theme),
-        shortcut_line("n", "Set device alias", &theme),
-        shortcut_line("f", "Cycle device filter", &theme),
-    ];
-
-    // Right column
-    let right_lines = vec![
-        Line::from(Span::styled(
-            "--- Charts ---",
-            Style::default()
-                .fg(theme.primary)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from(""),
-        shortcut_line("T", "Toggle temp on chart", &theme),
-        shortcut_line("H", "Toggle humidity on chart", &theme),
-        shortcut_line("0-4", "Time filter (History)", &theme),
-        Line::from(""),
-        Line::from(Span::styled(
-            "--- Alerts ---",
-            Style::default()
-                .fg(theme.primary)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from(""),
-        shortcut_line("Esc", "Dismiss alert", &theme),
-        shortcut_line("A", "Toggle sticky alerts", &theme),
-        shortcut_line("b", "Toggle bell", &theme),
-        shortcut_line("D", "Do Not Disturb", &theme),
-        shortcut_line("+/-", "Adjust thresholds", &theme),
-        Line::from(""),
-        Line::from(Span::styled(
-            "--- Settings ---",
-            Style::default()
-                .fg(theme.primary)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from(""),
-        shortcut_line("t", "Toggle theme", &theme),
-        shortcut_line("B", "Toggle BLE range", &theme),
-        shortcut_line("I", "Toggle Smart Home mode", &theme),
-        shortcut_line("Enter", "Change interval (Settings)", &theme),
-        Line::from(""),
-        Line::from(Span::styled(
-            "--- Other ---",
-            Style::default()
-                .fg(theme.primary)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from(""),
-        shortcut_line("e", "Export history", &theme),
-        shortcut_line("F", "Toggle export format", &theme),
-        shortcut_line("E", "Show error details", &theme),
-        shortcut_line("q/Ctrl+C", "Quit", &theme),
-        Line::from(""),
-        Line::from(""),
-        Line::from(Span::styled(
-            "Press ? or Esc to close",
-            Style::default().fg(theme.text_muted),
-        )),
-    ];
+    let left_lines = help_lines(HELP_LEFT, &app.keymap, &theme);
+    let mut right_lines = help_lines(HELP_RIGHT, &app.keymap, &theme);
+    right_lines.push(Line::from(""));
+    right_lines.push(Line::from(Span::styled(
+        tr_args(
+            "help-close",
+            &[("key", app.keymap.label(Action::ToggleHelp).into())],
+        ),
+        Style::default().fg(theme.text_muted),
+    )));
 
     let left_para = Paragraph::new(left_lines);
     let right_para = Paragraph::new(right_lines);
@@ -154,18 +179,49 @@ pub(super) fn draw_help_overlay(frame: &mut Frame) {
         .borders(Borders::ALL)
         .border_type(BORDER_TYPE)
         .border_style(theme.border_active_style())
-        .title(Span::styled(" Keyboard Shortcuts ", theme.title_style()));
+        .title(Span::styled(
+            format!(" {} ", tr("help-title")),
+            theme.title_style(),
+        ));
 
     frame.render_widget(block, help_area);
     frame.render_widget(left_para, inner_layout[0]);
     frame.render_widget(right_para, inner_layout[1]);
 }
 
+/// Lines for a help column, with keys looked up in the keymap.
+///
+/// A single action lists all of its keys; several related actions list the
+/// first key of each, e.g. `j/k`.
+fn help_lines<'a>(sections: &[HelpSection], keymap: &Keymap, theme: &AppTheme) -> Vec<Line<'a>> {
+    let mut lines = Vec::new();
+    for (title_id, entries) in sections {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            format!("--- {} ---", tr(title_id)),
+            Style::default()
+                .fg(theme.primary)
+                .add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from(""));
+        for (actions, desc_id) in *entries {
+            let keys = match actions {
+                [action] => keymap.label(*action),
+                _ => keymap.primary_label(actions),
+            };
+            lines.push(shortcut_line(&keys, desc_id, theme));
+        }
+    }
+    lines
+}
+
 /// Create a shortcut line with key and description.
-fn shortcut_line<'a>(key: &str, desc: &str, theme: &AppTheme) -> Line<'a> {
+fn shortcut_line<'a>(key: &str, desc_id: &str, theme: &AppTheme) -> Line<'a> {
     Line::from(vec![
         Span::styled(format!("{:>12} ", key), Style::default().fg(theme.warning)),
-        Span::styled(desc.to_string(), Style::default().fg(theme.text_secondary)),
+        Span::styled(tr(desc_id), Style::default().fg(theme.text_secondary)),
     ])
 }
 
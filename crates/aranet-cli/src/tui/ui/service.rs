@@ -10,6 +10,7 @@ use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
 
 use super::theme::BORDER_TYPE;
 use crate::tui::app::App;
+use crate::tui::input::Action;
 
 /// Renders the service management panel.
 pub(super) fn draw_service_panel(frame: &mut Frame, area: Rect, app: &App) {
@@ -143,7 +144,7 @@ fn draw_status_section(frame: &mut Frame, area: Rect, app: &App) {
                     Span::styled("Unknown", Style::default().fg(theme.text_muted)),
                 ]));
                 lines.push(Line::from(vec![Span::styled(
-                    "  Press 'r' to refresh",
+                    format!("  Press '{}' to refresh", app.keymap.label(Action::Refresh)),
                     Style::default().fg(theme.text_muted).italic(),
                 )]));
             }
@@ -249,13 +250,23 @@ fn draw_device_list(frame: &mut Frame, area: Rect, app: &App) {
 fn draw_help_section(frame: &mut Frame, area: Rect, app: &App) {
     let theme = app.app_theme();
 
+    let keys = &app.keymap;
     let help = Line::from(vec![
         Span::styled("  ", Style::default()),
-        Span::styled("j/k", Style::default().fg(theme.primary)),
+        Span::styled(
+            keys.primary_label(&[Action::SelectNext, Action::SelectPrevious]),
+            Style::default().fg(theme.primary),
+        ),
         Span::styled(" select  ", Style::default().fg(theme.text_muted)),
-        Span::styled("Enter", Style::default().fg(theme.primary)),
+        Span::styled(
+            keys.label(Action::ChangeSetting),
+            Style::default().fg(theme.primary),
+        ),
         Span::styled(" toggle  ", Style::default().fg(theme.text_muted)),
-        Span::styled("r", Style::default().fg(theme.primary)),
+        Span::styled(
+            keys.label(Action::Refresh),
+            Style::default().fg(theme.primary),
+        ),
         Span::styled(" refresh", Style::default().fg(theme.text_muted)),
     ]);
 
@@ -6,6 +6,7 @@ use ratatui::widgets::{Block, Borders, Paragraph};
 
 use super::colors::{battery_color, signal_strength_display};
 use super::theme::BORDER_TYPE;
+use crate::i18n::tr;
 use crate::tui::app::App;
 
 /// Renders the settings panel with device info and configuration options.
@@ -13,13 +14,16 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
     let theme = app.app_theme();
 
     let block = Block::default()
-        .title(Span::styled(" Settings ", theme.title_style()))
+        .title(Span::styled(
+            format!(" {} ", tr("tui-settings-title")),
+            theme.title_style(),
+        ))
         .borders(Borders::ALL)
         .border_type(BORDER_TYPE)
         .border_style(theme.border_active_style());
 
     if app.devices.is_empty() || app.selected_device >= app.devices.len() {
-        let msg = Paragraph::new("Select a device to view settings")
+        let msg = Paragraph::new(tr("tui-settings-select-device"))
             .style(Style::default().fg(theme.text_muted))
             .alignment(Alignment::Center)
             .block(block);
@@ -37,15 +41,24 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
     let mut info_lines = vec![
         Line::from(""),
         Line::from(vec![
-            Span::styled("  Device ID:    ", Style::default().fg(theme.text_muted)),
+            Span::styled(
+                label("tui-settings-device-id"),
+                Style::default().fg(theme.text_muted),
+            ),
             Span::styled(&device.id, Style::default().fg(theme.text_primary)),
         ]),
         Line::from(vec![
-            Span::styled("  Name:         ", Style::default().fg(theme.text_muted)),
+            Span::styled(
+                label("tui-settings-name"),
+                Style::default().fg(theme.text_muted),
+            ),
             Span::styled(device_name, Style::default().fg(theme.text_primary)),
         ]),
         Line::from(vec![
-            Span::styled("  Type:         ", Style::default().fg(theme.text_muted)),
+            Span::styled(
+                label("tui-settings-type"),
+                Style::default().fg(theme.text_muted),
+            ),
             Span::styled(device_type, Style::default().fg(theme.primary)),
         ]),
     ];
@@ -54,7 +67,10 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
     if let Some(rssi) = device.rssi {
         let (bars, color) = signal_strength_display(&theme, rssi);
         info_lines.push(Line::from(vec![
-            Span::styled("  Signal:       ", Style::default().fg(theme.text_muted)),
+            Span::styled(
+                label("tui-settings-signal"),
+                Style::default().fg(theme.text_muted),
+            ),
             Span::styled(bars, Style::default().fg(color)),
             Span::styled(
                 format!(" ({}dBm)", rssi),
@@ -66,7 +82,10 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
     // Show uptime if connected
     if let Some(uptime) = device.uptime() {
         info_lines.push(Line::from(vec![
-            Span::styled("  Connected:    ", Style::default().fg(theme.text_muted)),
+            Span::styled(
+                label("tui-settings-connected"),
+                Style::default().fg(theme.text_muted),
+            ),
             Span::styled(uptime, Style::default().fg(theme.success)),
         ]));
     }
@@ -83,13 +102,22 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
         if reading.interval > 0 {
             let interval_mins = reading.interval / 60;
             info_lines.push(Line::from(vec![
-                Span::styled("  Interval:     ", Style::default().fg(theme.text_muted)),
+                Span::styled(
+                    label("tui-settings-interval"),
+                    Style::default().fg(theme.text_muted),
+                ),
                 Span::styled(format!("[{}m]", interval_mins), interval_style),
-                Span::styled(" (Enter to change)", Style::default().fg(theme.text_muted)),
+                Span::styled(
+                    format!(" ({})", tr("tui-settings-enter-to-change")),
+                    Style::default().fg(theme.text_muted),
+                ),
             ]));
         }
         info_lines.push(Line::from(vec![
-            Span::styled("  Battery:      ", Style::default().fg(theme.text_muted)),
+            Span::styled(
+                label("tui-settings-battery"),
+                Style::default().fg(theme.text_muted),
+            ),
             Span::styled(
                 format!("{}%", reading.battery),
                 Style::default().fg(battery_color(&theme, reading.battery)),
@@ -99,7 +127,7 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
 
     info_lines.push(Line::from(""));
     info_lines.push(Line::from(Span::styled(
-        "  Alert Thresholds:",
+        format!("  {}:", tr("tui-settings-alert-thresholds")),
         Style::default().fg(theme.primary),
     )));
     info_lines.push(Line::from(""));
@@ -111,12 +139,18 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
         Style::default().fg(theme.text_primary)
     };
     info_lines.push(Line::from(vec![
-        Span::styled("  CO2 Alert:    ", Style::default().fg(theme.text_muted)),
+        Span::styled(
+            label("tui-settings-co2-alert"),
+            Style::default().fg(theme.text_muted),
+        ),
         Span::styled(
             format!("[{} ppm]", app.co2_alert_threshold),
             co2_threshold_style,
         ),
-        Span::styled(" (+/- to adjust)", Style::default().fg(theme.text_muted)),
+        Span::styled(
+            format!(" ({})", tr("tui-settings-adjust")),
+            Style::default().fg(theme.text_muted),
+        ),
     ]));
 
     // Radon Alert Threshold (setting 2)
@@ -126,17 +160,23 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
         Style::default().fg(theme.text_primary)
     };
     info_lines.push(Line::from(vec![
-        Span::styled("  Radon Alert:  ", Style::default().fg(theme.text_muted)),
+        Span::styled(
+            label("tui-settings-radon-alert"),
+            Style::default().fg(theme.text_muted),
+        ),
         Span::styled(
             format!("[{} Bq/m3]", app.radon_alert_threshold),
             radon_threshold_style,
         ),
-        Span::styled(" (+/- to adjust)", Style::default().fg(theme.text_muted)),
+        Span::styled(
+            format!(" ({})", tr("tui-settings-adjust")),
+            Style::default().fg(theme.text_muted),
+        ),
     ]));
 
     info_lines.push(Line::from(""));
     info_lines.push(Line::from(Span::styled(
-        "  Device Settings:",
+        format!("  {}:", tr("tui-settings-device-settings")),
         Style::default().fg(theme.primary),
     )));
     info_lines.push(Line::from(""));
@@ -145,11 +185,14 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
     if let Some(settings) = &device.settings {
         // Temperature unit
         let temp_unit_text = match settings.temperature_unit {
-            TemperatureUnit::Celsius => "Celsius",
-            TemperatureUnit::Fahrenheit => "Fahrenheit",
+            TemperatureUnit::Celsius => tr("tui-settings-celsius"),
+            TemperatureUnit::Fahrenheit => tr("tui-settings-fahrenheit"),
         };
         info_lines.push(Line::from(vec![
-            Span::styled("  Temp Unit:    ", Style::default().fg(theme.text_muted)),
+            Span::styled(
+                label("tui-settings-temp-unit"),
+                Style::default().fg(theme.text_muted),
+            ),
             Span::styled(temp_unit_text, Style::default().fg(theme.text_primary)),
         ]));
 
@@ -160,16 +203,19 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
                 RadonUnit::PciL => "pCi/L",
             };
             info_lines.push(Line::from(vec![
-                Span::styled("  Radon Unit:   ", Style::default().fg(theme.text_muted)),
+                Span::styled(
+                    label("tui-settings-radon-unit"),
+                    Style::default().fg(theme.text_muted),
+                ),
                 Span::styled(radon_unit_text, Style::default().fg(theme.text_primary)),
             ]));
         }
 
         // Smart Home setting
         let smart_home_text = if settings.smart_home_enabled {
-            "Enabled"
+            tr("tui-settings-enabled")
         } else {
-            "Disabled"
+            tr("tui-settings-disabled")
         };
         let smart_home_color = if settings.smart_home_enabled {
             theme.success
@@ -177,7 +223,10 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
             theme.text_muted
         };
         info_lines.push(Line::from(vec![
-            Span::styled("  Smart Home:   ", Style::default().fg(theme.text_muted)),
+            Span::styled(
+                label("tui-settings-smart-home"),
+                Style::default().fg(theme.text_muted),
+            ),
             Span::styled(smart_home_text, Style::default().fg(smart_home_color)),
         ]));
 
@@ -187,15 +236,18 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
             BluetoothRange::Extended => ("Extended", theme.info),
         };
         info_lines.push(Line::from(vec![
-            Span::styled("  BLE Range:    ", Style::default().fg(theme.text_muted)),
+            Span::styled(
+                label("tui-settings-ble-range"),
+                Style::default().fg(theme.text_muted),
+            ),
             Span::styled(range_text, Style::default().fg(range_color)),
         ]));
 
         // Buzzer setting
         let buzzer_text = if settings.buzzer_enabled {
-            "Enabled"
+            tr("tui-settings-enabled")
         } else {
-            "Disabled"
+            tr("tui-settings-disabled")
         };
         let buzzer_color = if settings.buzzer_enabled {
             theme.success
@@ -203,16 +255,19 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
             theme.text_muted
         };
         info_lines.push(Line::from(vec![
-            Span::styled("  Buzzer:       ", Style::default().fg(theme.text_muted)),
+            Span::styled(
+                label("tui-settings-buzzer"),
+                Style::default().fg(theme.text_muted),
+            ),
             Span::styled(buzzer_text, Style::default().fg(buzzer_color)),
         ]));
 
         // Auto calibration (Aranet4 only)
         if device.device_type == Some(aranet_types::DeviceType::Aranet4) {
             let auto_cal_text = if settings.auto_calibration_enabled {
-                "Enabled"
+                tr("tui-settings-enabled")
             } else {
-                "Disabled"
+                tr("tui-settings-disabled")
             };
             let auto_cal_color = if settings.auto_calibration_enabled {
                 theme.success
@@ -220,7 +275,10 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
                 theme.text_muted
             };
             info_lines.push(Line::from(vec![
-                Span::styled("  Auto Calib:   ", Style::default().fg(theme.text_muted)),
+                Span::styled(
+                    label("tui-settings-auto-calib"),
+                    Style::default().fg(theme.text_muted),
+                ),
                 Span::styled(auto_cal_text, Style::default().fg(auto_cal_color)),
             ]));
         }
@@ -228,7 +286,7 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
         info_lines.push(Line::from(vec![
             Span::styled("  ", Style::default()),
             Span::styled(
-                "(Connect to view device settings)",
+                format!("({})", tr("tui-settings-connect-to-view")),
                 Style::default().fg(theme.text_muted).italic(),
             ),
         ]));
@@ -249,3 +307,8 @@ pub(super) fn draw_settings_panel(frame: &mut Frame, area: Rect, app: &App) {
     let settings_para = Paragraph::new(info_lines).block(block);
     frame.render_widget(settings_para, area);
 }
+
+/// A left-aligned field label padded so values line up in any language.
+fn label(id: &str) -> String {
+    format!("  {:<14}", format!("{}:", tr(id)))
+}
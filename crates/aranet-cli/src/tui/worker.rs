@@ -28,11 +28,12 @@ async fn disconnect_quietly(device: &aranet_core::Device) {
 }
 
 use aranet_core::device::{ConnectionConfig, SignalQuality};
-use aranet_core::messages::{ErrorContext, ServiceDeviceStats};
+use aranet_core::messages::{CommandThrottle, ErrorContext, ServiceDeviceStats};
 use aranet_core::service_client::ServiceClient;
-use aranet_core::settings::{DeviceSettings, MeasurementInterval};
+use aranet_core::settings::{DeviceSettings, MeasurementInterval, SettingChange};
 use aranet_core::{
-    BluetoothRange, Device, RetryConfig, ScanOptions, scan::scan_with_options, with_retry,
+    BluetoothRange, Device, PassiveMonitor, PassiveMonitorOptions, RetryConfig, ScanOptions,
+    scan::scan_with_options, with_retry,
 };
 use aranet_store::Store;
 use aranet_types::{CurrentReading, DeviceType};
@@ -43,6 +44,29 @@ use tracing::{debug, error, info, warn};
 
 use super::messages::{CachedDevice, Command, SensorEvent};
 
+/// Map an advertising device onto the ID it is stored under.
+///
+/// Advertisements identify devices by address, while connections may use
+/// the platform peripheral ID, so fall back to matching the device name.
+fn resolve_store_id(store: &Store, device_id: String, name: Option<&str>) -> String {
+    if matches!(store.get_device(&device_id), Ok(Some(_))) {
+        return device_id;
+    }
+    let Some(name) = name else {
+        return device_id;
+    };
+    store
+        .list_devices()
+        .ok()
+        .and_then(|devices| {
+            devices
+                .into_iter()
+                .find(|d| d.name.as_deref() == Some(name))
+        })
+        .map(|d| d.id)
+        .unwrap_or(device_id)
+}
+
 /// Background worker that handles BLE operations.
 ///
 /// The worker receives commands from the UI thread and performs
@@ -71,6 +95,17 @@ pub struct SensorWorker {
     /// Cancellation token for long-running operations.
     /// Used to cancel scans, connections, and history syncs.
     cancel_token: CancellationToken,
+    /// Collapses duplicate refreshes and rapid reconnects before they hit BLE.
+    throttle: CommandThrottle,
+    /// Cancel token for the passive advertisement monitor, if running.
+    passive_monitor: Option<CancellationToken>,
+}
+
+/// Wait until a throttled command is due.
+async fn sleep_until_due(due: Option<std::time::Instant>) {
+    if let Some(due) = due {
+        tokio::time::sleep_until(due.into()).await;
+    }
 }
 
 /// Default URL for the aranet-service.
@@ -119,6 +154,8 @@ impl SensorWorker {
             background_polling: Arc::new(RwLock::new(HashMap::new())),
             signal_quality_cache: Arc::new(RwLock::new(HashMap::new())),
             cancel_token: CancellationToken::new(),
+            throttle: CommandThrottle::default(),
+            passive_monitor: None,
         }
     }
 
@@ -135,6 +172,20 @@ impl SensorWorker {
         }
     }
 
+    /// Record a settings write in the store's settings history.
+    fn save_setting_change(&self, device_id: &str, change: SettingChange) {
+        if let Some(store) = self.open_store()
+            && let Err(e) = aranet_service::collector::record_setting_changes(
+                &store,
+                device_id,
+                &[change],
+                time::OffsetDateTime::now_utc(),
+            )
+        {
+            warn!(error = %e, "Failed to save settings history");
+        }
+    }
+
     /// Run the worker's main loop.
     ///
     /// This method consumes the worker and runs until a [`Command::Shutdown`]
@@ -143,6 +194,7 @@ impl SensorWorker {
         info!("SensorWorker started");
 
         loop {
+            let next_due = self.throttle.next_due();
             tokio::select! {
                 // Handle incoming commands
                 cmd = self.command_rx.recv() => {
@@ -152,7 +204,12 @@ impl SensorWorker {
                             break;
                         }
                         Some(cmd) => {
-                            self.handle_command(cmd).await;
+                            if !self.throttle.admit(&cmd) {
+                                debug!(?cmd, "Deferring throttled command");
+                                continue;
+                            }
+                            self.handle_command(cmd.clone()).await;
+                            self.throttle.finish(&cmd);
                         }
                         None => {
                             info!("Command channel closed, shutting down worker");
@@ -160,9 +217,20 @@ impl SensorWorker {
                         }
                     }
                 }
+                // Run duplicates held back by the throttle once their window ends
+                _ = sleep_until_due(next_due), if next_due.is_some() => {
+                    for cmd in self.throttle.take_due() {
+                        self.handle_command(cmd.clone()).await;
+                        self.throttle.finish(&cmd);
+                    }
+                }
             }
         }
 
+        if let Some(cancel) = self.passive_monitor.take() {
+            cancel.cancel();
+        }
+
         info!("SensorWorker stopped");
     }
 
@@ -235,9 +303,19 @@ impl SensorWorker {
             Command::StopBackgroundPolling { device_id } => {
                 self.handle_stop_background_polling(&device_id).await;
             }
+            Command::StartPassiveMonitoring => {
+                self.handle_start_passive_monitoring().await;
+            }
+            Command::StopPassiveMonitoring => {
+                self.handle_stop_passive_monitoring().await;
+            }
             Command::Shutdown => {
                 // Handled in run() loop
             }
+            // The placement assistant is GUI-only
+            Command::StartPlacementProbe { .. } | Command::StopPlacementProbe => {
+                debug!("Placement assistant not supported in TUI");
+            }
             // System service commands not supported in TUI
             Command::InstallSystemService { .. }
             | Command::UninstallSystemService { .. }
@@ -250,6 +328,15 @@ impl SensorWorker {
             | Command::RemoveServiceDevice { .. } => {
                 info!("System service commands not supported in TUI");
             }
+            // Persistent alert history is GUI-only; the TUI keeps alerts in memory
+            Command::LoadAlerts { .. }
+            | Command::RecordAlert { .. }
+            | Command::AcknowledgeAlerts { .. }
+            | Command::ClearAlerts { .. }
+            | Command::PreviewImport { .. }
+            | Command::ImportFile { .. } => {
+                debug!("Alert persistence commands not supported in TUI");
+            }
         }
     }
 
@@ -794,6 +881,7 @@ impl SensorWorker {
 
         // Disconnect from device
         disconnect_quietly(&device).await;
+        self.save_setting_change(device_id, SettingChange::Interval(interval));
 
         info!(
             device_id,
@@ -876,6 +964,7 @@ impl SensorWorker {
 
         // Disconnect from device
         disconnect_quietly(&device).await;
+        self.save_setting_change(device_id, SettingChange::BluetoothRange(range));
 
         info!(device_id, range_name, "Bluetooth range set successfully");
 
@@ -948,6 +1037,7 @@ impl SensorWorker {
 
         // Disconnect from device
         disconnect_quietly(&device).await;
+        self.save_setting_change(device_id, SettingChange::SmartHome(enabled));
 
         info!(device_id, mode, "Smart Home set successfully");
 
@@ -1803,6 +1893,98 @@ impl SensorWorker {
         info!(device_id, "Background polling task spawned");
     }
 
+    /// Start updating devices from BLE advertisements instead of connections.
+    ///
+    /// Only devices with Smart Home integration enabled broadcast readings.
+    /// Each new advertisement is forwarded as a
+    /// [`SensorEvent::PassiveReadingUpdated`] and saved to the store.
+    async fn handle_start_passive_monitoring(&mut self) {
+        if self.passive_monitor.is_some() {
+            warn!("Passive monitoring already active");
+            return;
+        }
+
+        info!("Starting passive monitoring");
+        let cancel = CancellationToken::new();
+        let monitor = Arc::new(PassiveMonitor::new(PassiveMonitorOptions::default()));
+        let mut readings = monitor.subscribe();
+        monitor.start(cancel.clone());
+        self.passive_monitor = Some(cancel.clone());
+
+        let event_tx = self.event_tx.clone();
+        let store_path = self.store_path.clone();
+
+        let _ = event_tx.send(SensorEvent::PassiveMonitoringStarted).await;
+
+        tokio::spawn(async move {
+            loop {
+                let passive = tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    result = readings.recv() => match result {
+                        Ok(passive) => passive,
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            debug!(skipped, "Passive monitor receiver lagged");
+                            continue;
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    },
+                };
+
+                let reading = passive.data.to_reading();
+                let mut device_id = passive.device_id;
+                match Store::open(&store_path) {
+                    Ok(store) => {
+                        device_id =
+                            resolve_store_id(&store, device_id, passive.device_name.as_deref());
+                        if let Err(e) =
+                            store.upsert_device(&device_id, passive.device_name.as_deref())
+                        {
+                            warn!(device_id, error = %e, "Failed to upsert device");
+                        }
+                        let saved = match passive.data.counter {
+                            Some(counter) => {
+                                store.insert_reading_with_counter(&device_id, &reading, counter)
+                            }
+                            None => store.insert_reading(&device_id, &reading),
+                        };
+                        if let Err(e) = saved {
+                            warn!(device_id, error = %e, "Failed to save passive reading");
+                        }
+                    }
+                    Err(e) => warn!(error = %e, "Failed to open store"),
+                }
+
+                if event_tx
+                    .send(SensorEvent::PassiveReadingUpdated {
+                        device_id,
+                        name: passive.device_name,
+                        device_type: passive.data.device_type,
+                        reading,
+                        rssi: passive.rssi,
+                    })
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+
+            cancel.cancel();
+            let _ = event_tx.send(SensorEvent::PassiveMonitoringStopped).await;
+            info!("Passive monitoring stopped");
+        });
+    }
+
+    /// Stop the passive advertisement monitor.
+    async fn handle_stop_passive_monitoring(&mut self) {
+        if let Some(cancel) = self.passive_monitor.take() {
+            info!("Stopping passive monitoring");
+            cancel.cancel();
+        } else {
+            warn!("Passive monitoring is not active");
+        }
+    }
+
     /// Cancel any currently running long-running operation (scan, connect, history sync).
     ///
     /// This method cancels the current cancellation token and creates a new one
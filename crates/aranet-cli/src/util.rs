@@ -39,7 +39,7 @@ fn device_error(operation: &str, identifier: &str, cause: impl std::fmt::Display
 }
 
 /// Open the store database, printing a warning to stderr on failure.
-fn open_store() -> Option<aranet_store::Store> {
+pub fn open_store() -> Option<aranet_store::Store> {
     let store_path = aranet_store::default_db_path();
     match aranet_store::Store::open(&store_path) {
         Ok(store) => Some(store),
@@ -233,18 +233,18 @@ pub fn save_reading_to_store(device_id: &str, reading: &aranet_types::CurrentRea
     }
 }
 
-/// Save history records to the store database. Returns the number of records inserted.
-pub fn save_history_to_store(device_id: &str, records: &[aranet_types::HistoryRecord]) -> usize {
-    let Some(store) = open_store() else {
-        return 0;
-    };
-    match store.insert_history(device_id, records) {
-        Ok(count) => count,
-        Err(e) => {
-            tracing::warn!("Failed to save history to store: {}", e);
-            eprintln!("Warning: could not save history to local database: {e}");
-            0
-        }
+/// Record settings written to a device in the store's settings history.
+pub fn save_settings_to_store(device_id: &str, changes: &[aranet_core::SettingChange]) {
+    if let Some(store) = open_store()
+        && let Err(e) = aranet_service::collector::record_setting_changes(
+            &store,
+            device_id,
+            changes,
+            time::OffsetDateTime::now_utc(),
+        )
+    {
+        tracing::warn!("Failed to save settings history to store: {}", e);
+        eprintln!("Warning: could not save settings history to local database: {e}");
     }
 }
 
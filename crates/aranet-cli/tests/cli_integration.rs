@@ -177,7 +177,7 @@ fn test_version_command() {
 #[test]
 fn test_subcommand_help() {
     let subcommands = [
-        "scan", "read", "watch", "history", "info", "status", "sync", "cache", "doctor",
+        "scan", "read", "watch", "top", "history", "info", "status", "sync", "cache", "doctor",
     ];
 
     for cmd in subcommands {
@@ -214,6 +214,18 @@ fn test_doctor_runs() {
     );
 }
 
+#[test]
+fn test_doctor_json() {
+    let output = run_aranet(&["doctor", "--json"]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("doctor --json output");
+    assert!(json["checks"].as_array().is_some_and(|c| !c.is_empty()));
+    assert!(json["diagnostics"]["recent_errors"].is_array());
+    assert!(json["diagnostics"]["recent_operations"].is_array());
+}
+
 // =============================================================================
 // Config Commands (no device required)
 // =============================================================================
@@ -290,6 +302,56 @@ fn test_cache_devices() {
     assert!(output.status.success(), "Cache devices should succeed");
 }
 
+#[test]
+fn test_cache_maintenance_commands() {
+    let (_root, envs, _config_path, db_path) = create_test_env();
+
+    let output = run_aranet_with_env(&["cache", "verify"], &envs);
+    assert!(output.status.success(), "Cache verify should succeed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No problems found"));
+    assert!(db_path.exists());
+
+    let output = run_aranet_with_env(&["cache", "compact"], &envs);
+    assert!(output.status.success(), "Cache compact should succeed");
+
+    let output = run_aranet_with_env(&["cache", "purge", "--before", "2024-01-01", "-f"], &envs);
+    assert!(output.status.success(), "Cache purge should succeed");
+
+    let output = run_aranet_with_env(&["cache", "purge", "-f"], &envs);
+    assert!(
+        !output.status.success(),
+        "Purge without --device or --before should fail"
+    );
+}
+
+#[test]
+fn test_cache_sql() {
+    let (_root, envs, _config_path, _db_path) = create_test_env();
+
+    let output = run_aranet_with_env(
+        &[
+            "cache",
+            "sql",
+            "SELECT COUNT(*) AS n, ? AS x FROM v_history",
+            "--param",
+            "7",
+            "--format",
+            "json",
+        ],
+        &envs,
+    );
+    assert!(output.status.success(), "Cache sql should succeed");
+    let rows: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(rows, serde_json::json!([{"n": 0, "x": 7}]));
+
+    let output = run_aranet_with_env(&["cache", "sql", "--views"], &envs);
+    assert!(output.status.success(), "Cache sql --views should succeed");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("v_readings"));
+
+    let output = run_aranet_with_env(&["cache", "sql", "DELETE FROM history"], &envs);
+    assert!(!output.status.success(), "Writes should be refused");
+}
+
 // =============================================================================
 // Alias Commands (no device required)
 // =============================================================================
@@ -0,0 +1,293 @@
+//! Bluetooth adapter power tracking.
+//!
+//! [`AdapterMonitor`] follows whether the Bluetooth adapter is usable. It
+//! learns about power changes from the platform's state events where the BLE
+//! stack reports them, and from the error signatures of failed operations
+//! (see [`Error::is_adapter_unavailable`]) where it does not.
+//!
+//! Reconnect loops wait on the monitor while Bluetooth is off instead of
+//! retrying against a dead adapter, and resume as soon as it comes back.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use aranet_core::AdapterMonitor;
+//! use tokio_util::sync::CancellationToken;
+//!
+//! let monitor = Arc::new(AdapterMonitor::new());
+//! let handle = monitor.start(CancellationToken::new());
+//!
+//! // Blocks while Bluetooth is switched off
+//! monitor.wait_until_available().await;
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use btleplug::api::{Central, CentralEvent, CentralState};
+use futures::StreamExt;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::diagnostics::AdapterState;
+use crate::error::{DeviceNotFoundReason, Error};
+use crate::events::{DeviceEvent, EventSender};
+use crate::scan::get_adapter;
+
+/// How often the adapter is polled while it is unavailable.
+///
+/// Covers platforms that do not emit state events and adapters that were
+/// marked unavailable from an error signature.
+pub const ADAPTER_PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+impl From<CentralState> for AdapterState {
+    fn from(state: CentralState) -> Self {
+        match state {
+            CentralState::PoweredOn => AdapterState::Available,
+            CentralState::PoweredOff => AdapterState::PoweredOff,
+            CentralState::Unknown => AdapterState::Unknown,
+        }
+    }
+}
+
+impl AdapterState {
+    /// Whether BLE operations can be attempted.
+    ///
+    /// `Unknown` counts as available so that platforms without state
+    /// reporting are never paused on a guess.
+    pub fn is_available(self) -> bool {
+        !matches!(self, AdapterState::PoweredOff | AdapterState::NotFound)
+    }
+}
+
+/// Tracks the Bluetooth adapter's power state and announces changes.
+#[derive(Debug)]
+pub struct AdapterMonitor {
+    state: watch::Sender<AdapterState>,
+    events: Option<EventSender>,
+}
+
+impl Default for AdapterMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AdapterMonitor {
+    /// Create a monitor with the state initially unknown.
+    pub fn new() -> Self {
+        let (state, _) = watch::channel(AdapterState::Unknown);
+        Self {
+            state,
+            events: None,
+        }
+    }
+
+    /// Create a monitor that emits [`DeviceEvent::AdapterStateChanged`] on `events`.
+    pub fn with_events(events: EventSender) -> Self {
+        Self {
+            events: Some(events),
+            ..Self::new()
+        }
+    }
+
+    /// The last known adapter state.
+    pub fn state(&self) -> AdapterState {
+        *self.state.borrow()
+    }
+
+    /// Whether BLE operations can be attempted.
+    pub fn is_available(&self) -> bool {
+        self.state().is_available()
+    }
+
+    /// Watch for state changes.
+    pub fn subscribe(&self) -> watch::Receiver<AdapterState> {
+        self.state.subscribe()
+    }
+
+    /// Record a new adapter state. Returns `true` if it changed.
+    pub fn set_state(&self, state: AdapterState) -> bool {
+        let changed = self.state.send_if_modified(|current| {
+            if *current == state {
+                return false;
+            }
+            *current = state;
+            true
+        });
+        if changed {
+            match state {
+                AdapterState::Available => info!("Bluetooth adapter is available"),
+                AdapterState::Unknown => debug!("Bluetooth adapter state is unknown"),
+                _ => warn!("Bluetooth adapter is unavailable ({:?})", state),
+            }
+            if let Some(events) = &self.events {
+                let _ = events.send(DeviceEvent::AdapterStateChanged { state });
+            }
+        }
+        changed
+    }
+
+    /// Mark the adapter unavailable if `error` carries an adapter-off signature.
+    ///
+    /// Returns `true` if the error was recognised.
+    pub fn observe_error(&self, error: &Error) -> bool {
+        if !error.is_adapter_unavailable() {
+            return false;
+        }
+        let state = match error.root() {
+            Error::DeviceNotFound(DeviceNotFoundReason::NoAdapter) => AdapterState::NotFound,
+            _ => AdapterState::PoweredOff,
+        };
+        self.set_state(state);
+        true
+    }
+
+    /// Mark the adapter available after a BLE operation succeeded.
+    pub fn observe_success(&self) {
+        self.set_state(AdapterState::Available);
+    }
+
+    /// Wait until the adapter is available. Returns immediately if it is.
+    ///
+    /// Only returns once something updates the state, so pair it with
+    /// [`start`](Self::start) or a timeout.
+    pub async fn wait_until_available(&self) {
+        let mut rx = self.subscribe();
+        let _ = rx.wait_for(|state| state.is_available()).await;
+    }
+
+    /// Spawn a task that follows the platform's adapter state events.
+    ///
+    /// While the adapter is unavailable it is also polled every
+    /// [`ADAPTER_PROBE_INTERVAL`]. Runs until `cancel_token` is cancelled.
+    pub fn start(self: &Arc<Self>, cancel_token: CancellationToken) -> tokio::task::JoinHandle<()> {
+        let monitor = Arc::clone(self);
+
+        tokio::spawn(async move {
+            let adapter = loop {
+                match get_adapter().await {
+                    Ok(adapter) => break adapter,
+                    Err(e) => {
+                        monitor.observe_error(&e);
+                        debug!("Adapter monitor: no adapter yet: {}", e);
+                    }
+                }
+                tokio::select! {
+                    _ = cancel_token.cancelled() => return,
+                    _ = tokio::time::sleep(ADAPTER_PROBE_INTERVAL) => {}
+                }
+            };
+
+            match adapter.adapter_state().await {
+                Ok(state) => {
+                    monitor.set_state(state.into());
+                }
+                Err(e) => debug!("Adapter monitor: could not read adapter state: {}", e),
+            }
+
+            let mut events = match adapter.events().await {
+                Ok(events) => Some(events),
+                Err(e) => {
+                    debug!("Adapter monitor: no adapter events, polling only: {}", e);
+                    None
+                }
+            };
+
+            loop {
+                let next_event = async {
+                    match events.as_mut() {
+                        Some(events) => events.next().await,
+                        None => std::future::pending().await,
+                    }
+                };
+
+                tokio::select! {
+                    _ = cancel_token.cancelled() => {
+                        debug!("Adapter monitor cancelled");
+                        break;
+                    }
+                    event = next_event => match event {
+                        Some(CentralEvent::StateUpdate(state)) => {
+                            monitor.set_state(state.into());
+                        }
+                        Some(_) => {}
+                        None => {
+                            debug!("Adapter monitor: event stream ended, polling only");
+                            events = None;
+                        }
+                    },
+                    _ = tokio::time::sleep(ADAPTER_PROBE_INTERVAL), if !monitor.is_available() => {
+                        if let Ok(state) = adapter.adapter_state().await {
+                            monitor.set_state(state.into());
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ConnectionFailureReason;
+    use crate::events::event_channel;
+
+    #[test]
+    fn test_set_state_emits_event_on_change() {
+        let (tx, mut rx) = event_channel(8);
+        let monitor = AdapterMonitor::with_events(tx);
+
+        assert!(monitor.is_available());
+        assert!(monitor.set_state(AdapterState::PoweredOff));
+        assert!(!monitor.set_state(AdapterState::PoweredOff));
+        assert!(!monitor.is_available());
+
+        match rx.try_recv().unwrap() {
+            DeviceEvent::AdapterStateChanged { state } => {
+                assert_eq!(state, AdapterState::PoweredOff)
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_observe_error_marks_adapter_off() {
+        let monitor = AdapterMonitor::new();
+
+        assert!(!monitor.observe_error(&Error::NotConnected));
+        assert!(monitor.is_available());
+
+        let err = Error::connection_failed(None, ConnectionFailureReason::AdapterUnavailable);
+        assert!(monitor.observe_error(&err));
+        assert_eq!(monitor.state(), AdapterState::PoweredOff);
+
+        assert!(monitor.observe_error(&Error::DeviceNotFound(DeviceNotFoundReason::NoAdapter)));
+        assert_eq!(monitor.state(), AdapterState::NotFound);
+
+        monitor.observe_success();
+        assert_eq!(monitor.state(), AdapterState::Available);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_available_resumes_when_powered_on() {
+        let monitor = Arc::new(AdapterMonitor::new());
+        monitor.set_state(AdapterState::PoweredOff);
+
+        let waiter = {
+            let monitor = Arc::clone(&monitor);
+            tokio::spawn(async move { monitor.wait_until_available().await })
+        };
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+
+        monitor.set_state(AdapterState::Available);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter should resume")
+            .unwrap();
+    }
+}
@@ -12,7 +12,7 @@
 use bytes::Buf;
 use serde::{Deserialize, Serialize};
 
-use aranet_types::{DeviceType, Status};
+use aranet_types::{CurrentReading, DeviceType, Status};
 
 use crate::error::{Error, Result};
 
@@ -47,6 +47,30 @@ pub struct AdvertisementData {
     pub flags: u8,
 }
 
+impl AdvertisementData {
+    /// The advertised values as a [`CurrentReading`].
+    ///
+    /// Values the device type does not advertise are left at zero.
+    pub fn to_reading(&self) -> CurrentReading {
+        let mut builder = CurrentReading::builder()
+            .co2(self.co2.unwrap_or(0))
+            .temperature(self.temperature.unwrap_or(0.0))
+            .pressure(self.pressure.unwrap_or(0.0))
+            .humidity(self.humidity.unwrap_or(0))
+            .battery(self.battery)
+            .status(self.status)
+            .interval(self.interval)
+            .age(self.age);
+        if let Some(radon) = self.radon {
+            builder = builder.radon(radon);
+        }
+        if let Some(rate) = self.radiation_dose_rate {
+            builder = builder.radiation_rate(rate);
+        }
+        builder.build()
+    }
+}
+
 /// Parse advertisement data from raw manufacturer data bytes.
 ///
 /// The manufacturer data should be from manufacturer ID 0x0702 (SAF Tehnika).
@@ -579,6 +603,10 @@ mod tests {
 /// - `parse_aranet_radon_advertisement_never_panics`: Radon device type
 /// - `parse_aranet_radiation_advertisement_never_panics`: Radiation device type
 ///
+/// ## Round-Trip Tests
+/// - `*_round_trip`: Readings encoded with [`AdvertisementBuilder`] parse back
+///   to the same values, within the on-air resolution
+///
 /// # Running Tests
 ///
 /// ```bash
@@ -587,8 +615,13 @@ mod tests {
 #[cfg(test)]
 mod proptests {
     use super::*;
+    use aranet_types::{AdvertisementBuilder, CurrentReading};
     use proptest::prelude::*;
 
+    fn status() -> impl Strategy<Value = Status> {
+        prop_oneof![Just(Status::Green), Just(Status::Yellow), Just(Status::Red)]
+    }
+
     proptest! {
         /// Parsing random advertisement bytes should never panic.
         /// It may return an error, but should always be safe.
@@ -632,5 +665,111 @@ mod proptests {
             }
             let _ = parse_advertisement(&modified);
         }
+
+        /// Encoded Aranet4 readings parse back to the same values.
+        #[test]
+        fn aranet4_round_trip(
+            co2 in any::<u16>(),
+            temperature in -40.0f32..60.0,
+            pressure in 800.0f32..1200.0,
+            humidity in 0u8..=100,
+            battery in 0u8..=100,
+            status in status(),
+            interval in any::<u16>(),
+            age in any::<u16>(),
+            counter in any::<u8>(),
+        ) {
+            let reading = CurrentReading::builder()
+                .co2(co2)
+                .temperature(temperature)
+                .pressure(pressure)
+                .humidity(humidity)
+                .battery(battery)
+                .status(status)
+                .interval(interval)
+                .age(age)
+                .build();
+            let data = AdvertisementBuilder::new(DeviceType::Aranet4, &reading)
+                .counter(counter)
+                .build();
+
+            let parsed = parse_advertisement(&data).unwrap();
+            prop_assert_eq!(parsed.device_type, DeviceType::Aranet4);
+            prop_assert_eq!(parsed.co2, Some(co2));
+            prop_assert!((parsed.temperature.unwrap() - temperature).abs() <= 0.026);
+            prop_assert!((parsed.pressure.unwrap() - pressure).abs() <= 0.051);
+            prop_assert_eq!(parsed.humidity, Some(humidity));
+            prop_assert_eq!(parsed.battery, battery);
+            prop_assert_eq!(parsed.status, status);
+            prop_assert_eq!(parsed.interval, interval);
+            prop_assert_eq!(parsed.age, age);
+            prop_assert_eq!(parsed.counter, Some(counter));
+        }
+
+        /// Encoded Aranet2 readings parse back to the same values.
+        #[test]
+        fn aranet2_round_trip(
+            temperature in -40.0f32..60.0,
+            humidity in 0u8..=100,
+            battery in 0u8..=100,
+            status in status(),
+        ) {
+            let reading = CurrentReading::builder()
+                .temperature(temperature)
+                .humidity(humidity)
+                .battery(battery)
+                .status(status)
+                .build();
+            let data = AdvertisementBuilder::new(DeviceType::Aranet2, &reading).build();
+
+            let parsed = parse_advertisement(&data).unwrap();
+            prop_assert_eq!(parsed.device_type, DeviceType::Aranet2);
+            prop_assert!((parsed.temperature.unwrap() - temperature).abs() <= 0.026);
+            prop_assert_eq!(parsed.humidity, Some(humidity));
+            prop_assert_eq!(parsed.battery, battery);
+            prop_assert_eq!(parsed.status, status);
+        }
+
+        /// Encoded Aranet Radon readings parse back to the same values.
+        #[test]
+        fn aranet_radon_round_trip(
+            radon in 0u32..=u32::from(u16::MAX),
+            temperature in -40.0f32..60.0,
+            pressure in 800.0f32..1200.0,
+            humidity in 0u8..=100,
+            status in status(),
+        ) {
+            let reading = CurrentReading::builder()
+                .radon(radon)
+                .temperature(temperature)
+                .pressure(pressure)
+                .humidity(humidity)
+                .status(status)
+                .build();
+            let data = AdvertisementBuilder::new(DeviceType::AranetRadon, &reading).build();
+
+            let parsed = parse_advertisement(&data).unwrap();
+            prop_assert_eq!(parsed.device_type, DeviceType::AranetRadon);
+            prop_assert_eq!(parsed.radon, Some(radon));
+            prop_assert!((parsed.temperature.unwrap() - temperature).abs() <= 0.026);
+            prop_assert!((parsed.pressure.unwrap() - pressure).abs() <= 0.051);
+            prop_assert_eq!(parsed.humidity, Some(humidity));
+            prop_assert_eq!(parsed.status, status);
+        }
+
+        /// Encoded Aranet Radiation readings parse back to the same dose rate.
+        #[test]
+        fn aranet_radiation_round_trip(rate in 0.0f32..600.0, battery in 0u8..=100) {
+            let reading = CurrentReading::builder()
+                .radiation_rate(rate)
+                .battery(battery)
+                .build();
+            let data = AdvertisementBuilder::new(DeviceType::AranetRadiation, &reading).build();
+
+            let parsed = parse_advertisement(&data).unwrap();
+            prop_assert_eq!(parsed.device_type, DeviceType::AranetRadiation);
+            prop_assert!((parsed.radiation_dose_rate.unwrap() - rate).abs() <= 0.0051);
+            prop_assert_eq!(parsed.battery, battery);
+        }
     }
 }
@@ -0,0 +1,242 @@
+//! Ventilation advice from CO₂ readings.
+//!
+//! [`advise`] models the room as well-mixed air exchanging with outdoor air.
+//! The recent CO₂ slope stands in for what the occupants add, and opening a
+//! window is assumed to give [`AdviceConfig::air_changes_per_hour`]. From
+//! that it estimates how long a window needs to be open to get back below
+//! the target, or how soon the target will be passed if nothing changes.
+//!
+//! The numbers are rough: real air exchange depends on the window, the wind
+//! and the temperature difference. They are meant for "open a window for
+//! about ten minutes", not for planning.
+//!
+//! # Example
+//!
+//! ```
+//! use aranet_core::advice::{Advice, AdviceConfig, advise};
+//!
+//! let advice = advise(1200, Some(5.0), &AdviceConfig::default());
+//! assert!(matches!(advice, Advice::Ventilate { .. }));
+//! println!("{advice}");
+//! ```
+
+use std::fmt;
+
+use time::OffsetDateTime;
+
+/// Assumptions behind [`advise`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdviceConfig {
+    /// Outdoor CO₂ concentration in ppm.
+    pub outdoor_co2: u16,
+    /// Concentration to stay below, in ppm.
+    pub target_co2: u16,
+    /// Air changes per hour with a window open.
+    pub air_changes_per_hour: f32,
+    /// How far ahead, in minutes, a rising concentration is warned about.
+    pub horizon_minutes: u32,
+}
+
+impl Default for AdviceConfig {
+    fn default() -> Self {
+        Self {
+            outdoor_co2: 420,
+            target_co2: 800,
+            air_changes_per_hour: 4.0,
+            horizon_minutes: 30,
+        }
+    }
+}
+
+/// What to do about the CO₂ level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Advice {
+    /// Below the target and not expected to pass it within the horizon.
+    NoAction,
+    /// Below the target, but expected to pass it in about `minutes`.
+    VentilateSoon {
+        /// Estimated minutes until the target is passed.
+        minutes: u32,
+        /// Target concentration in ppm.
+        target: u16,
+    },
+    /// Above the target; a window open for about `minutes` should bring it
+    /// back below.
+    Ventilate {
+        /// Estimated minutes of ventilation needed.
+        minutes: u32,
+        /// Target concentration in ppm.
+        target: u16,
+    },
+    /// Above the target and rising faster than an open window can remove it.
+    VentilateContinuously {
+        /// Target concentration in ppm.
+        target: u16,
+    },
+}
+
+impl Advice {
+    /// Whether the advice asks for a window to be opened.
+    pub fn needs_action(&self) -> bool {
+        !matches!(self, Advice::NoAction)
+    }
+}
+
+impl fmt::Display for Advice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Advice::NoAction => write!(f, "no ventilation needed"),
+            Advice::VentilateSoon { minutes, target } => write!(
+                f,
+                "CO₂ will pass {target} ppm in ~{minutes} min; open a window soon"
+            ),
+            Advice::Ventilate { minutes, target } => write!(
+                f,
+                "open a window for ~{minutes} min to return below {target} ppm"
+            ),
+            Advice::VentilateContinuously { target } => write!(
+                f,
+                "keep a window open; CO₂ is rising too fast to return below {target} ppm"
+            ),
+        }
+    }
+}
+
+/// Advise on ventilation for a concentration of `co2` ppm.
+///
+/// `slope` is the recent change in ppm per minute (see [`co2_slope`]); with
+/// `None` the level is assumed to be steady.
+pub fn advise(co2: u16, slope: Option<f32>, config: &AdviceConfig) -> Advice {
+    let target = config.target_co2;
+    let slope = slope.unwrap_or(0.0);
+
+    if co2 <= target {
+        if slope <= 0.0 {
+            return Advice::NoAction;
+        }
+        let minutes = (f32::from(target - co2) / slope).ceil().max(1.0) as u32;
+        return if minutes <= config.horizon_minutes {
+            Advice::VentilateSoon { minutes, target }
+        } else {
+            Advice::NoAction
+        };
+    }
+
+    // dC/dt = slope - k (C - outdoor) with the window open, which settles at
+    // outdoor + slope / k
+    let k = config.air_changes_per_hour / 60.0;
+    if k <= 0.0 {
+        return Advice::VentilateContinuously { target };
+    }
+    let settled = f32::from(config.outdoor_co2) + slope.max(0.0) / k;
+    if settled >= f32::from(target) {
+        return Advice::VentilateContinuously { target };
+    }
+    let minutes = ((f32::from(co2) - settled) / (f32::from(target) - settled)).ln() / k;
+    Advice::Ventilate {
+        minutes: minutes.ceil().max(1.0) as u32,
+        target,
+    }
+}
+
+/// Least-squares CO₂ slope in ppm per minute.
+///
+/// Samples with a zero concentration are ignored. Returns `None` with fewer
+/// than two samples or when they span less than a minute.
+pub fn co2_slope(samples: impl IntoIterator<Item = (OffsetDateTime, u16)>) -> Option<f32> {
+    let samples: Vec<(OffsetDateTime, u16)> =
+        samples.into_iter().filter(|&(_, co2)| co2 > 0).collect();
+    let first = samples.iter().map(|&(at, _)| at).min()?;
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|&(at, co2)| ((at - first).as_seconds_f64() / 60.0, f64::from(co2)))
+        .collect();
+    let span = points.iter().map(|&(x, _)| x).fold(0.0, f64::max);
+    if points.len() < 2 || span < 1.0 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|&(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|&(_, y)| y).sum::<f64>() / n;
+    let covariance: f64 = points
+        .iter()
+        .map(|&(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f64 = points.iter().map(|&(x, _)| (x - mean_x).powi(2)).sum();
+    Some((covariance / variance) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advise_below_target() {
+        let config = AdviceConfig::default();
+        assert_eq!(advise(600, None, &config), Advice::NoAction);
+        assert_eq!(advise(600, Some(-2.0), &config), Advice::NoAction);
+        assert_eq!(
+            advise(700, Some(10.0), &config),
+            Advice::VentilateSoon {
+                minutes: 10,
+                target: 800
+            }
+        );
+        // 100 minutes away is past the horizon
+        assert_eq!(advise(700, Some(1.0), &config), Advice::NoAction);
+    }
+
+    #[test]
+    fn test_advise_above_target() {
+        let config = AdviceConfig::default();
+        // ln(780 / 380) / (4 / 60) = 10.8 minutes
+        assert_eq!(
+            advise(1200, None, &config),
+            Advice::Ventilate {
+                minutes: 11,
+                target: 800
+            }
+        );
+        // Occupants adding 5 ppm/min settle at 495 ppm, which takes longer
+        let Advice::Ventilate { minutes, .. } = advise(1200, Some(5.0), &config) else {
+            panic!("expected Ventilate");
+        };
+        assert!(minutes > 11);
+        // 30 ppm/min would settle at 870 ppm
+        assert_eq!(
+            advise(1200, Some(30.0), &config),
+            Advice::VentilateContinuously { target: 800 }
+        );
+    }
+
+    #[test]
+    fn test_advice_display() {
+        let advice = Advice::Ventilate {
+            minutes: 12,
+            target: 800,
+        };
+        assert_eq!(
+            advice.to_string(),
+            "open a window for ~12 min to return below 800 ppm"
+        );
+        assert!(advice.needs_action());
+        assert!(!Advice::NoAction.needs_action());
+    }
+
+    #[test]
+    fn test_co2_slope() {
+        let start = OffsetDateTime::UNIX_EPOCH;
+        let minute = time::Duration::minutes(1);
+        let rising = (0..10).map(|i| (start + minute * i, 600 + 5 * i as u16));
+        let slope = co2_slope(rising).unwrap();
+        assert!((slope - 5.0).abs() < 1e-3);
+
+        assert_eq!(co2_slope([(start, 600)]), None);
+        assert_eq!(
+            co2_slope([(start, 600), (start + time::Duration::seconds(30), 610)]),
+            None
+        );
+        assert_eq!(co2_slope([(start, 600), (start + minute, 0)]), None);
+    }
+}
@@ -0,0 +1,574 @@
+//! BLE bond (pairing) persistence and recovery.
+//!
+//! Aranet devices require an authenticated link for some characteristics,
+//! so the OS keeps bond keys for each device. When either side forgets them
+//! (device battery change or factory reset, adapter reset, `bluetoothctl
+//! remove`), connections fail with authentication errors until the device
+//! is paired again. These show up as [`Error::BondLost`].
+//!
+//! What can be done about it depends on the platform:
+//!
+//! - **Linux (BlueZ)**: bonds are inspected and managed over D-Bus. A stale
+//!   bond can be removed and re-paired ([`repair_bond`]), and devices can be
+//!   marked trusted so BlueZ keeps the bond and accepts reconnections.
+//! - **macOS / Windows**: the OS manages bonds itself; these functions return
+//!   [`Error::Unsupported`].
+//!
+//! Bond keys themselves live in root-owned BlueZ storage and are never read.
+//! Instead, [`BondStore`] remembers which devices this application has
+//! bonded ([`pair_and_trust`] records each one), and [`restore_bonds`]
+//! re-establishes trust and pairing for any that BlueZ has since forgotten.
+//! The first connection in a process with `auto_repair_bond` set does this
+//! for the default store.
+//!
+//! Set [`ConnectionConfig::auto_repair_bond`](crate::device::ConnectionConfig::auto_repair_bond)
+//! to have [`Device::connect_with_config`](crate::Device::connect_with_config)
+//! re-pair automatically when a bond is lost.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::error::{Error, Result};
+
+/// Bond state of a device as reported by the Bluetooth stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BondInfo {
+    /// Device address (`AA:BB:CC:DD:EE:FF`).
+    pub address: String,
+    /// Device name, if known.
+    pub name: Option<String>,
+    /// Whether the device has completed pairing.
+    pub paired: bool,
+    /// Whether the pairing keys are stored persistently.
+    pub bonded: bool,
+    /// Whether the stack accepts connections from the device without
+    /// re-authorization.
+    pub trusted: bool,
+}
+
+/// A device this application has bonded with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BondRecord {
+    /// Device address (`AA:BB:CC:DD:EE:FF`).
+    pub address: String,
+    /// Device name at the time of bonding.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// When the bond was recorded.
+    #[serde(with = "time::serde::rfc3339")]
+    pub bonded_at: OffsetDateTime,
+}
+
+/// Persistent list of bonded devices, stored as JSON.
+#[derive(Debug, Clone)]
+pub struct BondStore {
+    path: PathBuf,
+    records: BTreeMap<String, BondRecord>,
+}
+
+impl BondStore {
+    /// Load the store from `path`, starting empty if the file does not exist.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let records = match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                let list: Vec<BondRecord> = serde_json::from_str(&content).map_err(|e| {
+                    Error::InvalidData(format!("Invalid bond store {}: {e}", path.display()))
+                })?;
+                list.into_iter()
+                    .map(|r| (r.address.to_uppercase(), r))
+                    .collect()
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, records })
+    }
+
+    /// Load the store at [`bond_store_path`], starting empty if it cannot be
+    /// read.
+    pub fn load_default() -> Self {
+        let path = bond_store_path();
+        Self::load(&path).unwrap_or_else(|e| {
+            tracing::debug!("Ignoring bond store: {e}");
+            Self {
+                path,
+                records: BTreeMap::new(),
+            }
+        })
+    }
+
+    /// Where the store is saved.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write the store to disk.
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let list: Vec<&BondRecord> = self.records.values().collect();
+        let json = serde_json::to_string_pretty(&list)
+            .map_err(|e| Error::InvalidData(format!("Failed to serialize bond store: {e}")))?;
+        std::fs::write(&self.path, json)?;
+        Ok(())
+    }
+
+    /// Record a bonded device, replacing any earlier record for its address.
+    pub fn remember(&mut self, address: &str, name: Option<String>) {
+        self.records.insert(
+            address.to_uppercase(),
+            BondRecord {
+                address: address.to_uppercase(),
+                name,
+                bonded_at: OffsetDateTime::now_utc(),
+            },
+        );
+    }
+
+    /// Drop the record for `address`. Returns whether one existed.
+    pub fn forget(&mut self, address: &str) -> bool {
+        self.records.remove(&address.to_uppercase()).is_some()
+    }
+
+    /// The record for `address`, if any.
+    pub fn get(&self, address: &str) -> Option<&BondRecord> {
+        self.records.get(&address.to_uppercase())
+    }
+
+    /// All recorded bonds, ordered by address.
+    pub fn records(&self) -> impl Iterator<Item = &BondRecord> {
+        self.records.values()
+    }
+}
+
+/// Default location of the bond store, next to the
+/// [scan cache](crate::scan::scan_cache_path).
+pub fn bond_store_path() -> PathBuf {
+    crate::scan::scan_cache_path().with_file_name("bonds.json")
+}
+
+/// Record a bond in the default store. Failures are only logged: the store
+/// only helps recover lost bonds later.
+fn remember_bond(address: &str, name: Option<String>) {
+    let mut store = BondStore::load_default();
+    store.remember(address, name);
+    if let Err(e) = store.save() {
+        tracing::debug!("Failed to save bond store {}: {e}", store.path().display());
+    }
+}
+
+/// Whether a remembered bond must be re-established, given its state in the
+/// Bluetooth stack.
+fn needs_restore(info: &BondInfo) -> bool {
+    !(info.paired && info.trusted)
+}
+
+/// Whether a Bluetooth error message indicates the bond keys no longer match.
+///
+/// BlueZ reports these as authentication failures or missing keys;
+/// CoreBluetooth and WinRT as insufficient authentication or encryption.
+pub fn is_bond_lost_message(message: &str) -> bool {
+    const MARKERS: &[&str] = &[
+        "authenticationfailed",
+        "authentication failed",
+        "authenticationrejected",
+        "insufficient authentication",
+        "insufficient encryption",
+        "pin or key missing",
+        "key missing",
+        "encryption is insufficient",
+        "peer removed pairing",
+    ];
+    let message = message.to_lowercase();
+    MARKERS.iter().any(|m| message.contains(m))
+}
+
+/// Turn a Bluetooth error that means the bond is gone into [`Error::BondLost`].
+pub(crate) fn classify(error: Error, device: &str) -> Error {
+    match error {
+        Error::Bluetooth(e) if is_bond_lost_message(&e.to_string()) => Error::BondLost {
+            device: device.to_string(),
+            reason: e.to_string(),
+        },
+        other => other,
+    }
+}
+
+/// Whether `error` is CoreBluetooth reporting that the device removed its
+/// pairing information.
+///
+/// Unlike other bond losses, a fresh connection usually pairs again without
+/// help.
+pub(crate) fn is_peer_removed_pairing(error: &Error) -> bool {
+    match error.root() {
+        Error::BondLost { reason, .. } => reason.to_lowercase().contains("peer removed pairing"),
+        _ => false,
+    }
+}
+
+/// Current bond state of the device with `address`, or `None` if the
+/// Bluetooth stack does not know it.
+pub async fn bond_status(address: &str) -> Result<Option<BondInfo>> {
+    #[cfg(target_os = "linux")]
+    {
+        bluez::bond_status(address).await
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = address;
+        Err(unsupported())
+    }
+}
+
+/// Pair with the device (if not already paired) and mark it trusted so the
+/// bond is kept and reconnections are accepted.
+///
+/// The device must be known to the stack, i.e. seen in a recent scan. On
+/// success the device is recorded in the default [`BondStore`].
+pub async fn pair_and_trust(address: &str) -> Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        bluez::pair_and_trust(address).await?;
+        let name = bond_status(address)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|info| info.name);
+        remember_bond(address, name);
+        Ok(())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = address;
+        Err(unsupported())
+    }
+}
+
+/// Remove the stack's stale bond for `address` so the next connection pairs
+/// from scratch.
+///
+/// Returns `false` if the stack did not know the device.
+pub async fn remove_bond(address: &str) -> Result<bool> {
+    #[cfg(target_os = "linux")]
+    {
+        bluez::remove_bond(address).await
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = address;
+        Err(unsupported())
+    }
+}
+
+/// Recover from [`Error::BondLost`]: forget the stale bond, rediscover the
+/// device and pair with it again.
+pub async fn repair_bond(address: &str) -> Result<()> {
+    tracing::info!("Re-pairing {address} after lost bond");
+    remove_bond(address).await?;
+
+    // Removing the bond also removes the device object; scan until the
+    // stack sees the device again
+    crate::scan::find_device(address).await?;
+    pair_and_trust(address).await
+}
+
+/// Re-establish trust and pairing for every device in `store` whose bond the
+/// stack has forgotten or never trusted.
+///
+/// Devices not currently known to the stack (out of range, not scanned) are
+/// skipped, and a device that fails to restore is logged without stopping
+/// the others. Returns the addresses that were restored.
+pub async fn restore_bonds(store: &BondStore) -> Vec<String> {
+    let mut restored = Vec::new();
+    for record in store.records() {
+        let result = match bond_status(&record.address).await {
+            Ok(Some(info)) if !needs_restore(&info) => continue,
+            Ok(Some(_)) => pair_and_trust(&record.address).await,
+            Ok(None) => {
+                tracing::debug!("{} not visible; bond not restored", record.address);
+                continue;
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => restored.push(record.address.clone()),
+            Err(e) => tracing::warn!("Failed to restore bond with {}: {e}", record.address),
+        }
+    }
+    restored
+}
+
+/// Restore the bonds in the default store, once per process.
+///
+/// Called before the first connection with
+/// [`ConnectionConfig::auto_repair_bond`](crate::device::ConnectionConfig::auto_repair_bond)
+/// so devices whose bond BlueZ forgot are paired again before they fail
+/// with [`Error::BondLost`]. The restore shares the connection's `budget`,
+/// or is limited to `step_timeout` without one; if it is cut short, the
+/// next connection picks it up again. Failures are only logged.
+pub(crate) async fn restore_remembered_bonds(
+    budget: Option<&crate::budget::TimeoutBudget>,
+    step_timeout: std::time::Duration,
+) {
+    static RESTORED: tokio::sync::OnceCell<()> = tokio::sync::OnceCell::const_new();
+    let restore = RESTORED.get_or_init(|| async {
+        let store = BondStore::load_default();
+        if store.records().next().is_none() {
+            return;
+        }
+        let restored = restore_bonds(&store).await;
+        if !restored.is_empty() {
+            tracing::info!("Restored bonds with {}", restored.join(", "));
+        }
+    });
+    let result = crate::budget::run_step(budget, "restore bonds", step_timeout, async {
+        restore.await;
+        Ok(())
+    })
+    .await;
+    if let Err(e) = result {
+        tracing::debug!("Bond restore cut short: {e}");
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unsupported() -> Error {
+    Error::Unsupported("bond management requires BlueZ (Linux)".to_string())
+}
+
+#[cfg(target_os = "linux")]
+mod bluez {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use dbus::arg::{PropMap, prop_cast};
+    use dbus::nonblock::stdintf::org_freedesktop_dbus::{ObjectManager, Properties};
+    use dbus::nonblock::{Proxy, SyncConnection};
+    use tracing::{debug, info};
+
+    use super::BondInfo;
+    use crate::error::{Error, Result};
+
+    const DBUS_TIMEOUT: Duration = Duration::from_secs(5);
+    /// Pairing waits for the device, so allow more time than plain calls.
+    const PAIR_TIMEOUT: Duration = Duration::from_secs(30);
+
+    type ManagedObjects = HashMap<dbus::Path<'static>, HashMap<String, PropMap>>;
+
+    fn dbus_error(e: dbus::Error) -> Error {
+        Error::connection_failed_str(None, format!("BlueZ D-Bus error: {e}"))
+    }
+
+    fn system_bus() -> Result<Arc<SyncConnection>> {
+        let (resource, conn) = dbus_tokio::connection::new_system_sync().map_err(dbus_error)?;
+        tokio::spawn(async move {
+            let err = resource.await;
+            debug!("Bond management D-Bus connection closed: {err}");
+        });
+        Ok(conn)
+    }
+
+    /// The device object and its properties for `address`.
+    async fn find_device(
+        conn: &Arc<SyncConnection>,
+        address: &str,
+    ) -> Result<Option<(dbus::Path<'static>, PropMap)>> {
+        let root = Proxy::new("org.bluez", "/", DBUS_TIMEOUT, conn.clone());
+        let objects: ManagedObjects = root.get_managed_objects().await.map_err(dbus_error)?;
+        Ok(objects.into_iter().find_map(|(path, mut ifaces)| {
+            let props = ifaces.remove("org.bluez.Device1")?;
+            let matches = prop_cast::<String>(&props, "Address")
+                .is_some_and(|a| a.eq_ignore_ascii_case(address));
+            matches.then_some((path, props))
+        }))
+    }
+
+    pub(super) async fn bond_status(address: &str) -> Result<Option<BondInfo>> {
+        crate::bluez_agent::ensure_agent();
+        let conn = system_bus()?;
+        let flag = |props: &PropMap, name| prop_cast::<bool>(props, name).copied().unwrap_or(false);
+        Ok(find_device(&conn, address)
+            .await?
+            .map(|(_, props)| BondInfo {
+                address: prop_cast::<String>(&props, "Address")
+                    .cloned()
+                    .unwrap_or_else(|| address.to_string()),
+                name: prop_cast::<String>(&props, "Name").cloned(),
+                paired: flag(&props, "Paired"),
+                bonded: flag(&props, "Bonded"),
+                trusted: flag(&props, "Trusted"),
+            }))
+    }
+
+    pub(super) async fn pair_and_trust(address: &str) -> Result<()> {
+        // Pairing needs an agent to answer BlueZ's confirmation requests
+        crate::bluez_agent::ensure_agent();
+        let conn = system_bus()?;
+        let (path, props) = find_device(&conn, address)
+            .await?
+            .ok_or_else(|| Error::device_not_found(address))?;
+
+        if !prop_cast::<bool>(&props, "Paired")
+            .copied()
+            .unwrap_or(false)
+        {
+            let device = Proxy::new("org.bluez", path.clone(), PAIR_TIMEOUT, conn.clone());
+            let result: std::result::Result<(), dbus::Error> =
+                device.method_call("org.bluez.Device1", "Pair", ()).await;
+            match result {
+                Ok(()) => info!("Paired with {address}"),
+                Err(e) if e.name() == Some("org.bluez.Error.AlreadyExists") => {}
+                Err(e) => {
+                    return Err(Error::connection_failed(
+                        Some(address.to_string()),
+                        crate::error::ConnectionFailureReason::PairingFailed,
+                    )
+                    .context(format!("pair: {e}")));
+                }
+            }
+        }
+
+        let device = Proxy::new("org.bluez", path, DBUS_TIMEOUT, conn.clone());
+        device
+            .set("org.bluez.Device1", "Trusted", true)
+            .await
+            .map_err(dbus_error)?;
+        debug!("Marked {address} as trusted");
+        Ok(())
+    }
+
+    pub(super) async fn remove_bond(address: &str) -> Result<bool> {
+        let conn = system_bus()?;
+        let Some((path, props)) = find_device(&conn, address).await? else {
+            return Ok(false);
+        };
+        let Some(adapter) = prop_cast::<dbus::Path<'static>>(&props, "Adapter").cloned() else {
+            return Ok(false);
+        };
+
+        let adapter = Proxy::new("org.bluez", adapter, DBUS_TIMEOUT, conn.clone());
+        adapter
+            .method_call::<(), _, _, _>("org.bluez.Adapter1", "RemoveDevice", (path,))
+            .await
+            .map_err(dbus_error)?;
+        info!("Removed stale bond for {address}");
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_bond_lost_message() {
+        assert!(is_bond_lost_message(
+            "le-connection-abort-by-local: org.bluez.Error.AuthenticationFailed"
+        ));
+        assert!(is_bond_lost_message(
+            "ATT error: Insufficient Authentication"
+        ));
+        assert!(is_bond_lost_message("Peer removed pairing information"));
+        assert!(!is_bond_lost_message("Device not found"));
+        assert!(!is_bond_lost_message("Operation timed out"));
+    }
+
+    #[test]
+    fn test_classify_leaves_other_errors() {
+        let err = classify(Error::NotConnected, "AA:BB:CC:DD:EE:FF");
+        assert!(matches!(err, Error::NotConnected));
+
+        let err = classify(
+            Error::Bluetooth(btleplug::Error::Other(
+                "org.bluez.Error.AuthenticationFailed".into(),
+            )),
+            "AA:BB:CC:DD:EE:FF",
+        );
+        assert!(matches!(err, Error::BondLost { ref device, .. } if device == "AA:BB:CC:DD:EE:FF"));
+    }
+
+    #[test]
+    fn test_is_peer_removed_pairing() {
+        let removed = classify(
+            Error::Bluetooth(btleplug::Error::Other(
+                "Peer removed pairing information".into(),
+            )),
+            "AA:BB:CC:DD:EE:FF",
+        );
+        assert!(is_peer_removed_pairing(&removed));
+        assert!(is_peer_removed_pairing(&removed.context("open connection")));
+
+        let auth = classify(
+            Error::Bluetooth(btleplug::Error::Other(
+                "org.bluez.Error.AuthenticationFailed".into(),
+            )),
+            "AA:BB:CC:DD:EE:FF",
+        );
+        assert!(!is_peer_removed_pairing(&auth));
+        assert!(!is_peer_removed_pairing(&Error::NotConnected));
+    }
+
+    #[test]
+    fn test_bond_store_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "aranet-bond-store-test-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let mut store = BondStore::load(&path).unwrap();
+        assert_eq!(store.records().count(), 0);
+        store.remember("aa:bb:cc:dd:ee:ff", Some("Aranet4 17C3C".to_string()));
+        store.save().unwrap();
+
+        let mut loaded = BondStore::load(&path).unwrap();
+        let record = loaded.get("AA:BB:CC:DD:EE:FF").unwrap();
+        assert_eq!(record.address, "AA:BB:CC:DD:EE:FF");
+        assert_eq!(record.name.as_deref(), Some("Aranet4 17C3C"));
+        assert!(loaded.forget("aa:bb:cc:dd:ee:ff"));
+        assert!(!loaded.forget("aa:bb:cc:dd:ee:ff"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_remembered_bond_is_restored_once_forgotten() {
+        let path = std::env::temp_dir().join(format!(
+            "aranet-bond-restore-test-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        // What pairing records is what a later restore reads back
+        let mut store = BondStore::load(&path).unwrap();
+        store.remember("aa:bb:cc:dd:ee:ff", Some("Aranet4 17C3C".to_string()));
+        store.save().unwrap();
+        let loaded = BondStore::load(&path).unwrap();
+        let addresses: Vec<&str> = loaded.records().map(|r| r.address.as_str()).collect();
+        assert_eq!(addresses, ["AA:BB:CC:DD:EE:FF"]);
+
+        let mut info = BondInfo {
+            address: "AA:BB:CC:DD:EE:FF".to_string(),
+            name: None,
+            paired: true,
+            bonded: true,
+            trusted: true,
+        };
+        assert!(!needs_restore(&info));
+        info.trusted = false;
+        assert!(needs_restore(&info));
+        info.trusted = true;
+        info.paired = false;
+        assert!(needs_restore(&info));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,285 @@
+//! Overall timeout budgets for device operations.
+//!
+//! Each BLE step (scan, connect, service discovery, characteristic reads)
+//! has its own timeout in [`ConnectionConfig`](crate::ConnectionConfig), and
+//! some steps are retried. The worst case of a connect-and-read is therefore
+//! much longer than any single timeout. Setting
+//! [`ConnectionConfig::total_timeout`](crate::ConnectionConfig::total_timeout)
+//! gives the whole sequence one deadline: every step's timeout is capped at
+//! what is left of the budget, and once it runs out the operation fails with
+//! [`Error::TimeoutExceeded`], listing every attempt made.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use aranet_core::{ConnectionConfig, Device, Error};
+//!
+//! # async fn example() -> aranet_core::Result<()> {
+//! let config = ConnectionConfig::default().total_timeout(Duration::from_secs(20));
+//! let result = async {
+//!     let device = Device::connect_with_config("Aranet4 12345", config).await?;
+//!     device.read_current().await
+//! }
+//! .await;
+//!
+//! if let Err(e) = &result
+//!     && let Error::TimeoutExceeded { attempts, .. } = e.root()
+//! {
+//!     for attempt in attempts {
+//!         println!("{}: {:?} ({:?})", attempt.operation, attempt.elapsed, attempt.error);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::{Instant, timeout};
+
+use crate::error::{Error, Result};
+
+/// One step run under a [`TimeoutBudget`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeoutAttempt {
+    /// The step, e.g. `connect to device` or `read characteristic ...`.
+    pub operation: String,
+    /// How long the step ran.
+    pub elapsed: Duration,
+    /// Why the step failed, or `None` if it succeeded.
+    pub error: Option<String>,
+}
+
+/// A deadline shared by a sequence of operations.
+///
+/// Created by [`Device`](crate::Device) when
+/// [`ConnectionConfig::total_timeout`](crate::ConnectionConfig::total_timeout)
+/// is set. The budget starts when the connection attempt starts and covers
+/// every later operation on the device until [`reset`](Self::reset) is called.
+#[derive(Debug)]
+pub struct TimeoutBudget {
+    total: Duration,
+    state: Mutex<BudgetState>,
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    started: Instant,
+    attempts: Vec<TimeoutAttempt>,
+}
+
+impl TimeoutBudget {
+    /// Start a budget of `total` now.
+    pub fn new(total: Duration) -> Self {
+        Self {
+            total,
+            state: Mutex::new(BudgetState {
+                started: Instant::now(),
+                attempts: Vec::new(),
+            }),
+        }
+    }
+
+    /// The total budget.
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// Time left before the budget is exhausted.
+    pub fn remaining(&self) -> Duration {
+        let started = self.lock().started;
+        self.total.saturating_sub(started.elapsed())
+    }
+
+    /// Whether the budget has run out.
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// Steps run under this budget so far, oldest first.
+    pub fn attempts(&self) -> Vec<TimeoutAttempt> {
+        self.lock().attempts.clone()
+    }
+
+    /// Start a new budget window of the same length and forget past attempts.
+    ///
+    /// Long-lived devices call this before each connect-and-read cycle.
+    pub fn reset(&self) {
+        let mut state = self.lock();
+        state.started = Instant::now();
+        state.attempts.clear();
+    }
+
+    /// Run one step with its own `step_timeout`, capped at the remaining budget.
+    ///
+    /// Returns [`Error::Timeout`] if the step's own timeout fires, and
+    /// [`Error::TimeoutExceeded`] if the budget runs out first (or had already
+    /// run out).
+    pub async fn run<T, F>(&self, operation: &str, step_timeout: Duration, step: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let remaining = self.remaining();
+        if remaining.is_zero() {
+            return Err(self.exceeded(operation));
+        }
+
+        let limit = step_timeout.min(remaining);
+        let start = Instant::now();
+        let result = timeout(limit, step).await;
+        let elapsed = start.elapsed();
+
+        match result {
+            Ok(Ok(value)) => {
+                self.record(operation, elapsed, None);
+                Ok(value)
+            }
+            Ok(Err(e)) => {
+                self.record(operation, elapsed, Some(e.to_string()));
+                Err(e)
+            }
+            Err(_) if limit < step_timeout => {
+                self.record(
+                    operation,
+                    elapsed,
+                    Some("timeout budget exhausted".to_string()),
+                );
+                Err(self.exceeded(operation))
+            }
+            Err(_) => {
+                let error = Error::timeout(operation, limit);
+                self.record(operation, elapsed, Some(error.to_string()));
+                Err(error)
+            }
+        }
+    }
+
+    /// Build the error returned when the budget is exhausted.
+    fn exceeded(&self, operation: &str) -> Error {
+        Error::TimeoutExceeded {
+            operation: operation.to_string(),
+            budget: self.total,
+            attempts: self.attempts(),
+        }
+    }
+
+    fn record(&self, operation: &str, elapsed: Duration, error: Option<String>) {
+        self.lock().attempts.push(TimeoutAttempt {
+            operation: operation.to_string(),
+            elapsed,
+            error,
+        });
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BudgetState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Run a step with `step_timeout`, under `budget` when there is one.
+///
+/// Without a budget this is a plain timeout returning [`Error::Timeout`].
+pub(crate) async fn run_step<T, F>(
+    budget: Option<&TimeoutBudget>,
+    operation: &str,
+    step_timeout: Duration,
+    step: F,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    match budget {
+        Some(budget) => budget.run(operation, step_timeout, step).await,
+        None => timeout(step_timeout, step)
+            .await
+            .map_err(|_| Error::timeout(operation, step_timeout))?,
+    }
+}
+
+/// Run a step that has no timeout of its own, bounded only by `budget`.
+pub(crate) async fn run_unbounded<T, F>(
+    budget: Option<&TimeoutBudget>,
+    operation: &str,
+    step: F,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    match budget {
+        Some(budget) => budget.run(operation, Duration::MAX, step).await,
+        None => step.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_steps_are_capped_at_remaining_budget() {
+        let budget = TimeoutBudget::new(Duration::from_secs(10));
+
+        budget
+            .run("connect", Duration::from_secs(8), async {
+                tokio::time::sleep(Duration::from_secs(6)).await;
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        // The read's own 8s timeout would exceed the 4s left in the budget
+        let err = budget
+            .run("read", Duration::from_secs(8), async {
+                tokio::time::sleep(Duration::from_secs(7)).await;
+                Ok(())
+            })
+            .await
+            .unwrap_err();
+
+        match err {
+            Error::TimeoutExceeded {
+                operation,
+                budget: total,
+                attempts,
+            } => {
+                assert_eq!(operation, "read");
+                assert_eq!(total, Duration::from_secs(10));
+                assert_eq!(attempts.len(), 2);
+                assert_eq!(attempts[0].error, None);
+                assert_eq!(attempts[1].elapsed, Duration::from_secs(4));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert!(budget.is_exhausted());
+
+        // Further steps fail immediately
+        let err = budget
+            .run("retry", Duration::from_secs(1), async { Ok(()) })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::TimeoutExceeded { .. }));
+
+        budget.reset();
+        assert_eq!(budget.remaining(), Duration::from_secs(10));
+        assert!(budget.attempts().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_step_timeout_within_budget_is_plain_timeout() {
+        let budget = TimeoutBudget::new(Duration::from_secs(60));
+        let err = budget
+            .run("discover services", Duration::from_secs(5), async {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                Ok(())
+            })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::Timeout { .. }));
+        assert_eq!(budget.attempts().len(), 1);
+        assert!(!budget.is_exhausted());
+    }
+}
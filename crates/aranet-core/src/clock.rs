@@ -0,0 +1,323 @@
+//! Device clock drift estimation and correction.
+//!
+//! Aranet devices have no readable wall clock. They only report how many
+//! seconds ago the last measurement was taken and the measurement interval.
+//! History timestamps are reconstructed from those two numbers, assuming the
+//! device's seconds are the same length as ours. A crystal that runs a little
+//! fast or slow makes older history records drift away from their true time.
+//!
+//! Measurements land on a fixed grid in device time, so the grid can be used
+//! as the device clock: [`ClockDriftTracker`] watches where measurements land
+//! in host time across polls and estimates the drift rate, and
+//! [`correct_history_timestamps`] rescales downloaded history accordingly.
+//!
+//! # Example
+//!
+//! ```
+//! use aranet_core::clock::ClockDriftTracker;
+//! use time::{Duration, OffsetDateTime};
+//!
+//! let mut tracker = ClockDriftTracker::new();
+//! let start = OffsetDateTime::now_utc();
+//!
+//! // First poll only sets the reference point
+//! assert!(tracker.observe(start, 30, 300).is_none());
+//!
+//! // A day later the device is 9 seconds behind the 300s grid
+//! let later = start + Duration::days(1) + Duration::seconds(9);
+//! let drift = tracker.observe(later, 30, 300).unwrap();
+//! assert!(drift.ppm < -100.0);
+//! ```
+
+use aranet_types::HistoryRecord;
+use time::{Duration, OffsetDateTime};
+
+/// Minimum time between the reference and current measurement before a drift
+/// estimate is reported.
+///
+/// `age` has one-second resolution and BLE adds a second or two of latency,
+/// so shorter spans are dominated by noise.
+pub const MIN_DRIFT_SPAN: Duration = Duration::hours(6);
+
+/// Longest span a single estimate covers before the reference point is
+/// moved forward.
+///
+/// Bounds how far the step count can be extrapolated from a stale estimate
+/// and keeps the estimate tracking slow changes with temperature and age.
+pub const MAX_DRIFT_SPAN: Duration = Duration::days(7);
+
+/// Drift rates beyond this are treated as a discontinuity (device reboot,
+/// battery swap) rather than real drift, and restart the estimate.
+pub const MAX_PLAUSIBLE_DRIFT_PPM: f64 = 2000.0;
+
+/// An estimate of how fast a device's clock runs relative to the host.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockDrift {
+    /// Drift rate in parts per million.
+    ///
+    /// Positive means the device clock runs fast (its interval is shorter
+    /// than advertised), negative means it runs slow.
+    pub ppm: f64,
+    /// Host time covered by the estimate.
+    pub span: Duration,
+}
+
+impl ClockDrift {
+    /// Accumulated drift per day, in seconds.
+    pub fn seconds_per_day(&self) -> f64 {
+        self.ppm * 86_400.0 / 1_000_000.0
+    }
+}
+
+/// Estimates device clock drift from successive current readings.
+///
+/// Feed every successful poll into [`observe`](Self::observe). The first
+/// observation becomes the reference point; later ones are compared against
+/// it once [`MIN_DRIFT_SPAN`] has passed, so the estimate gets more precise
+/// the longer the tracker runs, up to [`MAX_DRIFT_SPAN`]. A change of
+/// measurement interval restarts the estimate.
+#[derive(Debug, Clone, Default)]
+pub struct ClockDriftTracker {
+    reference: Option<Reference>,
+    /// Last reported drift, used to count measurements across poll gaps.
+    estimate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Reference {
+    /// Host time of the reference measurement.
+    start: OffsetDateTime,
+    /// Measurement interval at that time.
+    interval: u16,
+    /// Host time of the most recent measurement observed.
+    last: OffsetDateTime,
+    /// Measurements the device has taken between `start` and `last`.
+    steps: f64,
+}
+
+impl Reference {
+    fn new(measured_at: OffsetDateTime, interval: u16) -> Self {
+        Self {
+            start: measured_at,
+            interval,
+            last: measured_at,
+            steps: 0.0,
+        }
+    }
+}
+
+impl ClockDriftTracker {
+    /// Create a tracker with no reference point.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a reading taken at host time `now`.
+    ///
+    /// `age` is the seconds since the device's last measurement and
+    /// `interval` the measurement interval, both as reported in the reading.
+    /// Returns a drift estimate once enough time has passed.
+    pub fn observe(&mut self, now: OffsetDateTime, age: u16, interval: u16) -> Option<ClockDrift> {
+        if interval == 0 {
+            return None;
+        }
+
+        let measured_at = now - Duration::seconds(i64::from(age));
+        let Some(reference) = self.reference.as_mut() else {
+            self.reference = Some(Reference::new(measured_at, interval));
+            return None;
+        };
+        if reference.interval != interval || measured_at < reference.last {
+            self.reference = Some(Reference::new(measured_at, interval));
+            return None;
+        }
+
+        // The device took a whole number of measurements since the last
+        // poll. Counting them per gap, scaled by the drift seen so far, keeps
+        // the count exact even once the total drift exceeds half an interval.
+        let scale = 1.0 + self.estimate.unwrap_or(0.0) / 1_000_000.0;
+        let gap = (measured_at - reference.last).as_seconds_f64();
+        reference.steps += (gap * scale / f64::from(interval)).round();
+        reference.last = measured_at;
+
+        let span = measured_at - reference.start;
+        if span < MIN_DRIFT_SPAN {
+            return None;
+        }
+
+        // The device believes exactly `steps * interval` seconds passed
+        let host_secs = span.as_seconds_f64();
+        let device_secs = reference.steps * f64::from(interval);
+        let ppm = (device_secs - host_secs) / host_secs * 1_000_000.0;
+
+        if ppm.abs() > MAX_PLAUSIBLE_DRIFT_PPM {
+            self.reset();
+            self.reference = Some(Reference::new(measured_at, interval));
+            return None;
+        }
+
+        self.estimate = Some(ppm);
+        if span >= MAX_DRIFT_SPAN {
+            *reference = Reference::new(measured_at, interval);
+        }
+
+        Some(ClockDrift { ppm, span })
+    }
+
+    /// Forget the reference point and any previous estimate.
+    pub fn reset(&mut self) {
+        self.reference = None;
+        self.estimate = None;
+    }
+}
+
+/// Rescale history timestamps for a measured clock drift.
+///
+/// The newest record is anchored to host time (it was taken `age` seconds
+/// before download) and is left alone; every older record is moved so its
+/// distance from the newest reflects real rather than device seconds.
+pub fn correct_history_timestamps(records: &mut [HistoryRecord], drift_ppm: f64) {
+    let Some(newest) = records.iter().map(|r| r.timestamp).max() else {
+        return;
+    };
+    let scale = 1.0 + drift_ppm / 1_000_000.0;
+    if scale <= 0.0 || drift_ppm == 0.0 {
+        return;
+    }
+
+    for record in records {
+        let device_offset = (newest - record.timestamp).as_seconds_f64();
+        let real_offset = (device_offset / scale).round() as i64;
+        record.timestamp = newest - Duration::seconds(real_offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record_at(timestamp: OffsetDateTime) -> HistoryRecord {
+        HistoryRecord {
+            timestamp,
+            co2: 800,
+            temperature: 21.0,
+            pressure: 1013.0,
+            humidity: 40,
+            radon: None,
+            radiation_rate: None,
+            radiation_total: None,
+        }
+    }
+
+    #[test]
+    fn test_tracker_needs_min_span() {
+        let mut tracker = ClockDriftTracker::new();
+        let t0 = OffsetDateTime::now_utc();
+
+        assert!(tracker.observe(t0, 10, 60).is_none());
+        assert!(tracker.observe(t0 + Duration::hours(1), 10, 60).is_none());
+        assert!(tracker.observe(t0 + MIN_DRIFT_SPAN, 10, 60).is_some());
+    }
+
+    #[test]
+    fn test_tracker_estimates_fast_clock() {
+        let mut tracker = ClockDriftTracker::new();
+        let t0 = OffsetDateTime::now_utc();
+        tracker.observe(t0, 0, 300);
+
+        // After one host day the device has completed 288 intervals, but the
+        // last one landed 8.64 seconds early: +100 ppm
+        let t1 = t0 + Duration::days(1) - Duration::milliseconds(8640);
+        let drift = tracker.observe(t1, 0, 300).unwrap();
+        assert!((drift.ppm - 100.0).abs() < 0.5, "ppm = {}", drift.ppm);
+        assert!((drift.seconds_per_day() - 8.64).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_tracker_resets_on_interval_change_and_jumps() {
+        let mut tracker = ClockDriftTracker::new();
+        let t0 = OffsetDateTime::now_utc();
+        tracker.observe(t0, 0, 300);
+
+        // Interval change restarts the estimate
+        assert!(tracker.observe(t0 + Duration::days(1), 0, 60).is_none());
+        assert!(
+            tracker
+                .observe(t0 + Duration::days(1) + MIN_DRIFT_SPAN, 0, 60)
+                .is_some()
+        );
+
+        // A reboot shifts the measurement grid by far more than any real drift
+        let mut tracker = ClockDriftTracker::new();
+        tracker.observe(t0, 0, 300);
+        let t2 = t0 + MIN_DRIFT_SPAN + Duration::seconds(120);
+        assert!(tracker.observe(t2, 0, 300).is_none());
+        // ...and the estimate restarts from there
+        assert!(tracker.observe(t2 + MIN_DRIFT_SPAN, 0, 300).is_some());
+    }
+
+    /// Poll a device whose clock runs `ppm` fast every `poll` for `days`,
+    /// returning every estimate reported along the way.
+    fn simulate(ppm: f64, interval: u16, poll: Duration, days: i64) -> Vec<ClockDrift> {
+        let mut tracker = ClockDriftTracker::new();
+        let t0 = OffsetDateTime::now_utc();
+        let device_interval = f64::from(interval) / (1.0 + ppm / 1_000_000.0);
+        let mut estimates = Vec::new();
+
+        let mut host = Duration::ZERO;
+        while host < Duration::days(days) {
+            let steps = (host.as_seconds_f64() / device_interval).floor();
+            let measured_at = t0 + Duration::seconds_f64(steps * device_interval);
+            let age = (t0 + host - measured_at).whole_seconds() as u16;
+            let now = measured_at + Duration::seconds(i64::from(age));
+            estimates.extend(tracker.observe(now, age, interval));
+            host += poll;
+        }
+        estimates
+    }
+
+    #[test]
+    fn test_tracker_follows_drift_over_many_days() {
+        for ppm in [50.0, 100.0, 200.0, -150.0] {
+            // At 100 ppm a 60s grid drifts by half an interval in 3.5 days
+            let estimates = simulate(ppm, 60, Duration::minutes(7), 10);
+            assert!(!estimates.is_empty());
+            for drift in &estimates {
+                assert!(drift.span <= MAX_DRIFT_SPAN + Duration::minutes(7));
+                assert!(
+                    (drift.ppm - ppm).abs() < 5.0,
+                    "expected {ppm} ppm, got {} after {}",
+                    drift.ppm,
+                    drift.span
+                );
+            }
+            // Span is capped, so estimates keep coming after a re-anchor
+            assert!(
+                estimates
+                    .iter()
+                    .filter(|d| d.span < MIN_DRIFT_SPAN + Duration::hours(1))
+                    .count()
+                    >= 2
+            );
+        }
+    }
+
+    #[test]
+    fn test_correct_history_timestamps() {
+        let newest = OffsetDateTime::now_utc();
+        let mut records = vec![
+            record_at(newest - Duration::seconds(1_000_000)),
+            record_at(newest),
+        ];
+
+        // Device runs 100 ppm fast: a million device seconds is 100 fewer real seconds
+        correct_history_timestamps(&mut records, 100.0);
+        assert_eq!(records[1].timestamp, newest);
+        assert_eq!((newest - records[0].timestamp).whole_seconds(), 999_900);
+
+        // No drift leaves timestamps alone
+        let before = records[0].timestamp;
+        correct_history_timestamps(&mut records, 0.0);
+        assert_eq!(records[0].timestamp, before);
+    }
+}
@@ -0,0 +1,498 @@
+//! Layered configuration loading shared by the CLI and the service.
+//!
+//! A setting is resolved from four layers, each overriding the one before:
+//!
+//! 1. Built-in defaults (the `#[serde(default)]` values of the config type)
+//! 2. The TOML config file
+//! 3. `ARANET_*` environment variables
+//! 4. Command-line flags, applied by the caller to the loaded value
+//!
+//! An environment variable names a key path in upper case, with `__`
+//! between table levels: `ARANET_TIMEOUT` sets `timeout` and
+//! `ARANET_SECURITY__API_KEY` sets `api_key` in the `[security]` table.
+//! Values are read as TOML literals (`true`, `30`, `["a", "b"]`) and fall
+//! back to plain strings, so `ARANET_DEVICE=Aranet4 12345` needs no quotes.
+//! Empty variables are ignored.
+//!
+//! Errors name the offending key and the layer it came from, e.g.
+//! ``Invalid value for `timeout` (from ARANET_TIMEOUT): invalid type: ...``.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aranet_core::config::ConfigLoader;
+//! use serde::Deserialize;
+//!
+//! #[derive(Debug, Default, Deserialize)]
+//! #[serde(default)]
+//! struct Settings {
+//!     timeout: Option<u64>,
+//! }
+//!
+//! # fn example() -> Result<(), aranet_core::config::ConfigError> {
+//! let settings: Settings = ConfigLoader::from_env().load_or_default("config.toml")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use toml::{Table, Value};
+
+/// Prefix of environment variables that override config keys.
+pub const ENV_PREFIX: &str = "ARANET_";
+
+/// Variables that locate files rather than name config keys.
+const RESERVED_VARS: &[&str] = &["ARANET_CONFIG_DIR", "ARANET_DATA_DIR"];
+
+/// Where a configuration value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Built-in defaults.
+    Default,
+    /// The config file at this path.
+    File(PathBuf),
+    /// The named environment variable.
+    Env(String),
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => write!(f, "defaults"),
+            Self::File(path) => write!(f, "in {}", path.display()),
+            Self::Env(var) => write!(f, "from {var}"),
+        }
+    }
+}
+
+/// Configuration errors.
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Failed to read config file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Failed to parse config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("Invalid value for `{key}` ({origin}): {message}")]
+    InvalidValue {
+        /// The key path (e.g., `server.bind` or `devices[0].address`).
+        key: String,
+        /// The layer that supplied the value.
+        origin: ConfigSource,
+        /// Description of the problem.
+        message: String,
+    },
+    #[error("Failed to serialize config: {0}")]
+    Serialize(toml::ser::Error),
+    #[error("Failed to write config file {path}: {source}")]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Configuration validation failed:\n{}", format_validation_errors(.0))]
+    Validation(Vec<ValidationError>),
+}
+
+impl ConfigError {
+    /// The key path this error points at, if it concerns a single key.
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            Self::InvalidValue { key, .. } => Some(key),
+            Self::Validation(errors) if errors.len() == 1 => Some(&errors[0].field),
+            _ => None,
+        }
+    }
+}
+
+/// A single validation error with context.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{field}: {message}")]
+pub struct ValidationError {
+    /// The field path (e.g., `server.bind` or `devices[0].address`).
+    pub field: String,
+    /// Description of the validation failure.
+    pub message: String,
+}
+
+fn format_validation_errors(errors: &[ValidationError]) -> String {
+    errors
+        .iter()
+        .map(|e| format!("  - {}", e))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// An environment override of one config key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvOverride {
+    /// The variable name, e.g. `ARANET_SERVER__BIND`.
+    pub var: String,
+    /// The dotted key path it sets, e.g. `server.bind`.
+    pub key: String,
+    /// The raw variable value.
+    pub value: String,
+}
+
+/// Loads a config type from defaults, a TOML file and environment overrides.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLoader {
+    overrides: Vec<EnvOverride>,
+}
+
+impl ConfigLoader {
+    /// A loader that reads only the config file, for read-modify-write of
+    /// the file where environment values must not be persisted.
+    pub fn file_only() -> Self {
+        Self::default()
+    }
+
+    /// A loader that applies `ARANET_*` variables from the process
+    /// environment.
+    pub fn from_env() -> Self {
+        Self::with_vars(std::env::vars())
+    }
+
+    /// A loader that applies `ARANET_*` variables from `vars`.
+    pub fn with_vars<I, K, V>(vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let mut overrides: Vec<EnvOverride> = vars
+            .into_iter()
+            .filter_map(|(var, value)| {
+                let var = var.into();
+                let value = value.into();
+                let key = env_key(&var)?;
+                (!value.is_empty() && !RESERVED_VARS.contains(&var.as_str()))
+                    .then_some(EnvOverride { var, key, value })
+            })
+            .collect();
+        // Apply parents before children so `ARANET_SERVER` cannot clobber
+        // `ARANET_SERVER__BIND`
+        overrides.sort_by_key(|o| (o.key.split('.').count(), o.var.clone()));
+        Self { overrides }
+    }
+
+    /// Skip a variable that is not a config key, e.g. one read by the
+    /// argument parser.
+    pub fn ignore(mut self, var: &str) -> Self {
+        self.overrides.retain(|o| o.var != var);
+        self
+    }
+
+    /// The environment overrides this loader applies.
+    pub fn overrides(&self) -> &[EnvOverride] {
+        &self.overrides
+    }
+
+    /// Load `T` from the file at `path` and the environment.
+    ///
+    /// Returns [`ConfigError::Read`] if the file does not exist.
+    pub fn load<T: DeserializeOwned>(&self, path: impl AsRef<Path>) -> Result<T, ConfigError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| ConfigError::Read {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        self.resolve(Some((&content, path)))
+    }
+
+    /// Load `T` like [`load`](Self::load), using defaults when the file is
+    /// absent.
+    pub fn load_or_default<T: DeserializeOwned>(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<T, ConfigError> {
+        let path = path.as_ref();
+        if path.exists() {
+            self.load(path)
+        } else {
+            self.resolve(None)
+        }
+    }
+
+    fn resolve<T: DeserializeOwned>(&self, file: Option<(&str, &Path)>) -> Result<T, ConfigError> {
+        let (mut table, file_source) = match file {
+            Some((content, path)) => {
+                let table = content.parse::<Table>().map_err(|e| ConfigError::Parse {
+                    path: path.to_path_buf(),
+                    source: e,
+                })?;
+                (table, ConfigSource::File(path.to_path_buf()))
+            }
+            None => (Table::new(), ConfigSource::Default),
+        };
+
+        // Surface file errors before env values can mask them
+        deserialize::<T>(&table).map_err(|(key, message)| ConfigError::InvalidValue {
+            key,
+            origin: file_source.clone(),
+            message,
+        })?;
+
+        for o in &self.overrides {
+            let origin = || ConfigSource::Env(o.var.clone());
+            let parsed = parse_env_value(&o.value);
+            let is_string = parsed.is_str();
+            set_path(&mut table, &o.key, parsed).map_err(|message| ConfigError::InvalidValue {
+                key: o.key.clone(),
+                origin: origin(),
+                message,
+            })?;
+
+            let Err((key, message)) = deserialize::<T>(&table) else {
+                continue;
+            };
+            // `ARANET_DEVICE=12345` is a name, not a number: retry as a string
+            if !is_string {
+                set_path(&mut table, &o.key, Value::String(o.value.clone()))
+                    .expect("path was just set");
+                if deserialize::<T>(&table).is_ok() {
+                    continue;
+                }
+            }
+            return Err(ConfigError::InvalidValue {
+                key,
+                origin: origin(),
+                message,
+            });
+        }
+
+        deserialize::<T>(&table).map_err(|(key, message)| ConfigError::InvalidValue {
+            key,
+            origin: file_source,
+            message,
+        })
+    }
+}
+
+/// Write `value` as TOML to `path`, creating parent directories.
+///
+/// On Unix the file is made readable by the owner only, since configs may
+/// hold API keys.
+pub fn save<T: Serialize>(value: &T, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+    let path = path.as_ref();
+    let content = toml::to_string_pretty(value).map_err(ConfigError::Serialize)?;
+
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent).map_err(|e| ConfigError::Write {
+            path: parent.to_path_buf(),
+            source: e,
+        })?;
+    }
+
+    std::fs::write(path, content).map_err(|e| ConfigError::Write {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let perms = std::fs::Permissions::from_mode(0o600);
+        let _ = std::fs::set_permissions(path, perms);
+    }
+
+    Ok(())
+}
+
+/// Map `ARANET_SECURITY__API_KEY` to `security.api_key`.
+fn env_key(var: &str) -> Option<String> {
+    let rest = var.strip_prefix(ENV_PREFIX)?;
+    let parts: Vec<String> = rest.split("__").map(str::to_lowercase).collect();
+    if parts.iter().any(String::is_empty) {
+        return None;
+    }
+    Some(parts.join("."))
+}
+
+fn parse_env_value(raw: &str) -> Value {
+    format!("v = {raw}")
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut t| t.remove("v"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+fn set_path(table: &mut Table, key: &str, value: Value) -> Result<(), String> {
+    let mut parts = key.split('.').peekable();
+    let mut current = table;
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            current.insert(part.to_string(), value);
+            return Ok(());
+        }
+        let entry = current
+            .entry(part.to_string())
+            .or_insert_with(|| Value::Table(Table::new()));
+        current = entry
+            .as_table_mut()
+            .ok_or_else(|| format!("`{part}` is not a table"))?;
+    }
+    Ok(())
+}
+
+fn deserialize<T: DeserializeOwned>(table: &Table) -> Result<T, (String, String)> {
+    serde_path_to_error::deserialize(Value::Table(table.clone())).map_err(|e| {
+        let key = e.path().to_string();
+        (key, e.into_inner().message().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Default, Deserialize)]
+    #[serde(default)]
+    struct TestConfig {
+        device: Option<String>,
+        timeout: Option<u64>,
+        verbose: bool,
+        server: TestServer,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(default)]
+    struct TestServer {
+        bind: String,
+        tags: Vec<String>,
+    }
+
+    impl Default for TestServer {
+        fn default() -> Self {
+            Self {
+                bind: "127.0.0.1:8080".to_string(),
+                tags: Vec::new(),
+            }
+        }
+    }
+
+    fn loader(vars: &[(&str, &str)]) -> ConfigLoader {
+        ConfigLoader::with_vars(vars.iter().map(|(k, v)| (k.to_string(), v.to_string())))
+    }
+
+    const FILE: &str = r#"
+timeout = 10
+
+[server]
+bind = "0.0.0.0:9000"
+"#;
+
+    fn resolve(loader: &ConfigLoader, content: &str) -> Result<TestConfig, ConfigError> {
+        loader.resolve(Some((content, Path::new("test.toml"))))
+    }
+
+    #[test]
+    fn test_env_key_mapping() {
+        assert_eq!(env_key("ARANET_TIMEOUT").as_deref(), Some("timeout"));
+        assert_eq!(
+            env_key("ARANET_SECURITY__API_KEY").as_deref(),
+            Some("security.api_key")
+        );
+        assert_eq!(env_key("ARANET_"), None);
+        assert_eq!(env_key("ARANET_SERVER__"), None);
+        assert_eq!(env_key("HOME"), None);
+    }
+
+    #[test]
+    fn test_layers_override_in_order() {
+        let config = resolve(&loader(&[]), FILE).unwrap();
+        assert_eq!(config.timeout, Some(10));
+        assert_eq!(config.server.bind, "0.0.0.0:9000");
+        assert!(!config.verbose);
+
+        let config = resolve(
+            &loader(&[
+                ("ARANET_TIMEOUT", "30"),
+                ("ARANET_VERBOSE", "true"),
+                ("ARANET_SERVER__TAGS", r#"["a", "b"]"#),
+            ]),
+            FILE,
+        )
+        .unwrap();
+        assert_eq!(config.timeout, Some(30));
+        assert!(config.verbose);
+        assert_eq!(config.server.bind, "0.0.0.0:9000");
+        assert_eq!(config.server.tags, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_env_without_file() {
+        let l = loader(&[("ARANET_SERVER__BIND", "10.0.0.1:80")]);
+        let config: TestConfig = l.resolve(None).unwrap();
+        assert_eq!(config.server.bind, "10.0.0.1:80");
+        assert_eq!(config.timeout, None);
+    }
+
+    #[test]
+    fn test_env_strings_need_no_quotes() {
+        let config = resolve(&loader(&[("ARANET_DEVICE", "12345")]), FILE).unwrap();
+        assert_eq!(config.device.as_deref(), Some("12345"));
+
+        let config = resolve(&loader(&[("ARANET_DEVICE", "Aranet4 17C3C")]), FILE).unwrap();
+        assert_eq!(config.device.as_deref(), Some("Aranet4 17C3C"));
+    }
+
+    #[test]
+    fn test_reserved_and_empty_vars_ignored() {
+        let l = loader(&[
+            ("ARANET_CONFIG_DIR", "/tmp"),
+            ("ARANET_TIMEOUT", ""),
+            ("ARANET_STYLE", "plain"),
+        ])
+        .ignore("ARANET_STYLE");
+        assert!(l.overrides().is_empty());
+    }
+
+    #[test]
+    fn test_env_error_names_variable_and_key() {
+        let err = resolve(&loader(&[("ARANET_TIMEOUT", "soon")]), FILE).unwrap_err();
+        match &err {
+            ConfigError::InvalidValue { key, origin, .. } => {
+                assert_eq!(key, "timeout");
+                assert_eq!(origin, &ConfigSource::Env("ARANET_TIMEOUT".to_string()));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert!(err.to_string().contains("`timeout` (from ARANET_TIMEOUT)"));
+    }
+
+    #[test]
+    fn test_env_error_through_non_table() {
+        let err = resolve(&loader(&[("ARANET_TIMEOUT__SECS", "5")]), FILE).unwrap_err();
+        assert_eq!(err.key(), Some("timeout.secs"));
+    }
+
+    #[test]
+    fn test_file_error_names_key_and_path() {
+        let err = resolve(&loader(&[]), "[server]\nbind = 8080\n").unwrap_err();
+        match &err {
+            ConfigError::InvalidValue { key, origin, .. } => {
+                assert_eq!(key, "server.bind");
+                assert_eq!(origin, &ConfigSource::File(PathBuf::from("test.toml")));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_file_syntax_error() {
+        let err = resolve(&loader(&[]), "timeout = [").unwrap_err();
+        assert!(matches!(err, ConfigError::Parse { .. }));
+    }
+}
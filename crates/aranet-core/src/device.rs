@@ -5,24 +5,35 @@
 
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use btleplug::api::{Characteristic, Peripheral as _, WriteType};
+use btleplug::api::{
+    Central as _, CharPropFlags, Characteristic, Peripheral as _, ScanFilter, WriteType,
+};
 use btleplug::platform::{Adapter, Peripheral};
+use serde::Serialize;
 use tokio::sync::RwLock;
 use tokio::time::timeout;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use crate::budget::{TimeoutBudget, run_step, run_unbounded};
+use crate::diagnostics::{OperationType, global_diagnostics};
 use crate::error::{Error, Result};
+use crate::platform::ConnectStrategy;
+use crate::queue::OperationQueue;
+use crate::quirks::Quirks;
+use crate::retry::{RetryConfig, with_retry};
 use crate::scan::{ScanOptions, find_device};
+use crate::settings::DeviceSettings;
 use crate::traits::AranetDevice;
 use crate::util::{create_identifier, format_peripheral_id};
 use crate::uuid::{
-    BATTERY_LEVEL, BATTERY_SERVICE, CURRENT_READINGS_DETAIL, CURRENT_READINGS_DETAIL_ALT,
-    DEVICE_INFO_SERVICE, DEVICE_NAME, FIRMWARE_REVISION, GAP_SERVICE, HARDWARE_REVISION,
-    MANUFACTURER_NAME, MODEL_NUMBER, SAF_TEHNIKA_SERVICE_NEW, SAF_TEHNIKA_SERVICE_OLD,
-    SERIAL_NUMBER, SOFTWARE_REVISION,
+    BATTERY_LEVEL, BATTERY_SERVICE, COMMAND, CURRENT_READINGS, CURRENT_READINGS_DETAIL,
+    CURRENT_READINGS_DETAIL_ALT, DEVICE_INFO_SERVICE, DEVICE_NAME, FIRMWARE_REVISION, GAP_SERVICE,
+    HARDWARE_REVISION, MANUFACTURER_ID, MANUFACTURER_NAME, MODEL_NUMBER, SAF_TEHNIKA_SERVICE_NEW,
+    SAF_TEHNIKA_SERVICE_OLD, SENSOR_STATE, SERIAL_NUMBER, SOFTWARE_REVISION,
 };
 use aranet_types::{CurrentReading, DeviceInfo, DeviceType};
 
@@ -68,6 +79,21 @@ pub struct Device {
     disconnected: AtomicBool,
     /// Connection configuration (timeouts, etc.).
     config: ConnectionConfig,
+    /// Overall deadline shared by the connection and later operations,
+    /// when [`ConnectionConfig::total_timeout`] is set.
+    budget: Option<Arc<TimeoutBudget>>,
+    /// Firmware quirks, selected when device info is read.
+    quirks: Mutex<Quirks>,
+    /// Serializes GATT operations from concurrent callers.
+    pub(crate) operations: OperationQueue,
+    /// Settings from the last [`Device::get_settings_cached`] call.
+    ///
+    /// Cleared when a command is written or the device reports a settings
+    /// change, so the next read goes back to the device.
+    settings: Arc<Mutex<Option<DeviceSettings>>>,
+    /// Whether [`Device::read_snapshot`] overlaps its reads. Cleared when the
+    /// BLE stack turns out not to support it.
+    pipelined_reads: AtomicBool,
 }
 
 impl std::fmt::Debug for Device {
@@ -80,10 +106,14 @@ impl std::fmt::Debug for Device {
             .field("address", &self.address)
             .field("device_type", &self.device_type)
             .field("services_discovered", &self.services_discovered)
+            .field("quirks", &self.quirks())
             .finish_non_exhaustive()
     }
 }
 
+/// Default number of operations that may wait for a busy device.
+const DEFAULT_MAX_QUEUED_OPERATIONS: usize = 32;
+
 /// Default timeout for BLE characteristic read operations.
 const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(10);
 
@@ -99,6 +129,42 @@ const DEFAULT_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(10);
 /// Default timeout for connection validation (keepalive check).
 const DEFAULT_VALIDATION_TIMEOUT: Duration = Duration::from_secs(3);
 
+/// How long to let the BLE stack settle after dropping a connection.
+const RECONNECT_SETTLE: Duration = Duration::from_secs(2);
+
+/// How long to scan before connecting to a previously discovered device.
+const REFRESH_SCAN_DURATION: Duration = Duration::from_millis(1500);
+
+/// Wait for the BLE stack to settle after a disconnect, within `budget`.
+async fn settle(budget: Option<&TimeoutBudget>) {
+    tokio::time::sleep(budget.map_or(RECONNECT_SETTLE, |b| b.remaining().min(RECONNECT_SETTLE)))
+        .await;
+}
+
+/// Scan briefly so the BLE stack sees the device again before connecting.
+///
+/// BlueZ drops devices it has not seen advertise recently. Failures are
+/// only logged; the connection attempt reports anything that matters.
+async fn refresh_scan(adapter: &Adapter, budget: Option<&TimeoutBudget>) {
+    debug!("Refreshing scan before connecting");
+    if let Err(e) = adapter.start_scan(ScanFilter::default()).await {
+        debug!("Refresh scan failed to start: {e}");
+        return;
+    }
+    let duration = budget.map_or(REFRESH_SCAN_DURATION, |b| {
+        b.remaining().min(REFRESH_SCAN_DURATION)
+    });
+    tokio::time::sleep(duration).await;
+    if let Err(e) = adapter.stop_scan().await {
+        debug!("Refresh scan failed to stop: {e}");
+    }
+}
+
+/// Whether the current platform supports pipelined reads.
+fn pipelined_reads_default() -> bool {
+    crate::platform::PlatformConfig::for_current_platform().supports_pipelined_reads
+}
+
 /// Configuration for BLE connection timeouts and behavior.
 ///
 /// Use this to customize timeout values for different environments.
@@ -128,6 +194,21 @@ pub struct ConnectionConfig {
     pub discovery_timeout: Duration,
     /// Timeout for connection validation (keepalive) checks.
     pub validation_timeout: Duration,
+    /// Re-pair and retry once when the connection fails with
+    /// [`Error::BondLost`]. Only effective on Linux; see [`crate::bonding`].
+    pub auto_repair_bond: bool,
+    /// Overall deadline for connecting (including scans and retries) and the
+    /// operations that follow. See [`crate::budget`].
+    pub total_timeout: Option<Duration>,
+    /// How many operations may wait for a busy device before further ones
+    /// fail with [`Error::Busy`].
+    pub max_queued_operations: usize,
+    /// Issue the reads of [`Device::read_snapshot`] together instead of one
+    /// after another. Off unless the platform is known to support it.
+    pub pipelined_reads: bool,
+    /// Workarounds for connection failures specific to the BLE stack.
+    /// Defaults to those the current platform needs.
+    pub connect_strategy: ConnectStrategy,
 }
 
 impl Default for ConnectionConfig {
@@ -138,6 +219,11 @@ impl Default for ConnectionConfig {
             write_timeout: DEFAULT_WRITE_TIMEOUT,
             discovery_timeout: DEFAULT_DISCOVERY_TIMEOUT,
             validation_timeout: DEFAULT_VALIDATION_TIMEOUT,
+            auto_repair_bond: false,
+            total_timeout: None,
+            max_queued_operations: DEFAULT_MAX_QUEUED_OPERATIONS,
+            pipelined_reads: pipelined_reads_default(),
+            connect_strategy: ConnectStrategy::for_current_platform(),
         }
     }
 }
@@ -157,6 +243,11 @@ impl ConnectionConfig {
             write_timeout: platform.recommended_operation_timeout,
             discovery_timeout: platform.recommended_operation_timeout,
             validation_timeout: DEFAULT_VALIDATION_TIMEOUT,
+            auto_repair_bond: false,
+            total_timeout: None,
+            max_queued_operations: DEFAULT_MAX_QUEUED_OPERATIONS,
+            pipelined_reads: platform.supports_pipelined_reads,
+            connect_strategy: platform.connect_strategy,
         }
     }
 
@@ -171,6 +262,11 @@ impl ConnectionConfig {
             write_timeout: Duration::from_secs(15),
             discovery_timeout: Duration::from_secs(30),
             validation_timeout: Duration::from_secs(5),
+            auto_repair_bond: false,
+            total_timeout: None,
+            max_queued_operations: DEFAULT_MAX_QUEUED_OPERATIONS,
+            pipelined_reads: pipelined_reads_default(),
+            connect_strategy: ConnectStrategy::for_current_platform(),
         }
     }
 
@@ -185,6 +281,11 @@ impl ConnectionConfig {
             write_timeout: Duration::from_secs(5),
             discovery_timeout: Duration::from_secs(5),
             validation_timeout: Duration::from_secs(2),
+            auto_repair_bond: false,
+            total_timeout: None,
+            max_queued_operations: DEFAULT_MAX_QUEUED_OPERATIONS,
+            pipelined_reads: pipelined_reads_default(),
+            connect_strategy: ConnectStrategy::for_current_platform(),
         }
     }
 
@@ -222,6 +323,89 @@ impl ConnectionConfig {
         self.validation_timeout = timeout;
         self
     }
+
+    /// Re-pair automatically when the device no longer accepts the stored
+    /// bond, then retry the connection once.
+    ///
+    /// Also restores remembered bonds before the first connection in the
+    /// process (see [`bonding::restore_bonds`](crate::bonding::restore_bonds)).
+    #[must_use]
+    pub fn auto_repair_bond(mut self, enabled: bool) -> Self {
+        self.auto_repair_bond = enabled;
+        self
+    }
+
+    /// Set how many operations may wait for a busy device.
+    ///
+    /// A history sync can hold the device for minutes; this bounds how much
+    /// work piles up behind it.
+    #[must_use]
+    pub fn max_queued_operations(mut self, max: usize) -> Self {
+        self.max_queued_operations = max;
+        self
+    }
+
+    /// Issue the reads of [`Device::read_snapshot`] together.
+    ///
+    /// When the BLE stack rejects overlapping reads the device falls back to
+    /// reading one characteristic at a time.
+    #[must_use]
+    pub fn pipelined_reads(mut self, enabled: bool) -> Self {
+        self.pipelined_reads = enabled;
+        self
+    }
+
+    /// Override the platform's connection workarounds.
+    ///
+    /// Use [`ConnectStrategy::none`] to connect without any.
+    #[must_use]
+    pub fn connect_strategy(mut self, strategy: ConnectStrategy) -> Self {
+        self.connect_strategy = strategy;
+        self
+    }
+
+    /// Bound connect, retries and reads by one overall deadline.
+    ///
+    /// Individual timeouts are capped at what is left of the budget, and
+    /// operations fail with [`Error::TimeoutExceeded`] once it runs out.
+    #[must_use]
+    pub fn total_timeout(mut self, timeout: Duration) -> Self {
+        self.total_timeout = Some(timeout);
+        self
+    }
+}
+
+/// How a characteristic write is acknowledged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteMode {
+    /// Write with response when the characteristic supports it, otherwise
+    /// without response.
+    Auto,
+    /// Wait for the device to acknowledge the write.
+    #[default]
+    WithResponse,
+    /// Send without waiting for an acknowledgement. Faster, but the write
+    /// can be dropped on a congested link.
+    WithoutResponse,
+}
+
+impl WriteMode {
+    /// The btleplug write type to use for a characteristic with `properties`.
+    fn write_type(self, properties: CharPropFlags) -> WriteType {
+        match self {
+            WriteMode::WithResponse => WriteType::WithResponse,
+            WriteMode::WithoutResponse => WriteType::WithoutResponse,
+            WriteMode::Auto => {
+                if !properties.contains(CharPropFlags::WRITE)
+                    && properties.contains(CharPropFlags::WRITE_WITHOUT_RESPONSE)
+                {
+                    WriteType::WithoutResponse
+                } else {
+                    WriteType::WithResponse
+                }
+            }
+        }
+    }
 }
 
 /// Signal strength quality levels based on RSSI values.
@@ -285,6 +469,176 @@ impl SignalQuality {
     }
 }
 
+/// A series of RSSI readings taken at a fixed interval.
+///
+/// Returned by [`Device::sample_rssi`]. A single RSSI reading swings by
+/// several dB, so the mean and spread of a series say far more about a
+/// sensor's placement than one value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RssiSamples {
+    /// Time between samples.
+    pub interval: Duration,
+    /// Samples in dBm, oldest first. Reads that failed are left out.
+    pub samples: Vec<i16>,
+}
+
+impl RssiSamples {
+    /// Create a series from samples taken `interval` apart.
+    pub fn new(interval: Duration, samples: Vec<i16>) -> Self {
+        Self { interval, samples }
+    }
+
+    /// Mean RSSI in dBm.
+    pub fn mean(&self) -> Option<f64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let sum: f64 = self.samples.iter().map(|&s| f64::from(s)).sum();
+        Some(sum / self.samples.len() as f64)
+    }
+
+    /// Population standard deviation in dB.
+    pub fn stddev(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        let variance = self
+            .samples
+            .iter()
+            .map(|&s| (f64::from(s) - mean).powi(2))
+            .sum::<f64>()
+            / self.samples.len() as f64;
+        Some(variance.sqrt())
+    }
+
+    /// Weakest sample in dBm.
+    pub fn min(&self) -> Option<i16> {
+        self.samples.iter().copied().min()
+    }
+
+    /// Strongest sample in dBm.
+    pub fn max(&self) -> Option<i16> {
+        self.samples.iter().copied().max()
+    }
+
+    /// Signal quality of the mean RSSI.
+    pub fn quality(&self) -> Option<SignalQuality> {
+        self.mean()
+            .map(|mean| SignalQuality::from_rssi(mean.round() as i16))
+    }
+}
+
+/// Take `count` RSSI readings `interval` apart using `read`.
+///
+/// Failed reads are skipped. Fails with the last error only if every read failed.
+pub(crate) async fn sample_rssi_with<F, Fut>(
+    interval: Duration,
+    count: usize,
+    mut read: F,
+) -> Result<RssiSamples>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<i16>>,
+{
+    if count == 0 {
+        return Err(Error::InvalidConfig(
+            "RSSI sample count must be at least 1".to_string(),
+        ));
+    }
+
+    let mut samples = Vec::with_capacity(count);
+    let mut last_error = None;
+    for i in 0..count {
+        if i > 0 {
+            tokio::time::sleep(interval).await;
+        }
+        match read().await {
+            Ok(rssi) => samples.push(rssi),
+            Err(e) => {
+                debug!("RSSI sample {} of {} failed: {}", i + 1, count, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    match last_error {
+        Some(e) if samples.is_empty() => Err(e),
+        _ => Ok(RssiSamples::new(interval, samples)),
+    }
+}
+
+/// Current reading, battery level and settings read in one go.
+///
+/// Returned by [`Device::read_snapshot`].
+#[derive(Debug, Clone)]
+pub struct DeviceSnapshot {
+    /// Current sensor measurements.
+    pub reading: CurrentReading,
+    /// Battery level (0-100).
+    pub battery: u8,
+    /// Device settings.
+    pub settings: DeviceSettings,
+}
+
+/// Whether `error` means the stack rejected a read because another one was
+/// still pending (BlueZ `InProgress`, "operation in progress", busy).
+///
+/// Anything else - a dropped link in particular - is a real failure and must
+/// not turn pipelining off or trigger a sequential retry.
+fn is_pipelining_unsupported(error: &Error) -> bool {
+    let message = match error.root() {
+        Error::Bluetooth(btleplug::Error::Other(e)) => e.to_string(),
+        Error::Bluetooth(btleplug::Error::RuntimeError(message)) => message.clone(),
+        _ => return false,
+    };
+    let message = message.to_ascii_lowercase();
+    ["inprogress", "in progress", "busy"]
+        .iter()
+        .any(|busy| message.contains(busy))
+}
+
+/// Read a snapshot using `reading`, `battery` and `settings`.
+///
+/// The three reads are polled together while `pipelined` is set. If that
+/// fails because the stack does not support it, `pipelined` is cleared and
+/// the reads are repeated one at a time.
+pub(crate) async fn read_snapshot_with<R, B, S, RF, BF, SF>(
+    pipelined: &AtomicBool,
+    reading: R,
+    battery: B,
+    settings: S,
+) -> Result<DeviceSnapshot>
+where
+    R: Fn() -> RF,
+    B: Fn() -> BF,
+    S: Fn() -> SF,
+    RF: std::future::Future<Output = Result<CurrentReading>>,
+    BF: std::future::Future<Output = Result<u8>>,
+    SF: std::future::Future<Output = Result<DeviceSettings>>,
+{
+    if pipelined.load(Ordering::Relaxed) {
+        let error = match tokio::join!(reading(), battery(), settings()) {
+            (Ok(reading), Ok(battery), Ok(settings)) => {
+                return Ok(DeviceSnapshot {
+                    reading,
+                    battery,
+                    settings,
+                });
+            }
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => e,
+        };
+        if !is_pipelining_unsupported(&error) {
+            return Err(error);
+        }
+        warn!("Pipelined reads not supported ({error}); reading sequentially");
+        pipelined.store(false, Ordering::Relaxed);
+    }
+
+    Ok(DeviceSnapshot {
+        reading: reading().await?,
+        battery: battery().await?,
+        settings: settings().await?,
+    })
+}
+
 impl Device {
     /// Connect to an Aranet device by name or MAC address.
     ///
@@ -315,7 +669,10 @@ impl Device {
     /// Connect to an Aranet device with full configuration.
     ///
     /// This is the most flexible connection method, allowing customization
-    /// of all timeout values.
+    /// of all timeout values. With [`ConnectionConfig::auto_repair_bond`], a
+    /// connection that fails with [`Error::BondLost`] is re-paired and
+    /// retried once. With [`ConnectionConfig::total_timeout`], the scan,
+    /// connection, retries and later reads all share one deadline.
     ///
     /// # Example
     ///
@@ -333,53 +690,130 @@ impl Device {
     /// ```
     #[tracing::instrument(level = "info", skip_all, fields(identifier = %identifier))]
     pub async fn connect_with_config(identifier: &str, config: ConnectionConfig) -> Result<Self> {
-        let options = ScanOptions {
-            duration: config.connection_timeout,
-            filter_aranet_only: false, // We're looking for a specific device
-            use_service_filter: false,
-        };
+        let started = Instant::now();
+        let budget = config
+            .total_timeout
+            .map(|total| Arc::new(TimeoutBudget::new(total)));
+        if config.auto_repair_bond {
+            crate::bonding::restore_remembered_bonds(budget.as_deref(), config.connection_timeout)
+                .await;
+        }
+        let result = async {
+            match Self::connect_once(identifier, config.clone(), budget.clone()).await {
+                Err(e) if config.auto_repair_bond => {
+                    let Error::BondLost { device, .. } = e.root() else {
+                        return Err(e);
+                    };
+                    warn!("Bond with {device} lost; re-pairing");
+                    run_unbounded(
+                        budget.as_deref(),
+                        "repair bond",
+                        crate::bonding::repair_bond(device),
+                    )
+                    .await
+                    .map_err(|e| e.context(format!("repair bond with {identifier}")))?;
+                    Self::connect_once(identifier, config, budget).await
+                }
+                result => result,
+            }
+        }
+        .await;
+        global_diagnostics()
+            .record_outcome(OperationType::Connect, started, Some(identifier), &result)
+            .await;
+        result
+    }
+
+    async fn connect_once(
+        identifier: &str,
+        config: ConnectionConfig,
+        budget: Option<Arc<TimeoutBudget>>,
+    ) -> Result<Self> {
+        // We're looking for a specific device
+        let options = ScanOptions::default()
+            .duration(config.connection_timeout)
+            .filter_aranet_only(false);
 
         // Try find_device first (uses default 5s scan), then with custom options
-        let (adapter, peripheral) = match find_device(identifier).await {
-            Ok(result) => result,
-            Err(_) => crate::scan::find_device_with_options(identifier, options).await?,
-        };
+        let (adapter, peripheral) = run_unbounded(budget.as_deref(), "scan", async {
+            match find_device(identifier).await {
+                Ok(result) => Ok(result),
+                Err(_) => crate::scan::find_device_with_options(identifier, options).await,
+            }
+        })
+        .await
+        .map_err(|e| {
+            e.context("scan")
+                .context(format!("connect to {identifier}"))
+        })?;
 
-        Self::from_peripheral_with_config(adapter, peripheral, config).await
+        Self::from_peripheral_budgeted(adapter, peripheral, config, budget)
+            .await
+            .map_err(|e| e.context(format!("connect to {identifier}")))
     }
 
     /// Connect to a device using an existing BLE adapter.
     ///
     /// This avoids creating a new btleplug `Manager` (and D-Bus connection) on
     /// every call.  Prefer this over [`connect_with_config`](Self::connect_with_config)
-    /// in long-running services that poll devices repeatedly.
+    /// in long-running services that poll devices repeatedly. Pass the
+    /// adapter from [`ScanService::adapter`](crate::ScanService::adapter) so
+    /// the device is found on the adapter the shared scan runs on.
     #[tracing::instrument(level = "info", skip_all, fields(identifier = %identifier))]
     pub async fn connect_with_adapter(
         adapter: Adapter,
         identifier: &str,
         config: ConnectionConfig,
     ) -> Result<Self> {
-        let options = ScanOptions {
-            duration: config.connection_timeout,
-            filter_aranet_only: false,
-            use_service_filter: false,
-        };
+        let started = Instant::now();
+        let budget = config
+            .total_timeout
+            .map(|total| Arc::new(TimeoutBudget::new(total)));
+        if config.auto_repair_bond {
+            crate::bonding::restore_remembered_bonds(budget.as_deref(), config.connection_timeout)
+                .await;
+        }
+        let result = Self::connect_with_adapter_inner(adapter, identifier, config, budget).await;
+        global_diagnostics()
+            .record_outcome(OperationType::Connect, started, Some(identifier), &result)
+            .await;
+        result
+    }
 
-        let peripheral = match crate::scan::find_device_with_adapter(
-            &adapter,
-            identifier,
-            ScanOptions::default(),
-        )
-        .await
-        {
-            Ok(p) => p,
-            Err(e) => {
-                debug!("Fast scan failed ({e}), retrying with extended options");
-                crate::scan::find_device_with_adapter(&adapter, identifier, options).await?
+    async fn connect_with_adapter_inner(
+        adapter: Adapter,
+        identifier: &str,
+        config: ConnectionConfig,
+        budget: Option<Arc<TimeoutBudget>>,
+    ) -> Result<Self> {
+        let options = ScanOptions::default()
+            .duration(config.connection_timeout)
+            .filter_aranet_only(false);
+
+        let peripheral = run_unbounded(budget.as_deref(), "scan", async {
+            match crate::scan::find_device_with_adapter(
+                &adapter,
+                identifier,
+                ScanOptions::default(),
+            )
+            .await
+            {
+                Ok(p) => Ok(p),
+                Err(e) => {
+                    debug!("Fast scan failed ({e}), retrying with extended options");
+                    crate::scan::find_device_with_adapter(&adapter, identifier, options).await
+                }
             }
-        };
+        })
+        .await
+        .map_err(|e| {
+            e.context("scan")
+                .context(format!("connect to {identifier}"))
+        })?;
 
-        Self::from_peripheral_with_config(adapter, peripheral, config).await
+        Self::from_peripheral_budgeted(adapter, peripheral, config, budget)
+            .await
+            .map_err(|e| e.context(format!("connect to {identifier}")))
     }
 
     /// Create a Device from an already-discovered peripheral.
@@ -406,48 +840,123 @@ impl Device {
         peripheral: Peripheral,
         config: ConnectionConfig,
     ) -> Result<Self> {
+        let budget = config
+            .total_timeout
+            .map(|total| Arc::new(TimeoutBudget::new(total)));
+        if config.connect_strategy.refresh_scan_before_connect {
+            refresh_scan(&adapter, budget.as_deref()).await;
+        }
+        Self::from_peripheral_budgeted(adapter, peripheral, config, budget).await
+    }
+
+    /// Connect to a discovered peripheral, sharing `budget` with earlier steps.
+    async fn from_peripheral_budgeted(
+        adapter: Adapter,
+        peripheral: Peripheral,
+        config: ConnectionConfig,
+        budget: Option<Arc<TimeoutBudget>>,
+    ) -> Result<Self> {
+        // Address for bond-loss errors, before the connection can fail
+        let bond_address = peripheral
+            .properties()
+            .await
+            .ok()
+            .flatten()
+            .map(|p| create_identifier(&p.address.to_string(), &peripheral.id()))
+            .unwrap_or_else(|| format_peripheral_id(&peripheral.id()));
+        let classify = |e: Error| crate::bonding::classify(e, &bond_address);
+        let strategy = config.connect_strategy;
+
+        // WinRT keeps service objects from an earlier connection until the
+        // peripheral is disconnected, even after the device dropped the link
+        if strategy.invalidate_service_cache && !peripheral.is_connected().await.unwrap_or(false) {
+            debug!("Clearing cached services before connecting");
+            let _ = peripheral.disconnect().await;
+        }
+
         // Connect to the device with timeout
         info!("Connecting to device...");
-        timeout(config.connection_timeout, peripheral.connect())
-            .await
-            .map_err(|_| Error::Timeout {
-                operation: "connect to device".to_string(),
-                duration: config.connection_timeout,
-            })??;
+        let connected = run_step(
+            budget.as_deref(),
+            "connect to device",
+            config.connection_timeout,
+            async { Ok(peripheral.connect().await?) },
+        )
+        .await
+        .map_err(classify);
+        match connected {
+            Err(e)
+                if strategy.retry_on_peer_removed_pairing
+                    && crate::bonding::is_peer_removed_pairing(&e) =>
+            {
+                // CoreBluetooth holds on to the stale pairing until the
+                // connection is dropped; the next attempt pairs afresh
+                warn!("Device removed its pairing information — reconnecting");
+                let _ = peripheral.disconnect().await;
+                settle(budget.as_deref()).await;
+                run_step(
+                    budget.as_deref(),
+                    "reconnect to device",
+                    config.connection_timeout,
+                    async { Ok(peripheral.connect().await?) },
+                )
+                .await
+                .map_err(|e| classify(e).context("reopen connection"))?;
+            }
+            result => result.map_err(|e| e.context("open connection"))?,
+        }
         info!("Connected!");
 
         // Discover services with timeout
         info!("Discovering services...");
-        timeout(config.discovery_timeout, peripheral.discover_services())
-            .await
-            .map_err(|_| Error::Timeout {
-                operation: "discover services".to_string(),
-                duration: config.discovery_timeout,
-            })??;
+        run_step(
+            budget.as_deref(),
+            "discover services",
+            config.discovery_timeout,
+            async { Ok(peripheral.discover_services().await?) },
+        )
+        .await
+        .map_err(|e| classify(e).context("discover services"))?;
 
         let mut services = peripheral.services();
 
         // If service discovery returned nothing, BlueZ may have stale state
         // from a previous failed connection (e.g., auth failure during GATT
-        // discovery). Disconnect, wait, and retry once with a clean connection.
-        if services.is_empty() {
-            warn!("Service discovery returned 0 services — retrying with fresh connection");
+        // discovery). WinRT can likewise return stale cached services that
+        // lack the Aranet service. Disconnect, wait, and retry once with a
+        // clean connection.
+        let missing_aranet_service = strategy.invalidate_service_cache
+            && !services
+                .iter()
+                .any(|s| s.uuid == SAF_TEHNIKA_SERVICE_NEW || s.uuid == SAF_TEHNIKA_SERVICE_OLD);
+        if services.is_empty() || missing_aranet_service {
+            if services.is_empty() {
+                warn!("Service discovery returned 0 services — retrying with fresh connection");
+            } else {
+                warn!(
+                    "Aranet service not among discovered services — retrying with fresh connection"
+                );
+            }
             let _ = peripheral.disconnect().await;
-            tokio::time::sleep(Duration::from_secs(2)).await;
-
-            timeout(config.connection_timeout, peripheral.connect())
-                .await
-                .map_err(|_| Error::Timeout {
-                    operation: "reconnect to device".to_string(),
-                    duration: config.connection_timeout,
-                })??;
-
-            timeout(config.discovery_timeout, peripheral.discover_services())
-                .await
-                .map_err(|_| Error::Timeout {
-                    operation: "rediscover services".to_string(),
-                    duration: config.discovery_timeout,
-                })??;
+            settle(budget.as_deref()).await;
+
+            run_step(
+                budget.as_deref(),
+                "reconnect to device",
+                config.connection_timeout,
+                async { Ok(peripheral.connect().await?) },
+            )
+            .await
+            .map_err(|e| e.context("reopen connection"))?;
+
+            run_step(
+                budget.as_deref(),
+                "rediscover services",
+                config.discovery_timeout,
+                async { Ok(peripheral.discover_services().await?) },
+            )
+            .await
+            .map_err(|e| e.context("rediscover services"))?;
 
             services = peripheral.services();
         }
@@ -491,7 +1000,12 @@ impl Device {
             characteristics_cache: RwLock::new(characteristics_cache),
             notification_handles: tokio::sync::Mutex::new(Vec::new()),
             disconnected: AtomicBool::new(false),
+            operations: OperationQueue::new(config.max_queued_operations),
+            pipelined_reads: AtomicBool::new(config.pipelined_reads),
             config,
+            budget,
+            quirks: Mutex::new(Quirks::default()),
+            settings: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -550,6 +1064,23 @@ impl Device {
         &self.config
     }
 
+    /// Number of operations waiting for their turn on this device.
+    ///
+    /// Operations from concurrent callers (e.g. a reading poll during a
+    /// history sync) run one at a time, in the order they were made.
+    pub fn queued_operations(&self) -> usize {
+        self.operations.queued()
+    }
+
+    /// The overall timeout budget, when [`ConnectionConfig::total_timeout`] is set.
+    ///
+    /// The budget starts when the connection attempt starts. Call
+    /// [`TimeoutBudget::reset`] to give a long-lived device a fresh budget
+    /// before each read cycle.
+    pub fn timeout_budget(&self) -> Option<&TimeoutBudget> {
+        self.budget.as_deref()
+    }
+
     /// Get the current signal quality based on RSSI.
     ///
     /// Returns `None` if RSSI cannot be read.
@@ -578,8 +1109,17 @@ impl Device {
             }
         }
 
-        self.peripheral.disconnect().await?;
-        Ok(())
+        let started = Instant::now();
+        let result = self.peripheral.disconnect().await.map_err(Error::from);
+        global_diagnostics()
+            .record_outcome(
+                OperationType::Disconnect,
+                started,
+                Some(&self.address),
+                &result,
+            )
+            .await;
+        result
     }
 
     /// Get the device name.
@@ -611,6 +1151,35 @@ impl Device {
             .ok_or_else(|| Error::InvalidData("RSSI not available".to_string()))
     }
 
+    /// Aranet manufacturer data from the last advertisement the platform
+    /// cached for this device, if any.
+    pub(crate) async fn advertised_manufacturer_data(&self) -> Option<Vec<u8>> {
+        let properties = self.peripheral.properties().await.ok()??;
+        properties.manufacturer_data.get(&MANUFACTURER_ID).cloned()
+    }
+
+    /// Read the RSSI `count` times, `interval` apart.
+    ///
+    /// Takes `interval * (count - 1)` in total. Reads that fail are skipped;
+    /// an error is returned only if none succeed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn example(device: &aranet_core::Device) -> aranet_core::Result<()> {
+    /// use std::time::Duration;
+    ///
+    /// let series = device.sample_rssi(Duration::from_millis(500), 10).await?;
+    /// if let (Some(mean), Some(stddev)) = (series.mean(), series.stddev()) {
+    ///     println!("RSSI {:.0} dBm ± {:.1}", mean, stddev);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sample_rssi(&self, interval: Duration, count: usize) -> Result<RssiSamples> {
+        sample_rssi_with(interval, count, || self.read_rssi()).await
+    }
+
     /// Find a characteristic by UUID using the cached lookup table.
     ///
     /// Uses O(1) lookup from the characteristics cache built during service discovery.
@@ -681,22 +1250,36 @@ impl Device {
         ))
     }
 
+    /// Get the firmware quirks in effect for this device.
+    ///
+    /// Empty until [`Self::read_device_info`] (or
+    /// [`Self::read_device_info_essential`]) has reported the firmware version.
+    pub fn quirks(&self) -> Quirks {
+        *self.quirks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Select the quirks for the reported firmware revision.
+    fn select_quirks(&self, firmware: &str) {
+        let Some(device_type) = self.device_type else {
+            return;
+        };
+        let quirks = Quirks::lookup(device_type, firmware);
+        if !quirks.is_empty() {
+            info!(
+                "Applying firmware quirks for {} {}: {:?}",
+                device_type, firmware, quirks
+            );
+        }
+        *self.quirks.lock().unwrap_or_else(|e| e.into_inner()) = quirks;
+    }
+
     /// Read a characteristic value by UUID.
     ///
     /// This method includes a timeout to prevent indefinite hangs on BLE operations.
     /// The timeout is controlled by [`ConnectionConfig::read_timeout`].
     pub async fn read_characteristic(&self, uuid: Uuid) -> Result<Vec<u8>> {
-        let characteristic = self.find_characteristic(uuid).await?;
-        let data = timeout(
-            self.config.read_timeout,
-            self.peripheral.read(&characteristic),
-        )
-        .await
-        .map_err(|_| Error::Timeout {
-            operation: format!("read characteristic {}", uuid),
-            duration: self.config.read_timeout,
-        })??;
-        Ok(data)
+        self.read_characteristic_with_timeout(uuid, self.config.read_timeout)
+            .await
     }
 
     /// Read a characteristic value with a custom timeout.
@@ -708,33 +1291,32 @@ impl Device {
         uuid: Uuid,
         read_timeout: Duration,
     ) -> Result<Vec<u8>> {
-        let characteristic = self.find_characteristic(uuid).await?;
-        let data = timeout(read_timeout, self.peripheral.read(&characteristic))
-            .await
-            .map_err(|_| Error::Timeout {
-                operation: format!("read characteristic {}", uuid),
-                duration: read_timeout,
-            })??;
-        Ok(data)
+        let result = self
+            .operations
+            .run("read characteristic", async {
+                let characteristic = self.find_characteristic(uuid).await?;
+                run_step(
+                    self.budget.as_deref(),
+                    &format!("read characteristic {}", uuid),
+                    read_timeout,
+                    async { Ok(self.peripheral.read(&characteristic).await?) },
+                )
+                .await
+            })
+            .await;
+        result.map_err(|e| self.annotate(e, format!("read characteristic {uuid}")))
     }
 
     /// Write a value to a characteristic.
     ///
-    /// This method includes a timeout to prevent indefinite hangs on BLE operations.
-    /// The timeout is controlled by [`ConnectionConfig::write_timeout`].
+    /// Waits for the device to acknowledge the write; use
+    /// [`write_characteristic_with_mode`](Self::write_characteristic_with_mode)
+    /// to choose another [`WriteMode`]. This method includes a timeout to
+    /// prevent indefinite hangs on BLE operations. The timeout is controlled
+    /// by [`ConnectionConfig::write_timeout`].
     pub async fn write_characteristic(&self, uuid: Uuid, data: &[u8]) -> Result<()> {
-        let characteristic = self.find_characteristic(uuid).await?;
-        timeout(
-            self.config.write_timeout,
-            self.peripheral
-                .write(&characteristic, data, WriteType::WithResponse),
-        )
-        .await
-        .map_err(|_| Error::Timeout {
-            operation: format!("write characteristic {}", uuid),
-            duration: self.config.write_timeout,
-        })??;
-        Ok(())
+        self.write_characteristic_with_mode(uuid, data, WriteMode::WithResponse)
+            .await
     }
 
     /// Write a value to a characteristic with a custom timeout.
@@ -744,18 +1326,84 @@ impl Device {
         data: &[u8],
         write_timeout: Duration,
     ) -> Result<()> {
-        let characteristic = self.find_characteristic(uuid).await?;
-        timeout(
-            write_timeout,
-            self.peripheral
-                .write(&characteristic, data, WriteType::WithResponse),
-        )
-        .await
-        .map_err(|_| Error::Timeout {
-            operation: format!("write characteristic {}", uuid),
-            duration: write_timeout,
-        })??;
-        Ok(())
+        self.write_inner(uuid, data, WriteMode::WithResponse, write_timeout)
+            .await
+    }
+
+    /// Write a value to a characteristic with an explicit write type.
+    ///
+    /// Writes without response are not acknowledged by the device and can be
+    /// rejected by the local BLE stack when its queue is full, so failed
+    /// attempts are retried with [`RetryConfig::for_write`].
+    pub async fn write_characteristic_with_mode(
+        &self,
+        uuid: Uuid,
+        data: &[u8],
+        mode: WriteMode,
+    ) -> Result<()> {
+        self.write_inner(uuid, data, mode, self.config.write_timeout)
+            .await
+    }
+
+    async fn write_inner(
+        &self,
+        uuid: Uuid,
+        data: &[u8],
+        mode: WriteMode,
+        write_timeout: Duration,
+    ) -> Result<()> {
+        let result = self
+            .operations
+            .run("write characteristic", async {
+                let characteristic = self.find_characteristic(uuid).await?;
+                let write_type = mode.write_type(characteristic.properties);
+                let write_once = || async {
+                    run_step(
+                        self.budget.as_deref(),
+                        &format!("write characteristic {}", uuid),
+                        write_timeout,
+                        async {
+                            Ok(self
+                                .peripheral
+                                .write(&characteristic, data, write_type)
+                                .await?)
+                        },
+                    )
+                    .await
+                };
+
+                match write_type {
+                    WriteType::WithResponse => write_once().await,
+                    WriteType::WithoutResponse => {
+                        with_retry(
+                            &RetryConfig::for_write(),
+                            "write without response",
+                            write_once,
+                        )
+                        .await
+                    }
+                }
+            })
+            .await;
+        if uuid == COMMAND {
+            // Any command may change settings, even if the write failed midway
+            self.invalidate_settings();
+        }
+        result.map_err(|e| self.annotate(e, format!("write characteristic {uuid}")))
+    }
+
+    /// Attach an operation breadcrumb and this device's description to an error.
+    ///
+    /// Only called on the error path, so the description is never built for
+    /// successful operations.
+    fn annotate(&self, error: Error, operation: String) -> Error {
+        let kind = self
+            .device_type
+            .map_or_else(|| "unknown device".to_string(), |t| t.to_string());
+        let label = self.name.as_deref().unwrap_or(&self.address);
+        error
+            .context(operation)
+            .on_device(format!("{} '{}'", kind, label))
     }
 
     /// Read current sensor measurements.
@@ -763,8 +1411,27 @@ impl Device {
     /// Automatically selects the correct characteristic UUID based on device type:
     /// - Aranet4 uses `f0cd3001`
     /// - Aranet2, Radon, Radiation use `f0cd3003`
+    /// - Aranet4 firmware with [`Quirks::basic_readings`] uses `f0cd1503`
     #[tracing::instrument(level = "debug", skip(self), fields(device_name = ?self.name, device_type = ?self.device_type))]
     pub async fn read_current(&self) -> Result<CurrentReading> {
+        let started = Instant::now();
+        let result = self
+            .operations
+            .run("read current", self.read_current_inner())
+            .await
+            .map_err(|e| e.context("read current"));
+        global_diagnostics()
+            .record_outcome(OperationType::Read, started, Some(&self.address), &result)
+            .await;
+        result
+    }
+
+    async fn read_current_inner(&self) -> Result<CurrentReading> {
+        if self.quirks().basic_readings {
+            let data = self.read_characteristic(CURRENT_READINGS).await?;
+            return crate::readings::parse_aranet4_basic_reading(&data);
+        }
+
         // Use the correct characteristic directly when device type is known,
         // otherwise probe primary then fall back to alternative.
         let data = match self.device_type {
@@ -777,7 +1444,7 @@ impl Device {
                 // Unknown type: try primary first, fall back to alternative
                 match self.read_characteristic(CURRENT_READINGS_DETAIL).await {
                     Ok(data) => data,
-                    Err(Error::CharacteristicNotFound { .. }) => {
+                    Err(e) if matches!(e.root(), Error::CharacteristicNotFound { .. }) => {
                         debug!("Primary reading characteristic not found, trying alternative");
                         self.read_characteristic(CURRENT_READINGS_DETAIL_ALT)
                             .await?
@@ -802,6 +1469,35 @@ impl Device {
         crate::readings::parse_reading_for_device(&data, device_type)
     }
 
+    /// Read the current reading, battery level and settings together.
+    ///
+    /// The reads run in one turn of the device's operation queue. When
+    /// [`ConnectionConfig::pipelined_reads`] is set they are all in flight at
+    /// once, which roughly halves the latency on slow connection intervals;
+    /// if the BLE stack rejects that, this and later snapshots read one
+    /// characteristic at a time.
+    #[tracing::instrument(level = "debug", skip(self), fields(device_name = ?self.name))]
+    pub async fn read_snapshot(&self) -> Result<DeviceSnapshot> {
+        let started = Instant::now();
+        let result = self
+            .operations
+            .run(
+                "read snapshot",
+                read_snapshot_with(
+                    &self.pipelined_reads,
+                    || self.read_current_inner(),
+                    || self.read_battery(),
+                    || self.get_settings(),
+                ),
+            )
+            .await
+            .map_err(|e| e.context("read snapshot"));
+        global_diagnostics()
+            .record_outcome(OperationType::Read, started, Some(&self.address), &result)
+            .await;
+        result
+    }
+
     /// Read the battery level (0-100).
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn read_battery(&self) -> Result<u8> {
@@ -814,7 +1510,8 @@ impl Device {
 
     /// Read device information.
     ///
-    /// This method reads all device info characteristics in parallel for better performance.
+    /// All device info characteristics are requested at once; the device's
+    /// operation queue runs the reads back to back.
     #[tracing::instrument(level = "debug", skip(self))]
     pub async fn read_device_info(&self) -> Result<DeviceInfo> {
         fn read_string(data: Vec<u8>) -> String {
@@ -824,7 +1521,7 @@ impl Device {
                 .to_string()
         }
 
-        // Read all characteristics in parallel for better performance
+        // Request all characteristics at once; the reads are queued back to back
         let (
             name_result,
             model_result,
@@ -850,6 +1547,7 @@ impl Device {
         let model = model_result.map(read_string).unwrap_or_default();
         let serial = serial_result.map(read_string).unwrap_or_default();
         let firmware = firmware_result.map(read_string).unwrap_or_default();
+        self.select_quirks(&firmware);
         let hardware = hardware_result.map(read_string).unwrap_or_default();
         let software = software_result.map(read_string).unwrap_or_default();
         let manufacturer = manufacturer_result.map(read_string).unwrap_or_default();
@@ -879,7 +1577,7 @@ impl Device {
                 .to_string()
         }
 
-        // Only read the essential characteristics in parallel
+        // Only request the essential characteristics
         let (name_result, serial_result, firmware_result) = tokio::join!(
             self.read_characteristic(DEVICE_NAME),
             self.read_characteristic(SERIAL_NUMBER),
@@ -891,6 +1589,7 @@ impl Device {
             .unwrap_or_else(|_| self.name.clone().unwrap_or_default());
         let serial = serial_result.map(read_string).unwrap_or_default();
         let firmware = firmware_result.map(read_string).unwrap_or_default();
+        self.select_quirks(&firmware);
 
         Ok(DeviceInfo {
             name,
@@ -914,7 +1613,11 @@ impl Device {
     {
         let characteristic = self.find_characteristic(uuid).await?;
 
-        self.peripheral.subscribe(&characteristic).await?;
+        self.operations
+            .run("subscribe", async {
+                Ok(self.peripheral.subscribe(&characteristic).await?)
+            })
+            .await?;
 
         // Set up notification handler
         let mut stream = self.peripheral.notifications().await?;
@@ -935,11 +1638,73 @@ impl Device {
         Ok(())
     }
 
+    /// Read device settings, reusing the last result until it is invalidated.
+    ///
+    /// The cache is cleared by [`Self::invalidate_settings`], by any write to
+    /// the command characteristic, and by settings notifications once
+    /// [`Self::subscribe_to_settings_changes`] is active.
+    pub async fn get_settings_cached(&self) -> Result<DeviceSettings> {
+        if let Some(settings) = self.cached_settings() {
+            return Ok(settings);
+        }
+        let settings = self.get_settings().await?;
+        *self.settings.lock().unwrap_or_else(|e| e.into_inner()) = Some(settings.clone());
+        Ok(settings)
+    }
+
+    /// Settings cached by [`Self::get_settings_cached`], if still valid.
+    pub fn cached_settings(&self) -> Option<DeviceSettings> {
+        self.settings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Drop the cached settings so the next read goes to the device.
+    pub fn invalidate_settings(&self) {
+        self.settings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+    }
+
+    /// Invalidate the settings cache when the device reports a settings change.
+    ///
+    /// Changing settings with the buttons on the device updates the sensor
+    /// state characteristic; on firmware that notifies on it, the cache is
+    /// cleared and `on_change` is called. Returns `Ok(false)` without
+    /// subscribing if the characteristic does not support notifications.
+    pub async fn subscribe_to_settings_changes<F>(&self, on_change: F) -> Result<bool>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let characteristic = self.find_characteristic(SENSOR_STATE).await?;
+        if !characteristic
+            .properties
+            .intersects(CharPropFlags::NOTIFY | CharPropFlags::INDICATE)
+        {
+            debug!("Sensor state does not notify; settings cache relies on writes only");
+            return Ok(false);
+        }
+
+        let settings = Arc::clone(&self.settings);
+        self.subscribe_to_notifications(SENSOR_STATE, move |_| {
+            debug!("Device reported a settings change");
+            settings.lock().unwrap_or_else(|e| e.into_inner()).take();
+            on_change();
+        })
+        .await?;
+        Ok(true)
+    }
+
     /// Unsubscribe from notifications on a characteristic.
     pub async fn unsubscribe_from_notifications(&self, uuid: Uuid) -> Result<()> {
         let characteristic = self.find_characteristic(uuid).await?;
-        self.peripheral.unsubscribe(&characteristic).await?;
-        Ok(())
+        self.operations
+            .run("unsubscribe", async {
+                Ok(self.peripheral.unsubscribe(&characteristic).await?)
+            })
+            .await
     }
 
     /// Get the number of cached characteristics.
@@ -1086,3 +1851,159 @@ impl AranetDevice for Device {
         Device::get_calibration(self).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    async fn slow<T>(value: T) -> Result<T> {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        Ok(value)
+    }
+
+    #[test]
+    fn test_write_mode_default_waits_for_response() {
+        assert_eq!(WriteMode::default(), WriteMode::WithResponse);
+    }
+
+    #[test]
+    fn test_write_mode_write_type() {
+        let write = CharPropFlags::WRITE;
+        let without = CharPropFlags::WRITE_WITHOUT_RESPONSE;
+        let both = write | without;
+        let neither = CharPropFlags::READ;
+
+        for properties in [write, without, both, neither] {
+            assert_eq!(
+                WriteMode::WithResponse.write_type(properties),
+                WriteType::WithResponse
+            );
+            assert_eq!(
+                WriteMode::WithoutResponse.write_type(properties),
+                WriteType::WithoutResponse
+            );
+        }
+
+        // Auto only drops the response when the characteristic cannot give one
+        assert_eq!(WriteMode::Auto.write_type(write), WriteType::WithResponse);
+        assert_eq!(
+            WriteMode::Auto.write_type(without),
+            WriteType::WithoutResponse
+        );
+        assert_eq!(WriteMode::Auto.write_type(both), WriteType::WithResponse);
+        assert_eq!(WriteMode::Auto.write_type(neither), WriteType::WithResponse);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_read_snapshot_pipelined_overlaps_reads() {
+        let pipelined = AtomicBool::new(true);
+        let started = tokio::time::Instant::now();
+        let snapshot = read_snapshot_with(
+            &pipelined,
+            || slow(CurrentReading::default()),
+            || slow(85),
+            || slow(DeviceSettings::default()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(snapshot.battery, 85);
+        assert_eq!(started.elapsed(), Duration::from_millis(100));
+        assert!(pipelined.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_read_snapshot_falls_back_to_sequential() {
+        let pipelined = AtomicBool::new(true);
+        let attempts = AtomicUsize::new(0);
+        let started = tokio::time::Instant::now();
+        let snapshot = read_snapshot_with(
+            &pipelined,
+            || slow(CurrentReading::default()),
+            || async {
+                if attempts.fetch_add(1, Ordering::Relaxed) == 0 {
+                    return Err(Error::Bluetooth(btleplug::Error::Other(
+                        "org.bluez.Error.InProgress: In Progress".into(),
+                    )));
+                }
+                slow(60).await
+            },
+            || slow(DeviceSettings::default()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(snapshot.battery, 60);
+        assert!(!pipelined.load(Ordering::Relaxed));
+        assert_eq!(started.elapsed(), Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn test_read_snapshot_returns_read_errors() {
+        let pipelined = AtomicBool::new(true);
+        let result = read_snapshot_with(
+            &pipelined,
+            || async { Err(Error::InvalidData("short reading".to_string())) },
+            || slow(85),
+            || slow(DeviceSettings::default()),
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::InvalidData(_))));
+        assert!(pipelined.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn test_read_snapshot_dropped_link_keeps_pipelining() {
+        let pipelined = AtomicBool::new(true);
+        let attempts = AtomicUsize::new(0);
+        let result = read_snapshot_with(
+            &pipelined,
+            || async {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                Err::<CurrentReading, _>(Error::Bluetooth(btleplug::Error::Other(
+                    "org.bluez.Error.Failed: Not connected".into(),
+                )))
+            },
+            || slow(85),
+            || slow(DeviceSettings::default()),
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::Bluetooth(_))));
+        // No sequential retry on a dead connection
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+        assert!(pipelined.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_is_pipelining_unsupported() {
+        let other = |msg: &str| Error::Bluetooth(btleplug::Error::Other(msg.to_string().into()));
+        assert!(is_pipelining_unsupported(&other(
+            "org.bluez.Error.InProgress: In Progress"
+        )));
+        assert!(is_pipelining_unsupported(&Error::Bluetooth(
+            btleplug::Error::RuntimeError("GATT operation already in progress".to_string())
+        )));
+        assert!(is_pipelining_unsupported(
+            &other("Device or resource busy").context("read snapshot")
+        ));
+
+        assert!(!is_pipelining_unsupported(&other(
+            "org.bluez.Error.Failed: Not connected"
+        )));
+        assert!(!is_pipelining_unsupported(&Error::Bluetooth(
+            btleplug::Error::RuntimeError("connection dropped".to_string())
+        )));
+        assert!(!is_pipelining_unsupported(&Error::Bluetooth(
+            btleplug::Error::NotSupported("read".to_string())
+        )));
+        assert!(!is_pipelining_unsupported(&Error::Bluetooth(
+            btleplug::Error::NotConnected
+        )));
+        assert!(!is_pipelining_unsupported(&Error::Unsupported(
+            "pipelined reads".to_string()
+        )));
+    }
+}
@@ -15,6 +15,12 @@
 //! println!("Adapter: {:?}", diagnostics.adapter_info);
 //! println!("Connection stats: {:?}", diagnostics.connection_stats);
 //! ```
+//!
+//! [`global_diagnostics()`] is fed by [`Device`](crate::Device) connects,
+//! reads and disconnects and by scans. It keeps bounded ring buffers of the
+//! most recent errors and operation timings, so a long-running process can
+//! export them with [`DiagnosticsCollector::export_json`] when a BLE failure
+//! is reported hours after it happened.
 
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
@@ -34,6 +40,9 @@ const MAX_RECENT_ERRORS: usize = 100;
 /// Maximum number of recent operations to track.
 const MAX_RECENT_OPERATIONS: usize = 50;
 
+/// Maximum number of connection/read/write durations kept for averaging.
+const MAX_TIMING_SAMPLES: usize = 1000;
+
 /// Bluetooth adapter state.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AdapterState {
@@ -189,45 +198,74 @@ pub enum ErrorCategory {
     Other,
 }
 
+impl ErrorCategory {
+    /// Snake-case name, e.g. for metric labels.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCategory::Connection => "connection",
+            ErrorCategory::Operation => "operation",
+            ErrorCategory::Timeout => "timeout",
+            ErrorCategory::DeviceNotFound => "device_not_found",
+            ErrorCategory::DataParsing => "data_parsing",
+            ErrorCategory::Configuration => "configuration",
+            ErrorCategory::Other => "other",
+        }
+    }
+}
+
 impl From<&Error> for ErrorCategory {
     fn from(error: &Error) -> Self {
         match error {
-            Error::ConnectionFailed { .. } | Error::NotConnected => ErrorCategory::Connection,
-            Error::Timeout { .. } => ErrorCategory::Timeout,
+            Error::ConnectionFailed { .. } | Error::NotConnected | Error::BondLost { .. } => {
+                ErrorCategory::Connection
+            }
+            Error::Timeout { .. } | Error::TimeoutExceeded { .. } => ErrorCategory::Timeout,
             Error::DeviceNotFound(_) => ErrorCategory::DeviceNotFound,
             Error::InvalidData(_)
             | Error::InvalidHistoryData { .. }
             | Error::InvalidReadingFormat { .. } => ErrorCategory::DataParsing,
             Error::InvalidConfig(_) => ErrorCategory::Configuration,
-            Error::CharacteristicNotFound { .. } | Error::WriteFailed { .. } => {
-                ErrorCategory::Operation
-            }
-            Error::Unsupported(_) | Error::Bluetooth(_) | Error::Io(_) | Error::Cancelled => {
-                ErrorCategory::Other
-            }
+            Error::CharacteristicNotFound { .. }
+            | Error::WriteFailed { .. }
+            | Error::Busy { .. } => ErrorCategory::Operation,
+            Error::Unsupported(_)
+            | Error::Bluetooth(_)
+            | Error::Io(_)
+            | Error::Cancelled
+            | Error::StreamGap { .. } => ErrorCategory::Other,
+            Error::Context(ctx) => ErrorCategory::from(ctx.error()),
         }
     }
 }
 
 /// A recorded operation for timing analysis.
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-struct RecordedOperation {
-    operation_type: OperationType,
-    start_time: Instant,
-    duration_ms: u64,
-    success: bool,
-    device_id: Option<String>,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedOperation {
+    /// When the operation finished (Unix timestamp millis).
+    pub timestamp_ms: u64,
+    /// Kind of operation.
+    pub operation: OperationType,
+    /// How long the operation took in milliseconds.
+    pub duration_ms: u64,
+    /// Whether the operation succeeded.
+    pub success: bool,
+    /// Device identifier if applicable.
+    pub device_id: Option<String>,
 }
 
 /// Types of operations being tracked.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[allow(dead_code)]
-enum OperationType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationType {
+    /// Connecting to a device (including the scan to find it).
     Connect,
+    /// Disconnecting from a device.
     Disconnect,
+    /// Reading from a device.
     Read,
+    /// Writing to a device.
     Write,
+    /// Scanning for devices.
     Scan,
 }
 
@@ -246,6 +284,9 @@ pub struct BluetoothDiagnostics {
     pub operation_stats: OperationStats,
     /// Recent errors (most recent first).
     pub recent_errors: Vec<RecordedError>,
+    /// Recent operations with their timings (most recent first).
+    #[serde(default)]
+    pub recent_operations: Vec<RecordedOperation>,
     /// Timestamp when diagnostics were collected (Unix millis).
     pub collected_at: u64,
     /// Uptime of the diagnostics collector in seconds.
@@ -342,10 +383,10 @@ impl DiagnosticsCollector {
     /// Record a successful connection with duration.
     pub async fn record_connection_success(&self, duration: Duration) {
         self.connection_successes.fetch_add(1, Ordering::Relaxed);
-        self.connection_times
-            .write()
-            .await
-            .push(duration.as_millis() as u64);
+        push_sample(
+            &mut *self.connection_times.write().await,
+            duration.as_millis() as u64,
+        );
     }
 
     /// Record a failed connection.
@@ -369,7 +410,7 @@ impl DiagnosticsCollector {
         if success {
             self.read_successes.fetch_add(1, Ordering::Relaxed);
             if let Some(d) = duration {
-                self.read_times.write().await.push(d.as_millis() as u64);
+                push_sample(&mut *self.read_times.write().await, d.as_millis() as u64);
             }
         }
     }
@@ -380,7 +421,7 @@ impl DiagnosticsCollector {
         if success {
             self.write_successes.fetch_add(1, Ordering::Relaxed);
             if let Some(d) = duration {
-                self.write_times.write().await.push(d.as_millis() as u64);
+                push_sample(&mut *self.write_times.write().await, d.as_millis() as u64);
             }
         }
     }
@@ -400,17 +441,14 @@ impl DiagnosticsCollector {
     /// Record an error.
     pub async fn record_error(&self, error: &Error, device_id: Option<String>) {
         let recorded = RecordedError {
-            timestamp_ms: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis() as u64,
-            message: error.to_string(),
+            timestamp_ms: now_millis(),
+            message: format!("{:#}", error),
             category: ErrorCategory::from(error),
             device_id,
         };
 
         // Track timeout specifically
-        if matches!(error, Error::Timeout { .. }) {
+        if matches!(error.root(), Error::Timeout { .. }) {
             self.record_timeout();
         }
 
@@ -421,6 +459,62 @@ impl DiagnosticsCollector {
         errors.push_front(recorded);
     }
 
+    /// Record the timing of an operation in the recent operations buffer.
+    pub async fn record_operation(
+        &self,
+        operation: OperationType,
+        duration: Duration,
+        success: bool,
+        device_id: Option<String>,
+    ) {
+        let recorded = RecordedOperation {
+            timestamp_ms: now_millis(),
+            operation,
+            duration_ms: duration.as_millis() as u64,
+            success,
+            device_id,
+        };
+
+        let mut operations = self.recent_operations.write().await;
+        if operations.len() >= MAX_RECENT_OPERATIONS {
+            operations.pop_back();
+        }
+        operations.push_front(recorded);
+    }
+
+    /// Record the outcome of an operation that started at `started`.
+    ///
+    /// Records the operation timing, updates the matching connection or
+    /// read/write counters, and records the error if `result` failed.
+    pub async fn record_outcome<T>(
+        &self,
+        operation: OperationType,
+        started: Instant,
+        device_id: Option<&str>,
+        result: &Result<T, Error>,
+    ) {
+        let duration = started.elapsed();
+        let success = result.is_ok();
+        match operation {
+            OperationType::Connect => {
+                self.record_connection_attempt();
+                if success {
+                    self.record_connection_success(duration).await;
+                } else {
+                    self.record_connection_failure();
+                }
+            }
+            OperationType::Read => self.record_read(success, Some(duration)).await,
+            OperationType::Write => self.record_write(success, Some(duration)).await,
+            OperationType::Disconnect | OperationType::Scan => {}
+        }
+        self.record_operation(operation, duration, success, device_id.map(str::to_string))
+            .await;
+        if let Err(e) = result {
+            self.record_error(e, device_id.map(str::to_string)).await;
+        }
+    }
+
     /// Collect current diagnostics snapshot.
     pub async fn collect(&self) -> BluetoothDiagnostics {
         let platform = Platform::current();
@@ -442,6 +536,13 @@ impl DiagnosticsCollector {
         // Collect recent errors
         let recent_errors: Vec<RecordedError> =
             self.recent_errors.read().await.iter().cloned().collect();
+        let recent_operations: Vec<RecordedOperation> = self
+            .recent_operations
+            .read()
+            .await
+            .iter()
+            .cloned()
+            .collect();
 
         BluetoothDiagnostics {
             platform: format!("{:?}", platform),
@@ -472,14 +573,17 @@ impl DiagnosticsCollector {
                 timeout_count: self.timeout_count.load(Ordering::Relaxed),
             },
             recent_errors,
-            collected_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis() as u64,
+            recent_operations,
+            collected_at: now_millis(),
             uptime_secs: self.start_time.elapsed().as_secs(),
         }
     }
 
+    /// Collect a snapshot and serialize it as pretty-printed JSON.
+    pub async fn export_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.collect().await)
+    }
+
     /// Reset all statistics.
     pub async fn reset(&self) {
         self.connection_attempts.store(0, Ordering::Relaxed);
@@ -526,6 +630,22 @@ impl DiagnosticsCollector {
     }
 }
 
+/// Current time as Unix timestamp millis.
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Append a timing sample, dropping the oldest once the window is full.
+fn push_sample(samples: &mut Vec<u64>, value: u64) {
+    if samples.len() >= MAX_TIMING_SAMPLES {
+        samples.remove(0);
+    }
+    samples.push(value);
+}
+
 /// Calculate min, max, and average from a slice of times.
 fn calculate_time_stats(times: &[u64]) -> (Option<u64>, Option<u64>, Option<u64>) {
     if times.is_empty() {
@@ -622,4 +742,55 @@ mod tests {
         let diag = collector.collect().await;
         assert_eq!(diag.connection_stats.failed, 0);
     }
+
+    #[tokio::test]
+    async fn test_recent_buffers_are_bounded_and_exported() {
+        let collector = DiagnosticsCollector::new();
+
+        for i in 0..MAX_RECENT_OPERATIONS + 5 {
+            let result: Result<(), Error> = if i % 2 == 0 {
+                Ok(())
+            } else {
+                Err(Error::NotConnected)
+            };
+            collector
+                .record_outcome(
+                    OperationType::Read,
+                    Instant::now(),
+                    Some("AA:BB:CC:DD:EE:FF"),
+                    &result,
+                )
+                .await;
+        }
+
+        let diag = collector.collect().await;
+        assert_eq!(diag.recent_operations.len(), MAX_RECENT_OPERATIONS);
+        assert_eq!(
+            diag.operation_stats.total_reads,
+            MAX_RECENT_OPERATIONS as u64 + 5
+        );
+        assert_eq!(diag.recent_errors.len(), (MAX_RECENT_OPERATIONS + 5) / 2);
+        // Most recent first: the last operation succeeded, the one before failed
+        assert!(diag.recent_operations[0].success);
+        assert!(!diag.recent_operations[1].success);
+        assert_eq!(diag.recent_errors[0].category, ErrorCategory::Connection);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&collector.export_json().await.unwrap()).unwrap();
+        assert_eq!(json["recent_operations"][0]["operation"], "read");
+        assert_eq!(json["recent_errors"][0]["device_id"], "AA:BB:CC:DD:EE:FF");
+
+        collector.reset().await;
+        assert!(collector.collect().await.recent_operations.is_empty());
+    }
+
+    #[test]
+    fn test_push_sample_keeps_window() {
+        let mut samples = Vec::new();
+        for i in 0..MAX_TIMING_SAMPLES as u64 + 10 {
+            push_sample(&mut samples, i);
+        }
+        assert_eq!(samples.len(), MAX_TIMING_SAMPLES);
+        assert_eq!(samples[0], 10);
+    }
 }
@@ -21,6 +21,7 @@
 //! | [`Error::DeviceNotFound`] | Do not retry | Device not in range or wrong name |
 //! | [`Error::CharacteristicNotFound`] | Do not retry | Firmware incompatibility |
 //! | [`Error::InvalidConfig`] | Do not retry | Fix configuration and restart |
+//! | [`Error::BondLost`] | Re-pair, then reconnect | Stored pairing keys no longer match |
 //!
 //! ## Recommended Timeouts
 //!
@@ -84,6 +85,7 @@
 //! - [`Error::WriteFailed`] - Write operations can fail transiently
 //! - [`Error::ConnectionFailed`] with `OutOfRange`, `Timeout`, or `BleError` reasons
 //! - [`Error::Io`] - I/O errors may be transient
+//! - [`Error::Busy`] - The device's operation queue drains as operations finish
 //!
 //! The following errors should NOT be retried:
 //!
@@ -93,7 +95,27 @@
 //! - [`Error::DeviceNotFound`] - Device is not available
 //! - [`Error::CharacteristicNotFound`] - Device doesn't support this feature
 //! - [`Error::Cancelled`] - Operation was intentionally cancelled
+//! - [`Error::StreamGap`] - A marker, not a failure
 //! - [`Error::InvalidConfig`] - Configuration error, fix and restart
+//! - [`Error::BondLost`] - Fails until the device is paired again; see
+//!   [`crate::bonding::repair_bond`] and
+//!   [`ConnectionConfig::auto_repair_bond`](crate::device::ConnectionConfig::auto_repair_bond)
+//!
+//! [`Error::Context`] is classified by the error it wraps.
+//!
+//! ## Breadcrumbs
+//!
+//! [`Device`](crate::Device) annotates errors with the steps that led to them
+//! (connect, discover services, read characteristic, ...) and the device type.
+//! Format with `{:#}` to see the trail:
+//!
+//! ```text
+//! connect to Aranet4 17C3C → discover services: Operation 'discover services' timed out after 10s
+//! read current → read characteristic f0cd3001-... on Aranet2 'Aranet2 1A2B3': Characteristic not found: ...
+//! ```
+//!
+//! Plain `{}` formatting is unchanged. Match on [`Error::root`] rather than
+//! the error itself to see through the annotation.
 //!
 //! ## Example: Robust Reading Loop
 //!
@@ -128,6 +150,7 @@
 //! }
 //! ```
 
+use std::borrow::Cow;
 use std::time::Duration;
 
 use thiserror::Error;
@@ -203,10 +226,49 @@ pub enum Error {
         duration: Duration,
     },
 
+    /// An operation ran out of its overall timeout budget
+    /// ([`ConnectionConfig::total_timeout`](crate::ConnectionConfig::total_timeout)).
+    #[error(
+        "Operation '{operation}' exceeded the {budget:?} timeout budget after {} step(s)",
+        .attempts.len()
+    )]
+    TimeoutExceeded {
+        /// The step that was running (or about to run) when the budget ran out.
+        operation: String,
+        /// The total budget.
+        budget: Duration,
+        /// Every step run under the budget, oldest first.
+        attempts: Vec<crate::budget::TimeoutAttempt>,
+    },
+
     /// Operation was cancelled.
     #[error("Operation cancelled")]
     Cancelled,
 
+    /// Too many operations were already queued for the device.
+    ///
+    /// See [`ConnectionConfig::max_queued_operations`](crate::ConnectionConfig::max_queued_operations).
+    #[error("Device busy: '{operation}' rejected with {queued} operation(s) already queued")]
+    Busy {
+        /// The operation that was rejected.
+        operation: String,
+        /// How many operations were waiting.
+        queued: usize,
+    },
+
+    /// Readings were missed while the device reconnected.
+    ///
+    /// Not a failure: a [`ReadingStream`](crate::ReadingStream) over a
+    /// [`ReconnectingDevice`](crate::ReconnectingDevice) sends this marker
+    /// before the first reading after a reconnect, whether or not
+    /// [`StreamOptions::include_errors`](crate::StreamOptions::include_errors)
+    /// is set, and keeps streaming.
+    #[error("Readings missed for {missed:?} while the device reconnected")]
+    StreamGap {
+        /// Time since the last reading before the reconnect.
+        missed: Duration,
+    },
+
     /// I/O error.
     #[error(transparent)]
     Io(#[from] std::io::Error),
@@ -232,6 +294,24 @@ pub enum Error {
     /// Invalid configuration provided.
     #[error("Invalid configuration: {0}")]
     InvalidConfig(String),
+
+    /// The device rejected the stored bond (pairing keys), e.g. after a
+    /// battery change or factory reset. See [`crate::bonding`].
+    #[error("Bond lost with {device}: {reason}")]
+    BondLost {
+        /// The device address.
+        device: String,
+        /// The underlying Bluetooth error.
+        reason: String,
+    },
+
+    /// An error annotated with the operations that led to it.
+    ///
+    /// Plain `{}` formatting shows only the underlying error; the alternate
+    /// `{:#}` form prefixes it with the breadcrumb trail and device. Use
+    /// [`Error::root`] to match on the underlying error.
+    #[error(transparent)]
+    Context(Box<ErrorContext>),
 }
 
 /// Structured reasons for connection failures.
@@ -302,7 +382,165 @@ impl std::fmt::Display for DeviceNotFoundReason {
     }
 }
 
+/// Breadcrumb trail attached to an [`Error`] by [`Error::context`].
+///
+/// Breadcrumbs are only built on the error path, so annotating a call costs
+/// nothing when it succeeds.
+#[derive(Debug)]
+pub struct ErrorContext {
+    /// Operations in progress when the error occurred, innermost first.
+    breadcrumbs: Vec<Cow<'static, str>>,
+    /// Description of the device the operation was running against.
+    device: Option<String>,
+    /// The underlying error. Never itself an [`Error::Context`].
+    error: Error,
+}
+
+impl ErrorContext {
+    /// The underlying error.
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    /// Operations in progress when the error occurred, outermost first.
+    pub fn breadcrumbs(&self) -> impl Iterator<Item = &str> {
+        self.breadcrumbs.iter().rev().map(|b| b.as_ref())
+    }
+
+    /// Description of the device the operation was running against.
+    pub fn device(&self) -> Option<&str> {
+        self.device.as_deref()
+    }
+}
+
+impl std::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            for (i, breadcrumb) in self.breadcrumbs().enumerate() {
+                if i > 0 {
+                    write!(f, " → ")?;
+                }
+                write!(f, "{}", breadcrumb)?;
+            }
+            if let Some(device) = &self.device {
+                let sep = if self.breadcrumbs.is_empty() { "" } else { " " };
+                write!(f, "{}on {}", sep, device)?;
+            }
+            if !self.breadcrumbs.is_empty() || self.device.is_some() {
+                write!(f, ": ")?;
+            }
+        }
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for ErrorContext {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
 impl Error {
+    /// Record that this error happened while performing `operation`.
+    ///
+    /// Call this on the way out of each step, innermost first; the trail is
+    /// shown outermost first by `{:#}` formatting.
+    ///
+    /// ```
+    /// use aranet_core::Error;
+    ///
+    /// let err = Error::characteristic_not_found("f0cd3001", 4)
+    ///     .context("read characteristic f0cd3001")
+    ///     .on_device("Aranet2 'Aranet2 1A2B3'")
+    ///     .context("read current");
+    ///
+    /// assert!(matches!(err.root(), Error::CharacteristicNotFound { .. }));
+    /// assert_eq!(
+    ///     format!("{:#}", err),
+    ///     "read current → read characteristic f0cd3001 on Aranet2 'Aranet2 1A2B3': \
+    ///      Characteristic not found: f0cd3001 (searched in 4 services)"
+    /// );
+    /// ```
+    pub fn context(self, operation: impl Into<Cow<'static, str>>) -> Self {
+        match self {
+            Self::Context(mut ctx) => {
+                ctx.breadcrumbs.push(operation.into());
+                Self::Context(ctx)
+            }
+            error => Self::Context(Box::new(ErrorContext {
+                breadcrumbs: vec![operation.into()],
+                device: None,
+                error,
+            })),
+        }
+    }
+
+    /// Record the device this error happened on.
+    ///
+    /// The first device recorded wins, so inner layers that know more about
+    /// the device are not overwritten.
+    pub fn on_device(self, device: impl Into<String>) -> Self {
+        let mut ctx = match self {
+            Self::Context(ctx) => ctx,
+            error => Box::new(ErrorContext {
+                breadcrumbs: Vec::new(),
+                device: None,
+                error,
+            }),
+        };
+        if ctx.device.is_none() {
+            ctx.device = Some(device.into());
+        }
+        Self::Context(ctx)
+    }
+
+    /// The underlying error, with any breadcrumbs stripped.
+    pub fn root(&self) -> &Error {
+        match self {
+            Self::Context(ctx) => &ctx.error,
+            error => error,
+        }
+    }
+
+    /// Operations in progress when the error occurred, outermost first.
+    pub fn breadcrumbs(&self) -> Vec<&str> {
+        match self {
+            Self::Context(ctx) => ctx.breadcrumbs().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether the error means the Bluetooth adapter is off or has gone away.
+    ///
+    /// Platforms report this differently: BlueZ answers `org.bluez.Error.NotReady`,
+    /// CoreBluetooth and WinRT describe the adapter as powered off.
+    pub fn is_adapter_unavailable(&self) -> bool {
+        const SIGNATURES: [&str; 6] = [
+            "powered off",
+            "not powered",
+            "notready",
+            "not ready",
+            "bluetooth is off",
+            "adapter is off",
+        ];
+        match self.root() {
+            Error::ConnectionFailed {
+                reason: ConnectionFailureReason::AdapterUnavailable,
+                ..
+            }
+            | Error::DeviceNotFound(DeviceNotFoundReason::NoAdapter) => true,
+            Error::Bluetooth(_)
+            | Error::ConnectionFailed {
+                reason: ConnectionFailureReason::BleError(_) | ConnectionFailureReason::Other(_),
+                ..
+            } => {
+                let message = self.root().to_string().to_lowercase();
+                SIGNATURES.iter().any(|sig| message.contains(sig))
+            }
+            _ => false,
+        }
+    }
+
     /// Create a device not found error for a specific identifier.
     pub fn device_not_found(identifier: impl Into<String>) -> Self {
         Self::DeviceNotFound(DeviceNotFoundReason::NotFound {
@@ -395,6 +633,26 @@ mod tests {
         assert!(err.to_string().contains("10s"));
     }
 
+    #[test]
+    fn test_is_adapter_unavailable() {
+        let err = Error::connection_failed(None, ConnectionFailureReason::AdapterUnavailable);
+        assert!(err.is_adapter_unavailable());
+
+        let err = Error::Bluetooth(btleplug::Error::Other(
+            "org.bluez.Error.NotReady: Resource Not Ready".into(),
+        ));
+        assert!(err.is_adapter_unavailable());
+
+        let err = Error::connection_failed_str(None, "Bluetooth is powered off")
+            .context("reconnect")
+            .on_device("Aranet4 12345");
+        assert!(err.is_adapter_unavailable());
+
+        assert!(!Error::NotConnected.is_adapter_unavailable());
+        assert!(!Error::device_not_found("Aranet4 12345").is_adapter_unavailable());
+        assert!(!Error::connection_failed_str(None, "out of range").is_adapter_unavailable());
+    }
+
     #[test]
     fn test_error_debug() {
         let err = Error::DeviceNotFound(DeviceNotFoundReason::NoDevicesInRange);
@@ -428,6 +686,37 @@ mod tests {
         _assert_from_impl::<Error>();
     }
 
+    #[test]
+    fn test_error_context() {
+        let err = Error::characteristic_not_found("f0cd3001", 4)
+            .context("read characteristic f0cd3001")
+            .on_device("Aranet4 'Aranet4 17C3C'")
+            .on_device("ignored")
+            .context("read current");
+
+        // Plain display is the underlying error
+        assert_eq!(
+            err.to_string(),
+            "Characteristic not found: f0cd3001 (searched in 4 services)"
+        );
+        assert_eq!(
+            format!("{:#}", err),
+            "read current → read characteristic f0cd3001 on Aranet4 'Aranet4 17C3C': \
+             Characteristic not found: f0cd3001 (searched in 4 services)"
+        );
+        assert_eq!(
+            err.breadcrumbs(),
+            ["read current", "read characteristic f0cd3001"]
+        );
+        assert!(matches!(err.root(), Error::CharacteristicNotFound { .. }));
+
+        // Errors without context are their own root
+        let err = Error::NotConnected;
+        assert!(matches!(err.root(), Error::NotConnected));
+        assert!(err.breadcrumbs().is_empty());
+        assert_eq!(format!("{:#}", err), "Not connected to device");
+    }
+
     #[test]
     fn test_io_error_conversion() {
         let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "file not found");
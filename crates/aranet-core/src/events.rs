@@ -2,11 +2,20 @@
 //!
 //! This module provides an event-based system for receiving notifications
 //! about device connections, disconnections, readings, and errors.
+//!
+//! [`EventRecorder`] records the events sent on a channel so tests can
+//! assert on what was emitted, and in what order.
+
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+
+use aranet_types::{CurrentReading, DeviceInfo, DeviceType, Status};
 
-use aranet_types::{CurrentReading, DeviceInfo, DeviceType};
+use crate::diagnostics::AdapterState;
+use crate::sensor_health::CalibrationState;
 
 /// Device identifier for events.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -74,6 +83,77 @@ pub enum DeviceEvent {
     ReconnectSucceeded { device: DeviceId, attempts: u32 },
     /// Battery level changed significantly.
     BatteryLow { device: DeviceId, level: u8 },
+    /// Device status turned Red.
+    StatusRed {
+        device: DeviceId,
+        previous: Option<Status>,
+    },
+    /// History sync was skipped because the battery is below the sync threshold.
+    HistorySyncDeferred {
+        device: DeviceId,
+        battery: u8,
+        threshold: u8,
+    },
+    /// Records not yet synced will soon be overwritten on the device.
+    ///
+    /// See [`crate::history::history_overwrite_risk`].
+    HistoryOverwriteRisk {
+        device: DeviceId,
+        #[serde(with = "time::serde::rfc3339")]
+        overwrite_at: time::OffsetDateTime,
+    },
+    /// Settings were changed on the device itself (e.g. with its buttons).
+    SettingsChanged { device: DeviceId },
+    /// The CO₂ sensor's calibration state changed.
+    CalibrationStateChanged {
+        device: DeviceId,
+        previous: Option<CalibrationState>,
+        state: CalibrationState,
+    },
+    /// The Bluetooth adapter was powered off, removed, or came back.
+    AdapterStateChanged { state: AdapterState },
+}
+
+impl DeviceEvent {
+    /// Name of the event, as in the `type` field it is serialized with.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DeviceEvent::Discovered { .. } => "discovered",
+            DeviceEvent::Connected { .. } => "connected",
+            DeviceEvent::Disconnected { .. } => "disconnected",
+            DeviceEvent::Reading { .. } => "reading",
+            DeviceEvent::Error { .. } => "error",
+            DeviceEvent::ReconnectStarted { .. } => "reconnect_started",
+            DeviceEvent::ReconnectSucceeded { .. } => "reconnect_succeeded",
+            DeviceEvent::BatteryLow { .. } => "battery_low",
+            DeviceEvent::StatusRed { .. } => "status_red",
+            DeviceEvent::HistorySyncDeferred { .. } => "history_sync_deferred",
+            DeviceEvent::HistoryOverwriteRisk { .. } => "history_overwrite_risk",
+            DeviceEvent::SettingsChanged { .. } => "settings_changed",
+            DeviceEvent::CalibrationStateChanged { .. } => "calibration_state_changed",
+            DeviceEvent::AdapterStateChanged { .. } => "adapter_state_changed",
+        }
+    }
+
+    /// The device the event is about, or `None` for adapter events.
+    pub fn device(&self) -> Option<&DeviceId> {
+        match self {
+            DeviceEvent::Discovered { device, .. }
+            | DeviceEvent::Connected { device, .. }
+            | DeviceEvent::Disconnected { device, .. }
+            | DeviceEvent::Reading { device, .. }
+            | DeviceEvent::Error { device, .. }
+            | DeviceEvent::ReconnectStarted { device, .. }
+            | DeviceEvent::ReconnectSucceeded { device, .. }
+            | DeviceEvent::BatteryLow { device, .. }
+            | DeviceEvent::StatusRed { device, .. }
+            | DeviceEvent::HistorySyncDeferred { device, .. }
+            | DeviceEvent::HistoryOverwriteRisk { device, .. }
+            | DeviceEvent::SettingsChanged { device }
+            | DeviceEvent::CalibrationStateChanged { device, .. } => Some(device),
+            DeviceEvent::AdapterStateChanged { .. } => None,
+        }
+    }
 }
 
 /// Reason for disconnection.
@@ -154,6 +234,111 @@ impl Default for EventDispatcher {
     }
 }
 
+/// Records the events sent on a channel, for tests.
+///
+/// Create the recorder before running the code under test, then assert on
+/// the kinds of events it emitted (see [`DeviceEvent::kind`]):
+///
+/// ```
+/// use aranet_core::events::{DeviceEvent, DeviceId, EventDispatcher, EventRecorder};
+///
+/// let events = EventDispatcher::default();
+/// let mut recorder = EventRecorder::new(&events.sender());
+///
+/// events.send(DeviceEvent::SettingsChanged {
+///     device: DeviceId::new("kitchen"),
+/// });
+/// recorder.expect_sequence(["settings_changed"]);
+/// ```
+#[derive(Debug)]
+pub struct EventRecorder {
+    receiver: EventReceiver,
+    events: Vec<DeviceEvent>,
+    /// Events the receiver fell too far behind to see.
+    missed: u64,
+}
+
+impl EventRecorder {
+    /// Start recording the events sent on `sender` from now on.
+    pub fn new(sender: &EventSender) -> Self {
+        Self {
+            receiver: sender.subscribe(),
+            events: Vec::new(),
+            missed: 0,
+        }
+    }
+
+    /// All events recorded so far, oldest first.
+    pub fn events(&mut self) -> &[DeviceEvent] {
+        self.drain();
+        &self.events
+    }
+
+    /// Kinds of all events recorded so far, oldest first.
+    pub fn kinds(&mut self) -> Vec<&'static str> {
+        self.events().iter().map(DeviceEvent::kind).collect()
+    }
+
+    /// Forget the events recorded so far.
+    pub fn clear(&mut self) {
+        self.drain();
+        self.events.clear();
+        self.missed = 0;
+    }
+
+    /// Wait up to `timeout` for the next event of `kind`.
+    ///
+    /// Only events not yet recorded are considered; everything received
+    /// meanwhile is recorded too. Returns `None` on timeout or when every
+    /// sender is gone.
+    pub async fn wait_for(&mut self, kind: &str, timeout: Duration) -> Option<DeviceEvent> {
+        let wait = async {
+            loop {
+                match self.receiver.recv().await {
+                    Ok(event) => {
+                        self.events.push(event.clone());
+                        if event.kind() == kind {
+                            return Some(event);
+                        }
+                    }
+                    Err(RecvError::Lagged(n)) => self.missed += n,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        };
+        tokio::time::timeout(timeout, wait).await.ok().flatten()
+    }
+
+    /// Assert that exactly the `expected` kinds of events were recorded, in
+    /// that order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the recorded events differ, or if the channel overflowed
+    /// and events were missed.
+    #[track_caller]
+    pub fn expect_sequence<'a>(&mut self, expected: impl IntoIterator<Item = &'a str>) {
+        let expected: Vec<&str> = expected.into_iter().collect();
+        let actual = self.kinds();
+        assert!(
+            self.missed == 0,
+            "missed {} events; raise the channel capacity",
+            self.missed
+        );
+        assert_eq!(actual, expected, "unexpected event sequence");
+    }
+
+    fn drain(&mut self) {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) => self.events.push(event),
+                Err(TryRecvError::Lagged(n)) => self.missed += n,
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -379,6 +564,53 @@ mod tests {
         assert!(json.contains("10"));
     }
 
+    #[test]
+    fn test_device_event_history_sync_deferred() {
+        let event = DeviceEvent::HistorySyncDeferred {
+            device: DeviceId::new("test"),
+            battery: 8,
+            threshold: 15,
+        };
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("history_sync_deferred"));
+        assert!(json.contains("\"battery\":8"));
+        assert!(json.contains("\"threshold\":15"));
+    }
+
+    #[test]
+    fn test_device_event_history_overwrite_risk() {
+        let event = DeviceEvent::HistoryOverwriteRisk {
+            device: DeviceId::new("test"),
+            overwrite_at: time::macros::datetime!(2026-03-01 12:00 UTC),
+        };
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"type\":\"history_overwrite_risk\""));
+        assert!(json.contains("\"overwrite_at\":\"2026-03-01T12:00:00Z\""));
+    }
+
+    #[test]
+    fn test_device_event_settings_changed() {
+        let event = DeviceEvent::SettingsChanged {
+            device: DeviceId::new("test"),
+        };
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"type\":\"settings_changed\""));
+    }
+
+    #[test]
+    fn test_device_event_adapter_state_changed() {
+        let event = DeviceEvent::AdapterStateChanged {
+            state: AdapterState::PoweredOff,
+        };
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"type\":\"adapter_state_changed\""));
+        assert!(json.contains("\"state\":\"PoweredOff\""));
+    }
+
     #[test]
     fn test_device_event_clone() {
         let event = DeviceEvent::Reading {
@@ -558,4 +790,115 @@ mod tests {
         let debug = format!("{:?}", dispatcher);
         assert!(debug.contains("EventDispatcher"));
     }
+
+    // ==================== EventRecorder Tests ====================
+
+    #[test]
+    fn test_event_kind_matches_serialized_type() {
+        let device = DeviceId::new("test");
+        let events = [
+            DeviceEvent::Discovered {
+                device: device.clone(),
+                rssi: None,
+            },
+            DeviceEvent::ReconnectStarted {
+                device: device.clone(),
+                attempt: 1,
+            },
+            DeviceEvent::HistoryOverwriteRisk {
+                device: device.clone(),
+                overwrite_at: time::OffsetDateTime::UNIX_EPOCH,
+            },
+            DeviceEvent::CalibrationStateChanged {
+                device: device.clone(),
+                previous: None,
+                state: CalibrationState::InProgress,
+            },
+            DeviceEvent::AdapterStateChanged {
+                state: AdapterState::PoweredOff,
+            },
+        ];
+        for event in &events {
+            let json = serde_json::to_value(event).unwrap();
+            assert_eq!(json["type"], event.kind());
+        }
+        assert_eq!(events[1].device(), Some(&device));
+        assert_eq!(events[4].device(), None);
+    }
+
+    #[test]
+    fn test_recorder_expect_sequence() {
+        let dispatcher = EventDispatcher::new(10);
+        let mut recorder = EventRecorder::new(&dispatcher.sender());
+        recorder.expect_sequence([]);
+
+        dispatcher.send(DeviceEvent::SettingsChanged {
+            device: DeviceId::new("a"),
+        });
+        dispatcher.send(DeviceEvent::BatteryLow {
+            device: DeviceId::new("a"),
+            level: 9,
+        });
+        recorder.expect_sequence(["settings_changed", "battery_low"]);
+        assert_eq!(recorder.events().len(), 2);
+
+        recorder.clear();
+        recorder.expect_sequence([]);
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected event sequence")]
+    fn test_recorder_expect_sequence_mismatch() {
+        let dispatcher = EventDispatcher::new(10);
+        let mut recorder = EventRecorder::new(&dispatcher.sender());
+        dispatcher.send(DeviceEvent::SettingsChanged {
+            device: DeviceId::new("a"),
+        });
+        recorder.expect_sequence(["battery_low"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "missed 2 events")]
+    fn test_recorder_reports_missed_events() {
+        let dispatcher = EventDispatcher::new(2);
+        let mut recorder = EventRecorder::new(&dispatcher.sender());
+        for _ in 0..4 {
+            dispatcher.send(DeviceEvent::SettingsChanged {
+                device: DeviceId::new("a"),
+            });
+        }
+        recorder.expect_sequence(["settings_changed", "settings_changed"]);
+    }
+
+    #[tokio::test]
+    async fn test_recorder_wait_for() {
+        let dispatcher = EventDispatcher::new(10);
+        let mut recorder = EventRecorder::new(&dispatcher.sender());
+
+        let sender = dispatcher.clone();
+        tokio::spawn(async move {
+            sender.send(DeviceEvent::SettingsChanged {
+                device: DeviceId::new("a"),
+            });
+            sender.send(DeviceEvent::BatteryLow {
+                device: DeviceId::new("a"),
+                level: 9,
+            });
+        });
+
+        let event = recorder
+            .wait_for("battery_low", Duration::from_secs(1))
+            .await;
+        assert!(matches!(
+            event,
+            Some(DeviceEvent::BatteryLow { level: 9, .. })
+        ));
+        assert!(
+            recorder
+                .wait_for("battery_low", Duration::from_millis(10))
+                .await
+                .is_none()
+        );
+        recorder.expect_sequence(["settings_changed", "battery_low"]);
+    }
 }
@@ -38,12 +38,22 @@
 //! Aranet devices support two history protocols:
 //! - **V1**: Notification-based (older devices) - uses characteristic notifications
 //! - **V2**: Read-based (newer devices, preferred) - direct read/write operations
+//!
+//! [`Device::download_history_with_options`] uses V1 when the device's
+//! [`Quirks`](crate::quirks::Quirks) require it.
+//!
+//! # Streaming
+//!
+//! [`Device::history_stream`] yields records as each response is decoded
+//! instead of returning them all at the end, for large histories or
+//! consumers that write records out as they arrive.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 use std::sync::Arc;
 use std::time::Duration;
 
 use bytes::Buf;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
 use time::OffsetDateTime;
 use tokio::time::sleep;
 use tracing::{debug, info, warn};
@@ -52,7 +62,7 @@ use crate::commands::{HISTORY_V1_REQUEST, HISTORY_V2_REQUEST};
 use crate::device::Device;
 use crate::error::{Error, Result};
 use crate::uuid::{COMMAND, HISTORY_V2, READ_INTERVAL, SECONDS_SINCE_UPDATE, TOTAL_READINGS};
-use aranet_types::HistoryRecord;
+use aranet_types::{DeviceType, HistoryRecord};
 
 /// Progress information for history download.
 #[derive(Debug, Clone)]
@@ -447,11 +457,132 @@ pub struct HistoryInfo {
     pub interval_seconds: u16,
     /// Seconds since the last reading.
     pub seconds_since_update: u16,
+    /// Records the device can hold before it overwrites the oldest, if known
+    /// for its type (see [`aranet_types::DeviceType::history_capacity`]).
+    pub capacity: Option<u16>,
+}
+
+impl HistoryInfo {
+    /// Fraction of the history memory in use, from 0.0 to 1.0.
+    pub fn fill_ratio(&self) -> Option<f32> {
+        let capacity = self.capacity.filter(|&c| c > 0)?;
+        Some((f32::from(self.total_readings) / f32::from(capacity)).min(1.0))
+    }
+
+    /// Time until the device starts overwriting its oldest records at the
+    /// current interval; zero once it is full.
+    pub fn time_until_overwrite(&self) -> Option<time::Duration> {
+        let capacity = self.capacity?;
+        if self.interval_seconds == 0 {
+            return None;
+        }
+        let remaining = capacity.saturating_sub(self.total_readings);
+        Some(time::Duration::seconds(
+            i64::from(remaining) * i64::from(self.interval_seconds),
+        ))
+    }
+
+    /// Summary such as `storage 68% full, ~3.2 days until overwrite`.
+    pub fn storage_summary(&self) -> Option<String> {
+        let ratio = self.fill_ratio()?;
+        let until = self.time_until_overwrite();
+        if until.is_some_and(|d| d.is_zero()) || ratio >= 1.0 {
+            return Some("storage full, oldest records are being overwritten".to_string());
+        }
+        let percent = (ratio * 100.0).floor();
+        let Some(until) = until else {
+            return Some(format!("storage {percent}% full"));
+        };
+        let until = if until >= time::Duration::days(1) {
+            format!("{:.1} days", until.as_seconds_f64() / 86_400.0)
+        } else if until >= time::Duration::hours(1) {
+            format!("{:.1} hours", until.as_seconds_f64() / 3_600.0)
+        } else {
+            format!("{} min", until.whole_minutes().max(1))
+        };
+        Some(format!("storage {percent}% full, ~{until} until overwrite"))
+    }
+
+    /// Find the 1-based index of the oldest record taken at or after `since`.
+    ///
+    /// Record timestamps are derived the same way as in
+    /// [`Device::download_history_with_options`]: the newest record was taken
+    /// `seconds_since_update` before `now`, and each older record one interval
+    /// earlier. Returns `None` when the device holds no records that new.
+    ///
+    /// If the interval is unknown (zero), every record is considered new.
+    pub fn first_index_since(&self, since: OffsetDateTime, now: OffsetDateTime) -> Option<u16> {
+        if self.total_readings == 0 {
+            return None;
+        }
+
+        let latest = now - time::Duration::seconds(i64::from(self.seconds_since_update));
+        if since > latest {
+            return None;
+        }
+        if self.interval_seconds == 0 {
+            return Some(1);
+        }
+
+        // Number of whole intervals between `since` and the newest record;
+        // records that many readings ago (or fewer) are at or after `since`
+        let readings_ago = (latest - since).whole_seconds() / i64::from(self.interval_seconds);
+        let start = i64::from(self.total_readings) - readings_ago;
+        Some(start.max(1) as u16)
+    }
+}
+
+/// Share of the overwrite window left at which un-synced history is at risk.
+///
+/// With 2016 records at a 5 minute interval the window is 7 days, so a sync
+/// becomes urgent 42 hours before the first un-synced record is lost.
+pub const OVERWRITE_RISK_FRACTION: f64 = 0.25;
+
+/// When the first record taken after `last_synced_at` will be overwritten.
+///
+/// The device keeps `capacity` records in a ring buffer, so each record
+/// survives `capacity` measurement intervals. Returns `None` if the device
+/// type keeps no history or the interval is unknown (zero).
+pub fn history_overwrite_at(
+    device_type: DeviceType,
+    interval_seconds: u16,
+    last_synced_at: OffsetDateTime,
+) -> Option<OffsetDateTime> {
+    let capacity = device_type.history_capacity()?;
+    if interval_seconds == 0 {
+        return None;
+    }
+    Some(
+        last_synced_at + time::Duration::seconds(i64::from(capacity) * i64::from(interval_seconds)),
+    )
+}
+
+/// When un-synced records will be overwritten, if that is close enough to
+/// sync now.
+///
+/// Returns the [`history_overwrite_at`] deadline once less than
+/// [`OVERWRITE_RISK_FRACTION`] of the window remains at `now`, including
+/// when it has already passed.
+pub fn history_overwrite_risk(
+    device_type: DeviceType,
+    interval_seconds: u16,
+    last_synced_at: OffsetDateTime,
+    now: OffsetDateTime,
+) -> Option<OffsetDateTime> {
+    let overwrite_at = history_overwrite_at(device_type, interval_seconds, last_synced_at)?;
+    let window = overwrite_at - last_synced_at;
+    (overwrite_at - now <= window * OVERWRITE_RISK_FRACTION).then_some(overwrite_at)
 }
 
 impl Device {
     /// Get information about the stored history.
     pub async fn get_history_info(&self) -> Result<HistoryInfo> {
+        self.operations
+            .run("read history info", self.get_history_info_inner())
+            .await
+    }
+
+    async fn get_history_info_inner(&self) -> Result<HistoryInfo> {
         // Read total readings count
         let total_data = self.read_characteristic(TOTAL_READINGS).await?;
         let total_readings = if total_data.len() >= 2 {
@@ -482,6 +613,7 @@ impl Device {
             total_readings,
             interval_seconds,
             seconds_since_update,
+            capacity: self.device_type().and_then(|t| t.history_capacity()),
         })
     }
 
@@ -491,8 +623,66 @@ impl Device {
             .await
     }
 
+    /// Download only the records taken at or after `since`.
+    ///
+    /// The start index is computed from the device's record count, interval,
+    /// and time since the last update (see [`HistoryInfo::first_index_since`]),
+    /// so incremental syncs don't need to do the index math themselves.
+    /// Returns an empty vector if there is nothing new.
+    pub async fn read_history_since(&self, since: OffsetDateTime) -> Result<Vec<HistoryRecord>> {
+        self.read_history_since_with_options(since, HistoryOptions::default())
+            .await
+    }
+
+    /// Download records taken at or after `since`, with custom options.
+    ///
+    /// Any `start_index` or `end_index` already set on `options` is replaced.
+    pub async fn read_history_since_with_options(
+        &self,
+        since: OffsetDateTime,
+        options: HistoryOptions,
+    ) -> Result<Vec<HistoryRecord>> {
+        // One queued operation, so no reading lands between the info read
+        // and the download from another task's request
+        self.operations
+            .run(
+                "download history",
+                self.read_history_since_inner(since, options),
+            )
+            .await
+    }
+
+    async fn read_history_since_inner(
+        &self,
+        since: OffsetDateTime,
+        mut options: HistoryOptions,
+    ) -> Result<Vec<HistoryRecord>> {
+        let info = self.get_history_info().await?;
+        let Some(start) = info.first_index_since(since, OffsetDateTime::now_utc()) else {
+            debug!("No history records since {}", since);
+            return Ok(Vec::new());
+        };
+
+        info!(
+            "Downloading history since {} (records {}..={})",
+            since, start, info.total_readings
+        );
+        options.start_index = Some(start);
+        options.end_index = None;
+
+        let mut records = self.download_history_with_options(options).await?;
+        // A new reading may land between the info read and the download,
+        // which shifts indices by one; trim anything older than requested
+        records.retain(|record| record.timestamp >= since);
+        Ok(records)
+    }
+
     /// Download historical readings with custom options.
     ///
+    /// Every parameter of the whole range is held in memory until the last one
+    /// is downloaded; use [`history_stream`](Self::history_stream) to handle
+    /// records as they arrive instead.
+    ///
     /// # Device Support
     ///
     /// - **Aranet4**: Downloads CO₂, temperature, pressure, humidity
@@ -515,10 +705,25 @@ impl Device {
     pub async fn download_history_with_options(
         &self,
         options: HistoryOptions,
+    ) -> Result<Vec<HistoryRecord>> {
+        self.operations
+            .run(
+                "download history",
+                self.download_history_with_options_inner(options),
+            )
+            .await
+    }
+
+    async fn download_history_with_options_inner(
+        &self,
+        options: HistoryOptions,
     ) -> Result<Vec<HistoryRecord>> {
         use aranet_types::DeviceType;
 
-        let info = self.get_history_info().await?;
+        let info = self
+            .get_history_info()
+            .await
+            .map_err(|e| e.context("read history info"))?;
         info!(
             "Device has {} readings, interval {}s, last update {}s ago",
             info.total_readings, info.interval_seconds, info.seconds_since_update
@@ -600,6 +805,14 @@ impl Device {
                 )
                 .await
             }
+            _ if self.quirks().history_v1 => {
+                // Old firmware only supports the V1 protocol, which always
+                // downloads everything; trim to the requested range.
+                let mut records = self.download_history_v1().await?;
+                records.truncate(usize::from(end_idx));
+                records.drain(..usize::from(start_idx - 1).min(records.len()));
+                Ok(records)
+            }
             _ => {
                 // For Aranet4 (and unknown devices), download CO2, temp, pressure, humidity
                 self.download_aranet4_history_internal(
@@ -963,16 +1176,76 @@ impl Device {
 
         let mut values: BTreeMap<u16, T> = BTreeMap::new();
         let mut current_idx = start_idx;
-        let mut consecutive_wrong_param = 0u32;
-        const MAX_WRONG_PARAM_RETRIES: u32 = 5;
 
         while current_idx <= end_idx {
+            let Some(chunk) = self
+                .read_history_chunk(param, current_idx, read_delay)
+                .await?
+            else {
+                break;
+            };
+            let (resp_start, resp_count) = (chunk.start, chunk.count);
+
+            // Check if we've reached the end (count == 0)
+            if resp_count == 0 {
+                debug!("Reached end of history (count=0)");
+                break;
+            }
+
+            // Parse data values
+            let data = &chunk.data[..];
+            let num_values = (data.len() / value_size).min(resp_count);
+
+            for i in 0..num_values {
+                let idx = resp_start + i as u16;
+                if idx > end_idx {
+                    break;
+                }
+                if let Some(value) = value_parser(data, i) {
+                    values.insert(idx, value);
+                }
+            }
+
+            current_idx = resp_start + num_values as u16;
+            debug!(
+                "Downloaded {} values, next index: {}",
+                num_values, current_idx
+            );
+
+            // Report progress
+            on_progress(values.len());
+
+            // Check if we've downloaded all available data
+            if (resp_start as usize + resp_count) >= end_idx as usize {
+                debug!("Reached end of requested range");
+                break;
+            }
+        }
+
+        // Convert to ordered vector (BTreeMap already maintains order)
+        Ok(values.into_values().collect())
+    }
+
+    /// Request one V2 history response for `param` starting at `index`.
+    ///
+    /// Returns `None` if the device sent a malformed response or kept
+    /// answering for a different parameter.
+    async fn read_history_chunk(
+        &self,
+        param: HistoryParam,
+        index: u16,
+        read_delay: Duration,
+    ) -> Result<Option<HistoryChunk>> {
+        const MAX_WRONG_PARAM_RETRIES: u32 = 5;
+        let mut consecutive_wrong_param = 0u32;
+
+        loop {
             // Send V2 history request using command constant
             let cmd = [
                 HISTORY_V2_REQUEST,
                 param as u8,
-                (current_idx & 0xFF) as u8,
-                ((current_idx >> 8) & 0xFF) as u8,
+                (index & 0xFF) as u8,
+                ((index >> 8) & 0xFF) as u8,
             ];
 
             self.write_characteristic(COMMAND, &cmd).await?;
@@ -994,7 +1267,7 @@ impl Device {
                     "Invalid history response: too short ({} bytes)",
                     response.len()
                 );
-                break;
+                return Ok(None);
             }
 
             let resp_param = response[0];
@@ -1006,61 +1279,24 @@ impl Device {
                 );
                 if consecutive_wrong_param >= MAX_WRONG_PARAM_RETRIES {
                     warn!("Too many wrong parameter responses, aborting download");
-                    break;
+                    return Ok(None);
                 }
                 // Wait and retry - device may not have processed command yet
                 sleep(read_delay).await;
                 continue;
             }
-            consecutive_wrong_param = 0;
-
-            // Parse header
-            let resp_start = u16::from_le_bytes([response[7], response[8]]);
-            let resp_count = response[9] as usize;
 
+            let chunk = HistoryChunk {
+                start: u16::from_le_bytes([response[7], response[8]]),
+                count: response[9] as usize,
+                data: response[10..].to_vec(),
+            };
             debug!(
                 "History response: param={}, start={}, count={}",
-                resp_param, resp_start, resp_count
-            );
-
-            // Check if we've reached the end (count == 0)
-            if resp_count == 0 {
-                debug!("Reached end of history (count=0)");
-                break;
-            }
-
-            // Parse data values
-            let data = &response[10..];
-            let num_values = (data.len() / value_size).min(resp_count);
-
-            for i in 0..num_values {
-                let idx = resp_start + i as u16;
-                if idx > end_idx {
-                    break;
-                }
-                if let Some(value) = value_parser(data, i) {
-                    values.insert(idx, value);
-                }
-            }
-
-            current_idx = resp_start + num_values as u16;
-            debug!(
-                "Downloaded {} values, next index: {}",
-                num_values, current_idx
+                resp_param, chunk.start, chunk.count
             );
-
-            // Report progress
-            on_progress(values.len());
-
-            // Check if we've downloaded all available data
-            if (resp_start as usize + resp_count) >= end_idx as usize {
-                debug!("Reached end of requested range");
-                break;
-            }
+            return Ok(Some(chunk));
         }
-
-        // Convert to ordered vector (BTreeMap already maintains order)
-        Ok(values.into_values().collect())
     }
 
     /// Download a single parameter's history using V2 protocol (u16 values) with progress.
@@ -1145,6 +1381,12 @@ impl Device {
     /// This is used for older devices that don't support the V2 read-based protocol.
     /// V1 uses notifications on the HISTORY_V1 characteristic.
     pub async fn download_history_v1(&self) -> Result<Vec<HistoryRecord>> {
+        self.operations
+            .run("download history", self.download_history_v1_inner())
+            .await
+    }
+
+    async fn download_history_v1_inner(&self) -> Result<Vec<HistoryRecord>> {
         use crate::uuid::HISTORY_V1;
         use tokio::sync::mpsc;
 
@@ -1317,6 +1559,356 @@ impl Device {
     }
 }
 
+/// One V2 history response: up to `count` values of a parameter from `start`.
+#[derive(Debug)]
+struct HistoryChunk {
+    start: u16,
+    count: usize,
+    data: Vec<u8>,
+}
+
+/// Decode value `i` of a V2 response for `param`.
+///
+/// Humidity is one byte per value, radon four and everything else two.
+fn history_chunk_value(param: HistoryParam, data: &[u8], i: usize) -> Option<u32> {
+    match param {
+        HistoryParam::Humidity => data.get(i).map(|&b| u32::from(b)),
+        HistoryParam::Radon => data
+            .get(i * 4..i * 4 + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+        _ => data
+            .get(i * 2..i * 2 + 2)
+            .map(|b| u32::from(u16::from_le_bytes([b[0], b[1]]))),
+    }
+}
+
+/// Decoding state of a [`Device::history_stream`] download.
+///
+/// Each response carries one parameter for a run of indices. Its values are
+/// queued per parameter, and a record is yielded as soon as every parameter
+/// has a value for its index, so at most about one response per parameter
+/// is held at a time.
+#[derive(Debug)]
+struct HistoryStreamState {
+    info: HistoryInfo,
+    /// Time of the newest stored reading, fixed when the stream starts so
+    /// every record uses the same clock.
+    latest_reading_time: OffsetDateTime,
+    /// Parameters making up a record, in request order.
+    params: Vec<HistoryParam>,
+    /// Decoded values per parameter, starting at index `next`.
+    pending: Vec<VecDeque<u32>>,
+    /// Next index to request per parameter; past `end` once exhausted.
+    /// (u32 so the end of a full u16 range is representable.)
+    requested: Vec<u32>,
+    /// Index of the next record to yield.
+    next: u32,
+    start: u32,
+    end: u32,
+    read_delay: Duration,
+}
+
+impl HistoryStreamState {
+    fn new(
+        info: HistoryInfo,
+        latest_reading_time: OffsetDateTime,
+        device_type: Option<DeviceType>,
+        start: u32,
+        end: u32,
+        read_delay: Duration,
+    ) -> Self {
+        let params = match device_type {
+            Some(DeviceType::AranetRadon) => vec![
+                HistoryParam::Radon,
+                HistoryParam::Temperature,
+                HistoryParam::Pressure,
+                HistoryParam::Humidity2,
+            ],
+            Some(DeviceType::Aranet2) => vec![HistoryParam::Temperature, HistoryParam::Humidity2],
+            _ => vec![
+                HistoryParam::Co2,
+                HistoryParam::Temperature,
+                HistoryParam::Pressure,
+                HistoryParam::Humidity,
+            ],
+        };
+        Self {
+            info,
+            latest_reading_time,
+            pending: vec![VecDeque::new(); params.len()],
+            requested: vec![start; params.len()],
+            params,
+            next: start,
+            start,
+            end,
+            read_delay,
+        }
+    }
+
+    /// The parameter (by position) and index to request next: whichever
+    /// parameter is furthest behind. `None` once every one is exhausted.
+    fn next_request(&self) -> Option<(usize, u16)> {
+        self.requested
+            .iter()
+            .enumerate()
+            .filter(|&(_, &index)| index <= self.end)
+            .min_by_key(|&(_, &index)| index)
+            .map(|(pos, &index)| (pos, index as u16))
+    }
+
+    /// Queue the values of a response for the parameter at `pos`.
+    ///
+    /// `None` (a malformed response) or an empty response means the device
+    /// has nothing more for this parameter.
+    fn accept(&mut self, pos: usize, chunk: Option<HistoryChunk>) {
+        let param = self.params[pos];
+        let Some(chunk) = chunk.filter(|chunk| chunk.count > 0) else {
+            debug!(
+                "No more {:?} history after index {}",
+                param, self.requested[pos]
+            );
+            self.requested[pos] = self.end + 1;
+            return;
+        };
+
+        let wanted = self.requested[pos];
+        let chunk_start = u32::from(chunk.start);
+        if chunk_start > wanted {
+            warn!(
+                "{:?} history skipped from {} to {}; filling with defaults",
+                param, wanted, chunk_start
+            );
+            let gap = (chunk_start.min(self.end + 1) - wanted) as usize;
+            self.pending[pos].extend(std::iter::repeat_n(0, gap));
+        }
+
+        let mut index = chunk_start;
+        for i in 0..chunk.count {
+            if index > self.end {
+                break;
+            }
+            let Some(value) = history_chunk_value(param, &chunk.data, i) else {
+                break;
+            };
+            // Values before the requested index were already queued
+            if index >= wanted {
+                self.pending[pos].push_back(value);
+            }
+            index += 1;
+        }
+        // Always advance, so a response with no usable values cannot repeat
+        self.requested[pos] = index.max(wanted + 1);
+    }
+
+    /// Records for which every parameter has been decoded, oldest first.
+    ///
+    /// Exhausted parameters no longer hold records back; their missing
+    /// values use defaults, like a partial batch download.
+    fn take_ready(&mut self) -> Vec<HistoryRecord> {
+        let exhausted = |pos: usize| self.requested[pos] > self.end;
+        let waiting = (0..self.params.len())
+            .filter(|&pos| !exhausted(pos))
+            .map(|pos| self.pending[pos].len())
+            .min();
+        let ready = match waiting {
+            Some(ready) => ready,
+            // Everything is downloaded: flush what is left
+            None => self.pending.iter().map(VecDeque::len).max().unwrap_or(0),
+        };
+        let ready = ready.min((self.end + 1).saturating_sub(self.next) as usize);
+
+        let mut records = Vec::with_capacity(ready);
+        for _ in 0..ready {
+            let values: Vec<Option<u32>> =
+                self.pending.iter_mut().map(VecDeque::pop_front).collect();
+            records.push(self.record(self.next, &values));
+            self.next += 1;
+        }
+        if waiting.is_none() && self.pending.iter().all(VecDeque::is_empty) {
+            self.next = self.end + 1;
+        }
+        records
+    }
+
+    /// Build the record at `index` from one value per parameter.
+    fn record(&self, index: u32, values: &[Option<u32>]) -> HistoryRecord {
+        let value = |param: HistoryParam| {
+            self.params
+                .iter()
+                .position(|&p| p == param)
+                .and_then(|pos| values[pos])
+        };
+        let raw = |param: HistoryParam| value(param).unwrap_or(0) as u16;
+
+        let readings_ago = i64::from(self.info.total_readings) - i64::from(index);
+        let timestamp = self.latest_reading_time
+            - time::Duration::seconds(readings_ago * i64::from(self.info.interval_seconds));
+        let humidity = if self.params.contains(&HistoryParam::Humidity2) {
+            // Humidity2 is stored as tenths of a percent
+            (raw(HistoryParam::Humidity2) / 10).min(100) as u8
+        } else {
+            raw(HistoryParam::Humidity) as u8
+        };
+
+        HistoryRecord {
+            timestamp,
+            co2: raw(HistoryParam::Co2),
+            temperature: raw_to_temperature(raw(HistoryParam::Temperature)),
+            pressure: raw_to_pressure(raw(HistoryParam::Pressure)),
+            humidity,
+            radon: self
+                .params
+                .contains(&HistoryParam::Radon)
+                .then(|| value(HistoryParam::Radon).unwrap_or(0)),
+            radiation_rate: None,
+            radiation_total: None,
+        }
+    }
+
+    /// Progress after a response for the parameter at `pos`.
+    fn progress(&self, pos: usize) -> HistoryProgress {
+        let total = (self.end + 1).saturating_sub(self.start) as usize;
+        let done = |pos: usize| {
+            (self.requested[pos].min(self.end + 1)).saturating_sub(self.start) as usize
+        };
+        let mut progress =
+            HistoryProgress::new(self.params[pos], pos + 1, self.params.len(), total);
+        progress.values_downloaded = done(pos);
+        let all_values = total * self.params.len();
+        progress.overall_progress = if all_values == 0 {
+            1.0
+        } else {
+            (0..self.params.len()).map(done).sum::<usize>() as f32 / all_values as f32
+        };
+        progress
+    }
+}
+
+impl Device {
+    /// Stream history records oldest first, as the responses arrive.
+    ///
+    /// Unlike [`Self::download_history_with_options`], which downloads each
+    /// parameter for the whole range before building any record, this
+    /// requests one response at a time for whichever parameter is furthest
+    /// behind and yields every record that is then complete. Peak memory is
+    /// about one response per parameter, and dropping the stream stops the
+    /// download before the next request. Other operations on the device can
+    /// run between responses.
+    ///
+    /// `start_index`, `end_index`, the read delay and the progress callback
+    /// from `options` are honoured; progress names the parameter of each
+    /// response. Checkpoints are not (a consumer that persists records as
+    /// they arrive can resume with `start_index`).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use aranet_core::{Device, HistoryOptions};
+    /// use futures::TryStreamExt;
+    ///
+    /// # async fn example() -> aranet_core::Result<()> {
+    /// let device = Device::connect("Aranet4 12345").await?;
+    /// let mut records = device.history_stream(HistoryOptions::default());
+    /// while let Some(record) = records.try_next().await? {
+    ///     println!("{}: {} ppm", record.timestamp, record.co2);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn history_stream(&self, options: HistoryOptions) -> BoxStream<'_, Result<HistoryRecord>> {
+        let options = Arc::new(options);
+        stream::try_unfold(None, move |state: Option<HistoryStreamState>| {
+            let options = Arc::clone(&options);
+            async move {
+                let mut state = match state {
+                    Some(state) => state,
+                    None => self.start_history_stream(&options).await?,
+                };
+                if state.next > state.end {
+                    return Ok::<_, Error>(None);
+                }
+
+                if self.quirks().history_v1 {
+                    // V1 cannot request a range: download everything once
+                    let mut records = self.download_history_v1().await?;
+                    records.truncate(state.end as usize);
+                    records.drain(..(state.next as usize - 1).min(records.len()));
+                    state.next = state.end + 1;
+                    return Ok(Some((records, Some(state))));
+                }
+
+                let Some((pos, index)) = state.next_request() else {
+                    // Every parameter is exhausted; flush the rest
+                    let records = state.take_ready();
+                    state.next = state.end + 1;
+                    return Ok(Some((records, Some(state))));
+                };
+
+                // Queued per response, so other operations get a turn
+                // during a long download
+                let chunk = self
+                    .operations
+                    .run(
+                        "download history chunk",
+                        self.read_history_chunk(state.params[pos], index, state.read_delay),
+                    )
+                    .await?;
+                state.accept(pos, chunk);
+                options.report_progress(&state.progress(pos));
+
+                Ok(Some((state.take_ready(), Some(state))))
+            }
+        })
+        .map_ok(|records| stream::iter(records.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+    }
+
+    /// Read history info and validate the requested range for a stream.
+    async fn start_history_stream(&self, options: &HistoryOptions) -> Result<HistoryStreamState> {
+        if self.device_type() == Some(DeviceType::AranetRadiation) {
+            return Err(Error::Unsupported(
+                "History download is not available for Aranet Radiation devices.".to_string(),
+            ));
+        }
+
+        let info = self
+            .get_history_info()
+            .await
+            .map_err(|e| e.context("read history info"))?;
+        let latest_reading_time = OffsetDateTime::now_utc()
+            - time::Duration::seconds(i64::from(info.seconds_since_update));
+
+        let start = u32::from(options.start_index.unwrap_or(1));
+        let end = u32::from(options.end_index.unwrap_or(info.total_readings))
+            .min(u32::from(info.total_readings));
+        if start == 0 {
+            return Err(Error::InvalidConfig(
+                "start_index must be >= 1 (indices are 1-based)".into(),
+            ));
+        }
+
+        let read_delay = if options.use_adaptive_delay {
+            options.effective_read_delay(self.signal_quality().await)
+        } else {
+            options.read_delay
+        };
+
+        debug!(
+            "Streaming history {}..={} of {}",
+            start, end, info.total_readings
+        );
+        Ok(HistoryStreamState::new(
+            info,
+            latest_reading_time,
+            self.device_type(),
+            start,
+            end,
+            read_delay,
+        ))
+    }
+}
+
 /// Build history records from downloaded parameter arrays.
 ///
 /// For Aranet4: pass co2_values and empty radon_values.
@@ -1547,6 +2139,7 @@ mod tests {
             total_readings: 1000,
             interval_seconds: 300,
             seconds_since_update: 120,
+            capacity: None,
         };
 
         assert_eq!(info.total_readings, 1000);
@@ -1560,10 +2153,227 @@ mod tests {
             total_readings: 500,
             interval_seconds: 60,
             seconds_since_update: 30,
+            capacity: None,
         };
 
         let debug_str = format!("{:?}", info);
         assert!(debug_str.contains("total_readings"));
         assert!(debug_str.contains("500"));
     }
+
+    // --- HistoryInfo::first_index_since tests ---
+
+    fn history_info(total: u16, interval: u16, age: u16) -> HistoryInfo {
+        HistoryInfo {
+            total_readings: total,
+            interval_seconds: interval,
+            seconds_since_update: age,
+            capacity: Some(2016),
+        }
+    }
+
+    #[test]
+    fn test_storage_usage() {
+        // 1371 of 2016 records at 5 minutes leaves 645 * 300s
+        let info = history_info(1371, 300, 60);
+        assert!((info.fill_ratio().unwrap() - 0.68).abs() < 0.001);
+        assert_eq!(
+            info.time_until_overwrite(),
+            Some(time::Duration::seconds(645 * 300))
+        );
+        assert_eq!(
+            info.storage_summary().unwrap(),
+            "storage 68% full, ~2.2 days until overwrite"
+        );
+
+        assert_eq!(
+            history_info(2010, 300, 0).storage_summary().unwrap(),
+            "storage 99% full, ~30 min until overwrite"
+        );
+        let full = history_info(2016, 60, 0);
+        assert_eq!(full.time_until_overwrite(), Some(time::Duration::ZERO));
+        assert_eq!(
+            full.storage_summary().unwrap(),
+            "storage full, oldest records are being overwritten"
+        );
+
+        let unknown = HistoryInfo {
+            capacity: None,
+            ..history_info(100, 300, 0)
+        };
+        assert_eq!(unknown.fill_ratio(), None);
+        assert_eq!(unknown.storage_summary(), None);
+    }
+
+    #[test]
+    fn test_history_overwrite_risk() {
+        let synced = OffsetDateTime::now_utc() - time::Duration::days(2);
+        // 2016 records at 5 minutes last 7 days
+        let overwrite_at = synced + time::Duration::days(7);
+        assert_eq!(
+            history_overwrite_at(DeviceType::Aranet4, 300, synced),
+            Some(overwrite_at)
+        );
+        assert_eq!(history_overwrite_at(DeviceType::Aranet4, 0, synced), None);
+        assert_eq!(
+            history_overwrite_at(DeviceType::AranetRadiation, 300, synced),
+            None
+        );
+
+        // The last quarter of the window (42 hours) is at risk
+        let risk = |now| history_overwrite_risk(DeviceType::Aranet4, 300, synced, now);
+        assert_eq!(risk(synced + time::Duration::days(5)), None);
+        assert_eq!(
+            risk(overwrite_at - time::Duration::hours(42)),
+            Some(overwrite_at)
+        );
+        assert_eq!(
+            risk(overwrite_at + time::Duration::hours(1)),
+            Some(overwrite_at)
+        );
+    }
+
+    #[test]
+    fn test_first_index_since_window() {
+        let now = OffsetDateTime::now_utc();
+        // Newest record 60s ago, one record every 300s
+        let info = history_info(100, 300, 60);
+        let latest = now - time::Duration::seconds(60);
+
+        // Exactly the newest record
+        assert_eq!(info.first_index_since(latest, now), Some(100));
+        // Just after the second newest record
+        let since = latest - time::Duration::seconds(299);
+        assert_eq!(info.first_index_since(since, now), Some(100));
+        // Exactly the second newest record
+        let since = latest - time::Duration::seconds(300);
+        assert_eq!(info.first_index_since(since, now), Some(99));
+        // Ten intervals back
+        let since = latest - time::Duration::seconds(3000);
+        assert_eq!(info.first_index_since(since, now), Some(90));
+    }
+
+    #[test]
+    fn test_first_index_since_bounds() {
+        let now = OffsetDateTime::now_utc();
+        let info = history_info(100, 300, 60);
+
+        // Nothing newer than the latest record
+        assert_eq!(info.first_index_since(now, now), None);
+        // Older than the whole buffer clamps to the first record
+        let since = now - time::Duration::days(365);
+        assert_eq!(info.first_index_since(since, now), Some(1));
+        // Empty history
+        assert_eq!(history_info(0, 300, 0).first_index_since(since, now), None);
+        // Unknown interval downloads everything
+        assert_eq!(
+            history_info(100, 0, 0).first_index_since(since, now),
+            Some(1)
+        );
+    }
+
+    // --- Streamed record decoding ---
+
+    fn chunk(param: HistoryParam, start: u16, values: &[u32]) -> Option<HistoryChunk> {
+        let data = values
+            .iter()
+            .flat_map(|&v| match param {
+                HistoryParam::Humidity => vec![v as u8],
+                HistoryParam::Radon => v.to_le_bytes().to_vec(),
+                _ => (v as u16).to_le_bytes().to_vec(),
+            })
+            .collect();
+        Some(HistoryChunk {
+            start,
+            count: values.len(),
+            data,
+        })
+    }
+
+    fn stream_state(device_type: DeviceType, total: u16) -> HistoryStreamState {
+        let latest = OffsetDateTime::now_utc() - time::Duration::seconds(60);
+        HistoryStreamState::new(
+            history_info(total, 300, 60),
+            latest,
+            Some(device_type),
+            1,
+            u32::from(total),
+            Duration::ZERO,
+        )
+    }
+
+    #[test]
+    fn test_stream_yields_records_once_every_param_has_arrived() {
+        let mut state = stream_state(DeviceType::Aranet4, 5);
+        let co2 = 0;
+        let humidity = 3;
+
+        assert_eq!(state.next_request(), Some((co2, 1)));
+        state.accept(co2, chunk(HistoryParam::Co2, 1, &[400, 401, 402]));
+        state.accept(1, chunk(HistoryParam::Temperature, 1, &[440; 5]));
+        state.accept(2, chunk(HistoryParam::Pressure, 1, &[10130; 5]));
+        // Humidity has not arrived, so no record is complete yet
+        assert!(state.take_ready().is_empty());
+
+        assert_eq!(state.next_request(), Some((humidity, 1)));
+        state.accept(humidity, chunk(HistoryParam::Humidity, 1, &[40, 41]));
+        let first = state.take_ready();
+        assert_eq!(first.len(), 2);
+        assert_eq!((first[0].co2, first[0].humidity), (400, 40));
+        assert_eq!((first[1].co2, first[1].humidity), (401, 41));
+        assert_eq!(first[1].temperature, 22.0);
+        assert_eq!(first[1].pressure, 1013.0);
+
+        // The parameter furthest behind is requested next
+        assert_eq!(state.next_request(), Some((humidity, 3)));
+        state.accept(humidity, chunk(HistoryParam::Humidity, 3, &[42, 43, 44]));
+        let second = state.take_ready();
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].co2, 402);
+
+        assert_eq!(state.next_request(), Some((co2, 4)));
+        state.accept(co2, chunk(HistoryParam::Co2, 4, &[403, 404]));
+        let third = state.take_ready();
+        assert_eq!(third.iter().map(|r| r.co2).collect::<Vec<_>>(), [403, 404]);
+        assert_eq!(third[1].timestamp, state.latest_reading_time);
+        assert_eq!(
+            first[0].timestamp,
+            state.latest_reading_time - time::Duration::seconds(4 * 300)
+        );
+        assert_eq!(state.next_request(), None);
+        assert_eq!(state.next, 6);
+    }
+
+    #[test]
+    fn test_stream_progress_names_the_downloaded_param() {
+        let mut state = stream_state(DeviceType::AranetRadon, 4);
+        state.accept(0, chunk(HistoryParam::Radon, 1, &[100, 110, 120, 130]));
+        state.accept(1, chunk(HistoryParam::Temperature, 1, &[440, 440]));
+
+        let progress = state.progress(1);
+        assert_eq!(progress.current_param, HistoryParam::Temperature);
+        assert_eq!((progress.param_index, progress.total_params), (2, 4));
+        assert_eq!((progress.values_downloaded, progress.total_values), (2, 4));
+        assert!((progress.overall_progress - 6.0 / 16.0).abs() < f32::EPSILON);
+
+        assert_eq!(state.progress(0).current_param, HistoryParam::Radon);
+    }
+
+    #[test]
+    fn test_stream_exhausted_param_uses_defaults() {
+        let mut state = stream_state(DeviceType::AranetRadon, 2);
+        state.accept(0, chunk(HistoryParam::Radon, 1, &[100, 110]));
+        state.accept(1, chunk(HistoryParam::Temperature, 1, &[440, 450]));
+        // The device has no pressure history
+        state.accept(2, chunk(HistoryParam::Pressure, 1, &[]));
+        state.accept(3, chunk(HistoryParam::Humidity2, 1, &[455, 465]));
+
+        let records = state.take_ready();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].radon, Some(110));
+        assert_eq!(records[1].co2, 0);
+        assert_eq!(records[1].pressure, 0.0);
+        assert_eq!(records[1].humidity, 46);
+        assert_eq!(state.next_request(), None);
+    }
 }
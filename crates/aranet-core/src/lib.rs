@@ -15,6 +15,7 @@
 //! - **Auto-reconnection**: Configurable backoff and retry logic
 //! - **Real-time streaming**: Subscribe to sensor value changes
 //! - **Multi-device support**: Manage multiple sensors simultaneously
+//! - **Output sinks**: Fan readings out to CSV files, channels, webhooks and more
 //!
 //! # Supported Devices
 //!
@@ -67,10 +68,16 @@
 //! }
 //! ```
 
+pub mod adapter;
 pub mod advertisement;
+pub mod advice;
 #[cfg(target_os = "linux")]
 pub mod bluez_agent;
+pub mod bonding;
+pub mod budget;
+pub mod clock;
 pub mod commands;
+pub mod config;
 pub mod device;
 pub mod diagnostics;
 pub mod error;
@@ -82,12 +89,18 @@ pub mod messages;
 pub mod metrics;
 pub mod mock;
 pub mod passive;
+pub mod placement;
 pub mod platform;
+mod queue;
+pub mod quirks;
 pub mod readings;
 pub mod reconnect;
 pub mod retry;
 pub mod scan;
+pub mod scan_service;
+pub mod sensor_health;
 pub mod settings;
+pub mod sink;
 pub mod streaming;
 pub mod thresholds;
 pub mod traits;
@@ -102,17 +115,24 @@ pub use aranet_types::types;
 pub use aranet_types::uuid;
 
 // Core exports
-pub use device::{ConnectionConfig, Device, SignalQuality};
-pub use error::{ConnectionFailureReason, DeviceNotFoundReason, Error, Result};
+pub use advice::{Advice, AdviceConfig};
+pub use device::{ConnectionConfig, Device, DeviceSnapshot, RssiSamples, SignalQuality, WriteMode};
+pub use error::{ConnectionFailureReason, DeviceNotFoundReason, Error, ErrorContext, Result};
 pub use history::{
-    HistoryCheckpoint, HistoryInfo, HistoryOptions, HistoryParam, PartialHistoryData,
+    HistoryCheckpoint, HistoryInfo, HistoryOptions, HistoryParam, OVERWRITE_RISK_FRACTION,
+    PartialHistoryData, history_overwrite_at, history_overwrite_risk,
 };
 pub use readings::ExtendedReading;
 pub use scan::{
-    DiscoveredDevice, FindProgress, ProgressCallback, ScanOptions, find_device_with_progress,
-    scan_with_retry,
+    DiscoveredDevice, FindProgress, ProgressCallback, ScanOptions, StableId,
+    find_device_with_progress, scan_with_retry, sort_discovered,
+};
+pub use scan_service::{ScanService, ScanSubscription};
+pub use sensor_health::{CalibrationState, SensorHealth, SensorHealthWarning};
+pub use settings::{
+    AppliedSettings, BluetoothRange, CalibrationData, DeviceSettings, MeasurementInterval,
+    RadonUnit, SettingChange, SettingsDiff, TemperatureUnit,
 };
-pub use settings::{BluetoothRange, CalibrationData, DeviceSettings, MeasurementInterval};
 pub use traits::AranetDevice;
 
 /// Type alias for a shared device reference.
@@ -204,31 +224,47 @@ pub use traits::AranetDevice;
 pub type SharedDevice = std::sync::Arc<Device>;
 
 // New module exports
+pub use adapter::{ADAPTER_PROBE_INTERVAL, AdapterMonitor};
 pub use advertisement::{AdvertisementData, parse_advertisement, parse_advertisement_with_name};
+pub use bonding::{BondInfo, BondRecord, BondStore};
+pub use budget::{TimeoutAttempt, TimeoutBudget};
 pub use commands::{
     HISTORY_V1_REQUEST, HISTORY_V2_REQUEST, SET_BLUETOOTH_RANGE, SET_INTERVAL, SET_SMART_HOME,
 };
 pub use diagnostics::{
     AdapterInfo, AdapterState, BluetoothDiagnostics, ConnectionStats, DiagnosticsCollector,
-    ErrorCategory, OperationStats, RecordedError, global_diagnostics,
+    ErrorCategory, OperationStats, OperationType, RecordedError, RecordedOperation,
+    global_diagnostics,
 };
-pub use events::{DeviceEvent, EventReceiver, EventSender};
+pub use events::{DeviceEvent, EventReceiver, EventRecorder, EventSender};
 pub use guard::{DeviceGuard, SharedDeviceGuard};
-pub use manager::{AdaptiveInterval, DeviceManager, DevicePriority, ManagedDevice, ManagerConfig};
-pub use messages::{CachedDevice, Command, SensorEvent};
+pub use manager::{
+    AdaptiveInterval, DEFAULT_MIN_HISTORY_SYNC_BATTERY, DeviceManager, DevicePriority,
+    ManagedDevice, ManagerConfig, history_sync_deferred_by,
+};
+pub use messages::{
+    CachedAlert, CachedDevice, Command, CommandThrottle, ImportFormat, ImportPreview, SensorEvent,
+};
 pub use metrics::{ConnectionMetrics, OperationMetrics};
-pub use mock::{MockDevice, MockDeviceBuilder};
-pub use passive::{PassiveMonitor, PassiveMonitorOptions, PassiveReading};
+pub use mock::{FaultStats, LatencyDistribution, MockDevice, MockDeviceBuilder};
+pub use passive::{PassiveMonitor, PassiveMonitorOptions, PassiveReading, PassiveReadingCallback};
+pub use placement::{PLACEMENT_SAMPLE_INTERVAL, PlacementProbe, ReceptionSample};
 pub use platform::{
-    AliasStore, DeviceAlias, Platform, PlatformConfig, current_platform, platform_config,
+    AliasStore, ConnectStrategy, DeviceAlias, Platform, PlatformConfig, current_platform,
+    platform_config,
 };
+pub use quirks::{FirmwareVersion, Quirk, Quirks};
 pub use reconnect::{ReconnectOptions, ReconnectingDevice};
 pub use retry::{RetryConfig, with_retry};
+pub use sink::{ReadingPipeline, ReadingSink, SinkError, SinkReading, SinkStats};
 pub use streaming::{ReadingStream, StreamOptions, StreamOptionsBuilder};
-pub use thresholds::{Co2Level, ThresholdConfig, Thresholds};
+pub use thresholds::{Co2Level, RadiationLevel, RadiationThresholds, ThresholdConfig, Thresholds};
 pub use util::{create_identifier, format_peripheral_id};
 pub use validation::{ReadingValidator, ValidationResult, ValidationWarning};
 
 // Re-export from aranet-types
 pub use aranet_types::uuid as uuids;
-pub use aranet_types::{CurrentReading, DeviceInfo, DeviceType, HistoryRecord, Status};
+pub use aranet_types::{
+    AdvertisementBuilder, CurrentReading, DeviceInfo, DeviceName, DeviceType, HistoryRecord,
+    ManagedDeviceState, ManagerState, ManagerStateStore, Status,
+};
@@ -2,42 +2,42 @@
 //!
 //! This module provides a manager for handling multiple Aranet devices
 //! simultaneously, with connection pooling and concurrent operations.
+//!
+//! The managed device roster (priorities, aliases, reconnect settings and the
+//! adaptive health check interval) can be saved with
+//! [`DeviceManager::save_state`] and restored after a restart with
+//! [`DeviceManager::restore_state`], using any [`ManagerStateStore`] such as
+//! `aranet_store::Store`.
+//!
+//! The health monitor pauses reconnects while the Bluetooth adapter is off
+//! and resumes once it is back; see [`DeviceManager::adapter`].
 
 use std::collections::HashMap;
-use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use futures::future::join_all;
-use tokio::sync::RwLock;
+use time::OffsetDateTime;
+use tokio::sync::{RwLock, broadcast};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
-use aranet_types::{CurrentReading, DeviceInfo, DeviceType};
+use aranet_types::{
+    CurrentReading, DeviceInfo, DeviceType, ManagedDeviceState, ManagerState, ManagerStateStore,
+};
 
+use crate::adapter::AdapterMonitor;
 use crate::device::Device;
 use crate::error::{Error, Result};
-use crate::events::{DeviceEvent, DeviceId, DisconnectReason, EventDispatcher};
+use crate::events::{DeviceEvent, DeviceId, DisconnectReason, EventDispatcher, EventReceiver};
+use crate::history::history_overwrite_risk;
 use crate::passive::{PassiveMonitor, PassiveMonitorOptions, PassiveReading};
 use crate::reconnect::ReconnectOptions;
 use crate::scan::{DiscoveredDevice, ScanOptions, scan_with_options};
+use crate::settings::DeviceSettings;
 
-/// Device priority levels for connection management.
-///
-/// When the connection limit is reached, lower priority devices
-/// may be disconnected to make room for higher priority devices.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
-pub enum DevicePriority {
-    /// Low priority - may be disconnected when at capacity.
-    Low,
-    /// Normal priority (default).
-    #[default]
-    Normal,
-    /// High priority - maintain connection, disconnect lower priorities if needed.
-    High,
-    /// Critical priority - never disconnect automatically.
-    Critical,
-}
+pub use aranet_types::DevicePriority;
 
 /// Adaptive interval that adjusts based on connection stability.
 ///
@@ -95,6 +95,12 @@ impl AdaptiveInterval {
         self.current
     }
 
+    /// Resume from a previously reached interval, clamped to `min..=max`.
+    pub fn resume_from(mut self, current: Duration) -> Self {
+        self.current = current.clamp(self.min, self.max);
+        self
+    }
+
     /// Record a successful health check.
     ///
     /// After enough consecutive successes, the interval will increase
@@ -152,6 +158,8 @@ pub struct ManagedDevice {
     pub name: Option<String>,
     /// Device type.
     pub device_type: Option<DeviceType>,
+    /// User-assigned alias.
+    pub alias: Option<String>,
     /// The connected device (if connected).
     /// Wrapped in Arc to allow concurrent access without holding the manager lock.
     device: Option<Arc<Device>>,
@@ -172,6 +180,15 @@ pub struct ManagedDevice {
     pub consecutive_failures: u32,
     /// Last successful connection timestamp (Unix epoch millis).
     pub last_success: Option<u64>,
+    /// Per-device override for the minimum battery level required to sync history.
+    ///
+    /// `None` uses [`ManagerConfig::min_history_sync_battery`]; `Some(0)` always syncs.
+    pub history_sync_min_battery: Option<u8>,
+    /// When history was last synced, used to warn before un-synced records
+    /// are overwritten (see [`DeviceEvent::HistoryOverwriteRisk`]).
+    pub last_history_sync: Option<OffsetDateTime>,
+    /// Whether the overwrite risk since the last sync has been reported.
+    overwrite_risk_reported: bool,
 }
 
 impl ManagedDevice {
@@ -181,6 +198,7 @@ impl ManagedDevice {
             id: id.to_string(),
             name: None,
             device_type: None,
+            alias: None,
             device: None,
             connecting: AtomicBool::new(false),
             auto_reconnect: true,
@@ -190,6 +208,9 @@ impl ManagedDevice {
             priority: DevicePriority::default(),
             consecutive_failures: 0,
             last_success: None,
+            history_sync_min_battery: None,
+            last_history_sync: None,
+            overwrite_risk_reported: false,
         }
     }
 
@@ -234,6 +255,29 @@ impl ManagedDevice {
         self.consecutive_failures += 1;
     }
 
+    /// Get the battery level that blocks a history sync, if any.
+    ///
+    /// Returns `Some(threshold)` when the last known battery level is below the
+    /// effective threshold (the per-device override, or `default_threshold`).
+    /// Returns `None` when the sync may proceed, including when no reading
+    /// (and therefore no battery level) is known yet.
+    pub fn history_sync_blocked_by(&self, default_threshold: u8) -> Option<u8> {
+        let threshold = self.history_sync_min_battery.unwrap_or(default_threshold);
+        let battery = self.last_reading.as_ref()?.battery;
+        (battery < threshold).then_some(threshold)
+    }
+
+    /// When un-synced history will be overwritten, if a sync is due at `now`.
+    ///
+    /// Needs the device type, the time of the last sync and a reading with
+    /// the measurement interval; returns `None` while any is unknown. See
+    /// [`history_overwrite_risk`].
+    pub fn history_overwrite_risk(&self, now: OffsetDateTime) -> Option<OffsetDateTime> {
+        let device_type = self.device_type?;
+        let interval = self.last_reading.as_ref()?.interval;
+        history_overwrite_risk(device_type, interval, self.last_history_sync?, now)
+    }
+
     /// Check if the device is connected (sync check, doesn't query BLE).
     pub fn has_device(&self) -> bool {
         self.device.is_some()
@@ -294,6 +338,44 @@ pub struct ManagerConfig {
     /// which performs an actual BLE read to verify the connection is alive.
     /// This catches "zombie connections" but uses more power.
     pub use_connection_validation: bool,
+    /// Minimum battery level (percent) required to run a full history sync.
+    ///
+    /// Large history transfers measurably drain low batteries, so
+    /// [`DeviceManager::should_defer_history_sync`] holds back devices below
+    /// this level. Set to 0 to always sync. Can be overridden per device.
+    pub min_history_sync_battery: u8,
+}
+
+/// Default for [`ManagerConfig::min_history_sync_battery`].
+pub const DEFAULT_MIN_HISTORY_SYNC_BATTERY: u8 = 15;
+
+/// Decide whether a device's history sync must wait for a better battery.
+///
+/// Returns the threshold `battery` is below, or `None` if the sync may run.
+/// A sync is never deferred while un-synced records are about to be
+/// overwritten (`overwrite_at`, see [`history_overwrite_risk`]). The decision
+/// is logged for `identifier`.
+pub fn history_sync_deferred_by(
+    identifier: &str,
+    battery: u8,
+    threshold: u8,
+    overwrite_at: Option<OffsetDateTime>,
+) -> Option<u8> {
+    if battery >= threshold {
+        return None;
+    }
+    if overwrite_at.is_some() {
+        warn!(
+            "Syncing history for {} despite battery {}% below {}%: un-synced records are about to be overwritten",
+            identifier, battery, threshold
+        );
+        return None;
+    }
+    info!(
+        "Deferring history sync for {}: battery {}% below {}%",
+        identifier, battery, threshold
+    );
+    Some(threshold)
 }
 
 impl Default for ManagerConfig {
@@ -312,6 +394,7 @@ impl Default for ManagerConfig {
             max_health_check_interval: Duration::from_secs(120),
             default_priority: DevicePriority::Normal,
             use_connection_validation: true,
+            min_history_sync_battery: DEFAULT_MIN_HISTORY_SYNC_BATTERY,
         }
     }
 }
@@ -352,6 +435,12 @@ impl ManagerConfig {
         self.use_connection_validation = enabled;
         self
     }
+
+    /// Set the minimum battery level required for history syncs (0 disables the check).
+    pub fn min_history_sync_battery(mut self, level: u8) -> Self {
+        self.min_history_sync_battery = level.min(100);
+        self
+    }
 }
 
 /// Manager for multiple Aranet devices.
@@ -360,8 +449,13 @@ pub struct DeviceManager {
     devices: RwLock<HashMap<String, ManagedDevice>>,
     /// Event dispatcher.
     events: EventDispatcher,
+    /// Bluetooth adapter power state, shared with reconnect loops.
+    adapter: Arc<AdapterMonitor>,
     /// Manager configuration.
     config: ManagerConfig,
+    /// Current adaptive health check interval, shared with the health monitor
+    /// so it can be saved and restored.
+    health_interval: Mutex<Option<Duration>>,
 }
 
 impl DeviceManager {
@@ -380,10 +474,13 @@ impl DeviceManager {
 
     /// Create a manager with full configuration.
     pub fn with_config(config: ManagerConfig) -> Self {
+        let events = EventDispatcher::new(config.event_capacity);
         Self {
             devices: RwLock::new(HashMap::new()),
-            events: EventDispatcher::new(config.event_capacity),
+            adapter: Arc::new(AdapterMonitor::with_events(events.sender())),
+            events,
             config,
+            health_interval: Mutex::new(None),
         }
     }
 
@@ -392,6 +489,14 @@ impl DeviceManager {
         &self.events
     }
 
+    /// Get the Bluetooth adapter monitor.
+    ///
+    /// The manager emits [`DeviceEvent::AdapterStateChanged`] through its
+    /// event dispatcher when the adapter is switched off or back on.
+    pub fn adapter(&self) -> &Arc<AdapterMonitor> {
+        &self.adapter
+    }
+
     /// Get the manager configuration.
     pub fn config(&self) -> &ManagerConfig {
         &self.config
@@ -537,13 +642,17 @@ impl DeviceManager {
 
         // Handle connection result
         let device = match connect_result {
-            Ok(d) => Arc::new(d),
+            Ok(d) => {
+                self.adapter.observe_success();
+                Arc::new(d)
+            }
             Err(e) => {
                 // Clear the connecting flag on failure
                 let devices = self.devices.read().await;
                 if let Some(managed) = devices.get(identifier) {
                     managed.connecting.store(false, Ordering::SeqCst);
                 }
+                self.adapter.observe_error(&e);
                 return Err(e);
             }
         };
@@ -552,6 +661,24 @@ impl DeviceManager {
         let device_type = device.device_type();
         let name = device.name().map(|s| s.to_string());
 
+        // Keep cached settings in step with changes made on the device itself
+        let events = self.events.clone();
+        let changed = DeviceId {
+            id: identifier.to_string(),
+            name: name.clone(),
+            device_type,
+        };
+        if let Err(e) = device
+            .subscribe_to_settings_changes(move || {
+                events.send(DeviceEvent::SettingsChanged {
+                    device: changed.clone(),
+                });
+            })
+            .await
+        {
+            debug!("Could not watch settings on {}: {}", identifier, e);
+        }
+
         // Update the managed device atomically
         {
             let mut devices = self.devices.write().await;
@@ -731,13 +858,218 @@ impl DeviceManager {
         {
             let mut devices = self.devices.write().await;
             if let Some(managed) = devices.get_mut(identifier) {
-                managed.last_reading = Some(reading);
+                self.cache_reading(identifier, managed, reading);
             }
         }
 
         Ok(reading)
     }
 
+    /// Read the settings of a connected device.
+    ///
+    /// Settings are cached on the device until a command is written or the
+    /// device reports a change ([`DeviceEvent::SettingsChanged`]).
+    pub async fn get_settings(&self, identifier: &str) -> Result<DeviceSettings> {
+        let device = {
+            let devices = self.devices.read().await;
+            let managed = devices
+                .get(identifier)
+                .ok_or_else(|| Error::device_not_found(identifier))?;
+            managed.device_arc().ok_or(Error::NotConnected)?
+        };
+        device.get_settings_cached().await
+    }
+
+    /// Override the low-battery history sync threshold for a single device.
+    ///
+    /// Pass `None` to fall back to [`ManagerConfig::min_history_sync_battery`],
+    /// or `Some(0)` to always sync this device regardless of battery level.
+    pub async fn set_history_sync_min_battery(
+        &self,
+        identifier: &str,
+        threshold: Option<u8>,
+    ) -> Result<()> {
+        let mut devices = self.devices.write().await;
+        let managed = devices
+            .get_mut(identifier)
+            .ok_or_else(|| Error::device_not_found(identifier))?;
+        managed.history_sync_min_battery = threshold.map(|t| t.min(100));
+        Ok(())
+    }
+
+    /// Set or clear the user-assigned alias of a device.
+    pub async fn set_alias(&self, identifier: &str, alias: Option<&str>) -> Result<()> {
+        let mut devices = self.devices.write().await;
+        let managed = devices
+            .get_mut(identifier)
+            .ok_or_else(|| Error::device_not_found(identifier))?;
+        managed.alias = alias.map(str::to_string);
+        Ok(())
+    }
+
+    /// Save the managed device roster and adaptive health check interval.
+    ///
+    /// Replaces any previously saved state and returns the number of devices
+    /// saved. Connections and readings are not saved.
+    pub async fn save_state<S: ManagerStateStore>(
+        &self,
+        store: &S,
+    ) -> std::result::Result<usize, S::Error> {
+        let state = self.state().await;
+        store.save_manager_state(&state)?;
+        debug!("Saved manager state with {} devices", state.devices.len());
+        Ok(state.devices.len())
+    }
+
+    /// Restore a roster saved with [`save_state`](Self::save_state).
+    ///
+    /// Devices not yet managed are added (disconnected); devices already
+    /// managed have their priority, alias and reconnect settings replaced.
+    /// Devices are not connected: call [`connect_all`](Self::connect_all) or
+    /// let the health monitor reconnect them. Returns the number of devices
+    /// restored, or 0 if no state was saved.
+    pub async fn restore_state<S: ManagerStateStore>(
+        &self,
+        store: &S,
+    ) -> std::result::Result<usize, S::Error> {
+        let Some(state) = store.load_manager_state()? else {
+            return Ok(0);
+        };
+
+        let mut devices = self.devices.write().await;
+        for saved in &state.devices {
+            let managed = devices.entry(saved.id.clone()).or_insert_with(|| {
+                ManagedDevice::with_reconnect_options(
+                    &saved.id,
+                    self.config.default_reconnect_options.clone(),
+                )
+            });
+            if saved.name.is_some() {
+                managed.name = saved.name.clone();
+            }
+            if saved.device_type.is_some() {
+                managed.device_type = saved.device_type;
+            }
+            managed.alias = saved.alias.clone();
+            managed.priority = saved.priority;
+            managed.auto_reconnect = saved.auto_reconnect;
+            managed.history_sync_min_battery = saved.history_sync_min_battery;
+        }
+        drop(devices);
+
+        if let Some(ms) = state.health_check_interval_ms {
+            *self.lock_health_interval() = Some(Duration::from_millis(ms));
+        }
+
+        info!(
+            "Restored {} devices from saved manager state",
+            state.devices.len()
+        );
+        Ok(state.devices.len())
+    }
+
+    /// Snapshot of the state saved by [`save_state`](Self::save_state).
+    pub async fn state(&self) -> ManagerState {
+        let devices = self.devices.read().await;
+        let mut saved: Vec<ManagedDeviceState> = devices
+            .values()
+            .map(|m| ManagedDeviceState {
+                id: m.id.clone(),
+                name: m.name.clone(),
+                alias: m.alias.clone(),
+                device_type: m.device_type,
+                priority: m.priority,
+                auto_reconnect: m.auto_reconnect,
+                history_sync_min_battery: m.history_sync_min_battery,
+            })
+            .collect();
+        saved.sort_by(|a, b| a.id.cmp(&b.id));
+
+        ManagerState {
+            devices: saved,
+            health_check_interval_ms: self
+                .lock_health_interval()
+                .map(|interval| interval.as_millis() as u64),
+        }
+    }
+
+    /// The health monitor's current adaptive interval, if it has one.
+    pub fn health_check_interval(&self) -> Option<Duration> {
+        *self.lock_health_interval()
+    }
+
+    fn lock_health_interval(&self) -> std::sync::MutexGuard<'_, Option<Duration>> {
+        self.health_interval
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Check whether a history sync for a device should be deferred due to low battery.
+    ///
+    /// Uses the last cached reading; returns `false` if no reading is known.
+    pub async fn should_defer_history_sync(&self, identifier: &str) -> bool {
+        let devices = self.devices.read().await;
+        devices
+            .get(identifier)
+            .and_then(|m| m.history_sync_blocked_by(self.config.min_history_sync_battery))
+            .is_some()
+    }
+
+    /// Record when a device's history was last synced.
+    ///
+    /// Call it after each history sync, or on startup with a time persisted
+    /// from an earlier run, so that [`DeviceEvent::HistoryOverwriteRisk`] can
+    /// be raised in time.
+    pub async fn set_last_history_sync(&self, identifier: &str, at: OffsetDateTime) -> Result<()> {
+        let mut devices = self.devices.write().await;
+        let managed = devices
+            .get_mut(identifier)
+            .ok_or_else(|| Error::device_not_found(identifier))?;
+        managed.last_history_sync = Some(at);
+        managed.overwrite_risk_reported = false;
+        Ok(())
+    }
+
+    /// Devices whose un-synced history is at risk of being overwritten.
+    ///
+    /// Sorted by the time the first record will be lost, so syncing them in
+    /// order saves the most data.
+    pub async fn devices_at_overwrite_risk(&self) -> Vec<(String, OffsetDateTime)> {
+        let now = OffsetDateTime::now_utc();
+        let devices = self.devices.read().await;
+        let mut at_risk: Vec<_> = devices
+            .iter()
+            .filter_map(|(id, m)| Some((id.clone(), m.history_overwrite_risk(now)?)))
+            .collect();
+        at_risk.sort_by_key(|(_, overwrite_at)| *overwrite_at);
+        at_risk
+    }
+
+    /// Cache a reading, emitting [`DeviceEvent::HistoryOverwriteRisk`] the
+    /// first time un-synced history is found at risk since the last sync.
+    fn cache_reading(
+        &self,
+        identifier: &str,
+        managed: &mut ManagedDevice,
+        reading: CurrentReading,
+    ) {
+        managed.last_reading = Some(reading);
+        if managed.overwrite_risk_reported {
+            return;
+        }
+        if let Some(overwrite_at) = managed.history_overwrite_risk(OffsetDateTime::now_utc()) {
+            warn!(
+                "Un-synced history on {} will be overwritten from {}",
+                identifier, overwrite_at
+            );
+            managed.overwrite_risk_reported = true;
+            self.events.send(DeviceEvent::HistoryOverwriteRisk {
+                device: DeviceId::new(identifier),
+                overwrite_at,
+            });
+        }
+    }
+
     /// Read current values from all connected devices (in parallel).
     ///
     /// This method releases the lock before performing async BLE operations,
@@ -783,7 +1115,7 @@ impl DeviceManager {
                 if let Ok(reading) = result
                     && let Some(managed) = devices.get_mut(id)
                 {
-                    managed.last_reading = Some(*reading);
+                    self.cache_reading(id, managed, *reading);
                 }
             }
         }
@@ -918,6 +1250,13 @@ impl DeviceManager {
     /// an actual BLE read (`device.validate_connection()`) to catch "zombie connections"
     /// where the BLE stack thinks it's connected but the device is out of range.
     ///
+    /// # Adapter Power Cycles
+    ///
+    /// The monitor also starts the [`AdapterMonitor`]. While the adapter is
+    /// off, health checks and reconnects are paused; they resume as soon as it
+    /// is powered back on, without counting the outage against the adaptive
+    /// interval.
+    ///
     /// # Example
     ///
     /// ```ignore
@@ -938,18 +1277,39 @@ impl DeviceManager {
         let manager = Arc::clone(self);
 
         tokio::spawn(async move {
-            // Initialize adaptive interval if enabled
+            // Initialize adaptive interval if enabled, resuming a restored interval
             let mut adaptive = if manager.config.use_adaptive_interval {
-                Some(AdaptiveInterval::new(
+                let adaptive = AdaptiveInterval::new(
                     manager.config.health_check_interval,
                     manager.config.min_health_check_interval,
                     manager.config.max_health_check_interval,
-                ))
+                );
+                let adaptive = match manager.health_check_interval() {
+                    Some(interval) => adaptive.resume_from(interval),
+                    None => adaptive,
+                };
+                *manager.lock_health_interval() = Some(adaptive.current());
+                Some(adaptive)
             } else {
                 None
             };
 
+            let _adapter_handle = manager.adapter.start(cancel_token.child_token());
+
             loop {
+                if !manager.adapter.is_available() {
+                    info!("Bluetooth adapter unavailable, pausing health checks");
+                    tokio::select! {
+                        _ = cancel_token.cancelled() => {
+                            info!("Health monitor cancelled, shutting down");
+                            break;
+                        }
+                        _ = manager.adapter.wait_until_available() => {
+                            info!("Bluetooth adapter available, resuming health checks");
+                        }
+                    }
+                }
+
                 // Get current interval
                 let current_interval = adaptive
                     .as_ref()
@@ -964,6 +1324,7 @@ impl DeviceManager {
                     _ = tokio::time::sleep(current_interval) => {
                         let mut any_failures = false;
                         let mut any_successes = false;
+                        let mut adapter_lost = false;
 
                         // Get devices that need checking
                         let devices_to_check: Vec<(String, Option<Arc<Device>>, bool, DevicePriority)> = {
@@ -983,9 +1344,14 @@ impl DeviceManager {
 
                         // Sort by priority (higher priority checked first)
                         let mut sorted_devices = devices_to_check;
-                        sorted_devices.sort_by(|a, b| b.3.cmp(&a.3));
+                        sorted_devices.sort_by_key(|d| std::cmp::Reverse(d.3));
 
                         for (id, device_opt, auto_reconnect, _priority) in sorted_devices {
+                            if !manager.adapter.is_available() {
+                                // Remaining devices wait for the adapter to return
+                                break;
+                            }
+
                             let should_reconnect = match device_opt {
                                 Some(device) => {
                                     // Use connection validation if enabled
@@ -1010,6 +1376,10 @@ impl DeviceManager {
                                             m.record_success();
                                         }
                                     }
+                                    Err(e) if e.is_adapter_unavailable() => {
+                                        debug!("Health monitor: adapter off while reconnecting {}", id);
+                                        adapter_lost = true;
+                                    }
                                     Err(e) => {
                                         warn!("Health monitor: reconnect failed for {}: {}", id, e);
                                         // Update failure in managed device
@@ -1023,14 +1393,18 @@ impl DeviceManager {
                             }
                         }
 
-                        // Update adaptive interval
-                        if let Some(ref mut adaptive) = adaptive {
+                        // Update adaptive interval (an adapter outage says nothing
+                        // about connection stability)
+                        if let Some(ref mut adaptive) = adaptive
+                            && !adapter_lost
+                        {
                             if any_failures && !any_successes {
                                 adaptive.on_failure();
                             } else if any_successes && !any_failures {
                                 adaptive.on_success();
                             }
                             // Mixed results: don't change interval
+                            *manager.lock_health_interval() = Some(adaptive.current());
                         }
                     }
                 }
@@ -1102,6 +1476,10 @@ impl DeviceManager {
     ///
     /// Smart Home integration must be enabled on each device for passive monitoring.
     ///
+    /// Battery and status alerts raised by the passive monitor
+    /// ([`DeviceEvent::BatteryLow`], [`DeviceEvent::StatusRed`]) are forwarded
+    /// to the manager's events.
+    ///
     /// # Example
     ///
     /// ```ignore
@@ -1132,52 +1510,79 @@ impl DeviceManager {
 
             // Create passive monitor
             let passive_monitor = Arc::new(PassiveMonitor::new(options));
-            let mut passive_rx = passive_monitor.subscribe();
+            let passive_rx = passive_monitor.subscribe();
+            let passive_events = passive_monitor.subscribe_events();
 
             // Start passive monitoring
             let passive_cancel = cancel_token.clone();
             let _passive_handle = passive_monitor.start(passive_cancel);
 
-            loop {
-                tokio::select! {
-                    _ = cancel_token.cancelled() => {
-                        info!("Hybrid monitor cancelled");
-                        break;
-                    }
-                    result = passive_rx.recv() => {
-                        match result {
-                            Ok(passive_reading) => {
-                                // Convert passive reading to CurrentReading and emit event
-                                if let Some(reading) = passive_reading_to_current(&passive_reading) {
-                                    // Update last reading in managed device if it exists
-                                    if let Some(m) = manager.devices.write().await.get_mut(&passive_reading.device_id) {
-                                        m.last_reading = Some(reading);
-                                        m.record_success();
-                                    }
+            manager
+                .forward_passive(passive_rx, passive_events, cancel_token)
+                .await;
+        })
+    }
 
-                                    // Emit reading event
-                                    manager.events.send(DeviceEvent::Reading {
-                                        device: DeviceId {
-                                            id: passive_reading.device_id.clone(),
-                                            name: passive_reading.device_name.clone(),
-                                            device_type: Some(passive_reading.data.device_type),
-                                        },
-                                        reading,
-                                    });
+    /// Emit passive readings and alerts as manager events until cancelled
+    /// or the passive monitor stops.
+    async fn forward_passive(
+        &self,
+        mut passive_rx: broadcast::Receiver<PassiveReading>,
+        mut passive_events: EventReceiver,
+        cancel_token: CancellationToken,
+    ) {
+        loop {
+            tokio::select! {
+                _ = cancel_token.cancelled() => {
+                    info!("Hybrid monitor cancelled");
+                    break;
+                }
+                result = passive_rx.recv() => {
+                    match result {
+                        Ok(passive_reading) => {
+                            // Convert passive reading to CurrentReading and emit event
+                            if let Some(reading) = passive_reading_to_current(&passive_reading) {
+                                // Update last reading in managed device if it exists
+                                if let Some(m) = self.devices.write().await.get_mut(&passive_reading.device_id) {
+                                    self.cache_reading(&passive_reading.device_id, m, reading);
+                                    m.record_success();
                                 }
+
+                                // Emit reading event
+                                self.events.send(DeviceEvent::Reading {
+                                    device: DeviceId {
+                                        id: passive_reading.device_id.clone(),
+                                        name: passive_reading.device_name.clone(),
+                                        device_type: Some(passive_reading.data.device_type),
+                                    },
+                                    reading,
+                                });
                             }
-                            Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
-                                warn!("Hybrid monitor lagged {} messages", n);
-                            }
-                            Err(tokio::sync::broadcast::error::RecvError::Closed) => {
-                                info!("Passive monitor channel closed");
-                                break;
-                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            warn!("Hybrid monitor lagged {} messages", n);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            info!("Passive monitor channel closed");
+                            break;
+                        }
+                    }
+                }
+                result = passive_events.recv() => {
+                    match result {
+                        // Battery and status alerts from advertisements
+                        Ok(event) => self.events.send(event),
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            warn!("Hybrid monitor lagged {} passive alerts", n);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            info!("Passive monitor alert channel closed");
+                            break;
                         }
                     }
                 }
             }
-        })
+        }
     }
 
     /// Get a reading using hybrid approach: try passive first, fall back to active.
@@ -1322,6 +1727,21 @@ mod tests {
         assert_eq!(manager.connected_count().await, 0);
     }
 
+    #[tokio::test]
+    async fn test_manager_get_settings_requires_connection() {
+        let manager = DeviceManager::new();
+        assert!(matches!(
+            manager.get_settings("missing").await,
+            Err(Error::DeviceNotFound(_))
+        ));
+
+        manager.add_device("test-device").await.unwrap();
+        assert!(matches!(
+            manager.get_settings("test-device").await,
+            Err(Error::NotConnected)
+        ));
+    }
+
     #[tokio::test]
     async fn test_manager_events() {
         let manager = DeviceManager::new();
@@ -1332,4 +1752,252 @@ mod tests {
         // Events are only emitted for actual device operations
         assert_eq!(manager.events().receiver_count(), 1);
     }
+
+    fn reading_with_battery(battery: u8) -> CurrentReading {
+        CurrentReading {
+            battery,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_history_sync_blocked_by_low_battery() {
+        let mut managed = ManagedDevice::new("test-device");
+        // Unknown battery never blocks
+        assert_eq!(managed.history_sync_blocked_by(15), None);
+
+        managed.last_reading = Some(reading_with_battery(10));
+        assert_eq!(managed.history_sync_blocked_by(15), Some(15));
+        assert_eq!(managed.history_sync_blocked_by(0), None);
+
+        managed.last_reading = Some(reading_with_battery(15));
+        assert_eq!(managed.history_sync_blocked_by(15), None);
+    }
+
+    #[test]
+    fn test_history_sync_per_device_override() {
+        let mut managed = ManagedDevice::new("test-device");
+        managed.last_reading = Some(reading_with_battery(10));
+
+        managed.history_sync_min_battery = Some(0);
+        assert_eq!(managed.history_sync_blocked_by(15), None);
+
+        managed.history_sync_min_battery = Some(25);
+        managed.last_reading = Some(reading_with_battery(20));
+        assert_eq!(managed.history_sync_blocked_by(15), Some(25));
+    }
+
+    #[test]
+    fn test_history_sync_deferred_unless_overwrite_at_risk() {
+        assert_eq!(history_sync_deferred_by("dev", 10, 15, None), Some(15));
+        assert_eq!(history_sync_deferred_by("dev", 15, 15, None), None);
+        assert_eq!(history_sync_deferred_by("dev", 10, 0, None), None);
+
+        let overwrite_at = OffsetDateTime::now_utc();
+        assert_eq!(
+            history_sync_deferred_by("dev", 10, 15, Some(overwrite_at)),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_history_overwrite_risk_event() {
+        let manager = DeviceManager::new();
+        let mut rx = manager.events().subscribe();
+        manager.add_device("test-device").await.unwrap();
+
+        let reading = CurrentReading {
+            interval: 300,
+            ..reading_with_battery(80)
+        };
+        let mut devices = manager.devices.write().await;
+        let managed = devices.get_mut("test-device").unwrap();
+        managed.device_type = Some(DeviceType::Aranet4);
+
+        // Never synced: nothing to lose yet
+        manager.cache_reading("test-device", managed, reading);
+        assert!(rx.try_recv().is_err());
+
+        // Synced 6 days ago, 7 days of capacity: reported once
+        managed.last_history_sync = Some(OffsetDateTime::now_utc() - time::Duration::days(6));
+        manager.cache_reading("test-device", managed, reading);
+        manager.cache_reading("test-device", managed, reading);
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(DeviceEvent::HistoryOverwriteRisk { .. })
+        ));
+        assert!(rx.try_recv().is_err());
+        drop(devices);
+
+        assert_eq!(manager.devices_at_overwrite_risk().await.len(), 1);
+        manager
+            .set_last_history_sync("test-device", OffsetDateTime::now_utc())
+            .await
+            .unwrap();
+        assert!(manager.devices_at_overwrite_risk().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_monitor_forwards_passive_readings() {
+        use crate::advertisement::AdvertisementData;
+        use crate::events::EventRecorder;
+        use aranet_types::Status;
+
+        let manager = DeviceManager::new();
+        let mut recorder = EventRecorder::new(&manager.events().sender());
+        manager.add_device("kitchen").await.unwrap();
+
+        let (reading_tx, reading_rx) = broadcast::channel(8);
+        let (_alert_tx, alert_rx) = broadcast::channel(8);
+        let cancel = CancellationToken::new();
+
+        let data = AdvertisementData {
+            device_type: DeviceType::Aranet4,
+            co2: Some(1450),
+            temperature: Some(22.5),
+            pressure: Some(1013.2),
+            humidity: Some(45),
+            battery: 8,
+            status: Status::Red,
+            interval: 300,
+            age: 12,
+            radon: None,
+            radiation_dose_rate: None,
+            counter: Some(1),
+            flags: 0x22,
+        };
+        reading_tx
+            .send(PassiveReading::new("kitchen".to_string(), None, None, data))
+            .unwrap();
+        // Closing the passive reading channel ends forwarding
+        drop(reading_tx);
+
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            manager.forward_passive(reading_rx, alert_rx, cancel),
+        )
+        .await
+        .expect("forwarding should stop when the passive monitor does");
+
+        recorder.expect_sequence(["reading"]);
+        match &recorder.events()[0] {
+            DeviceEvent::Reading { device, reading } => {
+                assert_eq!(device.id, "kitchen");
+                assert_eq!(device.device_type, Some(DeviceType::Aranet4));
+                assert_eq!(reading.co2, 1450);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert_eq!(
+            manager.get_last_reading("kitchen").await.map(|r| r.co2),
+            Some(1450)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_monitor_forwards_passive_alerts_until_cancelled() {
+        use crate::events::EventRecorder;
+
+        let manager = DeviceManager::new();
+        let mut recorder = EventRecorder::new(&manager.events().sender());
+        let (_reading_tx, reading_rx) = broadcast::channel::<PassiveReading>(8);
+        let (alert_tx, alert_rx) = broadcast::channel(8);
+        let cancel = CancellationToken::new();
+
+        alert_tx
+            .send(DeviceEvent::SettingsChanged {
+                device: DeviceId::new("kitchen"),
+            })
+            .unwrap();
+        let forward = manager.forward_passive(reading_rx, alert_rx, cancel.clone());
+        tokio::pin!(forward);
+        let _ = tokio::time::timeout(Duration::from_millis(50), &mut forward).await;
+        cancel.cancel();
+        tokio::time::timeout(Duration::from_secs(5), forward)
+            .await
+            .expect("forwarding should stop when cancelled");
+
+        recorder.expect_sequence(["settings_changed"]);
+    }
+
+    #[tokio::test]
+    async fn test_manager_set_history_sync_min_battery() {
+        let manager =
+            DeviceManager::with_config(ManagerConfig::default().min_history_sync_battery(20));
+        assert_eq!(manager.config().min_history_sync_battery, 20);
+
+        manager.add_device("test-device").await.unwrap();
+        assert!(!manager.should_defer_history_sync("test-device").await);
+
+        manager
+            .set_history_sync_min_battery("test-device", Some(0))
+            .await
+            .unwrap();
+        assert!(
+            manager
+                .set_history_sync_min_battery("missing", Some(0))
+                .await
+                .is_err()
+        );
+    }
+
+    /// In-memory [`ManagerStateStore`] for tests.
+    #[derive(Default)]
+    struct MemoryStateStore(std::sync::Mutex<Option<ManagerState>>);
+
+    impl ManagerStateStore for MemoryStateStore {
+        type Error = std::convert::Infallible;
+
+        fn save_manager_state(&self, state: &ManagerState) -> std::result::Result<(), Self::Error> {
+            *self.0.lock().unwrap() = Some(state.clone());
+            Ok(())
+        }
+
+        fn load_manager_state(&self) -> std::result::Result<Option<ManagerState>, Self::Error> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_manager_save_and_restore_state() {
+        let store = MemoryStateStore::default();
+        let manager = DeviceManager::new();
+        assert_eq!(manager.restore_state(&store).await.unwrap(), 0);
+
+        manager
+            .add_device_with_priority("kitchen", DevicePriority::Critical)
+            .await
+            .unwrap();
+        manager.add_device("office").await.unwrap();
+        manager.set_alias("kitchen", Some("Kitchen")).await.unwrap();
+        manager
+            .set_history_sync_min_battery("office", Some(0))
+            .await
+            .unwrap();
+        *manager.lock_health_interval() = Some(Duration::from_secs(60));
+        assert_eq!(manager.save_state(&store).await.unwrap(), 2);
+
+        let restored = DeviceManager::new();
+        assert_eq!(restored.restore_state(&store).await.unwrap(), 2);
+        assert_eq!(restored.device_count().await, 2);
+        assert_eq!(restored.connected_count().await, 0);
+        assert_eq!(restored.state().await, manager.state().await);
+        assert_eq!(
+            restored.health_check_interval(),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            restored.lowest_priority_connected().await,
+            None,
+            "restored devices are not connected"
+        );
+    }
+
+    #[test]
+    fn test_adaptive_interval_resume_is_clamped() {
+        let interval = AdaptiveInterval::default().resume_from(Duration::from_secs(600));
+        assert_eq!(interval.current(), Duration::from_secs(120));
+        let interval = AdaptiveInterval::default().resume_from(Duration::from_secs(1));
+        assert_eq!(interval.current(), Duration::from_secs(5));
+    }
 }
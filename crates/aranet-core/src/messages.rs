@@ -16,10 +16,15 @@
 //!
 //! - [`Command`]: Messages sent from the UI thread to the background worker
 //! - [`SensorEvent`]: Events sent from the worker back to the UI thread
+//! - [`CommandThrottle`]: Drops duplicate refreshes and connect storms before
+//!   they reach the BLE stack
 
-use std::time::Duration;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use crate::DiscoveredDevice;
+use crate::history::HistoryInfo;
 use crate::settings::DeviceSettings;
 use aranet_types::{CurrentReading, DeviceType, HistoryRecord};
 
@@ -65,6 +70,10 @@ impl ErrorContext {
                     operation
                 ),
             ),
+            crate::Error::TimeoutExceeded { .. } => Self::transient(
+                error.to_string(),
+                "The overall timeout budget ran out. Increase the total timeout or move closer to the device.",
+            ),
             crate::Error::ConnectionFailed { reason, .. } => match reason {
                 ConnectionFailureReason::OutOfRange => Self::transient(
                     error.to_string(),
@@ -118,6 +127,14 @@ impl ErrorContext {
                 error.to_string(),
                 "Bluetooth error. Try moving closer to the device or restarting Bluetooth.",
             ),
+            crate::Error::BondLost { .. } => Self {
+                message: error.to_string(),
+                retryable: false,
+                suggestion: Some(
+                    "The device no longer recognizes this computer. Remove and re-pair it."
+                        .to_string(),
+                ),
+            },
             crate::Error::DeviceNotFound(_) => Self::permanent(error.to_string()),
             crate::Error::CharacteristicNotFound { .. } => Self {
                 message: error.to_string(),
@@ -130,6 +147,11 @@ impl ErrorContext {
             | crate::Error::InvalidHistoryData { .. }
             | crate::Error::InvalidReadingFormat { .. } => Self::permanent(error.to_string()),
             crate::Error::Cancelled => Self::permanent("Operation was cancelled.".to_string()),
+            crate::Error::StreamGap { .. } => Self::permanent(error.to_string()),
+            crate::Error::Busy { .. } => Self::transient(
+                error.to_string(),
+                "The device is busy with other operations. Try again shortly.",
+            ),
             crate::Error::WriteFailed { .. } => {
                 Self::transient(error.to_string(), "Failed to write to device. Try again.")
             }
@@ -139,6 +161,11 @@ impl ErrorContext {
             crate::Error::InvalidConfig(_) | crate::Error::Unsupported(_) => {
                 Self::permanent(error.to_string())
             }
+            // Keep the classification of the wrapped error, but show the trail
+            crate::Error::Context(ctx) => Self {
+                message: format!("{:#}", error),
+                ..Self::from_error(ctx.error())
+            },
         }
     }
 }
@@ -249,6 +276,68 @@ pub enum Command {
         device_id: String,
     },
 
+    /// Start passive monitoring: update devices from BLE advertisements
+    /// without connecting (requires Smart Home integration on the device).
+    StartPassiveMonitoring,
+
+    /// Stop passive monitoring.
+    StopPassiveMonitoring,
+
+    /// Start the placement assistant: report a device's advertisement RSSI
+    /// and reception count every second while it is moved around.
+    StartPlacementProbe {
+        /// The device identifier.
+        device_id: String,
+    },
+
+    /// Stop the placement assistant.
+    StopPlacementProbe,
+
+    /// Load the most recent persisted alerts from the store.
+    LoadAlerts {
+        /// Maximum number of alerts to load.
+        limit: u32,
+    },
+
+    /// Persist an alert raised by the UI.
+    RecordAlert {
+        /// The device identifier.
+        device_id: String,
+        /// Alert kind (e.g. `co2`, `offline`), as used by the store.
+        kind: String,
+        /// Severity (`info`, `warning` or `critical`).
+        severity: String,
+        /// The measurement that triggered the alert, if any.
+        value: Option<f64>,
+        /// Human-readable description.
+        message: String,
+    },
+
+    /// Mark persisted alerts as acknowledged.
+    AcknowledgeAlerts {
+        /// Store IDs of the alerts to acknowledge.
+        ids: Vec<i64>,
+    },
+
+    /// Delete persisted alerts.
+    ClearAlerts {
+        /// Store IDs of the alerts to delete.
+        ids: Vec<i64>,
+    },
+
+    /// Read an exported CSV or device bundle and report what importing it
+    /// would add, without touching the store.
+    PreviewImport {
+        /// The file to read.
+        path: PathBuf,
+    },
+
+    /// Import an exported CSV or device bundle into the store.
+    ImportFile {
+        /// The file to import.
+        path: PathBuf,
+    },
+
     /// Shut down the worker thread.
     Shutdown,
 
@@ -312,6 +401,184 @@ pub enum Command {
     },
 }
 
+/// Default window in which repeated refreshes of the same target are dropped.
+pub const DEFAULT_REFRESH_COALESCE_WINDOW: Duration = Duration::from_secs(2);
+
+/// Default minimum gap between connect attempts to the same device.
+pub const DEFAULT_CONNECT_MIN_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Key identifying commands that are subject to throttling.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ThrottleKey {
+    Refresh(String),
+    RefreshAll,
+    Connect(String),
+}
+
+impl ThrottleKey {
+    fn for_command(cmd: &Command) -> Option<Self> {
+        match cmd {
+            Command::RefreshReading { device_id } => Some(Self::Refresh(device_id.clone())),
+            Command::RefreshAll => Some(Self::RefreshAll),
+            Command::Connect { device_id } => Some(Self::Connect(device_id.clone())),
+            _ => None,
+        }
+    }
+
+    fn window(&self, throttle: &CommandThrottle) -> Duration {
+        match self {
+            Self::Refresh(_) | Self::RefreshAll => throttle.refresh_window,
+            Self::Connect(_) => throttle.connect_interval,
+        }
+    }
+}
+
+/// Coalesces rapid, duplicate commands in a background worker.
+///
+/// Workers process commands one at a time, so button mashing queues up
+/// several identical BLE operations that would otherwise run back to back.
+/// The throttle holds back a [`Command::RefreshReading`] or
+/// [`Command::RefreshAll`] that arrives within the refresh window of the
+/// previous one, and a [`Command::Connect`] that arrives within the connect
+/// interval of the previous attempt for the same device. Windows are measured
+/// from when the previous command *finished*, so duplicates queued while it
+/// was running are collapsed too. Held-back duplicates collapse into one
+/// trailing command that [`take_due`](Self::take_due) returns once the window
+/// ends, so the last request is never lost. A [`Command::Disconnect`] resets
+/// the device's state, including any trailing command, so an explicit
+/// reconnect is never delayed. All other commands pass through.
+///
+/// # Example
+///
+/// ```
+/// use aranet_core::messages::{Command, CommandThrottle};
+///
+/// let mut throttle = CommandThrottle::default();
+/// let refresh = Command::RefreshReading { device_id: "AA:BB".into() };
+///
+/// assert!(throttle.admit(&refresh));
+/// throttle.finish(&refresh);
+/// assert!(!throttle.admit(&refresh));
+/// // The duplicate runs once the window ends
+/// assert!(throttle.next_due().is_some());
+/// ```
+#[derive(Debug, Clone)]
+pub struct CommandThrottle {
+    refresh_window: Duration,
+    connect_interval: Duration,
+    last_run: HashMap<ThrottleKey, Instant>,
+    /// Commands held back within their window, run once it ends.
+    trailing: HashMap<ThrottleKey, Command>,
+}
+
+impl Default for CommandThrottle {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_REFRESH_COALESCE_WINDOW,
+            DEFAULT_CONNECT_MIN_INTERVAL,
+        )
+    }
+}
+
+impl CommandThrottle {
+    /// Create a throttle with custom windows.
+    ///
+    /// A zero duration disables throttling for that kind of command.
+    pub fn new(refresh_window: Duration, connect_interval: Duration) -> Self {
+        Self {
+            refresh_window,
+            connect_interval,
+            last_run: HashMap::new(),
+            trailing: HashMap::new(),
+        }
+    }
+
+    /// Decide whether `cmd` should run now.
+    ///
+    /// Returns `false` if the command duplicates one that ran too recently.
+    /// It is then kept as the trailing command for its window.
+    pub fn admit(&mut self, cmd: &Command) -> bool {
+        self.admit_at(cmd, Instant::now())
+    }
+
+    /// When the earliest trailing command's window ends, if any are waiting.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.trailing
+            .keys()
+            .map(|key| match self.last_run.get(key) {
+                Some(last) => *last + key.window(self),
+                None => Instant::now(),
+            })
+            .min()
+    }
+
+    /// Take the trailing commands whose window has ended.
+    ///
+    /// The returned commands are admitted; call [`finish`](Self::finish)
+    /// after running each.
+    pub fn take_due(&mut self) -> Vec<Command> {
+        self.take_due_at(Instant::now())
+    }
+
+    /// Record that `cmd` finished, starting its coalescing window.
+    pub fn finish(&mut self, cmd: &Command) {
+        self.finish_at(cmd, Instant::now());
+    }
+
+    fn admit_at(&mut self, cmd: &Command, now: Instant) -> bool {
+        if let Command::Disconnect { device_id } = cmd {
+            let other_device = |key: &ThrottleKey| !matches!(key, ThrottleKey::Refresh(id) | ThrottleKey::Connect(id) if id == device_id);
+            self.last_run.retain(|key, _| other_device(key));
+            self.trailing.retain(|key, _| other_device(key));
+            return true;
+        }
+
+        let Some(key) = ThrottleKey::for_command(cmd) else {
+            return true;
+        };
+
+        let window = key.window(self);
+        match self.last_run.get(&key) {
+            Some(last) if now.saturating_duration_since(*last) < window => {
+                self.trailing.insert(key, cmd.clone());
+                false
+            }
+            _ => {
+                // Record the start as well, so a burst that arrives before
+                // this command finishes is still collapsed
+                self.last_run.insert(key, now);
+                true
+            }
+        }
+    }
+
+    fn take_due_at(&mut self, now: Instant) -> Vec<Command> {
+        let due: Vec<ThrottleKey> = self
+            .trailing
+            .keys()
+            .filter(|key| {
+                self.last_run
+                    .get(key)
+                    .is_none_or(|last| now.saturating_duration_since(*last) >= key.window(self))
+            })
+            .cloned()
+            .collect();
+        due.into_iter()
+            .filter_map(|key| {
+                let cmd = self.trailing.remove(&key)?;
+                self.last_run.insert(key, now);
+                Some(cmd)
+            })
+            .collect()
+    }
+
+    fn finish_at(&mut self, cmd: &Command, now: Instant) {
+        if let Some(key) = ThrottleKey::for_command(cmd) {
+            self.last_run.insert(key, now);
+        }
+    }
+}
+
 /// Cached device data loaded from the store.
 #[derive(Debug, Clone)]
 pub struct CachedDevice {
@@ -327,6 +594,57 @@ pub struct CachedDevice {
     pub last_sync: Option<time::OffsetDateTime>,
 }
 
+/// An alert loaded from or written to the store.
+#[derive(Debug, Clone)]
+pub struct CachedAlert {
+    /// Store row ID.
+    pub id: i64,
+    /// Device identifier.
+    pub device_id: String,
+    /// Alert kind (e.g. `co2`, `offline`).
+    pub kind: String,
+    /// Severity (`info`, `warning` or `critical`).
+    pub severity: String,
+    /// The measurement that triggered the alert, if any.
+    pub value: Option<f64>,
+    /// Human-readable description.
+    pub message: String,
+    /// When the alert was raised.
+    pub triggered_at: time::OffsetDateTime,
+    /// Whether the alert has been acknowledged.
+    pub acknowledged: bool,
+}
+
+/// Kind of file an import reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// History CSV, as written by `Store::export_history_csv`.
+    HistoryCsv,
+    /// A JSON device bundle, as written by `Store::export_device_bundle`.
+    DeviceBundle,
+}
+
+/// What importing a file would add to the store.
+#[derive(Debug, Clone)]
+pub struct ImportPreview {
+    /// Detected file format.
+    pub format: ImportFormat,
+    /// Device IDs the data belongs to.
+    pub devices: Vec<String>,
+    /// Current readings in the file.
+    pub readings: usize,
+    /// History records in the file.
+    pub history: usize,
+    /// Annotations in the file.
+    pub annotations: usize,
+    /// Rows that cannot be imported.
+    pub invalid_rows: usize,
+    /// Earliest timestamp in the file.
+    pub start: Option<time::OffsetDateTime>,
+    /// Latest timestamp in the file.
+    pub end: Option<time::OffsetDateTime>,
+}
+
 /// Events sent from the background worker to the UI thread.
 ///
 /// These events represent the results of background operations
@@ -422,6 +740,14 @@ pub enum SensorEvent {
         total_records: Option<u16>,
     },
 
+    /// History memory usage read from a device.
+    HistoryInfoLoaded {
+        /// The device identifier.
+        device_id: String,
+        /// Stored record count, interval and capacity.
+        info: HistoryInfo,
+    },
+
     /// History sync progress update.
     HistorySyncProgress {
         /// The device identifier.
@@ -592,6 +918,82 @@ pub enum SensorEvent {
         device_id: String,
     },
 
+    /// Passive monitoring started.
+    PassiveMonitoringStarted,
+
+    /// Passive monitoring stopped.
+    PassiveMonitoringStopped,
+
+    /// Received a reading from a device's BLE advertisement.
+    PassiveReadingUpdated {
+        /// The device identifier.
+        device_id: String,
+        /// The advertised device name, if available.
+        name: Option<String>,
+        /// The device type detected from the advertisement.
+        device_type: DeviceType,
+        /// The reading decoded from the advertisement.
+        reading: CurrentReading,
+        /// RSSI of the advertisement in dBm.
+        rssi: Option<i16>,
+    },
+
+    /// One second of placement assistant measurements.
+    PlacementSample {
+        /// The device identifier.
+        device_id: String,
+        /// Last advertisement RSSI in dBm, if any advertisement was received.
+        rssi: Option<i16>,
+        /// Number of advertisements received in the second.
+        advertisements: u32,
+    },
+
+    /// The placement assistant stopped because of an error.
+    PlacementError {
+        /// The device identifier.
+        device_id: String,
+        /// Error description.
+        error: String,
+    },
+
+    /// Persisted alerts loaded from the store, newest first.
+    AlertsLoaded {
+        /// The loaded alerts.
+        alerts: Vec<CachedAlert>,
+    },
+
+    /// An alert was written to the store.
+    AlertRecorded {
+        /// The stored alert, including its row ID.
+        alert: CachedAlert,
+    },
+
+    /// A file dropped for import was read and previewed.
+    ImportPreviewReady {
+        /// The previewed file.
+        path: PathBuf,
+        /// What importing it would add.
+        preview: ImportPreview,
+    },
+
+    /// A file was imported into the store.
+    ImportCompleted {
+        /// The imported file.
+        path: PathBuf,
+        /// Rows added to the store.
+        imported: usize,
+        /// Rows skipped as duplicates or invalid.
+        skipped: usize,
+    },
+
+    /// A file could not be previewed or imported.
+    ImportFailed {
+        /// The file.
+        path: PathBuf,
+        /// Description of the error.
+        error: String,
+    },
+
     /// Signal strength update (can be sent periodically or on connect).
     SignalStrengthUpdate {
         /// The device identifier.
@@ -815,4 +1217,118 @@ mod tests {
         assert!(ctx.retryable);
         assert_eq!(ctx.suggestion, Some("Move closer and retry".to_string()));
     }
+
+    fn refresh(id: &str) -> Command {
+        Command::RefreshReading {
+            device_id: id.to_string(),
+        }
+    }
+
+    fn connect(id: &str) -> Command {
+        Command::Connect {
+            device_id: id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_throttle_coalesces_refresh_per_device() {
+        let mut throttle = CommandThrottle::default();
+        let t0 = Instant::now();
+
+        assert!(throttle.admit_at(&refresh("a"), t0));
+        throttle.finish_at(&refresh("a"), t0 + Duration::from_secs(5));
+
+        // Duplicate queued while the first refresh was running
+        assert!(!throttle.admit_at(&refresh("a"), t0 + Duration::from_secs(6)));
+        // Other devices are unaffected
+        assert!(throttle.admit_at(&refresh("b"), t0 + Duration::from_secs(6)));
+        // Window has elapsed since the first refresh finished
+        assert!(throttle.admit_at(&refresh("a"), t0 + Duration::from_secs(8)));
+    }
+
+    #[test]
+    fn test_throttle_rate_limits_connect() {
+        let mut throttle = CommandThrottle::default();
+        let t0 = Instant::now();
+
+        assert!(throttle.admit_at(&connect("a"), t0));
+        assert!(!throttle.admit_at(&connect("a"), t0 + Duration::from_millis(100)));
+        assert!(!throttle.admit_at(&connect("a"), t0 + Duration::from_secs(2)));
+        assert!(throttle.admit_at(&connect("a"), t0 + DEFAULT_CONNECT_MIN_INTERVAL));
+    }
+
+    #[test]
+    fn test_throttle_disconnect_resets_device() {
+        let mut throttle = CommandThrottle::default();
+        let t0 = Instant::now();
+
+        assert!(throttle.admit_at(&connect("a"), t0));
+        assert!(throttle.admit_at(&connect("b"), t0));
+        let disconnect = Command::Disconnect {
+            device_id: "a".to_string(),
+        };
+        assert!(throttle.admit_at(&disconnect, t0));
+
+        assert!(throttle.admit_at(&connect("a"), t0 + Duration::from_millis(10)));
+        assert!(!throttle.admit_at(&connect("b"), t0 + Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn test_throttle_runs_trailing_command() {
+        let mut throttle = CommandThrottle::default();
+        let t0 = Instant::now();
+
+        assert!(throttle.admit_at(&refresh("a"), t0));
+        throttle.finish_at(&refresh("a"), t0 + Duration::from_secs(1));
+        assert!(throttle.next_due().is_none());
+
+        // Duplicates collapse into one trailing refresh
+        assert!(!throttle.admit_at(&refresh("a"), t0 + Duration::from_secs(1)));
+        assert!(!throttle.admit_at(&refresh("a"), t0 + Duration::from_secs(2)));
+        let due = t0 + Duration::from_secs(1) + DEFAULT_REFRESH_COALESCE_WINDOW;
+        assert_eq!(throttle.next_due(), Some(due));
+        assert!(
+            throttle
+                .take_due_at(due - Duration::from_millis(1))
+                .is_empty()
+        );
+
+        let commands = throttle.take_due_at(due);
+        assert_eq!(commands.len(), 1);
+        assert!(matches!(&commands[0], Command::RefreshReading { device_id } if device_id == "a"));
+        assert!(throttle.next_due().is_none());
+        // The trailing run starts a new window
+        assert!(!throttle.admit_at(&refresh("a"), due + Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn test_throttle_disconnect_drops_trailing_connect() {
+        let mut throttle = CommandThrottle::default();
+        let t0 = Instant::now();
+
+        assert!(throttle.admit_at(&connect("a"), t0));
+        assert!(!throttle.admit_at(&connect("a"), t0 + Duration::from_millis(100)));
+        let disconnect = Command::Disconnect {
+            device_id: "a".to_string(),
+        };
+        assert!(throttle.admit_at(&disconnect, t0 + Duration::from_millis(200)));
+
+        assert!(throttle.next_due().is_none());
+        assert!(
+            throttle
+                .take_due_at(t0 + Duration::from_secs(60))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_throttle_passes_other_commands() {
+        let mut throttle = CommandThrottle::new(Duration::ZERO, Duration::ZERO);
+        let t0 = Instant::now();
+
+        assert!(throttle.admit_at(&Command::RefreshAll, t0));
+        assert!(throttle.admit_at(&Command::RefreshAll, t0));
+        assert!(throttle.admit_at(&Command::LoadCachedData, t0));
+        assert!(throttle.admit_at(&Command::LoadCachedData, t0));
+    }
 }
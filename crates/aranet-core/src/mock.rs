@@ -10,15 +10,54 @@
 //!
 //! - **Failure injection**: Set the device to fail on specific operations
 //! - **Latency simulation**: Add artificial delays to simulate slow BLE responses
+//! - **Fault injection**: Seeded random read failures, latency distributions
+//!   and disconnects after N operations (see [`MockDeviceBuilder::seed`])
 //! - **Custom behavior**: Inject custom reading generators for dynamic test scenarios
-
+//!
+//! # Fault Injection
+//!
+//! Faults are drawn from a seeded random number generator, so a test sees
+//! the same sequence of failures and delays on every run. Combined with
+//! tokio's paused clock, retry, backoff and reconnect logic can be exercised
+//! deterministically:
+//!
+//! ```
+//! use std::time::Duration;
+//! use aranet_core::{LatencyDistribution, MockDeviceBuilder};
+//!
+//! # #[tokio::main(flavor = "current_thread", start_paused = true)]
+//! # async fn main() {
+//! let device = MockDeviceBuilder::new()
+//!     .seed(42)
+//!     .read_failure_probability(0.3)
+//!     .read_latency(LatencyDistribution::Uniform {
+//!         min: Duration::from_millis(50),
+//!         max: Duration::from_millis(400),
+//!     })
+//!     .disconnect_after(10)
+//!     .build();
+//!
+//! for _ in 0..10 {
+//!     let _ = device.read_current().await;
+//! }
+//! let stats = device.fault_stats();
+//! assert_eq!(stats.operations, 10);
+//! # }
+//! ```
+
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, AtomicI16, AtomicU32, AtomicU64, Ordering};
 use std::time::Duration;
 
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use tokio::sync::RwLock;
 
-use aranet_types::{CurrentReading, DeviceInfo, DeviceType, HistoryRecord, Status};
+use aranet_types::{
+    AdvertisementBuilder, CurrentReading, DeviceInfo, DeviceType, HistoryRecord, Status,
+};
 
+use crate::device::{RssiSamples, sample_rssi_with};
 use crate::error::{Error, Result};
 use crate::history::{HistoryInfo, HistoryOptions};
 use crate::settings::{CalibrationData, MeasurementInterval};
@@ -69,6 +108,107 @@ pub struct MockDevice {
     fail_count: AtomicU32,
     /// Current count of failures (decremented on each failure).
     remaining_failures: AtomicU32,
+    /// Seeded fault injection state.
+    faults: Mutex<FaultInjector>,
+}
+
+/// Distribution of injected latencies for a [`MockDevice`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LatencyDistribution {
+    /// Always the same delay.
+    Fixed(Duration),
+    /// Uniformly distributed between `min` and `max` (inclusive).
+    Uniform {
+        /// Shortest delay.
+        min: Duration,
+        /// Longest delay.
+        max: Duration,
+    },
+    /// Usually `base`, but `spike` with the given probability (0.0-1.0),
+    /// like a BLE link that occasionally stalls.
+    Spikes {
+        /// Normal delay.
+        base: Duration,
+        /// Delay of a spike.
+        spike: Duration,
+        /// Probability of a spike per operation.
+        probability: f64,
+    },
+}
+
+impl LatencyDistribution {
+    fn sample(&self, rng: &mut StdRng) -> Duration {
+        match *self {
+            LatencyDistribution::Fixed(latency) => latency,
+            LatencyDistribution::Uniform { min, max } => {
+                if max <= min {
+                    return min;
+                }
+                let nanos = rng.random_range(min.as_nanos() as u64..=max.as_nanos() as u64);
+                Duration::from_nanos(nanos)
+            }
+            LatencyDistribution::Spikes {
+                base,
+                spike,
+                probability,
+            } => {
+                if rng.random_bool(probability.clamp(0.0, 1.0)) {
+                    spike
+                } else {
+                    base
+                }
+            }
+        }
+    }
+}
+
+/// Counters for faults injected into a [`MockDevice`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultStats {
+    /// Operations (reads and writes) attempted, including failed ones.
+    pub operations: u32,
+    /// Operations failed by the random read failure probability.
+    pub injected_failures: u32,
+    /// Disconnects forced by [`MockDeviceBuilder::disconnect_after`].
+    pub injected_disconnects: u32,
+    /// Total latency injected into connects and operations.
+    pub injected_latency: Duration,
+}
+
+/// Fault injection settings, set through [`MockDeviceBuilder`].
+#[derive(Debug, Clone, Copy, Default)]
+struct FaultConfig {
+    seed: u64,
+    read_failure_probability: f64,
+    read_latency: Option<LatencyDistribution>,
+    connect_latency: Option<LatencyDistribution>,
+    disconnect_after: Option<u32>,
+}
+
+#[derive(Debug)]
+struct FaultInjector {
+    config: FaultConfig,
+    rng: StdRng,
+    operations_since_connect: u32,
+    stats: FaultStats,
+}
+
+impl FaultInjector {
+    fn new(config: FaultConfig) -> Self {
+        Self {
+            config,
+            rng: StdRng::seed_from_u64(config.seed),
+            operations_since_connect: 0,
+            stats: FaultStats::default(),
+        }
+    }
+}
+
+/// The outcome of fault injection for one operation.
+struct InjectedFault {
+    latency: Duration,
+    disconnect: bool,
+    fail: bool,
 }
 
 impl std::fmt::Debug for MockDevice {
@@ -104,6 +244,7 @@ impl MockDevice {
             connect_latency_ms: AtomicU64::new(0),
             fail_count: AtomicU32::new(0),
             remaining_failures: AtomicU32::new(0),
+            faults: Mutex::new(FaultInjector::new(FaultConfig::default())),
         }
     }
 
@@ -144,9 +285,17 @@ impl MockDevice {
         use crate::error::DeviceNotFoundReason;
 
         // Simulate connect latency
-        let latency = self.connect_latency_ms.load(Ordering::Relaxed);
-        if latency > 0 {
-            tokio::time::sleep(Duration::from_millis(latency)).await;
+        let latency = {
+            let mut faults = self.lock_faults();
+            let latency = match faults.config.connect_latency {
+                Some(distribution) => distribution.sample(&mut faults.rng),
+                None => Duration::from_millis(self.connect_latency_ms.load(Ordering::Relaxed)),
+            };
+            faults.stats.injected_latency += latency;
+            latency
+        };
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
         }
 
         // Check for transient failures first
@@ -162,6 +311,7 @@ impl MockDevice {
                 identifier: self.name.clone(),
             }));
         }
+        self.lock_faults().operations_since_connect = 0;
         self.connected.store(true, Ordering::Relaxed);
         Ok(())
     }
@@ -201,6 +351,16 @@ impl MockDevice {
         Ok(*self.current_reading.read().await)
     }
 
+    /// Smart Home advertisement data for the current reading, as a real
+    /// device would broadcast it. Does not require a connection.
+    pub async fn advertisement_data(&self) -> Vec<u8> {
+        let reading = *self.current_reading.read().await;
+        let counter = self.read_count.load(Ordering::Relaxed) as u8;
+        AdvertisementBuilder::new(self.device_type, &reading)
+            .counter(counter)
+            .build()
+    }
+
     /// Read battery level.
     pub async fn read_battery(&self) -> Result<u8> {
         self.check_connected()?;
@@ -215,6 +375,13 @@ impl MockDevice {
         Ok(self.rssi.load(Ordering::Relaxed))
     }
 
+    /// Read the RSSI `count` times, `interval` apart.
+    ///
+    /// See [`Device::sample_rssi`](crate::Device::sample_rssi).
+    pub async fn sample_rssi(&self, interval: Duration, count: usize) -> Result<RssiSamples> {
+        sample_rssi_with(interval, count, || self.read_rssi()).await
+    }
+
     /// Read device info.
     pub async fn read_device_info(&self) -> Result<DeviceInfo> {
         self.check_connected()?;
@@ -234,6 +401,7 @@ impl MockDevice {
             total_readings: history.len() as u16,
             interval_seconds: interval.as_seconds(),
             seconds_since_update: 60,
+            capacity: self.device_type.history_capacity(),
         })
     }
 
@@ -313,10 +481,22 @@ impl MockDevice {
     }
 
     async fn check_should_fail(&self) -> Result<()> {
+        let fault = self.inject_fault();
+
         // Simulate read latency
-        let latency = self.read_latency_ms.load(Ordering::Relaxed);
-        if latency > 0 {
-            tokio::time::sleep(Duration::from_millis(latency)).await;
+        if !fault.latency.is_zero() {
+            tokio::time::sleep(fault.latency).await;
+        }
+
+        if fault.disconnect {
+            self.connected.store(false, Ordering::Relaxed);
+            return Err(Error::NotConnected);
+        }
+        if fault.fail {
+            return Err(Error::timeout(
+                "mock operation (injected fault)",
+                fault.latency,
+            ));
         }
 
         // Check for transient failures first
@@ -332,6 +512,46 @@ impl MockDevice {
         }
     }
 
+    /// Draw this operation's latency and faults from the seeded generator.
+    fn inject_fault(&self) -> InjectedFault {
+        let mut faults = self.lock_faults();
+        let faults = &mut *faults;
+        faults.stats.operations += 1;
+        faults.operations_since_connect += 1;
+
+        let latency = match faults.config.read_latency {
+            Some(distribution) => distribution.sample(&mut faults.rng),
+            None => Duration::from_millis(self.read_latency_ms.load(Ordering::Relaxed)),
+        };
+        faults.stats.injected_latency += latency;
+
+        let disconnect = faults
+            .config
+            .disconnect_after
+            .is_some_and(|limit| faults.operations_since_connect > limit);
+        let fail = !disconnect
+            && faults.config.read_failure_probability > 0.0
+            && faults
+                .rng
+                .random_bool(faults.config.read_failure_probability);
+
+        if disconnect {
+            faults.stats.injected_disconnects += 1;
+        } else if fail {
+            faults.stats.injected_failures += 1;
+        }
+
+        InjectedFault {
+            latency,
+            disconnect,
+            fail,
+        }
+    }
+
+    fn lock_faults(&self) -> std::sync::MutexGuard<'_, FaultInjector> {
+        self.faults.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     // --- Test control methods ---
 
     /// Set the current reading for testing.
@@ -451,6 +671,17 @@ impl MockDevice {
     pub fn remaining_failures(&self) -> u32 {
         self.remaining_failures.load(Ordering::Relaxed)
     }
+
+    /// Get counters for the faults injected so far.
+    pub fn fault_stats(&self) -> FaultStats {
+        self.lock_faults().stats
+    }
+
+    /// Reset fault counters and restart the random sequence from the seed.
+    pub fn reset_faults(&self) {
+        let mut faults = self.lock_faults();
+        *faults = FaultInjector::new(faults.config);
+    }
 }
 
 // Implement the AranetDevice trait for MockDevice
@@ -549,6 +780,7 @@ pub struct MockDeviceBuilder {
     radon_avg_30d: Option<u32>,
     radiation_rate: Option<f32>,
     radiation_total: Option<f64>,
+    faults: FaultConfig,
 }
 
 impl Default for MockDeviceBuilder {
@@ -569,6 +801,7 @@ impl Default for MockDeviceBuilder {
             radon_avg_30d: None,
             radiation_rate: None,
             radiation_total: None,
+            faults: FaultConfig::default(),
         }
     }
 }
@@ -685,6 +918,50 @@ impl MockDeviceBuilder {
         self
     }
 
+    /// Seed the random generator used for fault injection (default 0).
+    ///
+    /// The same seed produces the same sequence of injected failures and
+    /// latencies.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.faults.seed = seed;
+        self
+    }
+
+    /// Fail each read or write with this probability (0.0-1.0).
+    ///
+    /// Injected failures are [`Error::Timeout`], which retry logic treats as
+    /// transient.
+    #[must_use]
+    pub fn read_failure_probability(mut self, probability: f64) -> Self {
+        self.faults.read_failure_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Delay each read or write by a latency drawn from `distribution`.
+    #[must_use]
+    pub fn read_latency(mut self, distribution: LatencyDistribution) -> Self {
+        self.faults.read_latency = Some(distribution);
+        self
+    }
+
+    /// Delay each connect by a latency drawn from `distribution`.
+    #[must_use]
+    pub fn connect_latency(mut self, distribution: LatencyDistribution) -> Self {
+        self.faults.connect_latency = Some(distribution);
+        self
+    }
+
+    /// Drop the connection after `operations` reads or writes.
+    ///
+    /// The next operation disconnects the device and fails with
+    /// [`Error::NotConnected`]; the count starts again on reconnect.
+    #[must_use]
+    pub fn disconnect_after(mut self, operations: u32) -> Self {
+        self.faults.disconnect_after = Some(operations);
+        self
+    }
+
     /// Build the mock device.
     ///
     /// Note: This is a sync method that sets initial state directly.
@@ -728,6 +1005,7 @@ impl MockDeviceBuilder {
             connect_latency_ms: AtomicU64::new(0),
             fail_count: AtomicU32::new(0),
             remaining_failures: AtomicU32::new(0),
+            faults: Mutex::new(FaultInjector::new(self.faults)),
         }
     }
 }
@@ -747,6 +1025,7 @@ impl MockDeviceBuilder {
 /// - `test_mock_device_read`: Basic reading retrieval
 /// - `test_mock_device_read_battery`: Battery level reading
 /// - `test_mock_device_read_rssi`: Signal strength reading
+/// - `test_mock_device_sample_rssi`: RSSI series and statistics
 /// - `test_mock_device_read_device_info`: Device information
 /// - `test_mock_device_set_values`: Dynamic value updates
 ///
@@ -762,6 +1041,9 @@ impl MockDeviceBuilder {
 /// ## Failure Injection Tests
 /// - `test_mock_device_fail`: Permanent failure mode
 /// - `test_mock_device_transient_failures`: Temporary failures for retry testing
+/// - `test_fault_injection_is_deterministic`: Seeded random failures
+/// - `test_fault_injection_latency_distribution`: Injected latency distributions
+/// - `test_fault_injection_disconnect_after`: Disconnect after N operations
 ///
 /// ## Builder Tests
 /// - `test_builder_defaults`: Default builder values
@@ -802,6 +1084,18 @@ mod tests {
         assert!((reading.temperature - 25.0).abs() < 0.01);
     }
 
+    #[tokio::test]
+    async fn test_mock_device_advertisement_data() {
+        let device = MockDeviceBuilder::new()
+            .co2(1200)
+            .auto_connect(false)
+            .build();
+
+        let data = device.advertisement_data().await;
+        let parsed = crate::advertisement::parse_advertisement(&data).unwrap();
+        assert_eq!(parsed.co2, Some(1200));
+    }
+
     #[tokio::test]
     async fn test_mock_device_fail() {
         let device = MockDeviceBuilder::new().build();
@@ -854,6 +1148,27 @@ mod tests {
         assert_eq!(rssi, -65);
     }
 
+    #[tokio::test]
+    async fn test_mock_device_sample_rssi() {
+        let device = MockDeviceBuilder::new().build();
+        device.set_rssi(-70);
+        let series = device.sample_rssi(Duration::ZERO, 4).await.unwrap();
+        assert_eq!(series.samples, vec![-70; 4]);
+        assert_eq!(series.mean(), Some(-70.0));
+        assert_eq!(series.stddev(), Some(0.0));
+        assert_eq!(series.quality(), Some(crate::SignalQuality::Good));
+
+        let series = RssiSamples::new(Duration::from_secs(1), vec![-60, -64, -68, -72]);
+        assert_eq!(series.mean(), Some(-66.0));
+        assert_eq!(series.min(), Some(-72));
+        assert_eq!(series.max(), Some(-60));
+        assert!((series.stddev().unwrap() - 20f64.sqrt()).abs() < 1e-9);
+
+        assert!(device.sample_rssi(Duration::ZERO, 0).await.is_err());
+        device.set_should_fail(true, None).await;
+        assert!(device.sample_rssi(Duration::ZERO, 3).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_mock_device_read_device_info() {
         let device = MockDeviceBuilder::new().name("Test Device").build();
@@ -1084,4 +1399,97 @@ mod tests {
         assert_eq!(battery, 77);
         assert_eq!(rssi, -55);
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fault_injection_is_deterministic() {
+        async fn outcomes(seed: u64) -> Vec<bool> {
+            let device = MockDeviceBuilder::new()
+                .seed(seed)
+                .read_failure_probability(0.5)
+                .build();
+            let mut outcomes = Vec::new();
+            for _ in 0..32 {
+                outcomes.push(device.read_current().await.is_ok());
+            }
+            let stats = device.fault_stats();
+            assert_eq!(stats.operations, 32);
+            assert_eq!(
+                stats.injected_failures as usize,
+                outcomes.iter().filter(|ok| !**ok).count()
+            );
+            outcomes
+        }
+
+        let first = outcomes(7).await;
+        assert_eq!(first, outcomes(7).await);
+        assert!(first.contains(&true) && first.contains(&false));
+
+        // Injected failures are transient, so retries get through
+        let device = MockDeviceBuilder::new()
+            .seed(7)
+            .read_failure_probability(0.5)
+            .build();
+        let config = crate::RetryConfig {
+            max_retries: 10,
+            ..Default::default()
+        };
+        let result = crate::with_retry(&config, "read", || device.read_current()).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fault_injection_latency_distribution() {
+        let device = MockDeviceBuilder::new()
+            .read_latency(LatencyDistribution::Uniform {
+                min: Duration::from_millis(100),
+                max: Duration::from_millis(200),
+            })
+            .connect_latency(LatencyDistribution::Fixed(Duration::from_secs(1)))
+            .auto_connect(false)
+            .build();
+
+        let start = tokio::time::Instant::now();
+        device.connect().await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+
+        for _ in 0..5 {
+            let start = tokio::time::Instant::now();
+            device.read_current().await.unwrap();
+            let elapsed = start.elapsed();
+            assert!(elapsed >= Duration::from_millis(100) && elapsed <= Duration::from_millis(200));
+        }
+        let injected = device.fault_stats().injected_latency;
+        assert!(injected >= Duration::from_millis(1500) && injected <= Duration::from_millis(2000));
+
+        let spiky = MockDeviceBuilder::new()
+            .read_latency(LatencyDistribution::Spikes {
+                base: Duration::ZERO,
+                spike: Duration::from_secs(5),
+                probability: 1.0,
+            })
+            .build();
+        let start = tokio::time::Instant::now();
+        spiky.read_battery().await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_fault_injection_disconnect_after() {
+        let device = MockDeviceBuilder::new().disconnect_after(2).build();
+
+        device.read_current().await.unwrap();
+        device.read_battery().await.unwrap();
+        assert!(matches!(device.read_rssi().await, Err(Error::NotConnected)));
+        assert!(!device.is_connected_sync());
+
+        // Reconnecting starts a new window of operations
+        device.connect().await.unwrap();
+        device.read_current().await.unwrap();
+        device.read_current().await.unwrap();
+        assert!(device.read_current().await.is_err());
+        assert_eq!(device.fault_stats().injected_disconnects, 2);
+
+        device.reset_faults();
+        assert_eq!(device.fault_stats(), FaultStats::default());
+    }
 }
@@ -32,20 +32,54 @@
 //!     println!("Device: {} CO2: {:?}", reading.device_name, reading.data.co2);
 //! }
 //! ```
+//!
+//! Advertisements repeat every few seconds while the device only measures
+//! once per interval. To react to real changes only, register per-device
+//! callbacks and a change threshold:
+//!
+//! ```ignore
+//! let options = PassiveMonitorOptions::new()
+//!     .min_co2_change(25)
+//!     .on_reading(vec!["AA:BB:CC:DD:EE:FF".to_string()], |reading| {
+//!         println!("Office CO2: {:?}", reading.data.co2);
+//!     });
+//! let monitor = Arc::new(PassiveMonitor::new(options));
+//! ```
+//!
+//! The monitor also raises alerts from the advertised battery level and
+//! status, so passive-only deployments notice a dying battery or a Red
+//! reading without connecting:
+//!
+//! ```ignore
+//! let options = PassiveMonitorOptions::new().battery_alert_thresholds(vec![25, 10]);
+//! let monitor = Arc::new(PassiveMonitor::new(options));
+//! let mut events = monitor.subscribe_events();
+//! while let Ok(event) = events.recv().await {
+//!     match event {
+//!         DeviceEvent::BatteryLow { device, level } => println!("{}: battery {level}%", device.id),
+//!         DeviceEvent::StatusRed { device, .. } => println!("{}: status Red", device.id),
+//!         _ => {}
+//!     }
+//! }
+//! ```
 
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
-use btleplug::api::{Central, Peripheral as _, ScanFilter};
+use btleplug::api::{Central, Peripheral as _};
 use tokio::sync::{RwLock, broadcast};
 use tokio::time::sleep;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
+use aranet_types::Status;
+
 use crate::advertisement::{AdvertisementData, parse_advertisement_with_name};
 use crate::error::Result;
-use crate::scan::get_adapter;
+use crate::events::{DeviceEvent, DeviceId};
+use crate::scan_service::ScanService;
+use crate::sensor_health::{CalibrationState, SensorHealth};
 use crate::uuid::MANUFACTURER_ID;
 
 /// Bitwise-exact comparison of two `Option<f32>` values (handles NaN correctly).
@@ -68,10 +102,64 @@ pub struct PassiveReading {
     pub rssi: Option<i16>,
     /// Parsed advertisement data.
     pub data: AdvertisementData,
+    /// Battery level in percent, if the advertisement carried a valid one.
+    pub battery: Option<u8>,
+    /// Status indicator, if the advertisement carried one.
+    pub status: Option<Status>,
     /// When this reading was received.
     pub received_at: std::time::Instant,
 }
 
+impl PassiveReading {
+    /// Build a reading from a parsed advertisement.
+    pub(crate) fn new(
+        device_id: String,
+        device_name: Option<String>,
+        rssi: Option<i16>,
+        data: AdvertisementData,
+    ) -> Self {
+        Self {
+            battery: (data.battery <= 100).then_some(data.battery),
+            status: (data.status != Status::Error).then_some(data.status),
+            device_id,
+            device_name,
+            rssi,
+            data,
+            received_at: std::time::Instant::now(),
+        }
+    }
+
+    fn device(&self) -> DeviceId {
+        DeviceId {
+            id: self.device_id.clone(),
+            name: self.device_name.clone(),
+            device_type: Some(self.data.device_type),
+        }
+    }
+}
+
+/// Callback registered with [`PassiveMonitorOptions::on_reading`].
+#[derive(Clone)]
+pub struct PassiveReadingCallback {
+    /// Device IDs this callback is interested in (empty = all devices).
+    device_filter: Vec<String>,
+    callback: Arc<dyn Fn(&PassiveReading) + Send + Sync>,
+}
+
+impl PassiveReadingCallback {
+    fn matches(&self, device_id: &str) -> bool {
+        self.device_filter.is_empty() || self.device_filter.iter().any(|id| id == device_id)
+    }
+}
+
+impl std::fmt::Debug for PassiveReadingCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PassiveReadingCallback")
+            .field("device_filter", &self.device_filter)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Options for passive monitoring.
 #[derive(Debug, Clone)]
 pub struct PassiveMonitorOptions {
@@ -87,6 +175,23 @@ pub struct PassiveMonitorOptions {
     pub max_reading_age: Duration,
     /// Filter to only these device IDs (empty = all Aranet devices).
     pub device_filter: Vec<String>,
+    /// Minimum CO₂ change, in ppm, that counts as a new reading (if deduplicate is true).
+    ///
+    /// When set, a reading is only emitted if the device's measurement
+    /// counter changed or CO₂ moved by at least this much since the last
+    /// emitted reading; advertisement repeats with small fluctuations in
+    /// other values are dropped. `None` emits on any value change.
+    pub min_co2_change: Option<u16>,
+    /// Callbacks invoked for each emitted reading.
+    pub callbacks: Vec<PassiveReadingCallback>,
+    /// Battery levels, in percent, that raise [`DeviceEvent::BatteryLow`]
+    /// when a device's advertised battery drops below them.
+    pub battery_alert_thresholds: Vec<u8>,
+    /// Raise [`DeviceEvent::StatusRed`] when a device's status turns Red.
+    pub status_alerts: bool,
+    /// Raise [`DeviceEvent::CalibrationStateChanged`] when an Aranet4's CO₂
+    /// calibration state changes.
+    pub calibration_alerts: bool,
 }
 
 impl Default for PassiveMonitorOptions {
@@ -98,6 +203,11 @@ impl Default for PassiveMonitorOptions {
             deduplicate: true,
             max_reading_age: Duration::from_secs(60),
             device_filter: Vec::new(),
+            min_co2_change: None,
+            callbacks: Vec::new(),
+            battery_alert_thresholds: vec![20, 10],
+            status_alerts: true,
+            calibration_alerts: true,
         }
     }
 }
@@ -131,6 +241,52 @@ impl PassiveMonitorOptions {
         self.device_filter = device_ids;
         self
     }
+
+    /// Only emit when the measurement counter changes or CO₂ moves by at
+    /// least `ppm`. Enables deduplication.
+    pub fn min_co2_change(mut self, ppm: u16) -> Self {
+        self.min_co2_change = Some(ppm);
+        self.deduplicate = true;
+        self
+    }
+
+    /// Call `callback` for every emitted reading from the given devices.
+    ///
+    /// An empty `device_filter` matches all devices. Callbacks run on the
+    /// monitor task, so they should be quick; hand off heavier work to a
+    /// channel. They see the same deduplicated stream as
+    /// [`PassiveMonitor::subscribe`].
+    pub fn on_reading<F>(mut self, device_filter: Vec<String>, callback: F) -> Self
+    where
+        F: Fn(&PassiveReading) + Send + Sync + 'static,
+    {
+        self.callbacks.push(PassiveReadingCallback {
+            device_filter,
+            callback: Arc::new(callback),
+        });
+        self
+    }
+
+    /// Set the battery levels that raise [`DeviceEvent::BatteryLow`].
+    ///
+    /// Each threshold fires once as the battery drops below it; an empty
+    /// list disables battery alerts.
+    pub fn battery_alert_thresholds(mut self, thresholds: Vec<u8>) -> Self {
+        self.battery_alert_thresholds = thresholds;
+        self
+    }
+
+    /// Enable or disable [`DeviceEvent::StatusRed`] alerts.
+    pub fn status_alerts(mut self, enable: bool) -> Self {
+        self.status_alerts = enable;
+        self
+    }
+
+    /// Enable or disable [`DeviceEvent::CalibrationStateChanged`] alerts.
+    pub fn calibration_alerts(mut self, enable: bool) -> Self {
+        self.calibration_alerts = enable;
+        self
+    }
 }
 
 /// Cached reading for deduplication.
@@ -139,6 +295,80 @@ struct CachedReading {
     received_at: std::time::Instant,
 }
 
+/// Per-device state for battery and status alerts.
+#[derive(Debug, Default)]
+struct AlertState {
+    /// Last status seen, to detect transitions to Red.
+    status: Option<Status>,
+    /// Lowest battery threshold already alerted for, reset once the battery
+    /// is back above every threshold.
+    battery_threshold: Option<u8>,
+    /// Last CO₂ calibration state seen.
+    calibration: Option<CalibrationState>,
+}
+
+impl AlertState {
+    /// Update the state with a new reading and return the alerts it raises.
+    fn update(
+        &mut self,
+        reading: &PassiveReading,
+        options: &PassiveMonitorOptions,
+    ) -> Vec<DeviceEvent> {
+        let mut alerts = Vec::new();
+
+        if let Some(level) = reading.battery {
+            // The lowest threshold the battery is below, if any
+            let crossed = options
+                .battery_alert_thresholds
+                .iter()
+                .copied()
+                .filter(|&threshold| level < threshold)
+                .min();
+            match crossed {
+                Some(threshold) if self.battery_threshold.is_none_or(|t| threshold < t) => {
+                    self.battery_threshold = Some(threshold);
+                    alerts.push(DeviceEvent::BatteryLow {
+                        device: reading.device(),
+                        level,
+                    });
+                }
+                Some(_) => {}
+                None => self.battery_threshold = None,
+            }
+        }
+
+        if let Some(status) = reading.status {
+            let previous = self.status.replace(status);
+            if options.status_alerts && status == Status::Red && previous != Some(Status::Red) {
+                alerts.push(DeviceEvent::StatusRed {
+                    device: reading.device(),
+                    previous,
+                });
+            }
+        }
+
+        if let Some(state) = SensorHealth::from_advertisement(&reading.data)
+            .and_then(|health| health.calibration_state)
+        {
+            let previous = self.calibration.replace(state);
+            // A device first seen idle is not news
+            let changed = match previous {
+                Some(previous) => previous != state,
+                None => state != CalibrationState::Idle,
+            };
+            if options.calibration_alerts && changed {
+                alerts.push(DeviceEvent::CalibrationStateChanged {
+                    device: reading.device(),
+                    previous,
+                    state,
+                });
+            }
+        }
+
+        alerts
+    }
+}
+
 /// Passive monitor for Aranet devices using BLE advertisements.
 ///
 /// This allows monitoring multiple devices without establishing connections,
@@ -152,16 +382,23 @@ pub struct PassiveMonitor {
     sender: broadcast::Sender<PassiveReading>,
     /// Cache of last readings for deduplication.
     cache: Arc<RwLock<HashMap<String, CachedReading>>>,
+    /// Broadcast sender for battery and status alerts.
+    events: broadcast::Sender<DeviceEvent>,
+    /// Alert state per device.
+    alerts: RwLock<HashMap<String, AlertState>>,
 }
 
 impl PassiveMonitor {
     /// Create a new passive monitor with the given options.
     pub fn new(options: PassiveMonitorOptions) -> Self {
         let (sender, _) = broadcast::channel(options.channel_capacity);
+        let (events, _) = broadcast::channel(options.channel_capacity);
         Self {
             options,
             sender,
             cache: Arc::new(RwLock::new(HashMap::new())),
+            events,
+            alerts: RwLock::new(HashMap::new()),
         }
     }
 
@@ -172,6 +409,18 @@ impl PassiveMonitor {
         self.sender.subscribe()
     }
 
+    /// Subscribe to alerts raised from advertisement data.
+    ///
+    /// Receives [`DeviceEvent::BatteryLow`] when a device's battery drops
+    /// below one of [`PassiveMonitorOptions::battery_alert_thresholds`], and
+    /// [`DeviceEvent::StatusRed`] when its status turns Red, and
+    /// [`DeviceEvent::CalibrationStateChanged`] when an Aranet4 starts, ends
+    /// or fails a CO₂ calibration. Alerts are evaluated on every
+    /// advertisement, before deduplication.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<DeviceEvent> {
+        self.events.subscribe()
+    }
+
     /// Get the number of active subscribers.
     pub fn subscriber_count(&self) -> usize {
         self.sender.receiver_count()
@@ -189,24 +438,6 @@ impl PassiveMonitor {
         tokio::spawn(async move {
             info!("Starting passive monitor");
 
-            // Acquire the adapter once and reuse across scan cycles.
-            // On persistent errors we re-acquire it in case the adapter
-            // was reset or the D-Bus connection was lost.
-            let mut adapter = loop {
-                match get_adapter().await {
-                    Ok(a) => break a,
-                    Err(e) => {
-                        warn!("Passive monitor failed to get adapter: {e} — retrying in 10s");
-                        tokio::select! {
-                            _ = cancel_token.cancelled() => {
-                                info!("Passive monitor cancelled while waiting for adapter");
-                                return;
-                            }
-                            _ = sleep(Duration::from_secs(10)) => {}
-                        }
-                    }
-                }
-            };
             let mut consecutive_errors: u32 = 0;
 
             loop {
@@ -215,7 +446,7 @@ impl PassiveMonitor {
                         info!("Passive monitor cancelled");
                         break;
                     }
-                    result = monitor.scan_cycle_with_adapter(&adapter) => {
+                    result = monitor.scan_cycle() => {
                         match result {
                             Ok(()) => {
                                 consecutive_errors = 0;
@@ -225,29 +456,22 @@ impl PassiveMonitor {
                                 warn!(
                                     "Passive monitor scan error ({consecutive_errors} consecutive): {e}"
                                 );
-                                // After several consecutive failures, try to
-                                // re-acquire the adapter — it may have been
-                                // reset or the D-Bus connection may have died.
-                                if consecutive_errors >= 5 {
+                                // After several consecutive failures, have the
+                                // scan service re-acquire the adapter — it may
+                                // have been reset or the D-Bus connection may
+                                // have died.
+                                if consecutive_errors == 5 {
                                     warn!("Passive monitor: re-acquiring adapter after {} consecutive errors", consecutive_errors);
-                                    match get_adapter().await {
-                                        Ok(a) => {
-                                            adapter = a;
-                                            info!("Passive monitor: adapter re-acquired");
-                                            consecutive_errors = 0;
-                                        }
-                                        Err(e2) => {
-                                            // Adapter re-acquisition failed — back off
-                                            // longer to avoid thrashing when the adapter
-                                            // is permanently unavailable.
-                                            warn!("Passive monitor: failed to re-acquire adapter: {}. Backing off.", e2);
-                                            let backoff = std::cmp::min(
-                                                monitor.options.scan_interval.saturating_mul(consecutive_errors),
-                                                std::time::Duration::from_secs(300),
-                                            );
-                                            sleep(backoff).await;
-                                        }
-                                    }
+                                    ScanService::global().reset_adapter().await;
+                                } else if consecutive_errors > 5 {
+                                    // Re-acquiring did not help — back off longer
+                                    // to avoid thrashing when the adapter is
+                                    // permanently unavailable.
+                                    let backoff = std::cmp::min(
+                                        monitor.options.scan_interval.saturating_mul(consecutive_errors),
+                                        std::time::Duration::from_secs(300),
+                                    );
+                                    sleep(backoff).await;
                                 }
                             }
                         }
@@ -259,12 +483,12 @@ impl PassiveMonitor {
         })
     }
 
-    /// Perform a single scan cycle using a pre-existing adapter.
-    async fn scan_cycle_with_adapter(&self, adapter: &btleplug::platform::Adapter) -> Result<()> {
-        // Start scanning
-        adapter.start_scan(ScanFilter::default()).await?;
+    /// Perform a single scan cycle as a subscriber of the shared scan.
+    async fn scan_cycle(&self) -> Result<()> {
+        let subscription = ScanService::global().subscribe(false).await?;
         sleep(self.options.scan_duration).await;
-        adapter.stop_scan().await?;
+        let adapter = subscription.adapter().clone();
+        subscription.stop().await?;
 
         // Process discovered peripherals
         let peripherals = adapter.peripherals().await?;
@@ -288,6 +512,14 @@ impl PassiveMonitor {
                     // Try to parse the advertisement
                     match parse_advertisement_with_name(data, props.local_name.as_deref()) {
                         Ok(adv_data) => {
+                            let reading = PassiveReading::new(
+                                device_id.clone(),
+                                props.local_name.clone(),
+                                props.rssi,
+                                adv_data.clone(),
+                            );
+                            self.check_alerts(&reading).await;
+
                             // Check for deduplication
                             let should_emit = if self.options.deduplicate {
                                 self.should_emit(&device_id, &adv_data).await
@@ -296,14 +528,6 @@ impl PassiveMonitor {
                             };
 
                             if should_emit {
-                                let reading = PassiveReading {
-                                    device_id: device_id.clone(),
-                                    device_name: props.local_name.clone(),
-                                    rssi: props.rssi,
-                                    data: adv_data.clone(),
-                                    received_at: std::time::Instant::now(),
-                                };
-
                                 // Update cache
                                 self.cache.write().await.insert(
                                     device_id,
@@ -313,6 +537,12 @@ impl PassiveMonitor {
                                     },
                                 );
 
+                                for callback in &self.options.callbacks {
+                                    if callback.matches(&reading.device_id) {
+                                        (callback.callback)(&reading);
+                                    }
+                                }
+
                                 // Send to subscribers (ignore if no receivers)
                                 let _ = self.sender.send(reading);
                             }
@@ -328,6 +558,22 @@ impl PassiveMonitor {
         Ok(())
     }
 
+    /// Raise battery and status alerts for a reading.
+    async fn check_alerts(&self, reading: &PassiveReading) {
+        let alerts = self
+            .alerts
+            .write()
+            .await
+            .entry(reading.device_id.clone())
+            .or_default()
+            .update(reading, &self.options);
+        for alert in alerts {
+            info!("Passive alert: {alert:?}");
+            // Ignore if no receivers
+            let _ = self.events.send(alert);
+        }
+    }
+
     /// Check if a reading should be emitted (for deduplication).
     async fn should_emit(&self, device_id: &str, data: &AdvertisementData) -> bool {
         let cache = self.cache.read().await;
@@ -338,6 +584,15 @@ impl PassiveMonitor {
                 return true;
             }
 
+            if let Some(min_change) = self.options.min_co2_change {
+                let co2_change = match (cached.data.co2, data.co2) {
+                    (Some(old), Some(new)) => old.abs_diff(new),
+                    (None, None) => 0,
+                    _ => u16::MAX,
+                };
+                return cached.data.counter != data.counter || co2_change >= min_change;
+            }
+
             // Check if values have changed (use total_cmp for floats to handle NaN correctly)
             if cached.data.co2 != data.co2
                 || !opt_f32_eq(cached.data.temperature, data.temperature)
@@ -374,9 +629,10 @@ impl PassiveMonitor {
         cache.keys().cloned().collect()
     }
 
-    /// Clear the reading cache.
+    /// Clear the reading cache and alert state.
     pub async fn clear_cache(&self) {
         self.cache.write().await.clear();
+        self.alerts.write().await.clear();
     }
 }
 
@@ -437,6 +693,119 @@ mod tests {
         }
     }
 
+    fn make_reading(battery: u8, status: aranet_types::Status) -> PassiveReading {
+        let mut data = make_adv_data();
+        data.battery = battery;
+        data.status = status;
+        PassiveReading::new("device-1".to_string(), None, None, data)
+    }
+
+    #[test]
+    fn test_passive_reading_battery_and_status() {
+        let reading = make_reading(85, Status::Yellow);
+        assert_eq!(reading.battery, Some(85));
+        assert_eq!(reading.status, Some(Status::Yellow));
+
+        let reading = make_reading(255, Status::Error);
+        assert_eq!(reading.battery, None);
+        assert_eq!(reading.status, None);
+    }
+
+    #[test]
+    fn test_battery_alerts_fire_once_per_threshold() {
+        let options = PassiveMonitorOptions::default();
+        let mut state = AlertState::default();
+        let mut battery_alerts = |level| {
+            state
+                .update(&make_reading(level, Status::Green), &options)
+                .into_iter()
+                .filter(|e| matches!(e, DeviceEvent::BatteryLow { .. }))
+                .count()
+        };
+
+        assert_eq!(battery_alerts(25), 0);
+        assert_eq!(battery_alerts(19), 1);
+        assert_eq!(battery_alerts(18), 0);
+        assert_eq!(battery_alerts(9), 1);
+        assert_eq!(battery_alerts(8), 0);
+        // A new battery resets the alerts
+        assert_eq!(battery_alerts(100), 0);
+        assert_eq!(battery_alerts(15), 1);
+    }
+
+    #[test]
+    fn test_status_alerts_on_transition_to_red() {
+        let options = PassiveMonitorOptions::default();
+        let mut state = AlertState::default();
+
+        assert!(
+            state
+                .update(&make_reading(80, Status::Yellow), &options)
+                .is_empty()
+        );
+        let alerts = state.update(&make_reading(80, Status::Red), &options);
+        assert!(matches!(
+            alerts.as_slice(),
+            [DeviceEvent::StatusRed {
+                previous: Some(Status::Yellow),
+                ..
+            }]
+        ));
+        assert!(
+            state
+                .update(&make_reading(80, Status::Red), &options)
+                .is_empty()
+        );
+        assert!(
+            state
+                .update(&make_reading(80, Status::Green), &options)
+                .is_empty()
+        );
+        assert_eq!(
+            state.update(&make_reading(80, Status::Red), &options).len(),
+            1
+        );
+
+        let options = PassiveMonitorOptions::new().status_alerts(false);
+        let mut state = AlertState::default();
+        assert!(
+            state
+                .update(&make_reading(80, Status::Red), &options)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_calibration_alerts_on_change() {
+        let options = PassiveMonitorOptions::default();
+        let mut state = AlertState::default();
+        let mut calibration_alerts = |flags| {
+            let mut reading = make_reading(80, Status::Green);
+            reading.data.flags = flags;
+            state
+                .update(&reading, &options)
+                .into_iter()
+                .filter_map(|e| match e {
+                    DeviceEvent::CalibrationStateChanged {
+                        previous, state, ..
+                    } => Some((previous, state)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert!(calibration_alerts(0x20).is_empty());
+        assert_eq!(
+            calibration_alerts(0x28),
+            [(Some(CalibrationState::Idle), CalibrationState::InProgress)]
+        );
+        assert!(calibration_alerts(0x28).is_empty());
+        assert_eq!(
+            calibration_alerts(0x2C),
+            [(Some(CalibrationState::InProgress), CalibrationState::Error)]
+        );
+    }
+
     #[tokio::test]
     async fn test_should_emit_first_reading() {
         let monitor = PassiveMonitor::default();
@@ -534,6 +903,47 @@ mod tests {
         assert!(monitor.should_emit("device-1", &data).await);
     }
 
+    #[tokio::test]
+    async fn test_should_emit_min_co2_change() {
+        let monitor = PassiveMonitor::new(PassiveMonitorOptions::new().min_co2_change(20));
+        let data = make_adv_data();
+
+        monitor.cache.write().await.insert(
+            "device-1".to_string(),
+            CachedReading {
+                data: data.clone(),
+                received_at: std::time::Instant::now(),
+            },
+        );
+
+        // Small CO2 and other value changes without a new measurement are dropped
+        let mut changed = data.clone();
+        changed.co2 = Some(810);
+        changed.battery = 84;
+        assert!(!monitor.should_emit("device-1", &changed).await);
+
+        // A large enough CO2 change is emitted
+        changed.co2 = Some(780);
+        assert!(monitor.should_emit("device-1", &changed).await);
+
+        // So is a new measurement, even with identical values
+        let mut changed = data;
+        changed.counter = Some(6);
+        assert!(monitor.should_emit("device-1", &changed).await);
+    }
+
+    #[test]
+    fn test_on_reading_callback_filter() {
+        let opts = PassiveMonitorOptions::new()
+            .on_reading(vec!["device-1".to_string()], |_| {})
+            .on_reading(Vec::new(), |_| {});
+
+        assert_eq!(opts.callbacks.len(), 2);
+        assert!(opts.callbacks[0].matches("device-1"));
+        assert!(!opts.callbacks[0].matches("device-2"));
+        assert!(opts.callbacks[1].matches("device-2"));
+    }
+
     #[tokio::test]
     async fn test_should_emit_different_device() {
         let monitor = PassiveMonitor::default();
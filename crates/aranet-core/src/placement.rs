@@ -0,0 +1,163 @@
+//! Advertisement reception probing for sensor placement.
+//!
+//! [`PlacementProbe`] follows one device's BLE advertisements and reports,
+//! once per interval, how many were received and at what RSSI. Moving a
+//! sensor around while watching these numbers shows where it can be heard
+//! reliably, without connecting to it.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aranet_core::PlacementProbe;
+//! use tokio_util::sync::CancellationToken;
+//!
+//! # async fn example() {
+//! let mut samples = PlacementProbe::new("AA:BB:CC:DD:EE:FF").start(CancellationToken::new());
+//! while let Some(Ok(sample)) = samples.recv().await {
+//!     println!("{:?} dBm, {} advertisements", sample.rssi, sample.advertisements);
+//! }
+//! # }
+//! ```
+
+use std::time::Duration;
+
+use btleplug::api::{Central, CentralEvent, Peripheral as _};
+use btleplug::platform::{Adapter, PeripheralId};
+use futures::StreamExt;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::error::Result;
+use crate::scan_service::ScanService;
+use crate::util::create_identifier;
+use crate::uuid::MANUFACTURER_ID;
+
+/// Default time covered by each [`ReceptionSample`].
+pub const PLACEMENT_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Advertisement reception over one sample interval.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReceptionSample {
+    /// RSSI of the last advertisement in dBm, if any was received.
+    pub rssi: Option<i16>,
+    /// Number of advertisements received.
+    pub advertisements: u32,
+}
+
+/// Reports a device's advertisement reception once per interval.
+#[derive(Debug, Clone)]
+pub struct PlacementProbe {
+    device_id: String,
+    interval: Duration,
+}
+
+impl PlacementProbe {
+    /// Create a probe for the device with the given identifier.
+    pub fn new(device_id: impl Into<String>) -> Self {
+        Self {
+            device_id: device_id.into(),
+            interval: PLACEMENT_SAMPLE_INTERVAL,
+        }
+    }
+
+    /// Set the time covered by each sample.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Spawn the probe and return its samples.
+    ///
+    /// The probe shares the process-wide scan (see [`ScanService`]) until
+    /// `cancel_token` is cancelled or the receiver is dropped. An error ends
+    /// the stream.
+    pub fn start(self, cancel_token: CancellationToken) -> mpsc::Receiver<Result<ReceptionSample>> {
+        let (tx, rx) = mpsc::channel(16);
+
+        tokio::spawn(async move {
+            info!(device_id = %self.device_id, "Starting placement probe");
+            if let Err(e) = self.run(&tx, &cancel_token).await {
+                warn!(device_id = %self.device_id, "Placement probe failed: {e}");
+                let _ = tx.send(Err(e)).await;
+            }
+            info!(device_id = %self.device_id, "Placement probe stopped");
+        });
+
+        rx
+    }
+
+    async fn run(
+        &self,
+        tx: &mpsc::Sender<Result<ReceptionSample>>,
+        cancel_token: &CancellationToken,
+    ) -> Result<()> {
+        let subscription = ScanService::global().subscribe(false).await?;
+        let adapter = subscription.adapter().clone();
+        let result = self.follow(&adapter, tx, cancel_token).await;
+        if let Err(e) = subscription.stop().await {
+            debug!("Failed to leave shared scan: {e}");
+        }
+        result
+    }
+
+    async fn follow(
+        &self,
+        adapter: &Adapter,
+        tx: &mpsc::Sender<Result<ReceptionSample>>,
+        cancel_token: &CancellationToken,
+    ) -> Result<()> {
+        let mut events = adapter.events().await?;
+        let mut ticker = tokio::time::interval(self.interval);
+        // The first tick completes immediately
+        ticker.tick().await;
+
+        let mut target: Option<PeripheralId> = None;
+        let mut sample = ReceptionSample::default();
+
+        loop {
+            tokio::select! {
+                _ = cancel_token.cancelled() => return Ok(()),
+                _ = ticker.tick() => {
+                    if tx.send(Ok(std::mem::take(&mut sample))).await.is_err() {
+                        return Ok(());
+                    }
+                }
+                event = events.next() => match event {
+                    Some(CentralEvent::ManufacturerDataAdvertisement { id, manufacturer_data })
+                        if manufacturer_data.contains_key(&MANUFACTURER_ID) =>
+                    {
+                        if target.as_ref() != Some(&id) {
+                            if target.is_some() || !self.is_target(adapter, &id).await {
+                                continue;
+                            }
+                            target = Some(id.clone());
+                        }
+                        sample.advertisements += 1;
+                        if let Ok(peripheral) = adapter.peripheral(&id).await
+                            && let Ok(Some(props)) = peripheral.properties().await
+                        {
+                            sample.rssi = props.rssi.or(sample.rssi);
+                        }
+                    }
+                    Some(_) => {}
+                    None => {
+                        warn!("Adapter event stream ended, stopping placement probe");
+                        return Ok(());
+                    }
+                },
+            }
+        }
+    }
+
+    /// Whether the peripheral is the probed device.
+    async fn is_target(&self, adapter: &Adapter, id: &PeripheralId) -> bool {
+        let Ok(peripheral) = adapter.peripheral(id).await else {
+            return false;
+        };
+        let Ok(Some(props)) = peripheral.properties().await else {
+            return false;
+        };
+        create_identifier(&props.address.to_string(), id).eq_ignore_ascii_case(&self.device_id)
+    }
+}
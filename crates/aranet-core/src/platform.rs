@@ -11,6 +11,9 @@
 //! | Linux | BlueZ | MAC Address | May need longer connection timeouts |
 //! | Windows | WinRT | MAC Address | Generally reliable defaults |
 //!
+//! Each stack also has its own ways of failing to connect; see
+//! [`ConnectStrategy`] for the workarounds applied on each.
+//!
 //! # macOS UUID Behavior
 //!
 //! On macOS, CoreBluetooth does **not** expose Bluetooth MAC addresses. Instead, it assigns
@@ -149,6 +152,76 @@ pub struct PlatformConfig {
     ///
     /// Most BLE adapters support 5-7 concurrent connections.
     pub max_concurrent_connections: usize,
+
+    /// Whether independent characteristic reads can be in flight together
+    /// on one connection.
+    ///
+    /// Each read waits a connection interval or two for its response, so
+    /// overlapping them cuts latency on slow links. No stack has been verified
+    /// to accept this yet, so every platform reads one at a time; opt in with
+    /// [`ConnectionConfig::pipelined_reads`](crate::device::ConnectionConfig::pipelined_reads).
+    pub supports_pipelined_reads: bool,
+
+    /// Workarounds for this stack's connection failures.
+    pub connect_strategy: ConnectStrategy,
+}
+
+/// Workarounds applied when connecting, for failures specific to one BLE stack.
+///
+/// [`ConnectStrategy::for_current_platform`] enables the ones the platform
+/// needs; [`ConnectionConfig::connect_strategy`](crate::device::ConnectionConfig::connect_strategy)
+/// overrides them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectStrategy {
+    /// Reconnect once when the connection fails because the device removed
+    /// its pairing information.
+    ///
+    /// CoreBluetooth (macOS) keeps using the stale pairing until the
+    /// connection is dropped; a second attempt pairs afresh.
+    pub retry_on_peer_removed_pairing: bool,
+    /// Scan briefly before connecting to a previously discovered device.
+    ///
+    /// BlueZ (Linux) drops devices it has not seen recently, and connecting
+    /// to one it dropped fails with "device not found" or an aborted
+    /// connection.
+    pub refresh_scan_before_connect: bool,
+    /// Drop cached GATT services before connecting, and again when discovery
+    /// does not find the Aranet service.
+    ///
+    /// WinRT (Windows) service objects from an earlier connection stay
+    /// cached after the device drops the link, and fail once reused.
+    pub invalidate_service_cache: bool,
+}
+
+impl ConnectStrategy {
+    /// No workarounds.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Workarounds for the current platform.
+    pub fn for_current_platform() -> Self {
+        Self::for_platform(Platform::current())
+    }
+
+    /// Workarounds for a specific platform.
+    pub fn for_platform(platform: Platform) -> Self {
+        match platform {
+            Platform::MacOS => Self {
+                retry_on_peer_removed_pairing: true,
+                ..Self::default()
+            },
+            Platform::Linux => Self {
+                refresh_scan_before_connect: true,
+                ..Self::default()
+            },
+            Platform::Windows => Self {
+                invalidate_service_cache: true,
+                ..Self::default()
+            },
+            Platform::Unknown => Self::default(),
+        }
+    }
 }
 
 impl PlatformConfig {
@@ -185,6 +258,9 @@ impl PlatformConfig {
             scan_retry_delay: Duration::from_millis(500),
             // CoreBluetooth typically supports ~5 connections
             max_concurrent_connections: 5,
+            // Not verified; see `supports_pipelined_reads`
+            supports_pipelined_reads: false,
+            connect_strategy: ConnectStrategy::for_platform(Platform::MacOS),
         }
     }
 
@@ -206,6 +282,9 @@ impl PlatformConfig {
             scan_retry_delay: Duration::from_millis(500),
             // Linux adapters typically support ~7 connections
             max_concurrent_connections: 7,
+            // BlueZ may reject a read while another is pending (InProgress)
+            supports_pipelined_reads: false,
+            connect_strategy: ConnectStrategy::for_platform(Platform::Linux),
         }
     }
 
@@ -224,6 +303,9 @@ impl PlatformConfig {
             scan_retry_delay: Duration::from_millis(500),
             // Windows adapters typically support ~5-6 connections
             max_concurrent_connections: 5,
+            // Not verified; see `supports_pipelined_reads`
+            supports_pipelined_reads: false,
+            connect_strategy: ConnectStrategy::for_platform(Platform::Windows),
         }
     }
 }
@@ -242,6 +324,8 @@ impl Default for PlatformConfig {
             recommended_scan_retries: 3,
             scan_retry_delay: Duration::from_millis(500),
             max_concurrent_connections: 5,
+            supports_pipelined_reads: false,
+            connect_strategy: ConnectStrategy::none(),
         }
     }
 }
@@ -600,6 +684,35 @@ mod tests {
         assert!(config.exposes_mac_address);
     }
 
+    #[test]
+    fn test_platform_config_pipelined_reads() {
+        // Sequential until a stack is verified to accept overlapping reads
+        assert!(!PlatformConfig::macos().supports_pipelined_reads);
+        assert!(!PlatformConfig::linux().supports_pipelined_reads);
+        assert!(!PlatformConfig::windows().supports_pipelined_reads);
+        assert!(!PlatformConfig::default().supports_pipelined_reads);
+    }
+
+    #[test]
+    fn test_connect_strategy_per_platform() {
+        let macos = PlatformConfig::macos().connect_strategy;
+        assert!(macos.retry_on_peer_removed_pairing);
+        assert!(!macos.refresh_scan_before_connect && !macos.invalidate_service_cache);
+
+        let linux = PlatformConfig::linux().connect_strategy;
+        assert!(linux.refresh_scan_before_connect);
+        assert!(!linux.retry_on_peer_removed_pairing && !linux.invalidate_service_cache);
+
+        let windows = PlatformConfig::windows().connect_strategy;
+        assert!(windows.invalidate_service_cache);
+        assert!(!windows.retry_on_peer_removed_pairing && !windows.refresh_scan_before_connect);
+
+        assert_eq!(
+            PlatformConfig::default().connect_strategy,
+            ConnectStrategy::none()
+        );
+    }
+
     #[test]
     fn test_current_platform_config() {
         let config = PlatformConfig::for_current_platform();
@@ -0,0 +1,268 @@
+//! Per-device operation queue.
+//!
+//! BLE stacks handle one GATT request per connection at a time. When two
+//! tasks share a [`Device`](crate::Device) (e.g. a `read_current` poll while
+//! a history sync is running), their reads and writes would otherwise
+//! interleave and fail with "operation in progress" errors, or worse, split
+//! a multi-step history download. Every device operation therefore runs
+//! through an [`OperationQueue`]:
+//!
+//! - Operations run one at a time, in the order they were queued.
+//! - An operation that calls other operations on the same device (a history
+//!   download reading characteristics) runs them inline instead of queueing
+//!   behind itself. The turn belongs to the running task, so this does
+//!   not extend to tasks it spawns: those queue like any other caller, and
+//!   an operation that waits on such a task deadlocks.
+//! - Once [`ConnectionConfig::max_queued_operations`](crate::ConnectionConfig::max_queued_operations)
+//!   operations are waiting, further ones fail with [`Error::Busy`] instead
+//!   of piling up behind a long sync.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use tokio::sync::Semaphore;
+
+use crate::error::{Error, Result};
+
+tokio::task_local! {
+    /// IDs of the queues whose turn the current task holds.
+    static HELD_QUEUES: Vec<u64>;
+}
+
+static NEXT_QUEUE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Serializes the operations on one device.
+///
+/// Nested calls are recognised through a task-local, which `tokio::spawn`
+/// does not inherit. Code inside [`run`](Self::run) must not spawn a task
+/// that runs operations on the same queue and then await it; run the
+/// nested operations on the current task instead.
+#[derive(Debug)]
+pub(crate) struct OperationQueue {
+    id: u64,
+    turn: Semaphore,
+    queued: AtomicUsize,
+    max_queued: usize,
+}
+
+impl OperationQueue {
+    /// Create a queue that rejects operations once `max_queued` are waiting.
+    pub(crate) fn new(max_queued: usize) -> Self {
+        Self {
+            id: NEXT_QUEUE_ID.fetch_add(1, Ordering::Relaxed),
+            // Tokio's semaphore is fair: waiters are served first come,
+            // first served
+            turn: Semaphore::new(1),
+            queued: AtomicUsize::new(0),
+            max_queued,
+        }
+    }
+
+    /// Run `operation` once every operation queued before it has finished.
+    pub(crate) async fn run<T, F>(&self, operation: &str, fut: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let held = HELD_QUEUES
+            .try_with(|held| held.clone())
+            .unwrap_or_default();
+        if held.contains(&self.id) {
+            // Already running inside an operation on this device
+            return fut.await;
+        }
+
+        let _permit = {
+            let waiting = Waiting::enter(&self.queued);
+            if waiting.ahead >= self.max_queued {
+                return Err(Error::Busy {
+                    operation: operation.to_string(),
+                    queued: waiting.ahead,
+                });
+            }
+            self.turn.acquire().await.map_err(|_| Error::Cancelled)?
+        };
+
+        let mut held = held;
+        held.push(self.id);
+        HELD_QUEUES.scope(held, fut).await
+    }
+
+    /// Number of operations waiting for their turn.
+    pub(crate) fn queued(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+}
+
+/// Counts an operation as queued until it gets its turn or is dropped.
+struct Waiting<'a> {
+    queued: &'a AtomicUsize,
+    /// Operations that were already waiting.
+    ahead: usize,
+}
+
+impl<'a> Waiting<'a> {
+    fn enter(queued: &'a AtomicUsize) -> Self {
+        let ahead = queued.fetch_add(1, Ordering::Relaxed);
+        Self { queued, ahead }
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.queued.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_operations_do_not_interleave() {
+        let queue = Arc::new(OperationQueue::new(16));
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let tasks: Vec<_> = (0..4)
+            .map(|i| {
+                let queue = Arc::clone(&queue);
+                let log = Arc::clone(&log);
+                tokio::spawn(async move {
+                    queue
+                        .run("test", async {
+                            log.lock().unwrap().push(format!("start {i}"));
+                            tokio::time::sleep(Duration::from_millis(5)).await;
+                            log.lock().unwrap().push(format!("end {i}"));
+                            Ok(())
+                        })
+                        .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        let log = log.lock().unwrap();
+        for pair in log.chunks(2) {
+            assert_eq!(pair[0].replace("start", "end"), pair[1]);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_operations_run_in_queue_order() {
+        let queue = Arc::new(OperationQueue::new(16));
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let mut tasks = Vec::new();
+        for i in 0..5 {
+            let queue = Arc::clone(&queue);
+            let order = Arc::clone(&order);
+            tasks.push(tokio::spawn(async move {
+                queue
+                    .run("test", async {
+                        order.lock().unwrap().push(i);
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        Ok(())
+                    })
+                    .await
+            }));
+            // Let each task queue up before spawning the next
+            tokio::task::yield_now().await;
+        }
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_nested_operations_run_inline() {
+        let queue = OperationQueue::new(16);
+        let other = OperationQueue::new(16);
+
+        let result = queue
+            .run("outer", async {
+                let inner = queue.run("inner", async { Ok(1) }).await?;
+                // Other devices are still queued separately
+                let other_device = other
+                    .run("other", async {
+                        assert_eq!(other.turn.available_permits(), 0);
+                        Ok(2)
+                    })
+                    .await?;
+                Ok(inner + other_device)
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(queue.turn.available_permits(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_spawned_operations_queue_behind_the_spawner() {
+        let queue = Arc::new(OperationQueue::new(16));
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let spawned = queue
+            .run("outer", async {
+                let queue = Arc::clone(&queue);
+                let order_inner = Arc::clone(&order);
+                let spawned = tokio::spawn(async move {
+                    queue
+                        .run("spawned", async {
+                            order_inner.lock().unwrap().push("spawned");
+                            Ok(())
+                        })
+                        .await
+                });
+                // The spawned task does not hold this turn, so it waits
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                assert!(!spawned.is_finished());
+                order.lock().unwrap().push("outer");
+                Ok(spawned)
+            })
+            .await
+            .unwrap();
+
+        spawned.await.unwrap().unwrap();
+        assert_eq!(*order.lock().unwrap(), vec!["outer", "spawned"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rejects_when_queue_is_full() {
+        let queue = Arc::new(OperationQueue::new(1));
+
+        let long = {
+            let queue = Arc::clone(&queue);
+            tokio::spawn(async move {
+                queue
+                    .run("sync history", async {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        Ok(())
+                    })
+                    .await
+            })
+        };
+        tokio::task::yield_now().await;
+        let waiting = {
+            let queue = Arc::clone(&queue);
+            tokio::spawn(async move { queue.run("read current", async { Ok(()) }).await })
+        };
+        tokio::task::yield_now().await;
+        assert_eq!(queue.queued(), 1);
+
+        let err = queue
+            .run("read battery", async { Ok(()) })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Busy { queued: 1, .. }));
+
+        long.await.unwrap().unwrap();
+        waiting.await.unwrap().unwrap();
+        assert_eq!(queue.queued(), 0);
+    }
+}
@@ -0,0 +1,205 @@
+//! Firmware-specific protocol quirks.
+//!
+//! Older firmware revisions differ from the protocol the rest of this crate
+//! assumes. Rather than checking versions at each call site, differences are
+//! listed once in a registry keyed by device type and firmware version range.
+//! [`Device::read_device_info`](crate::device::Device::read_device_info)
+//! selects the matching [`Quirks`] automatically, and reads and history
+//! downloads consult them.
+//!
+//! # Example
+//!
+//! ```
+//! use aranet_core::quirks::{FirmwareVersion, Quirks};
+//! use aranet_types::DeviceType;
+//!
+//! let version = FirmwareVersion::parse("v1.1.2").unwrap();
+//! assert_eq!(version, FirmwareVersion::new(1, 1, 2));
+//!
+//! let quirks = Quirks::lookup(DeviceType::Aranet4, "v1.1.2");
+//! assert!(quirks.basic_readings);
+//! assert!(Quirks::lookup(DeviceType::Aranet4, "v1.4.19").is_empty());
+//! ```
+
+use std::fmt;
+
+use aranet_types::DeviceType;
+
+/// A parsed firmware version such as `v1.4.19`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FirmwareVersion {
+    /// Major version.
+    pub major: u16,
+    /// Minor version.
+    pub minor: u16,
+    /// Patch version (0 if not reported).
+    pub patch: u16,
+}
+
+impl FirmwareVersion {
+    /// Create a firmware version.
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse a firmware revision string as reported by the device.
+    ///
+    /// Accepts an optional `v` prefix and two or three numeric components
+    /// (`v1.4.19`, `1.4`). Returns `None` for anything else.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().trim_end_matches('\0');
+        let s = s
+            .strip_prefix('v')
+            .or_else(|| s.strip_prefix('V'))
+            .unwrap_or(s);
+
+        let mut parts = s.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch = match parts.next() {
+            Some(patch) => patch.parse().ok()?,
+            None => 0,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A single protocol difference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quirk {
+    /// The detailed readings characteristic is missing; current readings
+    /// come from the basic characteristic without interval and age.
+    BasicReadings,
+    /// Only the notification-based (V1) history protocol is supported.
+    HistoryV1,
+}
+
+/// A registry entry: a quirk that applies to a device type within a
+/// firmware version range.
+#[derive(Debug, Clone, Copy)]
+pub struct QuirkEntry {
+    /// Device type the quirk applies to.
+    pub device_type: DeviceType,
+    /// First affected version (inclusive), or `None` for all earlier versions.
+    pub from: Option<FirmwareVersion>,
+    /// First fixed version (exclusive), or `None` if still present.
+    pub until: Option<FirmwareVersion>,
+    /// The quirk.
+    pub quirk: Quirk,
+}
+
+impl QuirkEntry {
+    /// Whether this entry applies to the given device and firmware.
+    pub fn matches(&self, device_type: DeviceType, version: FirmwareVersion) -> bool {
+        self.device_type == device_type
+            && self.from.is_none_or(|from| version >= from)
+            && self.until.is_none_or(|until| version < until)
+    }
+}
+
+/// Known firmware quirks.
+///
+/// Aranet4 firmware before v1.2.0 predates the detailed readings
+/// characteristic and the read-based history protocol.
+pub const KNOWN_QUIRKS: &[QuirkEntry] = &[
+    QuirkEntry {
+        device_type: DeviceType::Aranet4,
+        from: None,
+        until: Some(FirmwareVersion::new(1, 2, 0)),
+        quirk: Quirk::BasicReadings,
+    },
+    QuirkEntry {
+        device_type: DeviceType::Aranet4,
+        from: None,
+        until: Some(FirmwareVersion::new(1, 2, 0)),
+        quirk: Quirk::HistoryV1,
+    },
+];
+
+/// The quirks in effect for a connected device.
+///
+/// The default has no quirks, which is also what devices with an unknown
+/// type or unparseable firmware version get.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Quirks {
+    /// Read current values from the basic characteristic.
+    pub basic_readings: bool,
+    /// Download history with the V1 protocol.
+    pub history_v1: bool,
+}
+
+impl Quirks {
+    /// Look up the quirks for a device type and firmware revision string.
+    pub fn lookup(device_type: DeviceType, firmware: &str) -> Self {
+        FirmwareVersion::parse(firmware)
+            .map(|version| Self::for_version(device_type, version))
+            .unwrap_or_default()
+    }
+
+    /// Look up the quirks for a device type and parsed firmware version.
+    pub fn for_version(device_type: DeviceType, version: FirmwareVersion) -> Self {
+        KNOWN_QUIRKS
+            .iter()
+            .filter(|entry| entry.matches(device_type, version))
+            .fold(Self::default(), |quirks, entry| quirks.with(entry.quirk))
+    }
+
+    /// Return these quirks with `quirk` enabled.
+    pub fn with(mut self, quirk: Quirk) -> Self {
+        match quirk {
+            Quirk::BasicReadings => self.basic_readings = true,
+            Quirk::HistoryV1 => self.history_v1 = true,
+        }
+        self
+    }
+
+    /// Whether no quirks apply.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_firmware_version() {
+        assert_eq!(
+            FirmwareVersion::parse("v1.4.19"),
+            Some(FirmwareVersion::new(1, 4, 19))
+        );
+        assert_eq!(
+            FirmwareVersion::parse("1.2\0"),
+            Some(FirmwareVersion::new(1, 2, 0))
+        );
+        assert_eq!(FirmwareVersion::parse(""), None);
+        assert_eq!(FirmwareVersion::parse("v1"), None);
+        assert_eq!(FirmwareVersion::parse("v1.2.3.4"), None);
+        assert_eq!(FirmwareVersion::parse("beta"), None);
+        assert!(FirmwareVersion::new(1, 10, 0) > FirmwareVersion::new(1, 9, 9));
+    }
+
+    #[test]
+    fn test_quirks_lookup_by_version_range() {
+        let old = Quirks::lookup(DeviceType::Aranet4, "v1.1.9");
+        assert!(old.basic_readings && old.history_v1);
+
+        assert!(Quirks::lookup(DeviceType::Aranet4, "v1.2.0").is_empty());
+        assert!(Quirks::lookup(DeviceType::Aranet2, "v1.1.0").is_empty());
+        assert!(Quirks::lookup(DeviceType::Aranet4, "unknown").is_empty());
+    }
+}
@@ -8,6 +8,7 @@
 
 use bytes::Buf;
 
+use crate::advice::{Advice, AdviceConfig, advise};
 use crate::error::{Error, Result};
 use aranet_types::{CurrentReading, DeviceType, Status};
 
@@ -31,6 +32,19 @@ pub struct ExtendedReading {
     pub radiation_duration: Option<u64>,
 }
 
+impl ExtendedReading {
+    /// Ventilation advice for this reading, using the default
+    /// [`AdviceConfig`].
+    ///
+    /// `co2_slope` is the recent change in ppm per minute, e.g. from
+    /// [`advice::co2_slope`](crate::advice::co2_slope) over the last half
+    /// hour. Returns `None` for devices without a CO₂ sensor.
+    pub fn advice(&self, co2_slope: Option<f32>) -> Option<Advice> {
+        (self.reading.co2 > 0)
+            .then(|| advise(self.reading.co2, co2_slope, &AdviceConfig::default()))
+    }
+}
+
 /// Parse Aranet4 current readings from the detailed characteristic.
 ///
 /// Format (13 bytes):
@@ -46,6 +60,25 @@ pub fn parse_aranet4_reading(data: &[u8]) -> Result<CurrentReading> {
     CurrentReading::from_bytes(data).map_err(|e| Error::InvalidData(e.to_string()))
 }
 
+/// Parse Aranet4 current readings from the basic characteristic (f0cd1503).
+///
+/// Used for firmware without the detailed characteristic (see
+/// [`Quirk::BasicReadings`](crate::quirks::Quirk::BasicReadings)). The layout
+/// matches the first 9 bytes of [`parse_aranet4_reading`]; interval and age
+/// are not reported and are left at 0.
+pub fn parse_aranet4_basic_reading(data: &[u8]) -> Result<CurrentReading> {
+    if data.len() < 9 {
+        return Err(Error::InvalidData(format!(
+            "Aranet4 basic reading requires 9 bytes, got {}",
+            data.len()
+        )));
+    }
+
+    let mut detailed = [0u8; 13];
+    detailed[..9].copy_from_slice(&data[..9]);
+    parse_aranet4_reading(&detailed)
+}
+
 /// Parse Aranet2 current readings from GATT characteristic (f0cd3003).
 ///
 /// Delegates to [`CurrentReading::from_bytes_aranet2`].
@@ -288,6 +321,27 @@ mod tests {
         assert_eq!(reading.age, 120);
     }
 
+    #[test]
+    fn test_parse_aranet4_basic_reading() {
+        // 9-byte basic characteristic: no interval or age
+        let data: [u8; 9] = [
+            0x20, 0x03, // CO2 = 800
+            0xC2, 0x01, // temp_raw = 450 (22.5°C)
+            0x94, 0x27, // pressure_raw = 10132 (1013.2 hPa)
+            45,   // humidity
+            85,   // battery
+            1,    // status = Green
+        ];
+
+        let reading = parse_aranet4_basic_reading(&data).unwrap();
+        assert_eq!(reading.co2, 800);
+        assert!((reading.pressure - 1013.2).abs() < 0.1);
+        assert_eq!(reading.status, Status::Green);
+        assert_eq!((reading.interval, reading.age), (0, 0));
+
+        assert!(parse_aranet4_basic_reading(&data[..8]).is_err());
+    }
+
     #[test]
     fn test_parse_aranet4_reading_high_co2() {
         // High CO2 reading - red status
@@ -5,24 +5,40 @@
 //!
 //! [`ReconnectingDevice`] implements the [`AranetDevice`] trait,
 //! allowing it to be used interchangeably with regular devices in generic code.
+//!
+//! Notification subscriptions made through
+//! [`ReconnectingDevice::subscribe_to_notifications`] are restored on every
+//! reconnect, and [`ReadingStream`](crate::ReadingStream)s created with
+//! [`DeviceStreamExt`] keep running across reconnects, sending an
+//! [`Error::StreamGap`] marker before the first reading after one.
+//!
+//! Given an [`AdapterMonitor`] (see [`ReconnectingDevice::with_adapter_monitor`]),
+//! reconnection pauses while Bluetooth is off instead of spending attempts on
+//! it, and resumes when the adapter comes back.
 
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 
 use tokio::sync::RwLock;
 use tokio::time::sleep;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
 
 use aranet_types::{CurrentReading, DeviceInfo, DeviceType, HistoryRecord};
 
+use crate::adapter::AdapterMonitor;
 use crate::device::Device;
 use crate::error::{Error, Result};
 use crate::events::{DeviceEvent, DeviceId, EventSender};
 use crate::history::{HistoryInfo, HistoryOptions};
 use crate::settings::{CalibrationData, MeasurementInterval};
+use crate::streaming::{DeviceStreamExt, ReadingStream, StreamOptions};
 use crate::traits::AranetDevice;
 
+/// Callback for notifications on one characteristic.
+type NotificationCallback = Arc<dyn Fn(&[u8]) + Send + Sync>;
+
 /// Options for automatic reconnection.
 #[derive(Debug, Clone)]
 pub struct ReconnectOptions {
@@ -179,10 +195,16 @@ pub struct ReconnectingDevice {
     attempt_count: RwLock<u32>,
     /// Cancellation flag for stopping reconnection attempts.
     cancelled: Arc<AtomicBool>,
+    /// Adapter power state; reconnection waits while the adapter is off.
+    adapter: Option<Arc<AdapterMonitor>>,
     /// Cached device name (populated on first connection).
     cached_name: std::sync::OnceLock<String>,
     /// Cached device type (populated on first connection).
     cached_device_type: std::sync::OnceLock<DeviceType>,
+    /// Notification subscriptions to restore after a reconnect.
+    subscriptions: std::sync::Mutex<Vec<(Uuid, NotificationCallback)>>,
+    /// Number of successful reconnects.
+    reconnects: AtomicU64,
 }
 
 impl ReconnectingDevice {
@@ -210,8 +232,11 @@ impl ReconnectingDevice {
             event_sender: None,
             attempt_count: RwLock::new(0),
             cancelled: Arc::new(AtomicBool::new(false)),
+            adapter: None,
             cached_name,
             cached_device_type,
+            subscriptions: std::sync::Mutex::new(Vec::new()),
+            reconnects: AtomicU64::new(0),
         })
     }
 
@@ -226,6 +251,17 @@ impl ReconnectingDevice {
         Ok(this)
     }
 
+    /// Pause reconnection while `adapter` reports Bluetooth as off.
+    ///
+    /// Connection errors with an adapter-off signature are reported to the
+    /// monitor and do not count towards `max_attempts`. Use the monitor from
+    /// [`DeviceManager::adapter`](crate::DeviceManager::adapter) or start one
+    /// with [`AdapterMonitor::start`] so the pause ends when the adapter returns.
+    pub fn with_adapter_monitor(mut self, adapter: Arc<AdapterMonitor>) -> Self {
+        self.adapter = Some(adapter);
+        self
+    }
+
     /// Cancel any ongoing reconnection attempts.
     ///
     /// This will cause the reconnect loop to exit on its next iteration.
@@ -268,6 +304,71 @@ impl ReconnectingDevice {
         &self.identifier
     }
 
+    /// Number of times the connection has been re-established.
+    pub fn reconnect_count(&self) -> u64 {
+        self.reconnects.load(Ordering::SeqCst)
+    }
+
+    /// Subscribe to notifications on a characteristic, and again after every
+    /// reconnect.
+    ///
+    /// See [`Device::subscribe_to_notifications`].
+    pub async fn subscribe_to_notifications<F>(&self, uuid: Uuid, callback: F) -> Result<()>
+    where
+        F: Fn(&[u8]) + Send + Sync + 'static,
+    {
+        let callback: NotificationCallback = Arc::new(callback);
+        let subscribe = Arc::clone(&callback);
+        self.run_with_reconnect(move |d| {
+            let callback = Arc::clone(&subscribe);
+            Box::pin(async move {
+                d.subscribe_to_notifications(uuid, move |data| callback(data))
+                    .await
+            })
+        })
+        .await?;
+
+        let mut subscriptions = self.subscriptions.lock().unwrap_or_else(|e| e.into_inner());
+        subscriptions.retain(|(subscribed, _)| *subscribed != uuid);
+        subscriptions.push((uuid, callback));
+        Ok(())
+    }
+
+    /// Unsubscribe from notifications on a characteristic and stop restoring
+    /// the subscription on reconnect.
+    pub async fn unsubscribe_from_notifications(&self, uuid: Uuid) -> Result<()> {
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|(subscribed, _)| *subscribed != uuid);
+        self.run_with_reconnect(move |d| Box::pin(d.unsubscribe_from_notifications(uuid)))
+            .await
+    }
+
+    /// Re-subscribe `device` to every active notification subscription.
+    ///
+    /// Failures are logged rather than failing the reconnect; the
+    /// subscription is tried again on the next one.
+    async fn restore_subscriptions(&self, device: &Device) {
+        let subscriptions = self
+            .subscriptions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        for (uuid, callback) in subscriptions {
+            match device
+                .subscribe_to_notifications(uuid, move |data| callback(data))
+                .await
+            {
+                Ok(()) => debug!("Restored notifications for {} on {}", uuid, self.identifier),
+                Err(e) => warn!(
+                    "Failed to restore notifications for {} on {}: {}",
+                    uuid, self.identifier, e
+                ),
+            }
+        }
+    }
+
     /// Execute an operation, reconnecting if necessary.
     ///
     /// The closure is called with a reference to the device. If the operation
@@ -385,6 +486,30 @@ impl ReconnectingDevice {
                 return Err(Error::Cancelled);
             }
 
+            if let Some(adapter) = &self.adapter
+                && !adapter.is_available()
+            {
+                info!(
+                    "Bluetooth adapter unavailable, pausing reconnection to {}",
+                    self.identifier
+                );
+                // Poll for cancellation while waiting
+                while tokio::time::timeout(Duration::from_secs(1), adapter.wait_until_available())
+                    .await
+                    .is_err()
+                {
+                    if self.is_cancelled() {
+                        *self.state.write().await = ConnectionState::Disconnected;
+                        info!("Reconnection cancelled for {}", self.identifier);
+                        return Err(Error::Cancelled);
+                    }
+                }
+                info!(
+                    "Bluetooth adapter available, resuming reconnection to {}",
+                    self.identifier
+                );
+            }
+
             let attempt = {
                 let mut count = self.attempt_count.write().await;
                 *count += 1;
@@ -426,7 +551,12 @@ impl ReconnectingDevice {
             // Try to connect
             match Device::connect(&self.identifier).await {
                 Ok(new_device) => {
+                    if let Some(adapter) = &self.adapter {
+                        adapter.observe_success();
+                    }
+                    self.restore_subscriptions(&new_device).await;
                     *self.device.write().await = Some(Arc::new(new_device));
+                    self.reconnects.fetch_add(1, Ordering::SeqCst);
                     *self.state.write().await = ConnectionState::Connected;
 
                     // Send reconnect succeeded event
@@ -442,6 +572,12 @@ impl ReconnectingDevice {
                 }
                 Err(e) => {
                     warn!("Reconnection attempt {} failed: {}", attempt, e);
+                    if let Some(adapter) = &self.adapter
+                        && adapter.observe_error(&e)
+                    {
+                        // The adapter being off is not the device's fault
+                        *self.attempt_count.write().await -= 1;
+                    }
                 }
             }
         }
@@ -572,9 +708,97 @@ impl AranetDevice for ReconnectingDevice {
     }
 }
 
+impl DeviceStreamExt for ReconnectingDevice {
+    fn stream(self: Arc<Self>) -> ReadingStream {
+        ReadingStream::from_reconnecting(self, StreamOptions::default())
+    }
+
+    fn stream_with_options(self: Arc<Self>, options: StreamOptions) -> ReadingStream {
+        ReadingStream::from_reconnecting(self, options)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::diagnostics::AdapterState;
+    use crate::events::{EventDispatcher, EventRecorder};
+
+    /// A wrapper that has lost its connection, without connecting first.
+    fn disconnected(options: ReconnectOptions, events: EventSender) -> ReconnectingDevice {
+        ReconnectingDevice {
+            identifier: "test-device".to_string(),
+            device: RwLock::new(None),
+            options,
+            state: RwLock::new(ConnectionState::Disconnected),
+            event_sender: Some(events),
+            attempt_count: RwLock::new(0),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            adapter: None,
+            cached_name: std::sync::OnceLock::new(),
+            cached_device_type: std::sync::OnceLock::new(),
+            subscriptions: std::sync::Mutex::new(Vec::new()),
+            reconnects: AtomicU64::new(0),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reconnect_waits_for_adapter_then_reports_attempt() {
+        let events = EventDispatcher::new(16);
+        let mut recorder = EventRecorder::new(&events.sender());
+        let adapter = Arc::new(AdapterMonitor::with_events(events.sender()));
+        adapter.set_state(AdapterState::PoweredOff);
+
+        let device = Arc::new(
+            disconnected(
+                ReconnectOptions::fixed_delay(Duration::from_secs(60)),
+                events.sender(),
+            )
+            .with_adapter_monitor(Arc::clone(&adapter)),
+        );
+        let task = {
+            let device = Arc::clone(&device);
+            tokio::spawn(async move { device.reconnect().await })
+        };
+
+        // No attempts while the adapter is off
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert_eq!(device.state().await, ConnectionState::Reconnecting);
+        recorder.expect_sequence(["adapter_state_changed"]);
+
+        adapter.set_state(AdapterState::Available);
+        let started = recorder
+            .wait_for("reconnect_started", Duration::from_secs(5))
+            .await;
+        assert!(matches!(
+            started,
+            Some(DeviceEvent::ReconnectStarted { attempt: 1, .. })
+        ));
+
+        // Cancelled during the back-off delay, before connecting
+        device.cancel_reconnect();
+        assert!(matches!(task.await.unwrap(), Err(Error::Cancelled)));
+        assert_eq!(device.state().await, ConnectionState::Disconnected);
+        recorder.expect_sequence([
+            "adapter_state_changed",
+            "adapter_state_changed",
+            "reconnect_started",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_gives_up_without_events_when_out_of_attempts() {
+        let events = EventDispatcher::new(16);
+        let mut recorder = EventRecorder::new(&events.sender());
+        let device = disconnected(ReconnectOptions::new().max_attempts(0), events.sender());
+
+        assert!(matches!(
+            device.reconnect().await,
+            Err(Error::Timeout { .. })
+        ));
+        assert_eq!(device.state().await, ConnectionState::Failed);
+        recorder.expect_sequence([]);
+    }
 
     #[test]
     fn test_reconnect_options_default() {
@@ -340,6 +340,8 @@ fn is_retryable(error: &Error) -> bool {
     match error {
         // Timeout errors are usually transient
         Error::Timeout { .. } => true,
+        // The overall budget is spent, so another attempt cannot succeed
+        Error::TimeoutExceeded { .. } => false,
         // Bluetooth errors are often transient
         Error::Bluetooth(_) => true,
         // Connection failed - check the reason
@@ -368,12 +370,20 @@ fn is_retryable(error: &Error) -> bool {
         Error::CharacteristicNotFound { .. } => false,
         // Cancelled is not retryable
         Error::Cancelled => false,
+        // The queue drains as operations finish
+        Error::Busy { .. } => true,
+        // A gap marker is not a failed operation
+        Error::StreamGap { .. } => false,
         // I/O errors might be transient
         Error::Io(_) => true,
         // Invalid configuration is not retryable
         Error::InvalidConfig(_) => false,
         // Unsupported operations are not retryable
         Error::Unsupported(_) => false,
+        // A lost bond fails until the device is paired again
+        Error::BondLost { .. } => false,
+        // Annotated errors are classified by what they wrap
+        Error::Context(ctx) => is_retryable(ctx.error()),
     }
 }
 
@@ -424,6 +434,11 @@ mod tests {
         }));
         assert!(is_retryable(&Error::NotConnected));
         assert!(!is_retryable(&Error::InvalidData("test".to_string())));
+        assert!(!is_retryable(&Error::TimeoutExceeded {
+            operation: "test".to_string(),
+            budget: Duration::from_secs(1),
+            attempts: Vec::new(),
+        }));
         assert!(!is_retryable(&Error::DeviceNotFound(
             DeviceNotFoundReason::NotFound {
                 identifier: "test".to_string()
@@ -2,13 +2,23 @@
 //!
 //! This module provides functionality to scan for Aranet devices
 //! using Bluetooth Low Energy.
-
+//!
+//! Devices found by [`scan_with_options`] and by the `find_device` functions
+//! are remembered in a [`ScanCache`] in the platform data directory. When a
+//! device was seen recently, finding it again stops scanning as soon as it
+//! reappears instead of waiting out the full scan; [`cached_devices`] lists
+//! recent sightings without scanning at all.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use btleplug::api::{Central, Manager as _, Peripheral as _, ScanFilter};
+use btleplug::api::{Central, Manager as _, Peripheral as _};
 use btleplug::platform::{Adapter, Manager, Peripheral, PeripheralId};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 use tokio::sync::RwLock;
-use tokio::time::sleep;
+use tokio::time::{Instant, sleep};
 use tracing::{debug, info, warn};
 
 /// Cached BLE manager — avoids creating a new D-Bus connection on every call.
@@ -49,10 +59,12 @@ async fn reset_manager() {
     }
 }
 
+use crate::diagnostics::{OperationType, global_diagnostics};
 use crate::error::{Error, Result};
+use crate::scan_service::ScanService;
 use crate::util::{create_identifier, format_peripheral_id};
 use crate::uuid::{MANUFACTURER_ID, SAF_TEHNIKA_SERVICE_NEW, SAF_TEHNIKA_SERVICE_OLD};
-use aranet_types::DeviceType;
+use aranet_types::{DeviceName, DeviceType};
 
 /// Progress update for device finding operations.
 #[derive(Debug, Clone)]
@@ -98,6 +110,82 @@ pub struct DiscoveredDevice {
     pub manufacturer_data: Option<Vec<u8>>,
 }
 
+impl DiscoveredDevice {
+    /// The advertised name split into model and short serial.
+    ///
+    /// Use [`DeviceName::is_same_device`] to recognize a device that was
+    /// stored under a different identifier.
+    pub fn parsed_name(&self) -> Option<DeviceName> {
+        self.name.as_deref().and_then(DeviceName::parse)
+    }
+
+    /// A normalized identifier that compares equal across scans.
+    pub fn stable_id(&self) -> StableId {
+        StableId::new(&self.identifier, self.name.as_deref())
+    }
+}
+
+/// Normalized, comparable identity of a discovered device.
+///
+/// The identifier is lowercased and trimmed, so a MAC address reported as
+/// `AA:BB:..` on one run and `aa:bb:..` on another (or a macOS peripheral
+/// UUID in either case) compares equal. Ordering puts devices whose name
+/// carries a short serial first, by serial, then the rest by identifier,
+/// which is the order [`sort_discovered`] produces.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StableId {
+    /// Lowercased MAC address, or peripheral UUID on macOS.
+    pub identifier: String,
+    /// Short serial parsed from the advertised name, uppercased.
+    pub serial: Option<String>,
+}
+
+impl StableId {
+    /// Normalize a connection identifier and advertised name.
+    pub fn new(identifier: &str, name: Option<&str>) -> Self {
+        Self {
+            identifier: identifier.trim().to_ascii_lowercase(),
+            serial: name
+                .and_then(DeviceName::parse)
+                .and_then(|name| name.short_serial),
+        }
+    }
+}
+
+impl Ord for StableId {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.serial
+            .is_none()
+            .cmp(&other.serial.is_none())
+            .then_with(|| self.serial.cmp(&other.serial))
+            .then_with(|| self.identifier.cmp(&other.identifier))
+    }
+}
+
+impl PartialOrd for StableId {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for StableId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.serial {
+            Some(serial) => write!(f, "{} ({})", self.identifier, serial),
+            None => f.write_str(&self.identifier),
+        }
+    }
+}
+
+/// Sort devices into a deterministic order by [`StableId`].
+///
+/// Adapters list peripherals in whatever order they were seen, so without
+/// this the same set of devices reshuffles between runs. Scan results are
+/// already sorted.
+pub fn sort_discovered(devices: &mut [DiscoveredDevice]) {
+    devices.sort_by_cached_key(DiscoveredDevice::stable_id);
+}
+
 /// Options for scanning.
 #[derive(Debug, Clone)]
 pub struct ScanOptions {
@@ -108,6 +196,9 @@ pub struct ScanOptions {
     /// Use targeted BLE scan filter for Aranet service UUIDs.
     /// This reduces noise from non-Aranet devices but may not work on all platforms.
     pub use_service_filter: bool,
+    /// How recent a [`ScanCache`] sighting must be for the `find_device`
+    /// functions to use it, or `None` to ignore the cache.
+    pub cache_max_age: Option<Duration>,
 }
 
 impl Default for ScanOptions {
@@ -118,6 +209,7 @@ impl Default for ScanOptions {
             // Default to false for maximum compatibility - service filtering
             // may not work on all platforms/adapters
             use_service_filter: false,
+            cache_max_age: Some(DEFAULT_CACHE_MAX_AGE),
         }
     }
 }
@@ -163,6 +255,15 @@ impl ScanOptions {
         self
     }
 
+    /// Set how recent a cached sighting must be to use it, or `None` to
+    /// always scan from scratch.
+    ///
+    /// Default: [`DEFAULT_CACHE_MAX_AGE`]
+    pub fn cache_max_age(mut self, max_age: Option<Duration>) -> Self {
+        self.cache_max_age = max_age;
+        self
+    }
+
     /// Create optimized scan options for finding Aranet devices quickly.
     ///
     /// Uses service UUID filtering if available and a shorter scan duration.
@@ -171,6 +272,7 @@ impl ScanOptions {
             duration: Duration::from_secs(3),
             filter_aranet_only: true,
             use_service_filter: true,
+            cache_max_age: Some(DEFAULT_CACHE_MAX_AGE),
         }
     }
 }
@@ -218,9 +320,19 @@ pub async fn scan_for_devices() -> Result<Vec<DiscoveredDevice>> {
 }
 
 /// Scan for devices with custom options.
+///
+/// Runs as a subscriber of the shared [`ScanService`], so concurrent scans
+/// from different parts of the application do not conflict.
 pub async fn scan_with_options(options: ScanOptions) -> Result<Vec<DiscoveredDevice>> {
-    let adapter = get_adapter().await?;
-    scan_with_adapter(&adapter, options).await
+    let started = std::time::Instant::now();
+    let result = ScanService::global().scan(options).await;
+    global_diagnostics()
+        .record_outcome(OperationType::Scan, started, None, &result)
+        .await;
+    if let Ok(devices) = &result {
+        remember_devices(devices.iter().map(CachedDevice::from));
+    }
+    result
 }
 
 /// Scan for devices with retry logic for flaky Bluetooth environments.
@@ -280,40 +392,27 @@ pub async fn scan_with_retry(
 }
 
 /// Scan for devices using a specific adapter.
+///
+/// Runs as a subscriber of the shared [`ScanService`] on `adapter`; if
+/// another scan is already running, this joins it on the service's adapter
+/// instead. Pass the adapter from [`ScanService::adapter`] to avoid that.
 pub async fn scan_with_adapter(
     adapter: &Adapter,
     options: ScanOptions,
 ) -> Result<Vec<DiscoveredDevice>> {
-    info!(
-        "Starting BLE scan for {} seconds (service_filter={})...",
-        options.duration.as_secs(),
-        options.use_service_filter
-    );
-
-    // Create scan filter - optionally filter for Aranet service UUIDs
-    let scan_filter = if options.use_service_filter {
-        ScanFilter {
-            services: vec![SAF_TEHNIKA_SERVICE_NEW, SAF_TEHNIKA_SERVICE_OLD],
-        }
-    } else {
-        ScanFilter::default()
-    };
-
-    // Start scanning
-    adapter.start_scan(scan_filter).await?;
-
-    // Wait for the scan duration
-    sleep(options.duration).await;
-
-    // Stop scanning
-    adapter.stop_scan().await?;
+    ScanService::global().scan_on(adapter, options).await
+}
 
-    // Get discovered peripherals
+/// Collect the devices an adapter has seen so far.
+pub(crate) async fn collect_discovered(
+    adapter: &Adapter,
+    filter_aranet_only: bool,
+) -> Result<Vec<DiscoveredDevice>> {
     let peripherals = adapter.peripherals().await?;
     let mut discovered = Vec::new();
 
     for peripheral in peripherals {
-        match process_peripheral(&peripheral, options.filter_aranet_only).await {
+        match process_peripheral(&peripheral, filter_aranet_only).await {
             Ok(Some(device)) => {
                 info!("Found Aranet device: {:?}", device.name);
                 discovered.push(device);
@@ -327,7 +426,7 @@ pub async fn scan_with_adapter(
         }
     }
 
-    info!("Scan complete. Found {} device(s)", discovered.len());
+    sort_discovered(&mut discovered);
     Ok(discovered)
 }
 
@@ -432,6 +531,10 @@ pub async fn find_device_with_options(
 ///
 /// This avoids creating a new btleplug `Manager` (and D-Bus connection) on
 /// every call.  The caller is responsible for keeping the `Adapter` alive.
+///
+/// Scans run through the shared [`ScanService`], so pass the adapter from
+/// [`ScanService::adapter`]: the returned peripheral is looked up on the
+/// adapter the shared scan actually runs on.
 pub async fn find_device_with_adapter(
     adapter: &Adapter,
     identifier: &str,
@@ -459,6 +562,22 @@ pub async fn find_device_with_adapter_progress(
         return Ok(peripheral);
     }
 
+    // A recent sighting means the device is advertising: scan until it shows
+    // up rather than for the full duration, and also match it by the
+    // identifier it was seen under, which is known before its name is
+    let cached = options
+        .cache_max_age
+        .and_then(|max_age| ScanCache::load_default().find(identifier, max_age).cloned());
+    let mut targets = vec![identifier_lower.clone()];
+    if let Some(device) = &cached {
+        info!(
+            "Device was seen {}s ago as {}",
+            device.age().as_secs(),
+            device.identifier
+        );
+        targets.push(device.identifier.to_lowercase());
+    }
+
     let max_attempts: u32 = 3;
     let base_duration = options.duration.as_millis() as u64 / 2;
     let base_duration = Duration::from_millis(base_duration.max(2000));
@@ -480,15 +599,24 @@ pub async fn find_device_with_adapter_progress(
             });
         }
 
-        adapter.start_scan(ScanFilter::default()).await?;
-        sleep(scan_duration).await;
-        adapter.stop_scan().await?;
+        let subscription = ScanService::global().subscribe_on(adapter, false).await?;
+        let scanning = subscription.adapter();
+        let found = if cached.is_some() {
+            wait_for_peripheral(scanning, &targets, scan_duration).await?
+        } else {
+            sleep(scan_duration).await;
+            find_any_peripheral(scanning, &targets).await?
+        };
+        subscription.stop().await?;
 
-        if let Some(peripheral) = find_peripheral_by_identifier(adapter, &identifier_lower).await? {
+        if let Some(peripheral) = found {
             info!("Found device on attempt {}", attempt);
             if let Some(ref cb) = progress {
                 cb(FindProgress::Found { attempt });
             }
+            if let Ok(Some(device)) = process_peripheral(&peripheral, false).await {
+                remember_devices([CachedDevice::from(&device)]);
+            }
             return Ok(peripheral);
         }
 
@@ -516,12 +644,45 @@ pub async fn find_device_with_progress(
     options: ScanOptions,
     progress: Option<ProgressCallback>,
 ) -> Result<(Adapter, Peripheral)> {
-    let adapter = get_adapter().await?;
+    let adapter = ScanService::global().adapter().await?;
     let peripheral =
         find_device_with_adapter_progress(&adapter, identifier, options, progress).await?;
     Ok((adapter, peripheral))
 }
 
+/// How often [`wait_for_peripheral`] checks the adapter's peripherals.
+const CACHED_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Return the first known peripheral matching any of `targets`.
+async fn find_any_peripheral(adapter: &Adapter, targets: &[String]) -> Result<Option<Peripheral>> {
+    for target in targets {
+        if let Some(peripheral) = find_peripheral_by_identifier(adapter, target).await? {
+            return Ok(Some(peripheral));
+        }
+    }
+    Ok(None)
+}
+
+/// Poll the adapter until a peripheral matching any of `targets` appears,
+/// for at most `timeout`.
+async fn wait_for_peripheral(
+    adapter: &Adapter,
+    targets: &[String],
+    timeout: Duration,
+) -> Result<Option<Peripheral>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(peripheral) = find_any_peripheral(adapter, targets).await? {
+            return Ok(Some(peripheral));
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(None);
+        }
+        sleep(CACHED_POLL_INTERVAL.min(deadline - now)).await;
+    }
+}
+
 /// Search through known peripherals to find one matching the identifier.
 async fn find_peripheral_by_identifier(
     adapter: &Adapter,
@@ -562,6 +723,193 @@ async fn find_peripheral_by_identifier(
     Ok(None)
 }
 
+// ==================== Scan cache ====================
+
+/// How recent a sighting must be for the `find_device` functions to use it by
+/// default.
+pub const DEFAULT_CACHE_MAX_AGE: Duration = Duration::from_secs(60);
+
+/// Sightings older than this are dropped when the cache is saved.
+const SCAN_CACHE_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// A device as last seen by a scan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedDevice {
+    /// The advertised name, if any.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The connection identifier (peripheral ID on macOS, address elsewhere).
+    pub identifier: String,
+    /// The BLE address (may be zeros on macOS).
+    pub address: String,
+    /// RSSI when last seen.
+    #[serde(default)]
+    pub rssi: Option<i16>,
+    /// When the device was last seen.
+    #[serde(with = "time::serde::rfc3339")]
+    pub seen_at: OffsetDateTime,
+}
+
+impl CachedDevice {
+    /// Time since the device was last seen.
+    pub fn age(&self) -> Duration {
+        (OffsetDateTime::now_utc() - self.seen_at)
+            .try_into()
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Whether `identifier` names this device, using the same rules as
+    /// [`find_device`]: identifier, address with or without colons, or part
+    /// of the name, ignoring case.
+    pub fn matches(&self, identifier: &str) -> bool {
+        let identifier = identifier.to_lowercase();
+        let address = self.address.to_lowercase();
+        self.identifier.to_lowercase().contains(&identifier)
+            || (address != "00:00:00:00:00:00"
+                && address.replace(':', "") == identifier.replace(':', ""))
+            || self
+                .name
+                .as_ref()
+                .is_some_and(|name| name.to_lowercase().contains(&identifier))
+    }
+}
+
+impl From<&DiscoveredDevice> for CachedDevice {
+    fn from(device: &DiscoveredDevice) -> Self {
+        Self {
+            name: device.name.clone(),
+            identifier: device.identifier.clone(),
+            address: device.address.clone(),
+            rssi: device.rssi,
+            seen_at: OffsetDateTime::now_utc(),
+        }
+    }
+}
+
+/// Recent scan results, stored as JSON so later processes can skip or
+/// shorten their scans.
+#[derive(Debug, Clone)]
+pub struct ScanCache {
+    path: PathBuf,
+    devices: BTreeMap<String, CachedDevice>,
+}
+
+impl ScanCache {
+    /// Load the cache from `path`, starting empty if the file does not exist.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let devices = match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                let list: Vec<CachedDevice> = serde_json::from_str(&content).map_err(|e| {
+                    Error::InvalidData(format!("Invalid scan cache {}: {e}", path.display()))
+                })?;
+                list.into_iter()
+                    .map(|d| (d.identifier.to_lowercase(), d))
+                    .collect()
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, devices })
+    }
+
+    /// Load the cache at [`scan_cache_path`], starting empty if it cannot be
+    /// read.
+    pub fn load_default() -> Self {
+        let path = scan_cache_path();
+        Self::load(&path).unwrap_or_else(|e| {
+            debug!("Ignoring scan cache: {e}");
+            Self {
+                path,
+                devices: BTreeMap::new(),
+            }
+        })
+    }
+
+    /// Where the cache is saved.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write the cache to disk, dropping sightings older than a week.
+    pub fn save(&mut self) -> Result<()> {
+        self.devices
+            .retain(|_, device| device.age() <= SCAN_CACHE_RETENTION);
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let list: Vec<&CachedDevice> = self.devices.values().collect();
+        let json = serde_json::to_string_pretty(&list)
+            .map_err(|e| Error::InvalidData(format!("Failed to serialize scan cache: {e}")))?;
+        std::fs::write(&self.path, json)?;
+        Ok(())
+    }
+
+    /// Record sightings, replacing earlier ones for the same identifiers.
+    pub fn record(&mut self, devices: impl IntoIterator<Item = CachedDevice>) {
+        for device in devices {
+            self.devices
+                .insert(device.identifier.to_lowercase(), device);
+        }
+    }
+
+    /// Devices seen within `max_age`, most recent first.
+    pub fn devices(&self, max_age: Duration) -> Vec<CachedDevice> {
+        let mut devices: Vec<CachedDevice> = self
+            .devices
+            .values()
+            .filter(|device| device.age() <= max_age)
+            .cloned()
+            .collect();
+        devices.sort_by_key(|device| std::cmp::Reverse(device.seen_at));
+        devices
+    }
+
+    /// The most recent sighting within `max_age` matching `identifier`.
+    pub fn find(&self, identifier: &str, max_age: Duration) -> Option<&CachedDevice> {
+        self.devices
+            .values()
+            .filter(|device| device.age() <= max_age && device.matches(identifier))
+            .max_by_key(|device| device.seen_at)
+    }
+}
+
+/// Where the scan cache is stored.
+///
+/// Checks `ARANET_DATA_DIR` first, then falls back to the platform data
+/// directory, next to the readings database:
+/// - Linux: `~/.local/share/aranet/scan_cache.json`
+/// - macOS: `~/Library/Application Support/aranet/scan_cache.json`
+/// - Windows: `C:\Users\<user>\AppData\Local\aranet\scan_cache.json`
+pub fn scan_cache_path() -> PathBuf {
+    std::env::var_os("ARANET_DATA_DIR")
+        .map(PathBuf::from)
+        .or_else(|| dirs::data_local_dir().map(|d| d.join("aranet")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("scan_cache.json")
+}
+
+/// Devices seen by any scan within `max_age`, most recent first, without
+/// scanning.
+///
+/// Reads the cache at [`scan_cache_path`]; an unreadable cache counts as
+/// empty.
+pub fn cached_devices(max_age: Duration) -> Vec<CachedDevice> {
+    ScanCache::load_default().devices(max_age)
+}
+
+/// Add sightings to the default cache. Failures are only logged: the cache
+/// is an optimization.
+fn remember_devices(devices: impl IntoIterator<Item = CachedDevice>) {
+    let mut cache = ScanCache::load_default();
+    cache.record(devices);
+    if let Err(e) = cache.save() {
+        debug!("Failed to save scan cache {}: {e}", cache.path().display());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -715,4 +1063,99 @@ mod tests {
     //
     // The DiscoveredDevice struct derives Clone and Debug, so these traits are
     // guaranteed to work correctly by the compiler.
+
+    // ==================== StableId Tests ====================
+
+    #[test]
+    fn test_stable_id_normalizes_identifier() {
+        let upper = StableId::new(" AA:BB:CC:DD:EE:FF ", Some("Aranet4 17c3c"));
+        let lower = StableId::new("aa:bb:cc:dd:ee:ff", Some("Aranet4 17C3C"));
+        assert_eq!(upper, lower);
+        assert_eq!(upper.serial.as_deref(), Some("17C3C"));
+        assert_eq!(upper.to_string(), "aa:bb:cc:dd:ee:ff (17C3C)");
+        assert_eq!(StableId::new("AB-CD", Some("Speaker")).serial, None);
+    }
+
+    #[test]
+    fn test_stable_id_ordering() {
+        let mut ids = [
+            StableId::new("33:33:33:33:33:33", None),
+            StableId::new("22:22:22:22:22:22", Some("Aranet2 B0000")),
+            StableId::new("11:11:11:11:11:11", Some("Aranet4")),
+            StableId::new("44:44:44:44:44:44", Some("Aranet4 A0000")),
+        ];
+        ids.sort();
+        let identifiers: Vec<_> = ids.iter().map(|id| &id.identifier[..2]).collect();
+        // Serials first, then devices without one by identifier
+        assert_eq!(identifiers, ["44", "22", "11", "33"]);
+    }
+
+    // ==================== ScanCache Tests ====================
+
+    fn cached(name: &str, address: &str, seconds_ago: i64) -> CachedDevice {
+        CachedDevice {
+            name: Some(name.to_string()),
+            identifier: address.to_string(),
+            address: address.to_string(),
+            rssi: Some(-60),
+            seen_at: OffsetDateTime::now_utc() - time::Duration::seconds(seconds_ago),
+        }
+    }
+
+    #[test]
+    fn test_cached_device_matches() {
+        let device = cached("Aranet4 17C3C", "AA:BB:CC:DD:EE:FF", 0);
+        assert!(device.matches("aranet4 17c3c"));
+        assert!(device.matches("17C3C"));
+        assert!(device.matches("aa:bb:cc:dd:ee:ff"));
+        assert!(device.matches("AABBCCDDEEFF"));
+        assert!(!device.matches("Aranet2"));
+
+        let macos = CachedDevice {
+            identifier: "6f1c0a4e-3b2d-4c5e-9f80-112233445566".to_string(),
+            address: "00:00:00:00:00:00".to_string(),
+            ..device
+        };
+        assert!(macos.matches("6F1C0A4E-3B2D-4C5E-9F80-112233445566"));
+        assert!(!macos.matches("000000000000"));
+    }
+
+    #[test]
+    fn test_scan_cache_max_age() {
+        let path = std::env::temp_dir().join(format!(
+            "aranet-scan-cache-test-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let mut cache = ScanCache::load(&path).unwrap();
+        assert!(cache.devices(Duration::MAX).is_empty());
+        cache.record([
+            cached("Aranet4 17C3C", "AA:BB:CC:DD:EE:FF", 5),
+            cached("Aranet2 A1B2C", "11:22:33:44:55:66", 300),
+            cached("Aranet Radon 0A1B2", "66:55:44:33:22:11", 30 * 24 * 60 * 60),
+        ]);
+        cache.save().unwrap();
+
+        let loaded = ScanCache::load(&path).unwrap();
+        let names = |devices: Vec<CachedDevice>| {
+            devices
+                .into_iter()
+                .filter_map(|d| d.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(loaded.devices(Duration::MAX)),
+            ["Aranet4 17C3C", "Aranet2 A1B2C"]
+        );
+        assert_eq!(
+            names(loaded.devices(Duration::from_secs(60))),
+            ["Aranet4 17C3C"]
+        );
+        assert!(loaded.find("17C3C", Duration::from_secs(60)).is_some());
+        assert!(loaded.find("A1B2C", Duration::from_secs(60)).is_none());
+        assert!(loaded.find("A1B2C", Duration::from_secs(600)).is_some());
+
+        std::fs::remove_file(&path).unwrap();
+    }
 }
@@ -0,0 +1,425 @@
+//! One shared BLE scan for every consumer in the process.
+//!
+//! An adapter runs a single discovery session. When two modules scan at the
+//! same time they fight over it: on macOS a second `start_scan` replaces the
+//! first, BlueZ rejects it as already in progress, and whoever finishes first
+//! stops the scan for everyone else.
+//!
+//! [`ScanService`] owns that session and hands out [`ScanSubscription`]s. The
+//! scan starts with the first subscriber and stops when the last one is
+//! released, so the device manager, the passive monitor and UI device pickers
+//! can all scan concurrently. [`scan_with_options`](crate::scan::scan_with_options)
+//! and the passive monitor go through it automatically.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use aranet_core::ScanService;
+//!
+//! # async fn example() -> aranet_core::Result<()> {
+//! let subscription = ScanService::global().subscribe(false).await?;
+//! tokio::time::sleep(Duration::from_secs(5)).await;
+//! let adapter = subscription.adapter().clone();
+//! subscription.stop().await?;
+//! // Peripherals seen while subscribed are now available from `adapter`
+//! # Ok(())
+//! # }
+//! ```
+
+use btleplug::api::{Central, ScanFilter};
+use btleplug::platform::Adapter;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::error::Result;
+use crate::scan::{DiscoveredDevice, ScanOptions, collect_discovered, get_adapter};
+use crate::uuid::{SAF_TEHNIKA_SERVICE_NEW, SAF_TEHNIKA_SERVICE_OLD};
+
+static SCAN_SERVICE: ScanService = ScanService {
+    state: Mutex::const_new(ScanState {
+        adapter: None,
+        generation: 0,
+        subscribers: 0,
+        filtered: None,
+    }),
+};
+
+/// Multiplexes one underlying BLE scan across many subscribers.
+///
+/// Use [`ScanService::global`] to get the process-wide instance.
+#[derive(Debug)]
+pub struct ScanService {
+    state: Mutex<ScanState>,
+}
+
+#[derive(Debug)]
+struct ScanState {
+    /// Adapter the shared scan runs on.
+    adapter: Option<Adapter>,
+    /// Bumped by [`ScanService::reset_adapter`]; subscriptions from an older
+    /// generation no longer count towards the running scan.
+    generation: u64,
+    /// Number of live subscriptions in the current generation.
+    subscribers: usize,
+    /// Whether the running scan filters by Aranet service UUIDs;
+    /// `None` when no scan is running.
+    filtered: Option<bool>,
+}
+
+impl ScanState {
+    /// The filter the scan must be (re)started with for a new subscriber, or
+    /// `None` if the running scan already covers it.
+    ///
+    /// A filtered scan is widened if an unfiltered subscriber joins; an
+    /// unfiltered scan already covers filtered subscribers.
+    fn wanted_filter(&self, use_service_filter: bool) -> Option<bool> {
+        match self.filtered {
+            None => Some(use_service_filter),
+            Some(true) if !use_service_filter => Some(false),
+            Some(_) => None,
+        }
+    }
+
+    /// Count a subscriber in, returning the generation it belongs to.
+    fn add_subscriber(&mut self) -> u64 {
+        self.subscribers += 1;
+        self.generation
+    }
+
+    /// Count a subscriber of `generation` out.
+    ///
+    /// Returns `true` if it was the last one and the scan must be stopped.
+    fn remove_subscriber(&mut self, generation: u64) -> bool {
+        if generation != self.generation {
+            // Its scan was stopped when the adapter was reset
+            return false;
+        }
+        self.subscribers = self.subscribers.saturating_sub(1);
+        self.subscribers == 0 && self.filtered.take().is_some()
+    }
+
+    /// Start a new generation with no adapter and no subscribers.
+    ///
+    /// Returns `true` if a scan was running and must be stopped.
+    fn reset(&mut self) -> bool {
+        self.generation += 1;
+        self.subscribers = 0;
+        self.filtered.take().is_some()
+    }
+}
+
+impl ScanService {
+    /// The process-wide scan service.
+    pub fn global() -> &'static ScanService {
+        &SCAN_SERVICE
+    }
+
+    /// The adapter the shared scan runs on, acquiring one if needed.
+    ///
+    /// Look up and connect to peripherals through this adapter: on some
+    /// platforms each adapter handle only sees what it scanned itself.
+    pub async fn adapter(&self) -> Result<Adapter> {
+        let mut state = self.state.lock().await;
+        match &state.adapter {
+            Some(adapter) => Ok(adapter.clone()),
+            None => {
+                let adapter = get_adapter().await?;
+                state.adapter = Some(adapter.clone());
+                Ok(adapter)
+            }
+        }
+    }
+
+    /// Join the shared scan, starting it if nobody else is scanning.
+    ///
+    /// With `use_service_filter`, the platform is asked to report only
+    /// devices advertising the Aranet services. A filtered scan that is
+    /// already running is widened if an unfiltered subscriber joins; an
+    /// unfiltered scan already covers filtered subscribers.
+    pub async fn subscribe(&'static self, use_service_filter: bool) -> Result<ScanSubscription> {
+        self.subscribe_inner(None, use_service_filter).await
+    }
+
+    /// Join the shared scan, running it on `adapter` if nobody else is
+    /// scanning.
+    ///
+    /// While other subscribers are active the scan stays on the adapter it
+    /// was started on, so pass the one from [`adapter`](Self::adapter) and
+    /// read peripherals from [`ScanSubscription::adapter`].
+    pub async fn subscribe_on(
+        &'static self,
+        adapter: &Adapter,
+        use_service_filter: bool,
+    ) -> Result<ScanSubscription> {
+        self.subscribe_inner(Some(adapter), use_service_filter)
+            .await
+    }
+
+    async fn subscribe_inner(
+        &'static self,
+        preferred: Option<&Adapter>,
+        use_service_filter: bool,
+    ) -> Result<ScanSubscription> {
+        let mut state = self.state.lock().await;
+
+        let adapter = match (&state.adapter, preferred) {
+            (Some(adapter), _) if state.subscribers > 0 => adapter.clone(),
+            (_, Some(preferred)) => {
+                state.adapter = Some(preferred.clone());
+                preferred.clone()
+            }
+            (Some(adapter), None) => adapter.clone(),
+            (None, None) => {
+                let adapter = get_adapter().await?;
+                state.adapter = Some(adapter.clone());
+                adapter
+            }
+        };
+
+        if let Some(filtered) = state.wanted_filter(use_service_filter) {
+            if state.filtered.take().is_some() {
+                debug!("Widening shared scan to all devices");
+                if let Err(e) = adapter.stop_scan().await {
+                    debug!("Failed to stop filtered scan before widening: {e}");
+                }
+            } else {
+                info!("Starting shared BLE scan (service_filter={filtered})");
+            }
+            if let Err(e) = adapter.start_scan(scan_filter(filtered)).await {
+                // The adapter may have gone away; fetch a fresh one next time
+                if state.subscribers == 0 {
+                    state.adapter = None;
+                }
+                return Err(e.into());
+            }
+            state.filtered = Some(filtered);
+        }
+
+        let generation = state.add_subscriber();
+        debug!("Scan subscriber joined ({} active)", state.subscribers);
+        Ok(ScanSubscription {
+            service: self,
+            adapter,
+            generation,
+            released: false,
+        })
+    }
+
+    /// Scan for `options.duration` as one subscriber and return the devices
+    /// seen by the adapter.
+    pub async fn scan(&'static self, options: ScanOptions) -> Result<Vec<DiscoveredDevice>> {
+        let subscription = self.subscribe(options.use_service_filter).await?;
+        Self::scan_as(subscription, options).await
+    }
+
+    /// Like [`scan`](Self::scan), running the scan on `adapter` if nobody
+    /// else is scanning. See [`subscribe_on`](Self::subscribe_on).
+    pub async fn scan_on(
+        &'static self,
+        adapter: &Adapter,
+        options: ScanOptions,
+    ) -> Result<Vec<DiscoveredDevice>> {
+        let subscription = self
+            .subscribe_on(adapter, options.use_service_filter)
+            .await?;
+        Self::scan_as(subscription, options).await
+    }
+
+    async fn scan_as(
+        subscription: ScanSubscription,
+        options: ScanOptions,
+    ) -> Result<Vec<DiscoveredDevice>> {
+        info!(
+            "Scanning for {} seconds (service_filter={})...",
+            options.duration.as_secs(),
+            options.use_service_filter
+        );
+
+        sleep(options.duration).await;
+        let adapter = subscription.adapter().clone();
+        subscription.stop().await?;
+
+        let discovered = collect_discovered(&adapter, options.filter_aranet_only).await?;
+        info!("Scan complete. Found {} device(s)", discovered.len());
+        Ok(discovered)
+    }
+
+    /// Number of live subscriptions.
+    pub async fn subscriber_count(&self) -> usize {
+        self.state.lock().await.subscribers
+    }
+
+    /// Stop the shared scan and forget the cached adapter, so the next
+    /// subscription acquires a new one and starts a fresh scan.
+    ///
+    /// Call this after repeated scan failures, in case the adapter was reset
+    /// or the D-Bus connection died. Existing subscriptions stop receiving
+    /// results and releasing them no longer affects the new scan.
+    pub async fn reset_adapter(&self) {
+        let mut state = self.state.lock().await;
+        let adapter = state.adapter.take();
+        if state.reset()
+            && let Some(adapter) = adapter
+        {
+            info!("Stopping shared BLE scan on the old adapter");
+            if let Err(e) = adapter.stop_scan().await {
+                debug!("Failed to stop scan on the old adapter: {e}");
+            }
+        }
+    }
+
+    async fn release(&self, generation: u64) -> Result<()> {
+        let mut state = self.state.lock().await;
+        let stop = state.remove_subscriber(generation);
+        debug!("Scan subscriber left ({} active)", state.subscribers);
+
+        if stop && let Some(adapter) = &state.adapter {
+            info!("Stopping shared BLE scan");
+            adapter.stop_scan().await?;
+        }
+        Ok(())
+    }
+}
+
+/// A share of the running BLE scan.
+///
+/// The scan keeps running while any subscription is alive. Call
+/// [`stop`](Self::stop) when done to see errors from stopping the scan;
+/// dropping the subscription releases it in the background.
+#[derive(Debug)]
+pub struct ScanSubscription {
+    service: &'static ScanService,
+    adapter: Adapter,
+    generation: u64,
+    released: bool,
+}
+
+impl ScanSubscription {
+    /// The adapter the scan runs on, for reading discovered peripherals.
+    pub fn adapter(&self) -> &Adapter {
+        &self.adapter
+    }
+
+    /// Leave the scan, stopping it if this was the last subscriber.
+    pub async fn stop(mut self) -> Result<()> {
+        self.released = true;
+        self.service.release(self.generation).await
+    }
+}
+
+impl Drop for ScanSubscription {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+        // Dropped without `stop`, e.g. when a scanning future is cancelled
+        let service = self.service;
+        let generation = self.generation;
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = service.release(generation).await {
+                        warn!("Failed to stop shared scan: {e}");
+                    }
+                });
+            }
+            Err(_) => warn!("Scan subscription dropped outside a runtime; scan left running"),
+        }
+    }
+}
+
+fn scan_filter(filtered: bool) -> ScanFilter {
+    if filtered {
+        ScanFilter {
+            services: vec![SAF_TEHNIKA_SERVICE_NEW, SAF_TEHNIKA_SERVICE_OLD],
+        }
+    } else {
+        ScanFilter::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn idle() -> ScanState {
+        ScanState {
+            adapter: None,
+            generation: 0,
+            subscribers: 0,
+            filtered: None,
+        }
+    }
+
+    /// Join as `subscribe` does, recording the scan that was started.
+    fn join(state: &mut ScanState, use_service_filter: bool) -> (u64, Option<bool>) {
+        let started = state.wanted_filter(use_service_filter);
+        if let Some(filtered) = started {
+            state.filtered = Some(filtered);
+        }
+        (state.add_subscriber(), started)
+    }
+
+    #[test]
+    fn test_first_subscriber_starts_scan_and_last_stops_it() {
+        let mut state = idle();
+        let (first, started) = join(&mut state, true);
+        assert_eq!(started, Some(true));
+        let (second, started) = join(&mut state, true);
+        assert_eq!(started, None);
+        assert_eq!(state.subscribers, 2);
+
+        assert!(!state.remove_subscriber(first));
+        assert_eq!(state.filtered, Some(true));
+        assert!(state.remove_subscriber(second));
+        assert_eq!((state.subscribers, state.filtered), (0, None));
+
+        // A stray extra release neither underflows nor stops anything
+        assert!(!state.remove_subscriber(second));
+        assert_eq!(state.subscribers, 0);
+    }
+
+    #[test]
+    fn test_unfiltered_subscriber_widens_filtered_scan() {
+        let mut state = idle();
+        join(&mut state, true);
+        assert_eq!(join(&mut state, false).1, Some(false));
+        // An unfiltered scan already covers filtered subscribers
+        assert_eq!(join(&mut state, true).1, None);
+        assert_eq!(state.filtered, Some(false));
+    }
+
+    #[test]
+    fn test_reset_stops_scan_and_ignores_old_subscribers() {
+        let mut state = idle();
+        let (old_a, _) = join(&mut state, false);
+        let (old_b, _) = join(&mut state, false);
+
+        // The running scan must be stopped on the old adapter
+        assert!(state.reset());
+        assert_eq!((state.subscribers, state.filtered), (0, None));
+
+        // The next subscriber starts exactly one fresh scan
+        let (new, started) = join(&mut state, false);
+        assert_eq!(started, Some(false));
+        assert_ne!(new, old_a);
+
+        // Old subscriptions leaving do not stop or miscount the new scan
+        assert!(!state.remove_subscriber(old_a));
+        assert!(!state.remove_subscriber(old_b));
+        assert_eq!(state.subscribers, 1);
+        assert_eq!(state.filtered, Some(false));
+
+        assert!(state.remove_subscriber(new));
+    }
+
+    #[test]
+    fn test_reset_when_idle_has_nothing_to_stop() {
+        let mut state = idle();
+        let (generation, _) = join(&mut state, true);
+        assert!(state.remove_subscriber(generation));
+        assert!(!state.reset());
+    }
+}
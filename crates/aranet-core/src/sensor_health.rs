@@ -0,0 +1,257 @@
+//! CO₂ sensor calibration state and health.
+//!
+//! The Aranet4 reports the state of its CO₂ sensor calibration in bits 2-3
+//! of the flags byte at the start of its manufacturer data. The byte is
+//! advertised whether or not Smart Home integration is enabled, so the state
+//! can be read from a plain scan. Whether automatic calibration is enabled
+//! and the calibration offset are only available over a connection; see
+//! [`Device::read_sensor_health`].
+//!
+//! Other Aranet devices have no CO₂ sensor and report no calibration state.
+
+use serde::{Deserialize, Serialize};
+
+use aranet_types::DeviceType;
+
+use crate::advertisement::AdvertisementData;
+use crate::device::Device;
+use crate::error::{Error, Result};
+
+/// Flags bit set while a firmware update is in progress.
+const FLAG_DFU_ACTIVE: u8 = 1 << 4;
+
+/// State of the CO₂ sensor calibration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalibrationState {
+    /// No calibration running.
+    #[default]
+    Idle,
+    /// A calibration was asked to finish and is wrapping up.
+    EndRequested,
+    /// Calibration is running; CO₂ readings are unreliable until it ends.
+    InProgress,
+    /// The last calibration failed.
+    Error,
+}
+
+impl CalibrationState {
+    /// Decode the state from an advertisement flags byte.
+    pub fn from_flags(flags: u8) -> Self {
+        match (flags >> 2) & 0b11 {
+            0 => CalibrationState::Idle,
+            1 => CalibrationState::EndRequested,
+            2 => CalibrationState::InProgress,
+            _ => CalibrationState::Error,
+        }
+    }
+}
+
+impl std::fmt::Display for CalibrationState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalibrationState::Idle => write!(f, "idle"),
+            CalibrationState::EndRequested => write!(f, "finishing"),
+            CalibrationState::InProgress => write!(f, "in progress"),
+            CalibrationState::Error => write!(f, "failed"),
+        }
+    }
+}
+
+/// Something about the CO₂ sensor that deserves the user's attention.
+///
+/// This enum is marked `#[non_exhaustive]` to allow adding new warning types
+/// in future versions without breaking downstream code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum SensorHealthWarning {
+    /// The last calibration failed.
+    CalibrationFailed,
+    /// A calibration is running.
+    CalibrationInProgress,
+    /// Automatic calibration is off, so nothing corrects baseline drift.
+    AutoCalibrationDisabled,
+    /// A firmware update is in progress.
+    FirmwareUpdateActive,
+}
+
+impl std::fmt::Display for SensorHealthWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SensorHealthWarning::CalibrationFailed => {
+                write!(
+                    f,
+                    "CO₂ calibration failed; recalibrate in fresh outdoor air"
+                )
+            }
+            SensorHealthWarning::CalibrationInProgress => {
+                write!(f, "CO₂ calibration in progress; readings are unreliable")
+            }
+            SensorHealthWarning::AutoCalibrationDisabled => write!(
+                f,
+                "Automatic calibration is off; the CO₂ baseline may be stale unless calibrated manually"
+            ),
+            SensorHealthWarning::FirmwareUpdateActive => write!(f, "Firmware update in progress"),
+        }
+    }
+}
+
+/// Calibration state and health of a CO₂ sensor.
+///
+/// Fields are `None` when the source they come from was not available.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SensorHealth {
+    /// Calibration state from the latest advertisement.
+    pub calibration_state: Option<CalibrationState>,
+    /// Whether automatic (baseline) calibration is enabled.
+    pub auto_calibration_enabled: Option<bool>,
+    /// CO₂ calibration offset.
+    pub co2_offset: Option<i16>,
+    /// Whether a firmware update is in progress.
+    pub firmware_update_active: bool,
+}
+
+impl SensorHealth {
+    /// Health from raw Aranet manufacturer data.
+    ///
+    /// Returns `None` for devices without a CO₂ sensor. Works whether or not
+    /// Smart Home integration is enabled.
+    pub fn from_manufacturer_data(data: &[u8], name: Option<&str>) -> Option<Self> {
+        let is_aranet4 =
+            name.is_some_and(|n| n.starts_with("Aranet4")) || data.len() == 7 || data.len() == 22;
+        let flags = *data.first().filter(|_| is_aranet4)?;
+        Some(Self::from_flags(flags))
+    }
+
+    /// Health from a parsed advertisement, or `None` for devices without a
+    /// CO₂ sensor.
+    pub fn from_advertisement(data: &AdvertisementData) -> Option<Self> {
+        (data.device_type == DeviceType::Aranet4).then(|| Self::from_flags(data.flags))
+    }
+
+    fn from_flags(flags: u8) -> Self {
+        Self {
+            calibration_state: Some(CalibrationState::from_flags(flags)),
+            firmware_update_active: flags & FLAG_DFU_ACTIVE != 0,
+            ..Default::default()
+        }
+    }
+
+    /// Warnings for this sensor, most severe first.
+    pub fn warnings(&self) -> Vec<SensorHealthWarning> {
+        let mut warnings = Vec::new();
+        match self.calibration_state {
+            Some(CalibrationState::Error) => warnings.push(SensorHealthWarning::CalibrationFailed),
+            Some(CalibrationState::InProgress | CalibrationState::EndRequested) => {
+                warnings.push(SensorHealthWarning::CalibrationInProgress)
+            }
+            Some(CalibrationState::Idle) | None => {}
+        }
+        if self.auto_calibration_enabled == Some(false) {
+            warnings.push(SensorHealthWarning::AutoCalibrationDisabled);
+        }
+        if self.firmware_update_active {
+            warnings.push(SensorHealthWarning::FirmwareUpdateActive);
+        }
+        warnings
+    }
+
+    /// Returns `true` if there is nothing to warn about.
+    pub fn is_healthy(&self) -> bool {
+        self.warnings().is_empty()
+    }
+}
+
+impl Device {
+    /// Read the CO₂ sensor's calibration state and health.
+    ///
+    /// Combines the calibration flag from the device's last advertisement
+    /// with the auto-calibration setting and calibration offset read over
+    /// the connection. Parts that cannot be read are left as `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unsupported`] for devices without a CO₂ sensor.
+    pub async fn read_sensor_health(&self) -> Result<SensorHealth> {
+        if self.device_type().is_some_and(|t| t != DeviceType::Aranet4) {
+            return Err(Error::Unsupported(
+                "Device has no CO₂ sensor to calibrate".to_string(),
+            ));
+        }
+
+        let mut health = match self.advertised_manufacturer_data().await {
+            Some(data) => {
+                SensorHealth::from_manufacturer_data(&data, self.name()).unwrap_or_default()
+            }
+            None => SensorHealth::default(),
+        };
+        health.auto_calibration_enabled = self
+            .get_settings_cached()
+            .await
+            .ok()
+            .map(|settings| settings.auto_calibration_enabled);
+        health.co2_offset = self
+            .get_calibration()
+            .await
+            .ok()
+            .and_then(|calibration| calibration.co2_offset);
+        Ok(health)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calibration_state_from_flags() {
+        assert_eq!(CalibrationState::from_flags(0x22), CalibrationState::Idle);
+        assert_eq!(
+            CalibrationState::from_flags(0x24),
+            CalibrationState::EndRequested
+        );
+        assert_eq!(
+            CalibrationState::from_flags(0x08),
+            CalibrationState::InProgress
+        );
+        assert_eq!(CalibrationState::from_flags(0x0C), CalibrationState::Error);
+    }
+
+    #[test]
+    fn test_health_from_manufacturer_data() {
+        // Aranet4 with Smart Home off: 7 bytes, no measurements
+        let health = SensorHealth::from_manufacturer_data(&[0x1C, 0, 0, 0, 0, 0, 0], None).unwrap();
+        assert_eq!(health.calibration_state, Some(CalibrationState::Error));
+        assert!(health.firmware_update_active);
+
+        // Aranet2 has no CO₂ sensor
+        assert!(
+            SensorHealth::from_manufacturer_data(&[0x01, 0x20, 0, 0], Some("Aranet2 1A2B"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_warnings() {
+        let health = SensorHealth {
+            calibration_state: Some(CalibrationState::Idle),
+            auto_calibration_enabled: Some(true),
+            ..Default::default()
+        };
+        assert!(health.is_healthy());
+
+        let health = SensorHealth {
+            calibration_state: Some(CalibrationState::InProgress),
+            auto_calibration_enabled: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(
+            health.warnings(),
+            vec![
+                SensorHealthWarning::CalibrationInProgress,
+                SensorHealthWarning::AutoCalibrationDisabled
+            ]
+        );
+    }
+}
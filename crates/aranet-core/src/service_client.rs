@@ -23,6 +23,7 @@
 //! # }
 //! ```
 
+use aranet_types::{CurrentReading, Status};
 use reqwest::{Client, Method, RequestBuilder};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
@@ -163,6 +164,106 @@ pub struct HealthResponse {
     pub timestamp: OffsetDateTime,
 }
 
+/// Lifecycle of a history sync job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncJobStatus {
+    /// Waiting for the service's Bluetooth adapter.
+    Pending,
+    /// Downloading history.
+    Running,
+    /// Finished successfully.
+    Completed,
+    /// Finished with an error.
+    Failed,
+}
+
+impl SyncJobStatus {
+    /// Whether the job has completed or failed.
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+}
+
+/// An on-demand history sync run by the service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncJob {
+    pub id: u64,
+    pub device_id: String,
+    pub status: SyncJobStatus,
+    /// Records to download, once known.
+    #[serde(default)]
+    pub total_records: Option<u16>,
+    /// Download progress from 0.0 to 1.0.
+    #[serde(default)]
+    pub progress: f32,
+    /// Records downloaded from the device.
+    #[serde(default)]
+    pub downloaded: usize,
+    /// Records that were new to the service's database.
+    #[serde(default)]
+    pub inserted: usize,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Latest stored reading of a device, as reported by the service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrentReadingResponse {
+    pub device_id: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub captured_at: OffsetDateTime,
+    pub co2: u16,
+    pub temperature: f32,
+    pub pressure: f32,
+    pub humidity: u8,
+    pub battery: u8,
+    pub status: Status,
+    #[serde(default)]
+    pub radon: Option<u32>,
+    #[serde(default)]
+    pub radiation_rate: Option<f32>,
+    #[serde(default)]
+    pub radiation_total: Option<f64>,
+    #[serde(default)]
+    pub radon_avg_24h: Option<u32>,
+    #[serde(default)]
+    pub radon_avg_7d: Option<u32>,
+    #[serde(default)]
+    pub radon_avg_30d: Option<u32>,
+    /// Age of the reading in seconds when the service answered.
+    pub age_seconds: i64,
+    /// Whether the service considers the reading stale (older than three
+    /// poll intervals).
+    pub stale: bool,
+}
+
+impl CurrentReadingResponse {
+    /// Convert to a [`CurrentReading`].
+    ///
+    /// `age` is set from `age_seconds`, saturating at `u16::MAX`; `interval`
+    /// is unknown and set to 0.
+    pub fn to_reading(&self) -> CurrentReading {
+        CurrentReading {
+            co2: self.co2,
+            temperature: self.temperature,
+            pressure: self.pressure,
+            humidity: self.humidity,
+            battery: self.battery,
+            status: self.status,
+            interval: 0,
+            age: u16::try_from(self.age_seconds.max(0)).unwrap_or(u16::MAX),
+            captured_at: Some(self.captured_at),
+            radon: self.radon,
+            radiation_rate: self.radiation_rate,
+            radiation_total: self.radiation_total,
+            radon_avg_24h: self.radon_avg_24h,
+            radon_avg_7d: self.radon_avg_7d,
+            radon_avg_30d: self.radon_avg_30d,
+        }
+    }
+}
+
 // ==========================================================================
 // ServiceClient Implementation
 // ==========================================================================
@@ -288,6 +389,30 @@ impl ServiceClient {
         self.delete(&url).await
     }
 
+    /// Get the latest reading the service has stored for a device.
+    ///
+    /// The service answers from its database; no Bluetooth connection is made.
+    pub async fn current_reading(&self, device_id: &str) -> Result<CurrentReadingResponse> {
+        let url = format!("{}/api/devices/{}/current", self.base_url, device_id);
+        self.get(&url).await
+    }
+
+    /// Ask the service to sync a monitored device's history.
+    ///
+    /// Returns immediately with the job; poll it with
+    /// [`sync_job`](Self::sync_job). If a sync for the device is already
+    /// running, the service returns that job.
+    pub async fn sync_device_history(&self, device_id: &str) -> Result<SyncJob> {
+        let url = format!("{}/api/devices/{}/sync", self.base_url, device_id);
+        self.post_empty(&url).await
+    }
+
+    /// Get the status of a history sync job.
+    pub async fn sync_job(&self, job_id: u64) -> Result<SyncJob> {
+        let url = format!("{}/api/sync/jobs/{}", self.base_url, job_id);
+        self.get(&url).await
+    }
+
     // ======================================================================
     // Internal HTTP helpers
     // ======================================================================
@@ -372,17 +497,7 @@ impl ServiceClient {
         if status.is_success() {
             Ok(())
         } else {
-            let message = response
-                .json::<serde_json::Value>()
-                .await
-                .ok()
-                .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(String::from))
-                .unwrap_or_else(|| status.to_string());
-
-            Err(ServiceClientError::ApiError {
-                status: status.as_u16(),
-                message,
-            })
+            Err(api_error(response).await)
         }
     }
 
@@ -394,21 +509,38 @@ impl ServiceClient {
         if status.is_success() {
             response.json().await.map_err(ServiceClientError::Request)
         } else {
-            let message = response
-                .json::<serde_json::Value>()
-                .await
-                .ok()
-                .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(String::from))
-                .unwrap_or_else(|| status.to_string());
-
-            Err(ServiceClientError::ApiError {
-                status: status.as_u16(),
-                message,
-            })
+            Err(api_error(response).await)
         }
     }
 }
 
+/// Build a [`ServiceClientError::ApiError`] from an error response.
+///
+/// The message carries the service's request ID, when it sent one, so a
+/// failure can be found in the service logs.
+async fn api_error(response: reqwest::Response) -> ServiceClientError {
+    let status = response.status();
+    let request_id = response
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+    let message = response
+        .json::<serde_json::Value>()
+        .await
+        .ok()
+        .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(String::from))
+        .unwrap_or_else(|| status.to_string());
+
+    ServiceClientError::ApiError {
+        status: status.as_u16(),
+        message: match request_id {
+            Some(id) => format!("{} (request {})", message, id),
+            None => message,
+        },
+    }
+}
+
 fn normalize_base_url(base_url: &str) -> Result<String> {
     let base_url = base_url.trim_end_matches('/').to_string();
 
@@ -520,6 +652,33 @@ mod tests {
         assert_eq!(config.poll_interval, 60);
     }
 
+    #[test]
+    fn test_current_reading_response_to_reading() {
+        let json = r#"{
+            "id": 7,
+            "device_id": "AA:BB:CC:DD:EE:FF",
+            "captured_at": "2026-01-15T12:00:00Z",
+            "co2": 812,
+            "temperature": 21.5,
+            "pressure": 1012.3,
+            "humidity": 44,
+            "battery": 90,
+            "status": "Yellow",
+            "radon": null,
+            "quality": "ok",
+            "age_seconds": 100000,
+            "stale": true
+        }"#;
+        let response: CurrentReadingResponse = serde_json::from_str(json).unwrap();
+        assert!(response.stale);
+
+        let reading = response.to_reading();
+        assert_eq!(reading.co2, 812);
+        assert_eq!(reading.status, Status::Yellow);
+        assert_eq!(reading.age, u16::MAX);
+        assert_eq!(reading.captured_at, Some(response.captured_at));
+    }
+
     #[test]
     fn test_successful_collector_action_passes_through() {
         let response = CollectorActionResponse {
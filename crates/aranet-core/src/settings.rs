@@ -5,7 +5,7 @@
 
 use tracing::{debug, info};
 
-use crate::device::Device;
+use crate::device::{Device, WriteMode};
 use crate::error::{Error, Result};
 use crate::uuid::{CALIBRATION, COMMAND, READ_INTERVAL, SENSOR_STATE};
 
@@ -103,6 +103,95 @@ pub struct DeviceSettings {
     pub buzzer_enabled: bool,
     /// Whether automatic calibration is enabled (Aranet4 only).
     pub auto_calibration_enabled: bool,
+    /// Measurement interval, if known.
+    ///
+    /// Read from a separate characteristic, so only
+    /// [`Device::get_settings_with_interval`] fills it in; `None` means it
+    /// was not or could not be read. When used as a target for
+    /// [`Device::apply_settings`], `None` leaves the interval unchanged.
+    pub interval: Option<MeasurementInterval>,
+}
+
+impl DeviceSettings {
+    /// Compute the writes needed to turn `self` into `target`.
+    ///
+    /// Only writable settings are compared (interval, Bluetooth range, Smart
+    /// Home); the display units, buzzer and auto-calibration flags are
+    /// read-only and ignored. Changes are ordered the way
+    /// [`Device::apply_settings`] must write them: some firmware rejects a
+    /// range change issued before a pending interval change, so the interval
+    /// always goes first.
+    pub fn diff(&self, target: &DeviceSettings) -> SettingsDiff {
+        let mut changes = Vec::new();
+
+        if let Some(interval) = target.interval
+            && self.interval != Some(interval)
+        {
+            changes.push(SettingChange::Interval(interval));
+        }
+        if self.bluetooth_range != target.bluetooth_range {
+            changes.push(SettingChange::BluetoothRange(target.bluetooth_range));
+        }
+        if self.smart_home_enabled != target.smart_home_enabled {
+            changes.push(SettingChange::SmartHome(target.smart_home_enabled));
+        }
+
+        SettingsDiff { changes }
+    }
+}
+
+/// A single setting write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingChange {
+    /// Set the measurement interval.
+    Interval(MeasurementInterval),
+    /// Set the Bluetooth range.
+    BluetoothRange(BluetoothRange),
+    /// Enable or disable Smart Home integration.
+    SmartHome(bool),
+}
+
+impl std::fmt::Display for SettingChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingChange::Interval(interval) => write!(f, "interval={}s", interval.as_seconds()),
+            SettingChange::BluetoothRange(BluetoothRange::Standard) => {
+                write!(f, "bluetooth_range=standard")
+            }
+            SettingChange::BluetoothRange(BluetoothRange::Extended) => {
+                write!(f, "bluetooth_range=extended")
+            }
+            SettingChange::SmartHome(enabled) => write!(f, "smart_home={enabled}"),
+        }
+    }
+}
+
+/// Ordered set of writes produced by [`DeviceSettings::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SettingsDiff {
+    /// Changes in the order they must be written.
+    pub changes: Vec<SettingChange>,
+}
+
+impl SettingsDiff {
+    /// Returns `true` if the settings already match.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Number of writes needed.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+}
+
+/// Outcome of [`Device::apply_settings`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppliedSettings {
+    /// Writes that were performed and verified.
+    pub applied: Vec<SettingChange>,
+    /// Writes the device does not support, with the reason.
+    pub skipped: Vec<(SettingChange, String)>,
 }
 
 /// Calibration data from the device.
@@ -148,7 +237,8 @@ impl Device {
         };
 
         let cmd = [0x90, minutes];
-        self.write_characteristic(COMMAND, &cmd).await?;
+        self.write_characteristic_with_mode(COMMAND, &cmd, WriteMode::WithResponse)
+            .await?;
 
         Ok(())
     }
@@ -164,24 +254,28 @@ impl Device {
     /// Returns `Error::WriteFailed` if the read-back value doesn't match
     /// the requested interval.
     pub async fn set_interval_verified(&self, interval: MeasurementInterval) -> Result<()> {
-        self.set_interval(interval).await?;
-
-        // Small delay to allow the device to process the command
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-
-        let actual = self.get_interval().await?;
-        if actual != interval {
-            return Err(Error::WriteFailed {
-                uuid: COMMAND.to_string(),
-                reason: format!(
-                    "Interval verification failed: expected {:?}, got {:?}",
-                    interval, actual
-                ),
-            });
-        }
-
-        info!("Measurement interval verified: {:?}", interval);
-        Ok(())
+        self.operations
+            .run("set interval", async {
+                self.set_interval(interval).await?;
+
+                // Small delay to allow the device to process the command
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+                let actual = self.get_interval().await?;
+                if actual != interval {
+                    return Err(Error::WriteFailed {
+                        uuid: COMMAND.to_string(),
+                        reason: format!(
+                            "Interval verification failed: expected {:?}, got {:?}",
+                            interval, actual
+                        ),
+                    });
+                }
+
+                info!("Measurement interval verified: {:?}", interval);
+                Ok(())
+            })
+            .await
     }
 
     /// Enable or disable Smart Home integration.
@@ -196,7 +290,8 @@ impl Device {
 
         // Command format: 0x91 XX (XX = 00 disabled, 01 enabled)
         let cmd = [0x91, if enabled { 0x01 } else { 0x00 }];
-        self.write_characteristic(COMMAND, &cmd).await?;
+        self.write_characteristic_with_mode(COMMAND, &cmd, WriteMode::WithResponse)
+            .await?;
 
         Ok(())
     }
@@ -211,24 +306,28 @@ impl Device {
     /// Returns `Error::WriteFailed` if the read-back value doesn't match
     /// the requested setting.
     pub async fn set_smart_home_verified(&self, enabled: bool) -> Result<()> {
-        self.set_smart_home(enabled).await?;
-
-        // Small delay to allow the device to process the command
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-
-        let settings = self.get_settings().await?;
-        if settings.smart_home_enabled != enabled {
-            return Err(Error::WriteFailed {
-                uuid: COMMAND.to_string(),
-                reason: format!(
-                    "Smart Home verification failed: expected {}, got {}",
-                    enabled, settings.smart_home_enabled
-                ),
-            });
-        }
-
-        info!("Smart Home integration verified: {}", enabled);
-        Ok(())
+        self.operations
+            .run("set smart home", async {
+                self.set_smart_home(enabled).await?;
+
+                // Small delay to allow the device to process the command
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+                let settings = self.get_settings().await?;
+                if settings.smart_home_enabled != enabled {
+                    return Err(Error::WriteFailed {
+                        uuid: COMMAND.to_string(),
+                        reason: format!(
+                            "Smart Home verification failed: expected {}, got {}",
+                            enabled, settings.smart_home_enabled
+                        ),
+                    });
+                }
+
+                info!("Smart Home integration verified: {}", enabled);
+                Ok(())
+            })
+            .await
     }
 
     /// Set the Bluetooth range.
@@ -240,7 +339,8 @@ impl Device {
 
         // Command format: 0x92 XX (XX = 00 standard, 01 extended)
         let cmd = [0x92, range as u8];
-        self.write_characteristic(COMMAND, &cmd).await?;
+        self.write_characteristic_with_mode(COMMAND, &cmd, WriteMode::WithResponse)
+            .await?;
 
         Ok(())
     }
@@ -255,24 +355,82 @@ impl Device {
     /// Returns `Error::WriteFailed` if the read-back value doesn't match
     /// the requested setting.
     pub async fn set_bluetooth_range_verified(&self, range: BluetoothRange) -> Result<()> {
-        self.set_bluetooth_range(range).await?;
-
-        // Small delay to allow the device to process the command
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-
-        let settings = self.get_settings().await?;
-        if settings.bluetooth_range != range {
-            return Err(Error::WriteFailed {
-                uuid: COMMAND.to_string(),
-                reason: format!(
-                    "Bluetooth range verification failed: expected {:?}, got {:?}",
-                    range, settings.bluetooth_range
-                ),
-            });
-        }
+        self.operations
+            .run("set bluetooth range", async {
+                self.set_bluetooth_range(range).await?;
+
+                // Small delay to allow the device to process the command
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+                let settings = self.get_settings().await?;
+                if settings.bluetooth_range != range {
+                    return Err(Error::WriteFailed {
+                        uuid: COMMAND.to_string(),
+                        reason: format!(
+                            "Bluetooth range verification failed: expected {:?}, got {:?}",
+                            range, settings.bluetooth_range
+                        ),
+                    });
+                }
+
+                info!("Bluetooth range verified: {:?}", range);
+                Ok(())
+            })
+            .await
+    }
 
-        info!("Bluetooth range verified: {:?}", range);
-        Ok(())
+    /// Apply `target` settings, writing only the fields that differ.
+    ///
+    /// Reads the current settings, computes [`DeviceSettings::diff`], and
+    /// performs each change in order with a verified write. Changes the device
+    /// reports as unsupported are skipped and reported rather than treated
+    /// as failures.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first non-`Unsupported` error. Changes listed before the
+    /// failing one have already been written.
+    pub async fn apply_settings(&self, target: &DeviceSettings) -> Result<AppliedSettings> {
+        self.operations
+            .run("apply settings", async {
+                let current = self.get_settings_with_interval().await?;
+                let diff = current.diff(target);
+                let mut report = AppliedSettings::default();
+
+                if diff.is_empty() {
+                    debug!("Settings already match target; nothing to apply");
+                    return Ok(report);
+                }
+
+                info!("Applying {} settings change(s)", diff.len());
+                for change in diff.changes {
+                    let result = match change {
+                        SettingChange::Interval(interval) => {
+                            self.set_interval_verified(interval).await
+                        }
+                        SettingChange::BluetoothRange(range) => {
+                            self.set_bluetooth_range_verified(range).await
+                        }
+                        SettingChange::SmartHome(enabled) => {
+                            self.set_smart_home_verified(enabled).await
+                        }
+                    };
+
+                    match result {
+                        Ok(()) => report.applied.push(change),
+                        Err(e) => match e.root() {
+                            Error::Unsupported(reason) => {
+                                debug!("Skipping {:?}: {}", change, reason);
+                                report.skipped.push((change, reason.clone()));
+                            }
+                            _ => return Err(e),
+                        },
+                    }
+                }
+
+                Ok(report)
+            })
+            .await
     }
 
     /// Read calibration data from the device.
@@ -298,6 +456,10 @@ impl Device {
     /// - Radon display unit (for Aranet Radon devices)
     /// - Buzzer settings
     /// - Calibration settings
+    ///
+    /// The measurement interval lives in its own characteristic and is left
+    /// `None`; current readings already carry it. Use
+    /// [`Self::get_settings_with_interval`] when it is needed here.
     pub async fn get_settings(&self) -> Result<DeviceSettings> {
         let data = self.read_characteristic(SENSOR_STATE).await?;
 
@@ -374,8 +536,19 @@ impl Device {
             radon_unit,
             buzzer_enabled,
             auto_calibration_enabled,
+            interval: None,
         })
     }
+
+    /// Read device settings including the measurement interval.
+    ///
+    /// Costs one more read than [`Self::get_settings`]. An interval that
+    /// cannot be read is left `None` rather than failing the settings read.
+    pub async fn get_settings_with_interval(&self) -> Result<DeviceSettings> {
+        let mut settings = self.get_settings().await?;
+        settings.interval = self.get_interval().await.ok();
+        Ok(settings)
+    }
 }
 
 #[cfg(test)]
@@ -431,4 +604,76 @@ mod tests {
         assert_eq!(MeasurementInterval::FiveMinutes.as_seconds(), 300);
         assert_eq!(MeasurementInterval::TenMinutes.as_seconds(), 600);
     }
+
+    #[test]
+    fn test_settings_diff_empty_when_equal() {
+        let current = DeviceSettings {
+            interval: Some(MeasurementInterval::FiveMinutes),
+            ..Default::default()
+        };
+        assert!(current.diff(&current.clone()).is_empty());
+
+        // A target without an interval leaves the interval alone
+        let target = DeviceSettings::default();
+        assert!(current.diff(&target).is_empty());
+    }
+
+    #[test]
+    fn test_settings_diff_orders_interval_before_range() {
+        let current = DeviceSettings {
+            interval: Some(MeasurementInterval::FiveMinutes),
+            buzzer_enabled: true,
+            ..Default::default()
+        };
+        let target = DeviceSettings {
+            // Display units are read-only and never part of the diff
+            radon_unit: RadonUnit::PciL,
+            smart_home_enabled: true,
+            bluetooth_range: BluetoothRange::Extended,
+            interval: Some(MeasurementInterval::OneMinute),
+            // Read-only flags are never part of the diff
+            buzzer_enabled: false,
+            auto_calibration_enabled: true,
+            ..Default::default()
+        };
+
+        let diff = current.diff(&target);
+        assert_eq!(
+            diff.changes,
+            vec![
+                SettingChange::Interval(MeasurementInterval::OneMinute),
+                SettingChange::BluetoothRange(BluetoothRange::Extended),
+                SettingChange::SmartHome(true),
+            ]
+        );
+        assert_eq!(diff.len(), 3);
+    }
+
+    #[test]
+    fn test_setting_change_display() {
+        assert_eq!(
+            SettingChange::Interval(MeasurementInterval::FiveMinutes).to_string(),
+            "interval=300s"
+        );
+        assert_eq!(
+            SettingChange::BluetoothRange(BluetoothRange::Extended).to_string(),
+            "bluetooth_range=extended"
+        );
+        assert_eq!(
+            SettingChange::SmartHome(false).to_string(),
+            "smart_home=false"
+        );
+    }
+
+    #[test]
+    fn test_settings_diff_unknown_current_interval() {
+        let target = DeviceSettings {
+            interval: Some(MeasurementInterval::TenMinutes),
+            ..Default::default()
+        };
+        assert_eq!(
+            DeviceSettings::default().diff(&target).changes,
+            vec![SettingChange::Interval(MeasurementInterval::TenMinutes)]
+        );
+    }
 }
@@ -0,0 +1,606 @@
+//! Output sinks for sensor readings.
+//!
+//! A [`ReadingSink`] writes readings somewhere: a channel, a CSV file, a
+//! webhook, or (with aranet-store's `sink` feature) the local database.
+//! Other destinations, such as an MQTT broker, implement the trait
+//! themselves.
+//!
+//! A [`ReadingPipeline`] fans readings out to several sinks. Each sink runs
+//! in its own task behind its own buffer, so a slow or failing sink neither
+//! holds up nor breaks the others; failed writes are logged and counted.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use aranet_core::sink::{ChannelSink, CsvSink, ReadingPipeline, device_readings};
+//! use aranet_core::{ReadingStream, StreamOptions};
+//!
+//! let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+//! let pipeline = ReadingPipeline::new()
+//!     .with_sink(CsvSink::new("readings.csv"))
+//!     .with_sink(ChannelSink::new(tx));
+//!
+//! let stream = ReadingStream::new(device, StreamOptions::default());
+//! let stats = pipeline.run(device_readings("Aranet4 17C3C", stream)).await;
+//! ```
+//!
+//! Passive monitoring works the same way with
+//! [`passive_readings`]`(monitor.subscribe())`.
+
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use futures::stream::{Stream, StreamExt};
+use serde::Serialize;
+use time::OffsetDateTime;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use aranet_types::CurrentReading;
+
+use crate::passive::PassiveReading;
+
+/// Readings buffered per sink by [`ReadingPipeline::with_sink`].
+pub const DEFAULT_SINK_BUFFER: usize = 256;
+
+/// Errors from writing to a sink.
+#[derive(Debug, thiserror::Error)]
+pub enum SinkError {
+    /// Writing a file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The receiving end is gone.
+    #[error("Sink closed")]
+    Closed,
+
+    /// Any other failure, e.g. an HTTP error.
+    #[error("{0}")]
+    Other(String),
+}
+
+/// A reading together with the device it came from.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SinkReading {
+    /// Device identifier (address or name).
+    pub device_id: String,
+    /// Device name, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_name: Option<String>,
+    /// When the reading was received.
+    #[serde(with = "time::serde::rfc3339")]
+    pub received_at: OffsetDateTime,
+    /// The reading.
+    pub reading: CurrentReading,
+}
+
+impl SinkReading {
+    /// A reading from `device_id`, received now.
+    pub fn new(device_id: impl Into<String>, reading: CurrentReading) -> Self {
+        Self {
+            device_id: device_id.into(),
+            device_name: None,
+            received_at: OffsetDateTime::now_utc(),
+            reading,
+        }
+    }
+
+    /// Set the device name.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.device_name = Some(name.into());
+        self
+    }
+}
+
+impl From<&PassiveReading> for SinkReading {
+    fn from(passive: &PassiveReading) -> Self {
+        Self {
+            device_id: passive.device_id.clone(),
+            device_name: passive.device_name.clone(),
+            received_at: OffsetDateTime::now_utc() - passive.received_at.elapsed(),
+            reading: passive.data.to_reading(),
+        }
+    }
+}
+
+/// A destination for readings.
+///
+/// The pipeline calls [`write`](Self::write) once per reading, in order,
+/// from a task of its own, and [`flush`](Self::flush) once when it shuts
+/// down.
+pub trait ReadingSink: Send + 'static {
+    /// Short name used in logs and [`SinkStats`].
+    fn name(&self) -> &str;
+
+    /// Write one reading.
+    fn write(
+        &mut self,
+        reading: &SinkReading,
+    ) -> impl Future<Output = Result<(), SinkError>> + Send;
+
+    /// Write out anything buffered. The default does nothing.
+    fn flush(&mut self) -> impl Future<Output = Result<(), SinkError>> + Send {
+        async { Ok(()) }
+    }
+}
+
+/// Counts for one sink of a [`ReadingPipeline`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SinkStats {
+    /// The sink's [`name`](ReadingSink::name).
+    pub name: String,
+    /// Readings written.
+    pub written: u64,
+    /// Readings the sink failed to write.
+    pub failed: u64,
+    /// Readings dropped because the sink's buffer was full or it had stopped.
+    pub dropped: u64,
+}
+
+#[derive(Debug, Default)]
+struct SinkCounters {
+    written: AtomicU64,
+    failed: AtomicU64,
+    dropped: AtomicU64,
+}
+
+struct SinkHandle {
+    name: String,
+    tx: mpsc::Sender<Arc<SinkReading>>,
+    counters: Arc<SinkCounters>,
+    task: JoinHandle<()>,
+}
+
+impl SinkHandle {
+    fn stats(&self) -> SinkStats {
+        SinkStats {
+            name: self.name.clone(),
+            written: self.counters.written.load(Ordering::Relaxed),
+            failed: self.counters.failed.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Fans readings out to several [`ReadingSink`]s.
+///
+/// Sinks are started as Tokio tasks when added, so the pipeline must be
+/// built inside a runtime.
+#[derive(Default)]
+pub struct ReadingPipeline {
+    sinks: Vec<SinkHandle>,
+}
+
+impl fmt::Debug for ReadingPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadingPipeline")
+            .field("sinks", &self.stats())
+            .finish()
+    }
+}
+
+impl ReadingPipeline {
+    /// Create a pipeline without sinks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a sink with a buffer of [`DEFAULT_SINK_BUFFER`] readings.
+    pub fn with_sink<S: ReadingSink>(self, sink: S) -> Self {
+        self.with_buffered_sink(sink, DEFAULT_SINK_BUFFER)
+    }
+
+    /// Add a sink that can fall up to `buffer` readings behind before
+    /// readings for it are dropped.
+    pub fn with_buffered_sink<S: ReadingSink>(mut self, mut sink: S, buffer: usize) -> Self {
+        let name = sink.name().to_string();
+        let (tx, mut rx) = mpsc::channel::<Arc<SinkReading>>(buffer.max(1));
+        let counters = Arc::new(SinkCounters::default());
+        let task = tokio::spawn({
+            let name = name.clone();
+            let counters = Arc::clone(&counters);
+            async move {
+                while let Some(reading) = rx.recv().await {
+                    match sink.write(&reading).await {
+                        Ok(()) => {
+                            counters.written.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Err(e) => {
+                            counters.failed.fetch_add(1, Ordering::Relaxed);
+                            warn!(
+                                "Sink {} failed to write reading from {}: {}",
+                                name, reading.device_id, e
+                            );
+                        }
+                    }
+                }
+                if let Err(e) = sink.flush().await {
+                    warn!("Sink {} failed to flush: {}", name, e);
+                }
+            }
+        });
+        self.sinks.push(SinkHandle {
+            name,
+            tx,
+            counters,
+            task,
+        });
+        self
+    }
+
+    /// Hand a reading to every sink without waiting for them.
+    pub fn send(&self, reading: SinkReading) {
+        let reading = Arc::new(reading);
+        for sink in &self.sinks {
+            match sink.tx.try_send(Arc::clone(&reading)) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => {
+                    sink.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                    debug!("Sink {} is behind, dropping a reading", sink.name);
+                }
+                Err(TrySendError::Closed(_)) => {
+                    sink.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Current counts for each sink, in the order they were added.
+    pub fn stats(&self) -> Vec<SinkStats> {
+        self.sinks.iter().map(SinkHandle::stats).collect()
+    }
+
+    /// Send every reading from `readings` to the sinks, then
+    /// [`shutdown`](Self::shutdown) once the stream ends.
+    pub async fn run(self, readings: impl Stream<Item = SinkReading>) -> Vec<SinkStats> {
+        let mut readings = pin!(readings);
+        while let Some(reading) = readings.next().await {
+            self.send(reading);
+        }
+        self.shutdown().await
+    }
+
+    /// Let every sink write what it has buffered and flush, then return the
+    /// final counts.
+    pub async fn shutdown(self) -> Vec<SinkStats> {
+        let mut stats = Vec::with_capacity(self.sinks.len());
+        for sink in self.sinks {
+            drop(sink.tx);
+            if let Err(e) = sink.task.await {
+                warn!("Sink {} stopped unexpectedly: {}", sink.name, e);
+            }
+            stats.push(SinkStats {
+                name: sink.name,
+                written: sink.counters.written.load(Ordering::Relaxed),
+                failed: sink.counters.failed.load(Ordering::Relaxed),
+                dropped: sink.counters.dropped.load(Ordering::Relaxed),
+            });
+        }
+        stats
+    }
+}
+
+/// Readings for one device from a [`ReadingStream`](crate::ReadingStream)
+/// or any other stream of reading results.
+///
+/// Errors, including [`Error::StreamGap`](crate::Error::StreamGap), are
+/// skipped.
+pub fn device_readings<E: fmt::Display>(
+    device_id: impl Into<String>,
+    stream: impl Stream<Item = Result<CurrentReading, E>>,
+) -> impl Stream<Item = SinkReading> {
+    let device_id = device_id.into();
+    stream.filter_map(move |result| {
+        let reading = match result {
+            Ok(reading) => Some(SinkReading::new(device_id.clone(), reading)),
+            Err(e) => {
+                debug!("Skipping failed reading from {}: {}", device_id, e);
+                None
+            }
+        };
+        async move { reading }
+    })
+}
+
+/// Readings from a [`PassiveMonitor`](crate::PassiveMonitor) subscription.
+///
+/// Ends when the monitor stops. Readings missed because the pipeline fell
+/// behind the subscription are logged and skipped.
+pub fn passive_readings(
+    receiver: broadcast::Receiver<PassiveReading>,
+) -> impl Stream<Item = SinkReading> {
+    futures::stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(passive) => return Some((SinkReading::from(&passive), receiver)),
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("Reading pipeline missed {} passive readings", missed);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+/// Sends readings to an mpsc channel.
+#[derive(Debug, Clone)]
+pub struct ChannelSink {
+    tx: mpsc::Sender<SinkReading>,
+}
+
+impl ChannelSink {
+    /// Send readings to `tx`, waiting while the channel is full.
+    pub fn new(tx: mpsc::Sender<SinkReading>) -> Self {
+        Self { tx }
+    }
+}
+
+impl ReadingSink for ChannelSink {
+    fn name(&self) -> &str {
+        "channel"
+    }
+
+    async fn write(&mut self, reading: &SinkReading) -> Result<(), SinkError> {
+        self.tx
+            .send(reading.clone())
+            .await
+            .map_err(|_| SinkError::Closed)
+    }
+}
+
+/// Appends readings to a CSV file.
+///
+/// The file is created with a header row if it does not exist or is empty.
+#[derive(Debug)]
+pub struct CsvSink {
+    path: PathBuf,
+    file: Option<tokio::fs::File>,
+}
+
+/// Columns written by [`CsvSink`].
+pub const CSV_SINK_HEADER: &str = "timestamp,device_id,device_name,co2,temperature,humidity,pressure,battery,status,radon,radiation_rate";
+
+impl CsvSink {
+    /// Append to the CSV file at `path`. It is opened on the first write.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            file: None,
+        }
+    }
+
+    fn row(reading: &SinkReading) -> String {
+        let r = &reading.reading;
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        format!(
+            "{},{},{},{},{:.1},{},{:.1},{},{},{},{}\n",
+            reading
+                .received_at
+                .format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or_default(),
+            csv_field(&reading.device_id),
+            csv_field(reading.device_name.as_deref().unwrap_or("")),
+            r.co2,
+            r.temperature,
+            r.humidity,
+            r.pressure,
+            r.battery,
+            r.status,
+            optional(r.radon.map(|v| v.to_string())),
+            optional(r.radiation_rate.map(|v| format!("{:.3}", v))),
+        )
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl ReadingSink for CsvSink {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    async fn write(&mut self, reading: &SinkReading) -> Result<(), SinkError> {
+        if self.file.is_none() {
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .await?;
+            if file.metadata().await?.len() == 0 {
+                file.write_all(format!("{}\n", CSV_SINK_HEADER).as_bytes())
+                    .await?;
+            }
+            self.file = Some(file);
+        }
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(Self::row(reading).as_bytes()).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), SinkError> {
+        if let Some(file) = self.file.as_mut() {
+            file.flush().await?;
+        }
+        Ok(())
+    }
+}
+
+/// POSTs each reading as JSON to a URL.
+///
+/// The body is the [`SinkReading`] serialized as JSON. Non-2xx responses
+/// count as failed writes.
+#[cfg(feature = "webhook")]
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    url: String,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "webhook")]
+impl WebhookSink {
+    /// POST readings to `url`, with a 10 second timeout per request.
+    pub fn new(url: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self::with_client(url, client)
+    }
+
+    /// POST readings to `url` with a preconfigured client.
+    pub fn with_client(url: impl Into<String>, client: reqwest::Client) -> Self {
+        Self {
+            url: url.into(),
+            client,
+        }
+    }
+}
+
+#[cfg(feature = "webhook")]
+impl ReadingSink for WebhookSink {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn write(&mut self, reading: &SinkReading) -> Result<(), SinkError> {
+        self.client
+            .post(&self.url)
+            .json(reading)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(|e| SinkError::Other(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn reading(co2: u16) -> CurrentReading {
+        CurrentReading::builder().co2(co2).temperature(21.5).build()
+    }
+
+    /// Fails every other write.
+    struct FlakySink {
+        calls: u32,
+    }
+
+    impl ReadingSink for FlakySink {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn write(&mut self, _reading: &SinkReading) -> Result<(), SinkError> {
+            self.calls += 1;
+            if self.calls.is_multiple_of(2) {
+                Err(SinkError::Other("boom".to_string()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    /// Never finishes a write.
+    struct StuckSink;
+
+    impl ReadingSink for StuckSink {
+        fn name(&self) -> &str {
+            "stuck"
+        }
+
+        async fn write(&mut self, _reading: &SinkReading) -> Result<(), SinkError> {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_isolates_failing_sinks() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let readings = futures::stream::iter((0..4).map(|i| Ok::<_, String>(reading(400 + i))))
+            .chain(futures::stream::iter([Err("gap".to_string())]));
+        let stats = ReadingPipeline::new()
+            .with_sink(FlakySink { calls: 0 })
+            .with_sink(ChannelSink::new(tx))
+            .run(device_readings("Aranet4 17C3C", readings))
+            .await;
+
+        assert_eq!(stats[0].name, "flaky");
+        assert_eq!((stats[0].written, stats[0].failed), (2, 2));
+        assert_eq!(stats[1].name, "channel");
+        assert_eq!((stats[1].written, stats[1].failed), (4, 0));
+        for co2 in 400..404 {
+            let received = rx.recv().await.unwrap();
+            assert_eq!(received.device_id, "Aranet4 17C3C");
+            assert_eq!(received.reading.co2, co2);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_sink_drops_without_blocking_others() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let pipeline = ReadingPipeline::new()
+            .with_buffered_sink(StuckSink, 2)
+            .with_sink(ChannelSink::new(tx));
+        // Sent before either sink task runs, so the stuck sink's buffer of
+        // two fills and the rest are dropped
+        for i in 0..5 {
+            pipeline.send(SinkReading::new("dev", reading(i)));
+        }
+        for _ in 0..5 {
+            tokio::time::timeout(Duration::from_secs(1), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        let stats = pipeline.stats();
+        assert_eq!(stats[0].dropped, 3);
+        assert_eq!(stats[1].written, 5);
+    }
+
+    #[tokio::test]
+    async fn test_csv_sink_writes_header_once() {
+        let path = std::env::temp_dir().join(format!(
+            "aranet-sink-test-{}-{}.csv",
+            std::process::id(),
+            OffsetDateTime::now_utc().unix_timestamp_nanos()
+        ));
+        for co2 in [800, 900] {
+            let stats = ReadingPipeline::new()
+                .with_sink(CsvSink::new(&path))
+                .run(futures::stream::iter([SinkReading::new(
+                    "dev",
+                    reading(co2),
+                )
+                .with_name("Office, 2nd floor")]))
+                .await;
+            assert_eq!(stats[0].written, 1);
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_SINK_HEADER);
+        assert!(lines[1].contains(",dev,\"Office, 2nd floor\",800,21.5,"));
+        assert!(lines[2].contains(",900,"));
+    }
+}
@@ -1,26 +1,38 @@
 //! Real-time streaming of sensor readings via BLE notifications.
 //!
 //! This module provides functionality to subscribe to sensor readings
-//! and receive them as an async stream.
+//! and receive them as an async stream. Streams can be cloned to share one
+//! device's readings between several consumers.
 //!
 //! The stream supports graceful shutdown via the [`ReadingStream::close`] method,
 //! which uses a cancellation token to cleanly stop the background polling task.
+//!
+//! Streams over a [`ReconnectingDevice`] reconnect instead of failing when
+//! the connection drops, and mark the readings missed meanwhile with an
+//! [`Error::StreamGap`] item.
+//!
+//! With [`StreamOptions::events`] set, each poll is also reported as a
+//! [`DeviceEvent::Reading`] or [`DeviceEvent::Error`].
 
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
 
+use futures::ready;
 use futures::stream::Stream;
-use tokio::sync::mpsc;
-use tokio::time::interval;
-use tokio_util::sync::CancellationToken;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{Instant, interval};
+use tokio_util::sync::{CancellationToken, ReusableBoxFuture};
 use tracing::{debug, warn};
 
 use aranet_types::CurrentReading;
 
 use crate::device::Device;
 use crate::error::Error;
+use crate::events::{DeviceEvent, DeviceId, EventSender};
+use crate::reconnect::ReconnectingDevice;
 
 /// Options for reading streams.
 ///
@@ -60,6 +72,12 @@ pub struct StreamOptions {
     /// **Recommendation:** Set to `Some(5)` or similar for production use to
     /// prevent indefinite polling of a disconnected device.
     pub max_consecutive_failures: Option<u32>,
+    /// Where to report each poll as a [`DeviceEvent::Reading`] or
+    /// [`DeviceEvent::Error`].
+    ///
+    /// Errors are reported whether or not `include_errors` is set.
+    /// Default: none.
+    pub events: Option<EventSender>,
 }
 
 impl Default for StreamOptions {
@@ -69,6 +87,7 @@ impl Default for StreamOptions {
             buffer_size: 16,
             include_errors: false,
             max_consecutive_failures: Some(10),
+            events: None,
         }
     }
 }
@@ -148,6 +167,13 @@ impl StreamOptionsBuilder {
         self
     }
 
+    /// Report each poll on `sender`; see [`StreamOptions::events`].
+    #[must_use]
+    pub fn events(mut self, sender: EventSender) -> Self {
+        self.options.events = Some(sender);
+        self
+    }
+
     /// Build the StreamOptions.
     #[must_use]
     pub fn build(self) -> StreamOptions {
@@ -157,16 +183,96 @@ impl StreamOptionsBuilder {
 
 /// A stream of sensor readings from a device.
 ///
-/// The stream polls the device at a configured interval and sends readings
-/// through a channel. It supports graceful shutdown via [`close`](Self::close).
+/// The stream polls the device at a configured interval and broadcasts
+/// readings to every subscriber. It supports graceful shutdown via
+/// [`close`](Self::close).
+///
+/// # Fan-out
+///
+/// Cloning a `ReadingStream` adds a subscriber to the same background task,
+/// so a chart, an alert engine and a logger can all consume one device
+/// without polling it more than once. A clone receives readings from the
+/// next poll onwards. Each subscriber buffers up to
+/// [`StreamOptions::buffer_size`] readings; a subscriber that falls further
+/// behind skips the oldest ones rather than holding up the others.
+///
+/// Polling stops once every clone has been dropped, or as soon as any clone
+/// calls [`close`](Self::close).
 pub struct ReadingStream {
-    receiver: mpsc::Receiver<ReadingResult>,
+    next: ReusableBoxFuture<'static, Received>,
+    shared: Arc<StreamShared>,
+}
+
+/// State shared by every clone of a [`ReadingStream`].
+struct StreamShared {
+    /// Used to subscribe clones without keeping the channel open after the
+    /// polling task exits.
+    sender: broadcast::WeakSender<ReadingResult>,
     handle: tokio::task::JoinHandle<()>,
     cancel_token: CancellationToken,
 }
 
+impl Drop for StreamShared {
+    fn drop(&mut self) {
+        // Stop the background task once the last subscriber is gone.
+        self.cancel_token.cancel();
+    }
+}
+
 /// Result type for stream items.
-pub type ReadingResult = std::result::Result<CurrentReading, Error>;
+///
+/// Errors are shared between subscribers, so they arrive wrapped in an
+/// [`Arc`].
+pub type ReadingResult = std::result::Result<CurrentReading, Arc<Error>>;
+
+/// A received item, together with the receiver to poll next.
+type Received = (
+    std::result::Result<ReadingResult, RecvError>,
+    broadcast::Receiver<ReadingResult>,
+);
+
+async fn receive(mut receiver: broadcast::Receiver<ReadingResult>) -> Received {
+    (receiver.recv().await, receiver)
+}
+
+/// A successful poll, and how long readings were missed before it.
+struct Polled {
+    reading: CurrentReading,
+    gap: Option<Duration>,
+}
+
+impl From<CurrentReading> for Polled {
+    fn from(reading: CurrentReading) -> Self {
+        Self { reading, gap: None }
+    }
+}
+
+/// Notices reconnects between polls of a [`ReconnectingDevice`].
+#[derive(Debug)]
+struct GapTracker {
+    /// Reconnect count at the last reading.
+    reconnects: u64,
+    last_reading: Instant,
+}
+
+impl GapTracker {
+    fn new(reconnects: u64) -> Self {
+        Self {
+            reconnects,
+            last_reading: Instant::now(),
+        }
+    }
+
+    /// Record a reading taken after `reconnects` reconnects, returning the
+    /// time since the previous reading if the device reconnected in between.
+    fn observe(&mut self, reconnects: u64) -> Option<Duration> {
+        let now = Instant::now();
+        let gap = (reconnects != self.reconnects).then(|| now - self.last_reading);
+        self.reconnects = reconnects;
+        self.last_reading = now;
+        gap
+    }
+}
 
 impl ReadingStream {
     /// Create a new reading stream from a connected device (takes Arc).
@@ -180,16 +286,66 @@ impl ReadingStream {
     /// Invalid options (zero buffer size, zero poll interval) are replaced
     /// with defaults and a warning is logged.
     pub fn new(device: Arc<Device>, options: StreamOptions) -> Self {
+        let id = DeviceId::new(device.address());
+        Self::spawn(options, id, move || {
+            let device = Arc::clone(&device);
+            async move { device.read_current().await }
+        })
+    }
+
+    /// Create a reading stream that survives reconnects.
+    ///
+    /// Reads go through the [`ReconnectingDevice`], so a dropped connection
+    /// is re-established instead of counting towards
+    /// `max_consecutive_failures`. The first reading after a reconnect is
+    /// preceded by an [`Error::StreamGap`] item giving the time since the
+    /// last reading, sent even when `include_errors` is off.
+    pub fn from_reconnecting(device: Arc<ReconnectingDevice>, options: StreamOptions) -> Self {
+        let tracker = Arc::new(std::sync::Mutex::new(GapTracker::new(
+            device.reconnect_count(),
+        )));
+        let id = DeviceId::new(device.identifier());
+        Self::spawn(options, id, move || {
+            let device = Arc::clone(&device);
+            let tracker = Arc::clone(&tracker);
+            async move {
+                let reading = crate::traits::AranetDevice::read_current(device.as_ref()).await?;
+                let gap = tracker
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .observe(device.reconnect_count());
+                Ok(Polled { reading, gap })
+            }
+        })
+    }
+
+    /// Spawn the polling task around `read` and return the first subscriber.
+    ///
+    /// `id` names the device in the events sent to [`StreamOptions::events`].
+    fn spawn<F, Fut, T>(options: StreamOptions, id: DeviceId, mut read: F) -> Self
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = crate::error::Result<T>> + Send,
+        T: Into<Polled>,
+    {
         let options = if let Err(e) = options.validate() {
             warn!("Invalid stream options ({e}), using defaults");
             StreamOptions::default()
         } else {
             options
         };
-        let (tx, rx) = mpsc::channel(options.buffer_size);
+        let (tx, rx) = broadcast::channel(options.buffer_size);
+        let sender = tx.downgrade();
         let cancel_token = CancellationToken::new();
         let task_token = cancel_token.clone();
         let max_failures = options.max_consecutive_failures;
+        let events = options.events.clone();
+        let report = move |event: DeviceEvent| {
+            if let Some(events) = &events {
+                // No subscribers is fine
+                let _ = events.send(event);
+            }
+        };
 
         let handle = tokio::spawn(async move {
             let mut interval = interval(options.poll_interval);
@@ -202,17 +358,29 @@ impl ReadingStream {
                         break;
                     }
                     _ = interval.tick() => {
-                        match device.read_current().await {
-                            Ok(reading) => {
+                        match read().await.map(Into::into) {
+                            Ok(Polled { reading, gap }) => {
                                 // Reset failure counter on success
                                 consecutive_failures = 0;
-                                if tx.send(Ok(reading)).await.is_err() {
-                                    debug!("Stream receiver dropped, stopping");
+                                report(DeviceEvent::Reading {
+                                    device: id.clone(),
+                                    reading,
+                                });
+                                if let Some(missed) = gap {
+                                    debug!("Stream resumed after reconnect, missed {:?}", missed);
+                                    let _ = tx.send(Err(Arc::new(Error::StreamGap { missed })));
+                                }
+                                if tx.send(Ok(reading)).is_err() {
+                                    debug!("All stream receivers dropped, stopping");
                                     break;
                                 }
                             }
                             Err(e) => {
                                 consecutive_failures += 1;
+                                report(DeviceEvent::Error {
+                                    device: id.clone(),
+                                    error: e.to_string(),
+                                });
                                 warn!(
                                     "Error reading from device (failure {}/{}): {}",
                                     consecutive_failures,
@@ -229,13 +397,13 @@ impl ReadingStream {
                                         );
                                         // Send final error if configured to include errors
                                         if options.include_errors {
-                                            let _ = tx.send(Err(e)).await;
+                                            let _ = tx.send(Err(Arc::new(e)));
                                         }
                                         break;
                                     }
 
-                                if options.include_errors && tx.send(Err(e)).await.is_err() {
-                                    debug!("Stream receiver dropped, stopping");
+                                if options.include_errors && tx.send(Err(Arc::new(e))).is_err() {
+                                    debug!("All stream receivers dropped, stopping");
                                     break;
                                 }
                             }
@@ -246,20 +414,31 @@ impl ReadingStream {
         });
 
         Self {
-            receiver: rx,
-            handle,
-            cancel_token,
+            next: ReusableBoxFuture::new(receive(rx)),
+            shared: Arc::new(StreamShared {
+                sender,
+                handle,
+                cancel_token,
+            }),
         }
     }
 
+    /// Number of subscribers (clones) sharing this stream's polling task.
+    pub fn subscriber_count(&self) -> usize {
+        Arc::strong_count(&self.shared)
+    }
+
     /// Close the stream and stop the background polling task gracefully.
     ///
+    /// This ends the stream for every clone, not just this one; drop a clone
+    /// instead to unsubscribe only it.
+    ///
     /// This signals the background task to stop via a cancellation token,
     /// allowing it to complete any in-progress operations before exiting.
     /// This is preferred over aborting the task, which may leave resources
     /// in an inconsistent state.
     pub fn close(self) {
-        self.cancel_token.cancel();
+        self.shared.cancel_token.cancel();
         // The handle will complete on its own; we don't need to await it
     }
 
@@ -267,23 +446,23 @@ impl ReadingStream {
     ///
     /// This allows multiple places to trigger cancellation of the stream.
     pub fn cancellation_token(&self) -> CancellationToken {
-        self.cancel_token.clone()
+        self.shared.cancel_token.clone()
     }
 
     /// Check if the stream is still active (background task running).
     pub fn is_active(&self) -> bool {
-        !self.handle.is_finished()
+        !self.shared.handle.is_finished()
     }
 
     /// Check if the stream has been cancelled.
     pub fn is_cancelled(&self) -> bool {
-        self.cancel_token.is_cancelled()
+        self.shared.cancel_token.is_cancelled()
     }
 
     /// Check if the stream stopped unexpectedly.
     ///
     /// Returns `true` if the background task has finished but was not explicitly
-    /// cancelled via [`close()`](Self::close) or by dropping the stream.
+    /// cancelled via [`close()`](Self::close) or by dropping every clone.
     ///
     /// This can indicate:
     /// - A panic in the background task
@@ -302,7 +481,7 @@ impl ReadingStream {
     /// Note: To distinguish between auto-close due to failures vs actual panics,
     /// you may need additional monitoring of the stream's error output.
     pub fn has_unexpectedly_stopped(&self) -> bool {
-        self.handle.is_finished() && !self.cancel_token.is_cancelled()
+        self.shared.handle.is_finished() && !self.shared.cancel_token.is_cancelled()
     }
 
     /// Check if the background task has panicked.
@@ -319,11 +498,18 @@ impl ReadingStream {
     }
 }
 
-impl Drop for ReadingStream {
-    fn drop(&mut self) {
-        // Ensure the background task is cancelled when the stream is dropped.
-        // This prevents resource leaks if the stream is dropped without calling close().
-        self.cancel_token.cancel();
+impl Clone for ReadingStream {
+    /// Subscribe to the same polling task; see [Fan-out](Self#fan-out).
+    fn clone(&self) -> Self {
+        let receiver = match self.shared.sender.upgrade() {
+            Some(sender) => sender.subscribe(),
+            // The polling task has exited: hand out an already-closed receiver
+            None => broadcast::channel(1).1,
+        };
+        Self {
+            next: ReusableBoxFuture::new(receive(receiver)),
+            shared: Arc::clone(&self.shared),
+        }
     }
 }
 
@@ -331,7 +517,17 @@ impl Stream for ReadingStream {
     type Item = ReadingResult;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        Pin::new(&mut self.receiver).poll_recv(cx)
+        loop {
+            let (result, receiver) = ready!(self.next.poll(cx));
+            self.next.set(receive(receiver));
+            match result {
+                Ok(item) => return Poll::Ready(Some(item)),
+                Err(RecvError::Closed) => return Poll::Ready(None),
+                Err(RecvError::Lagged(skipped)) => {
+                    debug!("Stream subscriber lagged, skipped {} readings", skipped);
+                }
+            }
+        }
     }
 }
 
@@ -407,6 +603,11 @@ pub fn from_device_default(device: Arc<Device>) -> ReadingStream {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::events::{EventDispatcher, EventRecorder};
+
+    fn test_id() -> DeviceId {
+        DeviceId::new("test-device")
+    }
 
     #[test]
     fn test_stream_options_default() {
@@ -444,4 +645,114 @@ mod tests {
         assert_eq!(opts.buffer_size, 16); // default
         assert!(opts.include_errors); // set
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_clones_share_one_polling_task() {
+        use futures::StreamExt;
+        use std::sync::atomic::{AtomicU16, Ordering};
+
+        let polls = Arc::new(AtomicU16::new(0));
+        let counter = Arc::clone(&polls);
+        let mut chart = ReadingStream::spawn(StreamOptions::default(), test_id(), move || {
+            let co2 = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                Ok(CurrentReading {
+                    co2,
+                    ..Default::default()
+                })
+            }
+        });
+        let mut logger = chart.clone();
+        assert_eq!(chart.subscriber_count(), 2);
+
+        for expected in 0..3 {
+            assert_eq!(chart.next().await.unwrap().unwrap().co2, expected);
+            assert_eq!(logger.next().await.unwrap().unwrap().co2, expected);
+        }
+        assert!(polls.load(Ordering::SeqCst) <= 4);
+
+        // Dropping one subscriber leaves the other running
+        drop(logger);
+        assert!(chart.next().await.unwrap().is_ok());
+        assert!(chart.is_active());
+
+        // Closing any subscriber ends the stream for all of them
+        let mut other = chart.clone();
+        chart.close();
+        while other.next().await.is_some() {}
+        assert!(other.is_cancelled());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_gap_marker_after_reconnect() {
+        use futures::StreamExt;
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        // Stand-in for a ReconnectingDevice's reconnect count
+        let reconnects = Arc::new(AtomicU64::new(0));
+        let tracker = Arc::new(std::sync::Mutex::new(GapTracker::new(0)));
+        let counter = Arc::clone(&reconnects);
+        let mut stream = ReadingStream::spawn(StreamOptions::default(), test_id(), move || {
+            let counter = Arc::clone(&counter);
+            let tracker = Arc::clone(&tracker);
+            async move {
+                let gap = tracker
+                    .lock()
+                    .unwrap()
+                    .observe(counter.load(Ordering::SeqCst));
+                Ok(Polled {
+                    reading: CurrentReading::default(),
+                    gap,
+                })
+            }
+        });
+
+        assert!(stream.next().await.unwrap().is_ok());
+        assert!(stream.next().await.unwrap().is_ok());
+
+        reconnects.fetch_add(1, Ordering::SeqCst);
+        let marker = stream.next().await.unwrap().unwrap_err();
+        assert!(matches!(
+            *marker,
+            Error::StreamGap { missed } if missed == Duration::from_secs(1)
+        ));
+        assert!(stream.next().await.unwrap().is_ok());
+        assert!(stream.next().await.unwrap().is_ok());
+        assert!(stream.is_active());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stream_reports_readings_then_closes_after_failures() {
+        use futures::StreamExt;
+        use std::sync::atomic::{AtomicU16, Ordering};
+
+        let events = EventDispatcher::new(16);
+        let mut recorder = EventRecorder::new(&events.sender());
+        let options = StreamOptions::builder()
+            .max_consecutive_failures(2)
+            .events(events.sender())
+            .build();
+
+        // Two readings, then the device stops answering
+        let polls = Arc::new(AtomicU16::new(0));
+        let mut stream = ReadingStream::spawn(options, test_id(), move || {
+            let poll = polls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if poll < 2 {
+                    Ok(CurrentReading::default())
+                } else {
+                    Err(Error::NotConnected)
+                }
+            }
+        });
+
+        assert!(stream.next().await.unwrap().is_ok());
+        assert!(stream.next().await.unwrap().is_ok());
+        recorder.expect_sequence(["reading", "reading"]);
+
+        // Failed reads are reported even though they stay out of the stream
+        assert!(stream.next().await.is_none());
+        assert!(stream.has_unexpectedly_stopped());
+        recorder.expect_sequence(["reading", "reading", "error", "error"]);
+    }
 }
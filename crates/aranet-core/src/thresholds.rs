@@ -1,7 +1,8 @@
-//! CO2 level thresholds and categorization.
+//! CO2 level and radiation dose rate thresholds and categorization.
 //!
-//! This module provides configurable thresholds for categorizing CO2 levels
-//! and other sensor readings into actionable categories.
+//! This module provides configurable thresholds for categorizing CO2 levels,
+//! Aranet Radiation dose rates and other sensor readings into actionable
+//! categories.
 //!
 //! # Example
 //!
@@ -117,16 +118,96 @@ impl ThresholdConfig {
     }
 }
 
+/// Radiation dose rate category for Aranet Radiation readings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RadiationLevel {
+    /// Normal background radiation (typically < 0.3 µSv/h).
+    Normal,
+    /// Elevated dose rate, at or above the warning level.
+    Elevated,
+    /// High dose rate, at or above the alarm level.
+    High,
+}
+
+impl RadiationLevel {
+    /// Get a human-readable description of the radiation level.
+    pub fn description(&self) -> &'static str {
+        match self {
+            RadiationLevel::Normal => "Normal - typical background radiation",
+            RadiationLevel::Elevated => "Elevated - above typical background",
+            RadiationLevel::High => "High - investigate the source",
+        }
+    }
+
+    /// Get the suggested action for this radiation level.
+    pub fn action(&self) -> &'static str {
+        match self {
+            RadiationLevel::Normal => "No action needed",
+            RadiationLevel::Elevated => "Monitor the dose rate and check for nearby sources",
+            RadiationLevel::High => "Limit time in the area and identify the source",
+        }
+    }
+}
+
+/// Dose rate thresholds for Aranet Radiation devices, in µSv/h.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RadiationThresholds {
+    /// Dose rate at or above which the level is Elevated.
+    pub warning_usv: f32,
+    /// Dose rate at or above which the level is High.
+    pub alarm_usv: f32,
+}
+
+impl Default for RadiationThresholds {
+    fn default() -> Self {
+        Self {
+            warning_usv: 0.3,
+            alarm_usv: 1.0,
+        }
+    }
+}
+
+impl RadiationThresholds {
+    /// Create dose rate thresholds from warning and alarm levels in µSv/h.
+    pub fn new(warning_usv: f32, alarm_usv: f32) -> Self {
+        Self {
+            warning_usv,
+            alarm_usv,
+        }
+    }
+
+    /// Evaluate a dose rate in µSv/h.
+    pub fn evaluate(&self, usv_per_hour: f32) -> RadiationLevel {
+        if usv_per_hour >= self.alarm_usv {
+            RadiationLevel::High
+        } else if usv_per_hour >= self.warning_usv {
+            RadiationLevel::Elevated
+        } else {
+            RadiationLevel::Normal
+        }
+    }
+}
+
 /// Threshold evaluator for sensor readings.
 #[derive(Debug, Clone, Default)]
 pub struct Thresholds {
     config: ThresholdConfig,
+    radiation: RadiationThresholds,
 }
 
 impl Thresholds {
     /// Create a new threshold evaluator with the given configuration.
     pub fn new(config: ThresholdConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            radiation: RadiationThresholds::default(),
+        }
+    }
+
+    /// Set the radiation dose rate thresholds.
+    pub fn with_radiation(mut self, radiation: RadiationThresholds) -> Self {
+        self.radiation = radiation;
+        self
     }
 
     /// Create a threshold evaluator with strict thresholds.
@@ -144,6 +225,11 @@ impl Thresholds {
         &self.config
     }
 
+    /// Get the radiation dose rate thresholds.
+    pub fn radiation(&self) -> &RadiationThresholds {
+        &self.radiation
+    }
+
     /// Evaluate the CO2 level from a reading.
     pub fn evaluate_co2(&self, co2_ppm: u16) -> Co2Level {
         if co2_ppm <= self.config.excellent_max {
@@ -166,6 +252,11 @@ impl Thresholds {
         self.evaluate_co2(reading.co2)
     }
 
+    /// Evaluate the radiation level from a dose rate in µSv/h.
+    pub fn evaluate_radiation(&self, usv_per_hour: f32) -> RadiationLevel {
+        self.radiation.evaluate(usv_per_hour)
+    }
+
     /// Check if a CO2 reading exceeds a specific threshold.
     pub fn exceeds_threshold(&self, co2_ppm: u16, level: Co2Level) -> bool {
         match level {
@@ -241,4 +332,18 @@ mod tests {
         assert!(!t.exceeds_threshold(1000, Co2Level::Moderate));
         assert!(t.exceeds_threshold(1001, Co2Level::Moderate));
     }
+
+    #[test]
+    fn test_radiation_thresholds() {
+        let t = Thresholds::default();
+        assert_eq!(t.evaluate_radiation(0.1), RadiationLevel::Normal);
+        assert_eq!(t.evaluate_radiation(0.3), RadiationLevel::Elevated);
+        assert_eq!(t.evaluate_radiation(0.99), RadiationLevel::Elevated);
+        assert_eq!(t.evaluate_radiation(1.0), RadiationLevel::High);
+
+        let t = Thresholds::default().with_radiation(RadiationThresholds::new(0.2, 0.5));
+        assert_eq!(t.evaluate_radiation(0.25), RadiationLevel::Elevated);
+        assert_eq!(t.evaluate_radiation(0.6), RadiationLevel::High);
+        assert!(RadiationLevel::High > RadiationLevel::Elevated);
+    }
 }
@@ -777,3 +777,27 @@ async fn test_radon_device_with_validator() {
 
     assert!(!result2.is_valid, "High radon should fail validation");
 }
+
+// =============================================================================
+// Event emission tests (no BLE hardware required)
+// =============================================================================
+
+use aranet_core::{ConnectionFailureReason, DeviceManager, Error, EventRecorder};
+
+/// The manager announces the adapter going away and coming back.
+#[tokio::test]
+async fn test_manager_adapter_events() {
+    let manager = DeviceManager::new();
+    let mut recorder = EventRecorder::new(&manager.events().sender());
+    manager.add_device("AA:BB:CC:DD:EE:FF").await.unwrap();
+
+    let adapter = manager.adapter();
+    let err = Error::connection_failed(None, ConnectionFailureReason::AdapterUnavailable);
+    assert!(adapter.observe_error(&err));
+    // Repeated errors are not news
+    assert!(adapter.observe_error(&err));
+    adapter.observe_success();
+
+    recorder.expect_sequence(["adapter_state_changed", "adapter_state_changed"]);
+    assert!(recorder.events().iter().all(|e| e.device().is_none()));
+}
@@ -31,6 +31,8 @@
 //! - `--demo` - Run in demo mode with mock sensor data
 //! - `--screenshot <PATH>` - Take a screenshot and save to the specified path
 //! - `--screenshot-delay <N>` - Number of frames to wait before taking screenshot (default: 10)
+//! - `--scenario <NAME>` - Play a scripted demo scenario (`co2-rise`, `disconnect`,
+//!   `battery-drain` or `all`) to preview alerts without hardware
 //!
 //! ## Features
 //!
@@ -6,6 +6,7 @@
 use std::path::PathBuf;
 
 use anyhow::Result;
+use aranet_cli::gui::demo::DemoScenario;
 use clap::Parser;
 
 /// Aranet GUI - Desktop application for Aranet environmental sensors
@@ -23,19 +24,24 @@ struct Args {
     /// Number of frames to wait before taking screenshot (default: 10)
     #[arg(long, default_value = "10")]
     screenshot_delay: u32,
+
+    /// Play a scripted scenario to preview alerts (implies --demo)
+    #[arg(long, value_enum, value_name = "SCENARIO")]
+    scenario: Option<DemoScenario>,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
-    if args.demo || args.screenshot.is_some() {
+    if args.demo || args.screenshot.is_some() || args.scenario.is_some() {
         let mut options = aranet_cli::gui::GuiOptions {
             demo: args.demo,
             screenshot: args.screenshot,
             screenshot_delay_frames: args.screenshot_delay,
+            demo_scenario: args.scenario,
         };
-        // If taking a screenshot without explicit demo flag, enable demo mode
-        if options.screenshot.is_some() && !options.demo {
+        // Screenshots and scenarios run against the demo devices
+        if (options.screenshot.is_some() || options.demo_scenario.is_some()) && !options.demo {
             options.demo = true;
         }
         aranet_cli::gui::run_with_options(options)
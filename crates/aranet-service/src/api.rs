@@ -7,7 +7,9 @@
 //! All async handlers that access shared state acquire locks in a consistent order:
 //!
 //! - **`state.store`** (Mutex): Acquired for database operations. Held briefly during
-//!   queries; avoid long-running operations while holding this lock.
+//!   queries; avoid long-running operations while holding this lock. The
+//!   readings, history and ingest endpoints, which can move large batches,
+//!   run their queries on the blocking thread pool.
 //! - **`state.config`** (RwLock): Read lock for `get_*` endpoints, write lock for mutations.
 //!   Multiple readers allowed; writers are exclusive.
 //! - **`state.collector.device_stats`** (RwLock): Per-device collection statistics.
@@ -19,6 +21,13 @@
 //! 2. `store` (if needed)
 //! 3. `device_stats` (if needed)
 //!
+//! ## Device Privacy
+//!
+//! Requests without credentials (every request while authentication is off)
+//! do not see devices whose [`DevicePrivacy`] is not public: they are left
+//! out of listings and reported as not found. `/metrics` leaves out devices
+//! that are not forwarded.
+//!
 //! ## Error Handling
 //!
 //! All endpoints return structured JSON errors via [`AppError`]. Store errors are
@@ -39,18 +48,25 @@ use std::sync::Arc;
 
 use axum::{
     Json, Router,
-    extract::{Path, Query, State},
+    extract::{Extension, Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
-    routing::{get, post, put},
+    routing::{get, patch, post, put},
 };
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
 use crate::collector::{Collector, CollectorStartResult};
-use crate::config::DeviceConfig;
+use crate::config::{Config, DeviceConfig, DevicePrivacy};
+use crate::oidc::Scope;
 use crate::state::CollectorState;
-use crate::state::{AppState, DeviceCollectionStats};
+use crate::state::{
+    AppState, DeviceBackoff, DeviceCollectionStats, POLL_LATENCY_BUCKETS, ReadingEvent,
+    SettingsJob, SettingsJobStatus, SettingsPatch, SyncJob, SyncJobStatus,
+};
+use crate::ws::WsConnectionStats;
+use aranet_core::settings::{BluetoothRange, MeasurementInterval};
+use aranet_core::{BluetoothDiagnostics, global_diagnostics};
 
 /// Create the API router.
 pub fn router() -> Router<Arc<AppState>> {
@@ -58,6 +74,7 @@ pub fn router() -> Router<Arc<AppState>> {
         // Health and status
         .route("/api/health", get(health))
         .route("/api/health/detailed", get(health_detailed))
+        .route("/api/health/diagnostics", get(health_diagnostics))
         .route("/api/status", get(get_status))
         // Prometheus metrics
         .route("/metrics", get(prometheus_metrics))
@@ -75,11 +92,18 @@ pub fn router() -> Router<Arc<AppState>> {
         // Data endpoints
         .route("/api/devices", get(list_devices))
         .route("/api/devices/current", get(list_current_readings))
-        .route("/api/devices/{id}", get(get_device))
+        .route("/api/devices/{id}", get(get_device).delete(delete_device))
         .route("/api/devices/{id}/current", get(get_current_reading))
         .route("/api/devices/{id}/readings", get(get_readings))
         .route("/api/devices/{id}/history", get(get_history))
+        .route("/api/devices/{id}/channels", get(get_channel_values))
+        .route("/api/devices/{id}/settings", patch(patch_device_settings))
+        .route("/api/settings/jobs/{job_id}", get(get_settings_job))
+        .route("/api/devices/{id}/sync", post(sync_device_history))
+        .route("/api/sync/jobs/{job_id}", get(get_sync_job))
         .route("/api/readings", get(get_all_readings))
+        // Federation
+        .route("/api/ingest", post(ingest_readings))
 }
 
 /// Health check response.
@@ -113,6 +137,8 @@ pub struct DetailedHealthResponse {
     pub collector: CollectorHealth,
     /// Platform information
     pub platform: PlatformInfo,
+    /// WebSocket clients and connection limits
+    pub websocket: WebSocketHealth,
 }
 
 /// Database health information.
@@ -141,6 +167,23 @@ pub struct CollectorHealth {
     pub failing_devices: usize,
 }
 
+/// WebSocket connection information.
+#[derive(Debug, Serialize)]
+pub struct WebSocketHealth {
+    /// Number of connected clients
+    pub clients: usize,
+    /// Configured connection limit (0 for none)
+    pub max_connections: usize,
+    /// Clients closed to make room for new ones
+    pub evicted_total: u64,
+    /// Clients refused because the limit was reached
+    pub rejected_total: u64,
+    /// Clients closed after the idle timeout
+    pub idle_closed_total: u64,
+    /// Per-connection statistics
+    pub connections: Vec<WsConnectionStats>,
+}
+
 /// Platform information.
 #[derive(Debug, Serialize)]
 pub struct PlatformInfo {
@@ -156,6 +199,7 @@ pub struct PlatformInfo {
 /// - Database connectivity and counts
 /// - Collector status and device health
 /// - Platform information
+/// - Connected WebSocket clients
 ///
 /// Note: This endpoint acquires locks on store and device_stats.
 /// For high-frequency monitoring, prefer `/api/health`.
@@ -206,6 +250,16 @@ async fn health_detailed(State(state): State<Arc<AppState>>) -> Json<DetailedHea
         }
     };
 
+    let connections = state.ws_connections.stats();
+    let websocket = WebSocketHealth {
+        clients: connections.len(),
+        max_connections: state.config.read().await.server.websocket.max_connections,
+        evicted_total: state.ws_connections.evicted_total(),
+        rejected_total: state.ws_connections.rejected_total(),
+        idle_closed_total: state.ws_connections.idle_closed_total(),
+        connections,
+    };
+
     // Platform info
     let platform = PlatformInfo {
         os: std::env::consts::OS,
@@ -228,9 +282,19 @@ async fn health_detailed(State(state): State<Arc<AppState>>) -> Json<DetailedHea
         database,
         collector,
         platform,
+        websocket,
     })
 }
 
+/// BLE diagnostics endpoint.
+///
+/// Returns the process-wide [`global_diagnostics`] snapshot: connection and
+/// read counters plus the most recent errors and operation timings, so
+/// intermittent BLE failures can be inspected after the fact.
+async fn health_diagnostics() -> Json<BluetoothDiagnostics> {
+    Json(global_diagnostics().collect().await)
+}
+
 // ==========================================================================
 // Prometheus Metrics
 // ==========================================================================
@@ -257,12 +321,25 @@ const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8"
 /// - `aranet_radiation_rate_usvh` - Radiation rate in µSv/h
 /// - `aranet_radiation_total_msv` - Total radiation dose in mSv
 ///
+/// ## Computed Channels
+/// - `aranet_channel_value` - Latest value per device and `channel` label
+///
 /// ## Collector Stats
 /// - `aranet_collector_running` - Whether the collector is running (1 or 0)
 /// - `aranet_collector_uptime_seconds` - Collector uptime in seconds
 /// - `aranet_device_poll_success_total` - Total successful polls per device
 /// - `aranet_device_poll_failure_total` - Total failed polls per device
 ///
+/// ## Collector Internals
+/// - `aranet_device_connect_attempts_total` - BLE connection attempts per device
+/// - `aranet_device_poll_errors_total` - Failed polls per device and error `category`
+/// - `aranet_device_poll_latency_seconds` - Poll duration histogram per device
+/// - `aranet_collector_ble_permits_available` - BLE connection slots not in use
+/// - `aranet_collector_sync_jobs_queued` - History syncs pending or running
+/// - `aranet_collector_settings_jobs_queued` - Settings changes waiting for a connection
+/// - `aranet_ws_clients` - Connected WebSocket clients
+/// - `aranet_ws_messages_dropped_total` - Messages dropped for slow WebSocket clients
+///
 /// Write a Prometheus metric family (HELP, TYPE, and values) to the output buffer.
 fn write_metric_family(
     output: &mut String,
@@ -303,6 +380,7 @@ async fn prometheus_metrics(
             "Prometheus metrics endpoint is disabled. Enable it in server.toml with [prometheus] enabled = true".to_string(),
         ));
     }
+    let withheld = config.devices_without(DevicePrivacy::is_forwarded);
     drop(config);
 
     let mut output = String::with_capacity(4096);
@@ -317,14 +395,20 @@ async fn prometheus_metrics(
     ));
 
     // Collector status and per-device poll stats
-    build_collector_metrics(&mut output, &state.collector).await;
+    build_collector_metrics(&mut output, &state.collector, &withheld).await;
+    build_internal_metrics(&mut output, &state, &withheld).await;
 
     // Per-device reading metrics (CO2, temperature, humidity, etc.)
-    let device_readings = state
+    let mut device_readings = state
         .with_store_read(|store| store.list_latest_readings())
         .await?;
+    device_readings.retain(|(device, _)| !is_hidden(&withheld, &device.id));
+    let mut channel_values = state
+        .with_store_read(|store| store.latest_channel_values(None))
+        .await?;
+    channel_values.retain(|value| !is_hidden(&withheld, &value.device_id));
 
-    if !device_readings.is_empty() {
+    if !device_readings.is_empty() || !channel_values.is_empty() {
         let config = state.config.read().await;
         let alias_map: std::collections::HashMap<String, String> = config
             .devices
@@ -334,6 +418,7 @@ async fn prometheus_metrics(
         drop(config);
 
         build_device_metrics(&mut output, &device_readings, &alias_map);
+        build_channel_metrics(&mut output, &channel_values, &alias_map);
     }
 
     Ok((
@@ -343,8 +428,13 @@ async fn prometheus_metrics(
     ))
 }
 
-/// Build collector-level metrics: running state, uptime, and per-device poll statistics.
-async fn build_collector_metrics(output: &mut String, collector: &CollectorState) {
+/// Build collector-level metrics: running state, uptime, and per-device poll
+/// statistics for devices not in `withheld`.
+async fn build_collector_metrics(
+    output: &mut String,
+    collector: &CollectorState,
+    withheld: &[String],
+) {
     let running = collector.is_running();
     let uptime = collector.started_at().map(|s| {
         let now = OffsetDateTime::now_utc();
@@ -379,7 +469,10 @@ async fn build_collector_metrics(output: &mut String, collector: &CollectorState
         let mut polling_metrics = Vec::new();
         let mut duration_metrics = Vec::new();
 
-        for stat in device_stats.iter() {
+        for stat in device_stats
+            .iter()
+            .filter(|stat| !is_hidden(withheld, &stat.device_id))
+        {
             let alias = stat.alias.as_deref().unwrap_or(&stat.device_id);
             let labels = format!(
                 "device=\"{}\",address=\"{}\"",
@@ -438,6 +531,129 @@ async fn build_collector_metrics(output: &mut String, collector: &CollectorState
     }
 }
 
+/// Build metrics on the collector's own health: connection attempts, failures
+/// by category and poll latency per device, queue depths and WebSocket
+/// clients, so operators can alert on collection degrading.
+async fn build_internal_metrics(output: &mut String, state: &AppState, withheld: &[String]) {
+    use std::sync::atomic::Ordering;
+
+    let device_stats = state.collector.device_stats.read().await;
+    let metrics = state.collector.metrics.lock().await;
+    let mut connect_metrics = Vec::new();
+    let mut error_metrics = Vec::new();
+    let mut latency_metrics = Vec::new();
+    for stat in device_stats
+        .iter()
+        .filter(|stat| !is_hidden(withheld, &stat.device_id))
+    {
+        let Some(device) = metrics.get(&stat.device_id) else {
+            continue;
+        };
+        let alias = stat.alias.as_deref().unwrap_or(&stat.device_id);
+        let labels = format!(
+            "device=\"{}\",address=\"{}\"",
+            escape_label_value(alias),
+            escape_label_value(&stat.device_id)
+        );
+        connect_metrics.push(format!(
+            "aranet_device_connect_attempts_total{{{}}} {}",
+            labels, device.connect_attempts
+        ));
+        for (category, count) in &device.failures {
+            error_metrics.push(format!(
+                "aranet_device_poll_errors_total{{{},category=\"{}\"}} {}",
+                labels, category, count
+            ));
+        }
+        let latency = &device.poll_latency;
+        for (bound, count) in POLL_LATENCY_BUCKETS.iter().zip(latency.buckets) {
+            latency_metrics.push(format!(
+                "aranet_device_poll_latency_seconds_bucket{{{},le=\"{}\"}} {}",
+                labels, bound, count
+            ));
+        }
+        latency_metrics.push(format!(
+            "aranet_device_poll_latency_seconds_bucket{{{},le=\"+Inf\"}} {}",
+            labels, latency.count
+        ));
+        latency_metrics.push(format!(
+            "aranet_device_poll_latency_seconds_sum{{{}}} {:.3}",
+            labels, latency.sum
+        ));
+        latency_metrics.push(format!(
+            "aranet_device_poll_latency_seconds_count{{{}}} {}",
+            labels, latency.count
+        ));
+    }
+    drop(metrics);
+    drop(device_stats);
+
+    write_metric_family(
+        output,
+        "aranet_device_connect_attempts_total",
+        "BLE connection attempts, including history syncs",
+        "counter",
+        &connect_metrics,
+    );
+    write_metric_family(
+        output,
+        "aranet_device_poll_errors_total",
+        "Failed polls by error category",
+        "counter",
+        &error_metrics,
+    );
+    write_metric_family(
+        output,
+        "aranet_device_poll_latency_seconds",
+        "Poll duration in seconds",
+        "histogram",
+        &latency_metrics,
+    );
+
+    let sync_jobs = state.sync_jobs.lock().await.unfinished_count();
+    let settings_jobs = state.settings_jobs.lock().await.pending_count();
+    for (name, help, metric_type, value) in [
+        (
+            "aranet_collector_ble_permits_available",
+            "BLE connection slots not in use",
+            "gauge",
+            state.ble_semaphore.available_permits() as u64,
+        ),
+        (
+            "aranet_collector_sync_jobs_queued",
+            "History syncs pending or running",
+            "gauge",
+            sync_jobs as u64,
+        ),
+        (
+            "aranet_collector_settings_jobs_queued",
+            "Settings changes waiting for a connection",
+            "gauge",
+            settings_jobs as u64,
+        ),
+        (
+            "aranet_ws_clients",
+            "Connected WebSocket clients",
+            "gauge",
+            state.ws_clients.load(Ordering::Relaxed),
+        ),
+        (
+            "aranet_ws_messages_dropped_total",
+            "Broadcast messages dropped due to slow WebSocket subscribers",
+            "counter",
+            state.ws_messages_dropped.load(Ordering::Relaxed),
+        ),
+    ] {
+        write_metric_family(
+            output,
+            name,
+            help,
+            metric_type,
+            &[format!("{} {}", name, value)],
+        );
+    }
+}
+
 /// Build per-device reading metrics from the latest stored readings.
 fn build_device_metrics(
     output: &mut String,
@@ -579,6 +795,37 @@ fn build_device_metrics(
     }
 }
 
+/// Build `aranet_channel_value` metrics from the latest computed channel values.
+fn build_channel_metrics(
+    output: &mut String,
+    values: &[aranet_store::StoredChannelValue],
+    alias_map: &std::collections::HashMap<String, String>,
+) {
+    let metrics: Vec<String> = values
+        .iter()
+        .map(|value| {
+            let device_label = alias_map
+                .get(&value.device_id)
+                .map(|s| s.as_str())
+                .unwrap_or(&value.device_id);
+            format!(
+                "aranet_channel_value{{device=\"{}\",address=\"{}\",channel=\"{}\"}} {:.2}",
+                escape_label_value(device_label),
+                escape_label_value(&value.device_id),
+                escape_label_value(&value.channel),
+                value.value
+            )
+        })
+        .collect();
+    write_metric_family(
+        output,
+        "aranet_channel_value",
+        "Latest value of a computed channel",
+        "gauge",
+        &metrics,
+    );
+}
+
 /// Resolve a device's type from stored metadata, falling back to name-based detection.
 fn resolve_device_type(device: &aranet_store::StoredDevice) -> Option<aranet_types::DeviceType> {
     device.device_type.or_else(|| {
@@ -710,7 +957,7 @@ async fn collector_stop(State(state): State<Arc<AppState>>) -> Json<CollectorAct
 // Configuration Endpoints
 // ==========================================================================
 
-/// Configuration response (excludes storage path for security).
+/// Response to a configuration update (excludes storage path for security).
 #[derive(Debug, Serialize)]
 pub struct ConfigResponse {
     pub server: ServerConfigResponse,
@@ -731,6 +978,10 @@ pub struct DeviceConfigResponse {
     pub alias: Option<String>,
     #[serde(default = "default_poll_interval")]
     pub poll_interval: u64,
+    #[serde(default)]
+    pub privacy: DevicePrivacy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_history_sync_battery: Option<u8>,
 }
 
 fn default_poll_interval() -> u64 {
@@ -741,27 +992,15 @@ fn config_save_error(error: crate::config::ConfigError) -> AppError {
     AppError::Internal(format!("Failed to save configuration: {}", error))
 }
 
-/// Get current configuration.
+/// Get the current configuration with secrets redacted.
+///
+/// See [`Config::redacted`] for what is hidden.
 ///
 /// # Lock Acquisition
 ///
 /// Acquires a read lock on `config`. Multiple concurrent readers are allowed.
-async fn get_config(State(state): State<Arc<AppState>>) -> Json<ConfigResponse> {
-    let config = state.config.read().await;
-    Json(ConfigResponse {
-        server: ServerConfigResponse {
-            bind: config.server.bind.clone(),
-        },
-        devices: config
-            .devices
-            .iter()
-            .map(|d| DeviceConfigResponse {
-                address: d.address.clone(),
-                alias: d.alias.clone(),
-                poll_interval: d.poll_interval,
-            })
-            .collect(),
-    })
+async fn get_config(State(state): State<Arc<AppState>>) -> Json<Config> {
+    Json(state.config.read().await.redacted())
 }
 
 /// Request to update configuration.
@@ -798,6 +1037,8 @@ async fn update_config(
                     address: d.address,
                     alias: d.alias,
                     poll_interval: d.poll_interval,
+                    privacy: d.privacy,
+                    min_history_sync_battery: d.min_history_sync_battery,
                 })
                 .collect();
         }
@@ -827,6 +1068,8 @@ async fn update_config(
                     address: d.address.clone(),
                     alias: d.alias.clone(),
                     poll_interval: d.poll_interval,
+                    privacy: d.privacy,
+                    min_history_sync_battery: d.min_history_sync_battery,
                 })
                 .collect(),
         }
@@ -846,6 +1089,8 @@ pub struct AddDeviceRequest {
     pub alias: Option<String>,
     #[serde(default = "default_poll_interval")]
     pub poll_interval: u64,
+    #[serde(default)]
+    pub privacy: DevicePrivacy,
 }
 
 /// Add a device to monitor.
@@ -882,6 +1127,8 @@ async fn add_device(
             address: request.address.clone(),
             alias: request.alias.clone(),
             poll_interval: request.poll_interval,
+            privacy: request.privacy,
+            min_history_sync_battery: None,
         };
 
         // Validate the device config
@@ -907,6 +1154,8 @@ async fn add_device(
             address: request.address.clone(),
             alias: request.alias.clone(),
             poll_interval: request.poll_interval,
+            privacy: request.privacy,
+            min_history_sync_battery: None,
         }
     };
 
@@ -924,6 +1173,8 @@ pub struct UpdateDeviceRequest {
     pub alias: Option<Option<String>>,
     #[serde(default)]
     pub poll_interval: Option<u64>,
+    #[serde(default)]
+    pub privacy: Option<DevicePrivacy>,
 }
 
 /// Deserialize a field that distinguishes between absent, null, and present.
@@ -966,6 +1217,9 @@ async fn update_device(
             if let Some(poll_interval) = request.poll_interval {
                 device.poll_interval = poll_interval;
             }
+            if let Some(privacy) = request.privacy {
+                device.privacy = privacy;
+            }
 
             // Validate the updated device
             let errors = device.validate("device");
@@ -986,6 +1240,8 @@ async fn update_device(
                 address: device.address.clone(),
                 alias: device.alias.clone(),
                 poll_interval: device.poll_interval,
+                privacy: device.privacy,
+                min_history_sync_battery: device.min_history_sync_battery,
             }
         };
 
@@ -1050,6 +1306,13 @@ pub struct DeviceResponse {
     pub first_seen: OffsetDateTime,
     #[serde(with = "time::serde::rfc3339")]
     pub last_seen: OffsetDateTime,
+    /// Latest clock drift estimate (only included for single-device lookups).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clock_drift: Option<aranet_store::StoredClockDrift>,
+    /// Collector backoff state while the device keeps failing to poll
+    /// (only included for single-device lookups).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backoff: Option<DeviceBackoff>,
 }
 
 impl From<aranet_store::StoredDevice> for DeviceResponse {
@@ -1062,6 +1325,8 @@ impl From<aranet_store::StoredDevice> for DeviceResponse {
             firmware: d.firmware,
             first_seen: d.first_seen,
             last_seen: d.last_seen,
+            clock_drift: None,
+            backoff: None,
         }
     }
 }
@@ -1077,21 +1342,396 @@ impl From<aranet_store::StoredDevice> for DeviceResponse {
 /// Returns [`AppError::Store`] if the database query fails.
 async fn list_devices(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
 ) -> Result<Json<Vec<DeviceResponse>>, AppError> {
+    let hidden = hidden_devices(&state, &caller).await;
     let devices = state.with_store_read(|store| store.list_devices()).await?;
-    Ok(Json(devices.into_iter().map(Into::into).collect()))
+    Ok(Json(
+        devices
+            .into_iter()
+            .filter(|d| !is_hidden(&hidden, &d.id))
+            .map(Into::into)
+            .collect(),
+    ))
+}
+
+/// Credentials a request was authenticated with, if any.
+type Caller = Option<Extension<Scope>>;
+
+/// Configured devices whose readings the caller may not see.
+///
+/// Requests without credentials only see public devices.
+async fn hidden_devices(state: &AppState, caller: &Caller) -> Vec<String> {
+    if caller.is_some() {
+        return Vec::new();
+    }
+    state
+        .config
+        .read()
+        .await
+        .devices_without(DevicePrivacy::is_public)
+}
+
+fn is_hidden(hidden: &[String], device_id: &str) -> bool {
+    hidden.iter().any(|d| d.eq_ignore_ascii_case(device_id))
+}
+
+/// Fail with [`AppError::NotFound`] if the caller may not see the device.
+async fn ensure_visible(state: &AppState, caller: &Caller, id: &str) -> Result<(), AppError> {
+    if is_hidden(&hidden_devices(state, caller).await, id) {
+        return Err(AppError::NotFound(format!("Device not found: {}", id)));
+    }
+    Ok(())
 }
 
 /// Get a single device.
 async fn get_device(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Path(id): Path<String>,
 ) -> Result<Json<DeviceResponse>, AppError> {
-    let device = state
-        .with_store_read(|store| store.get_device(&id))
-        .await?
-        .ok_or(AppError::NotFound(format!("Device not found: {}", id)))?;
-    Ok(Json(device.into()))
+    ensure_visible(&state, &caller, &id).await?;
+    let (device, clock_drift) = state
+        .with_store_read(|store| Ok((store.get_device(&id)?, store.get_clock_drift(&id)?)))
+        .await?;
+    let device = device.ok_or(AppError::NotFound(format!("Device not found: {}", id)))?;
+
+    let mut response = DeviceResponse::from(device);
+    response.clock_drift = clock_drift;
+    response.backoff = state
+        .collector
+        .device_stats
+        .read()
+        .await
+        .iter()
+        .find(|s| s.device_id == id)
+        .and_then(|s| s.backoff.clone());
+    Ok(Json(response))
+}
+
+/// Query parameters for deleting a device.
+#[derive(Debug, Deserialize, Default)]
+pub struct DeleteDeviceQuery {
+    /// Also delete the device's readings, history and sync state.
+    #[serde(default)]
+    pub purge: bool,
+    /// Report what would be deleted without changing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Response for a device deletion.
+#[derive(Debug, Serialize)]
+pub struct DeleteDeviceResponse {
+    pub id: String,
+    pub dry_run: bool,
+    /// Whether the device was (or would be) removed from monitoring.
+    pub removed_from_config: bool,
+    /// Rows deleted (or that would be deleted) from the store, when purging.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purged: Option<aranet_store::DeviceDataCounts>,
+}
+
+/// Stop monitoring a device and optionally purge its stored data.
+///
+/// The device is removed from the config first so the collector stops
+/// polling it; with `purge=true` its rows are then deleted in a single
+/// store transaction. With `dry_run=true` nothing is changed and the
+/// response reports the row counts that would be deleted.
+///
+/// # Errors
+///
+/// Returns [`AppError::NotFound`] if the device is neither configured nor
+/// (when purging) stored.
+async fn delete_device(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<DeleteDeviceQuery>,
+) -> Result<Json<DeleteDeviceResponse>, AppError> {
+    let id_lower = id.to_lowercase();
+    // Readings are stored under the configured address, so prefer its casing
+    let configured = state
+        .config
+        .read()
+        .await
+        .devices
+        .iter()
+        .find(|d| d.address.to_lowercase() == id_lower)
+        .map(|d| d.address.clone());
+    let store_id = configured.clone().unwrap_or_else(|| id.clone());
+
+    let counts = if params.purge {
+        Some(
+            state
+                .with_store_read(|store| store.count_device_data(&store_id))
+                .await?,
+        )
+    } else {
+        None
+    };
+
+    let stored = counts.as_ref().is_some_and(|c| c.total() > 0);
+    if configured.is_none() && !stored {
+        return Err(AppError::NotFound(format!("Device not found: {}", id)));
+    }
+
+    if params.dry_run {
+        return Ok(Json(DeleteDeviceResponse {
+            id: store_id,
+            dry_run: true,
+            removed_from_config: configured.is_some(),
+            purged: counts,
+        }));
+    }
+
+    if configured.is_some() {
+        {
+            let mut config = state.config.write().await;
+            let previous_devices = config.devices.clone();
+            config
+                .devices
+                .retain(|d| d.address.to_lowercase() != id_lower);
+            if let Err(e) = config.save(&state.config_path) {
+                config.devices = previous_devices;
+                return Err(config_save_error(e));
+            }
+        }
+        // Stop polling before purging so no new readings land afterwards
+        state.on_devices_changed().await;
+    }
+
+    let purged = if params.purge {
+        Some(
+            state
+                .with_store_write(|store| store.purge_device(&store_id))
+                .await?,
+        )
+    } else {
+        None
+    };
+
+    Ok(Json(DeleteDeviceResponse {
+        id: store_id,
+        dry_run: false,
+        removed_from_config: configured.is_some(),
+        purged,
+    }))
+}
+
+/// Request to change device settings over BLE.
+///
+/// Omitted fields are left unchanged.
+#[derive(Debug, Deserialize)]
+pub struct DeviceSettingsPatchRequest {
+    /// Measurement interval in seconds (60, 120, 300, or 600).
+    #[serde(default)]
+    pub interval: Option<u16>,
+    /// Enable or disable Smart Home integration.
+    #[serde(default)]
+    pub smart_home: Option<bool>,
+    /// Bluetooth range: `"standard"` or `"extended"`.
+    #[serde(default)]
+    pub bluetooth_range: Option<String>,
+}
+
+impl DeviceSettingsPatchRequest {
+    /// Validate the request into a settings patch.
+    pub(crate) fn into_patch(self) -> Result<SettingsPatch, AppError> {
+        let interval = self
+            .interval
+            .map(|secs| {
+                MeasurementInterval::from_seconds(secs).ok_or_else(|| {
+                    AppError::BadRequest(format!(
+                        "Invalid interval {secs}s: must be 60, 120, 300, or 600"
+                    ))
+                })
+            })
+            .transpose()?;
+        let bluetooth_range = self
+            .bluetooth_range
+            .map(|range| match range.to_lowercase().as_str() {
+                "standard" => Ok(BluetoothRange::Standard),
+                "extended" => Ok(BluetoothRange::Extended),
+                _ => Err(AppError::BadRequest(format!(
+                    "Invalid bluetooth_range '{range}': must be 'standard' or 'extended'"
+                ))),
+            })
+            .transpose()?;
+
+        let patch = SettingsPatch {
+            interval,
+            smart_home: self.smart_home,
+            bluetooth_range,
+        };
+        if patch.is_empty() {
+            return Err(AppError::BadRequest(
+                "At least one of interval, smart_home, or bluetooth_range is required".into(),
+            ));
+        }
+        Ok(patch)
+    }
+}
+
+/// Status of a queued settings change.
+#[derive(Debug, Serialize)]
+pub struct SettingsJobResponse {
+    pub id: u64,
+    pub device_id: String,
+    pub status: SettingsJobStatus,
+    /// Requested interval in seconds.
+    pub interval: Option<u16>,
+    pub smart_home: Option<bool>,
+    pub bluetooth_range: Option<&'static str>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub completed_at: Option<OffsetDateTime>,
+    /// Changes written to the device.
+    pub applied: Vec<String>,
+    /// Changes the device does not support.
+    pub skipped: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl From<SettingsJob> for SettingsJobResponse {
+    fn from(job: SettingsJob) -> Self {
+        Self {
+            id: job.id,
+            device_id: job.device_id,
+            status: job.status,
+            interval: job.patch.interval.map(|i| i.as_seconds()),
+            smart_home: job.patch.smart_home,
+            bluetooth_range: job.patch.bluetooth_range.map(|range| match range {
+                BluetoothRange::Standard => "standard",
+                BluetoothRange::Extended => "extended",
+            }),
+            created_at: job.created_at,
+            completed_at: job.completed_at,
+            applied: job.applied,
+            skipped: job.skipped,
+            error: job.error,
+        }
+    }
+}
+
+/// Queue a settings change for a monitored device.
+///
+/// The collector applies it the next time it connects to the device; poll
+/// `/api/settings/jobs/{job_id}` for the result.
+async fn patch_device_settings(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(request): Json<DeviceSettingsPatchRequest>,
+) -> Result<(StatusCode, Json<SettingsJobResponse>), AppError> {
+    let patch = request.into_patch()?;
+
+    let address = monitored_device_address(&state, &id).await?;
+    let job = state.settings_jobs.lock().await.enqueue(&address, patch);
+    Ok((StatusCode::ACCEPTED, Json(job.into())))
+}
+
+/// Resolve a monitored device's configured address, case-insensitively.
+async fn monitored_device_address(state: &AppState, id: &str) -> Result<String, AppError> {
+    let config = state.config.read().await;
+    config
+        .devices
+        .iter()
+        .find(|d| d.address.eq_ignore_ascii_case(id))
+        .map(|d| d.address.clone())
+        .ok_or_else(|| AppError::NotFound(format!("Device {} not found in config", id)))
+}
+
+/// Get the status of a queued settings change.
+async fn get_settings_job(
+    State(state): State<Arc<AppState>>,
+    Path(job_id): Path<u64>,
+) -> Result<Json<SettingsJobResponse>, AppError> {
+    let job = state
+        .settings_jobs
+        .lock()
+        .await
+        .get(job_id)
+        .cloned()
+        .ok_or_else(|| AppError::NotFound(format!("Settings job not found: {}", job_id)))?;
+    Ok(Json(job.into()))
+}
+
+/// Status of an on-demand history sync.
+#[derive(Debug, Serialize)]
+pub struct SyncJobResponse {
+    pub id: u64,
+    pub device_id: String,
+    pub status: SyncJobStatus,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub started_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub completed_at: Option<OffsetDateTime>,
+    /// Records to download, once known.
+    pub total_records: Option<u16>,
+    /// Download progress from 0.0 to 1.0.
+    pub progress: f32,
+    /// Records downloaded from the device.
+    pub downloaded: usize,
+    /// Records that were new to the store.
+    pub inserted: usize,
+    pub error: Option<String>,
+}
+
+impl From<SyncJob> for SyncJobResponse {
+    fn from(job: SyncJob) -> Self {
+        Self {
+            id: job.id,
+            device_id: job.device_id,
+            status: job.status,
+            created_at: job.created_at,
+            started_at: job.started_at,
+            completed_at: job.completed_at,
+            total_records: job.total_records,
+            progress: job.progress,
+            downloaded: job.downloaded,
+            inserted: job.inserted,
+            error: job.error,
+        }
+    }
+}
+
+/// Start an on-demand history sync for a monitored device.
+///
+/// The sync runs as soon as the Bluetooth adapter is free; poll
+/// `/api/sync/jobs/{job_id}` for progress. If a sync for the device is already
+/// pending or running, that job is returned instead of starting another.
+async fn sync_device_history(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, Json<SyncJobResponse>), AppError> {
+    let address = monitored_device_address(&state, &id).await?;
+
+    let (job, created) = state.sync_jobs.lock().await.enqueue(&address);
+    if created {
+        tokio::spawn(crate::collector::run_sync_job(
+            Arc::clone(&state),
+            job.id,
+            address,
+        ));
+    }
+    Ok((StatusCode::ACCEPTED, Json(job.into())))
+}
+
+/// Get the status of a history sync job.
+async fn get_sync_job(
+    State(state): State<Arc<AppState>>,
+    Path(job_id): Path<u64>,
+) -> Result<Json<SyncJobResponse>, AppError> {
+    let job = state
+        .sync_jobs
+        .lock()
+        .await
+        .get(job_id)
+        .cloned()
+        .ok_or_else(|| AppError::NotFound(format!("Sync job not found: {}", job_id)))?;
+    Ok(Json(job.into()))
 }
 
 /// Default staleness threshold in seconds when no collector stats are available.
@@ -1151,7 +1791,9 @@ fn reading_is_stale(
 /// List the latest reading for every device with current metadata.
 async fn list_current_readings(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
 ) -> Result<Json<Vec<DeviceLatestReadingResponse>>, AppError> {
+    let hidden = hidden_devices(&state, &caller).await;
     let latest = state
         .with_store_read(|store| store.list_latest_readings())
         .await?;
@@ -1180,6 +1822,7 @@ async fn list_current_readings(
 
     let response = latest
         .into_iter()
+        .filter(|(device, _)| !is_hidden(&hidden, &device.id))
         .map(|(device, reading)| {
             let age_seconds = reading_age_seconds(&reading);
             DeviceLatestReadingResponse {
@@ -1202,8 +1845,10 @@ async fn list_current_readings(
 /// A reading is considered stale if its age exceeds 3x the device's poll interval.
 async fn get_current_reading(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Path(id): Path<String>,
 ) -> Result<Json<CurrentReadingResponse>, AppError> {
+    ensure_visible(&state, &caller, &id).await?;
     let reading = state
         .with_store_read(|store| store.get_latest_reading(&id))
         .await?
@@ -1238,6 +1883,13 @@ pub struct ReadingsQuery {
     pub until: Option<i64>,
     pub limit: Option<u32>,
     pub offset: Option<u32>,
+    /// Skip readings the collector flagged as invalid.
+    #[serde(default)]
+    pub exclude_invalid: bool,
+    /// Filter expression on measured values, e.g. `co2>1000 AND humidity<30`.
+    /// See [`aranet_store::ValueFilter`] for the syntax.
+    #[serde(default, rename = "where")]
+    pub filter: Option<String>,
 }
 
 /// Maximum allowed limit for query results.
@@ -1265,11 +1917,23 @@ impl ReadingsQuery {
         Self::parse_timestamp("until", self.until)
     }
 
+    fn value_filter(&self) -> Result<Option<aranet_store::ValueFilter>, AppError> {
+        self.filter
+            .as_deref()
+            .map(|filter| {
+                aranet_store::ValueFilter::parse(filter)
+                    .map_err(|e| AppError::BadRequest(format!("Invalid 'where': {}", e)))
+            })
+            .transpose()
+    }
+
     /// Validate the query parameters.
-    /// Returns an error if timestamps are invalid, `since > until`, or `limit` exceeds the maximum.
+    /// Returns an error if timestamps are invalid, `since > until`, `limit` exceeds the maximum,
+    /// or the `where` expression does not parse.
     pub fn validate(&self) -> Result<(), AppError> {
         let since = self.since_datetime()?;
         let until = self.until_datetime()?;
+        self.value_filter()?;
 
         if let (Some(since), Some(until)) = (since, until)
             && since > until
@@ -1324,6 +1988,7 @@ pub struct PaginationMeta {
 /// - `until`: Unix timestamp to filter readings until (inclusive)
 /// - `limit`: Maximum number of readings to return
 /// - `offset`: Number of readings to skip (for pagination)
+/// - `exclude_invalid`: Skip readings that failed validation
 ///
 /// # Lock Acquisition
 ///
@@ -1332,15 +1997,17 @@ pub struct PaginationMeta {
 ///
 /// # Errors
 ///
-/// - Returns [`AppError::BadRequest`] if `since > until`
+/// - Returns [`AppError::BadRequest`] if `since > until` or `where` is invalid
 /// - Returns [`AppError::Store`] if the database query fails
 async fn get_readings(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Path(id): Path<String>,
     Query(params): Query<ReadingsQuery>,
 ) -> Result<Json<PaginatedResponse<aranet_store::StoredReading>>, AppError> {
     // Validate query parameters
     params.validate()?;
+    ensure_visible(&state, &caller, &id).await?;
 
     let mut query = aranet_store::ReadingQuery::new().device(&id);
 
@@ -1359,9 +2026,15 @@ async fn get_readings(
     if let Some(offset) = params.offset {
         query = query.offset(offset);
     }
+    if params.exclude_invalid {
+        query = query.exclude_invalid();
+    }
+    if let Some(filter) = params.value_filter()? {
+        query = query.filter(filter);
+    }
 
     let mut readings = state
-        .with_store_read(|store| store.query_readings(&query))
+        .with_store_read_blocking(move |store| store.query_readings(&query))
         .await?;
 
     // Check if there are more items
@@ -1387,15 +2060,17 @@ async fn get_readings(
 ///
 /// # Errors
 ///
-/// - Returns [`AppError::BadRequest`] if `since > until`
+/// - Returns [`AppError::BadRequest`] if `since > until` or `where` is invalid
 /// - Returns [`AppError::Store`] if the database query fails
 async fn get_history(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Path(id): Path<String>,
     Query(params): Query<ReadingsQuery>,
 ) -> Result<Json<PaginatedResponse<aranet_store::StoredHistoryRecord>>, AppError> {
     // Validate query parameters
     params.validate()?;
+    ensure_visible(&state, &caller, &id).await?;
 
     let mut query = aranet_store::HistoryQuery::new().device(&id);
 
@@ -1414,9 +2089,12 @@ async fn get_history(
     if let Some(offset) = params.offset {
         query = query.offset(offset);
     }
+    if let Some(filter) = params.value_filter()? {
+        query = query.filter(filter);
+    }
 
     let mut history = state
-        .with_store_read(|store| store.query_history(&query))
+        .with_store_read_blocking(move |store| store.query_history(&query))
         .await?;
 
     // Check if there are more items
@@ -1436,22 +2114,77 @@ async fn get_history(
     }))
 }
 
+/// Query parameters for computed channel values.
+#[derive(Debug, Deserialize, Default)]
+pub struct ChannelValuesQuery {
+    /// Only values of this channel.
+    pub channel: Option<String>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub limit: Option<u32>,
+}
+
+/// Get computed channel values for a device, oldest first.
+///
+/// # Errors
+///
+/// - Returns [`AppError::BadRequest`] if a timestamp is invalid or `since > until`
+/// - Returns [`AppError::Store`] if the database query fails
+async fn get_channel_values(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(id): Path<String>,
+    Query(params): Query<ChannelValuesQuery>,
+) -> Result<Json<Vec<aranet_store::StoredChannelValue>>, AppError> {
+    let range = ReadingsQuery {
+        since: params.since,
+        until: params.until,
+        limit: params.limit,
+        ..Default::default()
+    };
+    range.validate()?;
+    ensure_visible(&state, &caller, &id).await?;
+
+    let mut query = aranet_store::ChannelQuery::new().device(&id);
+    if let Some(channel) = &params.channel {
+        query = query.channel(channel);
+    }
+    if let Some(dt) = range.since_datetime()? {
+        query = query.since(dt);
+    }
+    if let Some(dt) = range.until_datetime()? {
+        query = query.until(dt);
+    }
+    if let Some(limit) = params.limit {
+        query = query.limit(limit);
+    }
+
+    let values = state
+        .with_store_read(|store| store.query_channel_values(&query))
+        .await?;
+    Ok(Json(values))
+}
+
 /// Get all readings across devices.
 ///
 /// Returns a paginated response with readings from all devices.
 ///
 /// # Errors
 ///
-/// - Returns [`AppError::BadRequest`] if `since > until`
+/// - Returns [`AppError::BadRequest`] if `since > until` or `where` is invalid
 /// - Returns [`AppError::Store`] if the database query fails
 async fn get_all_readings(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Query(params): Query<ReadingsQuery>,
 ) -> Result<Json<PaginatedResponse<aranet_store::StoredReading>>, AppError> {
     // Validate query parameters
     params.validate()?;
 
     let mut query = aranet_store::ReadingQuery::new();
+    for device_id in hidden_devices(&state, &caller).await {
+        query = query.exclude_device(&device_id);
+    }
 
     if let Some(dt) = params.since_datetime()? {
         query = query.since(dt);
@@ -1468,9 +2201,15 @@ async fn get_all_readings(
     if let Some(offset) = params.offset {
         query = query.offset(offset);
     }
+    if params.exclude_invalid {
+        query = query.exclude_invalid();
+    }
+    if let Some(filter) = params.value_filter()? {
+        query = query.filter(filter);
+    }
 
     let mut readings = state
-        .with_store_read(|store| store.query_readings(&query))
+        .with_store_read_blocking(move |store| store.query_readings(&query))
         .await?;
 
     // Check if there are more items
@@ -1490,26 +2229,135 @@ async fn get_all_readings(
     }))
 }
 
-/// Application error type.
-#[derive(Debug, thiserror::Error)]
-pub enum AppError {
-    #[error("{0}")]
-    NotFound(String),
-    #[error("{0}")]
-    BadRequest(String),
-    #[error("{0}")]
-    Conflict(String),
-    #[error("{0}")]
-    ServiceUnavailable(String),
-    #[error(transparent)]
-    Store(#[from] aranet_store::Error),
-    #[error("{0}")]
-    Internal(String),
+/// Most readings accepted by one `POST /api/ingest` request.
+pub const MAX_INGEST_BATCH: usize = 1000;
+
+/// Readings pushed by another aranet-service instance.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IngestRequest {
+    /// Readings to store, in any order.
+    pub readings: Vec<IngestReading>,
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> axum::response::Response {
-        let (status, message) = match self {
+/// One reading in an [`IngestRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestReading {
+    /// Display name for the device, set on the receiving side if present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The reading as stored by the sender. Its `id` is ignored.
+    #[serde(flatten)]
+    pub reading: aranet_store::StoredReading,
+}
+
+/// Response for `POST /api/ingest`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IngestResponse {
+    /// Readings stored.
+    pub accepted: usize,
+    /// Readings skipped because one with the same device and capture time
+    /// was already stored, e.g. from a retried batch.
+    pub duplicates: usize,
+}
+
+/// Store readings forwarded by an edge collector.
+///
+/// Each reading is inserted with the quality flag the sender gave it and
+/// broadcast to WebSocket clients and exporters as if it had been collected
+/// here. Resending a batch is harmless: readings already stored for the same
+/// device and capture time are counted as duplicates and skipped.
+///
+/// # Errors
+///
+/// - [`AppError::BadRequest`] if the batch has more than [`MAX_INGEST_BATCH`]
+///   readings or a reading has an empty device ID
+/// - [`AppError::Store`] if the database write fails
+async fn ingest_readings(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<IngestRequest>,
+) -> Result<Json<IngestResponse>, AppError> {
+    if request.readings.len() > MAX_INGEST_BATCH {
+        return Err(AppError::BadRequest(format!(
+            "Batch of {} readings exceeds the limit of {}",
+            request.readings.len(),
+            MAX_INGEST_BATCH
+        )));
+    }
+    if request
+        .readings
+        .iter()
+        .any(|r| r.reading.device_id.trim().is_empty())
+    {
+        return Err(AppError::BadRequest(
+            "Reading device_id cannot be empty".to_string(),
+        ));
+    }
+
+    let received = request.readings.len();
+    let stored = state
+        .with_store_write_blocking(move |store| {
+            let mut stored = Vec::new();
+            for IngestReading { name, reading } in request.readings {
+                let query = aranet_store::ReadingQuery::new()
+                    .device(&reading.device_id)
+                    .since(reading.captured_at)
+                    .until(reading.captured_at)
+                    .limit(1);
+                if !store.query_readings(&query)?.is_empty() {
+                    continue;
+                }
+                if name.is_some() {
+                    store.upsert_device(&reading.device_id, name.as_deref())?;
+                }
+                let id = store.insert_reading_with_quality(
+                    &reading.device_id,
+                    &reading.to_reading(),
+                    reading.quality,
+                )?;
+                stored.push(aranet_store::StoredReading { id, ..reading });
+            }
+            Ok(stored)
+        })
+        .await?;
+
+    let response = IngestResponse {
+        accepted: stored.len(),
+        duplicates: received - stored.len(),
+    };
+    for reading in stored {
+        let channels = crate::channels::evaluate(&state, &reading.device_id, &reading).await;
+        let event = ReadingEvent {
+            device_id: reading.device_id.clone(),
+            reading,
+            channels,
+        };
+        // No subscribers is fine; the reading is already stored
+        let _ = state.readings_tx.send(event);
+    }
+
+    Ok(Json(response))
+}
+
+/// Application error type.
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    ServiceUnavailable(String),
+    #[error(transparent)]
+    Store(#[from] aranet_store::Error),
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, message) = match self {
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
             AppError::Conflict(msg) => (StatusCode::CONFLICT, msg),
@@ -1543,7 +2391,7 @@ mod tests {
     use time::Duration;
     use tower::ServiceExt;
 
-    use crate::config::{Config, SecurityConfig};
+    use crate::config::SecurityConfig;
     use crate::middleware::RateLimitState;
     use aranet_types::HistoryRecord;
 
@@ -1578,8 +2426,11 @@ mod tests {
             rate_limit_enabled: true,
             rate_limit_requests: 1,
             rate_limit_window_secs: 60,
+            rate_limit_api_key_requests: None,
+            rate_limit_routes: Vec::new(),
             rate_limit_max_entries: 1024,
             cors_origins: vec!["http://localhost:3000".to_string()],
+            oidc: Default::default(),
         }
     }
 
@@ -1676,6 +2527,86 @@ mod tests {
         assert!(json["error"].as_str().unwrap().contains("not found"));
     }
 
+    #[tokio::test]
+    async fn test_get_device_includes_clock_drift() {
+        let state = create_test_state();
+        state
+            .with_store_write(|store| {
+                store.upsert_device("AA:BB:CC:DD:EE:FF", Some("Office"))?;
+                store.update_clock_drift("AA:BB:CC:DD:EE:FF", 87.5, 43_200)
+            })
+            .await
+            .unwrap();
+        let app = router().with_state(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/devices/AA:BB:CC:DD:EE:FF")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let json: serde_json::Value = serde_json::from_str(&response_body(response).await).unwrap();
+        assert_eq!(json["clock_drift"]["drift_ppm"], 87.5);
+        assert_eq!(json["clock_drift"]["span_seconds"], 43_200);
+    }
+
+    #[tokio::test]
+    async fn test_get_device_includes_backoff() {
+        let state = create_test_state();
+        state
+            .with_store_write(|store| store.upsert_device("AA:BB:CC:DD:EE:FF", None))
+            .await
+            .unwrap();
+        let next_attempt_at = time::macros::datetime!(2026-01-01 12:00 UTC);
+        state
+            .collector
+            .device_stats
+            .write()
+            .await
+            .push(DeviceCollectionStats {
+                device_id: "AA:BB:CC:DD:EE:FF".to_string(),
+                alias: None,
+                poll_interval: 60,
+                last_poll_at: None,
+                last_error_at: None,
+                last_error: Some("connection failed".to_string()),
+                last_poll_duration_ms: None,
+                success_count: 0,
+                failure_count: 3,
+                polling: false,
+                clock_drift_ppm: None,
+                history_overwrite_at: None,
+                history_sync_deferred_below: None,
+                backoff: Some(DeviceBackoff {
+                    consecutive_failures: 3,
+                    delay_secs: 240,
+                    next_attempt_at,
+                }),
+            });
+        let app = router().with_state(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/devices/AA:BB:CC:DD:EE:FF")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let json: serde_json::Value = serde_json::from_str(&response_body(response).await).unwrap();
+        assert_eq!(json["backoff"]["consecutive_failures"], 3);
+        assert_eq!(json["backoff"]["delay_secs"], 240);
+        assert_eq!(json["backoff"]["next_attempt_at"], "2026-01-01T12:00:00Z");
+    }
+
     #[tokio::test]
     async fn test_get_current_reading_not_found() {
         let state = create_test_state();
@@ -1742,6 +2673,59 @@ mod tests {
         assert_eq!(json["pagination"]["count"], 0);
     }
 
+    #[tokio::test]
+    async fn test_private_devices_hidden_without_credentials() {
+        use crate::config::SecurityZone;
+
+        let state = create_test_state();
+        {
+            let mut config = state.config.write().await;
+            config.devices.push(DeviceConfig {
+                address: "Bedroom".to_string(),
+                alias: None,
+                poll_interval: 60,
+                privacy: DevicePrivacy {
+                    zone: SecurityZone::Local,
+                    ..Default::default()
+                },
+                min_history_sync_battery: None,
+            });
+            let store = state.store.lock().await;
+            let reading = aranet_types::CurrentReading::builder().co2(800).build();
+            store.insert_reading("Bedroom", &reading).unwrap();
+            store.insert_reading("Kitchen", &reading).unwrap();
+        }
+
+        let get = |uri: &str, scope: Option<Scope>| {
+            let mut app = router().with_state(Arc::clone(&state));
+            if let Some(scope) = scope {
+                app = app.layer(Extension(scope));
+            }
+            app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        };
+
+        let response = get("/api/devices/current", None).await.unwrap();
+        let json: serde_json::Value = serde_json::from_str(&response_body(response).await).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 1);
+        assert_eq!(json[0]["device_id"], "Kitchen");
+
+        let response = get("/api/readings", None).await.unwrap();
+        let json: serde_json::Value = serde_json::from_str(&response_body(response).await).unwrap();
+        assert_eq!(json["pagination"]["count"], 1);
+
+        let response = get("/api/devices/bedroom/current", None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = get("/api/devices/Bedroom/current", Some(Scope::Read))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = get("/api/devices", Some(Scope::Read)).await.unwrap();
+        let json: serde_json::Value = serde_json::from_str(&response_body(response).await).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+    }
+
     #[tokio::test]
     async fn test_get_history_empty() {
         let state = create_test_state();
@@ -1836,6 +2820,97 @@ mod tests {
 
         assert_eq!(second.status(), StatusCode::TOO_MANY_REQUESTS);
         assert_eq!(second.headers().get("x-ratelimit-limit").unwrap(), "1");
+        assert_eq!(second.headers().get("x-ratelimit-remaining").unwrap(), "0");
+        assert_eq!(second.headers().get("ratelimit-limit").unwrap(), "1");
+        assert_eq!(second.headers().get("ratelimit-remaining").unwrap(), "0");
+        assert_eq!(second.headers().get("ratelimit-policy").unwrap(), "1;w=60");
+        assert!(second.headers().contains_key("retry-after"));
+    }
+
+    #[tokio::test]
+    async fn test_full_app_rate_limit_route_overrides() {
+        let mut security = SecurityConfig {
+            api_key_enabled: false,
+            api_key: None,
+            ..create_security_config()
+        };
+        security.rate_limit_requests = 100;
+        security.rate_limit_routes = vec![
+            crate::config::RouteRateLimit {
+                path: "/api/health".to_string(),
+                requests: None,
+                window_secs: None,
+            },
+            crate::config::RouteRateLimit {
+                path: "/api/devices/{id}".to_string(),
+                requests: Some(1),
+                window_secs: Some(30),
+            },
+        ];
+        let app = create_full_app(security);
+        let get = |uri: &'static str| {
+            app.clone()
+                .oneshot(request_with_connect_info(axum::http::Method::GET, uri))
+        };
+
+        // Unlimited routes carry no rate limit headers
+        for _ in 0..3 {
+            let response = get("/api/health").await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(!response.headers().contains_key("ratelimit-limit"));
+        }
+
+        let first = get("/api/devices/a").await.unwrap();
+        assert_eq!(first.headers().get("ratelimit-policy").unwrap(), "1;w=30");
+        let second = get("/api/devices/b").await.unwrap();
+        assert_eq!(second.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // Other routes still use the default window
+        let response = get("/api/devices").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get("ratelimit-limit").unwrap(), "100");
+        assert_eq!(response.headers().get("ratelimit-remaining").unwrap(), "99");
+    }
+
+    #[tokio::test]
+    async fn test_full_app_rate_limit_per_api_key() {
+        let mut security = create_security_config();
+        security.rate_limit_requests = 1;
+        security.rate_limit_api_key_requests = Some(2);
+        let api_key = security.api_key.clone().unwrap();
+        let app = create_full_app(security);
+        let request = |key: Option<&str>| {
+            let mut request = request_with_connect_info(axum::http::Method::GET, "/api/devices");
+            if let Some(key) = key {
+                request
+                    .headers_mut()
+                    .insert("X-API-Key", key.parse().unwrap());
+            }
+            app.clone().oneshot(request)
+        };
+
+        // The key gets its own, larger window
+        assert_eq!(
+            request(Some(&api_key)).await.unwrap().status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            request(Some(&api_key)).await.unwrap().status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            request(Some(&api_key)).await.unwrap().status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+
+        // Wrong keys count against the IP's window
+        let wrong = request(Some("wrong-key")).await.unwrap();
+        assert_eq!(wrong.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(wrong.headers().get("ratelimit-limit").unwrap(), "1");
+        assert_eq!(
+            request(None).await.unwrap().status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
     }
 
     #[tokio::test]
@@ -1929,6 +3004,99 @@ mod tests {
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[tokio::test]
+    async fn test_get_channel_values() {
+        let state = create_test_state();
+        {
+            let store = state.store.lock().await;
+            for (i, (channel, value)) in [
+                ("dew_point", 9.5),
+                ("co2_delta", 780.0),
+                ("dew_point", 10.0),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let at = OffsetDateTime::UNIX_EPOCH + Duration::seconds(i as i64 + 1);
+                store
+                    .insert_channel_value("test-device", channel, at, value)
+                    .unwrap();
+            }
+        }
+        let app = router().with_state(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/devices/test-device/channels?channel=dew_point")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_body(response).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let values = json.as_array().unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0]["value"], 9.5);
+        assert_eq!(values[1]["channel"], "dew_point");
+    }
+
+    #[tokio::test]
+    async fn test_get_history_where_filter() {
+        let state = create_test_state();
+        {
+            let store = state.store.lock().await;
+            let records: Vec<HistoryRecord> = [(100, 40), (200, 41), (300, 42)]
+                .into_iter()
+                .enumerate()
+                .map(|(i, (co2, humidity))| HistoryRecord {
+                    timestamp: OffsetDateTime::UNIX_EPOCH + Duration::seconds(i as i64 + 1),
+                    co2,
+                    temperature: 20.0,
+                    pressure: 1000.0,
+                    humidity,
+                    radon: None,
+                    radiation_rate: None,
+                    radiation_total: None,
+                })
+                .collect();
+            store.insert_history("test-device", &records).unwrap();
+        }
+        let app = router().with_state(state);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/devices/test-device/history?where=co2%3E150%20AND%20humidity%3C42")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_body(response).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["pagination"]["count"], 1);
+        assert_eq!(json["data"][0]["co2"], 200);
+
+        // Anything outside the whitelist is rejected before reaching SQL
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/devices/test-device/history?where=device_id%3D1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = response_body(response).await;
+        assert!(body.contains("unknown field 'device_id'"));
+    }
+
     #[tokio::test]
     async fn test_get_history_respects_offset() {
         let state = create_test_state();
@@ -2155,6 +3323,154 @@ mod tests {
         assert!(json["devices"].is_array());
     }
 
+    #[tokio::test]
+    async fn test_get_config_redacts_secrets() {
+        let store = aranet_store::Store::open_in_memory().unwrap();
+        let mut config = Config::default();
+        config.mqtt.password = Some("hunter2".to_string());
+        config.influxdb.token = Some("influx-token".to_string());
+        let state = AppState::with_config_path(store, config, test_config_path());
+        let app = router().with_state(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/config")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_body(response).await;
+        assert!(!body.contains("hunter2"));
+        assert!(!body.contains("influx-token"));
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["mqtt"]["password"], crate::config::REDACTED);
+        assert_eq!(json["collector"]["max_concurrent_connections"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_stores_and_broadcasts_readings() {
+        let state = create_test_state();
+        let mut readings_rx = state.readings_tx.subscribe();
+        let app = router().with_state(Arc::clone(&state));
+
+        let reading = |co2: u16, captured_at: &str| {
+            serde_json::json!({
+                "id": 7,
+                "device_id": "Aranet4 17C3C",
+                "name": "Building B",
+                "captured_at": captured_at,
+                "co2": co2,
+                "temperature": 21.5,
+                "pressure": 1012.0,
+                "humidity": 40,
+                "battery": 90,
+                "status": "Green",
+                "radon": null,
+                "radiation_rate": null,
+                "radiation_total": null,
+                "radon_avg_24h": null,
+                "radon_avg_7d": null,
+                "radon_avg_30d": null,
+                "quality": "valid"
+            })
+        };
+        let request_body = serde_json::json!({
+            "readings": [
+                reading(650, "2024-03-28T08:00:00Z"),
+                reading(700, "2024-03-28T08:01:00Z"),
+            ]
+        });
+        let send = |app: Router| {
+            let body = serde_json::to_string(&request_body).unwrap();
+            async move {
+                app.oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/api/ingest")
+                        .header("content-type", "application/json")
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+            }
+        };
+
+        let response = send(app.clone()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&response_body(response).await).unwrap();
+        assert_eq!(json["accepted"], 2);
+        assert_eq!(json["duplicates"], 0);
+
+        let event = readings_rx.recv().await.unwrap();
+        assert_eq!(event.device_id, "Aranet4 17C3C");
+        assert_eq!(event.reading.co2, 650);
+        assert_ne!(event.reading.id, 7);
+
+        // A retried batch is not stored twice
+        let response = send(app).await;
+        let json: serde_json::Value = serde_json::from_str(&response_body(response).await).unwrap();
+        assert_eq!(json["accepted"], 0);
+        assert_eq!(json["duplicates"], 2);
+
+        let store = state.store.lock().await;
+        assert_eq!(store.count_readings(Some("Aranet4 17C3C")).unwrap(), 2);
+        let device = store.get_device("Aranet4 17C3C").unwrap().unwrap();
+        assert_eq!(device.name.as_deref(), Some("Building B"));
+        let readings = store
+            .query_readings(&aranet_store::ReadingQuery::new())
+            .unwrap();
+        assert!(
+            readings
+                .iter()
+                .all(|r| r.quality == aranet_store::ReadingQuality::Valid)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ingest_rejects_empty_device_id() {
+        let state = create_test_state();
+        let app = router().with_state(Arc::clone(&state));
+
+        let request_body = serde_json::json!({
+            "readings": [{
+                "id": 0,
+                "device_id": " ",
+                "captured_at": "2024-03-28T08:00:00Z",
+                "co2": 650,
+                "temperature": 21.5,
+                "pressure": 1012.0,
+                "humidity": 40,
+                "battery": 90,
+                "status": "Green",
+                "radon": null,
+                "radiation_rate": null,
+                "radiation_total": null,
+                "radon_avg_24h": null,
+                "radon_avg_7d": null,
+                "radon_avg_30d": null
+            }]
+        });
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/ingest")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_string(&request_body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
     #[tokio::test]
     async fn test_add_device_endpoint() {
         let state = create_test_state();
@@ -2199,6 +3515,8 @@ mod tests {
                 address: "AA:BB:CC:DD:EE:FF".to_string(),
                 alias: Some("First".to_string()),
                 poll_interval: 60,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
             });
         }
 
@@ -2235,6 +3553,8 @@ mod tests {
                 address: "AA:BB:CC:DD:EE:FF".to_string(),
                 alias: Some("Original".to_string()),
                 poll_interval: 60,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
             });
         }
 
@@ -2301,6 +3621,8 @@ mod tests {
                 address: "AA:BB:CC:DD:EE:FF".to_string(),
                 alias: Some("To Remove".to_string()),
                 poll_interval: 60,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
             });
         }
 
@@ -2339,6 +3661,73 @@ mod tests {
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn test_delete_device_with_purge() {
+        let state = create_test_state();
+        {
+            let mut config = state.config.write().await;
+            config.devices.push(DeviceConfig {
+                address: "AA:BB:CC:DD:EE:FF".to_string(),
+                alias: None,
+                poll_interval: 60,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
+            });
+        }
+        state
+            .with_store_write(|store| {
+                let reading = aranet_types::CurrentReading::builder().co2(800).build();
+                store.insert_reading("AA:BB:CC:DD:EE:FF", &reading)?;
+                store.insert_reading("AA:BB:CC:DD:EE:FF", &reading)
+            })
+            .await
+            .unwrap();
+
+        let delete = |uri: &str| {
+            Request::builder()
+                .method("DELETE")
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // Dry run reports counts and changes nothing
+        let response = router()
+            .with_state(Arc::clone(&state))
+            .oneshot(delete(
+                "/api/devices/aa:bb:cc:dd:ee:ff?purge=true&dry_run=true",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&response_body(response).await).unwrap();
+        assert_eq!(json["dry_run"], true);
+        assert_eq!(json["removed_from_config"], true);
+        assert_eq!(json["purged"]["readings"], 2);
+        assert_eq!(state.config.read().await.devices.len(), 1);
+
+        let response = router()
+            .with_state(Arc::clone(&state))
+            .oneshot(delete("/api/devices/AA:BB:CC:DD:EE:FF?purge=true"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.config.read().await.devices.is_empty());
+        let remaining = state
+            .with_store_read(|store| store.count_device_data("AA:BB:CC:DD:EE:FF"))
+            .await
+            .unwrap();
+        assert_eq!(remaining.total(), 0);
+
+        // Nothing left to delete
+        let response = router()
+            .with_state(state)
+            .oneshot(delete("/api/devices/AA:BB:CC:DD:EE:FF?purge=true"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
     #[tokio::test]
     async fn test_collector_start_stop() {
         let state = create_test_state();
@@ -2375,6 +3764,8 @@ mod tests {
                 address: "AA:BB:CC:DD:EE:FF".to_string(),
                 alias: Some("Test".to_string()),
                 poll_interval: 60,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
             });
         }
         let app = router().with_state(Arc::clone(&state));
@@ -2553,6 +3944,8 @@ mod tests {
                 address: "AA:BB:CC:DD:EE:FF".to_string(),
                 alias: Some("Test".to_string()),
                 poll_interval: 60,
+                privacy: DevicePrivacy::default(),
+                min_history_sync_battery: None,
             }],
         };
 
@@ -2772,6 +4165,8 @@ mod tests {
                 address: "AA:BB:CC:DD:EE:FF".to_string(),
                 alias: Some("Test".to_string()),
                 poll_interval: 60,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
             });
         }
 
@@ -2975,6 +4370,45 @@ mod tests {
         // Check platform info
         assert!(json["platform"]["os"].is_string());
         assert!(json["platform"]["arch"].is_string());
+
+        // Check WebSocket clients
+        assert_eq!(json["websocket"]["clients"], 0);
+        assert_eq!(json["websocket"]["max_connections"], 256);
+        assert!(json["websocket"]["connections"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_health_diagnostics_endpoint() {
+        let state = create_test_state();
+        let app = router().with_state(state);
+
+        global_diagnostics()
+            .record_operation(
+                aranet_core::OperationType::Scan,
+                std::time::Duration::from_millis(1200),
+                true,
+                None,
+            )
+            .await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/health/diagnostics")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_body(response).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert!(json["connection_stats"]["total_attempts"].is_number());
+        assert!(json["recent_errors"].is_array());
+        let operations = json["recent_operations"].as_array().unwrap();
+        assert!(operations.iter().any(|op| op["operation"] == "scan"));
     }
 
     #[tokio::test]
@@ -3097,4 +4531,194 @@ mod tests {
         assert_eq!(json["status"], "degraded");
         assert!(!json["collector"]["running"].as_bool().unwrap());
     }
+
+    async fn patch_settings(
+        app: axum::Router,
+        uri: &str,
+        body: serde_json::Value,
+    ) -> axum::response::Response {
+        app.oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_patch_device_settings_queues_job() {
+        let state = create_test_state();
+        {
+            let mut config = state.config.write().await;
+            config.devices.push(DeviceConfig {
+                address: "AA:BB:CC:DD:EE:FF".to_string(),
+                alias: None,
+                poll_interval: 60,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
+            });
+        }
+        let app = router().with_state(Arc::clone(&state));
+
+        let response = patch_settings(
+            app.clone(),
+            "/api/devices/aa:bb:cc:dd:ee:ff/settings",
+            serde_json::json!({ "interval": 300, "bluetooth_range": "extended" }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let json: serde_json::Value = serde_json::from_str(&response_body(response).await).unwrap();
+        assert_eq!(json["status"], "pending");
+        assert_eq!(json["device_id"], "AA:BB:CC:DD:EE:FF");
+        assert_eq!(json["interval"], 300);
+        assert_eq!(json["bluetooth_range"], "extended");
+        assert!(json["smart_home"].is_null());
+
+        // Simulate the collector applying it, then poll the job
+        let job_id = json["id"].as_u64().unwrap();
+        state.settings_jobs.lock().await.complete(
+            job_id,
+            vec!["interval=300s".to_string()],
+            Vec::new(),
+        );
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/settings/jobs/{job_id}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&response_body(response).await).unwrap();
+        assert_eq!(json["status"], "applied");
+        assert_eq!(json["applied"][0], "interval=300s");
+        assert!(json["completed_at"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_sync_device_history_creates_one_job_per_device() {
+        let state = create_test_state();
+        {
+            let mut config = state.config.write().await;
+            config.devices.push(DeviceConfig {
+                address: "AA:BB:CC:DD:EE:FF".to_string(),
+                alias: None,
+                poll_interval: 60,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
+            });
+        }
+        // Hold the adapter so the job stays pending instead of connecting
+        let permits = state.ble_semaphore.available_permits() as u32;
+        let _permits = state.ble_semaphore.acquire_many(permits).await.unwrap();
+        let app = router().with_state(Arc::clone(&state));
+
+        let post = |uri: &'static str| {
+            app.clone().oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        let response = post("/api/devices/aa:bb:cc:dd:ee:ff/sync").await.unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let json: serde_json::Value = serde_json::from_str(&response_body(response).await).unwrap();
+        assert_eq!(json["status"], "pending");
+        assert_eq!(json["device_id"], "AA:BB:CC:DD:EE:FF");
+        let job_id = json["id"].as_u64().unwrap();
+
+        // A second request joins the unfinished job
+        let response = post("/api/devices/AA:BB:CC:DD:EE:FF/sync").await.unwrap();
+        let json: serde_json::Value = serde_json::from_str(&response_body(response).await).unwrap();
+        assert_eq!(json["id"].as_u64(), Some(job_id));
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/sync/jobs/{job_id}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&response_body(response).await).unwrap();
+        assert_eq!(json["progress"], 0.0);
+        assert!(json["completed_at"].is_null());
+
+        let response = post("/api/devices/11:22:33:44:55:66/sync").await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/sync/jobs/999")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_patch_device_settings_validation() {
+        let state = create_test_state();
+        {
+            let mut config = state.config.write().await;
+            config.devices.push(DeviceConfig {
+                address: "AA:BB:CC:DD:EE:FF".to_string(),
+                alias: None,
+                poll_interval: 60,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
+            });
+        }
+        let app = router().with_state(state);
+        let uri = "/api/devices/AA:BB:CC:DD:EE:FF/settings";
+
+        let response = patch_settings(app.clone(), uri, serde_json::json!({})).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response =
+            patch_settings(app.clone(), uri, serde_json::json!({ "interval": 45 })).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = patch_settings(
+            app.clone(),
+            uri,
+            serde_json::json!({ "bluetooth_range": "far" }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = patch_settings(
+            app.clone(),
+            "/api/devices/11:22:33:44:55:66/settings",
+            serde_json::json!({ "smart_home": true }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/settings/jobs/999")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
 }
@@ -0,0 +1,293 @@
+//! Computed channels.
+//!
+//! A channel is a value derived from a device's readings, such as dew point
+//! or the CO2 difference to another room, configured under `[[channels]]`
+//! (see [`ChannelConfig`]). The collector evaluates a device's channels each
+//! time it stores a reading, stores the values alongside it and sends them
+//! with the [`ReadingEvent`](crate::state::ReadingEvent), so WebSocket
+//! clients, MQTT, InfluxDB and Prometheus all see the same numbers.
+
+use aranet_store::{ReadingQuery, StoredReading};
+use serde::Serialize;
+use time::{Duration, OffsetDateTime};
+use tracing::warn;
+
+use crate::config::{ChannelConfig, ChannelFormula, ChannelMetric};
+use crate::state::AppState;
+
+/// How far apart in time the two readings of a delta may be.
+pub const DELTA_MAX_AGE: Duration = Duration::minutes(15);
+
+/// One computed channel value for a reading.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChannelValue {
+    /// Channel name.
+    pub channel: String,
+    /// The computed value.
+    pub value: f64,
+}
+
+/// Dew point in °C (Magnus formula), or `None` without a humidity reading.
+pub fn dew_point(temperature: f64, humidity: f64) -> Option<f64> {
+    const A: f64 = 17.62;
+    const B: f64 = 243.12;
+    if humidity <= 0.0 {
+        return None;
+    }
+    let gamma = (humidity / 100.0).ln() + A * temperature / (B + temperature);
+    Some(B * gamma / (A - gamma))
+}
+
+/// Absolute humidity in g/m³, or `None` without a humidity reading.
+pub fn absolute_humidity(temperature: f64, humidity: f64) -> Option<f64> {
+    if humidity <= 0.0 {
+        return None;
+    }
+    let saturation_hpa = 6.112 * (17.67 * temperature / (temperature + 243.5)).exp();
+    Some(saturation_hpa * humidity * 2.1674 / (273.15 + temperature))
+}
+
+impl ChannelMetric {
+    /// This metric's value in `reading`, or `None` if the device does not
+    /// measure it.
+    pub fn value(self, reading: &StoredReading) -> Option<f64> {
+        match self {
+            ChannelMetric::Co2 => (reading.co2 > 0).then_some(f64::from(reading.co2)),
+            ChannelMetric::Temperature => Some(f64::from(reading.temperature)),
+            ChannelMetric::Humidity => {
+                (reading.humidity > 0).then_some(f64::from(reading.humidity))
+            }
+            ChannelMetric::Pressure => {
+                (reading.pressure > 0.0).then_some(f64::from(reading.pressure))
+            }
+            ChannelMetric::Radon => reading.radon.map(f64::from),
+        }
+    }
+}
+
+impl ChannelFormula {
+    /// Evaluate the formula for `reading`.
+    ///
+    /// `other` is the other device's reading nearest to `reading` for a
+    /// delta, and is ignored by the other formulas. Returns `None` when an input is
+    /// missing.
+    pub fn evaluate(&self, reading: &StoredReading, other: Option<&StoredReading>) -> Option<f64> {
+        let temperature = f64::from(reading.temperature);
+        let humidity = f64::from(reading.humidity);
+        match self {
+            ChannelFormula::DewPoint => dew_point(temperature, humidity),
+            ChannelFormula::AbsoluteHumidity => absolute_humidity(temperature, humidity),
+            ChannelFormula::Delta { metric, .. } => {
+                let other = other?;
+                if (reading.captured_at - other.captured_at).abs() > DELTA_MAX_AGE {
+                    return None;
+                }
+                Some(metric.value(reading)? - metric.value(other)?)
+            }
+        }
+    }
+}
+
+/// The reading of `device_id` captured nearest to `at`, within
+/// [`DELTA_MAX_AGE`] either side.
+///
+/// Readings can arrive late (ingest from another instance, history
+/// backfill), so the other device's latest reading is not necessarily the
+/// one to compare against.
+fn nearest_reading(
+    store: &aranet_store::Store,
+    device_id: &str,
+    at: OffsetDateTime,
+) -> aranet_store::Result<Option<StoredReading>> {
+    let query = ReadingQuery::new()
+        .device(device_id)
+        .since(at - DELTA_MAX_AGE)
+        .until(at + DELTA_MAX_AGE);
+    Ok(store
+        .query_readings(&query)?
+        .into_iter()
+        .min_by_key(|r| (r.captured_at - at).abs()))
+}
+
+/// Evaluate and store the channels configured for `device_id`.
+///
+/// Values that cannot be computed are skipped; a failure to store one is
+/// logged and the value is still returned for streaming.
+pub async fn evaluate(
+    state: &AppState,
+    device_id: &str,
+    reading: &StoredReading,
+) -> Vec<ChannelValue> {
+    let channels: Vec<(ChannelConfig, Option<String>)> = {
+        let config = state.config.read().await;
+        config
+            .channels
+            .iter()
+            .filter(|c| {
+                config
+                    .find_device(&c.device)
+                    .is_some_and(|d| d.address.eq_ignore_ascii_case(device_id))
+            })
+            .map(|c| {
+                let other = match &c.formula {
+                    ChannelFormula::Delta { other, .. } => {
+                        config.find_device(other).map(|d| d.address.clone())
+                    }
+                    _ => None,
+                };
+                (c.clone(), other)
+            })
+            .collect()
+    };
+
+    let mut values = Vec::new();
+    for (channel, other) in channels {
+        let other = match other {
+            Some(other) => state
+                .with_store_read(|store| nearest_reading(store, &other, reading.captured_at))
+                .await
+                .ok()
+                .flatten(),
+            None => None,
+        };
+        let Some(value) = channel.formula.evaluate(reading, other.as_ref()) else {
+            continue;
+        };
+
+        if let Err(e) = state
+            .with_store_write(|store| {
+                store.insert_channel_value(device_id, &channel.name, reading.captured_at, value)
+            })
+            .await
+        {
+            warn!(
+                "Failed to store channel {} for {}: {}",
+                channel.name, device_id, e
+            );
+        }
+        values.push(ChannelValue {
+            channel: channel.name,
+            value,
+        });
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use aranet_types::Status;
+
+    fn reading(
+        co2: u16,
+        temperature: f32,
+        humidity: u8,
+        captured_at: OffsetDateTime,
+    ) -> StoredReading {
+        StoredReading {
+            id: 1,
+            device_id: "test".to_string(),
+            captured_at,
+            co2,
+            temperature,
+            pressure: 1013.0,
+            humidity,
+            battery: 80,
+            status: Status::Green,
+            radon: None,
+            radiation_rate: None,
+            radiation_total: None,
+            radon_avg_24h: None,
+            radon_avg_7d: None,
+            radon_avg_30d: None,
+            quality: aranet_store::ReadingQuality::Unchecked,
+            counter: None,
+        }
+    }
+
+    #[test]
+    fn test_humidity_formulas() {
+        // 20 °C at 50% condenses at about 9.3 °C and holds about 8.6 g/m³
+        assert!((dew_point(20.0, 50.0).unwrap() - 9.26).abs() < 0.05);
+        assert!((absolute_humidity(20.0, 50.0).unwrap() - 8.64).abs() < 0.05);
+        assert!((dew_point(25.0, 100.0).unwrap() - 25.0).abs() < 1e-6);
+        assert_eq!(dew_point(20.0, 0.0), None);
+        assert_eq!(absolute_humidity(20.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_delta_formula() {
+        let now = OffsetDateTime::now_utc();
+        let indoor = reading(1200, 22.0, 45, now);
+        let outdoor = reading(420, 12.0, 70, now - Duration::minutes(5));
+        let delta = ChannelFormula::Delta {
+            metric: ChannelMetric::Co2,
+            other: "Balcony".to_string(),
+        };
+
+        assert_eq!(delta.evaluate(&indoor, Some(&outdoor)), Some(780.0));
+        assert_eq!(delta.evaluate(&indoor, None), None);
+
+        let stale = reading(420, 12.0, 70, now - Duration::hours(1));
+        assert_eq!(delta.evaluate(&indoor, Some(&stale)), None);
+
+        // An old reading is not compared against a much newer one either
+        let old = reading(1200, 22.0, 45, now - Duration::hours(1));
+        assert_eq!(delta.evaluate(&old, Some(&outdoor)), None);
+
+        // Aranet2 has no CO2 sensor
+        let aranet2 = reading(0, 12.0, 70, now);
+        assert_eq!(delta.evaluate(&indoor, Some(&aranet2)), None);
+    }
+
+    #[tokio::test]
+    async fn test_delta_uses_other_reading_nearest_in_time() {
+        let toml = r#"
+            [[devices]]
+            address = "Aranet4 17C3C"
+            alias = "Living Room"
+
+            [[devices]]
+            address = "Aranet4 0A1B2"
+            alias = "Balcony"
+
+            [[channels]]
+            name = "co2_above_outdoor"
+            device = "Living Room"
+            formula = "delta"
+            metric = "co2"
+            other = "Balcony"
+        "#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let store = aranet_store::Store::open_in_memory().unwrap();
+        let now = OffsetDateTime::now_utc().replace_nanosecond(0).unwrap();
+        let earlier = now - Duration::hours(2);
+        for outdoor in [
+            reading(400, 12.0, 70, earlier + Duration::minutes(10)),
+            reading(500, 12.0, 70, earlier - Duration::minutes(3)),
+            reading(450, 12.0, 70, now),
+        ] {
+            store
+                .insert_reading("Aranet4 0A1B2", &outdoor.to_reading())
+                .unwrap();
+        }
+        let state = AppState::new(store, config);
+
+        // A late reading from two hours ago is compared with the balcony
+        // reading from then, not the latest one
+        let late = reading(1200, 22.0, 45, earlier);
+        let values = evaluate(&state, "Aranet4 17C3C", &late).await;
+        assert_eq!(
+            values,
+            vec![ChannelValue {
+                channel: "co2_above_outdoor".to_string(),
+                value: 700.0,
+            }]
+        );
+
+        // Nothing near enough in time, nothing computed
+        let orphan = reading(1200, 22.0, 45, now - Duration::hours(1));
+        assert!(evaluate(&state, "Aranet4 17C3C", &orphan).await.is_empty());
+    }
+}
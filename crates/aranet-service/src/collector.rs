@@ -17,6 +17,8 @@
 //!
 //! 1. **`device_stats` write lock** - Brief lock to update polling status
 //! 2. **BLE device communication** - No Rust locks, but exclusive Bluetooth access
+//!    (the `settings_jobs` mutex is taken briefly to apply queued settings changes;
+//!    on-demand history syncs from the API wait for the same BLE semaphore)
 //! 3. **`store` mutex** - Brief lock to insert the reading
 //! 4. **`device_stats` write lock** - Brief lock to update success/failure counts
 //!
@@ -35,6 +37,12 @@
 //! ERROR level once, then silently retried. This prevents log spam for devices
 //! that are temporarily unavailable.
 //!
+//! Repeated failures also back the device off exponentially: after the second
+//! consecutive failure the next attempt waits twice the poll interval, then four
+//! times, and so on up to [`MAX_BACKOFF_SECS`], with up to 25% jitter so devices
+//! that failed together do not retry together. The backoff state is exposed in
+//! the device stats and cleared on the next successful poll.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -48,20 +56,45 @@
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use rand::Rng;
 use time::OffsetDateTime;
 use tokio::sync::watch;
 use tokio::time::interval;
 use tracing::{debug, error, info, warn};
 
-use aranet_core::Device;
-use aranet_store::StoredReading;
+use aranet_core::clock::ClockDriftTracker;
+use aranet_core::settings::{BluetoothRange, DeviceSettings, SettingChange};
+use aranet_core::validation::ValidatorConfig;
+use aranet_core::{Device, ErrorCategory, ReadingValidator};
+use aranet_store::{ReadingQuality, Store, StoredReading};
+use aranet_types::{CurrentReading, DeviceType};
 
+use crate::channels;
 use crate::config::DeviceConfig;
-use crate::state::{AppState, CollectorState, DeviceCollectionStats, ReadingEvent};
+use crate::state::{AppState, CollectorState, DeviceBackoff, DeviceCollectionStats, ReadingEvent};
 
 /// Per-device stagger interval to avoid BLE adapter contention on startup.
 const DEVICE_STAGGER_SECS: u64 = 5;
 
+/// Upper bound for the backoff delay of a failing device (30 minutes).
+///
+/// Devices with a longer poll interval are never polled more often than their interval.
+pub const MAX_BACKOFF_SECS: u64 = 30 * 60;
+
+/// Compute the delay before the next poll after `consecutive_failures` failed polls.
+///
+/// The first failure keeps the normal poll interval; each further failure doubles
+/// it, capped at [`MAX_BACKOFF_SECS`]. `jitter` (clamped to `0.0..=0.25`) adds a
+/// fraction of the capped delay on top.
+pub fn backoff_delay(poll_interval: Duration, consecutive_failures: u32, jitter: f64) -> Duration {
+    let exponent = consecutive_failures.saturating_sub(1).min(16);
+    let delay = poll_interval.saturating_mul(1 << exponent);
+    let capped = delay
+        .min(Duration::from_secs(MAX_BACKOFF_SECS))
+        .max(poll_interval);
+    capped.mul_f64(1.0 + jitter.clamp(0.0, 0.25))
+}
+
 /// Spawn staggered device-polling tasks into the collector's shared `JoinSet`.
 async fn spawn_staggered_device_tasks(
     collector: &CollectorState,
@@ -104,6 +137,10 @@ async fn initialize_device_stats(state: &AppState, devices: &[DeviceConfig]) {
             success_count: 0,
             failure_count: 0,
             polling: false,
+            clock_drift_ppm: None,
+            history_overwrite_at: None,
+            history_sync_deferred_below: None,
+            backoff: None,
         });
     }
 }
@@ -297,6 +334,7 @@ async fn collect_device(
 
     let mut interval_timer = interval(poll_interval);
     let mut consecutive_failures = 0u32;
+    let mut drift_tracker = ClockDriftTracker::new();
 
     loop {
         tokio::select! {
@@ -307,15 +345,25 @@ async fn collect_device(
                 }).await;
 
                 let poll_start = Instant::now();
-                match poll_device(&state, &device_id).await {
+                let result = poll_device(&state, &device_id, &mut drift_tracker).await;
+                state.collector.metrics.lock().await.record_poll(
+                    &device_id,
+                    poll_start.elapsed(),
+                    result.as_ref().err().map(CollectorError::category),
+                );
+                match result {
                     Ok(reading) => {
                         let poll_duration = poll_start.elapsed();
-                        consecutive_failures = 0;
                         debug!(
                             "Collected reading from {}: CO2={} (took {:.1}s)",
                             device_id, reading.co2, poll_duration.as_secs_f64()
                         );
 
+                        if consecutive_failures > 1 {
+                            info!("Device {} recovered, resuming normal polling", device_id);
+                        }
+                        consecutive_failures = 0;
+
                         // Update stats
                         update_device_stat(&state, &device_id, |stat| {
                             stat.last_poll_at = Some(OffsetDateTime::now_utc());
@@ -324,12 +372,15 @@ async fn collect_device(
                             stat.last_poll_duration_ms = Some(poll_duration.as_millis() as u64);
                             stat.success_count += 1;
                             stat.polling = false;
+                            stat.backoff = None;
                         }).await;
 
                         // Broadcast the reading to WebSocket clients
+                        let channels = channels::evaluate(&state, &device_id, &reading).await;
                         let event = ReadingEvent {
                             device_id: device_id.clone(),
                             reading,
+                            channels,
                         };
                         // Check thresholds and send desktop notification
                         #[cfg(feature = "notifications")]
@@ -348,29 +399,45 @@ async fn collect_device(
                         let poll_duration = poll_start.elapsed();
                         consecutive_failures += 1;
 
+                        let jitter = rand::rng().random::<f64>() * 0.25;
+                        let delay = backoff_delay(poll_interval, consecutive_failures, jitter);
+                        interval_timer.reset_after(delay);
+                        let now = OffsetDateTime::now_utc();
+                        let backoff = DeviceBackoff {
+                            consecutive_failures,
+                            delay_secs: delay.as_secs(),
+                            next_attempt_at: now + delay,
+                        };
+
                         // Update stats
                         update_device_stat(&state, &device_id, |stat| {
-                            stat.last_error_at = Some(OffsetDateTime::now_utc());
+                            stat.last_error_at = Some(now);
                             stat.last_error = Some(e.to_string());
                             stat.last_poll_duration_ms = Some(poll_duration.as_millis() as u64);
                             stat.failure_count += 1;
                             stat.polling = false;
+                            stat.backoff = Some(backoff);
                         }).await;
 
                         if consecutive_failures <= 3 {
                             warn!(
-                                "Failed to poll {}: {} (attempt {})",
-                                device_id, e, consecutive_failures
+                                "Failed to poll {}: {} (attempt {}, next attempt in {}s)",
+                                device_id, e, consecutive_failures, delay.as_secs()
                             );
                         } else if consecutive_failures == 4 {
                             error!(
-                                "Failed to poll {} after {} attempts, reducing log frequency",
-                                device_id, consecutive_failures
+                                "Failed to poll {} after {} attempts, backing off {}s and reducing log frequency",
+                                device_id, consecutive_failures, delay.as_secs()
                             );
                         } else if consecutive_failures.is_multiple_of(100) {
                             error!(
-                                "Failed to poll {} ({} consecutive failures): {}",
-                                device_id, consecutive_failures, e
+                                "Failed to poll {} ({} consecutive failures, backing off {}s): {}",
+                                device_id, consecutive_failures, delay.as_secs(), e
+                            );
+                        } else {
+                            debug!(
+                                "Backing off {} for {}s after {} consecutive failures",
+                                device_id, delay.as_secs(), consecutive_failures
                             );
                         }
                         // Continue trying - the device may come back online
@@ -402,11 +469,19 @@ where
 
 /// Poll a single device and store the reading.
 ///
-/// Acquires the BLE semaphore to ensure only one device uses the Bluetooth
-/// adapter at a time. This prevents BLE contention that causes connection
-/// failures and stale data when multiple devices are configured.
-async fn poll_device(state: &AppState, device_id: &str) -> Result<StoredReading, CollectorError> {
-    // Serialize BLE adapter access — only one device at a time
+/// Acquires a BLE semaphore permit so at most
+/// `collector.max_concurrent_connections` devices are connected at once.
+/// Polling many devices in parallel overwhelms common adapters and causes
+/// cascading timeouts.
+///
+/// Each reading also feeds the device's clock drift tracker; new estimates
+/// are saved to the store and device stats.
+async fn poll_device(
+    state: &Arc<AppState>,
+    device_id: &str,
+    drift_tracker: &mut ClockDriftTracker,
+) -> Result<StoredReading, CollectorError> {
+    // Limit simultaneous BLE connections on the adapter
     let permit = state
         .ble_semaphore
         .acquire()
@@ -415,12 +490,22 @@ async fn poll_device(state: &AppState, device_id: &str) -> Result<StoredReading,
 
     // Connect with moderate timeouts — fail fast and retry rather than blocking
     let config = aranet_core::device::ConnectionConfig::default();
+    state
+        .collector
+        .metrics
+        .lock()
+        .await
+        .record_connect_attempt(device_id);
     let device = Device::connect_with_config(device_id, config)
         .await
         .map_err(CollectorError::Connect)?;
 
+    // Apply any settings changes queued through the API while we hold the connection
+    apply_pending_settings(state, device_id, &device).await;
+
     // Read current values
     let reading_result = device.read_current().await;
+    let read_at = OffsetDateTime::now_utc();
 
     // Always disconnect after the read attempt to avoid relying on best-effort Drop cleanup.
     if let Err(e) = device.disconnect().await {
@@ -431,16 +516,419 @@ async fn poll_device(state: &AppState, device_id: &str) -> Result<StoredReading,
     drop(permit);
     let reading = reading_result.map_err(CollectorError::Read)?;
 
-    // Store the reading
+    // Store the reading, flagged so charts and exports can skip bad values
+    let quality = reading_quality(device.device_type(), &reading);
+    if quality == ReadingQuality::Invalid {
+        debug!("Reading from {} failed validation", device_id);
+    }
     let row_id = state
-        .with_store_write(|store| store.insert_reading(device_id, &reading))
+        .with_store_write(|store| store.insert_reading_with_quality(device_id, &reading, quality))
         .await
         .map_err(CollectorError::Store)?;
 
+    if let Some(drift) = drift_tracker.observe(read_at, reading.age, reading.interval) {
+        debug!(
+            "Clock drift for {}: {:.1} ppm ({:.1} s/day) over {}s",
+            device_id,
+            drift.ppm,
+            drift.seconds_per_day(),
+            drift.span.whole_seconds()
+        );
+        if let Err(e) = state
+            .with_store_write(|store| {
+                store.update_clock_drift(device_id, drift.ppm, drift.span.whole_seconds())
+            })
+            .await
+        {
+            warn!("Failed to store clock drift for {}: {}", device_id, e);
+        }
+        update_device_stat(state, device_id, |stat| {
+            stat.clock_drift_ppm = Some(drift.ppm);
+        })
+        .await;
+    }
+
+    check_history_overwrite(state, device_id, device.device_type(), reading.interval).await;
+
     // Return the stored reading
-    Ok(StoredReading::from_reading_with_id(
-        device_id, &reading, row_id,
-    ))
+    Ok(StoredReading {
+        quality,
+        ..StoredReading::from_reading_with_id(device_id, &reading, row_id)
+    })
+}
+
+/// Start a history sync if un-synced records are about to be overwritten.
+///
+/// The device's ring buffer holds a fixed number of records, so history
+/// taken after the last sync is lost once that many intervals have passed
+/// (see [`aranet_core::history_overwrite_risk`]). While a sync is due, the
+/// deadline is kept in the device stats, which raises the
+/// "history_overwrite" webhook, and a sync job is queued as if requested
+/// through the API so it runs as soon as the adapter is free. Devices that
+/// have never been synced have nothing to lose and are skipped.
+async fn check_history_overwrite(
+    state: &Arc<AppState>,
+    device_id: &str,
+    device_type: Option<DeviceType>,
+    interval_seconds: u16,
+) {
+    let last_sync = match state
+        .with_store_read(|store| store.get_sync_state(device_id))
+        .await
+    {
+        Ok(sync_state) => sync_state.and_then(|s| s.last_sync_at),
+        Err(e) => {
+            debug!("Failed to read sync state for {}: {}", device_id, e);
+            return;
+        }
+    };
+    let overwrite_at = device_type
+        .zip(last_sync)
+        .and_then(|(device_type, last_sync)| {
+            aranet_core::history_overwrite_risk(
+                device_type,
+                interval_seconds,
+                last_sync,
+                OffsetDateTime::now_utc(),
+            )
+        });
+    update_device_stat(state, device_id, |stat| {
+        stat.history_overwrite_at = overwrite_at;
+    })
+    .await;
+
+    let Some(overwrite_at) = overwrite_at else {
+        return;
+    };
+    let (job, created) = state.sync_jobs.lock().await.enqueue(device_id);
+    if created {
+        warn!(
+            "Un-synced history on {} will be overwritten from {}, starting sync job {}",
+            device_id, overwrite_at, job.id
+        );
+        tokio::spawn(run_sync_job(
+            Arc::clone(state),
+            job.id,
+            device_id.to_string(),
+        ));
+    }
+}
+
+/// Check a reading against the expected ranges for its device type.
+///
+/// Readings from devices of unknown type are left unchecked, since the
+/// default limits would flag sensors that legitimately report zeros.
+fn reading_quality(device_type: Option<DeviceType>, reading: &CurrentReading) -> ReadingQuality {
+    let Some(device_type) = device_type else {
+        return ReadingQuality::Unchecked;
+    };
+    let result = ReadingValidator::new(ValidatorConfig::for_device(device_type)).validate(reading);
+    if !result.is_valid {
+        ReadingQuality::Invalid
+    } else if result.has_warnings() {
+        ReadingQuality::Suspect
+    } else {
+        ReadingQuality::Valid
+    }
+}
+
+/// Apply settings changes queued for a device over an open connection.
+///
+/// Each job is marked applied or failed; a failure does not affect the poll.
+async fn apply_pending_settings(state: &AppState, device_id: &str, device: &Device) {
+    let jobs = state.settings_jobs.lock().await.pending_for(device_id);
+
+    for job in jobs {
+        info!("Applying settings job {} to {}", job.id, device_id);
+        let result = async {
+            let current = device.get_settings_with_interval().await?;
+            let mut target = current.clone();
+            job.patch.apply_to(&mut target);
+            let report = device.apply_settings(&target).await?;
+            Ok::<_, aranet_core::Error>((current, report))
+        }
+        .await;
+
+        if let Ok((current, report)) = &result {
+            record_settings_history(state, device_id, current, &report.applied).await;
+        }
+
+        let mut queue = state.settings_jobs.lock().await;
+        match result {
+            Ok((_, report)) => {
+                let applied = report.applied.iter().map(ToString::to_string).collect();
+                let skipped = report
+                    .skipped
+                    .iter()
+                    .map(|(change, reason)| format!("{change}: {reason}"))
+                    .collect();
+                queue.complete(job.id, applied, skipped);
+            }
+            Err(e) => {
+                warn!("Settings job {} for {} failed: {}", job.id, device_id, e);
+                queue.fail(job.id, e.to_string());
+            }
+        }
+    }
+}
+
+/// Run an on-demand history sync requested through the API.
+///
+/// Waits for the BLE semaphore like a regular poll, downloads the records
+/// added since the last sync, stores them and marks the job completed or
+/// failed. Progress is recorded on the job while the download runs.
+pub(crate) async fn run_sync_job(state: Arc<AppState>, job_id: u64, device_id: String) {
+    let result = sync_device_history(&state, job_id, &device_id).await;
+
+    let mut jobs = state.sync_jobs.lock().await;
+    match result {
+        Ok((downloaded, inserted)) => {
+            info!(
+                "History sync job {} for {}: {} records downloaded, {} new",
+                job_id, device_id, downloaded, inserted
+            );
+            jobs.complete(job_id, downloaded, inserted);
+        }
+        Err(e) => {
+            warn!(
+                "History sync job {} for {} failed: {}",
+                job_id, device_id, e
+            );
+            jobs.fail(job_id, e.to_string());
+        }
+    }
+}
+
+/// Download and store a device's new history records.
+///
+/// Returns the number of records downloaded and the number that were new.
+async fn sync_device_history(
+    state: &Arc<AppState>,
+    job_id: u64,
+    device_id: &str,
+) -> Result<(usize, usize), CollectorError> {
+    check_history_sync_battery(state, device_id).await?;
+
+    // Share the BLE connection limit with the polling tasks
+    let _permit = state
+        .ble_semaphore
+        .acquire()
+        .await
+        .map_err(|_| CollectorError::BleBusy)?;
+    state.sync_jobs.lock().await.start(job_id);
+
+    let config = aranet_core::device::ConnectionConfig::default();
+    state
+        .collector
+        .metrics
+        .lock()
+        .await
+        .record_connect_attempt(device_id);
+    let device = Device::connect_with_config(device_id, config)
+        .await
+        .map_err(CollectorError::Connect)?;
+
+    let download = download_new_history(state, job_id, device_id, &device).await;
+
+    if let Err(e) = device.disconnect().await {
+        debug!(
+            "Failed to disconnect {} after history sync: {}",
+            device_id, e
+        );
+    }
+
+    let Some((mut records, total_on_device)) = download? else {
+        return Ok((0, 0));
+    };
+    let inserted = state
+        .with_store_write(|store| {
+            correct_clock_drift(store, device_id, &mut records)?;
+            let inserted = store.insert_history(device_id, &records)?;
+            store.update_sync_state(device_id, total_on_device, total_on_device)?;
+            Ok(inserted)
+        })
+        .await
+        .map_err(CollectorError::Store)?;
+
+    Ok((records.len(), inserted))
+}
+
+/// Refuse to sync a device whose last stored battery level is low.
+///
+/// The threshold is the device's `min_history_sync_battery`, falling back to
+/// the collector's. Syncs queued because un-synced records are about to be
+/// overwritten always run. A deferral is recorded in the device's stats,
+/// which raises the "history_sync_deferred" webhook.
+async fn check_history_sync_battery(
+    state: &AppState,
+    device_id: &str,
+) -> Result<(), CollectorError> {
+    let battery = state
+        .with_store_read(|store| store.get_latest_reading(device_id))
+        .await
+        .map_err(CollectorError::Store)?
+        .map(|reading| reading.battery);
+    let Some(battery) = battery else {
+        return Ok(());
+    };
+    let overwrite_at = state
+        .collector
+        .device_stats
+        .read()
+        .await
+        .iter()
+        .find(|s| s.device_id == device_id)
+        .and_then(|s| s.history_overwrite_at);
+
+    let threshold = state
+        .config
+        .read()
+        .await
+        .min_history_sync_battery(device_id);
+    let deferred_by =
+        aranet_core::history_sync_deferred_by(device_id, battery, threshold, overwrite_at);
+    update_device_stat(state, device_id, |stat| {
+        stat.history_sync_deferred_below = deferred_by;
+    })
+    .await;
+    match deferred_by {
+        Some(threshold) => Err(CollectorError::HistorySyncDeferred { battery, threshold }),
+        None => Ok(()),
+    }
+}
+
+/// Rescale downloaded history for the device's measured clock drift.
+///
+/// Drift is measured by the collector while polling; devices it has never
+/// polled are stored as downloaded. The CLI's `sync` applies the same
+/// correction.
+pub fn correct_clock_drift(
+    store: &Store,
+    device_id: &str,
+    history: &mut [aranet_types::HistoryRecord],
+) -> aranet_store::Result<()> {
+    if let Some(drift) = store.get_clock_drift(device_id)? {
+        info!(
+            "Correcting {} history timestamps for {:.1} ppm clock drift",
+            device_id, drift.drift_ppm
+        );
+        aranet_core::clock::correct_history_timestamps(history, drift.drift_ppm);
+    }
+    Ok(())
+}
+
+/// Download the records the store does not have yet, with the device's
+/// total record count, or `None` if the store is already up to date.
+async fn download_new_history(
+    state: &Arc<AppState>,
+    job_id: u64,
+    device_id: &str,
+    device: &Device,
+) -> Result<Option<(Vec<aranet_types::HistoryRecord>, u16)>, CollectorError> {
+    let info = device
+        .get_history_info()
+        .await
+        .map_err(CollectorError::Read)?;
+    let total_on_device = info.total_readings;
+    let start_index = state
+        .with_store_read(|store| store.calculate_sync_start(device_id, total_on_device))
+        .await
+        .map_err(CollectorError::Store)?;
+    if start_index > total_on_device {
+        return Ok(None);
+    }
+
+    let to_download = total_on_device - start_index + 1;
+    state.sync_jobs.lock().await.update(job_id, |job| {
+        job.total_records = Some(to_download);
+    });
+
+    // Progress is best-effort: skip an update rather than block the download
+    let progress_state = Arc::clone(state);
+    let progress_callback = Arc::new(move |progress: aranet_core::history::HistoryProgress| {
+        if let Ok(mut jobs) = progress_state.sync_jobs.try_lock() {
+            jobs.update(job_id, |job| job.progress = progress.overall_progress);
+        }
+    });
+    let options = aranet_core::HistoryOptions {
+        start_index: Some(start_index),
+        progress_callback: Some(progress_callback),
+        ..Default::default()
+    };
+
+    let records = device
+        .download_history_with_options(options)
+        .await
+        .map_err(CollectorError::Read)?;
+    Ok(Some((records, total_on_device)))
+}
+
+/// Record settings in the store's audit log.
+///
+/// `current` is what the device reported before any writes, so the first
+/// job for a device also records its baseline; `applied` are the changes
+/// that were then written and verified.
+async fn record_settings_history(
+    state: &AppState,
+    device_id: &str,
+    current: &DeviceSettings,
+    applied: &[SettingChange],
+) {
+    let baseline = [
+        current.interval.map(SettingChange::Interval),
+        Some(SettingChange::BluetoothRange(current.bluetooth_range)),
+        Some(SettingChange::SmartHome(current.smart_home_enabled)),
+    ];
+    let changes: Vec<_> = baseline
+        .into_iter()
+        .flatten()
+        .chain(applied.iter().copied())
+        .collect();
+
+    let now = OffsetDateTime::now_utc();
+    let result = state
+        .with_store_write(|store| record_setting_changes(store, device_id, &changes, now))
+        .await;
+    if let Err(e) = result {
+        warn!("Failed to record settings history for {}: {}", device_id, e);
+    }
+}
+
+/// Record setting changes written to a device in the store's audit log.
+///
+/// Values that match the last recorded one are skipped. The CLI, TUI and GUI
+/// call this after their own settings writes so the log covers every writer.
+pub fn record_setting_changes(
+    store: &Store,
+    device_id: &str,
+    changes: &[SettingChange],
+    changed_at: OffsetDateTime,
+) -> aranet_store::Result<()> {
+    for change in changes {
+        let (setting, value) = setting_history_value(change);
+        store.record_setting(device_id, setting, &value, changed_at)?;
+    }
+    Ok(())
+}
+
+/// Store setting name and value for the changes tracked in settings history.
+fn setting_history_value(change: &SettingChange) -> (&'static str, String) {
+    match change {
+        SettingChange::Interval(interval) => (
+            aranet_store::SETTING_INTERVAL,
+            interval.as_seconds().to_string(),
+        ),
+        SettingChange::BluetoothRange(range) => {
+            let value = match range {
+                BluetoothRange::Standard => "standard",
+                BluetoothRange::Extended => "extended",
+            };
+            (aranet_store::SETTING_BLUETOOTH_RANGE, value.to_string())
+        }
+        SettingChange::SmartHome(enabled) => {
+            (aranet_store::SETTING_SMART_HOME, enabled.to_string())
+        }
+    }
 }
 
 /// Collector errors.
@@ -448,6 +936,8 @@ async fn poll_device(state: &AppState, device_id: &str) -> Result<StoredReading,
 pub enum CollectorError {
     #[error("BLE adapter busy (semaphore closed)")]
     BleBusy,
+    #[error("History sync deferred: battery {battery}% below {threshold}%")]
+    HistorySyncDeferred { battery: u8, threshold: u8 },
     #[error("Failed to connect: {0}")]
     Connect(aranet_core::Error),
     #[error("Failed to read: {0}")]
@@ -456,6 +946,19 @@ pub enum CollectorError {
     Store(aranet_store::Error),
 }
 
+impl CollectorError {
+    /// Category for failure metrics.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            CollectorError::BleBusy | CollectorError::HistorySyncDeferred { .. } => {
+                ErrorCategory::Operation
+            }
+            CollectorError::Connect(e) | CollectorError::Read(e) => ErrorCategory::from(e),
+            CollectorError::Store(_) => ErrorCategory::Other,
+        }
+    }
+}
+
 #[cfg(feature = "notifications")]
 mod notifications {
     use std::collections::HashMap;
@@ -508,6 +1011,16 @@ mod notifications {
             ));
         }
 
+        if let Some(rate) = reading.radiation_rate
+            && rate >= config.radiation_threshold
+        {
+            should_notify = true;
+            body.push_str(&format!(
+                "Radiation: {:.2} \u{00b5}Sv/h (threshold: {})\n",
+                rate, config.radiation_threshold
+            ));
+        }
+
         if should_notify {
             let title = format!("Aranet Alert: {}", alias);
             if let Err(e) = notify_rust::Notification::new()
@@ -554,6 +1067,57 @@ mod tests {
         AppState::with_config_path(store, config, test_config_path())
     }
 
+    #[test]
+    fn test_reading_quality() {
+        let good = CurrentReading::builder()
+            .co2(800)
+            .temperature(21.0)
+            .pressure(1010.0)
+            .humidity(40)
+            .build();
+        let spike = CurrentReading {
+            co2: 20_000,
+            ..good
+        };
+
+        assert_eq!(
+            reading_quality(Some(DeviceType::Aranet4), &good),
+            ReadingQuality::Valid
+        );
+        assert_eq!(
+            reading_quality(Some(DeviceType::Aranet4), &spike),
+            ReadingQuality::Invalid
+        );
+        assert_eq!(reading_quality(None, &spike), ReadingQuality::Unchecked);
+    }
+
+    #[test]
+    fn test_backoff_delay_doubles_up_to_cap() {
+        let interval = Duration::from_secs(60);
+        assert_eq!(backoff_delay(interval, 1, 0.0), interval);
+        assert_eq!(backoff_delay(interval, 2, 0.0), Duration::from_secs(120));
+        assert_eq!(backoff_delay(interval, 4, 0.0), Duration::from_secs(480));
+        assert_eq!(
+            backoff_delay(interval, 10, 0.0),
+            Duration::from_secs(MAX_BACKOFF_SECS)
+        );
+        assert_eq!(
+            backoff_delay(interval, u32::MAX, 0.0),
+            Duration::from_secs(MAX_BACKOFF_SECS)
+        );
+    }
+
+    #[test]
+    fn test_backoff_delay_jitter_and_long_intervals() {
+        let interval = Duration::from_secs(60);
+        assert_eq!(backoff_delay(interval, 2, 0.25), Duration::from_secs(150));
+        // Jitter is clamped to 25%
+        assert_eq!(backoff_delay(interval, 2, 5.0), Duration::from_secs(150));
+        // Intervals beyond the cap are never shortened
+        let long = Duration::from_secs(MAX_BACKOFF_SECS * 2);
+        assert_eq!(backoff_delay(long, 5, 0.0), long);
+    }
+
     #[test]
     fn test_collector_new() {
         let state = create_test_state();
@@ -622,6 +1186,8 @@ mod tests {
                 address: "AA:BB:CC:DD:EE:FF".to_string(),
                 alias: Some("Test Device".to_string()),
                 poll_interval: 60,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
             });
         }
 
@@ -702,6 +1268,10 @@ mod tests {
             success_count: 0,
             failure_count: 0,
             polling: false,
+            clock_drift_ppm: None,
+            history_overwrite_at: None,
+            history_sync_deferred_below: None,
+            backoff: None,
         };
 
         assert_eq!(stats.device_id, "test-device");
@@ -731,6 +1301,10 @@ mod tests {
                 success_count: 0,
                 failure_count: 0,
                 polling: false,
+                clock_drift_ppm: None,
+                history_overwrite_at: None,
+                history_sync_deferred_below: None,
+                backoff: None,
             });
         }
 
@@ -765,6 +1339,10 @@ mod tests {
                 success_count: 0,
                 failure_count: 0,
                 polling: false,
+                clock_drift_ppm: None,
+                history_overwrite_at: None,
+                history_sync_deferred_below: None,
+                backoff: None,
             });
         }
 
@@ -780,6 +1358,126 @@ mod tests {
         assert_eq!(stats[0].success_count, 0);
     }
 
+    #[tokio::test]
+    async fn test_history_sync_deferred_for_low_stored_battery() {
+        let state = create_test_state();
+        let device = DeviceConfig {
+            address: "AA:BB".to_string(),
+            alias: None,
+            poll_interval: 60,
+            privacy: Default::default(),
+            min_history_sync_battery: None,
+        };
+        initialize_device_stats(&state, &[device]).await;
+        let reading = CurrentReading::builder().co2(800).battery(5).build();
+        state
+            .with_store_write(|store| store.insert_reading("AA:BB", &reading))
+            .await
+            .unwrap();
+
+        let err = check_history_sync_battery(&state, "AA:BB")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CollectorError::HistorySyncDeferred {
+                battery: 5,
+                threshold: aranet_core::DEFAULT_MIN_HISTORY_SYNC_BATTERY,
+            }
+        ));
+        assert_eq!(
+            state.collector.device_stats.read().await[0].history_sync_deferred_below,
+            Some(aranet_core::DEFAULT_MIN_HISTORY_SYNC_BATTERY)
+        );
+
+        // The collector-wide threshold and the device's own override apply
+        state
+            .config
+            .write()
+            .await
+            .collector
+            .min_history_sync_battery = 5;
+        assert!(check_history_sync_battery(&state, "AA:BB").await.is_ok());
+        assert_eq!(
+            state.collector.device_stats.read().await[0].history_sync_deferred_below,
+            None
+        );
+        state.config.write().await.devices.push(DeviceConfig {
+            address: "aa:bb".to_string(),
+            alias: None,
+            poll_interval: 60,
+            privacy: Default::default(),
+            min_history_sync_battery: Some(50),
+        });
+        assert!(matches!(
+            check_history_sync_battery(&state, "AA:BB").await,
+            Err(CollectorError::HistorySyncDeferred { threshold: 50, .. })
+        ));
+
+        // Records about to be overwritten are synced regardless
+        update_device_stat(&state, "AA:BB", |stat| {
+            stat.history_overwrite_at = Some(OffsetDateTime::now_utc());
+        })
+        .await;
+        assert!(check_history_sync_battery(&state, "AA:BB").await.is_ok());
+
+        // Devices with no stored reading are not held back
+        assert!(check_history_sync_battery(&state, "CC:DD").await.is_ok());
+    }
+
+    #[test]
+    fn test_correct_clock_drift_uses_stored_drift() {
+        let store = Store::open_in_memory().unwrap();
+        let newest = OffsetDateTime::now_utc();
+        let record = |timestamp| {
+            aranet_types::HistoryRecord::builder()
+                .timestamp(timestamp)
+                .co2(800)
+                .build()
+        };
+        let mut history = vec![record(newest - time::Duration::days(1)), record(newest)];
+
+        // No drift measured yet: stored as downloaded
+        correct_clock_drift(&store, "AA:BB", &mut history).unwrap();
+        assert_eq!(history[0].timestamp, newest - time::Duration::days(1));
+
+        store.upsert_device("AA:BB", None).unwrap();
+        store.update_clock_drift("AA:BB", 100.0, 86_400).unwrap();
+        correct_clock_drift(&store, "AA:BB", &mut history).unwrap();
+        assert_ne!(history[0].timestamp, newest - time::Duration::days(1));
+        assert_eq!(history[1].timestamp, newest);
+    }
+
+    #[test]
+    fn test_record_setting_changes() {
+        use aranet_core::MeasurementInterval;
+
+        let store = Store::open_in_memory().unwrap();
+        let now = OffsetDateTime::now_utc();
+        let changes = [
+            SettingChange::Interval(MeasurementInterval::OneMinute),
+            SettingChange::BluetoothRange(BluetoothRange::Extended),
+            SettingChange::SmartHome(true),
+        ];
+        record_setting_changes(&store, "AA:BB", &changes, now).unwrap();
+        // Unchanged values are not recorded twice
+        record_setting_changes(&store, "AA:BB", &changes[..1], now).unwrap();
+
+        let history = store.settings_history("AA:BB").unwrap();
+        let values: Vec<_> = history
+            .iter()
+            .map(|change| (change.setting.as_str(), change.new_value.as_str()))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                (aranet_store::SETTING_INTERVAL, "60"),
+                (aranet_store::SETTING_BLUETOOTH_RANGE, "extended"),
+                (aranet_store::SETTING_SMART_HOME, "true"),
+            ]
+        );
+    }
+
     #[test]
     fn test_collector_error_ble_busy_display() {
         let err = CollectorError::BleBusy;
@@ -787,22 +1485,27 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_ble_semaphore_serializes_access() {
+    async fn test_ble_semaphore_limits_concurrent_connections() {
         let state = create_test_state();
+        let max = Config::default().collector.max_concurrent_connections;
+        assert_eq!(state.ble_semaphore.available_permits(), max);
 
-        // Acquire the single permit
-        let permit = state.ble_semaphore.acquire().await.unwrap();
+        // Up to the configured limit can connect at once
+        let mut permits = Vec::new();
+        for _ in 0..max {
+            permits.push(state.ble_semaphore.acquire().await.unwrap());
+        }
 
-        // A second acquire should not succeed immediately
+        // One more acquire should not succeed immediately
         let result =
             tokio::time::timeout(Duration::from_millis(50), state.ble_semaphore.acquire()).await;
         assert!(
             result.is_err(),
-            "second acquire should timeout while first permit is held"
+            "acquire should timeout while all permits are held"
         );
 
-        // After dropping, the next acquire succeeds
-        drop(permit);
+        // After releasing one, the next acquire succeeds
+        permits.pop();
         let result =
             tokio::time::timeout(Duration::from_millis(50), state.ble_semaphore.acquire()).await;
         assert!(
@@ -822,16 +1525,22 @@ mod tests {
                 address: "DEVICE-1".to_string(),
                 alias: Some("First".to_string()),
                 poll_interval: 30,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
             });
             config.devices.push(crate::config::DeviceConfig {
                 address: "DEVICE-2".to_string(),
                 alias: Some("Second".to_string()),
                 poll_interval: 60,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
             });
             config.devices.push(crate::config::DeviceConfig {
                 address: "DEVICE-3".to_string(),
                 alias: None,
                 poll_interval: 120,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
             });
         }
 
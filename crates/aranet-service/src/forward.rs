@@ -0,0 +1,457 @@
+//! Forwarding of readings to a central aranet-service.
+//!
+//! An edge collector (e.g. a Raspberry Pi next to the sensors) forwards every
+//! reading it stores to a central instance through `POST /api/ingest`, so
+//! several buildings can be aggregated into one database. Readings are
+//! buffered in memory and sent in batches; while the central service is
+//! unreachable they stay buffered and sending is retried with exponential
+//! backoff.
+//!
+//! # Example Configuration
+//!
+//! ```toml
+//! [forward]
+//! enabled = true
+//! url = "https://aranet.example.com"
+//! api_key = "central-service-api-key"
+//! batch_size = 100
+//! buffer_size = 10000
+//! interval_secs = 10
+//! max_retry_delay_secs = 300
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::Client;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+use crate::api::{IngestReading, IngestRequest, IngestResponse};
+use crate::config::ForwardConfig;
+use crate::state::AppState;
+
+/// Forwarder that pushes readings to a central aranet-service.
+pub struct Forwarder {
+    state: Arc<AppState>,
+}
+
+impl Forwarder {
+    /// Create a new forwarder.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Start the forwarder.
+    ///
+    /// Spawns a background task that listens to the readings broadcast channel
+    /// and sends buffered readings to the central service.
+    pub async fn start(&self) {
+        let config = self.state.config.read().await;
+        let forward_config = config.forward.clone();
+        drop(config);
+
+        if !forward_config.enabled {
+            info!("Forwarding is disabled");
+            return;
+        }
+
+        info!("Starting forwarder to {}", forward_config.url);
+
+        let state = Arc::clone(&self.state);
+        let shutdown_rx = self.state.subscribe_shutdown();
+
+        tokio::spawn(async move {
+            run_forwarder(state, forward_config, shutdown_rx).await;
+        });
+    }
+}
+
+/// Run the forwarder loop.
+async fn run_forwarder(
+    state: Arc<AppState>,
+    config: ForwardConfig,
+    mut shutdown_rx: tokio::sync::watch::Receiver<bool>,
+) {
+    let client = match ForwardClient::new(&config.url, config.api_key.clone()) {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("Failed to create HTTP client for forwarding: {e}");
+            return;
+        }
+    };
+
+    let mut readings_rx = state.readings_tx.subscribe();
+    let mut buffer = ForwardBuffer::new(config.buffer_size);
+    let mut backoff = RetryBackoff::new(
+        Duration::from_secs(config.interval_secs),
+        Duration::from_secs(config.max_retry_delay_secs),
+    );
+    let mut next_send = Instant::now() + backoff.interval();
+
+    loop {
+        tokio::select! {
+            result = readings_rx.recv() => {
+                match result {
+                    Ok(event) => {
+                        if !state.device_privacy(&event.device_id).await.is_forwarded() {
+                            continue;
+                        }
+                        let name = configured_alias(&state, &event.device_id).await;
+                        if buffer.push(IngestReading { name, reading: event.reading }) {
+                            warn!(
+                                "Forward buffer full, dropped oldest reading ({} dropped so far)",
+                                buffer.dropped()
+                            );
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Forwarder lagged, missed {} readings", n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        info!("Readings channel closed, stopping forwarder");
+                        break;
+                    }
+                }
+            }
+            _ = tokio::time::sleep_until(next_send) => {
+                next_send = Instant::now() + flush(&client, &config, &mut buffer, &mut backoff).await;
+            }
+            _ = shutdown_rx.changed() => {
+                if *shutdown_rx.borrow() {
+                    info!("Forwarder received stop signal");
+                    break;
+                }
+            }
+        }
+    }
+
+    // Best effort: a failure here loses whatever is still buffered
+    flush(&client, &config, &mut buffer, &mut backoff).await;
+    if !buffer.is_empty() {
+        warn!(
+            "Forwarder stopped with {} unsent readings",
+            buffer.readings.len()
+        );
+    }
+
+    info!("Forwarder stopped");
+}
+
+/// Send buffered readings in batches until the buffer is empty or a send
+/// fails. Returns how long to wait before the next attempt.
+async fn flush(
+    client: &ForwardClient,
+    config: &ForwardConfig,
+    buffer: &mut ForwardBuffer,
+    backoff: &mut RetryBackoff,
+) -> Duration {
+    while !buffer.is_empty() {
+        let batch = buffer.batch(config.batch_size);
+        let sent = batch.len();
+        match client.send(batch).await {
+            Ok(response) => {
+                debug!(
+                    "Forwarded {} readings ({} duplicates)",
+                    response.accepted, response.duplicates
+                );
+                buffer.acknowledge(sent);
+                if backoff.failures > 0 {
+                    info!(
+                        "Forwarding resumed, {} readings buffered",
+                        buffer.readings.len()
+                    );
+                }
+                backoff.reset();
+            }
+            Err(e) if e.is_rejected() => {
+                // Resending the same readings would fail again
+                warn!(
+                    "Central service rejected {} readings, dropping them: {}",
+                    sent, e
+                );
+                buffer.acknowledge(sent);
+            }
+            Err(e) => {
+                let delay = backoff.fail();
+                warn!(
+                    "Failed to forward readings ({} buffered), retrying in {}s: {}",
+                    buffer.readings.len(),
+                    delay.as_secs(),
+                    e
+                );
+                return delay;
+            }
+        }
+    }
+    backoff.interval()
+}
+
+async fn configured_alias(state: &AppState, device_id: &str) -> Option<String> {
+    let config = state.config.read().await;
+    config
+        .devices
+        .iter()
+        .find(|device| device.address == device_id)
+        .and_then(|device| device.alias.clone())
+}
+
+/// HTTP client for a central service's `POST /api/ingest` endpoint.
+///
+/// Used by the forwarder, and usable on its own to push readings from
+/// anything that isn't a full service (e.g. `aranet simulate`).
+#[derive(Debug, Clone)]
+pub struct ForwardClient {
+    client: Client,
+    url: String,
+    api_key: Option<String>,
+}
+
+impl ForwardClient {
+    /// Create a client for the service at `base_url` (e.g. `http://localhost:8080`).
+    pub fn new(base_url: &str, api_key: Option<String>) -> Result<Self, ForwardError> {
+        let client = Client::builder().timeout(Duration::from_secs(30)).build()?;
+        Ok(Self {
+            client,
+            url: format!("{}/api/ingest", base_url.trim_end_matches('/')),
+            api_key,
+        })
+    }
+
+    /// Post one batch of readings, at most [`crate::api::MAX_INGEST_BATCH`].
+    pub async fn send(&self, readings: &[IngestReading]) -> Result<IngestResponse, ForwardError> {
+        let mut request = self.client.post(&self.url).json(&IngestRequest {
+            readings: readings.to_vec(),
+        });
+        if let Some(api_key) = &self.api_key {
+            request = request.header("X-API-Key", api_key);
+        }
+
+        let response = request.send().await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ForwardError::Response {
+                status: status.as_u16(),
+                body,
+            });
+        }
+
+        Ok(response.json().await?)
+    }
+}
+
+/// Readings waiting to be forwarded, oldest first.
+#[derive(Debug)]
+struct ForwardBuffer {
+    readings: VecDeque<IngestReading>,
+    capacity: usize,
+    dropped: u64,
+}
+
+impl ForwardBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            readings: VecDeque::new(),
+            capacity: capacity.max(1),
+            dropped: 0,
+        }
+    }
+
+    /// Add a reading, dropping the oldest if full. Returns `true` if one was
+    /// dropped.
+    fn push(&mut self, reading: IngestReading) -> bool {
+        let overflow = self.readings.len() >= self.capacity;
+        if overflow {
+            self.readings.pop_front();
+            self.dropped += 1;
+        }
+        self.readings.push_back(reading);
+        overflow
+    }
+
+    /// The oldest readings, at most `size` of them.
+    fn batch(&mut self, size: usize) -> &[IngestReading] {
+        let len = size.min(self.readings.len());
+        &self.readings.make_contiguous()[..len]
+    }
+
+    /// Remove the first `count` readings after they were sent.
+    fn acknowledge(&mut self, count: usize) {
+        self.readings.drain(..count.min(self.readings.len()));
+    }
+
+    fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    fn is_empty(&self) -> bool {
+        self.readings.is_empty()
+    }
+}
+
+/// Delay between send attempts, doubling after each failure.
+#[derive(Debug)]
+struct RetryBackoff {
+    interval: Duration,
+    max: Duration,
+    failures: u32,
+}
+
+impl RetryBackoff {
+    fn new(interval: Duration, max: Duration) -> Self {
+        Self {
+            interval,
+            max: max.max(interval),
+            failures: 0,
+        }
+    }
+
+    /// The regular send interval.
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Record a failure and return the delay before the next attempt.
+    fn fail(&mut self) -> Duration {
+        let delay = self
+            .interval
+            .saturating_mul(2u32.saturating_pow(self.failures))
+            .min(self.max);
+        self.failures = self.failures.saturating_add(1);
+        delay
+    }
+
+    fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
+/// Errors that can occur when forwarding readings.
+#[derive(Debug, thiserror::Error)]
+pub enum ForwardError {
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Central service returned error {status}: {body}")]
+    Response { status: u16, body: String },
+}
+
+impl ForwardError {
+    /// Whether the central service refused the batch itself, rather than
+    /// being unreachable, overloaded or rejecting the credentials.
+    pub fn is_rejected(&self) -> bool {
+        matches!(
+            self,
+            ForwardError::Response { status, .. }
+                if matches!(status, 400 | 413 | 422)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aranet_types::Status;
+    use time::OffsetDateTime;
+
+    fn test_reading(co2: u16) -> IngestReading {
+        IngestReading {
+            name: None,
+            reading: aranet_store::StoredReading {
+                id: 1,
+                device_id: "Aranet4 12345".to_string(),
+                co2,
+                temperature: 22.5,
+                humidity: 45,
+                pressure: 1013.25,
+                battery: 85,
+                status: Status::Green,
+                radon: None,
+                radiation_rate: None,
+                radiation_total: None,
+                radon_avg_24h: None,
+                radon_avg_7d: None,
+                radon_avg_30d: None,
+                quality: aranet_store::ReadingQuality::Unchecked,
+                counter: None,
+                captured_at: OffsetDateTime::from_unix_timestamp(1711612800).unwrap(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_buffer_drops_oldest_when_full() {
+        let mut buffer = ForwardBuffer::new(2);
+
+        assert!(!buffer.push(test_reading(400)));
+        assert!(!buffer.push(test_reading(500)));
+        assert!(buffer.push(test_reading(600)));
+        assert_eq!(buffer.dropped(), 1);
+
+        let co2: Vec<u16> = buffer.batch(10).iter().map(|r| r.reading.co2).collect();
+        assert_eq!(co2, vec![500, 600]);
+    }
+
+    #[test]
+    fn test_buffer_keeps_batch_until_acknowledged() {
+        let mut buffer = ForwardBuffer::new(10);
+        for co2 in [400, 500, 600] {
+            buffer.push(test_reading(co2));
+        }
+
+        assert_eq!(buffer.batch(2).len(), 2);
+        // A failed send leaves the batch in place
+        assert_eq!(buffer.batch(2)[0].reading.co2, 400);
+
+        buffer.acknowledge(2);
+        let co2: Vec<u16> = buffer.batch(2).iter().map(|r| r.reading.co2).collect();
+        assert_eq!(co2, vec![600]);
+        buffer.acknowledge(2);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles_up_to_max() {
+        let mut backoff = RetryBackoff::new(Duration::from_secs(10), Duration::from_secs(60));
+
+        let delays: Vec<u64> = (0..5).map(|_| backoff.fail().as_secs()).collect();
+        assert_eq!(delays, vec![10, 20, 40, 60, 60]);
+
+        backoff.reset();
+        assert_eq!(backoff.fail(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_only_bad_batches_are_rejected() {
+        let response = |status| ForwardError::Response {
+            status,
+            body: String::new(),
+        };
+
+        assert!(response(400).is_rejected());
+        assert!(response(413).is_rejected());
+        assert!(!response(401).is_rejected());
+        assert!(!response(429).is_rejected());
+        assert!(!response(503).is_rejected());
+    }
+
+    #[test]
+    fn test_ingest_request_format() {
+        let request = IngestRequest {
+            readings: vec![IngestReading {
+                name: Some("Office".to_string()),
+                ..test_reading(800)
+            }],
+        };
+
+        let json = serde_json::to_value(&request).unwrap();
+        let reading = &json["readings"][0];
+        assert_eq!(reading["name"], "Office");
+        assert_eq!(reading["device_id"], "Aranet4 12345");
+        assert_eq!(reading["co2"], 800);
+        assert_eq!(reading["captured_at"], "2024-03-28T08:00:00Z");
+    }
+}
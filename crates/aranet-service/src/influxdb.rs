@@ -91,6 +91,9 @@ async fn run_influxdb_writer(
             result = readings_rx.recv() => {
                 match result {
                     Ok(event) => {
+                        if !state.device_privacy(&event.device_id).await.is_forwarded() {
+                            continue;
+                        }
                         let alias = configured_alias(&state, &event.device_id).await;
                         let line = to_line_protocol(&config, &event, alias.as_deref());
                         if let Err(e) = write_line(&client, &config, &line).await {
@@ -171,6 +174,9 @@ fn to_line_protocol(config: &InfluxDbConfig, event: &ReadingEvent, alias: Option
     if let Some(avg) = reading.radon_avg_30d {
         fields.push(format!("radon_avg_30d={}i", avg));
     }
+    for channel in &event.channels {
+        fields.push(format!("{}={:.2}", channel.channel, channel.value));
+    }
 
     let field_set = fields.join(",");
 
@@ -284,8 +290,11 @@ mod tests {
                 radon_avg_24h: None,
                 radon_avg_7d: None,
                 radon_avg_30d: None,
+                quality: aranet_store::ReadingQuality::Unchecked,
+                counter: None,
                 captured_at: OffsetDateTime::from_unix_timestamp(1711612800).unwrap(),
             },
+            channels: Vec::new(),
         }
     }
 
@@ -317,6 +326,20 @@ mod tests {
         assert!(line.contains("address=Aranet4\\ 12345"));
     }
 
+    #[test]
+    fn test_to_line_protocol_with_channels() {
+        let config = InfluxDbConfig::default();
+        let mut event = test_event("Aranet4 12345", 800);
+        event.channels.push(crate::channels::ChannelValue {
+            channel: "dew_point".to_string(),
+            value: 10.234,
+        });
+
+        let line = to_line_protocol(&config, &event, None);
+
+        assert!(line.contains(",dew_point=10.23 "));
+    }
+
     #[test]
     fn test_to_line_protocol_with_radon() {
         let config = InfluxDbConfig::default();
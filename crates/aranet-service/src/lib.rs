@@ -7,26 +7,36 @@
 //! - Stores readings in the local database
 //! - Exposes a REST API for querying data
 //! - Provides WebSocket connections for real-time updates
-//! - Optional API key authentication and rate limiting
+//! - Optional API key or OIDC bearer token authentication and rate limiting
 //!
 //! # REST API Endpoints
 //!
 //! - `GET /api/health` - Lightweight service health check (no auth required)
-//! - `GET /api/health/detailed` - Database, collector, and platform diagnostics
+//! - `GET /api/health/detailed` - Database, collector, WebSocket, and platform diagnostics
+//! - `GET /api/health/diagnostics` - Recent BLE errors and operation timings
 //! - `GET /api/status` - Collector status plus per-device polling statistics
 //! - `GET /api/devices` - List all known devices
 //! - `GET /api/devices/current` - Latest reading for every known device
 //! - `GET /api/devices/:id` - Get device info
+//! - `DELETE /api/devices/:id?purge=true&dry_run=true` - Stop monitoring a device and purge its data
 //! - `GET /api/devices/:id/current` - Latest reading wrapped in `CurrentReadingResponse`
 //! - `GET /api/devices/:id/readings` - Query readings with filters
 //! - `GET /api/devices/:id/history` - Query cached history
+//! - `GET /api/devices/:id/channels` - Query computed channel values
+//! - `PATCH /api/devices/:id/settings` - Queue a BLE settings change for a monitored device
+//! - `GET /api/settings/jobs/:id` - Status of a queued settings change
+//! - `POST /api/devices/:id/sync` - Start an on-demand history sync for a monitored device
+//! - `GET /api/sync/jobs/:id` - Progress and result of a history sync
 //! - `GET /api/readings` - All readings across devices
-//! - `GET /api/config`, `PUT /api/config` - Read or update runtime configuration
+//! - `POST /api/ingest` - Store readings pushed by an edge collector (see [`forward`])
+//! - `GET /api/config` - Full runtime configuration with secrets redacted
+//! - `PUT /api/config` - Update runtime configuration
 //! - `POST /api/config/devices`, `PUT/DELETE /api/config/devices/:id` - Manage monitored devices
 //! - `POST /api/collector/start`, `POST /api/collector/stop` - Control the background collector
 //! - `GET /metrics` - Prometheus metrics export
-//! - `WS /api/ws` - Real-time readings stream
+//! - `WS /api/ws` - Real-time readings stream (with optional backlog replay)
 //! - `GET /`, `GET /dashboard` - Embedded dashboard shell
+//! - `GET /static/*` - Embedded dashboard assets (CSS, JavaScript)
 //!
 //! # Configuration
 //!
@@ -45,6 +55,14 @@
 //! poll_interval = 60
 //! ```
 //!
+//! `ARANET_*` environment variables override file values, using `__` between
+//! table levels (`ARANET_SERVER__BIND`, `ARANET_SECURITY__API_KEY`), and
+//! command-line flags override both. See [`Config::load_layered`].
+//!
+//! `aranet-service config validate` checks a file without starting the
+//! service and reports each problem with its line number (see
+//! [`Config::check_file`]).
+//!
 //! # Security
 //!
 //! Optional security features can be enabled:
@@ -55,14 +73,34 @@
 //! api_key_enabled = true
 //! api_key = "your-secure-random-key-at-least-32-chars"
 //!
-//! # Rate limit requests per IP address
+//! # Rate limit requests per IP address, or per API key for requests sending it
 //! rate_limit_enabled = true
 //! rate_limit_requests = 100   # max requests per window
 //! rate_limit_window_secs = 60 # window duration
+//! rate_limit_api_key_requests = 1000
+//!
+//! # Per-route overrides; the first matching path wins
+//! [[security.rate_limit_routes]]
+//! path = "/api/health"        # no limit
+//!
+//! [[security.rate_limit_routes]]
+//! path = "/api/devices/{id}/sync"
+//! requests = 5
+//! window_secs = 300
+//!
+//! # Accept OIDC bearer tokens (Authorization: Bearer <jwt>) in addition to
+//! # the API key. Roles map to read-only or admin access.
+//! [security.oidc]
+//! enabled = true
+//! issuer = "https://auth.example.com/realms/home"
+//! audience = "aranet"
+//! role_claim = "roles"
+//! read_roles = ["viewer"]
+//! admin_roles = ["admin"]
 //! ```
 //!
-//! The dashboard shell routes (`/` and `/dashboard`) remain public so browsers can
-//! load the UI. Protected API, metrics, and WebSocket requests still honor the
+//! The dashboard shell routes (`/`, `/dashboard`, and `/static/*`) remain public so
+//! browsers can load the UI. Protected API, metrics, and WebSocket requests still honor the
 //! configured security settings.
 //!
 //! For WebSocket connections, browsers cannot set custom headers. Use the `token`
@@ -208,20 +246,25 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use axum::Router;
+use axum::http::Request;
 use tower_http::trace::TraceLayer;
 
 pub mod api;
+pub mod channels;
 pub mod collector;
 pub mod config;
 pub mod dashboard;
 pub mod middleware;
+pub mod oidc;
 pub mod state;
 pub mod ws;
 
 pub use collector::Collector;
 pub use config::{
-    Config, ConfigError, DeviceConfig, InfluxDbConfig, MqttConfig, NotificationConfig,
-    PrometheusConfig, SecurityConfig, ServerConfig, StorageConfig, WebhookConfig, WebhookEndpoint,
+    ChannelConfig, ChannelFormula, ChannelMetric, Config, ConfigError, ConfigIssue, ConfigReport,
+    DeviceConfig, DevicePrivacy, ForwardConfig, InfluxDbConfig, MqttConfig, NotificationConfig,
+    OidcConfig, PrometheusConfig, RouteRateLimit, SecurityConfig, SecurityZone, ServerConfig,
+    StorageConfig, WebhookConfig, WebhookEndpoint,
 };
 pub use state::{AppState, ReadingEvent};
 
@@ -231,6 +274,7 @@ pub mod mqtt;
 #[cfg(feature = "prometheus")]
 pub mod prometheus;
 
+pub mod forward;
 pub mod influxdb;
 pub mod mdns;
 pub mod webhook;
@@ -269,14 +313,32 @@ pub fn app(
         .merge(ws::router())
         .merge(dashboard::router())
         .layer(axum::middleware::from_fn_with_state(
-            Arc::clone(&security_config),
-            middleware::api_key_auth,
+            Arc::new(middleware::AuthState::new(Arc::clone(&security_config))),
+            middleware::authenticate,
         ))
         .layer(axum::middleware::from_fn_with_state(
             (security_config, rate_limit_state),
             middleware::rate_limit,
         ))
-        .layer(TraceLayer::new_for_http())
+        .layer(
+            TraceLayer::new_for_http().make_span_with(|request: &Request<_>| {
+                let request_id = request
+                    .extensions()
+                    .get::<middleware::RequestId>()
+                    .map_or("", |id| id.0.as_str());
+                // The path only: query strings may carry credentials
+                tracing::info_span!(
+                    "request",
+                    method = %request.method(),
+                    path = request.uri().path(),
+                    request_id,
+                )
+            }),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::clone(&state),
+            middleware::request_id,
+        ))
         .with_state(state)
 }
 
@@ -287,11 +349,7 @@ pub async fn run(options: RunOptions) -> anyhow::Result<()> {
         .clone()
         .unwrap_or_else(config::default_config_path);
 
-    let mut config = if config_path.exists() {
-        Config::load(&config_path)?
-    } else {
-        Config::default()
-    };
+    let mut config = Config::load_layered(&config_path)?;
 
     if let Some(bind) = options.bind {
         config.server.bind = bind;
@@ -305,19 +363,25 @@ pub async fn run(options: RunOptions) -> anyhow::Result<()> {
     tracing::info!("Opening database at {:?}", config.storage.path);
     let store = aranet_store::Store::open(&config.storage.path)?;
     let state = AppState::with_config_path(store, config.clone(), config_path);
+    if let Some(path) = &config.server.access_log {
+        state
+            .access_log
+            .open(path)
+            .map_err(|e| anyhow::anyhow!("Failed to open access log {:?}: {}", path, e))?;
+        tracing::info!("Writing access log to {:?}", path);
+    }
 
     let security_config = Arc::new(config.security.clone());
     let rate_limit_state = Arc::new(middleware::RateLimitState::new());
 
     {
         let rate_limit_state = Arc::clone(&rate_limit_state);
-        let window_secs = config.security.rate_limit_window_secs;
         let max_entries = config.security.rate_limit_max_entries;
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(std::time::Duration::from_secs(300));
             loop {
                 interval.tick().await;
-                rate_limit_state.cleanup(window_secs, max_entries).await;
+                rate_limit_state.cleanup(max_entries).await;
             }
         });
     }
@@ -357,6 +421,12 @@ pub async fn run(options: RunOptions) -> anyhow::Result<()> {
         influxdb_writer.start().await;
     }
 
+    {
+        use crate::forward::Forwarder;
+        let forwarder = Forwarder::new(Arc::clone(&state));
+        forwarder.start().await;
+    }
+
     let _mdns_handle = {
         use crate::mdns::MdnsAdvertiser;
         let advertiser = MdnsAdvertiser::new(Arc::clone(&state));
@@ -4,7 +4,7 @@
 
 use std::path::PathBuf;
 
-use aranet_service::{RunOptions, init_tracing, run};
+use aranet_service::{Config, RunOptions, init_tracing, run};
 use clap::{Parser, Subcommand};
 
 mod service;
@@ -44,6 +44,22 @@ enum Command {
         #[command(subcommand)]
         action: ServiceAction,
     },
+
+    /// Inspect the configuration file.
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigAction {
+    /// Check the configuration file and report problems with line numbers.
+    Validate {
+        /// File to check (defaults to --config or the default location).
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -96,6 +112,7 @@ async fn main() -> anyhow::Result<()> {
 
     match args.command {
         Some(Command::Service { action }) => handle_service_action(action, run_options),
+        Some(Command::Config { action }) => handle_config_action(action, args.config),
         Some(Command::Run) | None => {
             init_tracing()?;
             run(run_options).await
@@ -169,3 +186,31 @@ fn handle_service_action(action: ServiceAction, run_options: RunOptions) -> anyh
         }
     }
 }
+
+fn handle_config_action(action: ConfigAction, config: Option<PathBuf>) -> anyhow::Result<()> {
+    match action {
+        ConfigAction::Validate { file } => {
+            let path = file
+                .or(config)
+                .unwrap_or_else(aranet_service::config::default_config_path);
+            let report = Config::check_file(&path)?;
+
+            for error in &report.errors {
+                eprintln!("error: {}", error);
+            }
+            for warning in &report.warnings {
+                eprintln!("warning: {}", warning);
+            }
+
+            if !report.is_valid() {
+                anyhow::bail!("{} has {} error(s)", path.display(), report.errors.len());
+            }
+            println!(
+                "{} is valid ({} warning(s))",
+                path.display(),
+                report.warnings.len()
+            );
+            Ok(())
+        }
+    }
+}
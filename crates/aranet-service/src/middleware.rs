@@ -1,39 +1,274 @@
 //! Security middleware for the aranet-service API.
 //!
 //! This module provides middleware for:
-//! - API key authentication
+//! - Request IDs and access logging
+//! - API key and OIDC bearer token authentication
 //! - Rate limiting
 //! - Input sanitization
 
 use std::collections::HashMap;
-use std::net::IpAddr;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use axum::{
     Json,
+    body::Body,
     extract::{ConnectInfo, Request, State},
-    http::{HeaderMap, HeaderValue, StatusCode},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header},
     middleware::Next,
     response::{IntoResponse, Response},
 };
+use time::OffsetDateTime;
 use tokio::sync::RwLock;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{debug, warn};
 
 use crate::config::SecurityConfig;
+use crate::oidc::{OidcValidator, Scope};
+use crate::state::AppState;
+
+/// Header carrying the request ID, both inbound and on responses.
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest inbound `X-Request-Id` that is kept; longer ones are replaced.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Largest error body that gets a `request_id` field added.
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// The ID of the current request, stored in the request extensions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Use an inbound ID if it is short printable ASCII, otherwise generate one.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get(&REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|id| {
+                !id.is_empty()
+                    && id.len() <= MAX_REQUEST_ID_LEN
+                    && id.bytes().all(|b| b.is_ascii_graphic())
+            })
+            .map_or_else(Self::generate, |id| Self(id.to_string()))
+    }
+
+    /// A random 128-bit ID in hex.
+    pub fn generate() -> Self {
+        Self(format!("{:032x}", rand::random::<u128>()))
+    }
+}
+
+/// Destination for JSON access log lines (`server.access_log`).
+///
+/// Disabled until [`AccessLog::open`] is called.
+#[derive(Debug, Default)]
+pub struct AccessLog {
+    file: std::sync::Mutex<Option<File>>,
+}
+
+impl AccessLog {
+    /// Append access log lines to `path`, creating it if needed.
+    pub fn open(&self, path: &Path) -> io::Result<()> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        *self.file.lock().unwrap_or_else(|e| e.into_inner()) = Some(file);
+        Ok(())
+    }
+
+    /// Whether lines are being written.
+    pub fn is_enabled(&self) -> bool {
+        self.file
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some()
+    }
+
+    /// Write one JSON line. Failures are logged, not returned.
+    fn write(&self, entry: &serde_json::Value) {
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(file) = file.as_mut() {
+            let mut line = entry.to_string();
+            line.push('\n');
+            if let Err(e) = file.write_all(line.as_bytes()) {
+                warn!("Failed to write access log: {}", e);
+            }
+        }
+    }
+}
+
+/// Request ID and access log middleware.
+///
+/// Keeps an inbound `X-Request-Id` or generates one, makes it available to
+/// handlers and the request trace span as a [`RequestId`] extension, and
+/// echoes it in the response header and in the `request_id` field of JSON
+/// error bodies. Each request is logged at debug level and, when
+/// `server.access_log` is set, as a JSON line in that file.
+pub async fn request_id(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let started = Instant::now();
+    let id = RequestId::from_headers(request.headers());
+    let method = request.method().clone();
+    // The path only: query strings may carry credentials
+    let path = request.uri().path().to_string();
+    let user_agent = request
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+    request.extensions_mut().insert(id.clone());
+
+    let mut response = next.run(request).await;
+    if response.status().is_client_error() || response.status().is_server_error() {
+        response = add_request_id_to_error(response, &id).await;
+    }
+    if let Ok(value) = HeaderValue::from_str(&id.0) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    let status = response.status().as_u16();
+    let duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+    debug!(
+        target: "aranet_service::access",
+        request_id = %id.0,
+        %method,
+        %path,
+        status,
+        duration_ms,
+        "request completed"
+    );
+    if state.access_log.is_enabled() {
+        let bytes = response
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
+        state.access_log.write(&serde_json::json!({
+            "timestamp": OffsetDateTime::now_utc()
+                .format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or_default(),
+            "request_id": id.0,
+            "method": method.as_str(),
+            "path": path,
+            "status": status,
+            "duration_ms": (duration_ms * 1000.0).round() / 1000.0,
+            "client": addr.ip().to_string(),
+            "user_agent": user_agent,
+            "bytes": bytes,
+        }));
+    }
+    response
+}
+
+/// Add `request_id` to a JSON object error body; other bodies are returned
+/// unchanged.
+async fn add_request_id_to_error(response: Response, id: &RequestId) -> Response {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_ERROR_BODY).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(serde_json::Value::Object(mut object)) => {
+            object.insert("request_id".to_string(), id.0.clone().into());
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(serde_json::Value::Object(object).to_string())
+        }
+        _ => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
 
 /// State for rate limiting.
 #[derive(Debug, Default)]
 pub struct RateLimitState {
-    /// Request counts per IP address.
-    requests: RwLock<HashMap<IpAddr, RateLimitEntry>>,
+    /// Request counts per client and route.
+    requests: RwLock<HashMap<RateLimitKey, RateLimitEntry>>,
+}
+
+/// Who a request is counted against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitClient {
+    /// An unauthenticated client, by IP address.
+    Ip(IpAddr),
+    /// A client sending the configured API key, from any address.
+    ApiKey,
+}
+
+/// One rate limit window: a client, and the route override it applies to
+/// (an index into `rate_limit_routes`, or `None` for the default window).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RateLimitKey {
+    pub client: RateLimitClient,
+    pub route: Option<usize>,
+}
+
+impl From<IpAddr> for RateLimitKey {
+    fn from(ip: IpAddr) -> Self {
+        Self {
+            client: RateLimitClient::Ip(ip),
+            route: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 struct RateLimitEntry {
     count: u32,
     window_start: Instant,
+    window: Duration,
+}
+
+/// A window's state after counting a request, as reported in the
+/// `RateLimit-*` response headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Maximum requests per window.
+    pub limit: u32,
+    /// Requests left in the current window.
+    pub remaining: u32,
+    /// Seconds until the window resets.
+    pub reset_secs: u64,
+    /// Window length in seconds.
+    pub window_secs: u64,
+}
+
+impl RateLimitStatus {
+    /// Response headers for this status.
+    ///
+    /// `X-RateLimit-Limit` and `X-RateLimit-Remaining` are deprecated in
+    /// favor of the `RateLimit-*` headers and still sent for existing
+    /// clients until they are removed in a future release.
+    fn headers(&self) -> [(&'static str, String); 6] {
+        [
+            ("RateLimit-Limit", self.limit.to_string()),
+            ("RateLimit-Remaining", self.remaining.to_string()),
+            ("RateLimit-Reset", self.reset_secs.to_string()),
+            (
+                "RateLimit-Policy",
+                format!("{};w={}", self.limit, self.window_secs),
+            ),
+            ("X-RateLimit-Limit", self.limit.to_string()),
+            ("X-RateLimit-Remaining", self.remaining.to_string()),
+        ]
+    }
 }
 
 impl RateLimitState {
@@ -44,94 +279,181 @@ impl RateLimitState {
         }
     }
 
-    /// Check if a request from the given IP should be rate limited.
+    /// Count a request against `key`'s window.
+    ///
+    /// Returns `Err` if the request exceeds `max_requests`.
     pub async fn check_rate_limit(
         &self,
-        ip: IpAddr,
+        key: impl Into<RateLimitKey>,
         max_requests: u32,
         window_secs: u64,
-    ) -> Result<(), (u32, u64)> {
+    ) -> Result<RateLimitStatus, RateLimitStatus> {
         let window = Duration::from_secs(window_secs);
         let now = Instant::now();
 
         let mut requests = self.requests.write().await;
 
-        let entry = requests.entry(ip).or_insert_with(|| RateLimitEntry {
-            count: 0,
-            window_start: now,
-        });
+        let entry = requests
+            .entry(key.into())
+            .or_insert_with(|| RateLimitEntry {
+                count: 0,
+                window_start: now,
+                window,
+            });
 
         // Reset window if expired
+        entry.window = window;
         if now.duration_since(entry.window_start) >= window {
             entry.count = 0;
             entry.window_start = now;
         }
 
-        entry.count += 1;
+        entry.count = entry.count.saturating_add(1);
 
-        if entry.count > max_requests {
-            let remaining_secs = window
+        let status = RateLimitStatus {
+            limit: max_requests,
+            remaining: max_requests.saturating_sub(entry.count),
+            reset_secs: window
                 .checked_sub(now.duration_since(entry.window_start))
                 .map(|d| d.as_secs())
-                .unwrap_or(0);
-            Err((max_requests, remaining_secs))
+                .unwrap_or(0),
+            window_secs,
+        };
+        if entry.count > max_requests {
+            Err(status)
         } else {
-            Ok(())
+            Ok(status)
         }
     }
 
     /// Clean up expired entries to prevent memory leaks.
     ///
     /// Also enforces `max_entries` cap to prevent unbounded growth from many unique IPs.
-    pub async fn cleanup(&self, window_secs: u64, max_entries: usize) {
-        let window = Duration::from_secs(window_secs);
+    pub async fn cleanup(&self, max_entries: usize) {
         let now = Instant::now();
 
         let mut requests = self.requests.write().await;
         // Remove expired entries
-        requests.retain(|_, entry| now.duration_since(entry.window_start) < window * 2);
+        requests.retain(|_, entry| now.duration_since(entry.window_start) < entry.window * 2);
 
         // Evict oldest entries if we exceed the cap
         if requests.len() > max_entries {
-            let mut entries: Vec<(IpAddr, Instant)> = requests
+            let mut entries: Vec<(RateLimitKey, Instant)> = requests
                 .iter()
-                .map(|(ip, entry)| (*ip, entry.window_start))
+                .map(|(key, entry)| (*key, entry.window_start))
                 .collect();
             entries.sort_by_key(|(_, start)| *start);
             let to_remove = requests.len() - max_entries;
-            for (ip, _) in entries.into_iter().take(to_remove) {
-                requests.remove(&ip);
+            for (key, _) in entries.into_iter().take(to_remove) {
+                requests.remove(&key);
+            }
+        }
+    }
+}
+
+/// State for the authentication middleware.
+#[derive(Debug)]
+pub struct AuthState {
+    config: Arc<SecurityConfig>,
+    oidc: Option<OidcValidator>,
+}
+
+impl AuthState {
+    /// Create authentication state from the security configuration.
+    pub fn new(config: Arc<SecurityConfig>) -> Self {
+        let oidc = config
+            .oidc
+            .enabled
+            .then(|| OidcValidator::new(config.oidc.clone()));
+        Self { config, oidc }
+    }
+
+    /// Create authentication state with a preconfigured OIDC validator.
+    #[cfg(test)]
+    pub(crate) fn with_oidc(config: Arc<SecurityConfig>, oidc: OidcValidator) -> Self {
+        Self {
+            config,
+            oidc: Some(oidc),
+        }
+    }
+
+    /// Check a credential from the `token` query parameter, which may be
+    /// either the API key or a bearer token.
+    async fn check_token(&self, token: &str) -> Option<Scope> {
+        if let Some(scope) = self.check_api_key(token) {
+            return Some(scope);
+        }
+        self.check_bearer(token).await
+    }
+
+    /// Check an API key. The API key grants full access.
+    fn check_api_key(&self, provided: &str) -> Option<Scope> {
+        if !self.config.api_key_enabled {
+            return None;
+        }
+        let expected = self.config.api_key.as_ref()?;
+        // Use constant-time comparison to prevent timing attacks
+        constant_time_eq(expected.as_bytes(), provided.as_bytes()).then_some(Scope::Admin)
+    }
+
+    /// Check an OIDC bearer token.
+    async fn check_bearer(&self, token: &str) -> Option<Scope> {
+        let oidc = self.oidc.as_ref()?;
+        match oidc.validate(token).await {
+            Ok(scope) => Some(scope),
+            Err(e) => {
+                debug!("Bearer token rejected: {e}");
+                None
             }
         }
     }
 }
 
-/// API key authentication middleware.
+/// Authentication middleware.
+///
+/// Accepts the API key in the `X-API-Key` header, or an OIDC bearer token in
+/// the `Authorization` header when `[security.oidc]` is enabled. For WebSocket
+/// connections (which cannot set custom headers from browsers), either
+/// credential is also accepted in a `token` query parameter.
 ///
-/// Checks for the `X-API-Key` header and validates against the configured key.
-/// For WebSocket connections (which cannot set custom headers from browsers),
-/// also accepts a `token` query parameter.
+/// The API key grants full access. Bearer tokens grant read or admin access
+/// depending on their roles; requests that change state need admin access.
 ///
-/// Returns 401 Unauthorized if the key is missing or invalid.
-pub async fn api_key_auth(
+/// Returns 401 Unauthorized if the credential is missing or invalid, and
+/// 403 Forbidden if it does not grant the required access. Authenticated
+/// requests carry the granted [`Scope`] as a request extension.
+pub async fn authenticate(
     headers: HeaderMap,
-    State(config): State<Arc<SecurityConfig>>,
-    request: Request,
+    State(auth): State<Arc<AuthState>>,
+    mut request: Request,
     next: Next,
 ) -> Response {
     // Skip auth if not enabled
-    if !config.api_key_enabled {
+    if !auth.config.auth_enabled() {
         return next.run(request).await;
     }
 
     // Skip auth for the lightweight health endpoint and the dashboard shell.
     // The dashboard still authenticates its API and WebSocket requests separately.
-    if matches!(request.uri().path(), "/api/health" | "/" | "/dashboard") {
+    let path = request.uri().path();
+    if matches!(path, "/api/health" | "/" | "/dashboard")
+        || path.starts_with(crate::dashboard::STATIC_PREFIX)
+    {
         return next.run(request).await;
     }
 
-    // Get the API key from header first
-    let mut provided_key = headers.get("X-API-Key").and_then(|v| v.to_str().ok());
+    let mut scope = None;
+    if let Some(key) = headers.get("X-API-Key").and_then(|v| v.to_str().ok()) {
+        scope = auth.check_api_key(key);
+    }
+    if scope.is_none()
+        && let Some(token) = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+    {
+        scope = auth.check_bearer(token.trim()).await;
+    }
 
     // For WebSocket connections, also check query parameter
     // (browsers cannot set custom headers during WebSocket upgrade).
@@ -139,52 +461,79 @@ pub async fn api_key_auth(
     // SECURITY NOTE: Query parameters may be logged by reverse proxies,
     // appear in browser history, and leak via Referer headers. Prefer the
     // X-API-Key header for non-browser clients.
-    if provided_key.is_none()
+    if scope.is_none()
         && request.uri().path() == "/api/ws"
         && let Some(query) = request.uri().query()
     {
-        provided_key = query.split('&').find_map(|param| {
+        let token = query.split('&').find_map(|param| {
             let mut parts = param.splitn(2, '=');
             match (parts.next(), parts.next()) {
-                (Some("token"), Some(value)) => {
-                    debug!("WebSocket auth via query parameter (prefer X-API-Key header)");
-                    Some(value)
-                }
+                (Some("token"), Some(value)) => Some(value),
                 _ => None,
             }
         });
+        if let Some(token) = token {
+            debug!("WebSocket auth via query parameter (prefer X-API-Key header)");
+            scope = auth.check_token(token).await;
+        }
     }
 
-    // Validate
-    let valid = match (&config.api_key, provided_key) {
-        (Some(expected), Some(provided)) => {
-            // Use constant-time comparison to prevent timing attacks
-            constant_time_eq(expected.as_bytes(), provided.as_bytes())
+    let required = Scope::required_for(request.method());
+    match scope {
+        Some(scope) if scope >= required => {
+            request.extensions_mut().insert(scope);
+            next.run(request).await
+        }
+        Some(_) => {
+            warn!(
+                "Insufficient permissions for {} {}",
+                request.method(),
+                request.uri().path()
+            );
+            (
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({
+                    "error": "Insufficient permissions",
+                    "hint": "This request requires a token with an admin role"
+                })),
+            )
+                .into_response()
+        }
+        None => {
+            warn!("Authentication failed for {}", request.uri().path());
+            let (error, hint) = if auth.oidc.is_some() {
+                (
+                    "Invalid or missing credentials",
+                    "Provide a valid API key in the X-API-Key header or a bearer token in the Authorization header, or use the 'token' query parameter only for /api/ws",
+                )
+            } else {
+                (
+                    "Invalid or missing API key",
+                    "Provide a valid API key in the X-API-Key header, or use the 'token' query parameter only for /api/ws",
+                )
+            };
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({ "error": error, "hint": hint })),
+            )
+                .into_response()
         }
-        _ => false,
-    };
-
-    if valid {
-        next.run(request).await
-    } else {
-        warn!("API key authentication failed for {}", request.uri().path());
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({
-                "error": "Invalid or missing API key",
-                "hint": "Provide a valid API key in the X-API-Key header, or use the 'token' query parameter only for /api/ws"
-            })),
-        )
-            .into_response()
     }
 }
 
 /// Rate limiting middleware.
 ///
-/// Limits requests per IP address within a time window.
-/// Returns 429 Too Many Requests if the limit is exceeded.
+/// Limits requests per IP address, or per API key for requests sending the
+/// configured key, within a time window. Routes matching an entry in
+/// `rate_limit_routes` get their own window and limit, or none.
+///
+/// Limited responses carry `RateLimit-Limit`, `RateLimit-Remaining`,
+/// `RateLimit-Reset` and `RateLimit-Policy` headers, plus the deprecated
+/// `X-RateLimit-Limit` and `X-RateLimit-Remaining`. Returns 429 Too Many
+/// Requests, with `Retry-After`, if the limit is exceeded.
 pub async fn rate_limit(
     ConnectInfo(addr): ConnectInfo<std::net::SocketAddr>,
+    headers: HeaderMap,
     State((config, state)): State<(Arc<SecurityConfig>, Arc<RateLimitState>)>,
     request: Request,
     next: Next,
@@ -194,26 +543,64 @@ pub async fn rate_limit(
         return next.run(request).await;
     }
 
-    let ip = addr.ip();
+    let path = request.uri().path();
+    let sends_api_key = config.api_key_enabled
+        && match (&config.api_key, headers.get("X-API-Key")) {
+            (Some(expected), Some(provided)) => {
+                constant_time_eq(expected.as_bytes(), provided.as_bytes())
+            }
+            _ => false,
+        };
+    let client = if sends_api_key {
+        RateLimitClient::ApiKey
+    } else {
+        RateLimitClient::Ip(addr.ip())
+    };
+
+    let route = config
+        .rate_limit_routes
+        .iter()
+        .position(|route| route.matches(path));
+    let (max_requests, window_secs) = match route.map(|i| &config.rate_limit_routes[i]) {
+        Some(route) => match route.requests {
+            Some(requests) => (
+                requests,
+                route.window_secs.unwrap_or(config.rate_limit_window_secs),
+            ),
+            None => return next.run(request).await,
+        },
+        None if sends_api_key => (
+            config
+                .rate_limit_api_key_requests
+                .unwrap_or(config.rate_limit_requests),
+            config.rate_limit_window_secs,
+        ),
+        None => (config.rate_limit_requests, config.rate_limit_window_secs),
+    };
 
     match state
-        .check_rate_limit(
-            ip,
-            config.rate_limit_requests,
-            config.rate_limit_window_secs,
-        )
+        .check_rate_limit(RateLimitKey { client, route }, max_requests, window_secs)
         .await
     {
-        Ok(()) => next.run(request).await,
-        Err((limit, retry_after)) => {
-            warn!("Rate limit exceeded for {} on {}", ip, request.uri().path());
+        Ok(status) => {
+            let mut response = next.run(request).await;
+            for (name, value) in status.headers() {
+                if let Ok(value) = HeaderValue::from_str(&value) {
+                    response.headers_mut().insert(name, value);
+                }
+            }
+            response
+        }
+        Err(status) => {
+            match client {
+                RateLimitClient::Ip(ip) => warn!("Rate limit exceeded for {} on {}", ip, path),
+                RateLimitClient::ApiKey => warn!("Rate limit exceeded for API key on {}", path),
+            }
+            let retry_after = status.reset_secs;
             (
                 StatusCode::TOO_MANY_REQUESTS,
-                [
-                    ("Retry-After", retry_after.to_string()),
-                    ("X-RateLimit-Limit", limit.to_string()),
-                    ("X-RateLimit-Remaining", "0".to_string()),
-                ],
+                [("Retry-After", retry_after.to_string())],
+                status.headers(),
                 Json(serde_json::json!({
                     "error": "Too many requests",
                     "retry_after": retry_after
@@ -246,6 +633,7 @@ pub fn cors_layer(config: &SecurityConfig) -> CorsLayer {
             .allow_origin(Any)
             .allow_methods(Any)
             .allow_headers(Any)
+            .expose_headers([REQUEST_ID_HEADER])
     } else {
         let origins: Vec<HeaderValue> = config
             .cors_origins
@@ -256,6 +644,7 @@ pub fn cors_layer(config: &SecurityConfig) -> CorsLayer {
             .allow_origin(origins)
             .allow_methods(Any)
             .allow_headers(Any)
+            .expose_headers([REQUEST_ID_HEADER])
     }
 }
 
@@ -320,6 +709,25 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_request_id_from_headers() {
+        let mut headers = HeaderMap::new();
+        let generated = RequestId::from_headers(&headers);
+        assert_eq!(generated.0.len(), 32);
+        assert_ne!(generated, RequestId::from_headers(&headers));
+
+        headers.insert(&REQUEST_ID_HEADER, HeaderValue::from_static("req-42"));
+        assert_eq!(RequestId::from_headers(&headers).0, "req-42");
+
+        // Blank, spaced or oversized IDs are replaced
+        for bad in ["", "has space", &"x".repeat(MAX_REQUEST_ID_LEN + 1)] {
+            headers.insert(&REQUEST_ID_HEADER, HeaderValue::from_str(bad).unwrap());
+            let id = RequestId::from_headers(&headers);
+            assert_ne!(id.0, bad);
+            assert_eq!(id.0.len(), 32);
+        }
+    }
+
     #[test]
     fn test_sanitize_device_name_normal() {
         assert_eq!(sanitize_device_name("Aranet4 12345"), "Aranet4 12345");
@@ -431,7 +839,7 @@ mod tests {
         assert_eq!(state.requests.read().await.len(), 1);
 
         // Cleanup (entries within 2x window are kept)
-        state.cleanup(60, 10_000).await;
+        state.cleanup(10_000).await;
         assert_eq!(state.requests.read().await.len(), 1);
     }
 
@@ -447,7 +855,7 @@ mod tests {
         assert_eq!(state.requests.read().await.len(), 5);
 
         // Cleanup with max_entries=3 should evict the 2 oldest
-        state.cleanup(60, 3).await;
+        state.cleanup(3).await;
         assert_eq!(state.requests.read().await.len(), 3);
     }
 
@@ -516,8 +924,8 @@ mod tests {
         let app = Router::new()
             .route("/api/ws", get(|| async { StatusCode::OK }))
             .layer(axum::middleware::from_fn_with_state(
-                test_security_config(),
-                api_key_auth,
+                Arc::new(AuthState::new(test_security_config())),
+                authenticate,
             ));
 
         let response = app
@@ -538,8 +946,8 @@ mod tests {
         let app = Router::new()
             .route("/api/devices", get(|| async { StatusCode::OK }))
             .layer(axum::middleware::from_fn_with_state(
-                test_security_config(),
-                api_key_auth,
+                Arc::new(AuthState::new(test_security_config())),
+                authenticate,
             ));
 
         let response = app
@@ -554,4 +962,60 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     }
+
+    #[tokio::test]
+    async fn test_bearer_token_scopes() {
+        use crate::oidc::tests::{test_oidc_config, test_token, test_validator};
+
+        let config = Arc::new(SecurityConfig {
+            oidc: test_oidc_config(),
+            rate_limit_enabled: false,
+            ..Default::default()
+        });
+        let auth = AuthState::with_oidc(Arc::clone(&config), test_validator(test_oidc_config()));
+        let app = Router::new()
+            .route(
+                "/api/config",
+                get(|| async { StatusCode::OK }).put(|| async { StatusCode::OK }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(auth),
+                authenticate,
+            ));
+
+        let send = |method: &str, token: Option<String>| {
+            let mut request = Request::builder().method(method).uri("/api/config");
+            if let Some(token) = token {
+                request = request.header("Authorization", format!("Bearer {token}"));
+            }
+            app.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        let viewer = test_token(&["viewer"], serde_json::json!({}));
+        let admin = test_token(&["admin"], serde_json::json!({}));
+
+        assert_eq!(
+            send("GET", None).await.unwrap().status(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            send("GET", Some("not-a-jwt".to_string()))
+                .await
+                .unwrap()
+                .status(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            send("GET", Some(viewer.clone())).await.unwrap().status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            send("PUT", Some(viewer)).await.unwrap().status(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            send("PUT", Some(admin)).await.unwrap().status(),
+            StatusCode::OK
+        );
+    }
 }
@@ -17,6 +17,18 @@
 //! Where `{prefix}` is configurable (default: "aranet") and `{device}` is
 //! the device alias or address.
 //!
+//! # Commands
+//!
+//! With `commands = true`, the client also subscribes to
+//! `{prefix}/+/set/#` and queues device settings changes, applied the next
+//! time the collector connects to the device:
+//!
+//! - `{prefix}/{device}/set/interval` - Measurement interval in seconds (60, 120, 300, 600)
+//! - `{prefix}/{device}/set/smart_home` - `ON`/`OFF` (or `true`/`false`)
+//! - `{prefix}/{device}/set/range` - `standard` or `extended`
+//!
+//! Home Assistant discovery then also registers these as controls.
+//!
 //! # Example Configuration
 //!
 //! ```toml
@@ -37,11 +49,12 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS, TlsConfiguration, Transport};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, error, info, warn};
 
-use crate::config::MqttConfig;
-use crate::state::{AppState, ReadingEvent};
+use crate::api::DeviceSettingsPatchRequest;
+use crate::config::{DeviceConfig, MqttConfig};
+use crate::state::{AppState, ReadingEvent, SettingsPatch};
 
 /// MQTT publisher that forwards readings to an MQTT broker.
 pub struct MqttPublisher {
@@ -123,6 +136,13 @@ async fn run_mqtt_publisher(
     // Create MQTT client
     let (client, mut eventloop) = AsyncClient::new(mqtt_options, 100);
 
+    // Incoming command messages, forwarded from the event loop
+    let (commands_tx, mut commands_rx) = mpsc::channel::<(String, Vec<u8>)>(32);
+    let command_filter = config
+        .commands
+        .then(|| format!("{}/+/set/#", config.topic_prefix));
+    let subscribe_client = client.clone();
+
     // Subscribe to readings broadcast
     let mut readings_rx = state.readings_tx.subscribe();
     let mut reload_rx = state.collector.subscribe_reload();
@@ -148,6 +168,20 @@ async fn run_mqtt_publisher(
                         info!("MQTT connected: {:?}", ack);
                     }
                     consecutive_errors = 0;
+
+                    // Subscriptions do not survive a reconnect, so renew them on every ConnAck
+                    if let Some(filter) = &command_filter {
+                        match subscribe_client.try_subscribe(filter, QoS::AtLeastOnce) {
+                            Ok(()) => info!("Subscribed to MQTT commands on {}", filter),
+                            Err(e) => warn!("Failed to subscribe to MQTT commands: {}", e),
+                        }
+                    }
+                }
+                Ok(Event::Incoming(Packet::Publish(publish))) => {
+                    if let Err(e) = commands_tx.try_send((publish.topic, publish.payload.to_vec()))
+                    {
+                        warn!("Dropping MQTT command: {}", e);
+                    }
                 }
                 Ok(Event::Incoming(Packet::PingResp)) => {
                     debug!("MQTT ping response received");
@@ -182,7 +216,7 @@ async fn run_mqtt_publisher(
     if config.homeassistant {
         // Small delay to ensure MQTT connection is established
         tokio::time::sleep(Duration::from_secs(2)).await;
-        let devices = configured_devices(&state).await;
+        let devices = forwarded_devices(&state).await;
         if let Err(e) = publish_ha_discovery(&client, &config, &devices, qos).await {
             warn!("Failed to publish HA discovery: {}", e);
         }
@@ -194,6 +228,9 @@ async fn run_mqtt_publisher(
             result = readings_rx.recv() => {
                 match result {
                     Ok(event) => {
+                        if !state.device_privacy(&event.device_id).await.is_forwarded() {
+                            continue;
+                        }
                         if let Err(e) = publish_reading(&client, &config, &state, &event, qos).await {
                             warn!("Failed to publish reading: {}", e);
                         }
@@ -207,9 +244,12 @@ async fn run_mqtt_publisher(
                     }
                 }
             }
+            Some((topic, payload)) = commands_rx.recv() => {
+                handle_command(&state, &config, &topic, &payload).await;
+            }
             result = reload_rx.changed() => {
                 if result.is_ok() && config.homeassistant {
-                    let devices = configured_devices(&state).await;
+                    let devices = forwarded_devices(&state).await;
                     if let Err(e) = publish_ha_discovery(&client, &config, &devices, qos).await {
                         warn!("Failed to refresh HA discovery after config reload: {}", e);
                     }
@@ -383,6 +423,19 @@ async fn publish_reading(
             .await?;
     }
 
+    // Computed channels
+    for channel in &event.channels {
+        let topic = format!("{}/{}/{}", prefix, device_name, channel.channel);
+        client
+            .publish(
+                &topic,
+                qos,
+                retain,
+                format!("{:.2}", channel.value).as_bytes(),
+            )
+            .await?;
+    }
+
     debug!(
         "Published reading for {} to MQTT (CO2={})",
         event.device_id, reading.co2
@@ -391,11 +444,105 @@ async fn publish_reading(
     Ok(())
 }
 
-async fn configured_devices(state: &AppState) -> Vec<crate::config::DeviceConfig> {
+/// Queue the settings change requested by an incoming command message.
+async fn handle_command(state: &AppState, config: &MqttConfig, topic: &str, payload: &[u8]) {
+    let Some((device, setting)) = parse_command_topic(&config.topic_prefix, topic) else {
+        debug!("Ignoring MQTT message on {}", topic);
+        return;
+    };
+    let devices = configured_devices(state).await;
+    let Some(address) = resolve_command_device(&devices, device) else {
+        warn!("Ignoring MQTT command for unknown device '{}'", device);
+        return;
+    };
+
+    let payload = String::from_utf8_lossy(payload);
+    match parse_command(setting, payload.trim()) {
+        Ok(patch) => {
+            let job = state.settings_jobs.lock().await.enqueue(&address, patch);
+            info!(
+                "Queued settings job {} for {} from MQTT ({} = {})",
+                job.id,
+                address,
+                setting,
+                payload.trim()
+            );
+        }
+        Err(e) => warn!("Ignoring MQTT command on {}: {}", topic, e),
+    }
+}
+
+/// Split a command topic `{prefix}/{device}/set/{setting}` into device and setting.
+fn parse_command_topic<'a>(prefix: &str, topic: &'a str) -> Option<(&'a str, &'a str)> {
+    let rest = topic.strip_prefix(prefix)?.strip_prefix('/')?;
+    let mut parts = rest.split('/');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(device), Some("set"), Some(setting), None) if !device.is_empty() => {
+            Some((device, setting))
+        }
+        _ => None,
+    }
+}
+
+/// Find the configured device a topic segment refers to.
+///
+/// Matches the segment used in published topics (the sanitized alias or
+/// address), or the address itself.
+fn resolve_command_device(devices: &[DeviceConfig], segment: &str) -> Option<String> {
+    devices
+        .iter()
+        .find(|device| {
+            sanitize_topic_segment(device.alias.as_deref().unwrap_or(&device.address)) == segment
+                || device.address.eq_ignore_ascii_case(segment)
+        })
+        .map(|device| device.address.clone())
+}
+
+/// Parse a command payload into a settings patch.
+///
+/// Validation is shared with the REST settings endpoint.
+fn parse_command(setting: &str, payload: &str) -> Result<SettingsPatch, String> {
+    let mut request = DeviceSettingsPatchRequest {
+        interval: None,
+        smart_home: None,
+        bluetooth_range: None,
+    };
+    match setting {
+        "interval" => {
+            let secs = payload
+                .parse()
+                .map_err(|_| format!("invalid interval '{payload}': expected seconds"))?;
+            request.interval = Some(secs);
+        }
+        "smart_home" => {
+            request.smart_home = Some(match payload.to_lowercase().as_str() {
+                "on" | "true" | "1" => true,
+                "off" | "false" | "0" => false,
+                _ => {
+                    return Err(format!(
+                        "invalid smart_home '{payload}': expected ON or OFF"
+                    ));
+                }
+            });
+        }
+        "range" => request.bluetooth_range = Some(payload.to_string()),
+        _ => return Err(format!("unknown setting '{setting}'")),
+    }
+    request.into_patch().map_err(|e| e.to_string())
+}
+
+async fn configured_devices(state: &AppState) -> Vec<DeviceConfig> {
     let config = state.config.read().await;
     config.devices.clone()
 }
 
+/// Configured devices whose readings may be published.
+async fn forwarded_devices(state: &AppState) -> Vec<DeviceConfig> {
+    let mut devices = configured_devices(state).await;
+    devices.retain(|device| device.privacy.is_forwarded());
+    devices
+}
+
 async fn configured_device_name(state: &AppState, device_id: &str) -> Option<String> {
     let config = state.config.read().await;
     config
@@ -414,7 +561,7 @@ async fn configured_device_name(state: &AppState, device_id: &str) -> Option<Str
 async fn publish_ha_discovery(
     client: &AsyncClient,
     config: &MqttConfig,
-    devices: &[crate::config::DeviceConfig],
+    devices: &[DeviceConfig],
     qos: QoS,
 ) -> Result<(), rumqttc::ClientError> {
     let prefix = &config.ha_discovery_prefix;
@@ -473,6 +620,32 @@ async fn publish_ha_discovery(
             (config_topic, payload.to_string())
         };
 
+        // Helper to build a control discovery message for a command topic
+        let publish_control =
+            |component: &str, setting: &str, name_suffix: &str, options: Option<&[&str]>| {
+                let unique_id = format!("aranet_{}_{}", device_name, setting);
+                let command_topic = format!("{}/{}/set/{}", topic_prefix, device_name, setting);
+                let config_topic = format!(
+                    "{}/{}/{}_{}/config",
+                    prefix, component, device_name, setting
+                );
+
+                // No state topic: settings are not published, so HA tracks them optimistically
+                let mut payload = serde_json::json!({
+                    "name": format!("{} {}", display_name, name_suffix),
+                    "unique_id": unique_id,
+                    "command_topic": command_topic,
+                    "optimistic": true,
+                    "entity_category": "config",
+                    "device": device_json,
+                });
+                if let Some(options) = options {
+                    payload["options"] = serde_json::json!(options);
+                }
+
+                (config_topic, payload.to_string())
+            };
+
         // Define sensors to register
         let mut sensors = vec![];
 
@@ -521,6 +694,22 @@ async fn publish_ha_discovery(
             Some("measurement"),
         ));
 
+        if config.commands {
+            sensors.push(publish_control(
+                "select",
+                "interval",
+                "Measurement Interval",
+                Some(&["60", "120", "300", "600"]),
+            ));
+            sensors.push(publish_control("switch", "smart_home", "Smart Home", None));
+            sensors.push(publish_control(
+                "select",
+                "range",
+                "Bluetooth Range",
+                Some(&["standard", "extended"]),
+            ));
+        }
+
         // Publish all discovery messages
         for (config_topic, payload) in sensors {
             client
@@ -628,4 +817,67 @@ mod tests {
         assert_eq!(sanitize_topic_segment("office"), "office");
         assert_eq!(sanitize_topic_segment("kitchen-sensor"), "kitchen-sensor");
     }
+
+    #[test]
+    fn test_parse_command_topic() {
+        assert_eq!(
+            parse_command_topic("home/aranet", "home/aranet/office/set/interval"),
+            Some(("office", "interval"))
+        );
+        assert_eq!(parse_command_topic("aranet", "aranet/office/co2"), None);
+        assert_eq!(parse_command_topic("aranet", "aranet/office/set/a/b"), None);
+        assert_eq!(
+            parse_command_topic("aranet", "aranetx/office/set/range"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_resolve_command_device() {
+        let devices = vec![
+            DeviceConfig {
+                address: "AA:BB:CC:DD:EE:FF".to_string(),
+                alias: Some("Living Room".to_string()),
+                poll_interval: 60,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
+            },
+            DeviceConfig {
+                address: "11:22:33:44:55:66".to_string(),
+                alias: None,
+                poll_interval: 60,
+                privacy: Default::default(),
+                min_history_sync_battery: None,
+            },
+        ];
+        assert_eq!(
+            resolve_command_device(&devices, "Living_Room").as_deref(),
+            Some("AA:BB:CC:DD:EE:FF")
+        );
+        assert_eq!(
+            resolve_command_device(&devices, "11:22:33:44:55:66").as_deref(),
+            Some("11:22:33:44:55:66")
+        );
+        assert_eq!(resolve_command_device(&devices, "kitchen"), None);
+    }
+
+    #[test]
+    fn test_parse_command() {
+        use aranet_core::settings::{BluetoothRange, MeasurementInterval};
+
+        let patch = parse_command("interval", "300").unwrap();
+        assert_eq!(patch.interval, Some(MeasurementInterval::FiveMinutes));
+        assert_eq!(
+            parse_command("smart_home", "ON").unwrap().smart_home,
+            Some(true)
+        );
+        assert_eq!(
+            parse_command("range", "extended").unwrap().bluetooth_range,
+            Some(BluetoothRange::Extended)
+        );
+
+        assert!(parse_command("interval", "90").is_err());
+        assert!(parse_command("smart_home", "maybe").is_err());
+        assert!(parse_command("buzzer", "on").is_err());
+    }
 }
@@ -0,0 +1,441 @@
+//! OpenID Connect bearer token validation.
+//!
+//! When `[security.oidc]` is enabled, clients can authenticate with a JWT
+//! issued by an OIDC provider (Keycloak, Authentik, Auth0, ...) instead of
+//! the shared API key. Tokens are verified against the issuer's signing keys,
+//! which are fetched from its JWKS endpoint and cached.
+//!
+//! Roles from a configurable claim are mapped to a [`Scope`]: read-only
+//! access to readings and status, or admin access that also allows changing
+//! configuration and controlling devices.
+//!
+//! # Example Configuration
+//!
+//! ```toml
+//! [security.oidc]
+//! enabled = true
+//! issuer = "https://auth.example.com/realms/home"
+//! audience = "aranet"
+//! role_claim = "realm_access.roles"
+//! read_roles = ["aranet-viewer"]
+//! admin_roles = ["aranet-admin"]
+//! ```
+
+use std::time::{Duration, Instant};
+
+use axum::http::Method;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use crate::config::OidcConfig;
+
+/// Minimum time between JWKS fetches.
+///
+/// Tokens with an unknown key ID trigger a refresh (the issuer may have
+/// rotated its keys), so this keeps forged tokens from hammering the issuer.
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Access granted to an authenticated client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Scope {
+    /// Read readings, history, status, and configuration.
+    Read,
+    /// Everything, including configuration changes and device control.
+    Admin,
+}
+
+impl Scope {
+    /// The scope needed to make a request with the given method.
+    ///
+    /// Safe methods only read state; anything else changes it.
+    pub fn required_for(method: &Method) -> Self {
+        if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+            Scope::Read
+        } else {
+            Scope::Admin
+        }
+    }
+}
+
+/// Errors that can occur when validating a bearer token.
+#[derive(Debug, thiserror::Error)]
+pub enum OidcError {
+    #[error("Invalid token: {0}")]
+    InvalidToken(#[from] jsonwebtoken::errors::Error),
+    #[error("Unsupported token algorithm {0:?}")]
+    UnsupportedAlgorithm(Algorithm),
+    #[error("No signing key matches key ID {0:?}")]
+    UnknownKey(Option<String>),
+    #[error("Signing keys are unavailable")]
+    KeysUnavailable,
+    #[error("Token has none of the configured roles")]
+    NoRole,
+    #[error("Failed to fetch signing keys: {0}")]
+    Fetch(#[from] reqwest::Error),
+}
+
+/// The parts of the OIDC discovery document we need.
+#[derive(Debug, Deserialize)]
+struct DiscoveryDocument {
+    jwks_uri: String,
+}
+
+/// Cached signing keys.
+#[derive(Debug, Default)]
+struct KeyCache {
+    jwks: Option<JwkSet>,
+    fetched_at: Option<Instant>,
+    last_attempt: Option<Instant>,
+}
+
+/// Validates bearer tokens against an OIDC issuer.
+#[derive(Debug)]
+pub struct OidcValidator {
+    config: OidcConfig,
+    client: Client,
+    keys: RwLock<KeyCache>,
+}
+
+impl OidcValidator {
+    /// Create a validator. Signing keys are fetched on first use.
+    pub fn new(config: OidcConfig) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self {
+            config,
+            client,
+            keys: RwLock::new(KeyCache::default()),
+        }
+    }
+
+    /// Validate a token and return the scope it grants.
+    pub async fn validate(&self, token: &str) -> Result<Scope, OidcError> {
+        let header = decode_header(token)?;
+        // Symmetric algorithms would let anyone holding the (public) JWKS
+        // forge tokens, so only accept signatures made with a private key
+        if !matches!(
+            header.alg,
+            Algorithm::RS256
+                | Algorithm::RS384
+                | Algorithm::RS512
+                | Algorithm::PS256
+                | Algorithm::PS384
+                | Algorithm::PS512
+                | Algorithm::ES256
+                | Algorithm::ES384
+                | Algorithm::EdDSA
+        ) {
+            return Err(OidcError::UnsupportedAlgorithm(header.alg));
+        }
+
+        let jwk = self.signing_key(header.kid.as_deref()).await?;
+        let key = DecodingKey::from_jwk(&jwk)?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.config.issuer]);
+        match &self.config.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        let claims = decode::<serde_json::Value>(token, &key, &validation)?.claims;
+        self.scope_for(&claims).ok_or(OidcError::NoRole)
+    }
+
+    /// Map the roles in a token's claims to a scope.
+    fn scope_for(&self, claims: &serde_json::Value) -> Option<Scope> {
+        let roles = claim_values(claims, &self.config.role_claim);
+        let has_any = |wanted: &[String]| roles.iter().any(|role| wanted.iter().any(|w| w == role));
+
+        if has_any(&self.config.admin_roles) {
+            Some(Scope::Admin)
+        } else if self.config.read_roles.is_empty() || has_any(&self.config.read_roles) {
+            Some(Scope::Read)
+        } else {
+            None
+        }
+    }
+
+    /// Find the key a token was signed with, refreshing the JWKS if needed.
+    async fn signing_key(&self, kid: Option<&str>) -> Result<Jwk, OidcError> {
+        let ttl = Duration::from_secs(self.config.jwks_cache_secs);
+
+        {
+            let cache = self.keys.read().await;
+            if let Some(jwks) = &cache.jwks
+                && cache.fetched_at.is_some_and(|t| t.elapsed() < ttl)
+                && let Some(jwk) = select_key(jwks, kid)
+            {
+                return Ok(jwk.clone());
+            }
+        }
+
+        let mut cache = self.keys.write().await;
+        let expired = cache.fetched_at.is_none_or(|t| t.elapsed() >= ttl);
+        let missing = cache
+            .jwks
+            .as_ref()
+            .is_none_or(|jwks| select_key(jwks, kid).is_none());
+        let may_fetch = cache
+            .last_attempt
+            .is_none_or(|t| t.elapsed() >= MIN_REFRESH_INTERVAL);
+
+        if (expired || missing) && may_fetch {
+            cache.last_attempt = Some(Instant::now());
+            match self.fetch_jwks().await {
+                Ok(jwks) => {
+                    debug!("Fetched {} OIDC signing keys", jwks.keys.len());
+                    cache.jwks = Some(jwks);
+                    cache.fetched_at = Some(Instant::now());
+                }
+                // Keep using the old keys if the issuer is briefly unreachable
+                Err(e) if cache.jwks.is_some() => {
+                    warn!("Failed to refresh OIDC signing keys, using cached keys: {e}");
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let jwks = cache.jwks.as_ref().ok_or(OidcError::KeysUnavailable)?;
+        select_key(jwks, kid)
+            .cloned()
+            .ok_or_else(|| OidcError::UnknownKey(kid.map(str::to_string)))
+    }
+
+    /// Fetch the issuer's JWKS, discovering its URL if not configured.
+    async fn fetch_jwks(&self) -> Result<JwkSet, OidcError> {
+        let jwks_url = match &self.config.jwks_url {
+            Some(url) => url.clone(),
+            None => {
+                let discovery_url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.config.issuer.trim_end_matches('/')
+                );
+                self.client
+                    .get(discovery_url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<DiscoveryDocument>()
+                    .await?
+                    .jwks_uri
+            }
+        };
+
+        Ok(self
+            .client
+            .get(jwks_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<JwkSet>()
+            .await?)
+    }
+
+    /// Create a validator with preloaded keys, skipping the JWKS fetch.
+    #[cfg(test)]
+    pub(crate) fn with_keys(config: OidcConfig, jwks: JwkSet) -> Self {
+        let validator = Self::new(config);
+        let now = Instant::now();
+        *validator.keys.try_write().unwrap() = KeyCache {
+            jwks: Some(jwks),
+            fetched_at: Some(now),
+            last_attempt: Some(now),
+        };
+        validator
+    }
+}
+
+/// Pick the key matching a token's key ID.
+///
+/// Tokens without a key ID are accepted only when the issuer has a single key.
+fn select_key<'a>(jwks: &'a JwkSet, kid: Option<&str>) -> Option<&'a Jwk> {
+    match kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+}
+
+/// Read the string values of a claim given as a dotted path.
+///
+/// The claim may be an array of strings or a single space-separated string
+/// (as with the standard `scope` claim).
+fn claim_values<'a>(claims: &'a serde_json::Value, path: &str) -> Vec<&'a str> {
+    let value = path
+        .split('.')
+        .try_fold(claims, |value, key| value.get(key));
+
+    match value {
+        Some(serde_json::Value::Array(items)) => {
+            items.iter().filter_map(|item| item.as_str()).collect()
+        }
+        Some(serde_json::Value::String(s)) => s.split_whitespace().collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use jsonwebtoken::{EncodingKey, Header, encode};
+    use serde_json::json;
+
+    const ISSUER: &str = "https://auth.example.com/realms/home";
+
+    /// PKCS#8 Ed25519 key used to sign test tokens.
+    const TEST_KEY_PKCS8: &str = "302e020100300506032b6570042204202d96ee3108b10a13e661d21979278fa2205787ab3c2366d3a561511928f6518e";
+    /// Public half of [`TEST_KEY_PKCS8`], base64url encoded.
+    const TEST_KEY_X: &str = "5zdCwi2JbEFUn0ytKwxNgty9-RujqLXNYfa0UlFZUxQ";
+
+    pub(crate) fn test_oidc_config() -> OidcConfig {
+        OidcConfig {
+            enabled: true,
+            issuer: ISSUER.to_string(),
+            audience: Some("aranet".to_string()),
+            read_roles: vec!["viewer".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn test_jwks(kid: &str) -> JwkSet {
+        serde_json::from_value(json!({
+            "keys": [{
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "x": TEST_KEY_X,
+                "kid": kid,
+                "alg": "EdDSA"
+            }]
+        }))
+        .unwrap()
+    }
+
+    pub(crate) fn test_validator(config: OidcConfig) -> OidcValidator {
+        OidcValidator::with_keys(config, test_jwks("test-key"))
+    }
+
+    /// Sign a token with the test key, filling in valid standard claims.
+    pub(crate) fn test_token(roles: &[&str], overrides: serde_json::Value) -> String {
+        let der: Vec<u8> = (0..TEST_KEY_PKCS8.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&TEST_KEY_PKCS8[i..i + 2], 16).unwrap())
+            .collect();
+
+        let mut claims = json!({
+            "iss": ISSUER,
+            "aud": "aranet",
+            "sub": "user-1",
+            "exp": time::OffsetDateTime::now_utc().unix_timestamp() + 300,
+            "roles": roles,
+        });
+        if let (Some(claims), serde_json::Value::Object(overrides)) =
+            (claims.as_object_mut(), overrides)
+        {
+            claims.extend(overrides);
+        }
+
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some("test-key".to_string());
+        encode(&header, &claims, &EncodingKey::from_ed_der(&der)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_roles_map_to_scopes() {
+        let validator = test_validator(test_oidc_config());
+
+        let token = test_token(&["admin"], json!({}));
+        assert_eq!(validator.validate(&token).await.unwrap(), Scope::Admin);
+
+        let token = test_token(&["viewer"], json!({}));
+        assert_eq!(validator.validate(&token).await.unwrap(), Scope::Read);
+
+        let token = test_token(&["guest"], json!({}));
+        assert!(matches!(
+            validator.validate(&token).await,
+            Err(OidcError::NoRole)
+        ));
+
+        // With no read roles configured, any valid token can read
+        let validator = test_validator(OidcConfig {
+            read_roles: Vec::new(),
+            ..test_oidc_config()
+        });
+        assert_eq!(validator.validate(&token).await.unwrap(), Scope::Read);
+    }
+
+    #[tokio::test]
+    async fn test_nested_and_space_separated_role_claims() {
+        let validator = test_validator(OidcConfig {
+            role_claim: "realm_access.roles".to_string(),
+            ..test_oidc_config()
+        });
+        let token = test_token(&[], json!({ "realm_access": { "roles": ["admin"] } }));
+        assert_eq!(validator.validate(&token).await.unwrap(), Scope::Admin);
+
+        let validator = test_validator(OidcConfig {
+            role_claim: "scope".to_string(),
+            ..test_oidc_config()
+        });
+        let token = test_token(&[], json!({ "scope": "openid viewer" }));
+        assert_eq!(validator.validate(&token).await.unwrap(), Scope::Read);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_tokens() {
+        let validator = test_validator(test_oidc_config());
+
+        let wrong_issuer = test_token(&["admin"], json!({ "iss": "https://evil.example.com" }));
+        assert!(validator.validate(&wrong_issuer).await.is_err());
+
+        let wrong_audience = test_token(&["admin"], json!({ "aud": "other-app" }));
+        assert!(validator.validate(&wrong_audience).await.is_err());
+
+        let expired = test_token(&["admin"], json!({ "exp": 1_000_000 }));
+        assert!(validator.validate(&expired).await.is_err());
+
+        // Flip a byte in the signature
+        let mut tampered = test_token(&["admin"], json!({}));
+        let last = tampered.pop().unwrap();
+        tampered.push(if last == 'A' { 'B' } else { 'A' });
+        assert!(validator.validate(&tampered).await.is_err());
+
+        // Keys were just loaded, so an unknown key ID does not trigger a fetch
+        let rotated = OidcValidator::with_keys(test_oidc_config(), test_jwks("old-key"));
+        let token = test_token(&["admin"], json!({}));
+        assert!(matches!(
+            rotated.validate(&token).await,
+            Err(OidcError::UnknownKey(Some(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_symmetric_algorithms() {
+        let validator = test_validator(test_oidc_config());
+        let token = encode(
+            &Header::new(Algorithm::HS256),
+            &json!({ "iss": ISSUER, "aud": "aranet", "exp": 4_000_000_000u64, "roles": ["admin"] }),
+            &EncodingKey::from_secret(TEST_KEY_X.as_bytes()),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            validator.validate(&token).await,
+            Err(OidcError::UnsupportedAlgorithm(Algorithm::HS256))
+        ));
+    }
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(Scope::required_for(&Method::GET), Scope::Read);
+        assert_eq!(Scope::required_for(&Method::PUT), Scope::Admin);
+        assert_eq!(Scope::required_for(&Method::DELETE), Scope::Admin);
+        assert!(Scope::Admin > Scope::Read);
+    }
+}
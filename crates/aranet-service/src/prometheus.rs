@@ -18,6 +18,13 @@
 //! - `aranet_collector_running`
 //! - `aranet_collector_uptime_seconds`
 //! - `aranet_ws_messages_dropped_total`
+//! - `aranet_ws_clients`
+//! - `aranet_collector_ble_permits_available`
+//! - `aranet_collector_sync_jobs_queued`
+//! - `aranet_collector_settings_jobs_queued`
+//! - `aranet_device_connect_attempts_total`
+//! - `aranet_device_poll_errors_total`
+//! - `aranet_device_poll_latency_seconds`
 //! - `aranet_device_poll_success_total`
 //! - `aranet_device_poll_failure_total`
 //! - `aranet_device_poll_duration_ms`
@@ -30,6 +37,7 @@
 //! - `aranet_radon_bqm3`
 //! - `aranet_radiation_rate_usvh`
 //! - `aranet_radiation_total_msv`
+//! - `aranet_channel_value`
 
 use std::sync::Arc;
 use std::time::Duration;
@@ -38,8 +46,8 @@ use reqwest::Client;
 use time::OffsetDateTime;
 use tracing::{debug, info, warn};
 
-use crate::config::PrometheusConfig;
-use crate::state::AppState;
+use crate::config::{DevicePrivacy, PrometheusConfig};
+use crate::state::{AppState, POLL_LATENCY_BUCKETS};
 
 /// Prometheus push gateway client.
 pub struct PrometheusPusher {
@@ -134,8 +142,16 @@ async fn run_prometheus_pusher(
 }
 
 /// Generate metrics in Prometheus text format.
+///
+/// Devices whose readings are not forwarded are left out.
 async fn generate_metrics(state: &AppState) -> String {
     let mut output = String::with_capacity(4096);
+    let withheld = state
+        .config
+        .read()
+        .await
+        .devices_without(DevicePrivacy::is_forwarded);
+    let forwarded = |device_id: &str| !withheld.iter().any(|d| d.eq_ignore_ascii_case(device_id));
 
     // Collector status metrics
     let running = state.collector.is_running();
@@ -170,8 +186,43 @@ async fn generate_metrics(state: &AppState) -> String {
     output.push_str("# TYPE aranet_ws_messages_dropped_total counter\n");
     output.push_str(&format!("aranet_ws_messages_dropped_total {}\n", dropped));
 
+    let ws_clients = state.ws_clients.load(std::sync::atomic::Ordering::Relaxed);
+    output.push_str("# HELP aranet_ws_clients Connected WebSocket clients\n");
+    output.push_str("# TYPE aranet_ws_clients gauge\n");
+    output.push_str(&format!("aranet_ws_clients {}\n", ws_clients));
+
+    // Queue depths
+    output.push_str(
+        "# HELP aranet_collector_ble_permits_available BLE connection slots not in use\n",
+    );
+    output.push_str("# TYPE aranet_collector_ble_permits_available gauge\n");
+    output.push_str(&format!(
+        "aranet_collector_ble_permits_available {}\n",
+        state.ble_semaphore.available_permits()
+    ));
+    let sync_jobs = state.sync_jobs.lock().await.unfinished_count();
+    output.push_str("# HELP aranet_collector_sync_jobs_queued History syncs pending or running\n");
+    output.push_str("# TYPE aranet_collector_sync_jobs_queued gauge\n");
+    output.push_str(&format!(
+        "aranet_collector_sync_jobs_queued {}\n",
+        sync_jobs
+    ));
+    let settings_jobs = state.settings_jobs.lock().await.pending_count();
+    output.push_str(
+        "# HELP aranet_collector_settings_jobs_queued Settings changes waiting for a connection\n",
+    );
+    output.push_str("# TYPE aranet_collector_settings_jobs_queued gauge\n");
+    output.push_str(&format!(
+        "aranet_collector_settings_jobs_queued {}\n",
+        settings_jobs
+    ));
+
     // Device collection stats
-    let device_stats = state.collector.device_stats.read().await;
+    let all_stats = state.collector.device_stats.read().await;
+    let device_stats: Vec<_> = all_stats
+        .iter()
+        .filter(|stat| forwarded(&stat.device_id))
+        .collect();
     if !device_stats.is_empty() {
         output.push_str("# HELP aranet_device_poll_success_total Successful polls\n");
         output.push_str("# TYPE aranet_device_poll_success_total counter\n");
@@ -213,14 +264,79 @@ async fn generate_metrics(state: &AppState) -> String {
             }
         }
     }
+    // Collector internals per device
+    let metrics = state.collector.metrics.lock().await;
+    let polled: Vec<_> = device_stats
+        .iter()
+        .filter_map(|stat| {
+            let alias = stat.alias.as_deref().unwrap_or(&stat.device_id);
+            let labels = format!(
+                "device=\"{}\",address=\"{}\"",
+                escape_label_value(alias),
+                escape_label_value(&stat.device_id)
+            );
+            metrics.get(&stat.device_id).map(|m| (labels, m))
+        })
+        .collect();
+    if !polled.is_empty() {
+        output.push_str(
+            "# HELP aranet_device_connect_attempts_total BLE connection attempts, including history syncs\n",
+        );
+        output.push_str("# TYPE aranet_device_connect_attempts_total counter\n");
+        for (labels, m) in &polled {
+            output.push_str(&format!(
+                "aranet_device_connect_attempts_total{{{}}} {}\n",
+                labels, m.connect_attempts
+            ));
+        }
+
+        output.push_str("# HELP aranet_device_poll_errors_total Failed polls by error category\n");
+        output.push_str("# TYPE aranet_device_poll_errors_total counter\n");
+        for (labels, m) in &polled {
+            for (category, count) in &m.failures {
+                output.push_str(&format!(
+                    "aranet_device_poll_errors_total{{{},category=\"{}\"}} {}\n",
+                    labels, category, count
+                ));
+            }
+        }
+
+        output.push_str("# HELP aranet_device_poll_latency_seconds Poll duration in seconds\n");
+        output.push_str("# TYPE aranet_device_poll_latency_seconds histogram\n");
+        for (labels, m) in &polled {
+            let latency = &m.poll_latency;
+            for (bound, count) in POLL_LATENCY_BUCKETS.iter().zip(latency.buckets) {
+                output.push_str(&format!(
+                    "aranet_device_poll_latency_seconds_bucket{{{},le=\"{}\"}} {}\n",
+                    labels, bound, count
+                ));
+            }
+            output.push_str(&format!(
+                "aranet_device_poll_latency_seconds_bucket{{{},le=\"+Inf\"}} {}\n",
+                labels, latency.count
+            ));
+            output.push_str(&format!(
+                "aranet_device_poll_latency_seconds_sum{{{}}} {:.3}\n",
+                labels, latency.sum
+            ));
+            output.push_str(&format!(
+                "aranet_device_poll_latency_seconds_count{{{}}} {}\n",
+                labels, latency.count
+            ));
+        }
+    }
+    drop(polled);
+    drop(metrics);
     drop(device_stats);
+    drop(all_stats);
 
     // Get latest readings for all devices in a single pass
     // This avoids O(N²) behavior from calling get_latest_reading for each metric type
-    let device_readings = state
+    let mut device_readings = state
         .with_store_read(|store| store.list_latest_readings())
         .await
         .unwrap_or_default();
+    device_readings.retain(|(device, _)| forwarded(&device.id));
 
     if !device_readings.is_empty() {
         // Build per-device metrics, filtering by device capabilities
@@ -361,6 +477,24 @@ async fn generate_metrics(state: &AppState) -> String {
         }
     }
 
+    let mut channel_values = state
+        .with_store_read(|store| store.latest_channel_values(None))
+        .await
+        .unwrap_or_default();
+    channel_values.retain(|value| forwarded(&value.device_id));
+    if !channel_values.is_empty() {
+        output.push_str("# HELP aranet_channel_value Latest value of a computed channel\n");
+        output.push_str("# TYPE aranet_channel_value gauge\n");
+        for value in &channel_values {
+            output.push_str(&format!(
+                "aranet_channel_value{{address=\"{}\",channel=\"{}\"}} {:.2}\n",
+                escape_label_value(&value.device_id),
+                escape_label_value(&value.channel),
+                value.value
+            ));
+        }
+    }
+
     output
 }
 
@@ -553,6 +687,10 @@ mod tests {
                 success_count: 1,
                 failure_count: 0,
                 polling: false,
+                clock_drift_ppm: None,
+                history_overwrite_at: None,
+                history_sync_deferred_below: None,
+                backoff: None,
             });
         }
 
@@ -566,4 +704,61 @@ mod tests {
             "Poll duration should contain the value"
         );
     }
+
+    /// Verify that collector internals are emitted per device.
+    #[tokio::test]
+    async fn test_collector_internals_emitted() {
+        let store = Store::open_in_memory().unwrap();
+        let state = AppState::new(store, Config::default());
+
+        state
+            .collector
+            .device_stats
+            .write()
+            .await
+            .push(crate::state::DeviceCollectionStats {
+                device_id: "test-device".to_string(),
+                alias: Some("Test".to_string()),
+                poll_interval: 60,
+                last_poll_at: None,
+                last_error_at: None,
+                last_error: None,
+                last_poll_duration_ms: None,
+                success_count: 1,
+                failure_count: 1,
+                polling: true,
+                clock_drift_ppm: None,
+                history_overwrite_at: None,
+                history_sync_deferred_below: None,
+                backoff: None,
+            });
+        {
+            let mut metrics = state.collector.metrics.lock().await;
+            metrics.record_connect_attempt("test-device");
+            metrics.record_connect_attempt("test-device");
+            metrics.record_poll("test-device", Duration::from_millis(1500), None);
+            metrics.record_poll(
+                "test-device",
+                Duration::from_secs(30),
+                Some(aranet_core::ErrorCategory::Timeout),
+            );
+        }
+
+        let metrics = generate_metrics(&state).await;
+        let labels = "device=\"Test\",address=\"test-device\"";
+        for line in [
+            format!("aranet_device_connect_attempts_total{{{labels}}} 2"),
+            format!("aranet_device_poll_errors_total{{{labels},category=\"timeout\"}} 1"),
+            format!("aranet_device_poll_latency_seconds_bucket{{{labels},le=\"1\"}} 0"),
+            format!("aranet_device_poll_latency_seconds_bucket{{{labels},le=\"2.5\"}} 1"),
+            format!("aranet_device_poll_latency_seconds_bucket{{{labels},le=\"30\"}} 2"),
+            format!("aranet_device_poll_latency_seconds_bucket{{{labels},le=\"+Inf\"}} 2"),
+            format!("aranet_device_poll_latency_seconds_sum{{{labels}}} 31.500"),
+            format!("aranet_device_poll_latency_seconds_count{{{labels}}} 2"),
+            "aranet_ws_clients 0".to_string(),
+            "aranet_collector_sync_jobs_queued 0".to_string(),
+        ] {
+            assert!(metrics.contains(&line), "missing {line}");
+        }
+    }
 }
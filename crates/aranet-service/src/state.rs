@@ -23,22 +23,27 @@
 //! broadcast_buffer = 200  # Larger buffer for slow clients
 //! ```
 
+use std::collections::{BTreeMap, VecDeque};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 
+use aranet_core::ErrorCategory;
+use aranet_core::settings::{BluetoothRange, DeviceSettings, MeasurementInterval};
 use aranet_store::Store;
 use time::OffsetDateTime;
 use tokio::sync::{Mutex, RwLock, Semaphore, broadcast, watch};
 use tokio::task::{JoinHandle, JoinSet};
 
-use crate::config::{Config, default_config_path};
+use crate::config::{Config, DevicePrivacy, default_config_path};
+use crate::middleware::AccessLog;
+use crate::ws::WsConnections;
 
 /// Shared application state.
 pub struct AppState {
     /// The data store (wrapped in Mutex for thread-safe access).
-    pub store: Mutex<Store>,
+    pub store: Arc<Mutex<Store>>,
     /// File-backed database path for opening parallel read connections.
     ///
     /// In-memory stores leave this as `None` and fall back to the shared mutex.
@@ -49,12 +54,24 @@ pub struct AppState {
     pub config_path: PathBuf,
     /// Broadcast channel for real-time reading updates.
     pub readings_tx: broadcast::Sender<ReadingEvent>,
-    /// Semaphore to serialize BLE adapter access (only one device at a time).
+    /// Limits simultaneous BLE connections on the adapter.
+    ///
+    /// Sized from `collector.max_concurrent_connections` at startup.
     pub ble_semaphore: Semaphore,
     /// Collector control state.
     pub collector: CollectorState,
     /// Total number of broadcast messages dropped due to slow subscribers.
     pub ws_messages_dropped: AtomicU64,
+    /// Number of connected WebSocket clients.
+    pub ws_clients: AtomicU64,
+    /// Registry of connected WebSocket clients, for limits and diagnostics.
+    pub ws_connections: WsConnections,
+    /// JSON access log file, opened at startup when `server.access_log` is set.
+    pub access_log: AccessLog,
+    /// Device settings changes queued for the collector.
+    pub settings_jobs: Mutex<SettingsJobQueue>,
+    /// On-demand history syncs requested through the API.
+    pub sync_jobs: Mutex<SyncJobQueue>,
     /// Global application shutdown signal for background integrations.
     shutdown_tx: watch::Sender<bool>,
     /// Receiver side of the application shutdown signal.
@@ -76,20 +93,31 @@ impl AppState {
         let (readings_tx, _) = broadcast::channel(buffer_size);
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
         let store_path = store.database_path().map(PathBuf::from);
+        let ble_permits = config.collector.max_concurrent_connections.max(1);
         Arc::new(Self {
-            store: Mutex::new(store),
+            store: Arc::new(Mutex::new(store)),
             store_path,
             config: RwLock::new(config),
             config_path,
             readings_tx,
-            ble_semaphore: Semaphore::new(1),
+            ble_semaphore: Semaphore::new(ble_permits),
             collector: CollectorState::new(),
             ws_messages_dropped: AtomicU64::new(0),
+            ws_clients: AtomicU64::new(0),
+            ws_connections: WsConnections::default(),
+            access_log: AccessLog::default(),
+            settings_jobs: Mutex::new(SettingsJobQueue::default()),
+            sync_jobs: Mutex::new(SyncJobQueue::default()),
             shutdown_tx,
             shutdown_rx,
         })
     }
 
+    /// Privacy settings for a device. See [`Config::device_privacy`].
+    pub async fn device_privacy(&self, device_id: &str) -> DevicePrivacy {
+        self.config.read().await.device_privacy(device_id)
+    }
+
     /// Save the current configuration to disk.
     ///
     /// This should be called after any configuration changes made via the API.
@@ -125,6 +153,35 @@ impl AppState {
         f(&store)
     }
 
+    /// Like [`with_store_read`](Self::with_store_read), but runs `f` on the
+    /// blocking thread pool.
+    ///
+    /// Use this for queries that can return a large part of the database, so
+    /// they do not hold a runtime thread while SQLite works.
+    pub async fn with_store_read_blocking<T, F>(&self, f: F) -> aranet_store::Result<T>
+    where
+        F: FnOnce(&Store) -> aranet_store::Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        if let Some(path) = self.store_path.clone() {
+            spawn_store_call(move || f(&Store::open(path)?)).await
+        } else {
+            let store = Arc::clone(&self.store).lock_owned().await;
+            spawn_store_call(move || f(&store)).await
+        }
+    }
+
+    /// Like [`with_store_write`](Self::with_store_write), but runs `f` on the
+    /// blocking thread pool while holding the shared connection.
+    pub async fn with_store_write_blocking<T, F>(&self, f: F) -> aranet_store::Result<T>
+    where
+        F: FnOnce(&Store) -> aranet_store::Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let store = Arc::clone(&self.store).lock_owned().await;
+        spawn_store_call(move || f(&store)).await
+    }
+
     /// Signal that the device configuration has changed.
     ///
     /// Reload notifications are sent even when the collector is temporarily
@@ -161,6 +218,8 @@ pub struct CollectorState {
     reload_rx: watch::Receiver<u64>,
     /// Per-device collection stats.
     pub device_stats: RwLock<Vec<DeviceCollectionStats>>,
+    /// Connection and poll counters exported on `/metrics`.
+    pub metrics: Mutex<CollectorMetrics>,
     /// Shared JoinSet for device polling tasks.
     ///
     /// This allows both the initial collector start and the reload watcher
@@ -183,6 +242,7 @@ impl CollectorState {
             reload_tx,
             reload_rx,
             device_stats: RwLock::new(Vec::new()),
+            metrics: Mutex::new(CollectorMetrics::default()),
             device_tasks: Mutex::new(JoinSet::new()),
             reload_watcher: Mutex::new(None),
         }
@@ -328,6 +388,100 @@ impl Default for CollectorState {
     }
 }
 
+/// Upper bounds in seconds of the poll latency histogram buckets.
+///
+/// A poll connects over BLE, so it takes seconds rather than milliseconds.
+pub const POLL_LATENCY_BUCKETS: [f64; 8] = [0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0, 60.0];
+
+/// Cumulative poll latency histogram, as exported to Prometheus.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencyHistogram {
+    /// Observations at or below each of [`POLL_LATENCY_BUCKETS`].
+    pub buckets: [u64; POLL_LATENCY_BUCKETS.len()],
+    /// Sum of all observations in seconds.
+    pub sum: f64,
+    /// Number of observations.
+    pub count: u64,
+}
+
+impl LatencyHistogram {
+    /// Record one observation.
+    pub fn observe(&mut self, duration: Duration) {
+        let secs = duration.as_secs_f64();
+        for (bucket, bound) in self.buckets.iter_mut().zip(POLL_LATENCY_BUCKETS) {
+            if secs <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += secs;
+        self.count += 1;
+    }
+}
+
+/// Collector internals for one device.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DevicePollMetrics {
+    /// BLE connection attempts, including those made for history syncs.
+    pub connect_attempts: u64,
+    /// Failed polls by error category.
+    pub failures: BTreeMap<&'static str, u64>,
+    /// Duration of polls, successful or not.
+    pub poll_latency: LatencyHistogram,
+}
+
+/// Collector internals since the service started, keyed by device address.
+///
+/// Unlike [`DeviceCollectionStats`], these survive configuration reloads and
+/// are only exported on `/metrics`, so operators can alert on collection
+/// degrading as well as on air quality.
+#[derive(Debug, Clone, Default)]
+pub struct CollectorMetrics {
+    devices: BTreeMap<String, DevicePollMetrics>,
+}
+
+impl CollectorMetrics {
+    /// Count a BLE connection attempt.
+    pub fn record_connect_attempt(&mut self, device_id: &str) {
+        self.entry(device_id).connect_attempts += 1;
+    }
+
+    /// Record a finished poll and, if it failed, why.
+    pub fn record_poll(
+        &mut self,
+        device_id: &str,
+        duration: Duration,
+        failure: Option<ErrorCategory>,
+    ) {
+        let device = self.entry(device_id);
+        device.poll_latency.observe(duration);
+        if let Some(category) = failure {
+            *device.failures.entry(category.as_str()).or_default() += 1;
+        }
+    }
+
+    /// Metrics for a device, if it has been polled.
+    pub fn get(&self, device_id: &str) -> Option<&DevicePollMetrics> {
+        self.devices.get(device_id)
+    }
+
+    fn entry(&mut self, device_id: &str) -> &mut DevicePollMetrics {
+        self.devices.entry(device_id.to_string()).or_default()
+    }
+}
+
+/// Run a store call on the blocking thread pool.
+async fn spawn_store_call<T, F>(f: F) -> aranet_store::Result<T>
+where
+    F: FnOnce() -> aranet_store::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(aranet_store::Error::Cancelled(e.to_string())),
+    }
+}
+
 /// Collection statistics for a single device.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct DeviceCollectionStats {
@@ -353,6 +507,363 @@ pub struct DeviceCollectionStats {
     pub failure_count: u64,
     /// Whether the device is currently being polled.
     pub polling: bool,
+    /// Latest device clock drift estimate in parts per million.
+    pub clock_drift_ppm: Option<f64>,
+    /// When un-synced history records will start being overwritten on the
+    /// device, set while that is close enough to need a sync.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub history_overwrite_at: Option<OffsetDateTime>,
+    /// Battery threshold the last history sync was deferred for, until a
+    /// sync runs again.
+    pub history_sync_deferred_below: Option<u8>,
+    /// Backoff state while the device keeps failing to poll.
+    pub backoff: Option<DeviceBackoff>,
+}
+
+/// Exponential backoff state for a device that keeps failing to poll.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct DeviceBackoff {
+    /// Number of consecutive failed polls.
+    pub consecutive_failures: u32,
+    /// Delay before the next attempt in seconds, including jitter.
+    pub delay_secs: u64,
+    /// When the next poll will be attempted.
+    #[serde(with = "time::serde::rfc3339")]
+    pub next_attempt_at: OffsetDateTime,
+}
+
+/// A partial settings change requested through the API.
+///
+/// `None` fields are left unchanged on the device.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SettingsPatch {
+    /// New measurement interval.
+    pub interval: Option<MeasurementInterval>,
+    /// Enable or disable Smart Home integration.
+    pub smart_home: Option<bool>,
+    /// New Bluetooth range.
+    pub bluetooth_range: Option<BluetoothRange>,
+}
+
+impl SettingsPatch {
+    /// Returns `true` if the patch changes nothing.
+    pub fn is_empty(&self) -> bool {
+        self.interval.is_none() && self.smart_home.is_none() && self.bluetooth_range.is_none()
+    }
+
+    /// Overlay this patch on the device's current settings.
+    pub fn apply_to(&self, settings: &mut DeviceSettings) {
+        if let Some(interval) = self.interval {
+            settings.interval = Some(interval);
+        }
+        if let Some(enabled) = self.smart_home {
+            settings.smart_home_enabled = enabled;
+        }
+        if let Some(range) = self.bluetooth_range {
+            settings.bluetooth_range = range;
+        }
+    }
+}
+
+/// Lifecycle of a queued settings change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingsJobStatus {
+    /// Waiting for the collector's next connection to the device.
+    Pending,
+    /// Written to the device (some changes may have been skipped).
+    Applied,
+    /// The device rejected the change or could not be reached.
+    Failed,
+}
+
+/// A settings change queued for a device.
+#[derive(Debug, Clone)]
+pub struct SettingsJob {
+    /// Job identifier, unique for the lifetime of the service.
+    pub id: u64,
+    /// Address of the target device.
+    pub device_id: String,
+    /// Requested changes.
+    pub patch: SettingsPatch,
+    /// Current status.
+    pub status: SettingsJobStatus,
+    /// When the job was queued.
+    pub created_at: OffsetDateTime,
+    /// When the job finished.
+    pub completed_at: Option<OffsetDateTime>,
+    /// Changes that were written to the device.
+    pub applied: Vec<String>,
+    /// Changes the device does not support, with the reason.
+    pub skipped: Vec<String>,
+    /// Error message if the job failed.
+    pub error: Option<String>,
+}
+
+/// Maximum number of finished jobs kept for status polling.
+const MAX_FINISHED_SETTINGS_JOBS: usize = 100;
+
+/// Queue of settings changes waiting to be applied by the collector.
+///
+/// Finished jobs are kept (up to a limit) so clients can poll the result.
+#[derive(Debug, Default)]
+pub struct SettingsJobQueue {
+    next_id: u64,
+    jobs: VecDeque<SettingsJob>,
+}
+
+impl SettingsJobQueue {
+    /// Queue a settings change for a device and return the new job.
+    pub fn enqueue(&mut self, device_id: &str, patch: SettingsPatch) -> SettingsJob {
+        self.next_id += 1;
+        let job = SettingsJob {
+            id: self.next_id,
+            device_id: device_id.to_string(),
+            patch,
+            status: SettingsJobStatus::Pending,
+            created_at: OffsetDateTime::now_utc(),
+            completed_at: None,
+            applied: Vec::new(),
+            skipped: Vec::new(),
+            error: None,
+        };
+        self.jobs.push_back(job.clone());
+        job
+    }
+
+    /// Look up a job by ID.
+    pub fn get(&self, id: u64) -> Option<&SettingsJob> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+
+    /// Number of jobs waiting to be applied.
+    pub fn pending_count(&self) -> usize {
+        self.jobs
+            .iter()
+            .filter(|job| job.status == SettingsJobStatus::Pending)
+            .count()
+    }
+
+    /// Pending jobs for a device, oldest first.
+    pub fn pending_for(&self, device_id: &str) -> Vec<SettingsJob> {
+        self.jobs
+            .iter()
+            .filter(|job| {
+                job.status == SettingsJobStatus::Pending
+                    && job.device_id.eq_ignore_ascii_case(device_id)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Mark a job as applied.
+    pub fn complete(&mut self, id: u64, applied: Vec<String>, skipped: Vec<String>) {
+        self.finish(id, |job| {
+            job.status = SettingsJobStatus::Applied;
+            job.applied = applied;
+            job.skipped = skipped;
+        });
+    }
+
+    /// Mark a job as failed.
+    pub fn fail(&mut self, id: u64, error: String) {
+        self.finish(id, |job| {
+            job.status = SettingsJobStatus::Failed;
+            job.error = Some(error);
+        });
+    }
+
+    fn finish<F>(&mut self, id: u64, update_fn: F)
+    where
+        F: FnOnce(&mut SettingsJob),
+    {
+        if let Some(job) = self.jobs.iter_mut().find(|job| job.id == id) {
+            update_fn(job);
+            job.completed_at = Some(OffsetDateTime::now_utc());
+        }
+
+        // Drop the oldest finished jobs once over the limit
+        let mut finished = self
+            .jobs
+            .iter()
+            .filter(|job| job.status != SettingsJobStatus::Pending)
+            .count();
+        while finished > MAX_FINISHED_SETTINGS_JOBS {
+            if let Some(pos) = self
+                .jobs
+                .iter()
+                .position(|job| job.status != SettingsJobStatus::Pending)
+            {
+                self.jobs.remove(pos);
+            }
+            finished -= 1;
+        }
+    }
+}
+
+/// Lifecycle of an on-demand history sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncJobStatus {
+    /// Waiting for the Bluetooth adapter.
+    Pending,
+    /// Connected and downloading history.
+    Running,
+    /// New records were downloaded and stored.
+    Completed,
+    /// The device could not be reached or the download failed.
+    Failed,
+}
+
+impl SyncJobStatus {
+    /// Whether the job has completed or failed.
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+}
+
+/// An on-demand history sync for a device.
+#[derive(Debug, Clone)]
+pub struct SyncJob {
+    /// Job identifier, unique for the lifetime of the service.
+    pub id: u64,
+    /// Address of the target device.
+    pub device_id: String,
+    /// Current status.
+    pub status: SyncJobStatus,
+    /// When the job was requested.
+    pub created_at: OffsetDateTime,
+    /// When the download started.
+    pub started_at: Option<OffsetDateTime>,
+    /// When the job finished.
+    pub completed_at: Option<OffsetDateTime>,
+    /// Records to download, once known.
+    pub total_records: Option<u16>,
+    /// Download progress from 0.0 to 1.0.
+    pub progress: f32,
+    /// Records downloaded from the device.
+    pub downloaded: usize,
+    /// Records that were new to the store.
+    pub inserted: usize,
+    /// Error message if the job failed.
+    pub error: Option<String>,
+}
+
+/// Maximum number of finished sync jobs kept for status polling.
+const MAX_FINISHED_SYNC_JOBS: usize = 100;
+
+/// On-demand history syncs, for status polling.
+///
+/// At most one sync runs per device; requesting another while one is pending
+/// or running returns the existing job.
+#[derive(Debug, Default)]
+pub struct SyncJobQueue {
+    next_id: u64,
+    jobs: VecDeque<SyncJob>,
+}
+
+impl SyncJobQueue {
+    /// Create a sync job for a device, or return the unfinished one.
+    ///
+    /// The flag is `true` if a new job was created and needs to be run.
+    pub fn enqueue(&mut self, device_id: &str) -> (SyncJob, bool) {
+        if let Some(job) = self
+            .jobs
+            .iter()
+            .find(|job| !job.status.is_finished() && job.device_id.eq_ignore_ascii_case(device_id))
+        {
+            return (job.clone(), false);
+        }
+
+        self.next_id += 1;
+        let job = SyncJob {
+            id: self.next_id,
+            device_id: device_id.to_string(),
+            status: SyncJobStatus::Pending,
+            created_at: OffsetDateTime::now_utc(),
+            started_at: None,
+            completed_at: None,
+            total_records: None,
+            progress: 0.0,
+            downloaded: 0,
+            inserted: 0,
+            error: None,
+        };
+        self.jobs.push_back(job.clone());
+        (job, true)
+    }
+
+    /// Look up a job by ID.
+    pub fn get(&self, id: u64) -> Option<&SyncJob> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+
+    /// Number of jobs that are pending or running.
+    pub fn unfinished_count(&self) -> usize {
+        self.jobs
+            .iter()
+            .filter(|job| !job.status.is_finished())
+            .count()
+    }
+
+    /// Mark a job as running.
+    pub fn start(&mut self, id: u64) {
+        self.update(id, |job| {
+            job.status = SyncJobStatus::Running;
+            job.started_at = Some(OffsetDateTime::now_utc());
+        });
+    }
+
+    /// Update a job in place, e.g. to record progress.
+    pub fn update<F>(&mut self, id: u64, update_fn: F)
+    where
+        F: FnOnce(&mut SyncJob),
+    {
+        if let Some(job) = self.jobs.iter_mut().find(|job| job.id == id) {
+            update_fn(job);
+        }
+    }
+
+    /// Mark a job as completed.
+    pub fn complete(&mut self, id: u64, downloaded: usize, inserted: usize) {
+        self.finish(id, |job| {
+            job.status = SyncJobStatus::Completed;
+            job.progress = 1.0;
+            job.downloaded = downloaded;
+            job.inserted = inserted;
+        });
+    }
+
+    /// Mark a job as failed.
+    pub fn fail(&mut self, id: u64, error: String) {
+        self.finish(id, |job| {
+            job.status = SyncJobStatus::Failed;
+            job.error = Some(error);
+        });
+    }
+
+    fn finish<F>(&mut self, id: u64, update_fn: F)
+    where
+        F: FnOnce(&mut SyncJob),
+    {
+        self.update(id, |job| {
+            update_fn(job);
+            job.completed_at = Some(OffsetDateTime::now_utc());
+        });
+
+        // Drop the oldest finished jobs once over the limit
+        let mut finished = self
+            .jobs
+            .iter()
+            .filter(|job| job.status.is_finished())
+            .count();
+        while finished > MAX_FINISHED_SYNC_JOBS {
+            if let Some(pos) = self.jobs.iter().position(|job| job.status.is_finished()) {
+                self.jobs.remove(pos);
+            }
+            finished -= 1;
+        }
+    }
 }
 
 /// A reading event for WebSocket broadcast.
@@ -362,6 +873,9 @@ pub struct ReadingEvent {
     pub device_id: String,
     /// The reading data.
     pub reading: aranet_store::StoredReading,
+    /// Computed channel values for the reading.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<crate::channels::ChannelValue>,
 }
 
 #[cfg(test)]
@@ -386,6 +900,8 @@ mod tests {
             radon_avg_24h: None,
             radon_avg_7d: None,
             radon_avg_30d: None,
+            quality: aranet_store::ReadingQuality::Unchecked,
+            counter: None,
             captured_at: time::OffsetDateTime::now_utc(),
         }
     }
@@ -440,6 +956,7 @@ mod tests {
         let event = ReadingEvent {
             device_id: "test".to_string(),
             reading: reading.clone(),
+            channels: Vec::new(),
         };
 
         // Send should succeed (at least one subscriber)
@@ -458,6 +975,7 @@ mod tests {
         let event = ReadingEvent {
             device_id: "AA:BB:CC:DD:EE:FF".to_string(),
             reading,
+            channels: Vec::new(),
         };
 
         let json = serde_json::to_string(&event).unwrap();
@@ -472,6 +990,7 @@ mod tests {
         let event = ReadingEvent {
             device_id: "test".to_string(),
             reading,
+            channels: Vec::new(),
         };
 
         let debug = format!("{:?}", event);
@@ -557,6 +1076,10 @@ mod tests {
                 success_count: 0,
                 failure_count: 0,
                 polling: false,
+                clock_drift_ppm: None,
+                history_overwrite_at: None,
+                history_sync_deferred_below: None,
+                backoff: None,
             });
         }
 
@@ -579,6 +1102,10 @@ mod tests {
             success_count: 42,
             failure_count: 3,
             polling: true,
+            clock_drift_ppm: None,
+            history_overwrite_at: None,
+            history_sync_deferred_below: None,
+            backoff: None,
         };
 
         let json = serde_json::to_string(&stats).unwrap();
@@ -604,6 +1131,10 @@ mod tests {
             success_count: 10,
             failure_count: 5,
             polling: false,
+            clock_drift_ppm: None,
+            history_overwrite_at: None,
+            history_sync_deferred_below: None,
+            backoff: None,
         };
 
         let json = serde_json::to_string(&stats).unwrap();
@@ -623,6 +1154,10 @@ mod tests {
             success_count: 100,
             failure_count: 2,
             polling: true,
+            clock_drift_ppm: None,
+            history_overwrite_at: None,
+            history_sync_deferred_below: None,
+            backoff: None,
         };
 
         let cloned = original.clone();
@@ -647,6 +1182,10 @@ mod tests {
             success_count: 5,
             failure_count: 1,
             polling: false,
+            clock_drift_ppm: None,
+            history_overwrite_at: None,
+            history_sync_deferred_below: None,
+            backoff: None,
         };
 
         let debug = format!("{:?}", stats);
@@ -661,6 +1200,7 @@ mod tests {
         let event = ReadingEvent {
             device_id: "original".to_string(),
             reading,
+            channels: Vec::new(),
         };
 
         let cloned = event.clone();
@@ -698,6 +1238,7 @@ mod tests {
         let event = ReadingEvent {
             device_id: "multi".to_string(),
             reading,
+            channels: Vec::new(),
         };
 
         state.readings_tx.send(event).unwrap();
@@ -729,6 +1270,31 @@ mod tests {
         assert_eq!(device.name, Some("Test".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_app_state_blocking_store_operations() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path().join("data.db")).unwrap();
+        let state = AppState::new(store, Config::default());
+
+        state
+            .with_store_write_blocking(|store| {
+                store.upsert_device("test-device", Some("Test")).map(|_| ())
+            })
+            .await
+            .unwrap();
+
+        // File-backed reads use their own connection on the blocking pool
+        let device = state
+            .with_store_read_blocking(|store| store.get_device("test-device"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(device.name, Some("Test".to_string()));
+
+        // The shared connection is free again afterwards
+        assert!(state.store.try_lock().is_ok());
+    }
+
     #[test]
     fn test_collector_state_reload_signal() {
         let collector = CollectorState::new();
@@ -767,4 +1333,92 @@ mod tests {
         let result = handle.await.unwrap();
         assert_eq!(result, 1);
     }
+
+    #[test]
+    fn test_settings_patch_apply_to() {
+        let mut settings = DeviceSettings::default();
+        let patch = SettingsPatch {
+            interval: Some(MeasurementInterval::TwoMinutes),
+            smart_home: Some(true),
+            bluetooth_range: None,
+        };
+        assert!(!patch.is_empty());
+        assert!(SettingsPatch::default().is_empty());
+
+        patch.apply_to(&mut settings);
+        assert_eq!(settings.interval, Some(MeasurementInterval::TwoMinutes));
+        assert!(settings.smart_home_enabled);
+        assert_eq!(settings.bluetooth_range, BluetoothRange::Standard);
+    }
+
+    #[test]
+    fn test_settings_job_queue_lifecycle() {
+        let mut queue = SettingsJobQueue::default();
+        let patch = SettingsPatch {
+            smart_home: Some(true),
+            ..Default::default()
+        };
+
+        let first = queue.enqueue("AA:BB:CC:DD:EE:FF", patch.clone());
+        let second = queue.enqueue("11:22:33:44:55:66", patch);
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.status, SettingsJobStatus::Pending);
+
+        // Device matching is case-insensitive
+        let pending = queue.pending_for("aa:bb:cc:dd:ee:ff");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, first.id);
+
+        queue.complete(first.id, vec!["smart_home=true".to_string()], Vec::new());
+        queue.fail(second.id, "Failed to connect".to_string());
+
+        let first = queue.get(first.id).unwrap();
+        assert_eq!(first.status, SettingsJobStatus::Applied);
+        assert!(first.completed_at.is_some());
+        assert_eq!(
+            queue.get(second.id).unwrap().error.as_deref(),
+            Some("Failed to connect")
+        );
+        assert!(queue.pending_for("AA:BB:CC:DD:EE:FF").is_empty());
+    }
+
+    #[test]
+    fn test_settings_job_queue_prunes_finished_jobs() {
+        let mut queue = SettingsJobQueue::default();
+        let pending = queue.enqueue("pending", SettingsPatch::default());
+        for _ in 0..MAX_FINISHED_SETTINGS_JOBS + 5 {
+            let job = queue.enqueue("device", SettingsPatch::default());
+            queue.complete(job.id, Vec::new(), Vec::new());
+        }
+
+        assert_eq!(queue.jobs.len(), MAX_FINISHED_SETTINGS_JOBS + 1);
+        assert!(queue.get(pending.id).is_some());
+        // Oldest finished job was dropped
+        assert!(queue.get(pending.id + 1).is_none());
+    }
+
+    #[test]
+    fn test_sync_job_queue_reuses_unfinished_job() {
+        let mut queue = SyncJobQueue::default();
+        let (job, created) = queue.enqueue("AA:BB");
+        assert!(created);
+        assert_eq!(job.status, SyncJobStatus::Pending);
+
+        // A second request while the first is running joins it
+        queue.start(job.id);
+        let (again, created) = queue.enqueue("aa:bb");
+        assert!(!created);
+        assert_eq!(again.id, job.id);
+        assert_eq!(again.status, SyncJobStatus::Running);
+
+        queue.complete(job.id, 10, 7);
+        let done = queue.get(job.id).unwrap();
+        assert_eq!(done.status, SyncJobStatus::Completed);
+        assert_eq!((done.downloaded, done.inserted), (10, 7));
+        assert!(done.completed_at.is_some());
+
+        let (next, created) = queue.enqueue("AA:BB");
+        assert!(created);
+        assert_ne!(next.id, job.id);
+    }
 }
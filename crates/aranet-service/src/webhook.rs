@@ -13,7 +13,7 @@
 //!
 //! [[webhooks.endpoints]]
 //! url = "https://hooks.slack.com/services/T00/B00/xxx"
-//! events = ["co2_high", "radon_high", "battery_low"]
+//! events = ["co2_high", "radon_high", "radiation_high", "battery_low", "clock_drift", "history_overwrite", "history_sync_deferred"]
 //!
 //! [[webhooks.endpoints]]
 //! url = "https://ntfy.sh/my-aranet-alerts"
@@ -143,8 +143,17 @@ async fn run_webhook_dispatcher(
             result = readings_rx.recv() => {
                 match result {
                     Ok(event) => {
+                        if !state.device_privacy(&event.device_id).await.is_forwarded() {
+                            continue;
+                        }
                         let alias = configured_alias(&state, &event.device_id).await;
-                        let alerts = evaluate_thresholds(&config, &event, alias);
+                        let clock_drift = device_clock_drift(&state, &event.device_id).await;
+                        let overwrite_at = device_history_overwrite(&state, &event.device_id).await;
+                        let deferred_below = device_history_sync_deferral(&state, &event.device_id).await;
+                        let mut alerts = evaluate_thresholds(&config, &event, alias.clone());
+                        alerts.extend(evaluate_clock_drift(&config, &event, alias.clone(), clock_drift));
+                        alerts.extend(evaluate_history_overwrite(&event, alias.clone(), overwrite_at));
+                        alerts.extend(evaluate_history_sync_deferred(&event, alias, deferred_below));
                         let now = OffsetDateTime::now_utc();
                         let cooldown_duration = time::Duration::try_from(cooldown)
                             .unwrap_or(time::Duration::seconds(300));
@@ -236,6 +245,102 @@ async fn configured_alias(state: &AppState, device_id: &str) -> Option<String> {
         .and_then(|device| device.alias.clone())
 }
 
+/// Latest clock drift estimate the collector has for a device.
+async fn device_clock_drift(state: &AppState, device_id: &str) -> Option<f64> {
+    let stats = state.collector.device_stats.read().await;
+    stats
+        .iter()
+        .find(|stat| stat.device_id == device_id)
+        .and_then(|stat| stat.clock_drift_ppm)
+}
+
+/// When the collector expects un-synced history on a device to be overwritten.
+async fn device_history_overwrite(state: &AppState, device_id: &str) -> Option<OffsetDateTime> {
+    let stats = state.collector.device_stats.read().await;
+    stats
+        .iter()
+        .find(|stat| stat.device_id == device_id)
+        .and_then(|stat| stat.history_overwrite_at)
+}
+
+/// Battery threshold the collector last deferred a device's history sync for.
+async fn device_history_sync_deferral(state: &AppState, device_id: &str) -> Option<u8> {
+    let stats = state.collector.device_stats.read().await;
+    stats
+        .iter()
+        .find(|stat| stat.device_id == device_id)
+        .and_then(|stat| stat.history_sync_deferred_below)
+}
+
+/// Raise a "history_sync_deferred" alert while a history sync is held back
+/// by a low battery.
+fn evaluate_history_sync_deferred(
+    event: &ReadingEvent,
+    alias: Option<String>,
+    deferred_below: Option<u8>,
+) -> Option<WebhookPayload> {
+    let threshold = deferred_below?;
+
+    Some(WebhookPayload {
+        event: "history_sync_deferred".to_string(),
+        device_id: event.device_id.clone(),
+        alias,
+        value: f64::from(event.reading.battery),
+        threshold: f64::from(threshold),
+        unit: "%".to_string(),
+        reading: event.reading.clone(),
+        timestamp: OffsetDateTime::now_utc(),
+    })
+}
+
+/// Raise a "history_overwrite" alert while un-synced history is at risk.
+///
+/// The value is the number of hours left before records are lost, negative
+/// once they are being overwritten.
+fn evaluate_history_overwrite(
+    event: &ReadingEvent,
+    alias: Option<String>,
+    overwrite_at: Option<OffsetDateTime>,
+) -> Option<WebhookPayload> {
+    let overwrite_at = overwrite_at?;
+    let now = OffsetDateTime::now_utc();
+
+    Some(WebhookPayload {
+        event: "history_overwrite".to_string(),
+        device_id: event.device_id.clone(),
+        alias,
+        value: (overwrite_at - now).as_seconds_f64() / 3600.0,
+        threshold: 0.0,
+        unit: "h".to_string(),
+        reading: event.reading.clone(),
+        timestamp: now,
+    })
+}
+
+/// Raise a "clock_drift" alert if the device clock drifts too far.
+fn evaluate_clock_drift(
+    config: &WebhookConfig,
+    event: &ReadingEvent,
+    alias: Option<String>,
+    clock_drift_ppm: Option<f64>,
+) -> Option<WebhookPayload> {
+    let drift = clock_drift_ppm?;
+    if drift.abs() <= config.clock_drift_threshold_ppm {
+        return None;
+    }
+
+    Some(WebhookPayload {
+        event: "clock_drift".to_string(),
+        device_id: event.device_id.clone(),
+        alias,
+        value: drift,
+        threshold: config.clock_drift_threshold_ppm,
+        unit: "ppm".to_string(),
+        reading: event.reading.clone(),
+        timestamp: OffsetDateTime::now_utc(),
+    })
+}
+
 /// Evaluate thresholds for a reading and return any triggered alerts.
 fn evaluate_thresholds(
     config: &WebhookConfig,
@@ -276,6 +381,22 @@ fn evaluate_thresholds(
         });
     }
 
+    // Radiation dose rate threshold
+    if let Some(rate) = reading.radiation_rate
+        && rate >= config.radiation_threshold
+    {
+        alerts.push(WebhookPayload {
+            event: "radiation_high".to_string(),
+            device_id: event.device_id.clone(),
+            alias: alias.clone(),
+            value: f64::from(rate),
+            threshold: f64::from(config.radiation_threshold),
+            unit: "\u{b5}Sv/h".to_string(),
+            reading: reading.clone(),
+            timestamp: now,
+        });
+    }
+
     // Battery low threshold
     if reading.battery > 0 && reading.battery <= config.battery_threshold {
         alerts.push(WebhookPayload {
@@ -405,8 +526,11 @@ mod tests {
                 radon_avg_24h: None,
                 radon_avg_7d: None,
                 radon_avg_30d: None,
+                quality: aranet_store::ReadingQuality::Unchecked,
+                counter: None,
                 captured_at: OffsetDateTime::now_utc(),
             },
+            channels: Vec::new(),
         }
     }
 
@@ -416,7 +540,9 @@ mod tests {
             enabled: true,
             co2_threshold: 1000,
             radon_threshold: 300,
+            radiation_threshold: 1.0,
             battery_threshold: 10,
+            clock_drift_threshold_ppm: 200.0,
             cooldown_secs: 300,
             endpoints: vec![],
         };
@@ -446,7 +572,9 @@ mod tests {
             enabled: true,
             co2_threshold: 1000,
             radon_threshold: 300,
+            radiation_threshold: 1.0,
             battery_threshold: 20,
+            clock_drift_threshold_ppm: 200.0,
             cooldown_secs: 300,
             endpoints: vec![],
         };
@@ -469,7 +597,9 @@ mod tests {
             enabled: true,
             co2_threshold: 1000,
             radon_threshold: 300,
+            radiation_threshold: 1.0,
             battery_threshold: 20,
+            clock_drift_threshold_ppm: 200.0,
             cooldown_secs: 300,
             endpoints: vec![],
         };
@@ -480,13 +610,34 @@ mod tests {
         assert_eq!(alerts.len(), 2);
     }
 
+    #[test]
+    fn test_evaluate_thresholds_radiation_high() {
+        let config = WebhookConfig {
+            radiation_threshold: 0.5,
+            ..WebhookConfig::default()
+        };
+
+        let mut event = test_reading(0, 85);
+        event.reading.radiation_rate = Some(0.12);
+        assert!(evaluate_thresholds(&config, &event, None).is_empty());
+
+        event.reading.radiation_rate = Some(0.8);
+        let alerts = evaluate_thresholds(&config, &event, None);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].event, "radiation_high");
+        assert_eq!(alerts[0].threshold, 0.5);
+        assert_eq!(alerts[0].unit, "\u{b5}Sv/h");
+    }
+
     #[test]
     fn test_evaluate_thresholds_with_alias() {
         let config = WebhookConfig {
             enabled: true,
             co2_threshold: 1000,
             radon_threshold: 300,
+            radiation_threshold: 1.0,
             battery_threshold: 10,
+            clock_drift_threshold_ppm: 200.0,
             cooldown_secs: 300,
             endpoints: vec![],
         };
@@ -526,4 +677,42 @@ mod tests {
         assert!(endpoint.events.iter().any(|e| e == "battery_low"));
         assert!(!endpoint.events.iter().any(|e| e == "radon_high"));
     }
+
+    #[test]
+    fn test_evaluate_clock_drift() {
+        let config = WebhookConfig::default();
+        let event = test_reading(800, 80);
+
+        assert!(evaluate_clock_drift(&config, &event, None, None).is_none());
+        assert!(evaluate_clock_drift(&config, &event, None, Some(150.0)).is_none());
+
+        let alert = evaluate_clock_drift(&config, &event, None, Some(-350.0)).unwrap();
+        assert_eq!(alert.event, "clock_drift");
+        assert_eq!(alert.unit, "ppm");
+        assert!((alert.value + 350.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_evaluate_history_overwrite() {
+        let event = test_reading(800, 80);
+        assert!(evaluate_history_overwrite(&event, None, None).is_none());
+
+        let overwrite_at = OffsetDateTime::now_utc() + time::Duration::hours(12);
+        let alert = evaluate_history_overwrite(&event, None, Some(overwrite_at)).unwrap();
+        assert_eq!(alert.event, "history_overwrite");
+        assert_eq!(alert.unit, "h");
+        assert!((alert.value - 12.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_evaluate_history_sync_deferred() {
+        let event = test_reading(800, 10);
+        assert!(evaluate_history_sync_deferred(&event, None, None).is_none());
+
+        let alert = evaluate_history_sync_deferred(&event, None, Some(15)).unwrap();
+        assert_eq!(alert.event, "history_sync_deferred");
+        assert_eq!(alert.unit, "%");
+        assert!((alert.value - 10.0).abs() < f64::EPSILON);
+        assert!((alert.threshold - 15.0).abs() < f64::EPSILON);
+    }
 }
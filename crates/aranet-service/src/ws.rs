@@ -1,58 +1,504 @@
 //! WebSocket handler for real-time updates.
+//!
+//! On connect the server sends the latest reading of every device, then
+//! streams readings as they are collected. Clients may send a subscription
+//! to limit the stream to some devices and to replay recent history first:
+//!
+//! ```json
+//! {"subscribe": {"devices": ["Aranet4 17C3C"], "backlog": 50}}
+//! ```
+//!
+//! `devices` defaults to all devices and `backlog` (readings per device,
+//! capped at [`MAX_BACKLOG`]) to none. The backlog is sent as one
+//! `{"type": "backlog", "device_id": ..., "readings": [...]}` message per
+//! device, oldest reading first, followed by
+//! `{"type": "subscribed", "devices": [...], "backlog": n}` before live
+//! readings resume.
+//!
+//! # Wire formats
+//!
+//! Messages are JSON text frames unless the client requests the
+//! [`PROTOCOL_MSGPACK`] subprotocol, in which case the same messages are
+//! sent as MessagePack binary frames (maps keyed by field name, with sensor
+//! values as 32-bit floats), so a reading takes about a fifth fewer bytes.
+//! Clients may send their messages as JSON text frames in either format, or
+//! as MessagePack binary frames once MessagePack was negotiated.
+//! [`PROTOCOL_JSON`] may be requested to make the choice explicit; when a
+//! client offers both, MessagePack is used.
+//!
+//! The `permessage-deflate` extension is not negotiated, because the
+//! WebSocket stack this server uses does not implement it.
+//!
+//! # Keepalive and connection limits
+//!
+//! Every `server.websocket.ping_interval_secs` the server pings each client.
+//! Clients that send nothing, not even the pong, for
+//! `idle_timeout_secs` are closed with code 1001, and sends that stall for
+//! [`SEND_TIMEOUT`] drop the client, so connections that vanished without a
+//! close do not keep a broadcast receiver forever. At most
+//! `max_connections` clients are served: further clients are refused with
+//! 503, or with `eviction = "evict_idle"` the quietest client is closed
+//! with code 1013 to make room. Connected clients are listed with their
+//! message counts in `GET /api/health/detailed`.
+//!
+//! # Device privacy
+//!
+//! Devices whose [`DevicePrivacy`] does not allow broadcasts are left out of
+//! snapshots, backlogs and live readings, as are devices that are not public
+//! when the client connected without credentials.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use aranet_store::{ReadingQuery, Store, StoredReading};
 use axum::{
     Router,
     extract::{
-        State, WebSocketUpgrade,
-        ws::{Message, WebSocket},
+        ConnectInfo, Extension, State, WebSocketUpgrade,
+        ws::{CloseFrame, Message, WebSocket, close_code},
     },
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     routing::get,
 };
+use futures::stream::SplitSink;
 use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use time::OffsetDateTime;
 use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{Notify, mpsc};
+use tokio::time::{Instant, Interval, MissedTickBehavior};
 use tracing::{debug, info, warn};
 
+use crate::api::AppError;
+use crate::config::{DevicePrivacy, WebSocketConfig, WsEvictionPolicy};
+use crate::oidc::Scope;
 use crate::state::{AppState, ReadingEvent};
 
+/// Maximum number of backlog readings replayed per device.
+pub const MAX_BACKLOG: u32 = 1000;
+
+/// Subprotocol for JSON text frames (the default).
+pub const PROTOCOL_JSON: &str = "aranet.json";
+
+/// Subprotocol for MessagePack binary frames.
+pub const PROTOCOL_MSGPACK: &str = "aranet.msgpack";
+
+/// How long a single frame may take to send before the client is dropped.
+pub const SEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How messages to a client are encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WireFormat {
+    Json,
+    MessagePack,
+}
+
+impl WireFormat {
+    fn from_protocol(protocol: Option<&str>) -> Self {
+        match protocol {
+            Some(PROTOCOL_MSGPACK) => Self::MessagePack,
+            _ => Self::Json,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::MessagePack => "msgpack",
+        }
+    }
+
+    /// Encode `value` as a frame in this format.
+    fn encode<T: Serialize>(self, value: &T) -> Result<Message, EncodeError> {
+        Ok(match self {
+            Self::Json => Message::Text(serde_json::to_string(value)?.into()),
+            Self::MessagePack => Message::Binary(rmp_serde::to_vec_named(value)?.into()),
+        })
+    }
+}
+
+/// Failure to encode an outgoing message.
+#[derive(Debug, thiserror::Error)]
+enum EncodeError {
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    MessagePack(#[from] rmp_serde::encode::Error),
+}
+
+/// A message sent by a WebSocket client.
+#[derive(Debug, Deserialize)]
+struct ClientMessage {
+    subscribe: Option<Subscription>,
+}
+
+/// Devices a client wants readings for, and how much history to replay.
+#[derive(Debug, Default, Deserialize)]
+struct Subscription {
+    /// Device IDs to stream; all devices when absent.
+    #[serde(default)]
+    devices: Option<Vec<String>>,
+    /// Number of recent readings to send per device before live data.
+    #[serde(default)]
+    backlog: u32,
+}
+
+/// Requests forwarded from the receive task to the send task.
+enum ClientRequest {
+    Subscribe(Subscription),
+    Invalid(String),
+}
+
+/// Recent readings for one device, sent in reply to a subscription.
+#[derive(Debug, Serialize)]
+struct BacklogEvent {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    device_id: String,
+    readings: Vec<StoredReading>,
+}
+
 /// Create the WebSocket router.
 pub fn router() -> Router<Arc<AppState>> {
     Router::new().route("/api/ws", get(ws_handler))
 }
 
 /// WebSocket upgrade handler.
-async fn ws_handler(ws: WebSocketUpgrade, State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
+async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+    caller: Option<Extension<Scope>>,
+    peer: Option<Extension<ConnectInfo<SocketAddr>>>,
+) -> Result<Response, AppError> {
+    let ws = ws.protocols([PROTOCOL_MSGPACK, PROTOCOL_JSON]);
+    let format = WireFormat::from_protocol(ws.selected_protocol().and_then(|p| p.to_str().ok()));
+    let authenticated = caller.is_some();
+    let config = state.config.read().await.server.websocket.clone();
+    let peer = peer.map(|Extension(ConnectInfo(addr))| addr);
+    let Some(connection) = state
+        .ws_connections
+        .admit(&config, peer, format, authenticated)
+    else {
+        warn!(
+            "Refused WebSocket client: {} connections already open",
+            config.max_connections
+        );
+        return Err(AppError::ServiceUnavailable(format!(
+            "WebSocket connection limit of {} reached",
+            config.max_connections
+        )));
+    };
+    // Created before the upgrade so the slot is released if it fails
+    let client = ConnectedClient::new(state, connection);
+    Ok(ws
+        .on_upgrade(move |socket| handle_socket(socket, client, format, config))
+        .into_response())
 }
 
-/// Handle a WebSocket connection.
-async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
-    use std::sync::atomic::Ordering;
+/// Connected WebSocket clients, for connection limits and diagnostics.
+#[derive(Debug, Default)]
+pub struct WsConnections {
+    next_id: AtomicU64,
+    connections: Mutex<HashMap<u64, Arc<WsConnection>>>,
+    evicted: AtomicU64,
+    rejected: AtomicU64,
+    idle_closed: AtomicU64,
+}
+
+impl WsConnections {
+    /// Register a new client, or return `None` when the limit is reached and
+    /// the policy is to refuse it.
+    ///
+    /// With [`WsEvictionPolicy::EvictIdle`] the client that has been quiet
+    /// longest is told to close and removed to make room.
+    pub(crate) fn admit(
+        &self,
+        config: &WebSocketConfig,
+        peer: Option<SocketAddr>,
+        format: WireFormat,
+        authenticated: bool,
+    ) -> Option<Arc<WsConnection>> {
+        let mut connections = self.lock();
+        if config.max_connections > 0 {
+            // A loop, as the limit may have been lowered by a config reload
+            while connections.len() >= config.max_connections {
+                if config.eviction == WsEvictionPolicy::Reject {
+                    self.rejected.fetch_add(1, Ordering::Relaxed);
+                    return None;
+                }
+                let idlest = connections
+                    .values()
+                    .min_by_key(|c| (c.last_seen(), c.id))
+                    .map(|c| c.id)?;
+                if let Some(evicted) = connections.remove(&idlest) {
+                    info!("Evicting idle WebSocket client {}", evicted.id);
+                    evicted.evict.notify_one();
+                    self.evicted.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+
+        let connection = Arc::new(WsConnection::new(
+            self.next_id.fetch_add(1, Ordering::Relaxed) + 1,
+            peer,
+            format,
+            authenticated,
+        ));
+        connections.insert(connection.id, Arc::clone(&connection));
+        Some(connection)
+    }
+
+    fn remove(&self, id: u64) {
+        self.lock().remove(&id);
+    }
+
+    /// Number of registered clients.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if no clients are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Clients closed to make room for new ones.
+    pub fn evicted_total(&self) -> u64 {
+        self.evicted.load(Ordering::Relaxed)
+    }
+
+    /// Clients refused because the limit was reached.
+    pub fn rejected_total(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    /// Clients closed after the idle timeout.
+    pub fn idle_closed_total(&self) -> u64 {
+        self.idle_closed.load(Ordering::Relaxed)
+    }
+
+    /// Per-connection statistics, oldest connection first.
+    pub fn stats(&self) -> Vec<WsConnectionStats> {
+        let mut stats: Vec<_> = self.lock().values().map(|c| c.stats()).collect();
+        stats.sort_by_key(|s| s.id);
+        stats
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, Arc<WsConnection>>> {
+        self.connections.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// One connected WebSocket client.
+#[derive(Debug)]
+pub(crate) struct WsConnection {
+    id: u64,
+    peer: Option<SocketAddr>,
+    format: WireFormat,
+    authenticated: bool,
+    connected_at: OffsetDateTime,
+    started: Instant,
+    /// Milliseconds after `started` when the client last sent a frame.
+    last_seen_ms: AtomicU64,
+    messages_sent: AtomicU64,
+    messages_dropped: AtomicU64,
+    pings_sent: AtomicU64,
+    /// Notified when the client is evicted to make room for another.
+    evict: Notify,
+}
+
+impl WsConnection {
+    fn new(id: u64, peer: Option<SocketAddr>, format: WireFormat, authenticated: bool) -> Self {
+        Self {
+            id,
+            peer,
+            format,
+            authenticated,
+            connected_at: OffsetDateTime::now_utc(),
+            started: Instant::now(),
+            last_seen_ms: AtomicU64::new(0),
+            messages_sent: AtomicU64::new(0),
+            messages_dropped: AtomicU64::new(0),
+            pings_sent: AtomicU64::new(0),
+            evict: Notify::new(),
+        }
+    }
+
+    /// Record that the client sent a frame.
+    fn touch(&self) {
+        let elapsed = self.started.elapsed().as_millis() as u64;
+        self.last_seen_ms.fetch_max(elapsed, Ordering::Relaxed);
+    }
+
+    fn last_seen(&self) -> Instant {
+        self.started + Duration::from_millis(self.last_seen_ms.load(Ordering::Relaxed))
+    }
+
+    /// Time since the client last sent a frame.
+    fn idle(&self) -> Duration {
+        self.last_seen().elapsed()
+    }
+
+    fn stats(&self) -> WsConnectionStats {
+        WsConnectionStats {
+            id: self.id,
+            peer: self.peer.map(|addr| addr.to_string()),
+            format: self.format.name(),
+            authenticated: self.authenticated,
+            connected_at: self.connected_at,
+            idle_secs: self.idle().as_secs(),
+            messages_sent: self.messages_sent.load(Ordering::Relaxed),
+            messages_dropped: self.messages_dropped.load(Ordering::Relaxed),
+            pings_sent: self.pings_sent.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Statistics for one connected WebSocket client.
+#[derive(Debug, Clone, Serialize)]
+pub struct WsConnectionStats {
+    /// Connection number, counting from 1 since the service started.
+    pub id: u64,
+    /// Client address, when known.
+    pub peer: Option<String>,
+    /// Wire format: `json` or `msgpack`.
+    pub format: &'static str,
+    /// Whether the client connected with credentials.
+    pub authenticated: bool,
+    /// When the client connected.
+    #[serde(with = "time::serde::rfc3339")]
+    pub connected_at: OffsetDateTime,
+    /// Seconds since the client last sent a frame (including pongs).
+    pub idle_secs: u64,
+    /// Readings and replies sent to the client.
+    pub messages_sent: u64,
+    /// Readings skipped because the client fell behind.
+    pub messages_dropped: u64,
+    /// Keepalive pings sent to the client.
+    pub pings_sent: u64,
+}
+
+/// Whether a device's readings are sent to a client.
+fn streams(privacy: &DevicePrivacy, authenticated: bool) -> bool {
+    privacy.is_broadcast() && (authenticated || privacy.is_public())
+}
+
+/// Configured devices whose readings are not sent to a client.
+async fn withheld_devices(state: &AppState, authenticated: bool) -> Vec<String> {
+    state
+        .config
+        .read()
+        .await
+        .devices_without(|privacy| streams(privacy, authenticated))
+}
+
+fn is_withheld(withheld: &[String], device_id: &str) -> bool {
+    withheld.iter().any(|d| d.eq_ignore_ascii_case(device_id))
+}
+
+/// Counts a client in [`AppState::ws_clients`] and keeps it registered in
+/// [`AppState::ws_connections`] while it is connected.
+struct ConnectedClient {
+    state: Arc<AppState>,
+    connection: Arc<WsConnection>,
+}
+
+impl ConnectedClient {
+    fn new(state: Arc<AppState>, connection: Arc<WsConnection>) -> Self {
+        state.ws_clients.fetch_add(1, Ordering::Relaxed);
+        Self { state, connection }
+    }
+}
+
+impl Drop for ConnectedClient {
+    fn drop(&mut self) {
+        self.state.ws_clients.fetch_sub(1, Ordering::Relaxed);
+        self.state.ws_connections.remove(self.connection.id);
+    }
+}
+
+/// Send a frame, returning `false` if the client is gone or stopped reading.
+async fn send_frame(sender: &mut SplitSink<WebSocket, Message>, message: Message) -> bool {
+    matches!(
+        tokio::time::timeout(SEND_TIMEOUT, sender.send(message)).await,
+        Ok(Ok(()))
+    )
+}
+
+/// Close frame with the given code and reason.
+fn close(code: u16, reason: &'static str) -> Message {
+    Message::Close(Some(CloseFrame {
+        code,
+        reason: reason.into(),
+    }))
+}
+
+/// Timer for keepalive pings and idle checks, or `None` when both are off.
+fn heartbeat(config: &WebSocketConfig) -> Option<Interval> {
+    let secs = match (config.ping_interval_secs, config.idle_timeout_secs) {
+        (0, 0) => return None,
+        (0, idle) => (idle / 2).max(1),
+        (ping, _) => ping,
+    };
+    let period = Duration::from_secs(secs);
+    let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    Some(interval)
+}
+
+/// Wait for the next heartbeat, or forever without one.
+async fn next_beat(heartbeat: &mut Option<Interval>) {
+    match heartbeat {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
 
+/// Handle a WebSocket connection.
+async fn handle_socket(
+    socket: WebSocket,
+    client: ConnectedClient,
+    format: WireFormat,
+    config: WebSocketConfig,
+) {
+    let state = Arc::clone(&client.state);
+    let connection = Arc::clone(&client.connection);
+    let authenticated = connection.authenticated;
     let (mut sender, mut receiver) = socket.split();
 
     // Subscribe to reading events FIRST (before sending snapshot)
     // This ensures we don't miss any readings published while sending the snapshot
     let mut rx = state.readings_tx.subscribe();
-    let ws_dropped = Arc::clone(&state); // for tracking dropped messages
+    let task_state = Arc::clone(&state); // for backlog queries and dropped message tracking
+    let task_connection = Arc::clone(&connection);
+    let (request_tx, mut request_rx) = mpsc::channel::<ClientRequest>(8);
 
-    info!("WebSocket client connected");
+    info!("WebSocket client connected ({:?})", format);
 
     // Send initial snapshot of latest readings for all devices
     // This ensures clients immediately see current state without waiting for next poll
     // Collect all events while holding the lock, then release before sending
-    let snapshot: Vec<String> = match state
+    let withheld = withheld_devices(&state, authenticated).await;
+    let snapshot: Vec<Message> = match state
         .with_store_read(|store| {
             let mut events = Vec::new();
             for (device, reading) in store.list_latest_readings()? {
+                if is_withheld(&withheld, &device.id) {
+                    continue;
+                }
                 let event = ReadingEvent {
                     device_id: device.id.clone(),
                     reading,
+                    channels: Vec::new(),
                 };
-                if let Ok(json) = serde_json::to_string(&event) {
-                    events.push(json);
+                if let Ok(message) = format.encode(&event) {
+                    events.push(message);
                 }
             }
             Ok(events)
@@ -62,58 +508,165 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
         Ok(snapshot) => snapshot,
         Err(e) => {
             warn!("Failed to load initial WebSocket snapshot: {}", e);
-            let payload = serde_json::json!({
-                "type": "error",
-                "error": format!("Failed to load initial snapshot: {}", e),
-            })
-            .to_string();
-            let _ = sender.send(Message::Text(payload.into())).await;
-            let _ = sender.send(Message::Close(None)).await;
+            let payload = error_message(&format!("Failed to load initial snapshot: {}", e));
+            if let Ok(message) = format.encode(&payload) {
+                send_frame(&mut sender, message).await;
+            }
+            send_frame(&mut sender, Message::Close(None)).await;
             return;
         }
     };
 
-    for json in snapshot {
-        if sender.send(Message::Text(json.into())).await.is_err() {
+    for message in snapshot {
+        if !send_frame(&mut sender, message).await {
             info!("WebSocket client disconnected during initial snapshot");
             return;
         }
+        connection.messages_sent.fetch_add(1, Ordering::Relaxed);
     }
 
     debug!("Sent initial snapshot to WebSocket client");
 
-    // Spawn a task to send reading events to the client
+    // Spawn a task to send reading events (and subscription replies) to the client
     let mut send_task = tokio::spawn(async move {
+        // Device filter from the last subscription; None streams every device
+        let mut devices: Option<Vec<String>> = None;
+        // Newest replayed reading per device, so queued live events are not sent twice
+        let mut replayed_until: HashMap<String, OffsetDateTime> = HashMap::new();
+        let connection = task_connection;
+        let idle_timeout = Duration::from_secs(config.idle_timeout_secs);
+        let mut heartbeat = heartbeat(&config);
+
         loop {
-            match rx.recv().await {
-                Ok(event) => {
-                    let json = match serde_json::to_string(&event) {
-                        Ok(j) => j,
-                        Err(e) => {
-                            warn!("Failed to serialize event: {}", e);
-                            continue;
+            tokio::select! {
+                _ = next_beat(&mut heartbeat) => {
+                    if !idle_timeout.is_zero() && connection.idle() >= idle_timeout {
+                        info!(
+                            "Closing WebSocket client {} after {}s without a frame",
+                            connection.id,
+                            connection.idle().as_secs()
+                        );
+                        task_state
+                            .ws_connections
+                            .idle_closed
+                            .fetch_add(1, Ordering::Relaxed);
+                        send_frame(&mut sender, close(close_code::AWAY, "idle timeout")).await;
+                        break;
+                    }
+                    if config.ping_interval_secs > 0 {
+                        if !send_frame(&mut sender, Message::Ping(Default::default())).await {
+                            break;
+                        }
+                        connection.pings_sent.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+                _ = connection.evict.notified() => {
+                    send_frame(&mut sender, close(close_code::AGAIN, "connection limit reached")).await;
+                    break;
+                }
+                request = request_rx.recv() => {
+                    let Some(request) = request else { break };
+                    let replies = match request {
+                        ClientRequest::Subscribe(subscription) => {
+                            let backlog = subscription.backlog.min(MAX_BACKLOG);
+                            let requested = subscription.devices.clone();
+                            let withheld = withheld_devices(&task_state, authenticated).await;
+                            match task_state
+                                .with_store_read(|store| {
+                                    load_backlog(store, requested.as_deref(), backlog, &withheld)
+                                })
+                                .await
+                            {
+                                Ok(events) => {
+                                    replayed_until.clear();
+                                    let mut replies = Vec::with_capacity(events.len() + 1);
+                                    for event in &events {
+                                        if let Some(last) = event.readings.last() {
+                                            replayed_until.insert(event.device_id.clone(), last.captured_at);
+                                        }
+                                        replies.push(serde_json::to_value(event).unwrap_or_default());
+                                    }
+                                    debug!(
+                                        "WebSocket subscription with backlog {} for {} device(s)",
+                                        backlog,
+                                        events.len()
+                                    );
+                                    replies.push(
+                                        serde_json::json!({
+                                            "type": "subscribed",
+                                            "devices": subscription.devices,
+                                            "backlog": backlog,
+                                        }),
+                                    );
+                                    devices = subscription.devices;
+                                    replies
+                                }
+                                Err(e) => {
+                                    warn!("Failed to load WebSocket backlog: {}", e);
+                                    vec![error_message(&format!("Failed to load backlog: {}", e))]
+                                }
+                            }
                         }
+                        ClientRequest::Invalid(error) => vec![error_message(&error)],
                     };
 
-                    if sender.send(Message::Text(json.into())).await.is_err() {
-                        break;
+                    for reply in replies {
+                        let Ok(message) = format.encode(&reply) else { continue };
+                        if !send_frame(&mut sender, message).await {
+                            return;
+                        }
+                        connection.messages_sent.fetch_add(1, Ordering::Relaxed);
                     }
                 }
-                Err(RecvError::Lagged(n)) => {
-                    ws_dropped
-                        .ws_messages_dropped
-                        .fetch_add(n, Ordering::Relaxed);
-                    warn!("WebSocket client lagged, skipped {n} messages");
-                    continue;
-                }
-                Err(RecvError::Closed) => break,
+                result = rx.recv() => match result {
+                    Ok(event) => {
+                        if let Some(devices) = &devices
+                            && !devices.contains(&event.device_id)
+                        {
+                            continue;
+                        }
+                        if !streams(&task_state.device_privacy(&event.device_id).await, authenticated) {
+                            continue;
+                        }
+                        if let Some(until) = replayed_until.get(&event.device_id)
+                            && event.reading.captured_at <= *until
+                        {
+                            continue;
+                        }
+
+                        let message = match format.encode(&event) {
+                            Ok(message) => message,
+                            Err(e) => {
+                                warn!("Failed to serialize event: {}", e);
+                                continue;
+                            }
+                        };
+
+                        if !send_frame(&mut sender, message).await {
+                            break;
+                        }
+                        connection.messages_sent.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Err(RecvError::Lagged(n)) => {
+                        task_state
+                            .ws_messages_dropped
+                            .fetch_add(n, Ordering::Relaxed);
+                        connection.messages_dropped.fetch_add(n, Ordering::Relaxed);
+                        warn!("WebSocket client lagged, skipped {n} messages");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
             }
         }
     });
 
-    // Spawn a task to receive messages from the client (for keep-alive pings)
+    // Spawn a task to receive messages from the client (subscriptions, pings and pongs)
     let mut recv_task = tokio::spawn(async move {
         while let Some(result) = receiver.next().await {
+            if result.is_ok() {
+                connection.touch();
+            }
             match result {
                 Ok(Message::Close(_)) => break,
                 Ok(Message::Ping(data)) => {
@@ -121,6 +674,23 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                     // Pong is handled automatically by axum
                     let _ = data;
                 }
+                Ok(message @ (Message::Text(_) | Message::Binary(_))) => {
+                    let parsed = match &message {
+                        Message::Text(text) => parse_client_message(text),
+                        Message::Binary(data) if format == WireFormat::MessagePack => {
+                            parse_client_msgpack(data)
+                        }
+                        _ => continue,
+                    };
+                    let request = match parsed {
+                        Ok(Some(subscription)) => ClientRequest::Subscribe(subscription),
+                        Ok(None) => continue,
+                        Err(error) => ClientRequest::Invalid(error),
+                    };
+                    if request_tx.send(request).await.is_err() {
+                        break;
+                    }
+                }
                 Ok(_) => {
                     // Ignore other messages
                 }
@@ -142,5 +712,286 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
         },
     }
 
+    drop(client);
     info!("WebSocket client disconnected");
 }
+
+/// Parse a client message, returning the subscription it carries (if any).
+fn parse_client_message(text: &str) -> Result<Option<Subscription>, String> {
+    serde_json::from_str::<ClientMessage>(text)
+        .map(|message| message.subscribe)
+        .map_err(|e| format!("Invalid message: {}", e))
+}
+
+/// Parse a MessagePack client message, returning the subscription it
+/// carries (if any).
+fn parse_client_msgpack(data: &[u8]) -> Result<Option<Subscription>, String> {
+    rmp_serde::from_slice::<ClientMessage>(data)
+        .map(|message| message.subscribe)
+        .map_err(|e| format!("Invalid message: {}", e))
+}
+
+/// Load the most recent `backlog` readings (oldest first) for each device.
+///
+/// Devices without readings or in `withheld` are skipped; all devices are
+/// used when `devices` is `None`.
+fn load_backlog(
+    store: &Store,
+    devices: Option<&[String]>,
+    backlog: u32,
+    withheld: &[String],
+) -> aranet_store::Result<Vec<BacklogEvent>> {
+    if backlog == 0 {
+        return Ok(Vec::new());
+    }
+
+    let device_ids: Vec<String> = match devices {
+        Some(devices) => devices.to_vec(),
+        None => store.list_devices()?.into_iter().map(|d| d.id).collect(),
+    };
+
+    let mut events = Vec::with_capacity(device_ids.len());
+    for device_id in device_ids {
+        if is_withheld(withheld, &device_id) {
+            continue;
+        }
+        let mut readings =
+            store.query_readings(&ReadingQuery::new().device(&device_id).limit(backlog))?;
+        if readings.is_empty() {
+            continue;
+        }
+        readings.reverse();
+        events.push(BacklogEvent {
+            kind: "backlog",
+            device_id,
+            readings,
+        });
+    }
+    Ok(events)
+}
+
+fn error_message(error: &str) -> Value {
+    serde_json::json!({
+        "type": "error",
+        "error": error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aranet_types::CurrentReading;
+
+    #[test]
+    fn test_parse_client_message() {
+        let subscription =
+            parse_client_message(r#"{"subscribe": {"devices": ["a"], "backlog": 50}}"#)
+                .unwrap()
+                .unwrap();
+        assert_eq!(subscription.devices, Some(vec!["a".to_string()]));
+        assert_eq!(subscription.backlog, 50);
+
+        let subscription = parse_client_message(r#"{"subscribe": {}}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(subscription.devices, None);
+        assert_eq!(subscription.backlog, 0);
+
+        assert!(parse_client_message("{}").unwrap().is_none());
+        assert!(parse_client_message("not json").is_err());
+    }
+
+    #[test]
+    fn test_wire_format_from_protocol() {
+        assert_eq!(
+            WireFormat::from_protocol(Some(PROTOCOL_MSGPACK)),
+            WireFormat::MessagePack
+        );
+        assert_eq!(
+            WireFormat::from_protocol(Some(PROTOCOL_JSON)),
+            WireFormat::Json
+        );
+        assert_eq!(WireFormat::from_protocol(None), WireFormat::Json);
+    }
+
+    #[test]
+    fn test_parse_client_msgpack() {
+        let frame = rmp_serde::to_vec_named(&serde_json::json!({
+            "subscribe": {"devices": ["a"], "backlog": 50}
+        }))
+        .unwrap();
+        let subscription = parse_client_msgpack(&frame).unwrap().unwrap();
+        assert_eq!(subscription.devices, Some(vec!["a".to_string()]));
+        assert_eq!(subscription.backlog, 50);
+
+        let empty = rmp_serde::to_vec_named(&serde_json::json!({})).unwrap();
+        assert!(parse_client_msgpack(&empty).unwrap().is_none());
+        assert!(parse_client_msgpack(b"not msgpack").is_err());
+    }
+
+    #[test]
+    fn test_msgpack_frames_are_smaller() {
+        let reading = CurrentReading::builder()
+            .co2(812)
+            .temperature(22.4)
+            .pressure(1013.2)
+            .humidity(45)
+            .battery(85)
+            .build();
+        let event = ReadingEvent {
+            device_id: "Aranet4 17C3C".to_string(),
+            reading: StoredReading::from_reading("Aranet4 17C3C", &reading),
+            channels: Vec::new(),
+        };
+
+        let Message::Text(json) = WireFormat::Json.encode(&event).unwrap() else {
+            panic!("expected a text frame");
+        };
+        let Message::Binary(binary) = WireFormat::MessagePack.encode(&event).unwrap() else {
+            panic!("expected a binary frame");
+        };
+        assert!(binary.len() < json.len());
+
+        // Same fields, keyed by name
+        let decoded: Value = rmp_serde::from_slice(&binary).unwrap();
+        assert_eq!(decoded["device_id"], "Aranet4 17C3C");
+        assert_eq!(decoded["reading"]["co2"], 812);
+    }
+
+    #[test]
+    fn test_load_backlog_oldest_first() {
+        let store = Store::open_in_memory().unwrap();
+        let start = OffsetDateTime::now_utc() - time::Duration::minutes(10);
+        for (i, co2) in [500, 600, 700].into_iter().enumerate() {
+            let reading = CurrentReading::builder()
+                .co2(co2)
+                .captured_at(start + time::Duration::minutes(i as i64))
+                .build();
+            store.insert_reading("a", &reading).unwrap();
+        }
+        store
+            .insert_reading("b", &CurrentReading::builder().co2(900).build())
+            .unwrap();
+        store.upsert_device("empty", None).unwrap();
+
+        let events = load_backlog(&store, None, 2, &[]).unwrap();
+        assert_eq!(events.len(), 2);
+        let a = events.iter().find(|e| e.device_id == "a").unwrap();
+        let co2: Vec<u16> = a.readings.iter().map(|r| r.co2).collect();
+        assert_eq!(co2, vec![600, 700]);
+
+        let events = load_backlog(&store, Some(&["b".to_string()]), 10, &[]).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].readings.len(), 1);
+
+        assert!(load_backlog(&store, None, 0, &[]).unwrap().is_empty());
+
+        let events = load_backlog(&store, None, 10, &["A".to_string()]).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].device_id, "b");
+    }
+
+    #[test]
+    fn test_streams_respects_privacy() {
+        use crate::config::SecurityZone;
+
+        let shared = DevicePrivacy::default();
+        assert!(streams(&shared, false));
+
+        let local = DevicePrivacy {
+            zone: SecurityZone::Local,
+            ..Default::default()
+        };
+        assert!(!streams(&local, true));
+
+        let local_broadcast = DevicePrivacy {
+            broadcast: Some(true),
+            ..local
+        };
+        assert!(streams(&local_broadcast, true));
+        assert!(!streams(&local_broadcast, false));
+    }
+
+    fn limited(max_connections: usize, eviction: WsEvictionPolicy) -> WebSocketConfig {
+        WebSocketConfig {
+            max_connections,
+            eviction,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_connections_reject_at_limit() {
+        let connections = WsConnections::default();
+        let config = limited(2, WsEvictionPolicy::Reject);
+        let first = connections
+            .admit(&config, None, WireFormat::Json, false)
+            .unwrap();
+        let _second = connections
+            .admit(&config, None, WireFormat::Json, true)
+            .unwrap();
+        assert!(
+            connections
+                .admit(&config, None, WireFormat::Json, false)
+                .is_none()
+        );
+        assert_eq!(connections.rejected_total(), 1);
+        assert_eq!(connections.len(), 2);
+
+        // A closed client frees its slot
+        connections.remove(first.id);
+        assert!(
+            connections
+                .admit(&config, None, WireFormat::Json, false)
+                .is_some()
+        );
+
+        // No limit
+        let unlimited = limited(0, WsEvictionPolicy::Reject);
+        assert!(
+            connections
+                .admit(&unlimited, None, WireFormat::Json, false)
+                .is_some()
+        );
+        assert_eq!(connections.len(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_connections_evict_idlest() {
+        use futures::FutureExt;
+
+        let connections = WsConnections::default();
+        let config = limited(2, WsEvictionPolicy::EvictIdle);
+        let older = connections
+            .admit(&config, None, WireFormat::Json, false)
+            .unwrap();
+        let quiet = connections
+            .admit(&config, None, WireFormat::MessagePack, false)
+            .unwrap();
+
+        tokio::time::advance(Duration::from_secs(60)).await;
+        older.touch();
+        assert_eq!(quiet.idle(), Duration::from_secs(60));
+
+        let newest = connections
+            .admit(&config, None, WireFormat::Json, false)
+            .unwrap();
+        assert!(quiet.evict.notified().now_or_never().is_some());
+        assert!(older.evict.notified().now_or_never().is_none());
+        assert_eq!(connections.evicted_total(), 1);
+
+        let ids: Vec<u64> = connections.stats().iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![older.id, newest.id]);
+        assert_eq!(connections.stats()[0].idle_secs, 0);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_period() {
+        let mut config = WebSocketConfig::default();
+        assert!(heartbeat(&config).is_some());
+        config.ping_interval_secs = 0;
+        assert!(heartbeat(&config).is_some());
+        config.idle_timeout_secs = 0;
+        assert!(heartbeat(&config).is_none());
+    }
+}
@@ -6,7 +6,7 @@
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use axum::body::Body;
 use axum::extract::connect_info::MockConnectInfo;
@@ -14,9 +14,10 @@ use axum::http::{Request, StatusCode};
 use http_body_util::BodyExt;
 use tower::ServiceExt;
 
+use aranet_core::ErrorCategory;
 use aranet_service::config::{Config, DeviceConfig, SecurityConfig};
 use aranet_service::middleware::RateLimitState;
-use aranet_service::state::AppState;
+use aranet_service::state::{AppState, DeviceCollectionStats};
 use aranet_service::{ReadingEvent, app};
 use aranet_store::Store;
 use aranet_types::{CurrentReading, HistoryRecord, Status};
@@ -258,6 +259,68 @@ async fn test_get_device_not_found() {
     assert_eq!(status, StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn test_request_id_in_errors_and_access_log() {
+    let (app, state) = test_app();
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("access.log");
+    state.access_log.open(&log_path).unwrap();
+
+    // An inbound ID is echoed in the header and the error body
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/devices/nonexistent?token=secret")
+                .header("X-Request-Id", "client-abc-123")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.headers()["x-request-id"], "client-abc-123");
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["request_id"], "client-abc-123");
+    assert!(json["error"].is_string());
+
+    // Without one, an ID is generated; success bodies are left alone
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/health")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let generated = response.headers()["x-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(generated.len(), 32);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(json.get("request_id").is_none());
+
+    let log = std::fs::read_to_string(&log_path).unwrap();
+    let lines: Vec<serde_json::Value> = log
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["request_id"], "client-abc-123");
+    assert_eq!(lines[0]["method"], "GET");
+    assert_eq!(lines[0]["path"], "/api/devices/nonexistent");
+    assert_eq!(lines[0]["status"], 404);
+    assert_eq!(lines[0]["client"], "127.0.0.1");
+    assert!(lines[0]["duration_ms"].is_number());
+    assert_eq!(lines[1]["request_id"], generated.as_str());
+    assert!(!log.contains("secret"));
+}
+
 #[tokio::test]
 async fn test_get_current_reading() {
     let (app, state) = test_app();
@@ -441,6 +504,8 @@ async fn test_add_duplicate_device() {
             address: "Aranet4 12345".to_string(),
             alias: Some("Office".to_string()),
             poll_interval: 60,
+            privacy: Default::default(),
+            min_history_sync_battery: None,
         });
     }
 
@@ -460,6 +525,8 @@ async fn test_update_device_preserves_alias_when_omitted() {
             address: "Aranet4 12345".to_string(),
             alias: Some("Office".to_string()),
             poll_interval: 60,
+            privacy: Default::default(),
+            min_history_sync_battery: None,
         });
     }
 
@@ -490,6 +557,8 @@ async fn test_update_device_can_clear_alias_with_null() {
             address: "Aranet4 12345".to_string(),
             alias: Some("Office".to_string()),
             poll_interval: 60,
+            privacy: Default::default(),
+            min_history_sync_battery: None,
         });
     }
 
@@ -519,6 +588,8 @@ async fn test_remove_device() {
             address: "Aranet4 12345".to_string(),
             alias: Some("Office".to_string()),
             poll_interval: 60,
+            privacy: Default::default(),
+            min_history_sync_battery: None,
         });
     }
 
@@ -596,7 +667,7 @@ async fn test_dashboard_route_is_public_when_auth_enabled() {
 #[tokio::test]
 async fn test_dashboard_history_uses_cached_history_endpoint() {
     let (app, _) = test_app();
-    let (status, body) = get(&app, "/dashboard").await;
+    let (status, body) = get(&app, "/static/dashboard.js").await;
 
     assert_eq!(status, StatusCode::OK);
     assert!(body.contains("/api/devices/${encodeURIComponent(device)}/history"));
@@ -606,6 +677,27 @@ async fn test_dashboard_history_uses_cached_history_endpoint() {
     assert!(body.contains("fetchJson('/api/devices')"));
 }
 
+#[tokio::test]
+async fn test_dashboard_page_links_embedded_assets() {
+    let (app, _) = test_app();
+    let (status, body) = get(&app, "/").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("/static/dashboard.css"));
+    assert!(body.contains("/static/dashboard.js"));
+}
+
+#[tokio::test]
+async fn test_dashboard_assets_are_public_when_auth_enabled() {
+    let (app, _) = test_app_with_auth();
+    let (status, body) = get(&app, "/static/dashboard.css").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("--bg-primary"));
+
+    let (status, _) = get(&app, "/static/missing.js").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
 // ==========================================================================
 // Authentication
 // ==========================================================================
@@ -692,6 +784,8 @@ async fn test_broadcast_reading_updates_api() {
         radon_avg_24h: None,
         radon_avg_7d: None,
         radon_avg_30d: None,
+        quality: aranet_store::ReadingQuality::Unchecked,
+        counter: None,
         captured_at: time::OffsetDateTime::now_utc(),
     };
 
@@ -717,6 +811,7 @@ async fn test_broadcast_reading_updates_api() {
     let _ = state.readings_tx.send(ReadingEvent {
         device_id: "test-device".to_string(),
         reading,
+        channels: Vec::new(),
     });
 
     // Now fetch the reading via API
@@ -758,3 +853,66 @@ async fn test_prometheus_metrics_enabled() {
     assert_eq!(status, StatusCode::OK);
     assert!(body.contains("aranet_collector_running"));
 }
+
+#[tokio::test]
+async fn test_prometheus_collector_internals() {
+    let store = Store::open_in_memory().unwrap();
+    let mut config = Config::default();
+    config.prometheus.enabled = true;
+    let state = AppState::with_config_path(store, config.clone(), test_config_path());
+    let security_config = Arc::new(config.security.clone());
+    let rate_limit_state = Arc::new(RateLimitState::new());
+    let router = app(Arc::clone(&state), security_config, rate_limit_state)
+        .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+
+    state
+        .collector
+        .device_stats
+        .write()
+        .await
+        .push(DeviceCollectionStats {
+            device_id: "Aranet4 AAAAA".to_string(),
+            alias: Some("Office".to_string()),
+            poll_interval: 60,
+            last_poll_at: None,
+            last_error_at: None,
+            last_error: None,
+            last_poll_duration_ms: None,
+            success_count: 1,
+            failure_count: 1,
+            polling: false,
+            clock_drift_ppm: None,
+            history_overwrite_at: None,
+            history_sync_deferred_below: None,
+            backoff: None,
+        });
+    {
+        let mut metrics = state.collector.metrics.lock().await;
+        metrics.record_connect_attempt("Aranet4 AAAAA");
+        metrics.record_connect_attempt("Aranet4 AAAAA");
+        metrics.record_poll("Aranet4 AAAAA", Duration::from_millis(1500), None);
+        metrics.record_poll(
+            "Aranet4 AAAAA",
+            Duration::from_secs(30),
+            Some(ErrorCategory::Timeout),
+        );
+    }
+
+    let (status, body) = get(&router, "/metrics").await;
+    assert_eq!(status, StatusCode::OK);
+    let labels = "device=\"Office\",address=\"Aranet4 AAAAA\"";
+    for line in [
+        format!("aranet_device_connect_attempts_total{{{labels}}} 2"),
+        format!("aranet_device_poll_errors_total{{{labels},category=\"timeout\"}} 1"),
+        format!("aranet_device_poll_latency_seconds_bucket{{{labels},le=\"1\"}} 0"),
+        format!("aranet_device_poll_latency_seconds_bucket{{{labels},le=\"2.5\"}} 1"),
+        format!("aranet_device_poll_latency_seconds_bucket{{{labels},le=\"+Inf\"}} 2"),
+        format!("aranet_device_poll_latency_seconds_sum{{{labels}}} 31.500"),
+        format!("aranet_device_poll_latency_seconds_count{{{labels}}} 2"),
+        "aranet_collector_sync_jobs_queued 0".to_string(),
+        "aranet_ws_clients 0".to_string(),
+        "# TYPE aranet_device_poll_latency_seconds histogram".to_string(),
+    ] {
+        assert!(body.contains(&line), "missing {line}");
+    }
+}
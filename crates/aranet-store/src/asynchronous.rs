@@ -0,0 +1,347 @@
+//! Async access to the store for code running on a Tokio runtime.
+//!
+//! [`crate::Store`] calls SQLite directly, so a large export or import run
+//! from an async task holds a runtime thread for as long as it takes. The
+//! [`Store`] in this module owns the connection and runs every call on
+//! Tokio's blocking thread pool instead.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aranet_store::HistoryQuery;
+//! use aranet_store::asynchronous::Store;
+//!
+//! # async fn example() -> aranet_store::Result<()> {
+//! let store = Store::open_default().await?;
+//! let csv = store
+//!     .export_history_csv(HistoryQuery::new().device("Aranet4 17C3C"))
+//!     .await?;
+//!
+//! // Anything without a wrapper goes through `call`
+//! let report = store.call(|store| store.verify()).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use aranet_types::{CurrentReading, HistoryRecord};
+
+use crate::error::{Error, Result};
+use crate::models::{StoredDevice, StoredHistoryRecord, StoredReading, SyncState};
+use crate::queries::{HistoryQuery, ReadingQuery};
+use crate::store::{DailyStats, HistoryStats, ImportResult, QueryResult};
+
+/// A [`crate::Store`] whose calls run on the blocking thread pool.
+///
+/// Cloning is cheap; clones share one connection and their calls run one at
+/// a time, in the order they get the connection.
+#[derive(Clone)]
+pub struct Store {
+    inner: Arc<Mutex<crate::Store>>,
+    path: Option<PathBuf>,
+}
+
+impl std::fmt::Debug for Store {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Store")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Store {
+    /// Open or create a database at the given path.
+    ///
+    /// See [`crate::Store::open`].
+    pub async fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let store = spawn(move || crate::Store::open(path)).await?;
+        Ok(Self::from(store))
+    }
+
+    /// Open the database at the platform-specific default location.
+    ///
+    /// See [`crate::Store::open_default`].
+    pub async fn open_default() -> Result<Self> {
+        Self::open(crate::default_db_path()).await
+    }
+
+    /// Open an in-memory database.
+    pub async fn open_in_memory() -> Result<Self> {
+        let store = spawn(crate::Store::open_in_memory).await?;
+        Ok(Self::from(store))
+    }
+
+    /// Return the database path for file-backed stores.
+    pub fn database_path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Run `f` with the store on the blocking thread pool.
+    ///
+    /// Waits for calls already running on this store's connection.
+    pub async fn call<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&crate::Store) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let inner = Arc::clone(&self.inner);
+        spawn(move || f(&inner.lock().unwrap_or_else(|e| e.into_inner()))).await
+    }
+
+    /// Run `f` with mutable access to the store on the blocking thread pool.
+    ///
+    /// Needed for [`crate::Store::attach_archive`] and
+    /// [`crate::Store::rollover`].
+    pub async fn call_mut<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut crate::Store) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let inner = Arc::clone(&self.inner);
+        spawn(move || f(&mut inner.lock().unwrap_or_else(|e| e.into_inner()))).await
+    }
+
+    // === Devices ===
+
+    /// See [`crate::Store::upsert_device`].
+    pub async fn upsert_device(&self, device_id: &str, name: Option<&str>) -> Result<StoredDevice> {
+        let device_id = device_id.to_string();
+        let name = name.map(str::to_string);
+        self.call(move |store| store.upsert_device(&device_id, name.as_deref()))
+            .await
+    }
+
+    /// See [`crate::Store::get_device`].
+    pub async fn get_device(&self, device_id: &str) -> Result<Option<StoredDevice>> {
+        let device_id = device_id.to_string();
+        self.call(move |store| store.get_device(&device_id)).await
+    }
+
+    /// See [`crate::Store::list_devices`].
+    pub async fn list_devices(&self) -> Result<Vec<StoredDevice>> {
+        self.call(|store| store.list_devices()).await
+    }
+
+    // === Readings ===
+
+    /// See [`crate::Store::insert_reading`].
+    pub async fn insert_reading(&self, device_id: &str, reading: CurrentReading) -> Result<i64> {
+        let device_id = device_id.to_string();
+        self.call(move |store| store.insert_reading(&device_id, &reading))
+            .await
+    }
+
+    /// See [`crate::Store::query_readings`].
+    pub async fn query_readings(&self, query: ReadingQuery) -> Result<Vec<StoredReading>> {
+        self.call(move |store| store.query_readings(&query)).await
+    }
+
+    /// See [`crate::Store::get_latest_reading`].
+    pub async fn get_latest_reading(&self, device_id: &str) -> Result<Option<StoredReading>> {
+        let device_id = device_id.to_string();
+        self.call(move |store| store.get_latest_reading(&device_id))
+            .await
+    }
+
+    /// See [`crate::Store::list_latest_readings`].
+    pub async fn list_latest_readings(&self) -> Result<Vec<(StoredDevice, StoredReading)>> {
+        self.call(|store| store.list_latest_readings()).await
+    }
+
+    // === History ===
+
+    /// See [`crate::Store::insert_history`].
+    pub async fn insert_history(
+        &self,
+        device_id: &str,
+        records: Vec<HistoryRecord>,
+    ) -> Result<usize> {
+        let device_id = device_id.to_string();
+        self.call(move |store| store.insert_history(&device_id, &records))
+            .await
+    }
+
+    /// See [`crate::Store::query_history`].
+    pub async fn query_history(&self, query: HistoryQuery) -> Result<Vec<StoredHistoryRecord>> {
+        self.call(move |store| store.query_history(&query)).await
+    }
+
+    /// See [`crate::Store::history_stats`].
+    pub async fn history_stats(&self, query: HistoryQuery) -> Result<HistoryStats> {
+        self.call(move |store| store.history_stats(&query)).await
+    }
+
+    /// See [`crate::Store::daily_stats`].
+    pub async fn daily_stats(&self, query: HistoryQuery) -> Result<Vec<DailyStats>> {
+        self.call(move |store| store.daily_stats(&query)).await
+    }
+
+    /// See [`crate::Store::query_readonly`].
+    pub async fn query_readonly(
+        &self,
+        sql: String,
+        params: Vec<serde_json::Value>,
+    ) -> Result<QueryResult> {
+        self.call(move |store| store.query_readonly(&sql, &params))
+            .await
+    }
+
+    // === Sync state ===
+
+    /// See [`crate::Store::get_sync_state`].
+    pub async fn get_sync_state(&self, device_id: &str) -> Result<Option<SyncState>> {
+        let device_id = device_id.to_string();
+        self.call(move |store| store.get_sync_state(&device_id))
+            .await
+    }
+
+    /// See [`crate::Store::update_sync_state`].
+    pub async fn update_sync_state(
+        &self,
+        device_id: &str,
+        last_index: u16,
+        total_readings: u16,
+    ) -> Result<()> {
+        let device_id = device_id.to_string();
+        self.call(move |store| store.update_sync_state(&device_id, last_index, total_readings))
+            .await
+    }
+
+    /// See [`crate::Store::calculate_sync_start`].
+    pub async fn calculate_sync_start(&self, device_id: &str, current_total: u16) -> Result<u16> {
+        let device_id = device_id.to_string();
+        self.call(move |store| store.calculate_sync_start(&device_id, current_total))
+            .await
+    }
+
+    // === Export and import ===
+
+    /// See [`crate::Store::export_history_csv`].
+    pub async fn export_history_csv(&self, query: HistoryQuery) -> Result<String> {
+        self.call(move |store| store.export_history_csv(&query))
+            .await
+    }
+
+    /// See [`crate::Store::export_history_json`].
+    pub async fn export_history_json(&self, query: HistoryQuery) -> Result<String> {
+        self.call(move |store| store.export_history_json(&query))
+            .await
+    }
+
+    /// See [`crate::Store::import_history_csv`].
+    pub async fn import_history_csv(&self, csv_data: String) -> Result<ImportResult> {
+        self.call(move |store| store.import_history_csv(&csv_data))
+            .await
+    }
+
+    /// See [`crate::Store::import_history_json`].
+    pub async fn import_history_json(&self, json_data: String) -> Result<ImportResult> {
+        self.call(move |store| store.import_history_json(&json_data))
+            .await
+    }
+}
+
+impl From<crate::Store> for Store {
+    fn from(store: crate::Store) -> Self {
+        let path = store.database_path().map(Path::to_path_buf);
+        Self {
+            inner: Arc::new(Mutex::new(store)),
+            path,
+        }
+    }
+}
+
+/// Run `f` on the blocking thread pool, resuming its panic if it panics.
+async fn spawn<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(Error::Cancelled(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::OffsetDateTime;
+
+    fn record(minutes: i64, co2: u16) -> HistoryRecord {
+        HistoryRecord {
+            timestamp: OffsetDateTime::UNIX_EPOCH + time::Duration::minutes(minutes),
+            co2,
+            temperature: 21.0,
+            pressure: 1010.0,
+            humidity: 45,
+            radon: None,
+            radiation_rate: None,
+            radiation_total: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_store_round_trip() {
+        let store = Store::open_in_memory().await.unwrap();
+        assert!(store.database_path().is_none());
+
+        store
+            .upsert_device("Aranet4 17C3C", Some("Office"))
+            .await
+            .unwrap();
+        let records = (0..100).map(|i| record(i, 400 + i as u16)).collect();
+        assert_eq!(
+            store
+                .insert_history("Aranet4 17C3C", records)
+                .await
+                .unwrap(),
+            100
+        );
+
+        let history = store
+            .query_history(HistoryQuery::new().device("Aranet4 17C3C"))
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 100);
+
+        let csv = store
+            .export_history_csv(HistoryQuery::new().device("Aranet4 17C3C"))
+            .await
+            .unwrap();
+        assert_eq!(csv.lines().count(), 101);
+    }
+
+    #[tokio::test]
+    async fn test_async_store_clones_share_connection() {
+        let store = Store::open_in_memory().await.unwrap();
+        let other = store.clone();
+        other.upsert_device("Aranet2 A1B2C", None).await.unwrap();
+
+        let devices = store.list_devices().await.unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].id, "Aranet2 A1B2C");
+    }
+
+    #[tokio::test]
+    async fn test_async_store_open_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("data.db");
+        let store = Store::open(&path).await.unwrap();
+        assert_eq!(store.database_path(), Some(path.as_path()));
+
+        let count = store.call(|store| store.count_history(None)).await.unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "boom")]
+    async fn test_async_store_resumes_panics() {
+        let store = Store::open_in_memory().await.unwrap();
+        let _: Result<()> = store.call(|_| panic!("boom")).await;
+    }
+}
@@ -31,6 +31,28 @@ pub enum Error {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    /// A filter expression could not be parsed; see [`ValueFilter`](crate::ValueFilter).
+    #[error("Invalid filter: {0}")]
+    InvalidFilter(String),
+
+    /// Archive database could not be attached or written.
+    #[error("Archive error: {0}")]
+    Archive(String),
+
+    /// A query passed to [`Store::query_readonly`](crate::Store::query_readonly)
+    /// was refused or took too long.
+    #[error("Query rejected: {0}")]
+    Query(String),
+
+    /// A device bundle could not be imported.
+    #[error("Unsupported device bundle: {0}")]
+    UnsupportedBundle(String),
+
+    /// A blocking store call was cancelled before it finished, usually
+    /// because the runtime is shutting down.
+    #[error("Store call cancelled: {0}")]
+    Cancelled(String),
+
     /// IO error.
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -0,0 +1,432 @@
+//! Filter expressions on measured values.
+//!
+//! A [`ValueFilter`] is parsed from a small expression language, e.g.
+//! `co2>1000 AND humidity<30`, and narrows [`ReadingQuery`](crate::ReadingQuery)
+//! and [`HistoryQuery`](crate::HistoryQuery) results to rows worth looking
+//! at. Expressions come from untrusted input (the service's `?where=`
+//! parameter), so parsing is strict:
+//!
+//! - Only the measurement columns listed in [`FILTER_FIELDS`] can be named.
+//! - Values must be plain numbers; they are bound as SQL parameters, never
+//!   spliced into the statement.
+//! - Expressions are limited to [`MAX_FILTER_LENGTH`] characters and
+//!   [`MAX_FILTER_CONDITIONS`] comparisons.
+//!
+//! # Grammar
+//!
+//! ```text
+//! expr       = and_expr { "OR" and_expr }
+//! and_expr   = term { "AND" term }
+//! term       = "(" expr ")" | comparison
+//! comparison = field ( "=" | "!=" | "<" | "<=" | ">" | ">=" ) number
+//! ```
+//!
+//! `AND` and `OR` are case-insensitive, and `AND` binds tighter than `OR`.
+//! Rows where a compared value is missing (e.g. `radon` on an Aranet4) never
+//! match.
+//!
+//! # Example
+//!
+//! ```
+//! use aranet_store::{HistoryQuery, ValueFilter};
+//!
+//! let filter = ValueFilter::parse("co2 > 1000 AND (humidity < 30 OR humidity > 60)")?;
+//! let query = HistoryQuery::new().device("Aranet4 17C3C").filter(filter);
+//! # Ok::<(), aranet_store::Error>(())
+//! ```
+
+use std::fmt;
+
+use crate::error::{Error, Result};
+
+/// Columns a filter expression may compare.
+pub const FILTER_FIELDS: &[&str] = &[
+    "co2",
+    "temperature",
+    "pressure",
+    "humidity",
+    "radon",
+    "radiation_rate",
+    "radiation_total",
+];
+
+/// Maximum length of a filter expression, in characters.
+pub const MAX_FILTER_LENGTH: usize = 256;
+
+/// Maximum number of comparisons in a filter expression.
+pub const MAX_FILTER_CONDITIONS: usize = 16;
+
+/// A parsed filter expression on measured values.
+///
+/// See the [module documentation](self) for the syntax.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueFilter {
+    source: String,
+    expr: Expr,
+}
+
+impl ValueFilter {
+    /// Parse a filter expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFilter`] if the expression is too long, names
+    /// an unknown field, or is otherwise malformed.
+    pub fn parse(source: &str) -> Result<Self> {
+        if source.chars().count() > MAX_FILTER_LENGTH {
+            return Err(Error::InvalidFilter(format!(
+                "expression is longer than {} characters",
+                MAX_FILTER_LENGTH
+            )));
+        }
+
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            pos: 0,
+            conditions: 0,
+        };
+        let expr = parser.expr()?;
+        if let Some(token) = parser.peek() {
+            return Err(Error::InvalidFilter(format!("unexpected {}", token)));
+        }
+
+        Ok(Self {
+            source: source.trim().to_string(),
+            expr,
+        })
+    }
+
+    /// The expression as written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Build the SQL condition and its parameters.
+    pub(crate) fn to_sql(&self) -> (String, Vec<f64>) {
+        let mut params = Vec::new();
+        let sql = self.expr.to_sql(&mut params);
+        (sql, params)
+    }
+}
+
+impl fmt::Display for ValueFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Compare {
+        field: &'static str,
+        op: CompareOp,
+        value: f64,
+    },
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+}
+
+impl Expr {
+    fn to_sql(&self, params: &mut Vec<f64>) -> String {
+        match self {
+            Expr::Compare { field, op, value } => {
+                params.push(*value);
+                // `field` comes from FILTER_FIELDS, never from the input
+                format!("{} {} ?", field, op.as_sql())
+            }
+            Expr::And(terms) => Self::join(terms, " AND ", params),
+            Expr::Or(terms) => Self::join(terms, " OR ", params),
+        }
+    }
+
+    fn join(terms: &[Expr], separator: &str, params: &mut Vec<f64>) -> String {
+        let parts: Vec<String> = terms.iter().map(|term| term.to_sql(params)).collect();
+        format!("({})", parts.join(separator))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    fn as_sql(self) -> &'static str {
+        match self {
+            CompareOp::Eq => "=",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Field(&'static str),
+    Op(CompareOp),
+    Number(f64),
+    And,
+    Or,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Field(field) => write!(f, "field '{}'", field),
+            Token::Op(op) => write!(f, "'{}'", op.as_sql()),
+            Token::Number(value) => write!(f, "number {}", value),
+            Token::And => f.write_str("AND"),
+            Token::Or => f.write_str("OR"),
+            Token::Open => f.write_str("'('"),
+            Token::Close => f.write_str("')'"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let token = match c {
+            '(' => {
+                chars.next();
+                Token::Open
+            }
+            ')' => {
+                chars.next();
+                Token::Close
+            }
+            '<' | '>' | '=' | '!' => {
+                chars.next();
+                let eq = chars.next_if(|&(_, next)| next == '=').is_some();
+                Token::Op(match (c, eq) {
+                    ('<', false) => CompareOp::Lt,
+                    ('<', true) => CompareOp::Le,
+                    ('>', false) => CompareOp::Gt,
+                    ('>', true) => CompareOp::Ge,
+                    ('=', _) => CompareOp::Eq,
+                    ('!', true) => CompareOp::Ne,
+                    _ => return Err(Error::InvalidFilter("expected '!='".to_string())),
+                })
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '.' => {
+                let mut end = start;
+                while let Some((i, c)) =
+                    chars.next_if(|&(_, c)| c.is_ascii_digit() || c == '-' || c == '.')
+                {
+                    end = i + c.len_utf8();
+                }
+                let text = &source[start..end];
+                let value: f64 = text
+                    .parse()
+                    .map_err(|_| Error::InvalidFilter(format!("invalid number '{}'", text)))?;
+                if !value.is_finite() {
+                    return Err(Error::InvalidFilter(format!("invalid number '{}'", text)));
+                }
+                Token::Number(value)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = start;
+                while let Some((i, c)) =
+                    chars.next_if(|&(_, c)| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    end = i + c.len_utf8();
+                }
+                let word = &source[start..end];
+                if word.eq_ignore_ascii_case("and") {
+                    Token::And
+                } else if word.eq_ignore_ascii_case("or") {
+                    Token::Or
+                } else {
+                    let field = FILTER_FIELDS
+                        .iter()
+                        .find(|field| field.eq_ignore_ascii_case(word))
+                        .ok_or_else(|| {
+                            Error::InvalidFilter(format!(
+                                "unknown field '{}' (expected one of: {})",
+                                word,
+                                FILTER_FIELDS.join(", ")
+                            ))
+                        })?;
+                    Token::Field(field)
+                }
+            }
+            c => {
+                return Err(Error::InvalidFilter(format!(
+                    "unexpected character '{}'",
+                    c
+                )));
+            }
+        };
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    conditions: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let mut terms = vec![self.and_expr()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            terms.push(self.and_expr()?);
+        }
+        Ok(flatten(terms, Expr::Or))
+    }
+
+    fn and_expr(&mut self) -> Result<Expr> {
+        let mut terms = vec![self.term()?];
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            terms.push(self.term()?);
+        }
+        Ok(flatten(terms, Expr::And))
+    }
+
+    fn term(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Open) => {
+                let expr = self.expr()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    other => Err(expected("')'", other)),
+                }
+            }
+            Some(Token::Field(field)) => {
+                let op = match self.next() {
+                    Some(Token::Op(op)) => op,
+                    other => return Err(expected("a comparison operator", other)),
+                };
+                let value = match self.next() {
+                    Some(Token::Number(value)) => value,
+                    other => return Err(expected("a number", other)),
+                };
+                self.conditions += 1;
+                if self.conditions > MAX_FILTER_CONDITIONS {
+                    return Err(Error::InvalidFilter(format!(
+                        "more than {} conditions",
+                        MAX_FILTER_CONDITIONS
+                    )));
+                }
+                Ok(Expr::Compare { field, op, value })
+            }
+            other => Err(expected("a field or '('", other)),
+        }
+    }
+}
+
+fn flatten(mut terms: Vec<Expr>, combine: fn(Vec<Expr>) -> Expr) -> Expr {
+    if terms.len() == 1 {
+        terms.remove(0)
+    } else {
+        combine(terms)
+    }
+}
+
+fn expected(what: &str, found: Option<Token>) -> Error {
+    match found {
+        Some(token) => Error::InvalidFilter(format!("expected {}, found {}", what, token)),
+        None => Error::InvalidFilter(format!("expected {}, found end of expression", what)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sql(source: &str) -> (String, Vec<f64>) {
+        ValueFilter::parse(source).unwrap().to_sql()
+    }
+
+    fn error(source: &str) -> String {
+        ValueFilter::parse(source).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_single_comparison() {
+        assert_eq!(sql("co2>1000"), ("co2 > ?".to_string(), vec![1000.0]));
+        assert_eq!(
+            sql("  Temperature <= -2.5 "),
+            ("temperature <= ?".to_string(), vec![-2.5])
+        );
+    }
+
+    #[test]
+    fn test_and_binds_tighter_than_or() {
+        assert_eq!(
+            sql("co2 > 1000 AND humidity < 30 or radon >= 300"),
+            (
+                "((co2 > ? AND humidity < ?) OR radon >= ?)".to_string(),
+                vec![1000.0, 30.0, 300.0]
+            )
+        );
+        assert_eq!(
+            sql("co2 != 0 and (humidity < 30 OR humidity > 60)"),
+            (
+                "(co2 != ? AND (humidity < ? OR humidity > ?))".to_string(),
+                vec![0.0, 30.0, 60.0]
+            )
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields_and_sql() {
+        assert!(error("device_id = 1").contains("unknown field 'device_id'"));
+        assert!(error("co2 > 1000; DROP TABLE history").contains("unexpected character ';'"));
+        assert!(error("co2 > '1000'").contains("unexpected character"));
+        assert!(error("co2 > humidity").contains("expected a number"));
+    }
+
+    #[test]
+    fn test_rejects_malformed_expressions() {
+        assert!(error("").contains("found end of expression"));
+        assert!(error("co2 >").contains("expected a number"));
+        assert!(error("co2 1000").contains("expected a comparison operator"));
+        assert!(error("(co2 > 1").contains("expected ')'"));
+        assert!(error("co2 > 1 humidity < 2").contains("unexpected field"));
+        assert!(error("co2 ! 1").contains("expected '!='"));
+        assert!(error("co2 > 1-2").contains("invalid number"));
+    }
+
+    #[test]
+    fn test_limits() {
+        let many = vec!["co2 > 1"; MAX_FILTER_CONDITIONS + 1].join(" OR ");
+        assert!(many.len() <= MAX_FILTER_LENGTH);
+        assert!(error(&many).contains("more than"));
+
+        let long = format!("co2 > {}", "1".repeat(MAX_FILTER_LENGTH));
+        assert!(error(&long).contains("longer than"));
+    }
+}
@@ -0,0 +1,321 @@
+//! Export history in formats accepted by phone health apps.
+//!
+//! Neither Apple Health nor Google Fit has built-in types for indoor air
+//! quality, so each measurement becomes a generic sample named after the
+//! metric. Health importer apps and Shortcuts ("Log Health Sample") can map
+//! these onto whatever the user wants to track.
+//!
+//! | Field | Apple Health type | Google Fit data type | Unit |
+//! |-------|-------------------|----------------------|------|
+//! | `co2` | `CarbonDioxide` | `com.aranet.co2` | `ppm` |
+//! | `temperature` | `AmbientTemperature` | `com.aranet.temperature` | `degC` |
+//! | `humidity` | `RelativeHumidity` | `com.aranet.humidity` | `%` |
+//! | `pressure` | `BarometricPressure` | `com.aranet.pressure` | `hPa` |
+//! | `radon` | `Radon` | `com.aranet.radon` | `Bq/m^3` |
+//! | `radiation_rate` | `RadiationDoseRate` | `com.aranet.radiation_rate` | `uSv/hr` |
+//!
+//! Metrics a device does not measure (stored as zero or empty) are skipped.
+
+use std::fmt::Write as _;
+
+use time::OffsetDateTime;
+use time::macros::format_description;
+
+use crate::error::{Error, Result};
+use crate::models::StoredHistoryRecord;
+
+/// Health app export formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthExportFormat {
+    /// `export.xml`-style `<HealthData>` document of `<Record>` elements, as
+    /// produced by Apple Health's own export.
+    AppleHealthXml,
+    /// One row per sample with Apple Health column names, for importer
+    /// apps and Shortcuts.
+    AppleHealthCsv,
+    /// One row per data point with Google Fit REST API field names.
+    GoogleFitCsv,
+}
+
+/// How one history field maps onto health app samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthField {
+    /// Column name in the regular CSV export.
+    pub field: &'static str,
+    /// Record type used for Apple Health.
+    pub apple_health_type: &'static str,
+    /// Custom data type name used for Google Fit.
+    pub google_fit_type: &'static str,
+    /// Unit in HealthKit notation.
+    pub unit: &'static str,
+}
+
+/// Field mapping used by every health export format.
+pub const HEALTH_FIELD_MAPPING: [HealthField; 6] = [
+    HealthField {
+        field: "co2",
+        apple_health_type: "CarbonDioxide",
+        google_fit_type: "com.aranet.co2",
+        unit: "ppm",
+    },
+    HealthField {
+        field: "temperature",
+        apple_health_type: "AmbientTemperature",
+        google_fit_type: "com.aranet.temperature",
+        unit: "degC",
+    },
+    HealthField {
+        field: "humidity",
+        apple_health_type: "RelativeHumidity",
+        google_fit_type: "com.aranet.humidity",
+        unit: "%",
+    },
+    HealthField {
+        field: "pressure",
+        apple_health_type: "BarometricPressure",
+        google_fit_type: "com.aranet.pressure",
+        unit: "hPa",
+    },
+    HealthField {
+        field: "radon",
+        apple_health_type: "Radon",
+        google_fit_type: "com.aranet.radon",
+        unit: "Bq/m^3",
+    },
+    HealthField {
+        field: "radiation_rate",
+        apple_health_type: "RadiationDoseRate",
+        google_fit_type: "com.aranet.radiation_rate",
+        unit: "uSv/hr",
+    },
+];
+
+/// Source name reported on every sample.
+const SOURCE_NAME: &str = "Aranet";
+
+/// Render history records in a health app format.
+pub fn export_health(
+    records: &[StoredHistoryRecord],
+    format: HealthExportFormat,
+) -> Result<String> {
+    match format {
+        HealthExportFormat::AppleHealthXml => Ok(apple_health_xml(records)),
+        HealthExportFormat::AppleHealthCsv => apple_health_csv(records),
+        HealthExportFormat::GoogleFitCsv => google_fit_csv(records),
+    }
+}
+
+/// The measured value of `field` in `record`, or `None` if the device does
+/// not measure it.
+fn field_value(record: &StoredHistoryRecord, field: &HealthField) -> Option<String> {
+    match field.field {
+        "co2" => (record.co2 > 0).then(|| record.co2.to_string()),
+        "temperature" => Some(format!("{:.1}", record.temperature)),
+        "humidity" => (record.humidity > 0).then(|| record.humidity.to_string()),
+        "pressure" => (record.pressure > 0.0).then(|| format!("{:.2}", record.pressure)),
+        "radon" => record.radon.map(|r| r.to_string()),
+        "radiation_rate" => record.radiation_rate.map(|r| format!("{:.4}", r)),
+        _ => None,
+    }
+}
+
+/// Every (record, field, value) sample, in record then mapping order.
+fn samples(
+    records: &[StoredHistoryRecord],
+) -> impl Iterator<Item = (&StoredHistoryRecord, &'static HealthField, String)> {
+    records.iter().flat_map(|record| {
+        HEALTH_FIELD_MAPPING
+            .iter()
+            .filter_map(move |field| field_value(record, field).map(|v| (record, field, v)))
+    })
+}
+
+/// Apple Health's date format, e.g. `2024-01-15 10:30:00 +0000`.
+fn apple_date(ts: OffsetDateTime) -> String {
+    ts.format(format_description!(
+        "[year]-[month]-[day] [hour]:[minute]:[second] [offset_hour sign:mandatory][offset_minute]"
+    ))
+    .unwrap_or_default()
+}
+
+fn apple_health_xml(records: &[StoredHistoryRecord]) -> String {
+    let mut xml =
+        String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<HealthData locale=\"en_US\">\n");
+    for (record, field, value) in samples(records) {
+        let date = apple_date(record.timestamp);
+        let _ = writeln!(
+            xml,
+            "  <Record type=\"{}\" sourceName=\"{}\" device=\"{}\" unit=\"{}\" value=\"{}\" \
+             creationDate=\"{}\" startDate=\"{date}\" endDate=\"{date}\"/>",
+            field.apple_health_type,
+            SOURCE_NAME,
+            xml_escape(&record.device_id),
+            xml_escape(field.unit),
+            value,
+            apple_date(record.synced_at),
+        );
+    }
+    xml.push_str("</HealthData>\n");
+    xml
+}
+
+fn apple_health_csv(records: &[StoredHistoryRecord]) -> Result<String> {
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+    write_csv(
+        &mut wtr,
+        [
+            "type",
+            "sourceName",
+            "device",
+            "unit",
+            "value",
+            "startDate",
+            "endDate",
+        ],
+    )?;
+    for (record, field, value) in samples(records) {
+        let date = apple_date(record.timestamp);
+        write_csv(
+            &mut wtr,
+            [
+                field.apple_health_type,
+                SOURCE_NAME,
+                &record.device_id,
+                field.unit,
+                &value,
+                &date,
+                &date,
+            ],
+        )?;
+    }
+    finish_csv(wtr)
+}
+
+fn google_fit_csv(records: &[StoredHistoryRecord]) -> Result<String> {
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+    write_csv(
+        &mut wtr,
+        [
+            "dataTypeName",
+            "startTimeNanos",
+            "endTimeNanos",
+            "value",
+            "unit",
+            "originDataSourceId",
+        ],
+    )?;
+    for (record, field, value) in samples(records) {
+        let nanos = record.timestamp.unix_timestamp_nanos().to_string();
+        let source = format!("raw:{}:{}", field.google_fit_type, record.device_id);
+        write_csv(
+            &mut wtr,
+            [
+                field.google_fit_type,
+                &nanos,
+                &nanos,
+                &value,
+                field.unit,
+                &source,
+            ],
+        )?;
+    }
+    finish_csv(wtr)
+}
+
+fn write_csv<const N: usize>(wtr: &mut csv::Writer<Vec<u8>>, record: [&str; N]) -> Result<()> {
+    wtr.write_record(record)
+        .map_err(|e| Error::Io(std::io::Error::other(e)))
+}
+
+fn finish_csv(wtr: csv::Writer<Vec<u8>>) -> Result<String> {
+    let bytes = wtr
+        .into_inner()
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    String::from_utf8(bytes).map_err(|e| Error::Io(std::io::Error::other(e)))
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(device_id: &str, co2: u16) -> StoredHistoryRecord {
+        StoredHistoryRecord {
+            id: 1,
+            device_id: device_id.to_string(),
+            timestamp: OffsetDateTime::from_unix_timestamp(1_705_314_600).unwrap(),
+            synced_at: OffsetDateTime::from_unix_timestamp(1_705_318_200).unwrap(),
+            co2,
+            temperature: 21.5,
+            pressure: 1013.2,
+            humidity: 40,
+            radon: None,
+            radiation_rate: None,
+            radiation_total: None,
+        }
+    }
+
+    #[test]
+    fn test_apple_health_xml() {
+        let xml = export_health(
+            &[record("Aranet4 <1>", 800)],
+            HealthExportFormat::AppleHealthXml,
+        )
+        .unwrap();
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains(
+            "<Record type=\"CarbonDioxide\" sourceName=\"Aranet\" device=\"Aranet4 &lt;1&gt;\" \
+             unit=\"ppm\" value=\"800\" creationDate=\"2024-01-15 11:30:00 +0000\" \
+             startDate=\"2024-01-15 10:30:00 +0000\" endDate=\"2024-01-15 10:30:00 +0000\"/>"
+        ));
+        assert_eq!(xml.matches("<Record ").count(), 4);
+        assert!(xml.trim_end().ends_with("</HealthData>"));
+    }
+
+    #[test]
+    fn test_apple_health_csv_skips_unmeasured_fields() {
+        // Aranet2 has no CO2 sensor
+        let csv =
+            export_health(&[record("Aranet2", 0)], HealthExportFormat::AppleHealthCsv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "type,sourceName,device,unit,value,startDate,endDate"
+        );
+        assert_eq!(lines.len(), 4);
+        assert!(!csv.contains("CarbonDioxide"));
+        assert!(lines[1].starts_with("AmbientTemperature,Aranet,Aranet2,degC,21.5,"));
+    }
+
+    #[test]
+    fn test_google_fit_csv() {
+        let csv =
+            export_health(&[record("Aranet4", 650)], HealthExportFormat::GoogleFitCsv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("dataTypeName,startTimeNanos,endTimeNanos,value,unit,originDataSourceId")
+        );
+        assert_eq!(
+            lines.next(),
+            Some(
+                "com.aranet.co2,1705314600000000000,1705314600000000000,650,ppm,\
+                 raw:com.aranet.co2:Aranet4"
+            )
+        );
+    }
+}
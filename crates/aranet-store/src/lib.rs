@@ -10,8 +10,13 @@
 //! - Store current readings with timestamps
 //! - Cache history records (avoid re-downloading from device)
 //! - Incremental sync tracking per device
+//! - Per-day statistics cache for fast long-range summaries
 //! - Query by device, time range, with pagination
-//! - Export/import support
+//! - Read-only SQL over predefined views for custom analytics
+//! - Export/import support, including Apple Health and Google Fit formats
+//!   and single-device bundles for moving a sensor between machines
+//! - An [`asynchronous::Store`] that keeps SQLite off the async runtime threads
+//! - A `StoreSink` for aranet-core reading pipelines (`sink` feature)
 //!
 //! # Example
 //!
@@ -28,16 +33,36 @@
 //! # Ok::<(), aranet_store::Error>(())
 //! ```
 
+pub mod asynchronous;
 mod error;
+mod filter;
+pub mod health;
 mod models;
 mod queries;
 mod schema;
+#[cfg(feature = "sink")]
+pub mod sink;
 mod store;
 
 pub use error::{Error, Result};
-pub use models::{StoredDevice, StoredHistoryRecord, StoredReading, SyncState};
-pub use queries::{HistoryQuery, ReadingQuery};
-pub use store::{HistoryAggregates, HistoryStats, ImportResult, Store};
+pub use filter::{FILTER_FIELDS, MAX_FILTER_CONDITIONS, MAX_FILTER_LENGTH, ValueFilter};
+pub use health::HealthExportFormat;
+pub use models::{
+    ALERT_BATTERY_LOW, ALERT_CO2, ALERT_OFFLINE, ALERT_RADIATION, ALERT_RADON, ReadingQuality,
+    SETTING_BLUETOOTH_RANGE, SETTING_INTERVAL, SETTING_SMART_HOME, StoredAdvertisement,
+    StoredAlert, StoredAnnotation, StoredChannelValue, StoredClockDrift, StoredDevice,
+    StoredHistoryRecord, StoredReading, StoredSettingChange, SyncState,
+};
+pub use queries::{
+    AdvertisementQuery, AlertQuery, AnnotationQuery, ChannelQuery, HistoryQuery, ReadingQuery,
+};
+pub use store::{
+    ADVERTISEMENT_LOG_MAX_ROWS, AnnotatedHistory, DEVICE_BUNDLE_VERSION, DailyStats, DatabaseSize,
+    DeviceBundle, DeviceCacheStats, DeviceDataCounts, ExposureStats, HISTORY_DUPLICATE_WINDOW_SECS,
+    HistoryAggregates, HistoryStats, ImportPreview, ImportResult, IntegrityReport,
+    MeasurementCoverage, QUERY_MAX_ROWS, QUERY_TIMEOUT, QUERY_VIEWS, QueryResult, QueryView,
+    READING_DUPLICATE_WINDOW_SECS, RolloverSummary, Store, ThresholdBuckets,
+};
 
 /// Default database path following platform conventions.
 ///
@@ -4,8 +4,11 @@
 //!
 //! - [`StoredDevice`] - Device metadata and tracking information
 //! - [`StoredReading`] - Current/real-time sensor readings with database IDs
+//! - [`ReadingQuality`] - Validation flag stored with each reading
 //! - [`StoredHistoryRecord`] - Historical readings downloaded from device memory
 //! - [`SyncState`] - Tracks incremental history sync progress
+//! - [`StoredAnnotation`] - User notes on a point in time or time range
+//! - [`StoredChannelValue`] - Values of computed channels such as dew point
 //!
 //! All types implement `Serialize` and `Deserialize` for easy JSON export/import.
 
@@ -102,6 +105,60 @@ pub struct StoredReading {
     pub radon_avg_7d: Option<u32>,
     /// 30-day average radon concentration in Bq/m³ (radon devices only).
     pub radon_avg_30d: Option<u32>,
+    /// Validation flag. Readings stored without one are
+    /// [`Unchecked`](ReadingQuality::Unchecked).
+    #[serde(default)]
+    pub quality: ReadingQuality,
+    /// Measurement counter from the Smart Home advertisement the reading
+    /// came from. `None` for GATT reads.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counter: Option<u8>,
+}
+
+/// Data quality flag of a stored reading.
+///
+/// Set on insert with [`Store::insert_reading_with_quality`](crate::Store::insert_reading_with_quality)
+/// or afterwards with [`Store::set_reading_quality`](crate::Store::set_reading_quality).
+/// Flagged readings are kept until removed with
+/// [`Store::delete_readings_where`](crate::Store::delete_readings_where), and
+/// queries can skip them with [`ReadingQuery::exclude_invalid`](crate::ReadingQuery::exclude_invalid).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadingQuality {
+    /// Not validated, e.g. stored before quality flags existed.
+    #[default]
+    Unchecked,
+    /// Passed validation.
+    Valid,
+    /// Plausible but unusual: validation raised warnings.
+    Suspect,
+    /// Known bad: out of range, a spike, or a stuck sensor.
+    Invalid,
+}
+
+impl ReadingQuality {
+    /// All quality flags.
+    pub const ALL: [ReadingQuality; 4] = [
+        ReadingQuality::Unchecked,
+        ReadingQuality::Valid,
+        ReadingQuality::Suspect,
+        ReadingQuality::Invalid,
+    ];
+
+    /// Name as stored in the database and used in JSON.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReadingQuality::Unchecked => "unchecked",
+            ReadingQuality::Valid => "valid",
+            ReadingQuality::Suspect => "suspect",
+            ReadingQuality::Invalid => "invalid",
+        }
+    }
+
+    /// Parse a name produced by [`as_str`](Self::as_str).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|q| q.as_str() == name)
+    }
 }
 
 impl StoredReading {
@@ -123,6 +180,8 @@ impl StoredReading {
             radon_avg_24h: reading.radon_avg_24h,
             radon_avg_7d: reading.radon_avg_7d,
             radon_avg_30d: reading.radon_avg_30d,
+            quality: ReadingQuality::Unchecked,
+            counter: None,
         }
     }
 
@@ -173,8 +232,10 @@ impl StoredReading {
 /// downloads. The `timestamp` is the original measurement time from the device,
 /// while `synced_at` tracks when it was downloaded to this database.
 ///
-/// Records are deduplicated by `(device_id, timestamp)` - downloading the same
-/// record twice will not create duplicates.
+/// Records are deduplicated by `(device_id, timestamp)` and by content: each
+/// row stores a hash of its values, and a record whose hash matches one
+/// stored a few seconds away is the same measurement with a shifted
+/// timestamp. Downloading the same record twice will not create duplicates.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoredHistoryRecord {
     /// Database row ID.
@@ -247,6 +308,41 @@ impl StoredHistoryRecord {
     }
 }
 
+/// Content hash of a history record's measured values, as hex.
+///
+/// The timestamp is left out: it is derived from the sync time and the
+/// record's age, so the same record downloaded twice can land a second or
+/// two apart. FNV-1a keeps the value stable across builds and platforms.
+pub(crate) fn history_hash(record: &HistoryRecord) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut bytes = Vec::with_capacity(40);
+    bytes.extend_from_slice(&record.co2.to_le_bytes());
+    bytes.extend_from_slice(&record.temperature.to_bits().to_le_bytes());
+    bytes.extend_from_slice(&record.pressure.to_bits().to_le_bytes());
+    bytes.push(record.humidity);
+    // A presence byte per optional value keeps `None` distinct from zero
+    for value in [
+        record.radon.map(u64::from),
+        record.radiation_rate.map(|v| u64::from(v.to_bits())),
+        record.radiation_total.map(f64::to_bits),
+    ] {
+        match value {
+            Some(v) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&v.to_le_bytes());
+            }
+            None => bytes.push(0),
+        }
+    }
+
+    let hash = bytes.iter().fold(OFFSET, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    });
+    format!("{hash:016x}")
+}
+
 /// Tracks incremental sync progress for a device's history.
 ///
 /// Aranet devices use a ring buffer for history storage, with a 1-based index.
@@ -293,6 +389,196 @@ pub struct SyncState {
     pub last_sync_at: Option<OffsetDateTime>,
 }
 
+/// Latest clock drift estimate for a device.
+///
+/// Positive drift means the device clock runs fast. See
+/// `aranet_core::clock` for how it is measured.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredClockDrift {
+    /// Device identifier.
+    pub device_id: String,
+    /// Drift rate in parts per million.
+    pub drift_ppm: f64,
+    /// Length of the observation window the estimate is based on.
+    pub span_seconds: i64,
+    /// When the estimate was recorded.
+    #[serde(with = "time::serde::rfc3339")]
+    pub measured_at: OffsetDateTime,
+}
+
+/// Setting name for the measurement interval, recorded in seconds.
+pub const SETTING_INTERVAL: &str = "interval";
+/// Setting name for the Bluetooth range, recorded as `standard` or `extended`.
+pub const SETTING_BLUETOOTH_RANGE: &str = "bluetooth_range";
+/// Setting name for Smart Home integration, recorded as `true` or `false`.
+pub const SETTING_SMART_HOME: &str = "smart_home";
+
+/// A recorded change to a device setting.
+///
+/// Written by [`Store::record_setting`](crate::Store::record_setting) and
+/// returned by [`Store::settings_history`](crate::Store::settings_history).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredSettingChange {
+    /// Database row ID.
+    pub id: i64,
+    /// Device identifier.
+    pub device_id: String,
+    /// Setting name, e.g. [`SETTING_INTERVAL`](crate::SETTING_INTERVAL).
+    pub setting: String,
+    /// Previously recorded value, or `None` for the first record.
+    pub old_value: Option<String>,
+    /// New value.
+    pub new_value: String,
+    /// When the change was observed.
+    #[serde(with = "time::serde::rfc3339")]
+    pub changed_at: OffsetDateTime,
+}
+
+/// Alert kind for CO2 threshold crossings.
+pub const ALERT_CO2: &str = "co2";
+/// Alert kind for radon threshold crossings.
+pub const ALERT_RADON: &str = "radon";
+/// Alert kind for radiation threshold crossings.
+pub const ALERT_RADIATION: &str = "radiation";
+/// Alert kind for low battery warnings.
+pub const ALERT_BATTERY_LOW: &str = "battery_low";
+/// Alert kind for a device that stopped responding.
+pub const ALERT_OFFLINE: &str = "offline";
+
+/// A recorded alert.
+///
+/// Written by [`Store::insert_alert`](crate::Store::insert_alert) and
+/// returned by [`Store::query_alerts`](crate::Store::query_alerts).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredAlert {
+    /// Database row ID.
+    pub id: i64,
+    /// Device identifier.
+    pub device_id: String,
+    /// Alert kind, e.g. [`ALERT_CO2`](crate::ALERT_CO2).
+    pub kind: String,
+    /// Severity as recorded by the client (`info`, `warning` or `critical`).
+    pub severity: String,
+    /// The measurement that triggered the alert, if any.
+    pub value: Option<f64>,
+    /// Human-readable description.
+    pub message: String,
+    /// When the alert was raised.
+    #[serde(with = "time::serde::rfc3339")]
+    pub triggered_at: OffsetDateTime,
+    /// Whether the user has acknowledged the alert.
+    pub acknowledged: bool,
+}
+
+/// A user note attached to a point in time or a time range of a device's data.
+///
+/// Written by [`Store::add_annotation`](crate::Store::add_annotation) and
+/// returned by [`Store::query_annotations`](crate::Store::query_annotations)
+/// and [`Store::query_history_annotated`](crate::Store::query_history_annotated).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredAnnotation {
+    /// Database row ID.
+    pub id: i64,
+    /// Device identifier.
+    pub device_id: String,
+    /// Start of the annotated range, or the annotated point in time.
+    #[serde(with = "time::serde::rfc3339")]
+    pub start: OffsetDateTime,
+    /// End of the annotated range, or `None` for a point in time.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub end: Option<OffsetDateTime>,
+    /// The note, e.g. "window opened".
+    pub text: String,
+    /// Optional free-form tag for grouping, e.g. `ventilation`.
+    pub tag: Option<String>,
+    /// When the annotation was created.
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl StoredAnnotation {
+    /// Whether this annotation marks a single point in time.
+    pub fn is_point(&self) -> bool {
+        self.end.is_none()
+    }
+
+    /// Whether the annotation overlaps the inclusive range `start..=end`.
+    pub fn overlaps(&self, start: OffsetDateTime, end: OffsetDateTime) -> bool {
+        self.start <= end && self.end.unwrap_or(self.start) >= start
+    }
+}
+
+/// A value of a computed channel, such as dew point, derived from a reading.
+///
+/// Written by [`Store::insert_channel_value`](crate::Store::insert_channel_value)
+/// and returned by [`Store::query_channel_values`](crate::Store::query_channel_values).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredChannelValue {
+    /// Database row ID.
+    pub id: i64,
+    /// Device the channel belongs to.
+    pub device_id: String,
+    /// Channel name, e.g. `dew_point`.
+    pub channel: String,
+    /// When the reading the value was computed from was captured.
+    #[serde(with = "time::serde::rfc3339")]
+    pub captured_at: OffsetDateTime,
+    /// The computed value.
+    pub value: f64,
+}
+
+/// A raw BLE advertisement from the opt-in advertisement log.
+///
+/// Written by [`Store::log_advertisement`](crate::Store::log_advertisement)
+/// and returned by [`Store::query_advertisements`](crate::Store::query_advertisements).
+/// The payload is the manufacturer data as received, serialized as hex.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredAdvertisement {
+    /// Database row ID.
+    pub id: i64,
+    /// Identifier of the advertising device.
+    pub device_id: String,
+    /// When the advertisement was received.
+    #[serde(with = "time::serde::rfc3339")]
+    pub seen_at: OffsetDateTime,
+    /// Signal strength in dBm, if reported.
+    pub rssi: Option<i16>,
+    /// Raw manufacturer data bytes.
+    #[serde(with = "hex_bytes")]
+    pub payload: Vec<u8>,
+}
+
+impl StoredAdvertisement {
+    /// The payload as lowercase hex, e.g. `"2102040113"`.
+    pub fn payload_hex(&self) -> String {
+        hex_bytes::encode(&self.payload)
+    }
+}
+
+/// Serde helpers for byte payloads stored as hex strings.
+mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn encode(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        if hex.len() % 2 != 0 {
+            return Err(D::Error::custom("hex payload has an odd number of digits"));
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
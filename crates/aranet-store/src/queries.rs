@@ -1,4 +1,4 @@
-//! Query builders for readings and history.
+//! Query builders for readings, history and alerts.
 //!
 //! This module provides fluent query builders for filtering and paginating
 //! stored sensor data. Both [`ReadingQuery`] and [`HistoryQuery`] follow
@@ -33,6 +33,9 @@
 
 use time::OffsetDateTime;
 
+use crate::filter::ValueFilter;
+use crate::models::ReadingQuality;
+
 /// Maximum allowed limit for queries to prevent DoS via large result sets.
 /// This caps LIMIT values to prevent memory exhaustion attacks.
 pub const MAX_QUERY_LIMIT: u32 = 1_000_000;
@@ -74,6 +77,8 @@ pub const MAX_QUERY_LIMIT: u32 = 1_000_000;
 pub struct ReadingQuery {
     /// Filter by device ID.
     pub device_id: Option<String>,
+    /// Skip readings from these devices.
+    pub exclude_devices: Vec<String>,
     /// Filter readings after this time.
     pub since: Option<OffsetDateTime>,
     /// Filter readings before this time.
@@ -84,6 +89,12 @@ pub struct ReadingQuery {
     pub offset: Option<u32>,
     /// Order by captured_at descending (newest first).
     pub newest_first: bool,
+    /// Only include readings with this quality flag.
+    pub quality: Option<ReadingQuality>,
+    /// Skip readings flagged [`ReadingQuality::Invalid`].
+    pub exclude_invalid: bool,
+    /// Only include readings matching this filter expression.
+    pub filter: Option<ValueFilter>,
 }
 
 impl ReadingQuery {
@@ -109,6 +120,14 @@ impl ReadingQuery {
         self
     }
 
+    /// Skip readings from a device.
+    ///
+    /// May be called repeatedly to skip several devices.
+    pub fn exclude_device(mut self, device_id: &str) -> Self {
+        self.exclude_devices.push(device_id.to_string());
+        self
+    }
+
     /// Filter to readings captured at or after this time.
     ///
     /// Useful for querying "last N hours" or "since last sync".
@@ -154,6 +173,28 @@ impl ReadingQuery {
         self
     }
 
+    /// Filter to readings with the given quality flag.
+    ///
+    /// Combine with [`Store::delete_readings_where`](crate::Store::delete_readings_where)
+    /// to remove flagged readings in bulk.
+    pub fn quality(mut self, quality: ReadingQuality) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    /// Skip readings flagged [`ReadingQuality::Invalid`], e.g. for charts and
+    /// exports.
+    pub fn exclude_invalid(mut self) -> Self {
+        self.exclude_invalid = true;
+        self
+    }
+
+    /// Filter to readings matching an expression such as `co2 > 1000`.
+    pub fn filter(mut self, filter: ValueFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
     /// Build the SQL WHERE clause and parameters.
     pub(crate) fn build_where(&self) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
         let mut conditions = Vec::new();
@@ -164,6 +205,19 @@ impl ReadingQuery {
             params.push(Box::new(device_id.clone()));
         }
 
+        let excluded = (!self.exclude_devices.is_empty()).then(|| {
+            format!(
+                "device_id NOT IN ({})",
+                vec!["?"; self.exclude_devices.len()].join(", ")
+            )
+        });
+        if let Some(excluded) = &excluded {
+            conditions.push(excluded);
+            for device_id in &self.exclude_devices {
+                params.push(Box::new(device_id.clone()));
+            }
+        }
+
         if let Some(since) = self.since {
             conditions.push("captured_at >= ?");
             params.push(Box::new(since.unix_timestamp()));
@@ -174,6 +228,24 @@ impl ReadingQuery {
             params.push(Box::new(until.unix_timestamp()));
         }
 
+        if let Some(quality) = self.quality {
+            conditions.push("quality = ?");
+            params.push(Box::new(quality.as_str()));
+        }
+
+        if self.exclude_invalid {
+            conditions.push("quality != ?");
+            params.push(Box::new(ReadingQuality::Invalid.as_str()));
+        }
+
+        let filter = self.filter.as_ref().map(ValueFilter::to_sql);
+        if let Some((sql, values)) = &filter {
+            conditions.push(sql);
+            for value in values {
+                params.push(Box::new(*value));
+            }
+        }
+
         let where_clause = if conditions.is_empty() {
             String::new()
         } else {
@@ -184,16 +256,23 @@ impl ReadingQuery {
     }
 
     /// Build the full SQL query.
+    #[cfg(test)]
     pub(crate) fn build_sql(&self) -> String {
+        self.build_sql_from("readings")
+    }
+
+    /// Build the full SQL query against `table` (the readings table or a
+    /// view with the same columns).
+    pub(crate) fn build_sql_from(&self, table: &str) -> String {
         let (where_clause, _) = self.build_where();
         let order = if self.newest_first { "DESC" } else { "ASC" };
 
         let mut sql = format!(
             "SELECT id, device_id, captured_at, co2, temperature, pressure, humidity, \
              battery, status, radon, radiation_rate, radiation_total, \
-             radon_avg_24h, radon_avg_7d, radon_avg_30d \
-             FROM readings {} ORDER BY captured_at {}",
-            where_clause, order
+             radon_avg_24h, radon_avg_7d, radon_avg_30d, quality, counter \
+             FROM {} {} ORDER BY captured_at {}, id {}",
+            table, where_clause, order, order
         );
 
         if let Some(limit) = self.limit {
@@ -251,6 +330,8 @@ pub struct HistoryQuery {
     pub offset: Option<u32>,
     /// If true, order by timestamp descending (newest first). Default: true.
     pub newest_first: bool,
+    /// Only include records matching this filter expression.
+    pub filter: Option<ValueFilter>,
 }
 
 impl HistoryQuery {
@@ -321,6 +402,17 @@ impl HistoryQuery {
         self
     }
 
+    /// Filter to records matching an expression such as
+    /// `co2 > 1000 AND humidity < 30`.
+    ///
+    /// Applies to queries and exports of history rows and to
+    /// [`Store::history_stats`](crate::Store::history_stats); the pre-computed
+    /// daily and exposure summaries ignore it.
+    pub fn filter(mut self, filter: ValueFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
     /// Build the SQL WHERE clause and parameters.
     pub(crate) fn build_where(&self) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
         let mut conditions = Vec::new();
@@ -341,6 +433,14 @@ impl HistoryQuery {
             params.push(Box::new(until.unix_timestamp()));
         }
 
+        let filter = self.filter.as_ref().map(ValueFilter::to_sql);
+        if let Some((sql, values)) = &filter {
+            conditions.push(sql);
+            for value in values {
+                params.push(Box::new(*value));
+            }
+        }
+
         let where_clause = if conditions.is_empty() {
             String::new()
         } else {
@@ -368,12 +468,386 @@ impl HistoryQuery {
         sql
     }
 
-    /// Build the full SQL query for history rows.
-    pub(crate) fn build_sql(&self) -> String {
-        self.build_sql_with_select(
+    /// Build the full SQL query for history rows in `table` (the history
+    /// table or a view with the same columns).
+    pub(crate) fn build_sql_from(&self, table: &str) -> String {
+        self.build_sql_with_select(&format!(
             "SELECT id, device_id, timestamp, synced_at, co2, temperature, pressure, \
-             humidity, radon, radiation_rate, radiation_total FROM history",
-        )
+             humidity, radon, radiation_rate, radiation_total FROM {table}"
+        ))
+    }
+}
+
+/// Fluent query builder for stored alerts.
+///
+/// Use this to construct queries for [`Store::query_alerts`](crate::Store::query_alerts).
+/// Results are always ordered newest first.
+///
+/// # Example
+///
+/// ```
+/// use aranet_store::{AlertQuery, ALERT_CO2};
+///
+/// let query = AlertQuery::new()
+///     .device("Aranet4 17C3C")
+///     .kind(ALERT_CO2)
+///     .unacknowledged()
+///     .limit(50);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct AlertQuery {
+    /// Filter by device ID.
+    pub device_id: Option<String>,
+    /// Filter by alert kind.
+    pub kind: Option<String>,
+    /// Filter by severity.
+    pub severity: Option<String>,
+    /// Only include alerts that have not been acknowledged.
+    pub unacknowledged_only: bool,
+    /// Filter alerts raised at or after this time.
+    pub since: Option<OffsetDateTime>,
+    /// Maximum number of results.
+    pub limit: Option<u32>,
+}
+
+impl AlertQuery {
+    /// Create a new query matching all alerts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filter by device ID.
+    pub fn device(mut self, device_id: &str) -> Self {
+        self.device_id = Some(device_id.to_string());
+        self
+    }
+
+    /// Filter by alert kind, e.g. [`ALERT_OFFLINE`](crate::ALERT_OFFLINE).
+    pub fn kind(mut self, kind: &str) -> Self {
+        self.kind = Some(kind.to_string());
+        self
+    }
+
+    /// Filter by severity (`info`, `warning` or `critical`).
+    pub fn severity(mut self, severity: &str) -> Self {
+        self.severity = Some(severity.to_string());
+        self
+    }
+
+    /// Only include alerts that have not been acknowledged.
+    pub fn unacknowledged(mut self) -> Self {
+        self.unacknowledged_only = true;
+        self
+    }
+
+    /// Filter to alerts raised at or after this time.
+    pub fn since(mut self, time: OffsetDateTime) -> Self {
+        self.since = Some(time);
+        self
+    }
+
+    /// Limit the maximum number of results returned.
+    ///
+    /// Values are capped at `MAX_QUERY_LIMIT`.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit.min(MAX_QUERY_LIMIT));
+        self
+    }
+
+    /// Build the SQL WHERE clause and parameters.
+    pub(crate) fn build_where(&self) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let mut conditions = Vec::new();
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(ref device_id) = self.device_id {
+            conditions.push("device_id = ?");
+            params.push(Box::new(device_id.clone()));
+        }
+
+        if let Some(ref kind) = self.kind {
+            conditions.push("kind = ?");
+            params.push(Box::new(kind.clone()));
+        }
+
+        if let Some(ref severity) = self.severity {
+            conditions.push("severity = ?");
+            params.push(Box::new(severity.clone()));
+        }
+
+        if self.unacknowledged_only {
+            conditions.push("acknowledged = 0");
+        }
+
+        if let Some(since) = self.since {
+            conditions.push("triggered_at >= ?");
+            params.push(Box::new(since.unix_timestamp()));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        (where_clause, params)
+    }
+}
+
+/// Query builder for the raw advertisement log.
+///
+/// Use this to construct queries for
+/// [`Store::query_advertisements`](crate::Store::query_advertisements).
+/// Results are ordered oldest first, so exports read chronologically.
+#[derive(Debug, Default, Clone)]
+pub struct AdvertisementQuery {
+    /// Filter by device ID.
+    pub device_id: Option<String>,
+    /// Filter advertisements received at or after this time.
+    pub since: Option<OffsetDateTime>,
+    /// Filter advertisements received at or before this time.
+    pub until: Option<OffsetDateTime>,
+    /// Maximum number of results.
+    pub limit: Option<u32>,
+}
+
+impl AdvertisementQuery {
+    /// Create a new query matching all logged advertisements.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filter by device ID.
+    pub fn device(mut self, device_id: &str) -> Self {
+        self.device_id = Some(device_id.to_string());
+        self
+    }
+
+    /// Filter to advertisements received at or after this time.
+    pub fn since(mut self, time: OffsetDateTime) -> Self {
+        self.since = Some(time);
+        self
+    }
+
+    /// Filter to advertisements received at or before this time.
+    pub fn until(mut self, time: OffsetDateTime) -> Self {
+        self.until = Some(time);
+        self
+    }
+
+    /// Limit the maximum number of results returned.
+    ///
+    /// Values are capped at `MAX_QUERY_LIMIT`.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit.min(MAX_QUERY_LIMIT));
+        self
+    }
+
+    /// Build the SQL WHERE clause and parameters.
+    pub(crate) fn build_where(&self) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let mut conditions = Vec::new();
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(ref device_id) = self.device_id {
+            conditions.push("device_id = ?");
+            params.push(Box::new(device_id.clone()));
+        }
+
+        if let Some(since) = self.since {
+            conditions.push("seen_at >= ?");
+            params.push(Box::new(since.unix_timestamp()));
+        }
+
+        if let Some(until) = self.until {
+            conditions.push("seen_at <= ?");
+            params.push(Box::new(until.unix_timestamp()));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        (where_clause, params)
+    }
+}
+
+/// Query builder for annotations.
+///
+/// Time filters select annotations that overlap the range, so a range
+/// annotation that started before `since` is still returned.
+#[derive(Debug, Clone, Default)]
+pub struct AnnotationQuery {
+    /// Filter by device ID.
+    pub device_id: Option<String>,
+    /// Filter by tag.
+    pub tag: Option<String>,
+    /// Include only annotations ending at or after this time.
+    pub since: Option<OffsetDateTime>,
+    /// Include only annotations starting at or before this time.
+    pub until: Option<OffsetDateTime>,
+    /// Maximum number of results.
+    pub limit: Option<u32>,
+}
+
+impl AnnotationQuery {
+    /// Create a new query matching all annotations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filter by device ID.
+    pub fn device(mut self, device_id: &str) -> Self {
+        self.device_id = Some(device_id.to_string());
+        self
+    }
+
+    /// Filter by tag.
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tag = Some(tag.to_string());
+        self
+    }
+
+    /// Filter to annotations ending at or after this time.
+    pub fn since(mut self, time: OffsetDateTime) -> Self {
+        self.since = Some(time);
+        self
+    }
+
+    /// Filter to annotations starting at or before this time.
+    pub fn until(mut self, time: OffsetDateTime) -> Self {
+        self.until = Some(time);
+        self
+    }
+
+    /// Limit the maximum number of results returned.
+    ///
+    /// Values are capped at `MAX_QUERY_LIMIT`.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit.min(MAX_QUERY_LIMIT));
+        self
+    }
+
+    /// Build the SQL WHERE clause and parameters.
+    pub(crate) fn build_where(&self) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let mut conditions = Vec::new();
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(ref device_id) = self.device_id {
+            conditions.push("device_id = ?");
+            params.push(Box::new(device_id.clone()));
+        }
+
+        if let Some(ref tag) = self.tag {
+            conditions.push("tag = ?");
+            params.push(Box::new(tag.clone()));
+        }
+
+        if let Some(since) = self.since {
+            conditions.push("COALESCE(end_at, start_at) >= ?");
+            params.push(Box::new(since.unix_timestamp()));
+        }
+
+        if let Some(until) = self.until {
+            conditions.push("start_at <= ?");
+            params.push(Box::new(until.unix_timestamp()));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        (where_clause, params)
+    }
+}
+
+/// Query builder for computed channel values.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelQuery {
+    /// Filter by device ID.
+    pub device_id: Option<String>,
+    /// Filter by channel name.
+    pub channel: Option<String>,
+    /// Include only values captured at or after this time.
+    pub since: Option<OffsetDateTime>,
+    /// Include only values captured at or before this time.
+    pub until: Option<OffsetDateTime>,
+    /// Maximum number of results.
+    pub limit: Option<u32>,
+}
+
+impl ChannelQuery {
+    /// Create a new query matching all channel values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filter by device ID.
+    pub fn device(mut self, device_id: &str) -> Self {
+        self.device_id = Some(device_id.to_string());
+        self
+    }
+
+    /// Filter by channel name.
+    pub fn channel(mut self, channel: &str) -> Self {
+        self.channel = Some(channel.to_string());
+        self
+    }
+
+    /// Filter to values captured at or after this time.
+    pub fn since(mut self, time: OffsetDateTime) -> Self {
+        self.since = Some(time);
+        self
+    }
+
+    /// Filter to values captured at or before this time.
+    pub fn until(mut self, time: OffsetDateTime) -> Self {
+        self.until = Some(time);
+        self
+    }
+
+    /// Limit the maximum number of results returned.
+    ///
+    /// Values are capped at `MAX_QUERY_LIMIT`.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit.min(MAX_QUERY_LIMIT));
+        self
+    }
+
+    /// Build the SQL WHERE clause and parameters.
+    pub(crate) fn build_where(&self) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let mut conditions = Vec::new();
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(ref device_id) = self.device_id {
+            conditions.push("device_id = ?");
+            params.push(Box::new(device_id.clone()));
+        }
+
+        if let Some(ref channel) = self.channel {
+            conditions.push("channel = ?");
+            params.push(Box::new(channel.clone()));
+        }
+
+        if let Some(since) = self.since {
+            conditions.push("captured_at >= ?");
+            params.push(Box::new(since.unix_timestamp()));
+        }
+
+        if let Some(until) = self.until {
+            conditions.push("captured_at <= ?");
+            params.push(Box::new(until.unix_timestamp()));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        (where_clause, params)
     }
 }
 
@@ -480,6 +954,16 @@ mod tests {
         assert_eq!(params.len(), 1);
     }
 
+    #[test]
+    fn test_reading_query_build_where_excluded_devices() {
+        let query = ReadingQuery::new()
+            .exclude_device("bedroom")
+            .exclude_device("nursery");
+        let (where_clause, params) = query.build_where();
+        assert_eq!(where_clause, "WHERE device_id NOT IN (?, ?)");
+        assert_eq!(params.len(), 2);
+    }
+
     #[test]
     fn test_reading_query_build_where_time_range() {
         let since = datetime!(2024-01-01 00:00:00 UTC);
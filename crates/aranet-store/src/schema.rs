@@ -1,11 +1,13 @@
 //! Database schema and migrations.
 
+use aranet_types::HistoryRecord;
 use rusqlite::Connection;
 
 use crate::error::Result;
+use crate::models::history_hash;
 
 /// Current schema version.
-pub const SCHEMA_VERSION: i32 = 3;
+pub const SCHEMA_VERSION: i32 = 14;
 
 /// Initialize the database schema.
 pub fn initialize(conn: &Connection) -> Result<()> {
@@ -15,6 +17,17 @@ pub fn initialize(conn: &Connection) -> Result<()> {
         // Fresh database - create all tables in a single transaction
         let tx = conn.unchecked_transaction()?;
         create_schema_v1(&tx)?;
+        add_readings_quality(&tx)?;
+        add_history_hash(&tx)?;
+        add_readings_counter(&tx)?;
+        create_daily_stats_table(&tx)?;
+        create_clock_drift_table(&tx)?;
+        create_settings_history_table(&tx)?;
+        create_alerts_table(&tx)?;
+        create_annotations_table(&tx)?;
+        create_manager_state_table(&tx)?;
+        create_advertisements_table(&tx)?;
+        create_channel_values_table(&tx)?;
         set_schema_version(&tx, SCHEMA_VERSION)?;
         tx.commit()?;
     } else if version < SCHEMA_VERSION {
@@ -148,6 +161,51 @@ fn migrate(conn: &Connection, old_version: i32) -> Result<()> {
         migrate_to_v3(conn)?;
     }
 
+    if old_version < 4 {
+        migrate_to_v4(conn)?;
+    }
+
+    if old_version < 5 {
+        create_clock_drift_table(conn)?;
+    }
+
+    if old_version < 6 {
+        create_settings_history_table(conn)?;
+    }
+
+    if old_version < 7 {
+        create_alerts_table(conn)?;
+    }
+
+    if old_version < 8 {
+        create_annotations_table(conn)?;
+    }
+
+    if old_version < 9 {
+        create_manager_state_table(conn)?;
+    }
+
+    if old_version < 10 {
+        create_advertisements_table(conn)?;
+    }
+
+    if old_version < 11 {
+        add_readings_quality(conn)?;
+    }
+
+    if old_version < 12 {
+        add_history_hash(conn)?;
+        backfill_history_hash(conn)?;
+    }
+
+    if old_version < 13 {
+        add_readings_counter(conn)?;
+    }
+
+    if old_version < 14 {
+        create_channel_values_table(conn)?;
+    }
+
     if old_version > SCHEMA_VERSION {
         tracing::warn!(
             "Database schema version {} is newer than supported version {}. \
@@ -200,6 +258,339 @@ fn migrate_to_v3(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Migration to schema version 4: add the per-day statistics cache and
+/// backfill it from existing history.
+fn migrate_to_v4(conn: &Connection) -> Result<()> {
+    create_daily_stats_table(conn)?;
+    conn.execute(
+        REFRESH_DAILY_STATS_SQL,
+        rusqlite::params![None::<&str>, None::<i64>, None::<i64>],
+    )?;
+    Ok(())
+}
+
+/// Create the `clock_drift` table (schema version 5).
+///
+/// Holds the latest clock drift estimate per device, as measured by the
+/// service collector and used to correct history timestamps on import.
+fn create_clock_drift_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS clock_drift (
+            device_id TEXT PRIMARY KEY REFERENCES devices(id) ON DELETE CASCADE,
+            drift_ppm REAL NOT NULL,
+            span_seconds INTEGER NOT NULL,
+            measured_at INTEGER NOT NULL
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Create the `settings_history` table (schema version 6).
+///
+/// An audit log of device setting changes (measurement interval, Bluetooth
+/// range, Smart Home). A row is only written when a setting's value differs
+/// from the last one recorded for that device.
+fn create_settings_history_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS settings_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            device_id TEXT NOT NULL REFERENCES devices(id) ON DELETE CASCADE,
+            setting TEXT NOT NULL,
+            old_value TEXT,
+            new_value TEXT NOT NULL,
+            changed_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_settings_history_device_setting
+            ON settings_history(device_id, setting, changed_at);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Create the `alerts` table (schema version 7).
+///
+/// A persistent log of alerts raised by the GUI and TUI (threshold crossings,
+/// devices going offline, low battery), so alert history survives restarts
+/// and can be acknowledged.
+fn create_alerts_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS alerts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            device_id TEXT NOT NULL REFERENCES devices(id) ON DELETE CASCADE,
+            kind TEXT NOT NULL,
+            severity TEXT NOT NULL,
+            value REAL,
+            message TEXT NOT NULL,
+            triggered_at INTEGER NOT NULL,
+            acknowledged INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_alerts_device_time
+            ON alerts(device_id, triggered_at);
+        CREATE INDEX IF NOT EXISTS idx_alerts_triggered_at
+            ON alerts(triggered_at);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Create the `annotations` table (schema version 8).
+///
+/// User notes attached to a point in time (`end_at` is NULL) or a time range
+/// of a device's data, e.g. "window opened", for marking events on charts.
+fn create_annotations_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS annotations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            device_id TEXT NOT NULL REFERENCES devices(id) ON DELETE CASCADE,
+            start_at INTEGER NOT NULL,
+            end_at INTEGER,
+            text TEXT NOT NULL,
+            tag TEXT,
+            created_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_annotations_device_time
+            ON annotations(device_id, start_at);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Create the `manager_state` table (schema version 9).
+///
+/// A single row holding a device manager's roster as JSON, so long-running
+/// daemons can resume without rediscovering devices. Managed devices need
+/// not be in `devices`, so there is no foreign key.
+fn create_manager_state_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS manager_state (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            state TEXT NOT NULL,
+            saved_at INTEGER NOT NULL
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Create the `advertisements` table (schema version 10).
+///
+/// An opt-in log of raw BLE advertisements for offline analysis of payload
+/// formats and coverage. Only written when a client asks for it, and kept
+/// to a bounded number of rows. Advertising devices need not be in
+/// `devices`, so there is no foreign key.
+fn create_advertisements_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS advertisements (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            device_id TEXT NOT NULL,
+            seen_at INTEGER NOT NULL,
+            rssi INTEGER,
+            payload BLOB NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_advertisements_device_time
+            ON advertisements(device_id, seen_at);
+        CREATE INDEX IF NOT EXISTS idx_advertisements_seen_at
+            ON advertisements(seen_at);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Add the `quality` column to `readings` (schema version 11).
+///
+/// Holds a [`ReadingQuality`](crate::ReadingQuality) name. Existing rows
+/// become `unchecked`.
+fn add_readings_quality(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE readings ADD COLUMN quality TEXT NOT NULL DEFAULT 'unchecked';
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Add the `hash` column to `history` (schema version 12).
+///
+/// Holds the content hash of each record's values, so a record downloaded
+/// again with a slightly different timestamp is recognised as a duplicate.
+fn add_history_hash(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE history ADD COLUMN hash TEXT;
+        CREATE INDEX IF NOT EXISTS idx_history_device_hash
+            ON history(device_id, hash, timestamp);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Add the `counter` column to `readings` (schema version 13).
+///
+/// Holds the measurement counter from Smart Home advertisements. Readings
+/// without one (GATT reads, older rows) leave it `NULL`.
+fn add_readings_counter(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        ALTER TABLE readings ADD COLUMN counter INTEGER;
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Create the `channel_values` table (schema version 14).
+///
+/// Values of computed channels such as dew point, derived from a device's
+/// readings when they are collected. One row per channel per reading.
+fn create_channel_values_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS channel_values (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            device_id TEXT NOT NULL REFERENCES devices(id) ON DELETE CASCADE,
+            channel TEXT NOT NULL,
+            captured_at INTEGER NOT NULL,
+            value REAL NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_channel_values_device_channel_time
+            ON channel_values(device_id, channel, captured_at);
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Compute the content hash of every existing history row.
+///
+/// Reads the columns as they were at schema version 12, so later changes to
+/// the store's row decoding do not affect this migration. The timestamp is
+/// not part of the hash.
+fn backfill_history_hash(conn: &Connection) -> Result<()> {
+    let rows = {
+        let mut stmt = conn.prepare(
+            "SELECT id, co2, temperature, pressure, humidity, radon, radiation_rate, \
+             radiation_total FROM history",
+        )?;
+        stmt.query_map([], |row| {
+            let record = HistoryRecord {
+                timestamp: time::OffsetDateTime::UNIX_EPOCH,
+                co2: u16::try_from(row.get::<_, i64>(1)?).unwrap_or(0),
+                temperature: row.get(2)?,
+                pressure: row.get(3)?,
+                humidity: u8::try_from(row.get::<_, i64>(4)?).unwrap_or(0),
+                radon: row
+                    .get::<_, Option<i64>>(5)?
+                    .and_then(|v| u32::try_from(v).ok()),
+                radiation_rate: row.get(6)?,
+                radiation_total: row.get(7)?,
+            };
+            Ok((row.get::<_, i64>(0)?, history_hash(&record)))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?
+    };
+
+    let mut update = conn.prepare("UPDATE history SET hash = ?2 WHERE id = ?1")?;
+    for (id, hash) in rows {
+        update.execute(rusqlite::params![id, hash])?;
+    }
+    Ok(())
+}
+
+/// Create the `daily_stats` table.
+///
+/// One row per device per UTC day, holding min/max/sum per metric plus the
+/// number of records in each threshold bucket. Averages are `sum / count`.
+/// Rows are recomputed from `history` whenever records for that day are
+/// inserted, so summaries over long ranges only touch one row per day.
+fn create_daily_stats_table(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS daily_stats (
+            device_id TEXT NOT NULL REFERENCES devices(id) ON DELETE CASCADE,
+            day INTEGER NOT NULL,
+            count INTEGER NOT NULL,
+            co2_count INTEGER NOT NULL DEFAULT 0,
+            co2_min INTEGER,
+            co2_max INTEGER,
+            co2_sum INTEGER,
+            temperature_min REAL,
+            temperature_max REAL,
+            temperature_sum REAL,
+            pressure_count INTEGER NOT NULL DEFAULT 0,
+            pressure_min REAL,
+            pressure_max REAL,
+            pressure_sum REAL,
+            humidity_min INTEGER,
+            humidity_max INTEGER,
+            humidity_sum INTEGER,
+            radon_count INTEGER NOT NULL DEFAULT 0,
+            radon_min INTEGER,
+            radon_max INTEGER,
+            radon_sum INTEGER,
+            co2_good INTEGER NOT NULL DEFAULT 0,
+            co2_moderate INTEGER NOT NULL DEFAULT 0,
+            co2_poor INTEGER NOT NULL DEFAULT 0,
+            radon_good INTEGER NOT NULL DEFAULT 0,
+            radon_moderate INTEGER NOT NULL DEFAULT 0,
+            radon_poor INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (device_id, day)
+        );
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Recompute `daily_stats` rows from `history`.
+///
+/// Parameters: `?1` optional device ID, `?2`/`?3` optional half-open
+/// `[from, to)` range of record timestamps (Unix seconds). Callers pass
+/// day-aligned bounds so every touched day is rebuilt from all of its rows.
+///
+/// CO2 and pressure values of 0 mean "not measured" and are excluded. Bucket
+/// thresholds match the device status colors: CO2 < 1000 / < 1400 ppm and
+/// radon < 150 / < 300 Bq/m³.
+pub(crate) const REFRESH_DAILY_STATS_SQL: &str = r#"
+    INSERT OR REPLACE INTO daily_stats (
+        device_id, day, count,
+        co2_count, co2_min, co2_max, co2_sum,
+        temperature_min, temperature_max, temperature_sum,
+        pressure_count, pressure_min, pressure_max, pressure_sum,
+        humidity_min, humidity_max, humidity_sum,
+        radon_count, radon_min, radon_max, radon_sum,
+        co2_good, co2_moderate, co2_poor,
+        radon_good, radon_moderate, radon_poor
+    )
+    SELECT
+        device_id, (timestamp / 86400) * 86400 AS day, COUNT(*),
+        COUNT(NULLIF(co2, 0)), MIN(NULLIF(co2, 0)), MAX(NULLIF(co2, 0)), SUM(NULLIF(co2, 0)),
+        MIN(temperature), MAX(temperature), SUM(temperature),
+        COUNT(NULLIF(pressure, 0)), MIN(NULLIF(pressure, 0)), MAX(NULLIF(pressure, 0)),
+        SUM(NULLIF(pressure, 0)),
+        MIN(humidity), MAX(humidity), SUM(humidity),
+        COUNT(radon), MIN(radon), MAX(radon), SUM(radon),
+        COUNT(CASE WHEN co2 > 0 AND co2 < 1000 THEN 1 END),
+        COUNT(CASE WHEN co2 >= 1000 AND co2 < 1400 THEN 1 END),
+        COUNT(CASE WHEN co2 >= 1400 THEN 1 END),
+        COUNT(CASE WHEN radon < 150 THEN 1 END),
+        COUNT(CASE WHEN radon >= 150 AND radon < 300 THEN 1 END),
+        COUNT(CASE WHEN radon >= 300 THEN 1 END)
+    FROM history
+    WHERE (?1 IS NULL OR device_id = ?1)
+      AND (?2 IS NULL OR timestamp >= ?2)
+      AND (?3 IS NULL OR timestamp < ?3)
+    GROUP BY device_id, day
+"#;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,6 +613,10 @@ mod tests {
         assert!(tables.contains(&"readings".to_string()));
         assert!(tables.contains(&"history".to_string()));
         assert!(tables.contains(&"sync_state".to_string()));
+        assert!(tables.contains(&"daily_stats".to_string()));
+        assert!(tables.contains(&"clock_drift".to_string()));
+        assert!(tables.contains(&"settings_history".to_string()));
+        assert!(tables.contains(&"alerts".to_string()));
         assert!(tables.contains(&"schema_version".to_string()));
     }
 
@@ -236,4 +631,279 @@ mod tests {
         initialize(&conn).unwrap();
         assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
     }
+
+    #[test]
+    fn test_migration_to_v12_backfills_history_hash() {
+        let conn = Connection::open_in_memory().unwrap();
+        {
+            let tx = conn.unchecked_transaction().unwrap();
+            create_schema_v1(&tx).unwrap();
+            set_schema_version(&tx, 11).unwrap();
+            tx.commit().unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO devices (id, first_seen, last_seen) VALUES ('dev', 0, 0);
+             INSERT INTO history (device_id, timestamp, synced_at, co2, temperature, pressure, humidity)
+                VALUES ('dev', 86400, 0, 900, 20.0, 1000.0, 40);",
+        )
+        .unwrap();
+        // Version 11 tables that migrations before 12 would have added
+        add_readings_quality(&conn).unwrap();
+        for create in [
+            create_daily_stats_table,
+            create_clock_drift_table,
+            create_settings_history_table,
+            create_alerts_table,
+            create_annotations_table,
+            create_manager_state_table,
+            create_advertisements_table,
+        ] {
+            create(&conn).unwrap();
+        }
+
+        initialize(&conn).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        let hash: String = conn
+            .query_row("SELECT hash FROM history", [], |row| row.get(0))
+            .unwrap();
+        let record = aranet_types::HistoryRecord {
+            co2: 900,
+            temperature: 20.0,
+            pressure: 1000.0,
+            humidity: 40,
+            ..Default::default()
+        };
+        assert_eq!(hash, history_hash(&record));
+    }
+
+    #[test]
+    fn test_migration_to_v4_backfills_daily_stats() {
+        let conn = Connection::open_in_memory().unwrap();
+        {
+            let tx = conn.unchecked_transaction().unwrap();
+            create_schema_v1(&tx).unwrap();
+            set_schema_version(&tx, 3).unwrap();
+            tx.commit().unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO devices (id, first_seen, last_seen) VALUES ('dev', 0, 0);
+             INSERT INTO history (device_id, timestamp, synced_at, co2, temperature, pressure, humidity)
+                VALUES ('dev', 86400, 0, 900, 20.0, 1000.0, 40),
+                       ('dev', 90000, 0, 1500, 22.0, 1010.0, 50),
+                       ('dev', 172800, 0, 700, 21.0, 0.0, 45);",
+        )
+        .unwrap();
+
+        initialize(&conn).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        let (days, count, co2_max, co2_poor, pressure_count): (i64, i64, i64, i64, i64) = conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM daily_stats), count, co2_max, co2_poor,
+                        pressure_count
+                 FROM daily_stats WHERE day = 86400",
+                [],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                    ))
+                },
+            )
+            .unwrap();
+        assert_eq!(days, 2);
+        assert_eq!(count, 2);
+        assert_eq!(co2_max, 1500);
+        assert_eq!(co2_poor, 1);
+        assert_eq!(pressure_count, 2);
+    }
+
+    #[test]
+    fn test_migration_to_v5_adds_clock_drift() {
+        let conn = Connection::open_in_memory().unwrap();
+        {
+            let tx = conn.unchecked_transaction().unwrap();
+            create_schema_v1(&tx).unwrap();
+            create_daily_stats_table(&tx).unwrap();
+            set_schema_version(&tx, 4).unwrap();
+            tx.commit().unwrap();
+        }
+
+        initialize(&conn).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        let exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='clock_drift'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(exists);
+    }
+
+    #[test]
+    fn test_migration_to_v6_adds_settings_history() {
+        let conn = Connection::open_in_memory().unwrap();
+        {
+            let tx = conn.unchecked_transaction().unwrap();
+            create_schema_v1(&tx).unwrap();
+            create_daily_stats_table(&tx).unwrap();
+            create_clock_drift_table(&tx).unwrap();
+            set_schema_version(&tx, 5).unwrap();
+            tx.commit().unwrap();
+        }
+
+        initialize(&conn).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        let exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='settings_history'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(exists);
+    }
+
+    #[test]
+    fn test_migration_to_v7_adds_alerts() {
+        let conn = Connection::open_in_memory().unwrap();
+        {
+            let tx = conn.unchecked_transaction().unwrap();
+            create_schema_v1(&tx).unwrap();
+            create_daily_stats_table(&tx).unwrap();
+            create_clock_drift_table(&tx).unwrap();
+            create_settings_history_table(&tx).unwrap();
+            set_schema_version(&tx, 6).unwrap();
+            tx.commit().unwrap();
+        }
+
+        initialize(&conn).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        let exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='alerts'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(exists);
+    }
+
+    #[test]
+    fn test_migration_to_v8_adds_annotations() {
+        let conn = Connection::open_in_memory().unwrap();
+        {
+            let tx = conn.unchecked_transaction().unwrap();
+            create_schema_v1(&tx).unwrap();
+            create_daily_stats_table(&tx).unwrap();
+            create_clock_drift_table(&tx).unwrap();
+            create_settings_history_table(&tx).unwrap();
+            create_alerts_table(&tx).unwrap();
+            set_schema_version(&tx, 7).unwrap();
+            tx.commit().unwrap();
+        }
+
+        initialize(&conn).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        let exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='annotations'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(exists);
+    }
+
+    #[test]
+    fn test_migration_to_v9_adds_manager_state() {
+        let conn = Connection::open_in_memory().unwrap();
+        {
+            let tx = conn.unchecked_transaction().unwrap();
+            create_schema_v1(&tx).unwrap();
+            create_daily_stats_table(&tx).unwrap();
+            create_clock_drift_table(&tx).unwrap();
+            create_settings_history_table(&tx).unwrap();
+            create_alerts_table(&tx).unwrap();
+            create_annotations_table(&tx).unwrap();
+            set_schema_version(&tx, 8).unwrap();
+            tx.commit().unwrap();
+        }
+
+        initialize(&conn).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        let exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='manager_state'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(exists);
+    }
+
+    #[test]
+    fn test_migration_to_v10_adds_advertisements() {
+        let conn = Connection::open_in_memory().unwrap();
+        {
+            let tx = conn.unchecked_transaction().unwrap();
+            create_schema_v1(&tx).unwrap();
+            create_daily_stats_table(&tx).unwrap();
+            create_clock_drift_table(&tx).unwrap();
+            create_settings_history_table(&tx).unwrap();
+            create_alerts_table(&tx).unwrap();
+            create_annotations_table(&tx).unwrap();
+            create_manager_state_table(&tx).unwrap();
+            set_schema_version(&tx, 9).unwrap();
+            tx.commit().unwrap();
+        }
+
+        initialize(&conn).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        let exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name='advertisements'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(exists);
+    }
+
+    #[test]
+    fn test_migration_to_v13_adds_readings_counter() {
+        let conn = Connection::open_in_memory().unwrap();
+        {
+            let tx = conn.unchecked_transaction().unwrap();
+            create_schema_v1(&tx).unwrap();
+            add_readings_quality(&tx).unwrap();
+            add_history_hash(&tx).unwrap();
+            set_schema_version(&tx, 12).unwrap();
+            tx.commit().unwrap();
+        }
+        conn.execute_batch(
+            "INSERT INTO devices (id, first_seen, last_seen) VALUES ('dev', 0, 0);
+             INSERT INTO readings (device_id, captured_at) VALUES ('dev', 60);",
+        )
+        .unwrap();
+
+        initialize(&conn).unwrap();
+        assert_eq!(get_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        let counter: Option<i64> = conn
+            .query_row("SELECT counter FROM readings", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(counter, None);
+    }
 }
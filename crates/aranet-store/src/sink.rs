@@ -0,0 +1,76 @@
+//! A [`ReadingSink`] that saves readings to the store.
+//!
+//! Requires the `sink` feature.
+
+use std::collections::HashSet;
+
+use aranet_core::sink::{ReadingSink, SinkError, SinkReading};
+
+use crate::asynchronous::Store;
+
+/// Saves each reading to the store, recording device names as they are seen.
+#[derive(Debug, Clone)]
+pub struct StoreSink {
+    store: Store,
+    named: HashSet<String>,
+}
+
+impl StoreSink {
+    /// Save readings to `store`.
+    pub fn new(store: Store) -> Self {
+        Self {
+            store,
+            named: HashSet::new(),
+        }
+    }
+}
+
+impl ReadingSink for StoreSink {
+    fn name(&self) -> &str {
+        "store"
+    }
+
+    async fn write(&mut self, reading: &SinkReading) -> Result<(), SinkError> {
+        let to_sink_error = |e: crate::Error| SinkError::Other(e.to_string());
+        if let Some(name) = &reading.device_name
+            && !self.named.contains(&reading.device_id)
+        {
+            self.store
+                .upsert_device(&reading.device_id, Some(name))
+                .await
+                .map_err(to_sink_error)?;
+            self.named.insert(reading.device_id.clone());
+        }
+        self.store
+            .insert_reading(&reading.device_id, reading.reading)
+            .await
+            .map_err(to_sink_error)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aranet_core::sink::ReadingPipeline;
+    use aranet_types::CurrentReading;
+
+    #[tokio::test]
+    async fn test_store_sink_saves_readings() {
+        let store = Store::open_in_memory().await.unwrap();
+        let readings = [650, 700].map(|co2| {
+            SinkReading::new("AA:BB", CurrentReading::builder().co2(co2).build())
+                .with_name("Kitchen")
+        });
+        let stats = ReadingPipeline::new()
+            .with_sink(StoreSink::new(store.clone()))
+            .run(futures::stream::iter(readings))
+            .await;
+        assert_eq!(stats[0].written, 2);
+
+        let device = store.get_device("AA:BB").await.unwrap().unwrap();
+        assert_eq!(device.name.as_deref(), Some("Kitchen"));
+        let latest = store.get_latest_reading("AA:BB").await.unwrap().unwrap();
+        assert_eq!(latest.co2, 700);
+    }
+}
@@ -36,13 +36,18 @@
 //! - **macOS**: `~/Library/Application Support/aranet/data.db`
 //! - **Windows**: `C:\Users\<user>\AppData\Local\aranet\data.db`
 
+use std::collections::{BTreeSet, HashMap};
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
 
 use rusqlite::{Connection, OptionalExtension};
 use time::OffsetDateTime;
 use tracing::{debug, info, warn};
 
-use aranet_types::{CurrentReading, DeviceInfo, DeviceType, HistoryRecord, Status};
+use aranet_types::{
+    CurrentReading, DeviceInfo, DeviceName, DeviceType, HistoryRecord, ManagerState,
+    ManagerStateStore, Status,
+};
 
 /// Safely convert a Unix timestamp to OffsetDateTime.
 ///
@@ -82,11 +87,134 @@ fn radon_from_i64(v: i64, context: &str) -> Option<u32> {
     }
 }
 
+/// Map a row selected with the `history` column list used by
+/// [`HistoryQuery`] to a [`StoredHistoryRecord`].
+fn history_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<StoredHistoryRecord> {
+    Ok(StoredHistoryRecord {
+        id: row.get(0)?,
+        device_id: row.get(1)?,
+        timestamp: timestamp_from_unix(row.get(2)?),
+        synced_at: timestamp_from_unix(row.get(3)?),
+        co2: u16::try_from(row.get::<_, i64>(4)?).unwrap_or_else(|e| {
+            warn!("Invalid co2 value in history: {e}");
+            0
+        }),
+        temperature: row.get(5)?,
+        pressure: row.get(6)?,
+        humidity: u8::try_from(row.get::<_, i64>(7)?).unwrap_or_else(|e| {
+            warn!("Invalid humidity value in history: {e}");
+            0
+        }),
+        radon: row
+            .get::<_, Option<i64>>(8)?
+            .and_then(|v| radon_from_i64(v, "history")),
+        radiation_rate: row.get(9)?,
+        radiation_total: row.get(10)?,
+    })
+}
+
+/// Build a [`StoredChannelValue`] from `id, device_id, channel, captured_at, value`.
+fn channel_value_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<StoredChannelValue> {
+    Ok(StoredChannelValue {
+        id: row.get(0)?,
+        device_id: row.get(1)?,
+        channel: row.get(2)?,
+        captured_at: timestamp_from_unix(row.get(3)?),
+        value: row.get(4)?,
+    })
+}
+
+/// Length of a `daily_stats` bucket.
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// History records with the same content less than this many seconds apart
+/// are the same measurement.
+///
+/// Half the shortest Aranet measurement interval (1 minute), so consecutive
+/// records with identical values are never mistaken for copies.
+pub const HISTORY_DUPLICATE_WINDOW_SECS: i64 = 30;
+
+/// Readings of a device with the same values less than this many seconds
+/// apart are the same measurement seen by two sources (e.g. an advertisement
+/// and a GATT read) when inserted with [`Store::insert_readings_unordered`].
+pub const READING_DUPLICATE_WINDOW_SECS: i64 = 30;
+
+/// The measured values of a reading, compared bit for bit to spot copies.
+type ReadingValues = (u16, u32, u32, u8, Option<u32>, Option<u32>);
+
+fn reading_values(reading: &CurrentReading) -> ReadingValues {
+    (
+        reading.co2,
+        reading.temperature.to_bits(),
+        reading.pressure.to_bits(),
+        reading.humidity,
+        reading.radon,
+        reading.radiation_rate.map(f32::to_bits),
+    )
+}
+
+/// Insert one row into `readings`, returning its row ID.
+fn insert_reading_row(
+    conn: &Connection,
+    device_id: &str,
+    captured_at: i64,
+    reading: &CurrentReading,
+    quality: ReadingQuality,
+    counter: Option<u8>,
+) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO readings (device_id, captured_at, co2, temperature, pressure,
+         humidity, battery, status, radon, radiation_rate, radiation_total,
+         radon_avg_24h, radon_avg_7d, radon_avg_30d, quality, counter)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+        rusqlite::params![
+            device_id,
+            captured_at,
+            reading.co2,
+            reading.temperature,
+            reading.pressure,
+            reading.humidity,
+            reading.battery,
+            format!("{:?}", reading.status),
+            reading.radon,
+            reading.radiation_rate,
+            reading.radiation_total,
+            reading.radon_avg_24h,
+            reading.radon_avg_7d,
+            reading.radon_avg_30d,
+            quality.as_str(),
+            counter,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Start of the UTC day containing `ts`, matching the bucketing used by
+/// [`schema::REFRESH_DAILY_STATS_SQL`].
+fn day_start(ts: i64) -> i64 {
+    ts / SECONDS_PER_DAY * SECONDS_PER_DAY
+}
+
 use crate::error::{Error, Result};
-use crate::models::{StoredDevice, StoredHistoryRecord, StoredReading, SyncState};
-use crate::queries::{HistoryQuery, ReadingQuery};
+use crate::health::{self, HealthExportFormat};
+use crate::models::{
+    ReadingQuality, SETTING_INTERVAL, StoredAdvertisement, StoredAlert, StoredAnnotation,
+    StoredChannelValue, StoredClockDrift, StoredDevice, StoredHistoryRecord, StoredReading,
+    StoredSettingChange, SyncState, history_hash,
+};
+use crate::queries::{
+    AdvertisementQuery, AlertQuery, AnnotationQuery, ChannelQuery, HistoryQuery, MAX_QUERY_LIMIT,
+    ReadingQuery,
+};
 use crate::schema;
 
+/// Default number of rows kept in the advertisement log by
+/// [`Store::trim_advertisements`] callers.
+pub const ADVERTISEMENT_LOG_MAX_ROWS: u64 = 100_000;
+
+/// Longest advertisement payload stored; longer payloads are truncated.
+const MAX_ADVERTISEMENT_PAYLOAD: usize = 255;
+
 /// SQLite-based store for Aranet sensor data.
 ///
 /// `Store` provides persistent storage for sensor readings, history records,
@@ -139,6 +267,8 @@ use crate::schema;
 pub struct Store {
     conn: Connection,
     path: Option<PathBuf>,
+    /// Read-only archive databases attached with [`Store::attach_archive`].
+    archives: Vec<PathBuf>,
 }
 
 impl Store {
@@ -186,6 +316,7 @@ impl Store {
         Ok(Self {
             conn,
             path: Some(path.to_path_buf()),
+            archives: Vec::new(),
         })
     }
 
@@ -226,7 +357,11 @@ impl Store {
         let conn = Connection::open_in_memory()?;
         conn.execute_batch("PRAGMA foreign_keys = ON;")?;
         schema::initialize(&conn)?;
-        Ok(Self { conn, path: None })
+        Ok(Self {
+            conn,
+            path: None,
+            archives: Vec::new(),
+        })
     }
 
     /// Return the database path for file-backed stores.
@@ -436,37 +571,223 @@ impl Store {
         Ok(devices)
     }
 
+    /// Find the stored device an advertised name belongs to.
+    ///
+    /// Matches on model and short serial (see [`DeviceName`]) against each
+    /// device's name, or its ID when the ID is itself a name. This finds a
+    /// sensor that was stored under a different identifier, such as an old
+    /// CoreBluetooth UUID. Returns the most recently seen match.
+    pub fn find_device_by_name(&self, name: &str) -> Result<Option<StoredDevice>> {
+        let Some(wanted) = DeviceName::parse(name) else {
+            return Ok(None);
+        };
+
+        Ok(self.list_devices()?.into_iter().find(|device| {
+            [device.name.as_deref(), Some(device.id.as_str())]
+                .into_iter()
+                .flatten()
+                .filter_map(DeviceName::parse)
+                .any(|stored| stored.is_same_device(&wanted))
+        }))
+    }
+
     /// Delete a device and all associated data (readings, history, sync state).
     ///
     /// All deletions are performed within a transaction to ensure atomicity.
     /// Returns true if the device was deleted, false if it didn't exist.
     pub fn delete_device(&self, device_id: &str) -> Result<bool> {
+        Ok(self.purge_device(device_id)?.device)
+    }
+
+    /// Delete a device and all associated data, returning how many rows were
+    /// removed from each table.
+    ///
+    /// All deletions are performed within a transaction to ensure atomicity.
+    pub fn purge_device(&self, device_id: &str) -> Result<DeviceDataCounts> {
         let tx = self.conn.unchecked_transaction()?;
 
-        // Delete in order: history, readings, sync_state, device
-        tx.execute(
-            "DELETE FROM history WHERE device_id = ?1",
-            rusqlite::params![device_id],
-        )?;
+        let mut counts = [0u64; DEVICE_DATA_TABLES.len()];
+        for (count, table) in counts.iter_mut().zip(DEVICE_DATA_TABLES) {
+            *count = tx.execute(
+                &format!("DELETE FROM {table} WHERE device_id = ?1"),
+                rusqlite::params![device_id],
+            )? as u64;
+        }
 
-        tx.execute(
-            "DELETE FROM readings WHERE device_id = ?1",
+        let rows_deleted = tx.execute(
+            "DELETE FROM devices WHERE id = ?1",
             rusqlite::params![device_id],
         )?;
 
+        tx.commit()?;
+
+        Ok(DeviceDataCounts::from_table_counts(
+            rows_deleted > 0,
+            counts,
+        ))
+    }
+
+    /// Count the rows [`purge_device`](Self::purge_device) would delete,
+    /// without deleting anything.
+    pub fn count_device_data(&self, device_id: &str) -> Result<DeviceDataCounts> {
+        let mut counts = [0u64; DEVICE_DATA_TABLES.len()];
+        for (count, table) in counts.iter_mut().zip(DEVICE_DATA_TABLES) {
+            *count = self.conn.query_row(
+                &format!("SELECT COUNT(*) FROM {table} WHERE device_id = ?1"),
+                rusqlite::params![device_id],
+                |row| row.get::<_, i64>(0),
+            )? as u64;
+        }
+
+        let device = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM devices WHERE id = ?1",
+                rusqlite::params![device_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+
+        Ok(DeviceDataCounts::from_table_counts(device, counts))
+    }
+
+    /// Merge everything stored for `from_id` into `into_id`, then delete
+    /// `from_id`.
+    ///
+    /// Use this when the same physical sensor was recorded under two
+    /// identifiers (a new CoreBluetooth UUID on macOS, or a randomized MAC).
+    /// Runs in a single transaction and returns how many rows were moved from
+    /// each table:
+    ///
+    /// - Readings, settings changes, alerts, annotations and channel values
+    ///   are re-parented.
+    /// - History records are re-parented unless `into_id` already has a record
+    ///   at the same timestamp; duplicates are dropped.
+    /// - Sync state and clock drift keep whichever entry is newer.
+    /// - Daily statistics are rebuilt for `into_id` from the merged history;
+    ///   cached days without raw history are kept.
+    /// - If `into_id` has no device row, `from_id`'s row is copied over;
+    ///   otherwise missing metadata is filled in and first/last seen widened.
+    ///
+    /// Merging a device into itself does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DeviceNotFound`] if `from_id` has no device row.
+    pub fn merge_devices(&self, from_id: &str, into_id: &str) -> Result<DeviceDataCounts> {
+        if from_id == into_id {
+            return Ok(DeviceDataCounts::default());
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        let exists = |id: &str| {
+            tx.query_row(
+                "SELECT 1 FROM devices WHERE id = ?1",
+                rusqlite::params![id],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|row| row.is_some())
+        };
+        if !exists(from_id)? {
+            return Err(Error::DeviceNotFound(from_id.to_string()));
+        }
+
+        if exists(into_id)? {
+            tx.execute(
+                "UPDATE devices SET
+                    name = COALESCE(devices.name, src.name),
+                    device_type = COALESCE(devices.device_type, src.device_type),
+                    serial = COALESCE(devices.serial, src.serial),
+                    firmware = COALESCE(devices.firmware, src.firmware),
+                    hardware = COALESCE(devices.hardware, src.hardware),
+                    first_seen = MIN(devices.first_seen, src.first_seen),
+                    last_seen = MAX(devices.last_seen, src.last_seen)
+                 FROM (SELECT * FROM devices WHERE id = ?1) AS src
+                 WHERE devices.id = ?2",
+                rusqlite::params![from_id, into_id],
+            )?;
+        } else {
+            tx.execute(
+                "INSERT INTO devices (id, name, device_type, serial, firmware, hardware, first_seen, last_seen)
+                 SELECT ?2, name, device_type, serial, firmware, hardware, first_seen, last_seen
+                 FROM devices WHERE id = ?1",
+                rusqlite::params![from_id, into_id],
+            )?;
+        }
+
+        let params = rusqlite::params![from_id, into_id];
+        let reparent = |table: &str| -> Result<u64> {
+            Ok(tx.execute(
+                &format!("UPDATE {table} SET device_id = ?2 WHERE device_id = ?1"),
+                params,
+            )? as u64)
+        };
+        let readings = reparent("readings")?;
+        let settings_history = reparent("settings_history")?;
+        let alerts = reparent("alerts")?;
+        let annotations = reparent("annotations")?;
+        let channel_values = reparent("channel_values")?;
+
+        // Rows that would collide with an existing (device, key) are left
+        // behind and removed with the source device below.
+        let history = tx.execute(
+            "UPDATE OR IGNORE history SET device_id = ?2 WHERE device_id = ?1",
+            params,
+        )? as u64;
+        let daily_stats = tx.execute(
+            "UPDATE OR IGNORE daily_stats SET device_id = ?2 WHERE device_id = ?1",
+            params,
+        )? as u64;
+        let sync_state = tx.execute(
+            "INSERT OR REPLACE INTO sync_state (device_id, last_history_index, total_readings, last_sync_at)
+             SELECT ?2, last_history_index, total_readings, last_sync_at
+             FROM sync_state AS src
+             WHERE src.device_id = ?1
+               AND NOT EXISTS (
+                   SELECT 1 FROM sync_state AS dst
+                   WHERE dst.device_id = ?2
+                     AND COALESCE(dst.last_sync_at, 0) >= COALESCE(src.last_sync_at, 0)
+               )",
+            params,
+        )? as u64;
+        let clock_drift = tx.execute(
+            "INSERT OR REPLACE INTO clock_drift (device_id, drift_ppm, span_seconds, measured_at)
+             SELECT ?2, drift_ppm, span_seconds, measured_at
+             FROM clock_drift AS src
+             WHERE src.device_id = ?1
+               AND NOT EXISTS (
+                   SELECT 1 FROM clock_drift AS dst
+                   WHERE dst.device_id = ?2 AND dst.measured_at >= src.measured_at
+               )",
+            params,
+        )? as u64;
+
         tx.execute(
-            "DELETE FROM sync_state WHERE device_id = ?1",
-            rusqlite::params![device_id],
+            schema::REFRESH_DAILY_STATS_SQL,
+            rusqlite::params![into_id, None::<i64>, None::<i64>],
         )?;
-
-        let rows_deleted = tx.execute(
+        tx.execute(
             "DELETE FROM devices WHERE id = ?1",
-            rusqlite::params![device_id],
+            rusqlite::params![from_id],
         )?;
 
         tx.commit()?;
-
-        Ok(rows_deleted > 0)
+        info!("Merged device {} into {}", from_id, into_id);
+
+        Ok(DeviceDataCounts {
+            device: true,
+            history,
+            readings,
+            sync_state,
+            daily_stats,
+            clock_drift,
+            settings_history,
+            alerts,
+            annotations,
+            channel_values,
+        })
     }
 
     /// Delete history records older than the given timestamp.
@@ -498,6 +819,231 @@ impl Store {
         self.conn.execute_batch("VACUUM;")?;
         Ok(())
     }
+
+    /// Vacuum the database and truncate the write-ahead log, so the files on
+    /// disk shrink to the data actually stored.
+    pub fn compact(&self) -> Result<()> {
+        self.vacuum()?;
+        self.conn
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        Ok(())
+    }
+
+    /// Delete history, readings, channel values and daily statistics
+    /// recorded before `before`, for one device or (with `None`) every
+    /// device.
+    ///
+    /// Daily statistics are only removed for days that end at or before the
+    /// cutoff, so a partially purged day keeps its summary. The device rows
+    /// and all other tables are left alone; use
+    /// [`purge_device`](Self::purge_device) to remove a device entirely.
+    /// Runs in a single transaction.
+    pub fn purge_before(
+        &self,
+        device_id: Option<&str>,
+        before: OffsetDateTime,
+    ) -> Result<DeviceDataCounts> {
+        let ts = before.unix_timestamp();
+        let tx = self.conn.unchecked_transaction()?;
+
+        let history = tx.execute(
+            "DELETE FROM history WHERE timestamp < ?1 AND (?2 IS NULL OR device_id = ?2)",
+            rusqlite::params![ts, device_id],
+        )? as u64;
+        let readings = tx.execute(
+            "DELETE FROM readings WHERE captured_at < ?1 AND (?2 IS NULL OR device_id = ?2)",
+            rusqlite::params![ts, device_id],
+        )? as u64;
+        let daily_stats = tx.execute(
+            "DELETE FROM daily_stats WHERE day + ?3 <= ?1 AND (?2 IS NULL OR device_id = ?2)",
+            rusqlite::params![ts, device_id, SECONDS_PER_DAY],
+        )? as u64;
+        let channel_values = tx.execute(
+            "DELETE FROM channel_values WHERE captured_at < ?1 AND (?2 IS NULL OR device_id = ?2)",
+            rusqlite::params![ts, device_id],
+        )? as u64;
+
+        tx.commit()?;
+
+        Ok(DeviceDataCounts {
+            history,
+            readings,
+            daily_stats,
+            channel_values,
+            ..DeviceDataCounts::default()
+        })
+    }
+
+    /// Size of the main database in bytes, and how much of it is free pages
+    /// that [`compact`](Self::compact) would reclaim.
+    ///
+    /// The write-ahead log is not included.
+    pub fn database_size(&self) -> Result<DatabaseSize> {
+        let pragma = |name: &str| -> Result<u64> {
+            Ok(self
+                .conn
+                .query_row(&format!("PRAGMA {name}"), [], |row| row.get::<_, i64>(0))?
+                as u64)
+        };
+        let page_size = pragma("page_size")?;
+        Ok(DatabaseSize {
+            bytes: pragma("page_count")? * page_size,
+            free_bytes: pragma("freelist_count")? * page_size,
+        })
+    }
+
+    /// Row counts and recorded time ranges for every stored device.
+    ///
+    /// Only the main database is counted; attached archives are not.
+    pub fn device_cache_stats(&self) -> Result<Vec<DeviceCacheStats>> {
+        self.list_devices()?
+            .into_iter()
+            .map(|device| {
+                Ok(DeviceCacheStats {
+                    counts: self.count_device_data(&device.id)?,
+                    history_range: self.time_range("history", "timestamp", &device.id)?,
+                    readings_range: self.time_range("readings", "captured_at", &device.id)?,
+                    device_id: device.id,
+                    name: device.name,
+                })
+            })
+            .collect()
+    }
+
+    /// Oldest and newest value of `column` in `table` for one device.
+    fn time_range(
+        &self,
+        table: &str,
+        column: &str,
+        device_id: &str,
+    ) -> Result<Option<(OffsetDateTime, OffsetDateTime)>> {
+        let (oldest, newest): (Option<i64>, Option<i64>) = self.conn.query_row(
+            &format!("SELECT MIN({column}), MAX({column}) FROM {table} WHERE device_id = ?1"),
+            rusqlite::params![device_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(oldest
+            .zip(newest)
+            .map(|(oldest, newest)| (timestamp_from_unix(oldest), timestamp_from_unix(newest))))
+    }
+
+    /// Check the database for corruption, rows that belong to no known
+    /// device, history records that no longer match their content hash and
+    /// duplicated history records.
+    ///
+    /// Runs SQLite's `integrity_check` and rehashes every history record,
+    /// which reads the whole database and can take a while when it is large.
+    pub fn verify(&self) -> Result<IntegrityReport> {
+        let mut stmt = self.conn.prepare("PRAGMA integrity_check")?;
+        let problems = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .filter(|message| !matches!(message, Ok(m) if m == "ok"))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut orphans = Vec::new();
+        for table in DEVICE_DATA_TABLES {
+            let count = self.conn.query_row(
+                &format!(
+                    "SELECT COUNT(*) FROM {table} WHERE device_id NOT IN (SELECT id FROM devices)"
+                ),
+                [],
+                |row| row.get::<_, i64>(0),
+            )? as u64;
+            if count > 0 {
+                orphans.push((table.to_string(), count));
+            }
+        }
+
+        let mut checksum_mismatches = 0;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {HISTORY_COLUMNS}, hash FROM history WHERE hash IS NOT NULL"
+        ))?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let record = history_from_row(row)?;
+            if history_hash(&record.to_history()) != row.get::<_, String>(11)? {
+                checksum_mismatches += 1;
+            }
+        }
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT device_id, COUNT(*) FROM history h WHERE {HISTORY_DUPLICATE_CONDITION}
+             GROUP BY device_id ORDER BY device_id"
+        ))?;
+        let duplicates = stmt
+            .query_map([HISTORY_DUPLICATE_WINDOW_SECS], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(IntegrityReport {
+            problems,
+            orphans,
+            checksum_mismatches,
+            duplicates,
+        })
+    }
+
+    /// Delete history records that repeat an earlier record's content less
+    /// than [`HISTORY_DUPLICATE_WINDOW_SECS`] later, as reported by
+    /// [`verify`](Self::verify), keeping the earliest copy.
+    ///
+    /// Such copies were written by versions that only deduplicated on the
+    /// exact timestamp. Daily statistics for the affected days are rebuilt.
+    /// Returns the number of records deleted.
+    pub fn delete_history_duplicates(&self) -> Result<u64> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        let touched = {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT DISTINCT device_id, timestamp / {SECONDS_PER_DAY} * {SECONDS_PER_DAY}
+                 FROM history h WHERE {HISTORY_DUPLICATE_CONDITION}"
+            ))?;
+            stmt.query_map([HISTORY_DUPLICATE_WINDOW_SECS], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?
+        };
+
+        let deleted = tx.execute(
+            &format!(
+                "DELETE FROM history WHERE id IN (
+                     SELECT id FROM history h WHERE {HISTORY_DUPLICATE_CONDITION}
+                 )"
+            ),
+            [HISTORY_DUPLICATE_WINDOW_SECS],
+        )? as u64;
+
+        for (device_id, day) in touched {
+            tx.execute(
+                schema::REFRESH_DAILY_STATS_SQL,
+                rusqlite::params![device_id, day, day + SECONDS_PER_DAY],
+            )?;
+        }
+
+        tx.commit()?;
+        if deleted > 0 {
+            info!("Deleted {} duplicate history records", deleted);
+        }
+        Ok(deleted)
+    }
+
+    /// Delete rows that belong to no known device, as reported by
+    /// [`verify`](Self::verify).
+    ///
+    /// Returns the number of rows deleted.
+    pub fn delete_orphans(&self) -> Result<u64> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut deleted = 0;
+        for table in DEVICE_DATA_TABLES {
+            deleted += tx.execute(
+                &format!("DELETE FROM {table} WHERE device_id NOT IN (SELECT id FROM devices)"),
+                [],
+            )? as u64;
+        }
+        tx.commit()?;
+        Ok(deleted)
+    }
 }
 
 fn parse_device_type(s: &str) -> Option<DeviceType> {
@@ -523,6 +1069,14 @@ fn parse_status(s: &str) -> Status {
     }
 }
 
+/// Parse a stored quality flag, treating unknown names as unchecked.
+fn parse_quality(s: &str) -> ReadingQuality {
+    ReadingQuality::from_name(s).unwrap_or_else(|| {
+        tracing::warn!("Unknown reading quality '{}', treating as unchecked", s);
+        ReadingQuality::Unchecked
+    })
+}
+
 // Reading operations
 impl Store {
     /// Insert a current reading from a device.
@@ -560,6 +1114,46 @@ impl Store {
     /// # Ok::<(), aranet_store::Error>(())
     /// ```
     pub fn insert_reading(&self, device_id: &str, reading: &CurrentReading) -> Result<i64> {
+        self.insert_reading_with_quality(device_id, reading, ReadingQuality::Unchecked)
+    }
+
+    /// Insert a current reading together with its validation result.
+    ///
+    /// Same as [`insert_reading`](Self::insert_reading), but records the
+    /// [`ReadingQuality`] a validator assigned so bad readings can later be
+    /// filtered out or deleted in bulk.
+    pub fn insert_reading_with_quality(
+        &self,
+        device_id: &str,
+        reading: &CurrentReading,
+        quality: ReadingQuality,
+    ) -> Result<i64> {
+        self.insert_reading_row(device_id, reading, quality, None)
+    }
+
+    /// Insert a reading received in a Smart Home advertisement, together
+    /// with the advertisement's measurement counter.
+    ///
+    /// The counter goes up by one with every measurement the device takes,
+    /// so gaps between stored counters show measurements that were never
+    /// received; see [`measurement_coverage`](Self::measurement_coverage).
+    /// GATT reads do not carry the counter and are stored without one.
+    pub fn insert_reading_with_counter(
+        &self,
+        device_id: &str,
+        reading: &CurrentReading,
+        counter: u8,
+    ) -> Result<i64> {
+        self.insert_reading_row(device_id, reading, ReadingQuality::Unchecked, Some(counter))
+    }
+
+    fn insert_reading_row(
+        &self,
+        device_id: &str,
+        reading: &CurrentReading,
+        quality: ReadingQuality,
+        counter: Option<u8>,
+    ) -> Result<i64> {
         // Ensure device exists
         self.upsert_device(device_id, None)?;
 
@@ -568,30 +1162,156 @@ impl Store {
             .unwrap_or_else(OffsetDateTime::now_utc)
             .unix_timestamp();
 
-        self.conn.execute(
-            "INSERT INTO readings (device_id, captured_at, co2, temperature, pressure,
-             humidity, battery, status, radon, radiation_rate, radiation_total,
-             radon_avg_24h, radon_avg_7d, radon_avg_30d)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
-            rusqlite::params![
-                device_id,
-                captured_at,
-                reading.co2,
-                reading.temperature,
-                reading.pressure,
-                reading.humidity,
-                reading.battery,
-                format!("{:?}", reading.status),
-                reading.radon,
-                reading.radiation_rate,
-                reading.radiation_total,
-                reading.radon_avg_24h,
-                reading.radon_avg_7d,
-                reading.radon_avg_30d,
-            ],
+        let id = insert_reading_row(
+            &self.conn,
+            device_id,
+            captured_at,
+            reading,
+            quality,
+            counter,
         )?;
 
-        Ok(self.conn.last_insert_rowid())
+        // Readings carry the measurement interval, so interval changes are
+        // tracked without any extra device reads
+        if reading.interval > 0 {
+            self.record_reading_interval(device_id, reading.interval, captured_at)?;
+        }
+
+        Ok(id)
+    }
+
+    /// Insert a batch of readings that may arrive out of order.
+    ///
+    /// Meant for merged sources, such as passive advertisements and active
+    /// reads of the same devices, whose readings interleave in time. The
+    /// batch is grouped by device and sorted by `captured_at`, then written
+    /// in one transaction. Each device's existing readings around the batch
+    /// are fetched once, and a reading with the same values as one less than
+    /// [`READING_DUPLICATE_WINDOW_SECS`] away is skipped, as is an interval
+    /// older than the last interval change recorded for the device.
+    ///
+    /// Returns the row ID of each reading in batch order, or `None` for
+    /// readings skipped as duplicates.
+    pub fn insert_readings_unordered(
+        &self,
+        batch: &[(&str, CurrentReading, ReadingQuality)],
+    ) -> Result<Vec<Option<i64>>> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let mut order: Vec<(usize, i64)> = batch
+            .iter()
+            .enumerate()
+            .map(|(index, (_, reading, _))| {
+                let captured_at = reading.captured_at.map_or(now, |t| t.unix_timestamp());
+                (index, captured_at)
+            })
+            .collect();
+        order.sort_by(|a, b| batch[a.0].0.cmp(batch[b.0].0).then(a.1.cmp(&b.1)));
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut ids = vec![None; batch.len()];
+
+        for run in order.chunk_by(|a, b| batch[a.0].0 == batch[b.0].0) {
+            let device_id = batch[run[0].0].0;
+            let (first, last) = (run[0].1, run[run.len() - 1].1);
+            self.upsert_device(device_id, None)?;
+
+            let mut seen: Vec<(i64, ReadingValues)> = {
+                let mut stmt = tx.prepare(
+                    "SELECT captured_at, co2, temperature, pressure, humidity, radon, radiation_rate
+                     FROM readings WHERE device_id = ?1 AND captured_at > ?2 AND captured_at < ?3",
+                )?;
+                stmt.query_map(
+                    rusqlite::params![
+                        device_id,
+                        first - READING_DUPLICATE_WINDOW_SECS,
+                        last + READING_DUPLICATE_WINDOW_SECS
+                    ],
+                    |row| {
+                        let reading = CurrentReading {
+                            co2: u16::try_from(row.get::<_, i64>(1)?).unwrap_or(0),
+                            temperature: row.get(2)?,
+                            pressure: row.get(3)?,
+                            humidity: u8::try_from(row.get::<_, i64>(4)?).unwrap_or(0),
+                            radon: row
+                                .get::<_, Option<i64>>(5)?
+                                .and_then(|v| u32::try_from(v).ok()),
+                            radiation_rate: row.get(6)?,
+                            ..Default::default()
+                        };
+                        Ok((row.get(0)?, reading_values(&reading)))
+                    },
+                )?
+                .collect::<std::result::Result<_, _>>()?
+            };
+
+            for &(index, captured_at) in run {
+                let (_, reading, quality) = &batch[index];
+                let values = reading_values(reading);
+                let duplicate = seen.iter().any(|(at, seen_values)| {
+                    (at - captured_at).abs() < READING_DUPLICATE_WINDOW_SECS
+                        && *seen_values == values
+                });
+                if duplicate {
+                    continue;
+                }
+                ids[index] = Some(insert_reading_row(
+                    &tx,
+                    device_id,
+                    captured_at,
+                    reading,
+                    *quality,
+                    None,
+                )?);
+                seen.push((captured_at, values));
+            }
+
+            // Only the newest interval in the batch can be the current one
+            if let Some(&(index, captured_at)) = run
+                .iter()
+                .rev()
+                .find(|(index, _)| batch[*index].1.interval > 0)
+            {
+                self.record_reading_interval(device_id, batch[index].1.interval, captured_at)?;
+            }
+        }
+
+        tx.commit()?;
+
+        let inserted = ids.iter().flatten().count();
+        debug!(
+            "Inserted {} of {} unordered readings ({} duplicates skipped)",
+            inserted,
+            batch.len(),
+            batch.len() - inserted
+        );
+        Ok(ids)
+    }
+
+    /// Record the interval a reading was taken with, unless a later interval
+    /// change is already recorded (the reading arrived out of order).
+    fn record_reading_interval(
+        &self,
+        device_id: &str,
+        interval: u16,
+        captured_at: i64,
+    ) -> Result<()> {
+        let latest_change: Option<i64> = self.conn.query_row(
+            "SELECT MAX(changed_at) FROM settings_history WHERE device_id = ?1 AND setting = ?2",
+            rusqlite::params![device_id, SETTING_INTERVAL],
+            |row| row.get(0),
+        )?;
+        if latest_change.is_some_and(|latest| captured_at < latest) {
+            return Ok(());
+        }
+
+        self.record_setting(
+            device_id,
+            SETTING_INTERVAL,
+            &interval.to_string(),
+            OffsetDateTime::from_unix_timestamp(captured_at)
+                .unwrap_or_else(|_| OffsetDateTime::now_utc()),
+        )?;
+        Ok(())
     }
 
     /// Query readings with optional filters.
@@ -625,7 +1345,7 @@ impl Store {
     /// # Ok::<(), aranet_store::Error>(())
     /// ```
     pub fn query_readings(&self, query: &ReadingQuery) -> Result<Vec<StoredReading>> {
-        let sql = query.build_sql();
+        let sql = query.build_sql_from(self.readings_source());
         let (_, params) = query.build_where();
 
         debug!("Executing query: {}", sql);
@@ -668,6 +1388,10 @@ impl Store {
                     radon_avg_30d: row
                         .get::<_, Option<i64>>(14)?
                         .and_then(|v| radon_from_i64(v, "readings")),
+                    quality: parse_quality(&row.get::<_, String>(15)?),
+                    counter: row
+                        .get::<_, Option<i64>>(16)?
+                        .and_then(|v| u8::try_from(v).ok()),
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -675,6 +1399,51 @@ impl Store {
         Ok(readings)
     }
 
+    /// Set the quality flag of a stored reading.
+    ///
+    /// Returns `false` if no reading has that ID. Readings in attached
+    /// archives cannot be changed.
+    pub fn set_reading_quality(&self, id: i64, quality: ReadingQuality) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE readings SET quality = ?1 WHERE id = ?2",
+            rusqlite::params![quality.as_str(), id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Delete every reading matching the query's device, time range and
+    /// quality filters, returning the number deleted.
+    ///
+    /// Intended for flagged readings, e.g.
+    /// `ReadingQuery::new().quality(ReadingQuality::Invalid)`. Limit, offset
+    /// and ordering are ignored, and a query without filters deletes all
+    /// readings. Readings in attached archives are left alone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aranet_store::{ReadingQuality, ReadingQuery, Store};
+    ///
+    /// let store = Store::open_in_memory()?;
+    /// let invalid = ReadingQuery::new()
+    ///     .device("Aranet4 17C3C")
+    ///     .quality(ReadingQuality::Invalid);
+    /// let deleted = store.delete_readings_where(&invalid)?;
+    /// # Ok::<(), aranet_store::Error>(())
+    /// ```
+    pub fn delete_readings_where(&self, query: &ReadingQuery) -> Result<u64> {
+        let (where_clause, params) = query.build_where();
+        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let deleted = self.conn.execute(
+            &format!("DELETE FROM main.readings {}", where_clause),
+            params_ref.as_slice(),
+        )?;
+        if deleted > 0 {
+            info!("Deleted {} readings", deleted);
+        }
+        Ok(deleted as u64)
+    }
+
     /// Get the most recent reading for a device.
     ///
     /// Convenience method equivalent to `query_readings` with `limit(1)`.
@@ -714,7 +1483,8 @@ impl Store {
             "SELECT
                 d.id, d.name, d.device_type, d.serial, d.firmware, d.hardware, d.first_seen, d.last_seen,
                 r.id, r.device_id, r.captured_at, r.co2, r.temperature, r.pressure, r.humidity, r.battery,
-                r.status, r.radon, r.radiation_rate, r.radiation_total, r.radon_avg_24h, r.radon_avg_7d, r.radon_avg_30d
+                r.status, r.radon, r.radiation_rate, r.radiation_total, r.radon_avg_24h, r.radon_avg_7d, r.radon_avg_30d,
+                r.quality, r.counter
              FROM devices d
              JOIN readings r ON r.id = (
                 SELECT latest.id
@@ -764,6 +1534,10 @@ impl Store {
                     radon_avg_30d: row
                         .get::<_, Option<i64>>(22)?
                         .and_then(|v| radon_from_i64(v, "latest_readings")),
+                    quality: parse_quality(&row.get::<_, String>(23)?),
+                    counter: row
+                        .get::<_, Option<i64>>(24)?
+                        .and_then(|v| u8::try_from(v).ok()),
                 };
 
                 Ok((device, reading))
@@ -795,15 +1569,18 @@ impl Store {
     /// # Ok::<(), aranet_store::Error>(())
     /// ```
     pub fn count_readings(&self, device_id: Option<&str>) -> Result<u64> {
+        let table = self.readings_source();
         let count: i64 = match device_id {
             Some(id) => self.conn.query_row(
-                "SELECT COUNT(*) FROM readings WHERE device_id = ?",
+                &format!("SELECT COUNT(*) FROM {table} WHERE device_id = ?"),
                 [id],
                 |row| row.get(0),
             )?,
             None => self
                 .conn
-                .query_row("SELECT COUNT(*) FROM readings", [], |row| row.get(0))?,
+                .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                    row.get(0)
+                })?,
         };
 
         Ok(count as u64)
@@ -814,9 +1591,13 @@ impl Store {
 impl Store {
     /// Insert history records with automatic deduplication.
     ///
-    /// Records are deduplicated by `(device_id, timestamp)` - if a record with
-    /// the same timestamp already exists for this device, it is skipped.
-    /// This allows safe re-syncing without creating duplicates.
+    /// Records are deduplicated by `(device_id, timestamp, hash)`: a record is
+    /// skipped if one with the same timestamp already exists for this device,
+    /// or one with the same content hash exists less than
+    /// [`HISTORY_DUPLICATE_WINDOW_SECS`] away. History timestamps are derived
+    /// from the sync time, so the same record downloaded after a restart or
+    /// a repeated sync can shift by a second or two. This allows safe
+    /// re-syncing without creating duplicates.
     ///
     /// # Arguments
     ///
@@ -860,15 +1641,28 @@ impl Store {
         let tx = self.conn.unchecked_transaction()?;
         let synced_at = OffsetDateTime::now_utc().unix_timestamp();
         let mut inserted = 0;
+        let mut touched_days = BTreeSet::new();
 
         for record in records {
+            let timestamp = record.timestamp.unix_timestamp();
+            let hash = history_hash(record);
+            let shifted_copy: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM history WHERE device_id = ?1 AND hash = ?2
+                 AND timestamp > ?3 - ?4 AND timestamp < ?3 + ?4)",
+                rusqlite::params![device_id, hash, timestamp, HISTORY_DUPLICATE_WINDOW_SECS],
+                |row| row.get(0),
+            )?;
+            if shifted_copy {
+                continue;
+            }
+
             let result = tx.execute(
                 "INSERT OR IGNORE INTO history (device_id, timestamp, synced_at, co2,
-                 temperature, pressure, humidity, radon, radiation_rate, radiation_total)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                 temperature, pressure, humidity, radon, radiation_rate, radiation_total, hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
                 rusqlite::params![
                     device_id,
-                    record.timestamp.unix_timestamp(),
+                    timestamp,
                     synced_at,
                     record.co2,
                     record.temperature,
@@ -877,11 +1671,23 @@ impl Store {
                     record.radon,
                     record.radiation_rate,
                     record.radiation_total,
+                    hash,
                 ],
             )?;
+            if result > 0 {
+                touched_days.insert(day_start(timestamp));
+            }
             inserted += result;
         }
 
+        // Keep the per-day stats cache in step with the raw rows
+        for day in touched_days {
+            tx.execute(
+                schema::REFRESH_DAILY_STATS_SQL,
+                rusqlite::params![device_id, day, day + SECONDS_PER_DAY],
+            )?;
+        }
+
         tx.commit()?;
 
         let skipped = records.len() - inserted;
@@ -927,36 +1733,43 @@ impl Store {
     /// # Ok::<(), aranet_store::Error>(())
     /// ```
     pub fn query_history(&self, query: &HistoryQuery) -> Result<Vec<StoredHistoryRecord>> {
-        let sql = query.build_sql();
+        let sql = query.build_sql_from(self.history_source());
         let (_, params) = query.build_where();
         let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
 
         let mut stmt = self.conn.prepare(&sql)?;
         let records = stmt
-            .query_map(params_ref.as_slice(), |row| {
-                Ok(StoredHistoryRecord {
-                    id: row.get(0)?,
-                    device_id: row.get(1)?,
-                    timestamp: timestamp_from_unix(row.get(2)?),
-                    synced_at: timestamp_from_unix(row.get(3)?),
-                    co2: u16::try_from(row.get::<_, i64>(4)?).unwrap_or_else(|e| {
-                        warn!("Invalid co2 value in history: {e}");
-                        0
-                    }),
-                    temperature: row.get(5)?,
-                    pressure: row.get(6)?,
-                    humidity: u8::try_from(row.get::<_, i64>(7)?).unwrap_or_else(|e| {
-                        warn!("Invalid humidity value in history: {e}");
-                        0
-                    }),
-                    radon: row
-                        .get::<_, Option<i64>>(8)?
-                        .and_then(|v| radon_from_i64(v, "history")),
-                    radiation_rate: row.get(9)?,
-                    radiation_total: row.get(10)?,
-                })
-            })?
+            .query_map(params_ref.as_slice(), history_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(records)
+    }
+
+    /// Pick up to `count` random history records matching `query`.
+    ///
+    /// The query's ordering, limit and offset are ignored. Results are
+    /// returned oldest first. Useful for spot-checking cached data against
+    /// the device without re-downloading everything.
+    pub fn sample_history(
+        &self,
+        query: &HistoryQuery,
+        count: u32,
+    ) -> Result<Vec<StoredHistoryRecord>> {
+        let (where_clause, params) = query.build_where();
+        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let sql = format!(
+            "SELECT id, device_id, timestamp, synced_at, co2, temperature, pressure, \
+             humidity, radon, radiation_rate, radiation_total FROM {} {where_clause} \
+             ORDER BY RANDOM() LIMIT {}",
+            self.history_source(),
+            count.min(MAX_QUERY_LIMIT)
+        );
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut records = stmt
+            .query_map(params_ref.as_slice(), history_from_row)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
+        records.sort_by_key(|r| r.timestamp);
 
         Ok(records)
     }
@@ -983,31 +1796,321 @@ impl Store {
     /// # Ok::<(), aranet_store::Error>(())
     /// ```
     pub fn count_history(&self, device_id: Option<&str>) -> Result<u64> {
+        let table = self.history_source();
         let count: i64 = match device_id {
             Some(id) => self.conn.query_row(
-                "SELECT COUNT(*) FROM history WHERE device_id = ?",
+                &format!("SELECT COUNT(*) FROM {table} WHERE device_id = ?"),
                 [id],
                 |row| row.get(0),
             )?,
             None => self
                 .conn
-                .query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))?,
+                .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                    row.get(0)
+                })?,
         };
 
         Ok(count as u64)
     }
 }
 
-// Sync state operations
+/// Columns of the `readings` table, in schema order.
+const READINGS_COLUMNS: &str = "id, device_id, captured_at, co2, temperature, pressure, \
+     humidity, battery, status, radon, radiation_rate, radiation_total, radon_avg_24h, \
+     radon_avg_7d, radon_avg_30d, quality, counter";
+
+/// [`READINGS_COLUMNS`] that every archive has.
+const READINGS_ARCHIVE_COLUMNS: &str = "id, device_id, captured_at, co2, temperature, \
+     pressure, humidity, battery, status, radon, radiation_rate, radiation_total, \
+     radon_avg_24h, radon_avg_7d, radon_avg_30d";
+
+/// Later [`READINGS_COLUMNS`], with the value to select from archives made
+/// before the column existed.
+const READINGS_ADDED_COLUMNS: [(&str, &str); 2] = [("quality", "'unchecked'"), ("counter", "NULL")];
+
+/// Columns of the `history` table, in schema order.
+const HISTORY_COLUMNS: &str = "id, device_id, timestamp, synced_at, co2, temperature, \
+     pressure, humidity, radon, radiation_rate, radiation_total";
+
+/// Matches a history row `h` that repeats the content of an earlier row for
+/// the same device less than `?1` seconds before it.
+const HISTORY_DUPLICATE_CONDITION: &str = "h.hash IS NOT NULL AND EXISTS (
+         SELECT 1 FROM history e
+         WHERE e.device_id = h.device_id AND e.hash = h.hash
+           AND e.timestamp < h.timestamp AND e.timestamp > h.timestamp - ?1
+     )";
+
+/// Columns of the `devices` table, in schema order.
+const DEVICES_COLUMNS: &str =
+    "id, name, device_type, serial, firmware, hardware, first_seen, last_seen";
+
+// Archive operations
 impl Store {
-    /// Get the sync state for a device.
-    ///
-    /// Sync state tracks the last downloaded history index and total readings,
-    /// enabling incremental history downloads instead of re-downloading everything.
-    ///
-    /// # Arguments
+    /// Attach a read-only archive database.
     ///
-    /// * `device_id` - The device to get sync state for
+    /// Reading and history queries ([`query_readings`](Self::query_readings),
+    /// [`query_history`](Self::query_history), counts, stats and exports)
+    /// then return rows from the active database and every attached archive.
+    /// Writes always go to the active database. Attaching the same path
+    /// twice is a no-op.
+    ///
+    /// Archives are usually created by [`rollover`](Self::rollover); any
+    /// aranet database file works.
+    pub fn attach_archive<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if self.archives.iter().any(|p| p == path) {
+            return Ok(());
+        }
+        if !path.is_file() {
+            return Err(Error::Archive(format!("{} does not exist", path.display())));
+        }
+
+        let alias = archive_alias(self.archives.len());
+        self.conn.execute(
+            &format!("ATTACH DATABASE ?1 AS {alias}"),
+            [read_only_uri(path)],
+        )?;
+
+        let tables: i64 = self.conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM {alias}.sqlite_master \
+                 WHERE type = 'table' AND name IN ('history', 'readings')"
+            ),
+            [],
+            |row| row.get(0),
+        )?;
+        if tables != 2 {
+            self.conn.execute(&format!("DETACH DATABASE {alias}"), [])?;
+            return Err(Error::Archive(format!(
+                "{} is not an aranet database",
+                path.display()
+            )));
+        }
+
+        info!("Attached archive {}", path.display());
+        self.archives.push(path.to_path_buf());
+        self.rebuild_archive_views()
+    }
+
+    /// Paths of the attached archives, in attach order.
+    pub fn archives(&self) -> &[PathBuf] {
+        &self.archives
+    }
+
+    /// Where [`rollover`](Self::rollover) puts rows for `year`: next to the
+    /// active database, with the year appended to the file stem
+    /// (`data.db` -> `data-2024.db`).
+    ///
+    /// In-memory stores return `None`.
+    pub fn archive_path(&self, year: i32) -> Option<PathBuf> {
+        let path = self.path.as_deref()?;
+        let stem = path.file_stem()?.to_string_lossy();
+        let name = match path.extension() {
+            Some(ext) => format!("{stem}-{year}.{}", ext.to_string_lossy()),
+            None => format!("{stem}-{year}"),
+        };
+        Some(path.with_file_name(name))
+    }
+
+    /// Move history and readings from `year` and earlier into the archive
+    /// for `year`, then attach it.
+    ///
+    /// Rows are copied to [`archive_path`](Self::archive_path) (created if
+    /// needed, appended to if it exists) and deleted from the active
+    /// database in one transaction, so queries see each row exactly once
+    /// before and after. Devices, sync state and daily statistics stay in the
+    /// active database. Run [`vacuum`](Self::vacuum) afterwards to return the
+    /// freed space to the filesystem.
+    pub fn rollover(&mut self, year: i32) -> Result<RolloverSummary> {
+        let path = self
+            .archive_path(year)
+            .ok_or_else(|| Error::Archive("in-memory stores cannot roll over".to_string()))?;
+        let cutoff = time::Date::from_calendar_date(year + 1, time::Month::January, 1)
+            .map_err(|e| Error::InvalidTimestamp(e.to_string()))?
+            .midnight()
+            .assume_utc()
+            .unix_timestamp();
+
+        // Create the archive with the current schema
+        schema::initialize(&Connection::open(&path)?)?;
+
+        // A read-only attachment of the same file would block writing to it
+        let attached = self.detach_archives()?;
+        let moved = self.move_to_archive(&path, cutoff);
+        for archive in attached {
+            self.attach_archive(archive)?;
+        }
+        let (history, readings) = moved?;
+        self.attach_archive(&path)?;
+
+        info!(
+            "Rolled over {} history records and {} readings into {}",
+            history,
+            readings,
+            path.display()
+        );
+
+        Ok(RolloverSummary {
+            year,
+            path,
+            history,
+            readings,
+        })
+    }
+
+    /// Copy rows older than `cutoff` into the archive at `path` and delete
+    /// them here. Returns the (history, readings) counts moved.
+    fn move_to_archive(&self, path: &Path, cutoff: i64) -> Result<(u64, u64)> {
+        self.conn
+            .execute("ATTACH DATABASE ?1 AS rollover", [path.to_string_lossy()])?;
+
+        let result = (|| {
+            let tx = self.conn.unchecked_transaction()?;
+            tx.execute(
+                &format!(
+                    "INSERT OR REPLACE INTO rollover.devices ({DEVICES_COLUMNS})
+                     SELECT {DEVICES_COLUMNS} FROM main.devices WHERE id IN (
+                         SELECT device_id FROM main.history WHERE timestamp < ?1
+                         UNION SELECT device_id FROM main.readings WHERE captured_at < ?1
+                     )"
+                ),
+                [cutoff],
+            )?;
+            tx.execute(
+                &format!(
+                    "INSERT OR IGNORE INTO rollover.history ({HISTORY_COLUMNS})
+                     SELECT {HISTORY_COLUMNS} FROM main.history WHERE timestamp < ?1"
+                ),
+                [cutoff],
+            )?;
+            tx.execute(
+                &format!(
+                    "INSERT OR IGNORE INTO rollover.readings ({READINGS_COLUMNS})
+                     SELECT {READINGS_COLUMNS} FROM main.readings WHERE captured_at < ?1"
+                ),
+                [cutoff],
+            )?;
+            let history = tx.execute("DELETE FROM main.history WHERE timestamp < ?1", [cutoff])?;
+            let readings =
+                tx.execute("DELETE FROM main.readings WHERE captured_at < ?1", [cutoff])?;
+            tx.commit()?;
+            Ok((history as u64, readings as u64))
+        })();
+
+        self.conn.execute("DETACH DATABASE rollover", [])?;
+        result
+    }
+
+    /// Detach every archive, returning their paths.
+    fn detach_archives(&mut self) -> Result<Vec<PathBuf>> {
+        self.conn.execute_batch(
+            "DROP VIEW IF EXISTS temp.history_all;
+             DROP VIEW IF EXISTS temp.readings_all;",
+        )?;
+        for index in 0..self.archives.len() {
+            self.conn
+                .execute(&format!("DETACH DATABASE {}", archive_alias(index)), [])?;
+        }
+        Ok(std::mem::take(&mut self.archives))
+    }
+
+    /// Recreate the views that union the active tables with each archive.
+    fn rebuild_archive_views(&self) -> Result<()> {
+        // Archives are read-only, so older ones are never migrated
+        let mut readings_columns = Vec::with_capacity(self.archives.len());
+        for index in 0..self.archives.len() {
+            let mut columns = vec![READINGS_ARCHIVE_COLUMNS.to_string()];
+            for (column, default) in READINGS_ADDED_COLUMNS {
+                let present: bool = self.conn.query_row(
+                    "SELECT COUNT(*) > 0 FROM pragma_table_info('readings', ?1) \
+                     WHERE name = ?2",
+                    [archive_alias(index), column.to_string()],
+                    |row| row.get(0),
+                )?;
+                columns.push(if present {
+                    column.to_string()
+                } else {
+                    format!("{default} AS {column}")
+                });
+            }
+            readings_columns.push(columns.join(", "));
+        }
+
+        // Each archive's SELECT uses the columns it actually has
+        let union = |table: &str, columns: &str, archive_columns: &[&str]| {
+            std::iter::once(format!("SELECT {columns} FROM main.{table}"))
+                .chain(archive_columns.iter().enumerate().map(|(index, columns)| {
+                    format!("SELECT {columns} FROM {}.{table}", archive_alias(index))
+                }))
+                .collect::<Vec<_>>()
+                .join(" UNION ALL ")
+        };
+        self.conn.execute_batch(&format!(
+            "DROP VIEW IF EXISTS temp.history_all;
+             DROP VIEW IF EXISTS temp.readings_all;
+             CREATE TEMP VIEW history_all AS {};
+             CREATE TEMP VIEW readings_all AS {};",
+            union(
+                "history",
+                HISTORY_COLUMNS,
+                &vec![HISTORY_COLUMNS; self.archives.len()]
+            ),
+            union(
+                "readings",
+                READINGS_COLUMNS,
+                &readings_columns
+                    .iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+            ),
+        ))?;
+        Ok(())
+    }
+
+    /// Table or view that history queries read from.
+    fn history_source(&self) -> &'static str {
+        if self.archives.is_empty() {
+            "history"
+        } else {
+            "temp.history_all"
+        }
+    }
+
+    /// Table or view that reading queries read from.
+    fn readings_source(&self) -> &'static str {
+        if self.archives.is_empty() {
+            "readings"
+        } else {
+            "temp.readings_all"
+        }
+    }
+}
+
+/// Schema name of the archive attached at `index`.
+fn archive_alias(index: usize) -> String {
+    format!("archive_{index}")
+}
+
+/// SQLite URI that opens `path` read-only.
+fn read_only_uri(path: &Path) -> String {
+    let path = path
+        .to_string_lossy()
+        .replace('%', "%25")
+        .replace('?', "%3f")
+        .replace('#', "%23");
+    format!("file:{path}?mode=ro")
+}
+
+// Sync state operations
+impl Store {
+    /// Get the sync state for a device.
+    ///
+    /// Sync state tracks the last downloaded history index and total readings,
+    /// enabling incremental history downloads instead of re-downloading everything.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_id` - The device to get sync state for
     ///
     /// # Returns
     ///
@@ -1104,909 +2207,3795 @@ impl Store {
         Ok(())
     }
 
-    /// Calculate the start index for incremental sync.
+    /// Record the latest clock drift estimate for a device.
     ///
-    /// Returns the index to start downloading from (1-based).
-    /// If the device has new readings since last sync, returns the next index.
-    /// If this is the first sync, returns 1 to download all.
+    /// Replaces any previous estimate. `span_seconds` is the observation
+    /// window the estimate covers; longer windows are more precise.
+    pub fn update_clock_drift(
+        &self,
+        device_id: &str,
+        drift_ppm: f64,
+        span_seconds: i64,
+    ) -> Result<()> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+
+        self.conn.execute(
+            "INSERT INTO clock_drift (device_id, drift_ppm, span_seconds, measured_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(device_id) DO UPDATE SET
+                drift_ppm = ?2,
+                span_seconds = ?3,
+                measured_at = ?4",
+            rusqlite::params![device_id, drift_ppm, span_seconds, now],
+        )?;
+
+        debug!(
+            "Updated clock drift for {}: {:.1} ppm over {}s",
+            device_id, drift_ppm, span_seconds
+        );
+
+        Ok(())
+    }
+
+    /// Get the latest clock drift estimate for a device, if one was recorded.
+    pub fn get_clock_drift(&self, device_id: &str) -> Result<Option<StoredClockDrift>> {
+        let drift = self
+            .conn
+            .query_row(
+                "SELECT device_id, drift_ppm, span_seconds, measured_at
+                 FROM clock_drift WHERE device_id = ?",
+                [device_id],
+                |row| {
+                    Ok(StoredClockDrift {
+                        device_id: row.get(0)?,
+                        drift_ppm: row.get(1)?,
+                        span_seconds: row.get(2)?,
+                        measured_at: timestamp_from_unix(row.get(3)?),
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(drift)
+    }
+
+    /// Record the current value of a device setting.
     ///
-    /// # Buffer Wrap-Around Detection
+    /// A change is only written when `value` differs from the last value
+    /// recorded for this setting, so this can be called on every poll.
+    /// Returns the recorded change, or `None` if the value was unchanged.
     ///
-    /// Aranet devices have a circular buffer (e.g., ~2016 readings for Aranet4 at 10-min
-    /// intervals). When the buffer fills up, new readings replace the oldest ones, but
-    /// `total_readings` stays constant. This function detects this wrap-around case by
-    /// comparing the latest stored timestamp with the expected time since last sync.
-    pub fn calculate_sync_start(&self, device_id: &str, current_total: u16) -> Result<u16> {
-        let state = self.get_sync_state(device_id)?;
+    /// Setting names used by this workspace are [`SETTING_INTERVAL`] (seconds),
+    /// [`SETTING_BLUETOOTH_RANGE`](crate::SETTING_BLUETOOTH_RANGE)
+    /// (`standard`/`extended`) and [`SETTING_SMART_HOME`](crate::SETTING_SMART_HOME)
+    /// (`true`/`false`). Interval changes are recorded automatically by
+    /// [`insert_reading`](Self::insert_reading).
+    pub fn record_setting(
+        &self,
+        device_id: &str,
+        setting: &str,
+        value: &str,
+        changed_at: OffsetDateTime,
+    ) -> Result<Option<StoredSettingChange>> {
+        let previous: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT new_value FROM settings_history
+                 WHERE device_id = ?1 AND setting = ?2
+                 ORDER BY changed_at DESC, id DESC LIMIT 1",
+                rusqlite::params![device_id, setting],
+                |row| row.get(0),
+            )
+            .optional()?;
 
-        match state {
-            Some(s) if s.total_readings == Some(current_total) => {
-                // Same total readings as last sync - could mean:
-                // 1. No new readings (buffer not full, recent sync)
-                // 2. Buffer wrapped (old readings replaced with new)
-                // 3. History cache was cleared but sync state exists
+        if previous.as_deref() == Some(value) {
+            return Ok(None);
+        }
 
-                // Check if buffer has likely wrapped by comparing timestamps
-                if s.last_sync_at.is_some() {
-                    let latest_stored = self.get_latest_history_timestamp(device_id)?;
+        self.upsert_device(device_id, None)?;
+        self.conn.execute(
+            "INSERT INTO settings_history (device_id, setting, old_value, new_value, changed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                device_id,
+                setting,
+                previous,
+                value,
+                changed_at.unix_timestamp()
+            ],
+        )?;
 
-                    match latest_stored {
-                        Some(latest_ts) => {
-                            let now = OffsetDateTime::now_utc();
-                            let time_since_latest = now - latest_ts;
+        debug!(
+            "Recorded {} change for {}: {:?} -> {}",
+            setting, device_id, previous, value
+        );
 
-                            // If more than 10 minutes since latest record, new data likely exists
-                            // (10 min is the longest standard Aranet4 interval)
-                            if time_since_latest > time::Duration::minutes(10) {
-                                debug!(
-                                    "Buffer may have wrapped for {} (latest record is {} min old), doing full sync",
-                                    device_id,
-                                    time_since_latest.whole_minutes()
-                                );
-                                return Ok(1);
-                            }
+        Ok(Some(StoredSettingChange {
+            id: self.conn.last_insert_rowid(),
+            device_id: device_id.to_string(),
+            setting: setting.to_string(),
+            old_value: previous,
+            new_value: value.to_string(),
+            changed_at,
+        }))
+    }
 
-                            // Recent sync and no indication of wrap-around
-                            debug!("No new readings for {}", device_id);
-                            Ok(current_total.saturating_add(1))
-                        }
-                        None => {
-                            // Sync state exists but no history records - cache was likely cleared
-                            // Do a full sync to repopulate
-                            debug!(
-                                "Sync state exists but no history for {}, doing full sync",
-                                device_id
-                            );
-                            Ok(1)
-                        }
-                    }
-                } else {
-                    // No last_sync_at - shouldn't happen but do full sync to be safe
-                    debug!("No sync timestamp for {}, doing full sync", device_id);
-                    Ok(1)
-                }
-            }
-            Some(s) if s.last_history_index.is_some() => {
-                // We have previous state, calculate new records
-                let last_index = match s.last_history_index {
-                    Some(idx) => idx,
-                    None => unreachable!("guarded by is_some() in match arm"),
-                };
-                let prev_total = s.total_readings.unwrap_or(0);
+    /// Get all recorded setting changes for a device, oldest first.
+    ///
+    /// Useful for explaining sudden changes in data density (interval
+    /// changes) or battery drain (extended range, Smart Home).
+    pub fn settings_history(&self, device_id: &str) -> Result<Vec<StoredSettingChange>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, device_id, setting, old_value, new_value, changed_at
+             FROM settings_history WHERE device_id = ?
+             ORDER BY changed_at, id",
+        )?;
 
-                // Check if device was reset (current_total < prev_total)
-                if current_total < prev_total {
-                    debug!(
-                        "Device total decreased ({} -> {}) for {}, device was reset - doing full sync",
-                        prev_total, current_total, device_id
-                    );
-                    return Ok(1);
-                }
+        let changes = stmt
+            .query_map([device_id], |row| {
+                Ok(StoredSettingChange {
+                    id: row.get(0)?,
+                    device_id: row.get(1)?,
+                    setting: row.get(2)?,
+                    old_value: row.get(3)?,
+                    new_value: row.get(4)?,
+                    changed_at: timestamp_from_unix(row.get(5)?),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
-                let new_count = current_total.saturating_sub(prev_total);
+        Ok(changes)
+    }
 
-                if new_count > 0 {
-                    // Start from where we left off
-                    let start = last_index.saturating_add(1);
+    /// Record an alert raised for a device.
+    ///
+    /// `kind` is one of the `ALERT_*` constants (e.g. [`ALERT_CO2`](crate::ALERT_CO2))
+    /// and `severity` is `info`, `warning` or `critical`. New alerts are
+    /// unacknowledged.
+    pub fn insert_alert(
+        &self,
+        device_id: &str,
+        kind: &str,
+        severity: &str,
+        value: Option<f64>,
+        message: &str,
+        triggered_at: OffsetDateTime,
+    ) -> Result<StoredAlert> {
+        self.upsert_device(device_id, None)?;
+        self.conn.execute(
+            "INSERT INTO alerts (device_id, kind, severity, value, message, triggered_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                device_id,
+                kind,
+                severity,
+                value,
+                message,
+                triggered_at.unix_timestamp()
+            ],
+        )?;
 
-                    // Validate start index doesn't exceed current total
-                    // This can happen if device buffer wrapped or was reset
-                    if start > current_total {
-                        debug!(
-                            "Start index {} exceeds device total {} for {}, doing full sync",
-                            start, current_total, device_id
-                        );
-                        return Ok(1);
-                    }
+        Ok(StoredAlert {
+            id: self.conn.last_insert_rowid(),
+            device_id: device_id.to_string(),
+            kind: kind.to_string(),
+            severity: severity.to_string(),
+            value,
+            message: message.to_string(),
+            triggered_at,
+            acknowledged: false,
+        })
+    }
 
-                    debug!(
-                        "Incremental sync for {}: {} new readings, starting at {}",
-                        device_id, new_count, start
-                    );
-                    Ok(start)
-                } else {
-                    Ok(current_total.saturating_add(1))
-                }
-            }
-            _ => {
-                // First sync - download all
-                debug!(
-                    "First sync for {}: downloading all {} readings",
-                    device_id, current_total
-                );
-                Ok(1)
-            }
+    /// Query stored alerts, newest first.
+    pub fn query_alerts(&self, query: &AlertQuery) -> Result<Vec<StoredAlert>> {
+        let (where_clause, params) = query.build_where();
+        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let mut sql = format!(
+            "SELECT id, device_id, kind, severity, value, message, triggered_at, acknowledged
+             FROM alerts {where_clause} ORDER BY triggered_at DESC, id DESC"
+        );
+        if let Some(limit) = query.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
         }
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let alerts = stmt
+            .query_map(params_ref.as_slice(), |row| {
+                Ok(StoredAlert {
+                    id: row.get(0)?,
+                    device_id: row.get(1)?,
+                    kind: row.get(2)?,
+                    severity: row.get(3)?,
+                    value: row.get(4)?,
+                    message: row.get(5)?,
+                    triggered_at: timestamp_from_unix(row.get(6)?),
+                    acknowledged: row.get(7)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(alerts)
     }
 
-    /// Get the timestamp of the most recent history record for a device.
+    /// Mark a single alert as acknowledged.
     ///
-    /// Returns `None` if no history exists for the device.
-    fn get_latest_history_timestamp(&self, device_id: &str) -> Result<Option<OffsetDateTime>> {
-        let ts: Option<i64> = self
+    /// Returns `false` if no alert with this ID exists.
+    pub fn acknowledge_alert(&self, id: i64) -> Result<bool> {
+        let updated = self
             .conn
-            .query_row(
-                "SELECT MAX(timestamp) FROM history WHERE device_id = ?",
-                [device_id],
-                |row| row.get(0),
-            )
-            .optional()?
-            .flatten();
+            .execute("UPDATE alerts SET acknowledged = 1 WHERE id = ?1", [id])?;
+        Ok(updated > 0)
+    }
 
-        Ok(ts.map(timestamp_from_unix))
+    /// Delete a single alert.
+    ///
+    /// Returns `false` if no alert with this ID exists.
+    pub fn delete_alert(&self, id: i64) -> Result<bool> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM alerts WHERE id = ?1", [id])?;
+        Ok(deleted > 0)
     }
-}
 
-/// Aggregate statistics for history data.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct HistoryStats {
-    /// Number of records.
-    pub count: u64,
-    /// Minimum values.
-    pub min: HistoryAggregates,
-    /// Maximum values.
-    pub max: HistoryAggregates,
-    /// Average values.
-    pub avg: HistoryAggregates,
-    /// Time range of records.
-    pub time_range: Option<(OffsetDateTime, OffsetDateTime)>,
-}
+    /// Mark all alerts matching `query` as acknowledged.
+    ///
+    /// The query's limit is ignored. Returns the number of alerts updated.
+    pub fn acknowledge_alerts(&self, query: &AlertQuery) -> Result<u64> {
+        let (where_clause, params) = query.build_where();
+        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let updated = self.conn.execute(
+            &format!("UPDATE alerts SET acknowledged = 1 {where_clause}"),
+            params_ref.as_slice(),
+        )?;
+        Ok(updated as u64)
+    }
 
-/// Aggregate values for a single metric set.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct HistoryAggregates {
-    /// CO2 in ppm.
-    pub co2: Option<f64>,
-    /// Temperature in Celsius.
-    pub temperature: Option<f64>,
-    /// Pressure in hPa.
-    pub pressure: Option<f64>,
-    /// Humidity percentage.
-    pub humidity: Option<f64>,
-    /// Radon in Bq/m3 (for radon devices).
-    pub radon: Option<f64>,
-}
+    /// Delete all alerts matching `query`.
+    ///
+    /// The query's limit is ignored. Returns the number of alerts deleted.
+    pub fn clear_alerts(&self, query: &AlertQuery) -> Result<u64> {
+        let (where_clause, params) = query.build_where();
+        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let deleted = self.conn.execute(
+            &format!("DELETE FROM alerts {where_clause}"),
+            params_ref.as_slice(),
+        )?;
+        Ok(deleted as u64)
+    }
 
-// Aggregate and export operations
-impl Store {
-    /// Calculate aggregate statistics for history records.
+    /// Append a raw advertisement to the opt-in advertisement log.
     ///
-    /// Computes min, max, and average values for all sensor metrics across
-    /// the records matching the query. Useful for dashboards and reports.
+    /// Payloads longer than 255 bytes are truncated. The log is not trimmed
+    /// here; call [`Store::trim_advertisements`] after a batch to cap its size.
+    pub fn log_advertisement(
+        &self,
+        device_id: &str,
+        rssi: Option<i16>,
+        payload: &[u8],
+        seen_at: OffsetDateTime,
+    ) -> Result<i64> {
+        let payload = &payload[..payload.len().min(MAX_ADVERTISEMENT_PAYLOAD)];
+        self.conn.execute(
+            "INSERT INTO advertisements (device_id, seen_at, rssi, payload)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![device_id, seen_at.unix_timestamp(), rssi, payload],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Query the advertisement log, oldest first.
+    pub fn query_advertisements(
+        &self,
+        query: &AdvertisementQuery,
+    ) -> Result<Vec<StoredAdvertisement>> {
+        let (where_clause, params) = query.build_where();
+        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let mut sql = format!(
+            "SELECT id, device_id, seen_at, rssi, payload
+             FROM advertisements {where_clause} ORDER BY seen_at, id"
+        );
+        if let Some(limit) = query.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let adverts = stmt
+            .query_map(params_ref.as_slice(), |row| {
+                Ok(StoredAdvertisement {
+                    id: row.get(0)?,
+                    device_id: row.get(1)?,
+                    seen_at: timestamp_from_unix(row.get(2)?),
+                    rssi: row.get(3)?,
+                    payload: row.get(4)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(adverts)
+    }
+
+    /// Delete the oldest logged advertisements so at most `max_rows` remain.
     ///
-    /// # Arguments
+    /// Returns the number of rows deleted. See [`ADVERTISEMENT_LOG_MAX_ROWS`]
+    /// for a sensible default.
+    pub fn trim_advertisements(&self, max_rows: u64) -> Result<u64> {
+        let deleted = self.conn.execute(
+            "DELETE FROM advertisements WHERE id NOT IN
+             (SELECT id FROM advertisements ORDER BY id DESC LIMIT ?1)",
+            [i64::try_from(max_rows).unwrap_or(i64::MAX)],
+        )?;
+        if deleted > 0 {
+            debug!("Trimmed {} old advertisement(s) from the log", deleted);
+        }
+        Ok(deleted as u64)
+    }
+
+    /// Attach a note to a device's data.
     ///
-    /// * `query` - Filter which records to include in the statistics
+    /// Pass a single point as `t..=t`; any other range is stored as a range
+    /// annotation. Returns [`Error::InvalidTimestamp`] if the range ends
+    /// before it starts.
     ///
     /// # Example
     ///
     /// ```
-    /// use aranet_store::{Store, HistoryQuery};
-    /// use time::{OffsetDateTime, Duration};
+    /// use aranet_store::Store;
+    /// use time::OffsetDateTime;
     ///
     /// let store = Store::open_in_memory()?;
-    ///
-    /// // Get stats for last 24 hours
-    /// let yesterday = OffsetDateTime::now_utc() - Duration::hours(24);
-    /// let query = HistoryQuery::new()
-    ///     .device("Aranet4 17C3C")
-    ///     .since(yesterday);
-    ///
-    /// let stats = store.history_stats(&query)?;
-    /// if let Some(avg_co2) = stats.avg.co2 {
-    ///     println!("Average CO2: {:.0} ppm", avg_co2);
-    /// }
-    /// if let Some((start, end)) = stats.time_range {
-    ///     println!("Time range: {} to {}", start, end);
-    /// }
+    /// let now = OffsetDateTime::now_utc();
+    /// store.add_annotation("Aranet4 17C3C", now..=now, "Window opened", Some("ventilation"))?;
     /// # Ok::<(), aranet_store::Error>(())
     /// ```
-    pub fn history_stats(&self, query: &HistoryQuery) -> Result<HistoryStats> {
-        let mut conditions = Vec::new();
-        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
-
-        if let Some(ref device_id) = query.device_id {
-            conditions.push("device_id = ?");
-            params.push(Box::new(device_id.clone()));
+    pub fn add_annotation(
+        &self,
+        device_id: &str,
+        range: RangeInclusive<OffsetDateTime>,
+        text: &str,
+        tag: Option<&str>,
+    ) -> Result<StoredAnnotation> {
+        let (start, end) = range.into_inner();
+        if end < start {
+            return Err(Error::InvalidTimestamp(format!(
+                "annotation ends ({end}) before it starts ({start})"
+            )));
         }
+        let end = (end != start).then_some(end);
+        let created_at = OffsetDateTime::now_utc();
 
-        if let Some(since) = query.since {
-            conditions.push("timestamp >= ?");
-            params.push(Box::new(since.unix_timestamp()));
-        }
+        self.upsert_device(device_id, None)?;
+        self.conn.execute(
+            "INSERT INTO annotations (device_id, start_at, end_at, text, tag, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                device_id,
+                start.unix_timestamp(),
+                end.map(|t| t.unix_timestamp()),
+                text,
+                tag,
+                created_at.unix_timestamp()
+            ],
+        )?;
+
+        Ok(StoredAnnotation {
+            id: self.conn.last_insert_rowid(),
+            device_id: device_id.to_string(),
+            start,
+            end,
+            text: text.to_string(),
+            tag: tag.map(str::to_string),
+            created_at,
+        })
+    }
 
-        if let Some(until) = query.until {
-            conditions.push("timestamp <= ?");
-            params.push(Box::new(until.unix_timestamp()));
+    /// Query annotations, oldest first.
+    pub fn query_annotations(&self, query: &AnnotationQuery) -> Result<Vec<StoredAnnotation>> {
+        let (where_clause, params) = query.build_where();
+        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let mut sql = format!(
+            "SELECT id, device_id, start_at, end_at, text, tag, created_at
+             FROM annotations {where_clause} ORDER BY start_at ASC, id ASC"
+        );
+        if let Some(limit) = query.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
         }
 
-        let where_clause = if conditions.is_empty() {
-            String::new()
-        } else {
-            format!("WHERE {}", conditions.join(" AND "))
-        };
+        let mut stmt = self.conn.prepare(&sql)?;
+        let annotations = stmt
+            .query_map(params_ref.as_slice(), |row| {
+                Ok(StoredAnnotation {
+                    id: row.get(0)?,
+                    device_id: row.get(1)?,
+                    start: timestamp_from_unix(row.get(2)?),
+                    end: row.get::<_, Option<i64>>(3)?.map(timestamp_from_unix),
+                    text: row.get(4)?,
+                    tag: row.get(5)?,
+                    created_at: timestamp_from_unix(row.get(6)?),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        let sql = format!(
-            "SELECT
-                COUNT(*) as count,
-                MIN(co2) as min_co2, MAX(co2) as max_co2, AVG(co2) as avg_co2,
-                MIN(temperature) as min_temp, MAX(temperature) as max_temp, AVG(temperature) as avg_temp,
-                MIN(pressure) as min_press, MAX(pressure) as max_press, AVG(pressure) as avg_press,
-                MIN(humidity) as min_hum, MAX(humidity) as max_hum, AVG(humidity) as avg_hum,
-                MIN(radon) as min_radon, MAX(radon) as max_radon, AVG(radon) as avg_radon,
-                MIN(timestamp) as min_ts, MAX(timestamp) as max_ts
-             FROM history {}",
-            where_clause
-        );
+        Ok(annotations)
+    }
 
-        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    /// Delete a single annotation.
+    ///
+    /// Returns `false` if no annotation with this ID exists.
+    pub fn delete_annotation(&self, id: i64) -> Result<bool> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM annotations WHERE id = ?1", [id])?;
+        Ok(deleted > 0)
+    }
 
-        let stats = self.conn.query_row(&sql, params_refs.as_slice(), |row| {
-            let count: i64 = row.get(0)?;
-            let min_ts: Option<i64> = row.get(16)?;
-            let max_ts: Option<i64> = row.get(17)?;
+    /// Store a value of a computed channel, e.g. dew point, for a device.
+    pub fn insert_channel_value(
+        &self,
+        device_id: &str,
+        channel: &str,
+        captured_at: OffsetDateTime,
+        value: f64,
+    ) -> Result<i64> {
+        self.upsert_device(device_id, None)?;
+        self.conn.execute(
+            "INSERT INTO channel_values (device_id, channel, captured_at, value)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![device_id, channel, captured_at.unix_timestamp(), value],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
 
-            let time_range = match (min_ts, max_ts) {
-                (Some(min), Some(max)) => {
-                    Some((timestamp_from_unix(min), timestamp_from_unix(max)))
-                }
-                _ => None,
-            };
+    /// Query computed channel values, oldest first.
+    pub fn query_channel_values(&self, query: &ChannelQuery) -> Result<Vec<StoredChannelValue>> {
+        let (where_clause, params) = query.build_where();
+        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let mut sql = format!(
+            "SELECT id, device_id, channel, captured_at, value
+             FROM channel_values {where_clause} ORDER BY captured_at ASC, id ASC"
+        );
+        if let Some(limit) = query.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
 
-            Ok(HistoryStats {
-                count: count as u64,
-                min: HistoryAggregates {
-                    co2: row.get::<_, Option<i64>>(1)?.map(|v| v as f64),
-                    temperature: row.get(4)?,
-                    pressure: row.get(7)?,
-                    humidity: row.get::<_, Option<i64>>(10)?.map(|v| v as f64),
-                    radon: row.get::<_, Option<i64>>(13)?.map(|v| v as f64),
-                },
-                max: HistoryAggregates {
-                    co2: row.get::<_, Option<i64>>(2)?.map(|v| v as f64),
-                    temperature: row.get(5)?,
-                    pressure: row.get(8)?,
-                    humidity: row.get::<_, Option<i64>>(11)?.map(|v| v as f64),
-                    radon: row.get::<_, Option<i64>>(14)?.map(|v| v as f64),
-                },
-                avg: HistoryAggregates {
-                    co2: row.get(3)?,
-                    temperature: row.get(6)?,
-                    pressure: row.get(9)?,
-                    humidity: row.get(12)?,
-                    radon: row.get(15)?,
-                },
-                time_range,
-            })
-        })?;
+        let mut stmt = self.conn.prepare(&sql)?;
+        let values = stmt
+            .query_map(params_ref.as_slice(), channel_value_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        Ok(stats)
+        Ok(values)
     }
 
-    /// Export history records to CSV format.
-    ///
-    /// Exports records matching the query to a CSV string with the following columns:
-    /// `timestamp`, `device_id`, `co2`, `temperature`, `pressure`, `humidity`, `radon`.
-    ///
-    /// Timestamps are formatted as RFC 3339 (e.g., `2024-01-15T10:30:00Z`).
-    ///
-    /// # Arguments
-    ///
-    /// * `query` - Filter which records to export
+    /// The latest value of every computed channel, optionally for one device.
     ///
-    /// # Example
+    /// Ordered by device and channel name.
+    pub fn latest_channel_values(
+        &self,
+        device_id: Option<&str>,
+    ) -> Result<Vec<StoredChannelValue>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, device_id, channel, captured_at, value
+             FROM channel_values c
+             WHERE (?1 IS NULL OR device_id = ?1)
+               AND id = (
+                   SELECT latest.id FROM channel_values latest
+                   WHERE latest.device_id = c.device_id AND latest.channel = c.channel
+                   ORDER BY latest.captured_at DESC, latest.id DESC
+                   LIMIT 1
+               )
+             ORDER BY device_id, channel",
+        )?;
+        let values = stmt
+            .query_map([device_id], channel_value_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(values)
+    }
+
+    /// Query history together with the annotations that fall within it.
     ///
-    /// ```
-    /// use aranet_store::{Store, HistoryQuery};
-    /// use std::fs;
+    /// Annotations are those of the query's device (or all devices) that
+    /// overlap the time span of the returned records, so paginated queries
+    /// only carry the annotations for their page.
+    pub fn query_history_annotated(&self, query: &HistoryQuery) -> Result<AnnotatedHistory> {
+        let records = self.query_history(query)?;
+
+        let span = records.iter().map(|r| r.timestamp).fold(
+            None,
+            |span: Option<(OffsetDateTime, OffsetDateTime)>, t| {
+                Some(span.map_or((t, t), |(lo, hi)| (lo.min(t), hi.max(t))))
+            },
+        );
+        let annotations = match span {
+            Some((since, until)) => {
+                let mut annotation_query = AnnotationQuery::new().since(since).until(until);
+                annotation_query.device_id = query.device_id.clone();
+                self.query_annotations(&annotation_query)?
+            }
+            None => Vec::new(),
+        };
+
+        Ok(AnnotatedHistory {
+            records,
+            annotations,
+        })
+    }
+
+    /// Match the annotations for `query`'s device and time range to history records.
+    fn annotation_labels(&self, query: &HistoryQuery) -> Result<AnnotationLabels> {
+        let mut annotation_query = AnnotationQuery::new();
+        annotation_query.device_id = query.device_id.clone();
+        annotation_query.since = query.since;
+        annotation_query.until = query.until;
+
+        let mut labels = AnnotationLabels::default();
+        for annotation in self.query_annotations(&annotation_query)? {
+            if !annotation.is_point() {
+                labels.ranges.push(annotation);
+                continue;
+            }
+            let target: Option<i64> = self.conn.query_row(
+                &format!(
+                    "SELECT MIN(timestamp) FROM {} WHERE device_id = ?1 AND timestamp >= ?2",
+                    self.history_source()
+                ),
+                rusqlite::params![annotation.device_id, annotation.start.unix_timestamp()],
+                |row| row.get(0),
+            )?;
+            if let Some(target) = target {
+                labels
+                    .points
+                    .entry((annotation.device_id, target))
+                    .or_default()
+                    .push(annotation.text);
+            }
+        }
+        Ok(labels)
+    }
+
+    /// Calculate the start index for incremental sync.
     ///
-    /// let store = Store::open_in_memory()?;
+    /// Returns the index to start downloading from (1-based).
+    /// If the device has new readings since last sync, returns the next index.
+    /// If this is the first sync, returns 1 to download all.
     ///
-    /// let query = HistoryQuery::new().device("Aranet4 17C3C").oldest_first();
-    /// let csv = store.export_history_csv(&query)?;
+    /// # Buffer Wrap-Around Detection
     ///
-    /// // Write to file
-    /// // fs::write("history.csv", &csv)?;
-    /// # Ok::<(), aranet_store::Error>(())
-    /// ```
-    pub fn export_history_csv(&self, query: &HistoryQuery) -> Result<String> {
-        let sql = query.build_sql_with_select(
-            "SELECT timestamp, device_id, co2, temperature, pressure, humidity, radon, \
-             radiation_rate, radiation_total FROM history",
-        );
-        let (_, params) = query.build_where();
-        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
-        let mut stmt = self.conn.prepare(&sql)?;
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-
-        // Header
-        wtr.write_record([
-            "timestamp",
-            "device_id",
-            "co2",
-            "temperature",
-            "pressure",
-            "humidity",
-            "radon",
-            "radiation_rate",
-            "radiation_total",
-        ])
-        .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    /// Aranet devices have a circular buffer (e.g., ~2016 readings for Aranet4 at 10-min
+    /// intervals). When the buffer fills up, new readings replace the oldest ones, but
+    /// `total_readings` stays constant. This function detects this wrap-around case by
+    /// comparing the latest stored timestamp with the expected time since last sync.
+    pub fn calculate_sync_start(&self, device_id: &str, current_total: u16) -> Result<u16> {
+        let state = self.get_sync_state(device_id)?;
 
-        let rows = stmt.query_map(params_ref.as_slice(), |row| {
-            Ok((
-                timestamp_from_unix(row.get(0)?),
-                row.get::<_, String>(1)?,
-                u16::try_from(row.get::<_, i64>(2)?).unwrap_or_else(|e| {
-                    warn!("Invalid co2 value in export row: {e}");
-                    0
-                }),
-                row.get::<_, f32>(3)?,
-                row.get::<_, f32>(4)?,
-                u8::try_from(row.get::<_, i64>(5)?).unwrap_or_else(|e| {
-                    warn!("Invalid humidity value in export row: {e}");
-                    0
-                }),
-                row.get::<_, Option<i64>>(6)?
-                    .and_then(|v| u32::try_from(v).ok()),
-                row.get::<_, Option<f64>>(7)?,
-                row.get::<_, Option<f64>>(8)?,
-            ))
-        })?;
+        match state {
+            Some(s) if s.total_readings == Some(current_total) => {
+                // Same total readings as last sync - could mean:
+                // 1. No new readings (buffer not full, recent sync)
+                // 2. Buffer wrapped (old readings replaced with new)
+                // 3. History cache was cleared but sync state exists
 
-        // Data rows
-        for row in rows {
-            let (
-                timestamp,
-                device_id,
-                co2,
-                temperature,
-                pressure,
-                humidity,
-                radon,
-                radiation_rate,
-                radiation_total,
-            ) = row?;
-            let timestamp = match timestamp.format(&time::format_description::well_known::Rfc3339) {
-                Ok(ts) => ts,
-                Err(e) => {
-                    warn!("Skipping CSV row with unformattable timestamp: {e}");
-                    continue;
+                // Check if buffer has likely wrapped by comparing timestamps
+                if s.last_sync_at.is_some() {
+                    let latest_stored = self.get_latest_history_timestamp(device_id)?;
+
+                    match latest_stored {
+                        Some(latest_ts) => {
+                            let now = OffsetDateTime::now_utc();
+                            let time_since_latest = now - latest_ts;
+
+                            // If more than 10 minutes since latest record, new data likely exists
+                            // (10 min is the longest standard Aranet4 interval)
+                            if time_since_latest > time::Duration::minutes(10) {
+                                debug!(
+                                    "Buffer may have wrapped for {} (latest record is {} min old), doing full sync",
+                                    device_id,
+                                    time_since_latest.whole_minutes()
+                                );
+                                return Ok(1);
+                            }
+
+                            // Recent sync and no indication of wrap-around
+                            debug!("No new readings for {}", device_id);
+                            Ok(current_total.saturating_add(1))
+                        }
+                        None => {
+                            // Sync state exists but no history records - cache was likely cleared
+                            // Do a full sync to repopulate
+                            debug!(
+                                "Sync state exists but no history for {}, doing full sync",
+                                device_id
+                            );
+                            Ok(1)
+                        }
+                    }
+                } else {
+                    // No last_sync_at - shouldn't happen but do full sync to be safe
+                    debug!("No sync timestamp for {}, doing full sync", device_id);
+                    Ok(1)
+                }
+            }
+            Some(s) if s.last_history_index.is_some() => {
+                // We have previous state, calculate new records
+                let last_index = match s.last_history_index {
+                    Some(idx) => idx,
+                    None => unreachable!("guarded by is_some() in match arm"),
+                };
+                let prev_total = s.total_readings.unwrap_or(0);
+
+                // Check if device was reset (current_total < prev_total)
+                if current_total < prev_total {
+                    debug!(
+                        "Device total decreased ({} -> {}) for {}, device was reset - doing full sync",
+                        prev_total, current_total, device_id
+                    );
+                    return Ok(1);
+                }
+
+                let new_count = current_total.saturating_sub(prev_total);
+
+                if new_count > 0 {
+                    // Start from where we left off
+                    let start = last_index.saturating_add(1);
+
+                    // Validate start index doesn't exceed current total
+                    // This can happen if device buffer wrapped or was reset
+                    if start > current_total {
+                        debug!(
+                            "Start index {} exceeds device total {} for {}, doing full sync",
+                            start, current_total, device_id
+                        );
+                        return Ok(1);
+                    }
+
+                    debug!(
+                        "Incremental sync for {}: {} new readings, starting at {}",
+                        device_id, new_count, start
+                    );
+                    Ok(start)
+                } else {
+                    Ok(current_total.saturating_add(1))
+                }
+            }
+            _ => {
+                // First sync - download all
+                debug!(
+                    "First sync for {}: downloading all {} readings",
+                    device_id, current_total
+                );
+                Ok(1)
+            }
+        }
+    }
+
+    /// Get the timestamp of the most recent history record for a device.
+    ///
+    /// Returns `None` if no history exists for the device.
+    fn get_latest_history_timestamp(&self, device_id: &str) -> Result<Option<OffsetDateTime>> {
+        let ts: Option<i64> = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT MAX(timestamp) FROM {} WHERE device_id = ?",
+                    self.history_source()
+                ),
+                [device_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+
+        Ok(ts.map(timestamp_from_unix))
+    }
+}
+
+/// Aggregate statistics for history data.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HistoryStats {
+    /// Number of records.
+    pub count: u64,
+    /// Minimum values.
+    pub min: HistoryAggregates,
+    /// Maximum values.
+    pub max: HistoryAggregates,
+    /// Average values.
+    pub avg: HistoryAggregates,
+    /// Time range of records.
+    pub time_range: Option<(OffsetDateTime, OffsetDateTime)>,
+}
+
+/// Aggregate values for a single metric set.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HistoryAggregates {
+    /// CO2 in ppm.
+    pub co2: Option<f64>,
+    /// Temperature in Celsius.
+    pub temperature: Option<f64>,
+    /// Pressure in hPa.
+    pub pressure: Option<f64>,
+    /// Humidity percentage.
+    pub humidity: Option<f64>,
+    /// Radon in Bq/m3 (for radon devices).
+    pub radon: Option<f64>,
+}
+
+/// Cached statistics for one device over one UTC day.
+///
+/// Maintained incrementally from history inserts, so summaries over weeks or
+/// months read one row per day instead of every raw record.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DailyStats {
+    /// Device identifier.
+    pub device_id: String,
+    /// Start of the day (UTC midnight).
+    #[serde(with = "time::serde::rfc3339")]
+    pub day: OffsetDateTime,
+    /// Number of history records for the day.
+    pub count: u64,
+    /// Records with a CO2 value (zero readings are skipped).
+    pub co2_count: u64,
+    /// Records with a pressure value (zero readings are skipped).
+    pub pressure_count: u64,
+    /// Records with a radon value.
+    pub radon_count: u64,
+    /// Minimum values.
+    pub min: HistoryAggregates,
+    /// Maximum values.
+    pub max: HistoryAggregates,
+    /// Average values.
+    pub avg: HistoryAggregates,
+    /// Records per CO2 bucket (< 1000, 1000-1399, >= 1400 ppm).
+    pub co2_buckets: ThresholdBuckets,
+    /// Records per radon bucket (< 150, 150-299, >= 300 Bq/m³).
+    pub radon_buckets: ThresholdBuckets,
+}
+
+/// Number of records falling into each threshold band.
+///
+/// With the device's fixed logging interval, counts are proportional to the
+/// time spent in each band.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ThresholdBuckets {
+    /// Records below the first threshold (green).
+    pub good: u64,
+    /// Records between the thresholds (yellow).
+    pub moderate: u64,
+    /// Records at or above the upper threshold (red).
+    pub poor: u64,
+}
+
+impl ThresholdBuckets {
+    /// Total number of bucketed records.
+    pub fn total(&self) -> u64 {
+        self.good + self.moderate + self.poor
+    }
+}
+
+/// Longest gap between two records that still counts as measured time.
+///
+/// Longer gaps (device out of range, history not synced) are treated as
+/// missing data rather than as time spent at the last CO₂ level.
+const EXPOSURE_MAX_GAP_SECS: i64 = 20 * 60;
+
+/// Cumulative CO₂ exposure for one device over one UTC day.
+///
+/// Exposure is the time-weighted excess over a threshold in ppm-hours: an
+/// hour at 1200 ppm adds 400 ppm·h above 800 ppm and 200 ppm·h above
+/// 1000 ppm. Each record stands for the time until the next record (the last
+/// one for the same span as the one before it), capped at 20 minutes.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ExposureStats {
+    /// Device identifier.
+    pub device_id: String,
+    /// Start of the day (UTC midnight).
+    #[serde(with = "time::serde::rfc3339")]
+    pub day: OffsetDateTime,
+    /// Hours covered by CO₂ records.
+    pub measured_hours: f64,
+    /// Hours spent above 800 ppm.
+    pub hours_above_800: f64,
+    /// Hours spent above 1000 ppm.
+    pub hours_above_1000: f64,
+    /// ppm-hours above 800 ppm.
+    pub ppm_hours_above_800: f64,
+    /// ppm-hours above 1000 ppm.
+    pub ppm_hours_above_1000: f64,
+}
+
+impl ExposureStats {
+    /// Compute per-day exposure from one device's `(timestamp, co2)` samples,
+    /// oldest first.
+    ///
+    /// Samples without a CO₂ value (`0`) are skipped, so devices without a
+    /// CO₂ sensor yield no days.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aranet_store::ExposureStats;
+    /// use time::macros::datetime;
+    ///
+    /// let samples = [
+    ///     (datetime!(2026-01-15 09:00 UTC), 900),
+    ///     (datetime!(2026-01-15 09:10 UTC), 1300),
+    /// ];
+    /// let days = ExposureStats::from_samples("office", samples);
+    /// // Two 10-minute spans: 100 and 500 ppm above 800
+    /// assert!((days[0].ppm_hours_above_800 - 100.0).abs() < 1e-9);
+    /// ```
+    pub fn from_samples(
+        device_id: &str,
+        samples: impl IntoIterator<Item = (OffsetDateTime, u16)>,
+    ) -> Vec<ExposureStats> {
+        let samples: Vec<(i64, u16)> = samples
+            .into_iter()
+            .filter(|&(_, co2)| co2 > 0)
+            .map(|(ts, co2)| (ts.unix_timestamp(), co2))
+            .collect();
+
+        let mut days: Vec<ExposureStats> = Vec::new();
+        let mut last_span = 0;
+        for (i, &(ts, co2)) in samples.iter().enumerate() {
+            let span = match samples.get(i + 1) {
+                Some(&(next, _)) => (next - ts).clamp(0, EXPOSURE_MAX_GAP_SECS),
+                None => last_span,
+            };
+            last_span = span;
+
+            let day = day_start(ts);
+            let stats = match days.last_mut() {
+                Some(stats) if stats.day.unix_timestamp() == day => stats,
+                _ => {
+                    days.push(ExposureStats {
+                        device_id: device_id.to_string(),
+                        day: timestamp_from_unix(day),
+                        measured_hours: 0.0,
+                        hours_above_800: 0.0,
+                        hours_above_1000: 0.0,
+                        ppm_hours_above_800: 0.0,
+                        ppm_hours_above_1000: 0.0,
+                    });
+                    days.last_mut().expect("just pushed")
                 }
             };
-            let radon = radon.map(|r| r.to_string()).unwrap_or_default();
-            let radiation_rate = radiation_rate
-                .map(|r| format!("{:.4}", r))
-                .unwrap_or_default();
-            let radiation_total = radiation_total
-                .map(|r| format!("{:.4}", r))
-                .unwrap_or_default();
 
-            wtr.write_record(&[
-                timestamp,
-                device_id,
-                co2.to_string(),
-                format!("{:.1}", temperature),
-                format!("{:.2}", pressure),
-                humidity.to_string(),
-                radon,
-                radiation_rate,
-                radiation_total,
-            ])
-            .map_err(|e| Error::Io(std::io::Error::other(e)))?;
-        }
+            let hours = span as f64 / 3600.0;
+            let co2 = f64::from(co2);
+            stats.measured_hours += hours;
+            if co2 > 800.0 {
+                stats.hours_above_800 += hours;
+                stats.ppm_hours_above_800 += (co2 - 800.0) * hours;
+            }
+            if co2 > 1000.0 {
+                stats.hours_above_1000 += hours;
+                stats.ppm_hours_above_1000 += (co2 - 1000.0) * hours;
+            }
+        }
+        days
+    }
+}
+
+/// How many of a device's measurements made it into the store.
+///
+/// Computed from the measurement counters of advertised readings by
+/// [`Store::measurement_coverage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MeasurementCoverage {
+    /// Measurements the device took, from the first to the last counted reading.
+    pub expected: u64,
+    /// Distinct measurements with a stored reading.
+    pub received: u64,
+}
+
+impl MeasurementCoverage {
+    /// Measurements the device took that were never stored.
+    pub fn missed(&self) -> u64 {
+        self.expected.saturating_sub(self.received)
+    }
+
+    /// Share of the device's measurements that were stored, in percent.
+    pub fn percent(&self) -> f64 {
+        if self.expected == 0 {
+            return 0.0;
+        }
+        self.received as f64 / self.expected as f64 * 100.0
+    }
+
+    /// Compute coverage from one device's `(captured_at, counter)` samples,
+    /// oldest first.
+    ///
+    /// The counter is 8 bits wide, so after a gap longer than 255
+    /// measurements it has wrapped around; `interval_secs` (the device's
+    /// measurement interval) is used to count the whole wraps. Without it,
+    /// each gap is assumed to be shorter than that. Returns `None` for no
+    /// samples.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aranet_store::MeasurementCoverage;
+    /// use time::macros::datetime;
+    ///
+    /// let samples = [
+    ///     (datetime!(2026-01-15 09:00 UTC), 254),
+    ///     (datetime!(2026-01-15 09:01 UTC), 255),
+    ///     (datetime!(2026-01-15 09:04 UTC), 2),
+    /// ];
+    /// let coverage = MeasurementCoverage::from_counters(samples, Some(60)).unwrap();
+    /// // Counters 0 and 1 were never received
+    /// assert_eq!((coverage.expected, coverage.received), (5, 3));
+    /// ```
+    pub fn from_counters(
+        samples: impl IntoIterator<Item = (OffsetDateTime, u8)>,
+        interval_secs: Option<u32>,
+    ) -> Option<Self> {
+        let mut samples = samples.into_iter();
+        let (mut last_at, mut last_counter) = samples.next()?;
+        let mut coverage = Self {
+            expected: 1,
+            received: 1,
+        };
+
+        for (at, counter) in samples {
+            let delta = u64::from(counter.wrapping_sub(last_counter));
+            let wraps = match interval_secs {
+                Some(interval) if interval > 0 => {
+                    let elapsed =
+                        (at - last_at).whole_seconds().max(0) as f64 / f64::from(interval);
+                    ((elapsed - delta as f64) / 256.0).round().max(0.0) as u64
+                }
+                _ => 0,
+            };
+            let taken = delta + 256 * wraps;
+            // The same measurement seen again, e.g. in a repeated advertisement
+            if taken > 0 {
+                coverage.expected += taken;
+                coverage.received += 1;
+            }
+            last_at = at;
+            last_counter = counter;
+        }
+
+        Some(coverage)
+    }
+}
+
+// Device manager state
+impl ManagerStateStore for Store {
+    type Error = Error;
+
+    /// Replace the saved device manager state.
+    fn save_manager_state(&self, state: &ManagerState) -> Result<()> {
+        let json = serde_json::to_string(state)?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO manager_state (id, state, saved_at) VALUES (1, ?1, ?2)",
+            rusqlite::params![json, OffsetDateTime::now_utc().unix_timestamp()],
+        )?;
+        debug!("Saved manager state ({} devices)", state.devices.len());
+        Ok(())
+    }
+
+    /// Load the saved device manager state, if any.
+    fn load_manager_state(&self) -> Result<Option<ManagerState>> {
+        let json: Option<String> = self
+            .conn
+            .query_row("SELECT state FROM manager_state WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        json.map(|json| serde_json::from_str(&json).map_err(Error::from))
+            .transpose()
+    }
+}
+
+// Aggregate and export operations
+impl Store {
+    /// Calculate aggregate statistics for history records.
+    ///
+    /// Computes min, max, and average values for all sensor metrics across
+    /// the records matching the query. Useful for dashboards and reports.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Filter which records to include in the statistics
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aranet_store::{Store, HistoryQuery};
+    /// use time::{OffsetDateTime, Duration};
+    ///
+    /// let store = Store::open_in_memory()?;
+    ///
+    /// // Get stats for last 24 hours
+    /// let yesterday = OffsetDateTime::now_utc() - Duration::hours(24);
+    /// let query = HistoryQuery::new()
+    ///     .device("Aranet4 17C3C")
+    ///     .since(yesterday);
+    ///
+    /// let stats = store.history_stats(&query)?;
+    /// if let Some(avg_co2) = stats.avg.co2 {
+    ///     println!("Average CO2: {:.0} ppm", avg_co2);
+    /// }
+    /// if let Some((start, end)) = stats.time_range {
+    ///     println!("Time range: {} to {}", start, end);
+    /// }
+    /// # Ok::<(), aranet_store::Error>(())
+    /// ```
+    pub fn history_stats(&self, query: &HistoryQuery) -> Result<HistoryStats> {
+        // Ordering, limit and offset don't apply to aggregates
+        let (where_clause, params) = query.build_where();
+
+        let sql = format!(
+            "SELECT
+                COUNT(*) as count,
+                MIN(co2) as min_co2, MAX(co2) as max_co2, AVG(co2) as avg_co2,
+                MIN(temperature) as min_temp, MAX(temperature) as max_temp, AVG(temperature) as avg_temp,
+                MIN(pressure) as min_press, MAX(pressure) as max_press, AVG(pressure) as avg_press,
+                MIN(humidity) as min_hum, MAX(humidity) as max_hum, AVG(humidity) as avg_hum,
+                MIN(radon) as min_radon, MAX(radon) as max_radon, AVG(radon) as avg_radon,
+                MIN(timestamp) as min_ts, MAX(timestamp) as max_ts
+             FROM {} {}",
+            self.history_source(),
+            where_clause
+        );
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+
+        let stats = self.conn.query_row(&sql, params_refs.as_slice(), |row| {
+            let count: i64 = row.get(0)?;
+            let min_ts: Option<i64> = row.get(16)?;
+            let max_ts: Option<i64> = row.get(17)?;
+
+            let time_range = match (min_ts, max_ts) {
+                (Some(min), Some(max)) => {
+                    Some((timestamp_from_unix(min), timestamp_from_unix(max)))
+                }
+                _ => None,
+            };
+
+            Ok(HistoryStats {
+                count: count as u64,
+                min: HistoryAggregates {
+                    co2: row.get::<_, Option<i64>>(1)?.map(|v| v as f64),
+                    temperature: row.get(4)?,
+                    pressure: row.get(7)?,
+                    humidity: row.get::<_, Option<i64>>(10)?.map(|v| v as f64),
+                    radon: row.get::<_, Option<i64>>(13)?.map(|v| v as f64),
+                },
+                max: HistoryAggregates {
+                    co2: row.get::<_, Option<i64>>(2)?.map(|v| v as f64),
+                    temperature: row.get(5)?,
+                    pressure: row.get(8)?,
+                    humidity: row.get::<_, Option<i64>>(11)?.map(|v| v as f64),
+                    radon: row.get::<_, Option<i64>>(14)?.map(|v| v as f64),
+                },
+                avg: HistoryAggregates {
+                    co2: row.get(3)?,
+                    temperature: row.get(6)?,
+                    pressure: row.get(9)?,
+                    humidity: row.get(12)?,
+                    radon: row.get(15)?,
+                },
+                time_range,
+            })
+        })?;
+
+        Ok(stats)
+    }
+
+    /// Read cached per-day statistics.
+    ///
+    /// Honors the query's device filter and time range; `since`/`until` select
+    /// whole days, so a day is included if any part of it is in range. Limit,
+    /// offset, and ordering are ignored - days are returned oldest first,
+    /// grouped by device.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aranet_store::{Store, HistoryQuery};
+    /// use time::{OffsetDateTime, Duration};
+    ///
+    /// let store = Store::open_in_memory()?;
+    ///
+    /// let month_ago = OffsetDateTime::now_utc() - Duration::days(30);
+    /// let query = HistoryQuery::new().device("Aranet4 17C3C").since(month_ago);
+    ///
+    /// for day in store.daily_stats(&query)? {
+    ///     println!("{}: {} records, avg CO2 {:?}", day.day.date(), day.count, day.avg.co2);
+    /// }
+    /// # Ok::<(), aranet_store::Error>(())
+    /// ```
+    pub fn daily_stats(&self, query: &HistoryQuery) -> Result<Vec<DailyStats>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT device_id, day, count,
+                    co2_count, co2_min, co2_max, co2_sum,
+                    temperature_min, temperature_max, temperature_sum,
+                    pressure_count, pressure_min, pressure_max, pressure_sum,
+                    humidity_min, humidity_max, humidity_sum,
+                    radon_count, radon_min, radon_max, radon_sum,
+                    co2_good, co2_moderate, co2_poor,
+                    radon_good, radon_moderate, radon_poor
+             FROM daily_stats
+             WHERE (?1 IS NULL OR device_id = ?1)
+               AND (?2 IS NULL OR day >= ?2)
+               AND (?3 IS NULL OR day <= ?3)
+             ORDER BY device_id, day",
+        )?;
+
+        let since = query.since.map(|t| day_start(t.unix_timestamp()));
+        let until = query.until.map(|t| t.unix_timestamp());
+        let rows = stmt
+            .query_map(
+                rusqlite::params![query.device_id.as_deref(), since, until],
+                |row| {
+                    let count: i64 = row.get(2)?;
+                    let avg =
+                        |sum: Option<f64>, n: i64| sum.filter(|_| n > 0).map(|s| s / n as f64);
+                    let co2_count: i64 = row.get(3)?;
+                    let pressure_count: i64 = row.get(10)?;
+                    let radon_count: i64 = row.get(17)?;
+
+                    Ok(DailyStats {
+                        device_id: row.get(0)?,
+                        day: timestamp_from_unix(row.get(1)?),
+                        count: count as u64,
+                        co2_count: co2_count as u64,
+                        pressure_count: pressure_count as u64,
+                        radon_count: radon_count as u64,
+                        min: HistoryAggregates {
+                            co2: row.get::<_, Option<i64>>(4)?.map(|v| v as f64),
+                            temperature: row.get(7)?,
+                            pressure: row.get(11)?,
+                            humidity: row.get::<_, Option<i64>>(14)?.map(|v| v as f64),
+                            radon: row.get::<_, Option<i64>>(18)?.map(|v| v as f64),
+                        },
+                        max: HistoryAggregates {
+                            co2: row.get::<_, Option<i64>>(5)?.map(|v| v as f64),
+                            temperature: row.get(8)?,
+                            pressure: row.get(12)?,
+                            humidity: row.get::<_, Option<i64>>(15)?.map(|v| v as f64),
+                            radon: row.get::<_, Option<i64>>(19)?.map(|v| v as f64),
+                        },
+                        avg: HistoryAggregates {
+                            co2: avg(row.get(6)?, co2_count),
+                            temperature: avg(row.get(9)?, count),
+                            pressure: avg(row.get(13)?, pressure_count),
+                            humidity: avg(row.get(16)?, count),
+                            radon: avg(row.get(20)?, radon_count),
+                        },
+                        co2_buckets: ThresholdBuckets {
+                            good: row.get::<_, i64>(21)? as u64,
+                            moderate: row.get::<_, i64>(22)? as u64,
+                            poor: row.get::<_, i64>(23)? as u64,
+                        },
+                        radon_buckets: ThresholdBuckets {
+                            good: row.get::<_, i64>(24)? as u64,
+                            moderate: row.get::<_, i64>(25)? as u64,
+                            poor: row.get::<_, i64>(26)? as u64,
+                        },
+                    })
+                },
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(rows)
+    }
+
+    /// Compute daily CO₂ exposure (ppm-hours above 800 and 1000 ppm).
+    ///
+    /// Honors the query's device filter and time range; limit, offset and
+    /// ordering are ignored. Days are returned oldest first, grouped by
+    /// device. See [`ExposureStats`] for how records are weighted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aranet_store::{Store, HistoryQuery};
+    /// use time::{OffsetDateTime, Duration};
+    ///
+    /// let store = Store::open_in_memory()?;
+    ///
+    /// let week_ago = OffsetDateTime::now_utc() - Duration::days(7);
+    /// let query = HistoryQuery::new().device("Aranet4 17C3C").since(week_ago);
+    ///
+    /// for day in store.exposure_stats(&query)? {
+    ///     println!("{}: {:.0} ppm·h above 1000 ppm", day.day.date(), day.ppm_hours_above_1000);
+    /// }
+    /// # Ok::<(), aranet_store::Error>(())
+    /// ```
+    pub fn exposure_stats(&self, query: &HistoryQuery) -> Result<Vec<ExposureStats>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT device_id, timestamp, co2 FROM {}
+             WHERE co2 > 0
+               AND (?1 IS NULL OR device_id = ?1)
+               AND (?2 IS NULL OR timestamp >= ?2)
+               AND (?3 IS NULL OR timestamp <= ?3)
+             ORDER BY device_id, timestamp",
+            self.history_source()
+        ))?;
+        let rows = stmt
+            .query_map(
+                rusqlite::params![
+                    query.device_id.as_deref(),
+                    query.since.map(|t| t.unix_timestamp()),
+                    query.until.map(|t| t.unix_timestamp())
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                    ))
+                },
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut stats = Vec::new();
+        for device_rows in rows.chunk_by(|a, b| a.0 == b.0) {
+            stats.extend(ExposureStats::from_samples(
+                &device_rows[0].0,
+                device_rows
+                    .iter()
+                    .map(|(_, ts, co2)| (timestamp_from_unix(*ts), *co2 as u16)),
+            ));
+        }
+        Ok(stats)
+    }
+
+    /// Estimate how many of a device's measurements were stored.
+    ///
+    /// Uses the counters of readings stored with
+    /// [`insert_reading_with_counter`](Self::insert_reading_with_counter)
+    /// since `since` (all of them if `None`), and the device's last recorded
+    /// measurement interval. Returns `None` if no reading in the range has a
+    /// counter. See [`MeasurementCoverage::from_counters`].
+    pub fn measurement_coverage(
+        &self,
+        device_id: &str,
+        since: Option<OffsetDateTime>,
+    ) -> Result<Option<MeasurementCoverage>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT captured_at, counter FROM {}
+             WHERE device_id = ?1 AND counter IS NOT NULL
+               AND (?2 IS NULL OR captured_at >= ?2)
+             ORDER BY captured_at, id",
+            self.readings_source()
+        ))?;
+        let samples = stmt
+            .query_map(
+                rusqlite::params![device_id, since.map(|t| t.unix_timestamp())],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let interval: Option<u32> = self
+            .conn
+            .query_row(
+                "SELECT new_value FROM settings_history
+                 WHERE device_id = ?1 AND setting = ?2
+                 ORDER BY changed_at DESC, id DESC LIMIT 1",
+                rusqlite::params![device_id, SETTING_INTERVAL],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+            .and_then(|value| value.parse().ok());
+
+        Ok(MeasurementCoverage::from_counters(
+            samples.into_iter().filter_map(|(at, counter)| {
+                Some((timestamp_from_unix(at), u8::try_from(counter).ok()?))
+            }),
+            interval,
+        ))
+    }
+
+    /// Rebuild the per-day statistics cache from raw history.
+    ///
+    /// Only needed if history rows were modified outside of [`Store`]; inserts
+    /// through [`Store::insert_history`] keep the cache current. Days whose
+    /// raw rows have been pruned keep their cached statistics.
+    ///
+    /// Returns the number of days rebuilt.
+    pub fn rebuild_daily_stats(&self, device_id: Option<&str>) -> Result<usize> {
+        let rebuilt = self.conn.execute(
+            schema::REFRESH_DAILY_STATS_SQL,
+            rusqlite::params![device_id, None::<i64>, None::<i64>],
+        )?;
+        Ok(rebuilt)
+    }
+
+    /// Export history records to CSV format.
+    ///
+    /// Exports records matching the query to a CSV string with the following columns:
+    /// `timestamp`, `device_id`, `co2`, `temperature`, `pressure`, `humidity`, `radon`.
+    ///
+    /// Timestamps are formatted as RFC 3339 (e.g., `2024-01-15T10:30:00Z`).
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Filter which records to export
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aranet_store::{Store, HistoryQuery};
+    /// use std::fs;
+    ///
+    /// let store = Store::open_in_memory()?;
+    ///
+    /// let query = HistoryQuery::new().device("Aranet4 17C3C").oldest_first();
+    /// let csv = store.export_history_csv(&query)?;
+    ///
+    /// // Write to file
+    /// // fs::write("history.csv", &csv)?;
+    /// # Ok::<(), aranet_store::Error>(())
+    /// ```
+    pub fn export_history_csv(&self, query: &HistoryQuery) -> Result<String> {
+        self.write_history_csv(query, None)
+    }
+
+    /// Export history records to CSV with an extra `annotation` column.
+    ///
+    /// The column holds the text of the annotations that apply to each record,
+    /// joined with `; `. Range annotations apply to every record inside the
+    /// range; point annotations apply to the first record at or after their
+    /// time. See [`Store::add_annotation`].
+    pub fn export_history_csv_annotated(&self, query: &HistoryQuery) -> Result<String> {
+        let labels = self.annotation_labels(query)?;
+        self.write_history_csv(query, Some(&labels))
+    }
+
+    fn write_history_csv(
+        &self,
+        query: &HistoryQuery,
+        labels: Option<&AnnotationLabels>,
+    ) -> Result<String> {
+        let sql = query.build_sql_with_select(&format!(
+            "SELECT timestamp, device_id, co2, temperature, pressure, humidity, radon, \
+             radiation_rate, radiation_total FROM {}",
+            self.history_source()
+        ));
+        let (_, params) = query.build_where();
+        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+
+        // Header
+        let mut header = vec![
+            "timestamp",
+            "device_id",
+            "co2",
+            "temperature",
+            "pressure",
+            "humidity",
+            "radon",
+            "radiation_rate",
+            "radiation_total",
+        ];
+        if labels.is_some() {
+            header.push("annotation");
+        }
+        wtr.write_record(&header)
+            .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+
+        let rows = stmt.query_map(params_ref.as_slice(), |row| {
+            Ok((
+                timestamp_from_unix(row.get(0)?),
+                row.get::<_, String>(1)?,
+                u16::try_from(row.get::<_, i64>(2)?).unwrap_or_else(|e| {
+                    warn!("Invalid co2 value in export row: {e}");
+                    0
+                }),
+                row.get::<_, f32>(3)?,
+                row.get::<_, f32>(4)?,
+                u8::try_from(row.get::<_, i64>(5)?).unwrap_or_else(|e| {
+                    warn!("Invalid humidity value in export row: {e}");
+                    0
+                }),
+                row.get::<_, Option<i64>>(6)?
+                    .and_then(|v| u32::try_from(v).ok()),
+                row.get::<_, Option<f64>>(7)?,
+                row.get::<_, Option<f64>>(8)?,
+            ))
+        })?;
+
+        // Data rows
+        for row in rows {
+            let (
+                timestamp,
+                device_id,
+                co2,
+                temperature,
+                pressure,
+                humidity,
+                radon,
+                radiation_rate,
+                radiation_total,
+            ) = row?;
+            let annotation =
+                labels.map(|labels| labels.label(&device_id, timestamp).unwrap_or_default());
+            let timestamp = match timestamp.format(&time::format_description::well_known::Rfc3339) {
+                Ok(ts) => ts,
+                Err(e) => {
+                    warn!("Skipping CSV row with unformattable timestamp: {e}");
+                    continue;
+                }
+            };
+            let radon = radon.map(|r| r.to_string()).unwrap_or_default();
+            let radiation_rate = radiation_rate
+                .map(|r| format!("{:.4}", r))
+                .unwrap_or_default();
+            let radiation_total = radiation_total
+                .map(|r| format!("{:.4}", r))
+                .unwrap_or_default();
+
+            let mut record = vec![
+                timestamp,
+                device_id,
+                co2.to_string(),
+                format!("{:.1}", temperature),
+                format!("{:.2}", pressure),
+                humidity.to_string(),
+                radon,
+                radiation_rate,
+                radiation_total,
+            ];
+            record.extend(annotation);
+            wtr.write_record(&record)
+                .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+        }
+
+        let bytes = wtr
+            .into_inner()
+            .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+        String::from_utf8(bytes).map_err(|e| Error::Io(std::io::Error::other(e)))
+    }
+
+    /// Export history records to JSON format.
+    ///
+    /// Exports records matching the query as a pretty-printed JSON array of
+    /// [`StoredHistoryRecord`] objects.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Filter which records to export
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aranet_store::{Store, HistoryQuery};
+    ///
+    /// let store = Store::open_in_memory()?;
+    ///
+    /// let query = HistoryQuery::new().device("Aranet4 17C3C");
+    /// let json = store.export_history_json(&query)?;
+    /// println!("{}", json);
+    /// # Ok::<(), aranet_store::Error>(())
+    /// ```
+    pub fn export_history_json(&self, query: &HistoryQuery) -> Result<String> {
+        self.write_history_json(query, None)
+    }
+
+    /// Export history records to JSON with an `annotation` field on annotated records.
+    ///
+    /// Annotations are matched to records as in
+    /// [`export_history_csv_annotated`](Self::export_history_csv_annotated).
+    /// Records without annotations have no `annotation` field.
+    pub fn export_history_json_annotated(&self, query: &HistoryQuery) -> Result<String> {
+        let labels = self.annotation_labels(query)?;
+        self.write_history_json(query, Some(&labels))
+    }
+
+    fn write_history_json(
+        &self,
+        query: &HistoryQuery,
+        labels: Option<&AnnotationLabels>,
+    ) -> Result<String> {
+        let sql = query.build_sql_from(self.history_source());
+        let (_, params) = query.build_where();
+        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_ref.as_slice(), |row| {
+            Ok(StoredHistoryRecord {
+                id: row.get(0)?,
+                device_id: row.get(1)?,
+                timestamp: timestamp_from_unix(row.get(2)?),
+                synced_at: timestamp_from_unix(row.get(3)?),
+                co2: u16::try_from(row.get::<_, i64>(4)?).unwrap_or_else(|e| {
+                    warn!("Invalid co2 value in JSON export: {e}");
+                    0
+                }),
+                temperature: row.get(5)?,
+                pressure: row.get(6)?,
+                humidity: u8::try_from(row.get::<_, i64>(7)?).unwrap_or_else(|e| {
+                    warn!("Invalid humidity value in JSON export: {e}");
+                    0
+                }),
+                radon: row
+                    .get::<_, Option<i64>>(8)?
+                    .and_then(|v| radon_from_i64(v, "json_export")),
+                radiation_rate: row.get(9)?,
+                radiation_total: row.get(10)?,
+            })
+        })?;
+
+        let mut json = String::from("[");
+        let mut first = true;
+
+        for row in rows {
+            let record = row?;
+            let annotation =
+                labels.and_then(|labels| labels.label(&record.device_id, record.timestamp));
+            let record_json = serde_json::to_string_pretty(&AnnotatedRecord {
+                record: &record,
+                annotation,
+            })?;
+            if first {
+                json.push('\n');
+                first = false;
+            } else {
+                json.push_str(",\n");
+            }
+
+            for line in record_json.lines() {
+                json.push_str("  ");
+                json.push_str(line);
+                json.push('\n');
+            }
+        }
+
+        json.push(']');
+
+        Ok(json)
+    }
+
+    /// Export history records for import into a phone health app.
+    ///
+    /// See [`crate::health`] for the formats and field mapping.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aranet_store::{HealthExportFormat, HistoryQuery, Store};
+    ///
+    /// let store = Store::open_in_memory()?;
+    ///
+    /// let query = HistoryQuery::new().device("Aranet4 17C3C");
+    /// let xml = store.export_history_health(&query, HealthExportFormat::AppleHealthXml)?;
+    /// println!("{}", xml);
+    /// # Ok::<(), aranet_store::Error>(())
+    /// ```
+    pub fn export_history_health(
+        &self,
+        query: &HistoryQuery,
+        format: HealthExportFormat,
+    ) -> Result<String> {
+        let records = self.query_history(query)?;
+        health::export_health(&records, format)
+    }
+
+    /// Import history records from CSV format.
+    ///
+    /// Expected CSV format:
+    /// ```csv
+    /// timestamp,device_id,co2,temperature,pressure,humidity,radon
+    /// 2024-01-15T10:30:00Z,Aranet4 17C3C,800,22.5,1013.25,45,
+    /// ```
+    ///
+    /// Returns the number of records imported (deduplicated by device_id + timestamp).
+    pub fn import_history_csv(&self, csv_data: &str) -> Result<ImportResult> {
+        let ParsedHistoryCsv {
+            total,
+            device_records,
+            errors,
+        } = parse_history_csv(csv_data);
+        let mut imported = 0;
+        let mut skipped = errors.len();
+
+        for (device_id, records) in device_records {
+            self.upsert_device(&device_id, None)?;
+            let total_records = records.len();
+            let count = self.insert_history(&device_id, &records)?;
+            imported += count;
+            skipped += total_records.saturating_sub(count);
+        }
+
+        Ok(ImportResult {
+            total,
+            imported,
+            skipped,
+            errors,
+        })
+    }
+
+    /// Import history records from JSON format.
+    ///
+    /// Expected JSON format: an array of StoredHistoryRecord objects.
+    ///
+    /// Returns the number of records imported (deduplicated by device_id + timestamp).
+    pub fn import_history_json(&self, json_data: &str) -> Result<ImportResult> {
+        let records: Vec<StoredHistoryRecord> = serde_json::from_str(json_data)?;
+
+        let total = records.len();
+        let mut imported = 0;
+        let mut skipped = 0;
+        let mut device_records: std::collections::HashMap<String, Vec<HistoryRecord>> =
+            std::collections::HashMap::new();
+
+        for record in records {
+            let device_id = record.device_id.clone();
+            device_records
+                .entry(device_id)
+                .or_default()
+                .push(record.to_history());
+        }
+
+        for (device_id, records) in device_records {
+            self.upsert_device(&device_id, None)?;
+            let total_records = records.len();
+            let count = self.insert_history(&device_id, &records)?;
+            imported += count;
+            skipped += total_records.saturating_sub(count);
+        }
+
+        Ok(ImportResult {
+            total,
+            imported,
+            skipped,
+            errors: Vec::new(),
+        })
+    }
+
+    /// Write everything needed to move one device to another store as a
+    /// single JSON document (see [`DeviceBundle`]).
+    ///
+    /// Meant for migrating a sensor between machines or attaching its data
+    /// to a bug report. Returns how many rows were written from each table.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DeviceNotFound`] if the device has no device row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aranet_store::Store;
+    ///
+    /// let store = Store::open_in_memory()?;
+    /// store.upsert_device("Aranet4 17C3C", Some("Kitchen"))?;
+    ///
+    /// let mut bundle = Vec::new();
+    /// store.export_device_bundle("Aranet4 17C3C", &mut bundle)?;
+    ///
+    /// let other = Store::open_in_memory()?;
+    /// other.import_device_bundle(bundle.as_slice())?;
+    /// assert!(other.get_device("Aranet4 17C3C")?.is_some());
+    /// # Ok::<(), aranet_store::Error>(())
+    /// ```
+    pub fn export_device_bundle<W: std::io::Write>(
+        &self,
+        device_id: &str,
+        writer: W,
+    ) -> Result<DeviceDataCounts> {
+        let device = self
+            .get_device(device_id)?
+            .ok_or_else(|| Error::DeviceNotFound(device_id.to_string()))?;
+        let bundle = DeviceBundle {
+            version: DEVICE_BUNDLE_VERSION,
+            exported_at: OffsetDateTime::now_utc(),
+            readings: self.query_readings(&ReadingQuery::new().device(device_id).oldest_first())?,
+            history: self.query_history(&HistoryQuery::new().device(device_id).oldest_first())?,
+            annotations: self.query_annotations(&AnnotationQuery::new().device(device_id))?,
+            sync_state: self.get_sync_state(device_id)?,
+            device,
+        };
+
+        serde_json::to_writer_pretty(writer, &bundle)?;
+        Ok(bundle.counts())
+    }
+
+    /// Import a document written by [`export_device_bundle`](Self::export_device_bundle).
+    ///
+    /// Data is stored under the device ID in the bundle; use
+    /// [`merge_devices`](Self::merge_devices) afterwards if the sensor has a
+    /// different ID on this machine. Importing is idempotent:
+    ///
+    /// - Readings and history records already stored are skipped, using the
+    ///   same duplicate detection as regular inserts.
+    /// - Annotations with the same range and text are skipped.
+    /// - The sync state is replaced only if the bundle's is newer.
+    /// - Missing device metadata is filled in and first/last seen widened.
+    ///
+    /// Returns how many rows were added to each table.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedBundle`] for a bundle written by a newer
+    /// version of this crate.
+    pub fn import_device_bundle<R: std::io::Read>(&self, reader: R) -> Result<DeviceDataCounts> {
+        let bundle: DeviceBundle = serde_json::from_reader(reader)?;
+        if bundle.version > DEVICE_BUNDLE_VERSION {
+            return Err(Error::UnsupportedBundle(format!(
+                "version {} is newer than the supported version {}",
+                bundle.version, DEVICE_BUNDLE_VERSION
+            )));
+        }
+        let device_id = bundle.device.id.as_str();
+        let existing = self.get_device(device_id)?;
+
+        let batch: Vec<_> = bundle
+            .readings
+            .iter()
+            .map(|r| (device_id, r.to_reading(), r.quality))
+            .collect();
+        let readings = self
+            .insert_readings_unordered(&batch)?
+            .iter()
+            .filter(|id| id.is_some())
+            .count() as u64;
+
+        let records: Vec<_> = bundle.history.iter().map(|r| r.to_history()).collect();
+        let history = self.insert_history(device_id, &records)? as u64;
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut annotations = 0;
+        for annotation in &bundle.annotations {
+            annotations += tx.execute(
+                "INSERT INTO annotations (device_id, start_at, end_at, text, tag, created_at)
+                 SELECT ?1, ?2, ?3, ?4, ?5, ?6
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM annotations
+                     WHERE device_id = ?1 AND start_at = ?2 AND end_at IS ?3 AND text = ?4
+                 )",
+                rusqlite::params![
+                    device_id,
+                    annotation.start.unix_timestamp(),
+                    annotation.end.map(|t| t.unix_timestamp()),
+                    annotation.text,
+                    annotation.tag,
+                    annotation.created_at.unix_timestamp()
+                ],
+            )? as u64;
+        }
+
+        let sync_state = match &bundle.sync_state {
+            Some(state) => tx.execute(
+                "INSERT OR REPLACE INTO sync_state (device_id, last_history_index, total_readings, last_sync_at)
+                 SELECT ?1, ?2, ?3, ?4
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM sync_state
+                     WHERE device_id = ?1 AND COALESCE(last_sync_at, 0) >= COALESCE(?4, 0)
+                 )",
+                rusqlite::params![
+                    device_id,
+                    state.last_history_index,
+                    state.total_readings,
+                    state.last_sync_at.map(|t| t.unix_timestamp())
+                ],
+            )? as u64,
+            None => 0,
+        };
+
+        // Inserting data touched the device row; restore its metadata last
+        let imported = &bundle.device;
+        let device = match existing {
+            Some(existing) => StoredDevice {
+                name: existing.name.or_else(|| imported.name.clone()),
+                device_type: existing.device_type.or(imported.device_type),
+                serial: existing.serial.or_else(|| imported.serial.clone()),
+                firmware: existing.firmware.or_else(|| imported.firmware.clone()),
+                hardware: existing.hardware.or_else(|| imported.hardware.clone()),
+                first_seen: existing.first_seen.min(imported.first_seen),
+                last_seen: existing.last_seen.max(imported.last_seen),
+                id: existing.id,
+            },
+            None => imported.clone(),
+        };
+        tx.execute(
+            "INSERT INTO devices (id, name, device_type, serial, firmware, hardware, first_seen, last_seen)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                device_type = excluded.device_type,
+                serial = excluded.serial,
+                firmware = excluded.firmware,
+                hardware = excluded.hardware,
+                first_seen = excluded.first_seen,
+                last_seen = excluded.last_seen",
+            rusqlite::params![
+                device_id,
+                device.name,
+                device.device_type.map(|dt| format!("{:?}", dt)),
+                device.serial,
+                device.firmware,
+                device.hardware,
+                device.first_seen.unix_timestamp(),
+                device.last_seen.unix_timestamp()
+            ],
+        )?;
+        tx.commit()?;
+        info!(
+            "Imported device bundle for {}: {} readings, {} history records",
+            device_id, readings, history
+        );
+
+        Ok(DeviceDataCounts {
+            device: true,
+            history,
+            readings,
+            sync_state,
+            annotations,
+            ..Default::default()
+        })
+    }
+}
+
+/// Version written to new [`DeviceBundle`]s.
+pub const DEVICE_BUNDLE_VERSION: u32 = 1;
+
+/// One device's data as written by
+/// [`Store::export_device_bundle`] and read by [`Store::import_device_bundle`].
+///
+/// Row IDs are kept for reference when reading a bundle by hand; they are
+/// reassigned on import.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DeviceBundle {
+    /// Bundle format version, see [`DEVICE_BUNDLE_VERSION`].
+    pub version: u32,
+    /// When the bundle was written.
+    #[serde(with = "time::serde::rfc3339")]
+    pub exported_at: OffsetDateTime,
+    /// Device metadata.
+    pub device: StoredDevice,
+    /// Current readings, oldest first.
+    #[serde(default)]
+    pub readings: Vec<StoredReading>,
+    /// History records, oldest first.
+    #[serde(default)]
+    pub history: Vec<StoredHistoryRecord>,
+    /// User annotations.
+    #[serde(default)]
+    pub annotations: Vec<StoredAnnotation>,
+    /// History sync state.
+    #[serde(default)]
+    pub sync_state: Option<SyncState>,
+}
+
+impl DeviceBundle {
+    /// Rows in the bundle, per table.
+    pub fn counts(&self) -> DeviceDataCounts {
+        DeviceDataCounts {
+            device: true,
+            history: self.history.len() as u64,
+            readings: self.readings.len() as u64,
+            sync_state: u64::from(self.sync_state.is_some()),
+            annotations: self.annotations.len() as u64,
+            ..Default::default()
+        }
+    }
+}
+
+/// History CSV rows grouped by device, with errors for rows that were skipped.
+struct ParsedHistoryCsv {
+    total: usize,
+    device_records: HashMap<String, Vec<HistoryRecord>>,
+    errors: Vec<String>,
+}
+
+/// Parse CSV in the format read by [`Store::import_history_csv`].
+fn parse_history_csv(csv_data: &str) -> ParsedHistoryCsv {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(csv_data.as_bytes());
+
+    let mut total = 0;
+    let mut errors = Vec::new();
+    let mut device_records: std::collections::HashMap<String, Vec<HistoryRecord>> =
+        std::collections::HashMap::new();
+
+    for (line_num, result) in reader.records().enumerate() {
+        total += 1;
+        let line = line_num + 2; // Account for header and 0-indexing
+
+        let record = match result {
+            Ok(r) => r,
+            Err(e) => {
+                errors.push(format!("Line {}: parse error - {}", line, e));
+                continue;
+            }
+        };
+
+        // Parse fields
+        let timestamp_str = record.get(0).unwrap_or("").trim();
+        let device_id = record.get(1).unwrap_or("").trim();
+        let co2_str = record.get(2).unwrap_or("").trim();
+        let temp_str = record.get(3).unwrap_or("").trim();
+        let pressure_str = record.get(4).unwrap_or("").trim();
+        let humidity_str = record.get(5).unwrap_or("").trim();
+        let radon_str = record.get(6).unwrap_or("").trim();
+
+        // Validate required fields
+        if device_id.is_empty() {
+            errors.push(format!("Line {}: missing device_id", line));
+            continue;
+        }
+
+        if timestamp_str.is_empty() {
+            errors.push(format!("Line {}: missing timestamp", line));
+            continue;
+        }
+
+        // Parse timestamp
+        let timestamp = match OffsetDateTime::parse(
+            timestamp_str,
+            &time::format_description::well_known::Rfc3339,
+        ) {
+            Ok(ts) => ts,
+            Err(_) => {
+                errors.push(format!(
+                    "Line {}: invalid timestamp '{}'",
+                    line, timestamp_str
+                ));
+                continue;
+            }
+        };
+
+        // Parse numeric fields with defaults and validation
+        // Parse as u32 first so values > u16::MAX (65535) produce a clear
+        // "exceeds maximum" message instead of a generic parse error.
+        let co2: u16 = match co2_str.parse::<u32>() {
+            Ok(v) if v <= 10000 => v as u16, // CO2 sensor max is typically 10000 ppm
+            Ok(v) => {
+                errors.push(format!(
+                    "Line {}: CO2 value {} exceeds maximum of 10000 ppm",
+                    line, v
+                ));
+                continue;
+            }
+            Err(_) if co2_str.is_empty() => 0,
+            Err(_) => {
+                errors.push(format!("Line {}: invalid CO2 value '{}'", line, co2_str));
+                continue;
+            }
+        };
+
+        let temperature: f32 = match temp_str.parse::<f32>() {
+            Ok(v) if (-40.0..=100.0).contains(&v) => v,
+            Ok(v) => {
+                errors.push(format!(
+                    "Line {}: temperature {} is outside valid range (-40 to 100°C)",
+                    line, v
+                ));
+                continue;
+            }
+            Err(_) if temp_str.is_empty() => 0.0,
+            Err(_) => {
+                errors.push(format!(
+                    "Line {}: invalid temperature value '{}'",
+                    line, temp_str
+                ));
+                continue;
+            }
+        };
+
+        let pressure: f32 = match pressure_str.parse::<f32>() {
+            Ok(v) if v == 0.0 || (800.0..=1200.0).contains(&v) => v,
+            Ok(v) => {
+                errors.push(format!(
+                    "Line {}: pressure {} is outside valid range (800-1200 hPa)",
+                    line, v
+                ));
+                continue;
+            }
+            Err(_) if pressure_str.is_empty() => 0.0,
+            Err(_) => {
+                errors.push(format!(
+                    "Line {}: invalid pressure value '{}'",
+                    line, pressure_str
+                ));
+                continue;
+            }
+        };
+
+        let humidity: u8 = match humidity_str.parse::<u8>() {
+            Ok(v) if v <= 100 => v,
+            Ok(v) => {
+                errors.push(format!(
+                    "Line {}: humidity {} exceeds maximum of 100%",
+                    line, v
+                ));
+                continue;
+            }
+            Err(_) if humidity_str.is_empty() => 0,
+            Err(_) => {
+                errors.push(format!(
+                    "Line {}: invalid humidity value '{}'",
+                    line, humidity_str
+                ));
+                continue;
+            }
+        };
+
+        let radon: Option<u32> = if radon_str.is_empty() {
+            None
+        } else {
+            match radon_str.parse::<u32>() {
+                Ok(v) if v <= 100000 => Some(v), // Radon max ~100000 Bq/m³
+                Ok(v) => {
+                    errors.push(format!(
+                        "Line {}: radon value {} exceeds maximum of 100000 Bq/m³",
+                        line, v
+                    ));
+                    continue;
+                }
+                Err(_) => {
+                    errors.push(format!(
+                        "Line {}: invalid radon value '{}'",
+                        line, radon_str
+                    ));
+                    continue;
+                }
+            }
+        };
+
+        // Create history record
+        let history_record = HistoryRecord {
+            timestamp,
+            co2,
+            temperature,
+            pressure,
+            humidity,
+            radon,
+            radiation_rate: None,
+            radiation_total: None,
+        };
+
+        device_records
+            .entry(device_id.to_string())
+            .or_default()
+            .push(history_record);
+    }
+
+    ParsedHistoryCsv {
+        total,
+        device_records,
+        errors,
+    }
+}
+
+/// Tables holding per-device rows, in the order they are purged.
+const DEVICE_DATA_TABLES: [&str; 9] = [
+    "history",
+    "readings",
+    "sync_state",
+    "daily_stats",
+    "clock_drift",
+    "settings_history",
+    "alerts",
+    "annotations",
+    "channel_values",
+];
+
+/// Rows stored for one device, per table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DeviceDataCounts {
+    /// Whether the device row itself exists.
+    pub device: bool,
+    /// History records.
+    pub history: u64,
+    /// Current readings.
+    pub readings: u64,
+    /// Sync state rows (0 or 1).
+    pub sync_state: u64,
+    /// Daily statistics rows.
+    pub daily_stats: u64,
+    /// Clock drift rows (0 or 1).
+    pub clock_drift: u64,
+    /// Settings change records.
+    pub settings_history: u64,
+    /// Recorded alerts.
+    #[serde(default)]
+    pub alerts: u64,
+    /// User annotations.
+    #[serde(default)]
+    pub annotations: u64,
+    /// Computed channel values.
+    #[serde(default)]
+    pub channel_values: u64,
+}
+
+impl DeviceDataCounts {
+    fn from_table_counts(device: bool, counts: [u64; DEVICE_DATA_TABLES.len()]) -> Self {
+        let [
+            history,
+            readings,
+            sync_state,
+            daily_stats,
+            clock_drift,
+            settings_history,
+            alerts,
+            annotations,
+            channel_values,
+        ] = counts;
+        Self {
+            device,
+            history,
+            readings,
+            sync_state,
+            daily_stats,
+            clock_drift,
+            settings_history,
+            alerts,
+            annotations,
+            channel_values,
+        }
+    }
+
+    /// Total number of rows, including the device row.
+    pub fn total(&self) -> u64 {
+        u64::from(self.device)
+            + self.history
+            + self.readings
+            + self.sync_state
+            + self.daily_stats
+            + self.clock_drift
+            + self.settings_history
+            + self.alerts
+            + self.annotations
+            + self.channel_values
+    }
+}
+
+/// Stored rows and time coverage for one device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceCacheStats {
+    /// Device identifier.
+    pub device_id: String,
+    /// Device name, if known.
+    pub name: Option<String>,
+    /// Rows per table.
+    pub counts: DeviceDataCounts,
+    /// Oldest and newest history record timestamps.
+    pub history_range: Option<(OffsetDateTime, OffsetDateTime)>,
+    /// Oldest and newest reading capture times.
+    pub readings_range: Option<(OffsetDateTime, OffsetDateTime)>,
+}
+
+/// On-disk size of the main database.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct DatabaseSize {
+    /// Total size in bytes.
+    pub bytes: u64,
+    /// Bytes in free pages, reclaimable by [`Store::compact`].
+    pub free_bytes: u64,
+}
+
+/// Result of [`Store::verify`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct IntegrityReport {
+    /// Problems reported by SQLite's integrity check (empty when healthy).
+    pub problems: Vec<String>,
+    /// Tables with rows whose device no longer exists, and how many.
+    pub orphans: Vec<(String, u64)>,
+    /// History records whose values no longer match their content hash.
+    pub checksum_mismatches: u64,
+    /// Devices with duplicated history records, and how many copies.
+    pub duplicates: Vec<(String, u64)>,
+}
+
+impl IntegrityReport {
+    /// Whether no corruption, orphaned rows, checksum mismatches or
+    /// duplicates were found.
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+            && self.orphans.is_empty()
+            && self.checksum_mismatches == 0
+            && self.duplicates.is_empty()
+    }
+
+    /// Total number of duplicated history records.
+    pub fn duplicate_count(&self) -> u64 {
+        self.duplicates.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Total number of orphaned rows.
+    pub fn orphan_count(&self) -> u64 {
+        self.orphans.iter().map(|(_, count)| count).sum()
+    }
+}
+
+/// Annotation text per history record, for annotated exports.
+#[derive(Default)]
+struct AnnotationLabels {
+    /// Point annotation texts, keyed by the (device, timestamp) of the record
+    /// they attach to.
+    points: HashMap<(String, i64), Vec<String>>,
+    /// Range annotations, which apply to every record inside them.
+    ranges: Vec<StoredAnnotation>,
+}
+
+impl AnnotationLabels {
+    /// The annotation text for a record, or `None` if nothing applies.
+    fn label(&self, device_id: &str, timestamp: OffsetDateTime) -> Option<String> {
+        let mut texts: Vec<&str> = self
+            .ranges
+            .iter()
+            .filter(|a| a.device_id == device_id && a.overlaps(timestamp, timestamp))
+            .map(|a| a.text.as_str())
+            .collect();
+        if let Some(points) = self
+            .points
+            .get(&(device_id.to_string(), timestamp.unix_timestamp()))
+        {
+            texts.extend(points.iter().map(String::as_str));
+        }
+        (!texts.is_empty()).then(|| texts.join("; "))
+    }
+}
+
+/// A history record in a JSON export, with its optional annotation.
+#[derive(serde::Serialize)]
+struct AnnotatedRecord<'a> {
+    #[serde(flatten)]
+    record: &'a StoredHistoryRecord,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    annotation: Option<String>,
+}
+
+/// History records and their annotations, from [`Store::query_history_annotated`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AnnotatedHistory {
+    /// History records, in the query's order.
+    pub records: Vec<StoredHistoryRecord>,
+    /// Annotations overlapping the records' time span, oldest first.
+    pub annotations: Vec<StoredAnnotation>,
+}
+
+/// Rows moved by [`Store::rollover`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RolloverSummary {
+    /// The year rolled over; rows from this year and earlier were moved.
+    pub year: i32,
+    /// The archive database the rows were moved to.
+    pub path: PathBuf,
+    /// History records moved.
+    pub history: u64,
+    /// Readings moved.
+    pub readings: u64,
+}
+
+/// Result of an import operation.
+#[derive(Debug, Clone)]
+pub struct ImportResult {
+    /// Total records processed.
+    pub total: usize,
+    /// Records successfully imported.
+    pub imported: usize,
+    /// Records skipped (duplicates or errors).
+    pub skipped: usize,
+    /// Error messages for failed records.
+    pub errors: Vec<String>,
+}
+
+/// What importing some data would add, read without touching a store.
+///
+/// Counts are upper bounds: rows already stored are only skipped when the
+/// data is actually imported.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportPreview {
+    /// Device IDs the data belongs to, sorted.
+    pub devices: Vec<String>,
+    /// Current readings.
+    pub readings: usize,
+    /// History records.
+    pub history: usize,
+    /// Annotations.
+    pub annotations: usize,
+    /// Rows that cannot be imported, such as CSV lines with invalid values.
+    pub invalid: usize,
+    /// Earliest reading or record timestamp.
+    pub start: Option<OffsetDateTime>,
+    /// Latest reading or record timestamp.
+    pub end: Option<OffsetDateTime>,
+}
+
+impl ImportPreview {
+    /// Preview CSV in the format read by [`Store::import_history_csv`].
+    pub fn history_csv(csv_data: &str) -> Self {
+        let parsed = parse_history_csv(csv_data);
+        let mut devices: Vec<String> = parsed.device_records.keys().cloned().collect();
+        devices.sort();
+        let timestamps = || {
+            parsed
+                .device_records
+                .values()
+                .flatten()
+                .map(|r| r.timestamp)
+        };
+        Self {
+            devices,
+            history: parsed.device_records.values().map(Vec::len).sum(),
+            invalid: parsed.errors.len(),
+            start: timestamps().min(),
+            end: timestamps().max(),
+            ..Self::default()
+        }
+    }
+
+    /// Preview a bundle read by [`Store::import_device_bundle`].
+    pub fn device_bundle(bundle: &DeviceBundle) -> Self {
+        let timestamps = || {
+            bundle
+                .readings
+                .iter()
+                .map(|r| r.captured_at)
+                .chain(bundle.history.iter().map(|r| r.timestamp))
+        };
+        Self {
+            devices: vec![bundle.device.id.clone()],
+            readings: bundle.readings.len(),
+            history: bundle.history.len(),
+            annotations: bundle.annotations.len(),
+            invalid: 0,
+            start: timestamps().min(),
+            end: timestamps().max(),
+        }
+    }
+}
+
+// Read-only SQL queries
+
+/// A view that [`Store::query_readonly`] can read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryView {
+    /// View name, e.g. `v_history`.
+    pub name: &'static str,
+    /// What the view holds.
+    pub description: &'static str,
+}
+
+/// Views available to [`Store::query_readonly`].
+///
+/// Times are Unix seconds (`captured_at`, `timestamp`) with a UTC
+/// `YYYY-MM-DDTHH:MM:SSZ` text copy in `time`. CO₂ and pressure values of 0
+/// ("not measured") are `NULL`, so aggregates skip them.
+pub const QUERY_VIEWS: &[QueryView] = &[
+    QueryView {
+        name: "v_devices",
+        description: "Known devices with model, firmware and first/last seen times",
+    },
+    QueryView {
+        name: "v_readings",
+        description: "Polled current readings, including attached archives",
+    },
+    QueryView {
+        name: "v_history",
+        description: "History downloaded from device memory, including attached archives",
+    },
+    QueryView {
+        name: "v_daily_stats",
+        description: "Per-device daily min/max/avg of the history",
+    },
+    QueryView {
+        name: "v_annotations",
+        description: "User notes on points or ranges of time",
+    },
+    QueryView {
+        name: "v_alerts",
+        description: "Threshold and battery alerts",
+    },
+];
+
+/// Most rows [`Store::query_readonly`] returns; further rows are dropped.
+pub const QUERY_MAX_ROWS: usize = 10_000;
+
+/// How long [`Store::query_readonly`] lets a query run.
+pub const QUERY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Rows returned by [`Store::query_readonly`].
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct QueryResult {
+    /// Column names, in select order.
+    pub columns: Vec<String>,
+    /// Row values; blobs are lowercase hex strings.
+    pub rows: Vec<Vec<serde_json::Value>>,
+    /// Whether rows past [`QUERY_MAX_ROWS`] were dropped.
+    pub truncated: bool,
+}
+
+/// `strftime` format for the `time` columns of the query views.
+const QUERY_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// Clears the authorizer and progress handler when a query finishes.
+struct QueryGuard<'a>(&'a Connection);
+
+impl Drop for QueryGuard<'_> {
+    fn drop(&mut self) {
+        self.0
+            .authorizer(None::<fn(rusqlite::hooks::AuthContext<'_>) -> _>);
+        self.0.progress_handler(0, None::<fn() -> bool>);
+    }
+}
+
+impl Store {
+    /// Run a single `SELECT` over the [`QUERY_VIEWS`].
+    ///
+    /// An escape hatch for analytics the typed queries don't cover. The
+    /// statement may only read the views (plus CTEs and SQL functions);
+    /// tables, pragmas, writes, `ATTACH` and multiple statements are
+    /// refused before anything runs. `?` placeholders are bound from
+    /// `params` in order. At most [`QUERY_MAX_ROWS`] rows are returned and a
+    /// query is interrupted after [`QUERY_TIMEOUT`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Query`] when the statement is refused or times out,
+    /// and [`Error::Database`] for SQL errors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use aranet_store::Store;
+    ///
+    /// let store = Store::open_in_memory()?;
+    /// let result = store.query_readonly(
+    ///     "SELECT device_id, COUNT(*) FROM v_history WHERE co2 > ? GROUP BY device_id",
+    ///     &[serde_json::json!(1000)],
+    /// )?;
+    /// assert_eq!(result.columns, ["device_id", "COUNT(*)"]);
+    ///
+    /// assert!(store.query_readonly("DELETE FROM history", &[]).is_err());
+    /// # Ok::<(), aranet_store::Error>(())
+    /// ```
+    pub fn query_readonly(&self, sql: &str, params: &[serde_json::Value]) -> Result<QueryResult> {
+        use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+        use rusqlite::types::{Value, ValueRef};
+
+        self.create_query_views()?;
+
+        let params = params
+            .iter()
+            .map(|param| match param {
+                serde_json::Value::Null => Ok(Value::Null),
+                serde_json::Value::Bool(b) => Ok(Value::Integer(i64::from(*b))),
+                serde_json::Value::Number(n) => Ok(n
+                    .as_i64()
+                    .map(Value::Integer)
+                    .unwrap_or_else(|| Value::Real(n.as_f64().unwrap_or_default()))),
+                serde_json::Value::String(s) => Ok(Value::Text(s.clone())),
+                other => Err(Error::Query(format!(
+                    "parameters must be strings, numbers, booleans or null, not {other}"
+                ))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let _guard = QueryGuard(&self.conn);
+        self.conn.authorizer(Some(|context: AuthContext<'_>| {
+            let is_view = |name: &str| {
+                QUERY_VIEWS.iter().any(|view| view.name == name)
+                    || name == "history_all"
+                    || name == "readings_all"
+            };
+            match context.action {
+                AuthAction::Select | AuthAction::Recursive | AuthAction::Function { .. } => {
+                    Authorization::Allow
+                }
+                // Reads made by a view are reported with the view as accessor
+                AuthAction::Read { table_name, .. }
+                    if is_view(table_name) || context.accessor.is_some_and(is_view) =>
+                {
+                    Authorization::Allow
+                }
+                _ => Authorization::Deny,
+            }
+        }));
+        let deadline = std::time::Instant::now() + QUERY_TIMEOUT;
+        self.conn
+            .progress_handler(1000, Some(move || std::time::Instant::now() > deadline));
+
+        let refused = |e: rusqlite::Error| match e {
+            rusqlite::Error::SqliteFailure(failure, _)
+                if failure.code == rusqlite::ErrorCode::AuthorizationForStatementDenied =>
+            {
+                let views: Vec<_> = QUERY_VIEWS.iter().map(|view| view.name).collect();
+                Error::Query(format!(
+                    "only SELECT statements over {} are allowed",
+                    views.join(", ")
+                ))
+            }
+            rusqlite::Error::SqliteFailure(failure, _)
+                if failure.code == rusqlite::ErrorCode::OperationInterrupted =>
+            {
+                Error::Query(format!(
+                    "query took longer than {}s",
+                    QUERY_TIMEOUT.as_secs()
+                ))
+            }
+            rusqlite::Error::MultipleStatement => {
+                Error::Query("only one statement may be run".to_string())
+            }
+            e => Error::Database(e),
+        };
+
+        let mut stmt = self.conn.prepare(sql).map_err(refused)?;
+        if !stmt.readonly() {
+            return Err(Error::Query(
+                "statement would modify the database".to_string(),
+            ));
+        }
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+
+        let mut result = QueryResult {
+            columns,
+            ..Default::default()
+        };
+        let mut rows = stmt
+            .query(rusqlite::params_from_iter(params))
+            .map_err(refused)?;
+        while let Some(row) = rows.next().map_err(refused)? {
+            if result.rows.len() == QUERY_MAX_ROWS {
+                result.truncated = true;
+                break;
+            }
+            let values = (0..result.columns.len())
+                .map(|i| {
+                    Ok(match row.get_ref(i)? {
+                        ValueRef::Null => serde_json::Value::Null,
+                        ValueRef::Integer(i) => i.into(),
+                        ValueRef::Real(f) => serde_json::Number::from_f64(f)
+                            .map_or(serde_json::Value::Null, serde_json::Value::Number),
+                        ValueRef::Text(text) => String::from_utf8_lossy(text).into_owned().into(),
+                        ValueRef::Blob(blob) => blob
+                            .iter()
+                            .map(|b| format!("{b:02x}"))
+                            .collect::<String>()
+                            .into(),
+                    })
+                })
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            result.rows.push(values);
+        }
+        Ok(result)
+    }
+
+    /// (Re)create the [`QUERY_VIEWS`] over the current tables and archives.
+    fn create_query_views(&self) -> Result<()> {
+        let time = |column: &str| format!("strftime('{QUERY_TIME_FORMAT}', {column}, 'unixepoch')");
+        self.conn.execute_batch(&format!(
+            "DROP VIEW IF EXISTS temp.v_devices;
+             DROP VIEW IF EXISTS temp.v_readings;
+             DROP VIEW IF EXISTS temp.v_history;
+             DROP VIEW IF EXISTS temp.v_daily_stats;
+             DROP VIEW IF EXISTS temp.v_annotations;
+             DROP VIEW IF EXISTS temp.v_alerts;
+             CREATE TEMP VIEW v_devices AS
+                SELECT id AS device_id, name, device_type, serial, firmware, hardware,
+                       {first_seen} AS first_seen, {last_seen} AS last_seen
+                FROM main.devices;
+             CREATE TEMP VIEW v_readings AS
+                SELECT r.device_id, d.name AS device_name, r.captured_at,
+                       {captured_at} AS time, NULLIF(r.co2, 0) AS co2, r.temperature,
+                       NULLIF(r.pressure, 0) AS pressure, r.humidity, r.battery, r.status,
+                       r.radon, r.radiation_rate, r.radiation_total, r.quality
+                FROM {readings} r LEFT JOIN main.devices d ON d.id = r.device_id;
+             CREATE TEMP VIEW v_history AS
+                SELECT h.device_id, d.name AS device_name, h.timestamp, {timestamp} AS time,
+                       NULLIF(h.co2, 0) AS co2, h.temperature, NULLIF(h.pressure, 0) AS pressure,
+                       h.humidity, h.radon, h.radiation_rate, h.radiation_total
+                FROM {history} h LEFT JOIN main.devices d ON d.id = h.device_id;
+             CREATE TEMP VIEW v_daily_stats AS
+                SELECT device_id, strftime('%Y-%m-%d', day, 'unixepoch') AS day, count,
+                       co2_min, co2_max, co2_sum * 1.0 / NULLIF(co2_count, 0) AS co2_avg,
+                       temperature_min, temperature_max,
+                       temperature_sum / NULLIF(count, 0) AS temperature_avg,
+                       pressure_min, pressure_max,
+                       pressure_sum / NULLIF(pressure_count, 0) AS pressure_avg,
+                       humidity_min, humidity_max,
+                       humidity_sum * 1.0 / NULLIF(count, 0) AS humidity_avg,
+                       radon_min, radon_max, radon_sum * 1.0 / NULLIF(radon_count, 0) AS radon_avg
+                FROM main.daily_stats;
+             CREATE TEMP VIEW v_annotations AS
+                SELECT id, device_id, {start_at} AS start_time, {end_at} AS end_time, text, tag
+                FROM main.annotations;
+             CREATE TEMP VIEW v_alerts AS
+                SELECT id, device_id, kind, severity, value, message,
+                       {triggered_at} AS time, acknowledged
+                FROM main.alerts;",
+            first_seen = time("first_seen"),
+            last_seen = time("last_seen"),
+            captured_at = time("r.captured_at"),
+            timestamp = time("h.timestamp"),
+            start_at = time("start_at"),
+            end_at = time("end_at"),
+            triggered_at = time("triggered_at"),
+            readings = self.readings_source(),
+            history = self.history_source(),
+        ))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ALERT_CO2, ALERT_OFFLINE, SETTING_SMART_HOME, ValueFilter};
+    use aranet_types::Status;
+
+    fn create_test_reading() -> CurrentReading {
+        CurrentReading {
+            co2: 800,
+            temperature: 22.5,
+            pressure: 1013.0,
+            humidity: 45,
+            battery: 85,
+            status: Status::Green,
+            interval: 60,
+            age: 30,
+            captured_at: Some(OffsetDateTime::now_utc()),
+            radon: None,
+            radiation_rate: None,
+            radiation_total: None,
+            radon_avg_24h: None,
+            radon_avg_7d: None,
+            radon_avg_30d: None,
+        }
+    }
+
+    #[test]
+    fn test_open_in_memory() {
+        let store = Store::open_in_memory().unwrap();
+        let devices = store.list_devices().unwrap();
+        assert!(devices.is_empty());
+    }
+
+    #[test]
+    fn test_upsert_device() {
+        let store = Store::open_in_memory().unwrap();
+
+        let device = store.upsert_device("test-device", Some("Test")).unwrap();
+        assert_eq!(device.id, "test-device");
+        assert_eq!(device.name, Some("Test".to_string()));
+
+        // Update name
+        let device = store
+            .upsert_device("test-device", Some("New Name"))
+            .unwrap();
+        assert_eq!(device.name, Some("New Name".to_string()));
+    }
+
+    #[test]
+    fn test_insert_and_query_reading() {
+        let store = Store::open_in_memory().unwrap();
+        let reading = create_test_reading();
+
+        store.insert_reading("test-device", &reading).unwrap();
+
+        let query = ReadingQuery::new().device("test-device");
+        let readings = store.query_readings(&query).unwrap();
+
+        assert_eq!(readings.len(), 1);
+        assert_eq!(readings[0].co2, 800);
+        assert_eq!(readings[0].temperature, 22.5);
+    }
+
+    #[test]
+    fn test_get_latest_reading() {
+        let store = Store::open_in_memory().unwrap();
+
+        let mut reading1 = create_test_reading();
+        reading1.co2 = 700;
+        store.insert_reading("test-device", &reading1).unwrap();
+
+        let mut reading2 = create_test_reading();
+        reading2.co2 = 900;
+        store.insert_reading("test-device", &reading2).unwrap();
+
+        let latest = store.get_latest_reading("test-device").unwrap().unwrap();
+        assert_eq!(latest.co2, 900);
+    }
+
+    #[test]
+    fn test_insert_readings_unordered_interleaved() {
+        let store = Store::open_in_memory().unwrap();
+        let t0 = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let reading_at = |minutes: i64, co2: u16| {
+            let mut reading = create_test_reading();
+            reading.co2 = co2;
+            reading.captured_at = Some(t0 + time::Duration::minutes(minutes));
+            reading
+        };
+
+        // An earlier active read lands first; passive readings for both
+        // devices arrive afterwards, interleaved and out of order
+        store.insert_reading("a", &reading_at(4, 840)).unwrap();
+        let batch = [
+            ("b", reading_at(3, 530), ReadingQuality::Valid),
+            ("a", reading_at(5, 850), ReadingQuality::Valid),
+            ("a", reading_at(1, 810), ReadingQuality::Valid),
+            ("b", reading_at(0, 500), ReadingQuality::Valid),
+            ("a", reading_at(3, 830), ReadingQuality::Valid),
+            ("a", reading_at(2, 820), ReadingQuality::Valid),
+        ];
+        let ids = store.insert_readings_unordered(&batch).unwrap();
+        assert!(ids.iter().all(Option::is_some));
+
+        let co2 = |query: &ReadingQuery| -> Vec<u16> {
+            store
+                .query_readings(query)
+                .unwrap()
+                .iter()
+                .map(|r| r.co2)
+                .collect()
+        };
+        assert_eq!(
+            co2(&ReadingQuery::new().device("a").oldest_first()),
+            vec![810, 820, 830, 840, 850]
+        );
+        assert_eq!(
+            co2(&ReadingQuery::new().device("a")),
+            vec![850, 840, 830, 820, 810]
+        );
+        assert_eq!(
+            co2(&ReadingQuery::new()
+                .device("a")
+                .since(t0 + time::Duration::minutes(2))
+                .until(t0 + time::Duration::minutes(4))
+                .oldest_first()),
+            vec![820, 830, 840]
+        );
+        assert_eq!(
+            co2(&ReadingQuery::new().device("a").limit(2).offset(1)),
+            vec![840, 830]
+        );
+        assert_eq!(co2(&ReadingQuery::new().device("b")), vec![530, 500]);
+
+        // "Latest" follows capture time, not insertion order
+        assert_eq!(store.get_latest_reading("a").unwrap().unwrap().co2, 850);
+        assert_eq!(store.get_latest_reading("b").unwrap().unwrap().co2, 530);
+        let latest: Vec<_> = store
+            .list_latest_readings()
+            .unwrap()
+            .into_iter()
+            .map(|(device, reading)| (device.id, reading.co2))
+            .collect();
+        assert!(latest.contains(&("a".to_string(), 850)));
+        assert!(latest.contains(&("b".to_string(), 530)));
+
+        // Row IDs line up with the batch
+        let stored = store
+            .query_readings(&ReadingQuery::new().device("a").oldest_first())
+            .unwrap();
+        assert_eq!(stored[0].id, ids[2].unwrap());
+        assert_eq!(stored[0].quality, ReadingQuality::Valid);
+    }
+
+    #[test]
+    fn test_insert_readings_unordered_skips_copies() {
+        let store = Store::open_in_memory().unwrap();
+        let t0 = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let mut reading = create_test_reading();
+        reading.captured_at = Some(t0);
+        store.insert_reading("a", &reading).unwrap();
+
+        // The same measurement from an advertisement a few seconds off, and a
+        // different measurement inside the window
+        let mut advertised = reading;
+        advertised.captured_at = Some(t0 + time::Duration::seconds(4));
+        let mut changed = reading;
+        changed.co2 += 1;
+        changed.captured_at = Some(t0 + time::Duration::seconds(10));
+        let batch = [
+            ("a", changed, ReadingQuality::Unchecked),
+            ("a", advertised, ReadingQuality::Unchecked),
+            ("a", advertised, ReadingQuality::Unchecked),
+        ];
+
+        let ids = store.insert_readings_unordered(&batch).unwrap();
+        assert!(ids[0].is_some());
+        assert_eq!(ids[1..], [None, None]);
+        assert_eq!(
+            store
+                .query_readings(&ReadingQuery::new().device("a"))
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_out_of_order_reading_keeps_current_interval() {
+        let store = Store::open_in_memory().unwrap();
+        let t0 = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+
+        let mut reading = create_test_reading();
+        reading.interval = 60;
+        reading.captured_at = Some(t0 + time::Duration::minutes(10));
+        store.insert_reading("a", &reading).unwrap();
+
+        // A late reading taken before the interval change
+        reading.interval = 300;
+        reading.captured_at = Some(t0);
+        store.insert_reading("a", &reading).unwrap();
+        store
+            .insert_readings_unordered(&[("a", reading, ReadingQuality::Unchecked)])
+            .unwrap();
+
+        let history = store.settings_history("a").unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].new_value, "60");
+    }
+
+    #[test]
+    fn test_list_latest_readings_returns_one_row_per_device() {
+        let store = Store::open_in_memory().unwrap();
+
+        let mut alpha_old = create_test_reading();
+        alpha_old.co2 = 700;
+        alpha_old.captured_at = Some(OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(1));
+        store.insert_reading("alpha", &alpha_old).unwrap();
+
+        let mut alpha_new = create_test_reading();
+        alpha_new.co2 = 900;
+        alpha_new.captured_at = Some(OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(2));
+        store.insert_reading("alpha", &alpha_new).unwrap();
+
+        let mut beta = create_test_reading();
+        beta.co2 = 500;
+        beta.captured_at = Some(OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(3));
+        store.insert_reading("beta", &beta).unwrap();
+
+        store.upsert_device("gamma", Some("No Reading")).unwrap();
+
+        let latest = store.list_latest_readings().unwrap();
+
+        assert_eq!(latest.len(), 2);
+        let latest_by_device: std::collections::HashMap<_, _> = latest
+            .into_iter()
+            .map(|(device, reading)| (device.id, reading.co2))
+            .collect();
+        assert_eq!(latest_by_device.get("alpha"), Some(&900));
+        assert_eq!(latest_by_device.get("beta"), Some(&500));
+        assert!(!latest_by_device.contains_key("gamma"));
+    }
+
+    #[test]
+    fn test_insert_history_deduplication() {
+        let store = Store::open_in_memory().unwrap();
+
+        let now = OffsetDateTime::now_utc();
+        let records = vec![
+            HistoryRecord {
+                timestamp: now,
+                co2: 800,
+                temperature: 22.0,
+                pressure: 1013.0,
+                humidity: 45,
+                radon: None,
+                radiation_rate: None,
+                radiation_total: None,
+            },
+            HistoryRecord {
+                timestamp: now, // Same timestamp - should be deduplicated
+                co2: 850,
+                temperature: 23.0,
+                pressure: 1014.0,
+                humidity: 46,
+                radon: None,
+                radiation_rate: None,
+                radiation_total: None,
+            },
+        ];
+
+        let inserted = store.insert_history("test-device", &records).unwrap();
+        assert_eq!(inserted, 1); // Only one inserted due to dedup
 
-        let bytes = wtr
-            .into_inner()
-            .map_err(|e| Error::Io(std::io::Error::other(e)))?;
-        String::from_utf8(bytes).map_err(|e| Error::Io(std::io::Error::other(e)))
+        let count = store.count_history(Some("test-device")).unwrap();
+        assert_eq!(count, 1);
     }
 
-    /// Export history records to JSON format.
-    ///
-    /// Exports records matching the query as a pretty-printed JSON array of
-    /// [`StoredHistoryRecord`] objects.
-    ///
-    /// # Arguments
-    ///
-    /// * `query` - Filter which records to export
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use aranet_store::{Store, HistoryQuery};
-    ///
-    /// let store = Store::open_in_memory()?;
-    ///
-    /// let query = HistoryQuery::new().device("Aranet4 17C3C");
-    /// let json = store.export_history_json(&query)?;
-    /// println!("{}", json);
-    /// # Ok::<(), aranet_store::Error>(())
-    /// ```
-    pub fn export_history_json(&self, query: &HistoryQuery) -> Result<String> {
-        let sql = query.build_sql();
-        let (_, params) = query.build_where();
-        let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
-        let mut stmt = self.conn.prepare(&sql)?;
-        let rows = stmt.query_map(params_ref.as_slice(), |row| {
-            Ok(StoredHistoryRecord {
-                id: row.get(0)?,
-                device_id: row.get(1)?,
-                timestamp: timestamp_from_unix(row.get(2)?),
-                synced_at: timestamp_from_unix(row.get(3)?),
-                co2: u16::try_from(row.get::<_, i64>(4)?).unwrap_or_else(|e| {
-                    warn!("Invalid co2 value in JSON export: {e}");
-                    0
-                }),
-                temperature: row.get(5)?,
-                pressure: row.get(6)?,
-                humidity: u8::try_from(row.get::<_, i64>(7)?).unwrap_or_else(|e| {
-                    warn!("Invalid humidity value in JSON export: {e}");
-                    0
-                }),
-                radon: row
-                    .get::<_, Option<i64>>(8)?
-                    .and_then(|v| radon_from_i64(v, "json_export")),
-                radiation_rate: row.get(9)?,
-                radiation_total: row.get(10)?,
+    #[test]
+    fn test_insert_history_skips_shifted_copies() {
+        let store = Store::open_in_memory().unwrap();
+        let t0 = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let record = |offset_secs: i64, co2: u16| HistoryRecord {
+            timestamp: t0 + time::Duration::seconds(offset_secs),
+            co2,
+            temperature: 22.0,
+            pressure: 1013.0,
+            humidity: 45,
+            ..Default::default()
+        };
+
+        // Identical values one interval apart are separate measurements
+        let first = [record(0, 800), record(60, 800)];
+        assert_eq!(store.insert_history("test-device", &first).unwrap(), 2);
+
+        // A repeated sync shifts timestamps by a second or two
+        let again = [record(1, 800), record(62, 800), record(121, 810)];
+        assert_eq!(store.insert_history("test-device", &again).unwrap(), 1);
+        assert_eq!(store.count_history(Some("test-device")).unwrap(), 3);
+        assert!(store.verify().unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_verify_finds_and_deletes_history_duplicates() {
+        let store = Store::open_in_memory().unwrap();
+        let t0 = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let records: Vec<_> = (0..3)
+            .map(|i| HistoryRecord {
+                timestamp: t0 + time::Duration::minutes(i),
+                co2: 800,
+                temperature: 22.0,
+                pressure: 1013.0,
+                humidity: 45,
+                ..Default::default()
             })
-        })?;
+            .collect();
+        store.insert_history("test-device", &records).unwrap();
 
-        let mut json = String::from("[");
-        let mut first = true;
+        // Copies written before content deduplication existed
+        store
+            .conn
+            .execute(
+                "INSERT INTO history (device_id, timestamp, synced_at, co2, temperature,
+                 pressure, humidity, hash)
+                 SELECT device_id, timestamp + 2, synced_at, co2, temperature, pressure,
+                        humidity, hash
+                 FROM history",
+                [],
+            )
+            .unwrap();
+        store
+            .conn
+            .execute(
+                "UPDATE history SET co2 = 900 WHERE timestamp = ?1",
+                [t0.unix_timestamp()],
+            )
+            .unwrap();
 
-        for row in rows {
-            let record = row?;
-            let record_json = serde_json::to_string_pretty(&record)?;
-            if first {
-                json.push('\n');
-                first = false;
-            } else {
-                json.push_str(",\n");
-            }
+        let report = store.verify().unwrap();
+        assert!(!report.is_ok());
+        assert_eq!(report.duplicates, vec![("test-device".to_string(), 3)]);
+        assert_eq!(report.checksum_mismatches, 1);
 
-            for line in record_json.lines() {
-                json.push_str("  ");
-                json.push_str(line);
-                json.push('\n');
-            }
-        }
+        assert_eq!(store.delete_history_duplicates().unwrap(), 3);
+        assert_eq!(store.count_history(Some("test-device")).unwrap(), 3);
+        let report = store.verify().unwrap();
+        assert_eq!(report.duplicate_count(), 0);
 
-        json.push(']');
+        let days = store
+            .daily_stats(&HistoryQuery::new().device("test-device"))
+            .unwrap();
+        assert_eq!(days.iter().map(|d| d.count).sum::<u64>(), 3);
+    }
 
-        Ok(json)
+    #[test]
+    fn test_sample_history() {
+        let store = Store::open_in_memory().unwrap();
+        let t0 = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let records: Vec<_> = (0..50)
+            .map(|i| HistoryRecord {
+                timestamp: t0 + time::Duration::minutes(i),
+                co2: 400 + i as u16,
+                temperature: 21.0,
+                pressure: 1013.0,
+                humidity: 40,
+                radon: None,
+                radiation_rate: None,
+                radiation_total: None,
+            })
+            .collect();
+        store.insert_history("test-device", &records).unwrap();
+
+        let query = HistoryQuery::new()
+            .device("test-device")
+            .since(t0 + time::Duration::minutes(10));
+        let sample = store.sample_history(&query, 5).unwrap();
+        assert_eq!(sample.len(), 5);
+        assert!(sample.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+        assert!(sample.iter().all(|r| r.co2 >= 410));
+
+        // Asking for more than exist returns everything that matches
+        let all = store.sample_history(&query, 1000).unwrap();
+        assert_eq!(all.len(), 40);
     }
 
-    /// Import history records from CSV format.
-    ///
-    /// Expected CSV format:
-    /// ```csv
-    /// timestamp,device_id,co2,temperature,pressure,humidity,radon
-    /// 2024-01-15T10:30:00Z,Aranet4 17C3C,800,22.5,1013.25,45,
-    /// ```
-    ///
-    /// Returns the number of records imported (deduplicated by device_id + timestamp).
-    pub fn import_history_csv(&self, csv_data: &str) -> Result<ImportResult> {
-        let mut reader = csv::ReaderBuilder::new()
-            .has_headers(true)
-            .flexible(true)
-            .trim(csv::Trim::All)
-            .from_reader(csv_data.as_bytes());
+    #[test]
+    fn test_sync_state() {
+        let store = Store::open_in_memory().unwrap();
+        store.upsert_device("test-device", None).unwrap();
 
-        let mut total = 0;
-        let mut imported = 0;
-        let mut skipped = 0;
-        let mut errors = Vec::new();
-        let mut device_records: std::collections::HashMap<String, Vec<HistoryRecord>> =
-            std::collections::HashMap::new();
+        // Initially no sync state
+        let state = store.get_sync_state("test-device").unwrap();
+        assert!(state.is_none());
 
-        for (line_num, result) in reader.records().enumerate() {
-            total += 1;
-            let line = line_num + 2; // Account for header and 0-indexing
+        // Update sync state
+        store.update_sync_state("test-device", 100, 100).unwrap();
 
-            let record = match result {
-                Ok(r) => r,
-                Err(e) => {
-                    errors.push(format!("Line {}: parse error - {}", line, e));
-                    skipped += 1;
-                    continue;
-                }
-            };
+        let state = store.get_sync_state("test-device").unwrap().unwrap();
+        assert_eq!(state.last_history_index, Some(100));
+        assert_eq!(state.total_readings, Some(100));
+        assert!(state.last_sync_at.is_some());
+    }
 
-            // Parse fields
-            let timestamp_str = record.get(0).unwrap_or("").trim();
-            let device_id = record.get(1).unwrap_or("").trim();
-            let co2_str = record.get(2).unwrap_or("").trim();
-            let temp_str = record.get(3).unwrap_or("").trim();
-            let pressure_str = record.get(4).unwrap_or("").trim();
-            let humidity_str = record.get(5).unwrap_or("").trim();
-            let radon_str = record.get(6).unwrap_or("").trim();
-
-            // Validate required fields
-            if device_id.is_empty() {
-                errors.push(format!("Line {}: missing device_id", line));
-                skipped += 1;
-                continue;
-            }
+    #[test]
+    fn test_clock_drift() {
+        let store = Store::open_in_memory().unwrap();
+        store.upsert_device("test", None).unwrap();
 
-            if timestamp_str.is_empty() {
-                errors.push(format!("Line {}: missing timestamp", line));
-                skipped += 1;
-                continue;
-            }
+        assert!(store.get_clock_drift("test").unwrap().is_none());
 
-            // Parse timestamp
-            let timestamp = match OffsetDateTime::parse(
-                timestamp_str,
-                &time::format_description::well_known::Rfc3339,
-            ) {
-                Ok(ts) => ts,
-                Err(_) => {
-                    errors.push(format!(
-                        "Line {}: invalid timestamp '{}'",
-                        line, timestamp_str
-                    ));
-                    skipped += 1;
-                    continue;
-                }
-            };
+        store.update_clock_drift("test", 42.5, 21_600).unwrap();
+        store.update_clock_drift("test", -12.0, 86_400).unwrap();
 
-            // Parse numeric fields with defaults and validation
-            // Parse as u32 first so values > u16::MAX (65535) produce a clear
-            // "exceeds maximum" message instead of a generic parse error.
-            let co2: u16 = match co2_str.parse::<u32>() {
-                Ok(v) if v <= 10000 => v as u16, // CO2 sensor max is typically 10000 ppm
-                Ok(v) => {
-                    errors.push(format!(
-                        "Line {}: CO2 value {} exceeds maximum of 10000 ppm",
-                        line, v
-                    ));
-                    skipped += 1;
-                    continue;
-                }
-                Err(_) if co2_str.is_empty() => 0,
-                Err(_) => {
-                    errors.push(format!("Line {}: invalid CO2 value '{}'", line, co2_str));
-                    skipped += 1;
-                    continue;
-                }
-            };
+        let drift = store.get_clock_drift("test").unwrap().unwrap();
+        assert_eq!(drift.device_id, "test");
+        assert!((drift.drift_ppm + 12.0).abs() < f64::EPSILON);
+        assert_eq!(drift.span_seconds, 86_400);
 
-            let temperature: f32 = match temp_str.parse::<f32>() {
-                Ok(v) if (-40.0..=100.0).contains(&v) => v,
-                Ok(v) => {
-                    errors.push(format!(
-                        "Line {}: temperature {} is outside valid range (-40 to 100°C)",
-                        line, v
-                    ));
-                    skipped += 1;
-                    continue;
-                }
-                Err(_) if temp_str.is_empty() => 0.0,
-                Err(_) => {
-                    errors.push(format!(
-                        "Line {}: invalid temperature value '{}'",
-                        line, temp_str
-                    ));
-                    skipped += 1;
-                    continue;
-                }
-            };
+        assert!(store.delete_device("test").unwrap());
+        assert!(store.get_clock_drift("test").unwrap().is_none());
+    }
 
-            let pressure: f32 = match pressure_str.parse::<f32>() {
-                Ok(v) if v == 0.0 || (800.0..=1200.0).contains(&v) => v,
-                Ok(v) => {
-                    errors.push(format!(
-                        "Line {}: pressure {} is outside valid range (800-1200 hPa)",
-                        line, v
-                    ));
-                    skipped += 1;
-                    continue;
-                }
-                Err(_) if pressure_str.is_empty() => 0.0,
-                Err(_) => {
-                    errors.push(format!(
-                        "Line {}: invalid pressure value '{}'",
-                        line, pressure_str
-                    ));
-                    skipped += 1;
-                    continue;
-                }
-            };
+    #[test]
+    fn test_find_device_by_name() {
+        let store = Store::open_in_memory().unwrap();
+        store
+            .upsert_device("OLD-UUID", Some("Aranet4 17C3C"))
+            .unwrap();
+        store.upsert_device("Aranet2 2B4F1", None).unwrap();
 
-            let humidity: u8 = match humidity_str.parse::<u8>() {
-                Ok(v) if v <= 100 => v,
-                Ok(v) => {
-                    errors.push(format!(
-                        "Line {}: humidity {} exceeds maximum of 100%",
-                        line, v
-                    ));
-                    skipped += 1;
-                    continue;
-                }
-                Err(_) if humidity_str.is_empty() => 0,
-                Err(_) => {
-                    errors.push(format!(
-                        "Line {}: invalid humidity value '{}'",
-                        line, humidity_str
-                    ));
-                    skipped += 1;
-                    continue;
-                }
-            };
+        let found = store.find_device_by_name("aranet4 17c3c").unwrap().unwrap();
+        assert_eq!(found.id, "OLD-UUID");
+        // Names used as IDs match too
+        let found = store.find_device_by_name("Aranet2 2B4F1").unwrap().unwrap();
+        assert_eq!(found.id, "Aranet2 2B4F1");
 
-            let radon: Option<u32> = if radon_str.is_empty() {
-                None
-            } else {
-                match radon_str.parse::<u32>() {
-                    Ok(v) if v <= 100000 => Some(v), // Radon max ~100000 Bq/m³
-                    Ok(v) => {
-                        errors.push(format!(
-                            "Line {}: radon value {} exceeds maximum of 100000 Bq/m³",
-                            line, v
-                        ));
-                        skipped += 1;
-                        continue;
-                    }
-                    Err(_) => {
-                        errors.push(format!(
-                            "Line {}: invalid radon value '{}'",
-                            line, radon_str
-                        ));
-                        skipped += 1;
-                        continue;
-                    }
-                }
-            };
+        assert!(
+            store
+                .find_device_by_name("Aranet4 17C3D")
+                .unwrap()
+                .is_none()
+        );
+        assert!(store.find_device_by_name("Aranet4").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_purge_device_counts() {
+        let store = Store::open_in_memory().unwrap();
+        store
+            .insert_reading("test", &create_test_reading())
+            .unwrap();
+        store
+            .insert_reading("test", &create_test_reading())
+            .unwrap();
+        store.update_clock_drift("test", 5.0, 3_600).unwrap();
+        store
+            .insert_reading("other", &create_test_reading())
+            .unwrap();
 
-            // Create history record
-            let history_record = HistoryRecord {
-                timestamp,
-                co2,
-                temperature,
-                pressure,
-                humidity,
-                radon,
-                radiation_rate: None,
-                radiation_total: None,
-            };
+        let counts = store.count_device_data("test").unwrap();
+        assert!(counts.device);
+        assert_eq!(counts.readings, 2);
+        assert_eq!(counts.clock_drift, 1);
+        // The first reading records the device's measurement interval
+        assert_eq!(counts.settings_history, 1);
+        assert_eq!(counts.history, 0);
+        assert_eq!(counts.total(), 5);
+
+        // Counting leaves the data in place
+        assert_eq!(store.count_device_data("test").unwrap(), counts);
+
+        assert_eq!(store.purge_device("test").unwrap(), counts);
+        assert_eq!(store.count_device_data("test").unwrap().total(), 0);
+        assert_eq!(store.count_device_data("other").unwrap().readings, 1);
+        assert_eq!(store.purge_device("test").unwrap().total(), 0);
+    }
 
-            device_records
-                .entry(device_id.to_string())
-                .or_default()
-                .push(history_record);
-        }
+    #[test]
+    fn test_merge_devices() {
+        let store = Store::open_in_memory().unwrap();
+        let record_at = |minutes: i64| HistoryRecord {
+            timestamp: time::macros::datetime!(2024-06-01 12:00 UTC)
+                + time::Duration::minutes(minutes),
+            co2: 800,
+            temperature: 22.0,
+            pressure: 1013.0,
+            humidity: 45,
+            radon: None,
+            radiation_rate: None,
+            radiation_total: None,
+        };
 
-        for (device_id, records) in device_records {
-            self.upsert_device(&device_id, None)?;
-            let total_records = records.len();
-            let count = self.insert_history(&device_id, &records)?;
-            imported += count;
-            skipped += total_records.saturating_sub(count);
-        }
+        store
+            .insert_reading("old-uuid", &create_test_reading())
+            .unwrap();
+        store
+            .insert_reading("old-uuid", &create_test_reading())
+            .unwrap();
+        store
+            .insert_history("old-uuid", &[record_at(0), record_at(5)])
+            .unwrap();
+        store.update_clock_drift("old-uuid", 5.0, 3_600).unwrap();
+        store
+            .insert_history("new-uuid", &[record_at(5), record_at(10)])
+            .unwrap();
 
-        Ok(ImportResult {
-            total,
-            imported,
-            skipped,
-            errors,
-        })
+        let moved = store.merge_devices("old-uuid", "new-uuid").unwrap();
+        assert_eq!(moved.readings, 2);
+        // The record at +5 min already exists under the new ID
+        assert_eq!(moved.history, 1);
+        assert_eq!(moved.clock_drift, 1);
+
+        assert_eq!(store.count_device_data("old-uuid").unwrap().total(), 0);
+        let merged = store.count_device_data("new-uuid").unwrap();
+        assert_eq!((merged.readings, merged.history), (2, 3));
+        assert!(store.get_clock_drift("new-uuid").unwrap().is_some());
+        let days = store
+            .daily_stats(&HistoryQuery::new().device("new-uuid"))
+            .unwrap();
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].count, 3);
+
+        // Merging into an unknown ID carries the device row over
+        store.merge_devices("new-uuid", "renamed").unwrap();
+        assert!(store.get_device("renamed").unwrap().is_some());
+        assert_eq!(store.count_device_data("renamed").unwrap().history, 3);
+
+        assert!(matches!(
+            store.merge_devices("missing", "renamed"),
+            Err(Error::DeviceNotFound(_))
+        ));
+        assert_eq!(
+            store.merge_devices("renamed", "renamed").unwrap().total(),
+            0
+        );
     }
 
-    /// Import history records from JSON format.
-    ///
-    /// Expected JSON format: an array of StoredHistoryRecord objects.
-    ///
-    /// Returns the number of records imported (deduplicated by device_id + timestamp).
-    pub fn import_history_json(&self, json_data: &str) -> Result<ImportResult> {
-        let records: Vec<StoredHistoryRecord> = serde_json::from_str(json_data)?;
+    #[test]
+    fn test_device_bundle_round_trip() {
+        let store = Store::open_in_memory().unwrap();
+        let at = time::macros::datetime!(2024-06-01 12:00 UTC);
+        let record = HistoryRecord {
+            timestamp: at,
+            co2: 800,
+            temperature: 22.0,
+            pressure: 1013.0,
+            humidity: 45,
+            radon: None,
+            radiation_rate: None,
+            radiation_total: None,
+        };
+        store.upsert_device("kitchen", Some("Kitchen")).unwrap();
+        store
+            .insert_reading_with_quality(
+                "kitchen",
+                &CurrentReading {
+                    captured_at: Some(at),
+                    ..create_test_reading()
+                },
+                ReadingQuality::Invalid,
+            )
+            .unwrap();
+        store.insert_history("kitchen", &[record]).unwrap();
+        store
+            .add_annotation("kitchen", at..=at, "Window opened", None)
+            .unwrap();
+        store.update_sync_state("kitchen", 1, 1).unwrap();
+        store
+            .insert_reading("other", &create_test_reading())
+            .unwrap();
 
-        let total = records.len();
-        let mut imported = 0;
-        let mut skipped = 0;
-        let mut device_records: std::collections::HashMap<String, Vec<HistoryRecord>> =
-            std::collections::HashMap::new();
+        let mut bundle = Vec::new();
+        let exported = store.export_device_bundle("kitchen", &mut bundle).unwrap();
+        assert_eq!(
+            (exported.readings, exported.history, exported.annotations),
+            (1, 1, 1)
+        );
 
-        for record in records {
-            let device_id = record.device_id.clone();
-            device_records
-                .entry(device_id)
-                .or_default()
-                .push(record.to_history());
-        }
+        let parsed: DeviceBundle = serde_json::from_slice(&bundle).unwrap();
+        let preview = ImportPreview::device_bundle(&parsed);
+        assert_eq!(preview.devices, vec!["kitchen".to_string()]);
+        assert_eq!((preview.readings, preview.history), (1, 1));
+        assert_eq!((preview.start, preview.end), (Some(at), Some(at)));
+
+        let other = Store::open_in_memory().unwrap();
+        let imported = other.import_device_bundle(bundle.as_slice()).unwrap();
+        assert_eq!(imported, exported);
+        assert_eq!(other.list_devices().unwrap().len(), 1);
+        let device = other.get_device("kitchen").unwrap().unwrap();
+        assert_eq!(device.name.as_deref(), Some("Kitchen"));
+        let readings = other
+            .query_readings(&ReadingQuery::new().device("kitchen"))
+            .unwrap();
+        assert_eq!(readings[0].quality, ReadingQuality::Invalid);
+        assert_eq!(readings[0].captured_at, at);
+        assert!(other.get_sync_state("kitchen").unwrap().is_some());
+
+        // Importing again adds nothing
+        let again = other.import_device_bundle(bundle.as_slice()).unwrap();
+        assert_eq!(again.total(), 1);
+
+        assert!(matches!(
+            store.export_device_bundle("missing", Vec::new()),
+            Err(Error::DeviceNotFound(_))
+        ));
+        let future =
+            String::from_utf8(bundle)
+                .unwrap()
+                .replacen("\"version\": 1", "\"version\": 99", 1);
+        assert!(matches!(
+            other.import_device_bundle(future.as_bytes()),
+            Err(Error::UnsupportedBundle(_))
+        ));
+    }
 
-        for (device_id, records) in device_records {
-            self.upsert_device(&device_id, None)?;
-            let total_records = records.len();
-            let count = self.insert_history(&device_id, &records)?;
-            imported += count;
-            skipped += total_records.saturating_sub(count);
-        }
+    #[test]
+    fn test_purge_before_and_cache_stats() {
+        let store = Store::open_in_memory().unwrap();
+        let day = time::macros::datetime!(2024-06-01 00:00 UTC);
+        let record_at = |hours: i64| HistoryRecord {
+            timestamp: day + time::Duration::hours(hours),
+            co2: 800,
+            temperature: 22.0,
+            pressure: 1013.0,
+            humidity: 45,
+            radon: None,
+            radiation_rate: None,
+            radiation_total: None,
+        };
+        let records = [record_at(1), record_at(12), record_at(25), record_at(49)];
+        store.insert_history("a", &records).unwrap();
+        store.insert_history("b", &records).unwrap();
+
+        let stats = store.device_cache_stats().unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].counts.history, 4);
+        assert_eq!(stats[0].counts.daily_stats, 3);
+        assert_eq!(
+            stats[0].history_range,
+            Some((record_at(1).timestamp, record_at(49).timestamp))
+        );
+        assert_eq!(stats[0].readings_range, None);
 
-        Ok(ImportResult {
-            total,
-            imported,
-            skipped,
-            errors: Vec::new(),
-        })
+        // Cutting mid-way through the second day keeps that day's summary
+        let purged = store
+            .purge_before(Some("a"), day + time::Duration::hours(30))
+            .unwrap();
+        assert_eq!((purged.history, purged.daily_stats), (3, 1));
+        assert_eq!(store.count_device_data("a").unwrap().history, 1);
+        assert_eq!(store.count_device_data("b").unwrap().history, 4);
+
+        let purged = store
+            .purge_before(None, day + time::Duration::days(3))
+            .unwrap();
+        assert_eq!((purged.history, purged.daily_stats), (5, 5));
+        assert!(store.get_device("a").unwrap().is_some());
     }
-}
 
-/// Result of an import operation.
-#[derive(Debug, Clone)]
-pub struct ImportResult {
-    /// Total records processed.
-    pub total: usize,
-    /// Records successfully imported.
-    pub imported: usize,
-    /// Records skipped (duplicates or errors).
-    pub skipped: usize,
-    /// Error messages for failed records.
-    pub errors: Vec<String>,
-}
+    #[test]
+    fn test_verify_and_compact() {
+        let store = Store::open_in_memory().unwrap();
+        store
+            .insert_reading("test", &create_test_reading())
+            .unwrap();
+        assert!(store.verify().unwrap().is_ok());
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use aranet_types::Status;
+        // Rows written without foreign key enforcement can outlive their device
+        store
+            .conn
+            .execute_batch("PRAGMA foreign_keys = OFF;")
+            .unwrap();
+        store
+            .conn
+            .execute("DELETE FROM devices WHERE id = 'test'", [])
+            .unwrap();
+        store
+            .conn
+            .execute_batch("PRAGMA foreign_keys = ON;")
+            .unwrap();
 
-    fn create_test_reading() -> CurrentReading {
-        CurrentReading {
+        let report = store.verify().unwrap();
+        assert!(report.problems.is_empty());
+        assert!(!report.is_ok());
+        assert_eq!(report.orphans[0], ("readings".to_string(), 1));
+        assert_eq!(store.delete_orphans().unwrap(), report.orphan_count());
+        assert!(store.verify().unwrap().is_ok());
+
+        store.compact().unwrap();
+        let size = store.database_size().unwrap();
+        assert!(size.bytes > 0);
+        assert_eq!(size.free_bytes, 0);
+    }
+
+    #[test]
+    fn test_rollover_moves_old_rows_into_attached_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = Store::open(dir.path().join("data.db")).unwrap();
+
+        let history_at = |year: i32| HistoryRecord {
+            timestamp: time::Date::from_calendar_date(year, time::Month::June, 1)
+                .unwrap()
+                .midnight()
+                .assume_utc(),
             co2: 800,
-            temperature: 22.5,
+            temperature: 22.0,
             pressure: 1013.0,
             humidity: 45,
-            battery: 85,
-            status: Status::Green,
-            interval: 60,
-            age: 30,
-            captured_at: Some(OffsetDateTime::now_utc()),
             radon: None,
             radiation_rate: None,
             radiation_total: None,
-            radon_avg_24h: None,
-            radon_avg_7d: None,
-            radon_avg_30d: None,
+        };
+        store
+            .insert_history(
+                "test",
+                &[history_at(2023), history_at(2024), history_at(2025)],
+            )
+            .unwrap();
+        let mut reading = create_test_reading();
+        reading.captured_at = Some(history_at(2024).timestamp);
+        store.insert_reading("test", &reading).unwrap();
+        store
+            .insert_reading("test", &create_test_reading())
+            .unwrap();
+
+        let summary = store.rollover(2024).unwrap();
+        assert_eq!(summary.history, 2);
+        assert_eq!(summary.readings, 1);
+        assert_eq!(summary.path, dir.path().join("data-2024.db"));
+        assert_eq!(store.archives(), std::slice::from_ref(&summary.path));
+
+        // Queries still see every row exactly once
+        assert_eq!(store.count_history(Some("test")).unwrap(), 3);
+        assert_eq!(store.count_readings(Some("test")).unwrap(), 2);
+        let years: Vec<i32> = store
+            .query_history(&HistoryQuery::new().oldest_first())
+            .unwrap()
+            .iter()
+            .map(|r| r.timestamp.year())
+            .collect();
+        assert_eq!(years, [2023, 2024, 2025]);
+
+        // ...but only recent rows remain in the active database
+        let active = Store::open(dir.path().join("data.db")).unwrap();
+        assert_eq!(active.count_history(None).unwrap(), 1);
+        assert_eq!(active.count_readings(None).unwrap(), 1);
+
+        // Writes go to the active database; archives stay read-only
+        store.insert_history("test", &[history_at(2026)]).unwrap();
+        assert_eq!(store.count_history(None).unwrap(), 4);
+
+        // Rolling over again appends to the same archive
+        let summary = store.rollover(2025).unwrap();
+        assert_eq!(summary.history, 1);
+        assert_eq!(store.archives().len(), 2);
+        assert_eq!(store.count_history(None).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_attach_archive_without_quality_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("data-2023.db");
+        {
+            let archive = Store::open(&old).unwrap();
+            archive
+                .insert_reading("test", &create_test_reading())
+                .unwrap();
+            // Archives written before quality flags existed
+            archive
+                .conn
+                .execute_batch("ALTER TABLE readings DROP COLUMN quality;")
+                .unwrap();
         }
+
+        let mut store = Store::open_in_memory().unwrap();
+        store
+            .insert_reading_with_quality("test", &create_test_reading(), ReadingQuality::Valid)
+            .unwrap();
+        store.attach_archive(&old).unwrap();
+
+        let mut qualities: Vec<ReadingQuality> = store
+            .query_readings(&ReadingQuery::new())
+            .unwrap()
+            .iter()
+            .map(|r| r.quality)
+            .collect();
+        qualities.sort_by_key(|q| q.as_str());
+        assert_eq!(
+            qualities,
+            [ReadingQuality::Unchecked, ReadingQuality::Valid]
+        );
     }
 
     #[test]
-    fn test_open_in_memory() {
-        let store = Store::open_in_memory().unwrap();
-        let devices = store.list_devices().unwrap();
-        assert!(devices.is_empty());
+    fn test_attach_archive_rejects_missing_and_foreign_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = Store::open_in_memory().unwrap();
+        assert!(store.archive_path(2024).is_none());
+        assert!(matches!(store.rollover(2024), Err(Error::Archive(_))));
+
+        let missing = dir.path().join("missing.db");
+        assert!(matches!(
+            store.attach_archive(&missing),
+            Err(Error::Archive(_))
+        ));
+
+        let foreign = dir.path().join("foreign.db");
+        Connection::open(&foreign)
+            .unwrap()
+            .execute_batch("CREATE TABLE notes (body TEXT);")
+            .unwrap();
+        assert!(matches!(
+            store.attach_archive(&foreign),
+            Err(Error::Archive(_))
+        ));
+        assert!(store.archives().is_empty());
     }
 
     #[test]
-    fn test_upsert_device() {
+    fn test_settings_history() {
         let store = Store::open_in_memory().unwrap();
-
-        let device = store.upsert_device("test-device", Some("Test")).unwrap();
-        assert_eq!(device.id, "test-device");
-        assert_eq!(device.name, Some("Test".to_string()));
-
-        // Update name
-        let device = store
-            .upsert_device("test-device", Some("New Name"))
+        let t0 = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+
+        // Interval changes are picked up from readings
+        let mut reading = create_test_reading();
+        reading.interval = 300;
+        reading.captured_at = Some(t0);
+        store.insert_reading("test", &reading).unwrap();
+        reading.captured_at = Some(t0 + time::Duration::minutes(5));
+        store.insert_reading("test", &reading).unwrap();
+        reading.interval = 60;
+        reading.captured_at = Some(t0 + time::Duration::minutes(10));
+        store.insert_reading("test", &reading).unwrap();
+
+        // Unchanged values are not recorded again
+        let t1 = t0 + time::Duration::hours(1);
+        assert!(
+            store
+                .record_setting("test", SETTING_SMART_HOME, "false", t1)
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            store
+                .record_setting("test", SETTING_SMART_HOME, "false", t1)
+                .unwrap()
+                .is_none()
+        );
+        let change = store
+            .record_setting("test", SETTING_SMART_HOME, "true", t1)
+            .unwrap()
             .unwrap();
-        assert_eq!(device.name, Some("New Name".to_string()));
+        assert_eq!(change.old_value.as_deref(), Some("false"));
+
+        let history = store.settings_history("test").unwrap();
+        let summary: Vec<_> = history
+            .iter()
+            .map(|c| {
+                (
+                    c.setting.as_str(),
+                    c.old_value.as_deref(),
+                    c.new_value.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (SETTING_INTERVAL, None, "300"),
+                (SETTING_INTERVAL, Some("300"), "60"),
+                (SETTING_SMART_HOME, None, "false"),
+                (SETTING_SMART_HOME, Some("false"), "true"),
+            ]
+        );
+        assert_eq!(history[1].changed_at, t0 + time::Duration::minutes(10));
+
+        assert!(store.delete_device("test").unwrap());
+        assert!(store.settings_history("test").unwrap().is_empty());
     }
 
     #[test]
-    fn test_insert_and_query_reading() {
+    fn test_advertisement_log_query_and_trim() {
         let store = Store::open_in_memory().unwrap();
-        let reading = create_test_reading();
+        let t0 = time::macros::datetime!(2024-06-01 12:00 UTC);
 
-        store.insert_reading("test-device", &reading).unwrap();
+        for i in 0..5u8 {
+            store
+                .log_advertisement(
+                    if i % 2 == 0 { "a" } else { "b" },
+                    Some(-60 - i16::from(i)),
+                    &[0x21, i],
+                    t0 + time::Duration::seconds(i64::from(i)),
+                )
+                .unwrap();
+        }
+        store
+            .log_advertisement("a", None, &[0xAB; 300], t0 + time::Duration::minutes(1))
+            .unwrap();
 
-        let query = ReadingQuery::new().device("test-device");
-        let readings = store.query_readings(&query).unwrap();
+        let all = store
+            .query_advertisements(&AdvertisementQuery::new())
+            .unwrap();
+        assert_eq!(all.len(), 6);
+        assert_eq!(all[0].payload_hex(), "2100");
+        assert_eq!(all[1].rssi, Some(-61));
+        assert_eq!(all[5].payload.len(), MAX_ADVERTISEMENT_PAYLOAD);
 
-        assert_eq!(readings.len(), 1);
-        assert_eq!(readings[0].co2, 800);
-        assert_eq!(readings[0].temperature, 22.5);
+        let b = store
+            .query_advertisements(&AdvertisementQuery::new().device("b"))
+            .unwrap();
+        assert_eq!(b.len(), 2);
+        let late = store
+            .query_advertisements(&AdvertisementQuery::new().since(t0 + time::Duration::seconds(4)))
+            .unwrap();
+        assert_eq!(late.len(), 2);
+
+        // Advertisements are not tied to the devices table
+        assert!(store.list_devices().unwrap().is_empty());
+
+        assert_eq!(store.trim_advertisements(2).unwrap(), 4);
+        let kept = store
+            .query_advertisements(&AdvertisementQuery::new())
+            .unwrap();
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].payload_hex(), "2104");
+        assert_eq!(store.trim_advertisements(2).unwrap(), 0);
+
+        let json = serde_json::to_string(&kept[0]).unwrap();
+        assert!(json.contains("\"payload\":\"2104\""));
+        let parsed: StoredAdvertisement = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, kept[0]);
     }
 
     #[test]
-    fn test_get_latest_reading() {
+    fn test_alerts_query_acknowledge_and_clear() {
         let store = Store::open_in_memory().unwrap();
+        let t0 = time::macros::datetime!(2024-06-01 12:00 UTC);
 
-        let mut reading1 = create_test_reading();
-        reading1.co2 = 700;
-        store.insert_reading("test-device", &reading1).unwrap();
+        let first = store
+            .insert_alert("a", ALERT_CO2, "warning", Some(1200.0), "CO2 elevated", t0)
+            .unwrap();
+        store
+            .insert_alert(
+                "a",
+                ALERT_OFFLINE,
+                "warning",
+                None,
+                "Device offline",
+                t0 + time::Duration::minutes(5),
+            )
+            .unwrap();
+        store
+            .insert_alert("b", ALERT_CO2, "critical", Some(2100.0), "CO2 high", t0)
+            .unwrap();
 
-        let mut reading2 = create_test_reading();
-        reading2.co2 = 900;
-        store.insert_reading("test-device", &reading2).unwrap();
+        let all = store.query_alerts(&AlertQuery::new()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].kind, ALERT_OFFLINE);
+        assert_eq!(all[0].value, None);
 
-        let latest = store.get_latest_reading("test-device").unwrap().unwrap();
-        assert_eq!(latest.co2, 900);
+        let co2 = store
+            .query_alerts(&AlertQuery::new().kind(ALERT_CO2))
+            .unwrap();
+        assert_eq!(co2.len(), 2);
+        let critical = store
+            .query_alerts(&AlertQuery::new().severity("critical"))
+            .unwrap();
+        assert_eq!(critical[0].device_id, "b");
+
+        assert!(store.acknowledge_alert(first.id).unwrap());
+        assert!(!store.acknowledge_alert(9999).unwrap());
+        assert!(!store.delete_alert(9999).unwrap());
+        let open = store
+            .query_alerts(&AlertQuery::new().device("a").unacknowledged())
+            .unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].kind, ALERT_OFFLINE);
+
+        assert_eq!(
+            store
+                .acknowledge_alerts(&AlertQuery::new().unacknowledged())
+                .unwrap(),
+            2
+        );
+        assert!(
+            store
+                .query_alerts(&AlertQuery::new().unacknowledged())
+                .unwrap()
+                .is_empty()
+        );
+
+        assert_eq!(
+            store.clear_alerts(&AlertQuery::new().device("a")).unwrap(),
+            2
+        );
+        assert_eq!(store.count_device_data("b").unwrap().alerts, 1);
+        assert_eq!(store.purge_device("b").unwrap().alerts, 1);
+        assert!(store.query_alerts(&AlertQuery::new()).unwrap().is_empty());
     }
 
     #[test]
-    fn test_list_latest_readings_returns_one_row_per_device() {
+    fn test_manager_state_round_trip() {
+        use aranet_types::{DevicePriority, ManagedDeviceState};
+
         let store = Store::open_in_memory().unwrap();
+        assert_eq!(store.load_manager_state().unwrap(), None);
+
+        let mut state = ManagerState {
+            devices: vec![ManagedDeviceState {
+                id: "AA:BB".to_string(),
+                name: Some("Aranet4 12345".to_string()),
+                alias: Some("office".to_string()),
+                device_type: Some(DeviceType::Aranet4),
+                priority: DevicePriority::High,
+                auto_reconnect: true,
+                history_sync_min_battery: Some(20),
+            }],
+            health_check_interval_ms: Some(60_000),
+        };
+        store.save_manager_state(&state).unwrap();
+        assert_eq!(store.load_manager_state().unwrap(), Some(state.clone()));
 
-        let mut alpha_old = create_test_reading();
-        alpha_old.co2 = 700;
-        alpha_old.captured_at = Some(OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(1));
-        store.insert_reading("alpha", &alpha_old).unwrap();
+        // Saving again replaces the previous state
+        state.devices.clear();
+        store.save_manager_state(&state).unwrap();
+        assert_eq!(store.load_manager_state().unwrap(), Some(state));
+    }
 
-        let mut alpha_new = create_test_reading();
-        alpha_new.co2 = 900;
-        alpha_new.captured_at = Some(OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(2));
-        store.insert_reading("alpha", &alpha_new).unwrap();
+    #[test]
+    fn test_channel_values_query_and_latest() {
+        let store = Store::open_in_memory().unwrap();
+        let t0 = time::macros::datetime!(2024-06-01 12:00 UTC);
+        let t1 = t0 + time::Duration::minutes(5);
 
-        let mut beta = create_test_reading();
-        beta.co2 = 500;
-        beta.captured_at = Some(OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(3));
-        store.insert_reading("beta", &beta).unwrap();
+        store
+            .insert_channel_value("a", "dew_point", t0, 10.5)
+            .unwrap();
+        store
+            .insert_channel_value("a", "dew_point", t1, 11.0)
+            .unwrap();
+        store
+            .insert_channel_value("a", "co2_delta", t1, 350.0)
+            .unwrap();
+        store
+            .insert_channel_value("b", "dew_point", t0, 8.0)
+            .unwrap();
 
-        store.upsert_device("gamma", Some("No Reading")).unwrap();
+        let values = store
+            .query_channel_values(&ChannelQuery::new().device("a").channel("dew_point"))
+            .unwrap();
+        assert_eq!(
+            values.iter().map(|v| v.value).collect::<Vec<_>>(),
+            [10.5, 11.0]
+        );
+        assert_eq!(values[1].captured_at, t1);
 
-        let latest = store.list_latest_readings().unwrap();
+        let since = store
+            .query_channel_values(&ChannelQuery::new().since(t1))
+            .unwrap();
+        assert_eq!(since.len(), 2);
 
-        assert_eq!(latest.len(), 2);
-        let latest_by_device: std::collections::HashMap<_, _> = latest
-            .into_iter()
-            .map(|(device, reading)| (device.id, reading.co2))
+        let latest = store.latest_channel_values(None).unwrap();
+        let latest: Vec<_> = latest
+            .iter()
+            .map(|v| (v.device_id.as_str(), v.channel.as_str(), v.value))
             .collect();
-        assert_eq!(latest_by_device.get("alpha"), Some(&900));
-        assert_eq!(latest_by_device.get("beta"), Some(&500));
-        assert!(!latest_by_device.contains_key("gamma"));
+        assert_eq!(
+            latest,
+            [
+                ("a", "co2_delta", 350.0),
+                ("a", "dew_point", 11.0),
+                ("b", "dew_point", 8.0)
+            ]
+        );
+        assert_eq!(store.latest_channel_values(Some("b")).unwrap().len(), 1);
+
+        let counts = store.purge_device("a").unwrap();
+        assert_eq!(counts.channel_values, 3);
     }
 
     #[test]
-    fn test_insert_history_deduplication() {
+    fn test_annotations_overlap_and_history() {
         let store = Store::open_in_memory().unwrap();
+        let t0 = time::macros::datetime!(2024-06-01 12:00 UTC);
+        let minutes = time::Duration::minutes;
 
-        let now = OffsetDateTime::now_utc();
-        let records = vec![
-            HistoryRecord {
-                timestamp: now,
+        let records: Vec<HistoryRecord> = (0..6)
+            .map(|i| HistoryRecord {
+                timestamp: t0 + minutes(10 * i),
                 co2: 800,
-                temperature: 22.0,
+                temperature: 21.0,
                 pressure: 1013.0,
                 humidity: 45,
                 radon: None,
                 radiation_rate: None,
                 radiation_total: None,
-            },
-            HistoryRecord {
-                timestamp: now, // Same timestamp - should be deduplicated
-                co2: 850,
-                temperature: 23.0,
-                pressure: 1014.0,
-                humidity: 46,
-                radon: None,
-                radiation_rate: None,
-                radiation_total: None,
-            },
-        ];
-
-        let inserted = store.insert_history("test-device", &records).unwrap();
-        assert_eq!(inserted, 1); // Only one inserted due to dedup
+            })
+            .collect();
+        store.insert_history("a", &records).unwrap();
+
+        let window = store
+            .add_annotation(
+                "a",
+                t0 + minutes(15)..=t0 + minutes(15),
+                "Window opened",
+                None,
+            )
+            .unwrap();
+        assert!(window.is_point());
+        store
+            .add_annotation(
+                "a",
+                t0 - minutes(60)..=t0 + minutes(5),
+                "Ventilation installed",
+                Some("ventilation"),
+            )
+            .unwrap();
+        store
+            .add_annotation("a", t0 + minutes(120)..=t0 + minutes(120), "Later", None)
+            .unwrap();
+        store
+            .add_annotation("b", t0..=t0, "Other device", None)
+            .unwrap();
+        assert!(
+            store
+                .add_annotation("a", t0..=t0 - minutes(1), "Backwards", None)
+                .is_err()
+        );
 
-        let count = store.count_history(Some("test-device")).unwrap();
-        assert_eq!(count, 1);
-    }
+        // Range annotations that started before `since` still overlap
+        let overlapping = store
+            .query_annotations(
+                &AnnotationQuery::new()
+                    .device("a")
+                    .since(t0)
+                    .until(t0 + minutes(50)),
+            )
+            .unwrap();
+        assert_eq!(overlapping.len(), 2);
+        assert_eq!(overlapping[0].tag.as_deref(), Some("ventilation"));
+        assert_eq!(overlapping[1].text, "Window opened");
 
-    #[test]
-    fn test_sync_state() {
-        let store = Store::open_in_memory().unwrap();
-        store.upsert_device("test-device", None).unwrap();
+        let annotated = store
+            .query_history_annotated(&HistoryQuery::new().device("a"))
+            .unwrap();
+        assert_eq!(annotated.records.len(), 6);
+        assert_eq!(annotated.annotations, overlapping);
 
-        // Initially no sync state
-        let state = store.get_sync_state("test-device").unwrap();
-        assert!(state.is_none());
+        let tagged = store
+            .query_annotations(&AnnotationQuery::new().tag("ventilation"))
+            .unwrap();
+        assert_eq!(tagged.len(), 1);
 
-        // Update sync state
-        store.update_sync_state("test-device", 100, 100).unwrap();
+        let csv = store
+            .export_history_csv_annotated(&HistoryQuery::new().device("a").oldest_first())
+            .unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].ends_with(",annotation"));
+        assert!(lines[1].ends_with(",Ventilation installed"));
+        assert!(lines[2].ends_with(","));
+        assert!(lines[3].ends_with(",Window opened"));
+        assert!(
+            !store
+                .export_history_csv(&HistoryQuery::new().device("a"))
+                .unwrap()
+                .contains("annotation")
+        );
 
-        let state = store.get_sync_state("test-device").unwrap().unwrap();
-        assert_eq!(state.last_history_index, Some(100));
-        assert_eq!(state.total_readings, Some(100));
-        assert!(state.last_sync_at.is_some());
+        let json = store
+            .export_history_json_annotated(&HistoryQuery::new().device("a").oldest_first())
+            .unwrap();
+        let exported: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+        assert_eq!(exported[2]["annotation"], "Window opened");
+        assert!(exported[1].get("annotation").is_none());
+
+        assert!(store.delete_annotation(window.id).unwrap());
+        assert!(!store.delete_annotation(window.id).unwrap());
+        assert_eq!(store.count_device_data("a").unwrap().annotations, 2);
+        assert_eq!(store.purge_device("b").unwrap().annotations, 1);
     }
 
     #[test]
@@ -2164,6 +6153,16 @@ invalid-timestamp,test-device,800,22.5,1013.25,45,
         assert_eq!(result.imported, 1);
         assert_eq!(result.skipped, 2);
         assert_eq!(result.errors.len(), 2);
+
+        // The preview sees the same rows without importing anything
+        let preview = ImportPreview::history_csv(csv_data);
+        assert_eq!(preview.devices, vec!["valid-device".to_string()]);
+        assert_eq!((preview.history, preview.invalid), (1, 2));
+        assert_eq!(
+            preview.start,
+            Some(time::macros::datetime!(2024-01-15 11:30 UTC))
+        );
+        assert_eq!(preview.start, preview.end);
     }
 
     #[test]
@@ -2308,6 +6307,44 @@ invalid-timestamp,test-device,800,22.5,1013.25,45,
         assert_eq!(stats.avg.humidity, Some(50.0));
     }
 
+    #[test]
+    fn test_history_value_filter() {
+        let store = Store::open_in_memory().unwrap();
+
+        let base_time = OffsetDateTime::now_utc();
+        let records: Vec<HistoryRecord> = [(600, 40), (1200, 25), (1400, 55)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (co2, humidity))| HistoryRecord {
+                timestamp: base_time + time::Duration::hours(i as i64),
+                co2,
+                temperature: 21.0,
+                pressure: 1013.0,
+                humidity,
+                radon: None,
+                radiation_rate: None,
+                radiation_total: None,
+            })
+            .collect();
+        store.insert_history("test-device", &records).unwrap();
+
+        let filter = ValueFilter::parse("co2 > 1000 AND humidity < 30").unwrap();
+        let query = HistoryQuery::new().device("test-device").filter(filter);
+        let history = store.query_history(&query).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].co2, 1200);
+
+        let stats = store
+            .history_stats(&HistoryQuery::new().filter(ValueFilter::parse("co2>=1200").unwrap()))
+            .unwrap();
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.avg.co2, Some(1300.0));
+
+        // Missing values never match
+        let radon = HistoryQuery::new().filter(ValueFilter::parse("radon >= 0").unwrap());
+        assert!(store.query_history(&radon).unwrap().is_empty());
+    }
+
     #[test]
     fn test_history_stats_with_device_filter() {
         let store = Store::open_in_memory().unwrap();
@@ -2356,6 +6393,164 @@ invalid-timestamp,test-device,800,22.5,1013.25,45,
         assert_eq!(stats.avg.co2, Some(1200.0));
     }
 
+    fn history_at(timestamp: OffsetDateTime, co2: u16, radon: Option<u32>) -> HistoryRecord {
+        HistoryRecord {
+            timestamp,
+            co2,
+            temperature: 21.0,
+            pressure: 1013.0,
+            humidity: 45,
+            radon,
+            radiation_rate: None,
+            radiation_total: None,
+        }
+    }
+
+    #[test]
+    fn test_daily_stats_updated_on_insert() {
+        let store = Store::open_in_memory().unwrap();
+        let day1 = time::macros::datetime!(2026-03-01 08:00 UTC);
+        let day2 = time::macros::datetime!(2026-03-02 08:00 UTC);
+
+        store
+            .insert_history(
+                "test-device",
+                &[
+                    history_at(day1, 600, None),
+                    history_at(day1 + time::Duration::hours(1), 1200, None),
+                    history_at(day2, 1500, None),
+                ],
+            )
+            .unwrap();
+
+        let days = store.daily_stats(&HistoryQuery::new()).unwrap();
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].day, time::macros::datetime!(2026-03-01 00:00 UTC));
+        assert_eq!(days[0].count, 2);
+        assert_eq!(days[0].min.co2, Some(600.0));
+        assert_eq!(days[0].max.co2, Some(1200.0));
+        assert_eq!(days[0].avg.co2, Some(900.0));
+        assert_eq!(
+            days[0].co2_buckets,
+            ThresholdBuckets {
+                good: 1,
+                moderate: 1,
+                poor: 0
+            }
+        );
+        assert_eq!(days[1].co2_buckets.poor, 1);
+        assert!(days[0].avg.radon.is_none());
+
+        // A later insert into the same day updates it; duplicates do not count twice
+        store
+            .insert_history(
+                "test-device",
+                &[
+                    history_at(day1, 600, None),
+                    history_at(day1 + time::Duration::hours(2), 300, None),
+                ],
+            )
+            .unwrap();
+        let days = store.daily_stats(&HistoryQuery::new().until(day1)).unwrap();
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].count, 3);
+        assert_eq!(days[0].min.co2, Some(300.0));
+        assert_eq!(days[0].co2_buckets.total(), 3);
+    }
+
+    #[test]
+    fn test_exposure_stats() {
+        let store = Store::open_in_memory().unwrap();
+        let start = time::macros::datetime!(2026-03-01 23:40 UTC);
+        let mins = |m: i64| start + time::Duration::minutes(m);
+
+        store
+            .insert_history(
+                "office",
+                &[
+                    history_at(mins(0), 700, None),
+                    history_at(mins(10), 1100, None),
+                    // Crosses midnight into the next day
+                    history_at(mins(20), 1300, None),
+                    // A two-hour gap only counts for 20 minutes
+                    history_at(mins(30), 900, None),
+                    history_at(mins(150), 600, None),
+                ],
+            )
+            .unwrap();
+        store
+            .insert_history("radon", &[history_at(mins(0), 0, Some(120))])
+            .unwrap();
+
+        let days = store.exposure_stats(&HistoryQuery::new()).unwrap();
+        assert_eq!(days.len(), 2);
+        assert!(days.iter().all(|d| d.device_id == "office"));
+
+        let first = &days[0];
+        assert_eq!(first.day, time::macros::datetime!(2026-03-01 00:00 UTC));
+        assert!((first.measured_hours - 20.0 / 60.0).abs() < 1e-9);
+        assert!((first.hours_above_800 - 10.0 / 60.0).abs() < 1e-9);
+        assert!((first.ppm_hours_above_800 - 300.0 / 6.0).abs() < 1e-9);
+        assert!((first.ppm_hours_above_1000 - 100.0 / 6.0).abs() < 1e-9);
+
+        let second = &days[1];
+        // 10 min at 1300, 20 min at 900, 20 min (last span) at 600
+        assert!((second.measured_hours - 50.0 / 60.0).abs() < 1e-9);
+        assert!((second.hours_above_800 - 0.5).abs() < 1e-9);
+        assert!((second.ppm_hours_above_800 - (500.0 / 6.0 + 100.0 / 3.0)).abs() < 1e-9);
+        assert!((second.hours_above_1000 - 10.0 / 60.0).abs() < 1e-9);
+
+        let filtered = store
+            .exposure_stats(&HistoryQuery::new().device("office").until(mins(15)))
+            .unwrap();
+        assert_eq!(filtered.len(), 1);
+    }
+
+    #[test]
+    fn test_daily_stats_radon_and_filters() {
+        let store = Store::open_in_memory().unwrap();
+        let day = time::macros::datetime!(2026-03-01 12:00 UTC);
+
+        store
+            .insert_history(
+                "radon",
+                &[
+                    history_at(day, 0, Some(100)),
+                    history_at(day + time::Duration::hours(1), 0, Some(200)),
+                    history_at(day + time::Duration::hours(2), 0, Some(400)),
+                ],
+            )
+            .unwrap();
+        store
+            .insert_history("other", &[history_at(day, 800, None)])
+            .unwrap();
+
+        // since falls mid-day but still selects the whole day
+        let query = HistoryQuery::new().device("radon").since(day);
+        let days = store.daily_stats(&query).unwrap();
+        assert_eq!(days.len(), 1);
+        assert!(days[0].avg.co2.is_none());
+        assert_eq!((days[0].co2_count, days[0].radon_count), (0, 3));
+        assert_eq!(days[0].avg.radon, Some(700.0 / 3.0));
+        assert_eq!(
+            days[0].radon_buckets,
+            ThresholdBuckets {
+                good: 1,
+                moderate: 1,
+                poor: 1
+            }
+        );
+
+        store.delete_device("radon").unwrap();
+        assert!(
+            store
+                .daily_stats(&HistoryQuery::new().device("radon"))
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(store.rebuild_daily_stats(None).unwrap(), 1);
+    }
+
     #[test]
     fn test_history_stats_with_time_range() {
         let store = Store::open_in_memory().unwrap();
@@ -2673,6 +6868,106 @@ invalid-timestamp,test-device,800,22.5,1013.25,45,
         assert_eq!(readings[2].co2, 740); // 5th reading
     }
 
+    #[test]
+    fn test_measurement_coverage_counts_missed_measurements() {
+        let store = Store::open_in_memory().unwrap();
+        let start = OffsetDateTime::now_utc() - time::Duration::hours(1);
+        for (minute, counter) in [(0, 254), (1, 255), (4, 2), (4, 2)] {
+            let reading = CurrentReading {
+                captured_at: Some(start + time::Duration::minutes(minute)),
+                ..create_test_reading()
+            };
+            store
+                .insert_reading_with_counter("dev", &reading, counter)
+                .unwrap();
+        }
+        // GATT reads carry no counter and do not count
+        store.insert_reading("dev", &create_test_reading()).unwrap();
+
+        let coverage = store.measurement_coverage("dev", None).unwrap().unwrap();
+        assert_eq!(coverage.expected, 5);
+        assert_eq!(coverage.received, 3);
+        assert_eq!(coverage.missed(), 2);
+        assert!((coverage.percent() - 60.0).abs() < 1e-9);
+
+        let counters: Vec<Option<u8>> = store
+            .query_readings(&ReadingQuery::new().device("dev"))
+            .unwrap()
+            .iter()
+            .map(|r| r.counter)
+            .collect();
+        assert_eq!(counters, [None, Some(2), Some(2), Some(255), Some(254)]);
+        assert!(store.measurement_coverage("other", None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_measurement_coverage_resolves_counter_wraps() {
+        let start = OffsetDateTime::UNIX_EPOCH;
+        // 300 one-minute measurements later the counter has wrapped once
+        let samples = [(start, 10), (start + time::Duration::minutes(300), 54)];
+
+        let coverage = MeasurementCoverage::from_counters(samples, Some(60)).unwrap();
+        assert_eq!(coverage.expected, 301);
+        assert_eq!(coverage.received, 2);
+
+        let coverage = MeasurementCoverage::from_counters(samples, None).unwrap();
+        assert_eq!(coverage.expected, 45);
+    }
+
+    #[test]
+    fn test_reading_quality_filters_and_bulk_delete() {
+        let store = Store::open_in_memory().unwrap();
+
+        let mut ids = Vec::new();
+        for (co2, quality) in [
+            (800, ReadingQuality::Valid),
+            (0, ReadingQuality::Invalid),
+            (9000, ReadingQuality::Suspect),
+        ] {
+            let mut reading = create_test_reading();
+            reading.co2 = co2;
+            ids.push(
+                store
+                    .insert_reading_with_quality("dev", &reading, quality)
+                    .unwrap(),
+            );
+        }
+        store
+            .insert_reading("other", &create_test_reading())
+            .unwrap();
+
+        let latest = store.get_latest_reading("other").unwrap().unwrap();
+        assert_eq!(latest.quality, ReadingQuality::Unchecked);
+
+        let co2 = |query: &ReadingQuery| -> Vec<u16> {
+            store
+                .query_readings(&query.clone().oldest_first())
+                .unwrap()
+                .iter()
+                .map(|r| r.co2)
+                .collect()
+        };
+        let dev = ReadingQuery::new().device("dev");
+        assert_eq!(co2(&dev.clone().exclude_invalid()), [800, 9000]);
+        assert_eq!(co2(&dev.clone().quality(ReadingQuality::Suspect)), [9000]);
+
+        // Flag the spike after the fact, then drop everything known bad
+        assert!(
+            store
+                .set_reading_quality(ids[2], ReadingQuality::Invalid)
+                .unwrap()
+        );
+        assert!(
+            !store
+                .set_reading_quality(-1, ReadingQuality::Invalid)
+                .unwrap()
+        );
+        let invalid = ReadingQuery::new().quality(ReadingQuality::Invalid);
+        assert_eq!(store.delete_readings_where(&invalid).unwrap(), 2);
+        assert_eq!(co2(&dev), [800]);
+        assert_eq!(store.count_readings(None).unwrap(), 2);
+    }
+
     #[test]
     fn test_query_readings_time_range() {
         let store = Store::open_in_memory().unwrap();
@@ -3168,4 +7463,94 @@ invalid-timestamp,test-device,800,22.5,1013.25,45,
         let device = guard.get_device("contested-device").unwrap().unwrap();
         assert!(device.name.unwrap().starts_with("Name-"));
     }
+
+    #[test]
+    fn test_query_readonly_views() {
+        let store = Store::open_in_memory().unwrap();
+        let day = time::macros::datetime!(2026-03-01 08:00 UTC);
+        store
+            .insert_history(
+                "test-device",
+                &[
+                    history_at(day, 600, None),
+                    history_at(day + time::Duration::hours(1), 1200, None),
+                ],
+            )
+            .unwrap();
+
+        let result = store
+            .query_readonly(
+                "WITH high AS (SELECT * FROM v_history WHERE co2 > ?) \
+                 SELECT device_id, time, co2 FROM high",
+                &[serde_json::json!(1000)],
+            )
+            .unwrap();
+        assert_eq!(result.columns, ["device_id", "time", "co2"]);
+        assert_eq!(
+            result.rows,
+            vec![vec![
+                serde_json::json!("test-device"),
+                serde_json::json!("2026-03-01T09:00:00Z"),
+                serde_json::json!(1200),
+            ]]
+        );
+        assert!(!result.truncated);
+
+        let result = store
+            .query_readonly("SELECT day, co2_avg FROM v_daily_stats", &[])
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![vec![
+                serde_json::json!("2026-03-01"),
+                serde_json::json!(900.0)
+            ]]
+        );
+
+        // Every view can be read
+        for view in QUERY_VIEWS {
+            store
+                .query_readonly(&format!("SELECT * FROM {} LIMIT 1", view.name), &[])
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_query_readonly_refuses_everything_else() {
+        let store = Store::open_in_memory().unwrap();
+        store
+            .insert_reading("test", &create_test_reading())
+            .unwrap();
+
+        for sql in [
+            "SELECT * FROM readings",
+            "SELECT * FROM sqlite_master",
+            "DELETE FROM readings",
+            "INSERT INTO devices (id, first_seen, last_seen) VALUES ('x', 0, 0)",
+            "DROP VIEW v_readings",
+            "PRAGMA table_info(readings)",
+            "ATTACH DATABASE ':memory:' AS other",
+            "SELECT * FROM v_readings; DELETE FROM readings",
+            "CREATE TEMP TABLE t AS SELECT * FROM v_readings",
+        ] {
+            assert!(
+                matches!(store.query_readonly(sql, &[]), Err(Error::Query(_))),
+                "{sql}"
+            );
+        }
+        assert!(matches!(
+            store.query_readonly("SELECT ?", &[serde_json::json!([1])]),
+            Err(Error::Query(_))
+        ));
+
+        // Refused queries leave the store usable
+        assert_eq!(store.count_readings(None).unwrap(), 1);
+        assert_eq!(
+            store
+                .query_readonly("SELECT COUNT(*) FROM v_readings", &[])
+                .unwrap()
+                .rows,
+            vec![vec![serde_json::json!(1)]]
+        );
+    }
 }
@@ -11,6 +11,8 @@
 //! - Device information structures
 //! - UUID constants for BLE characteristics
 //! - Error types for data parsing
+//! - Smart Home advertisement encoding
+//! - Persisted device manager state
 //!
 //! # Example
 //!
@@ -20,11 +22,17 @@
 //! // Types can be used for parsing and serialization
 //! ```
 
+pub mod advertisement;
 pub mod error;
+pub mod manager_state;
+pub mod name;
 pub mod types;
 pub mod uuid;
 
+pub use advertisement::AdvertisementBuilder;
 pub use error::{ParseError, ParseResult};
+pub use manager_state::{DevicePriority, ManagedDeviceState, ManagerState, ManagerStateStore};
+pub use name::DeviceName;
 pub use types::{
     CurrentReading, CurrentReadingBuilder, DeviceInfo, DeviceInfoBuilder, DeviceType,
     HistoryRecord, HistoryRecordBuilder, MIN_CURRENT_READING_BYTES, Status,
@@ -764,6 +772,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_device_name_parse() {
+        let name = DeviceName::parse("AranetRn+ 306b8").unwrap();
+        assert_eq!(name.device_type, Some(DeviceType::AranetRadon));
+        assert_eq!(name.short_serial.as_deref(), Some("306B8"));
+        assert_eq!(name.default_alias(), "aranet-radon-306b8");
+        assert_eq!(name.to_string(), "Aranet Radon 306B8");
+
+        let name = DeviceName::parse("Aranet\u{2622} 30ED1\0").unwrap();
+        assert_eq!(name.device_type, Some(DeviceType::AranetRadiation));
+        assert_eq!(name.short_serial.as_deref(), Some("30ED1"));
+
+        // Names without a serial still parse, but never identify a unit
+        let plain = DeviceName::parse("Aranet Radiation").unwrap();
+        assert_eq!(plain.device_type, Some(DeviceType::AranetRadiation));
+        assert_eq!(plain.short_serial, None);
+        assert!(!plain.is_same_device(&plain));
+
+        let a = DeviceName::parse("Aranet4 17C3C").unwrap();
+        assert!(!a.is_same_device(&DeviceName::parse("Aranet4 17C3D").unwrap()));
+        assert!(!a.is_same_device(&DeviceName::parse("Aranet2 17C3C").unwrap()));
+
+        assert!(DeviceName::parse("").is_none());
+        assert!(DeviceName::parse("Speaker 12345").is_none());
+    }
+
     #[test]
     fn test_device_type_has_co2() {
         assert!(DeviceType::Aranet4.has_co2());
@@ -921,6 +955,49 @@ mod tests {
         assert!(err.to_string().contains("pressure"));
     }
 
+    #[test]
+    fn test_builder_for_device_rejects_cross_wired_fields() {
+        let result = CurrentReadingBuilder::for_device(DeviceType::Aranet2)
+            .co2(800)
+            .temperature(21.0)
+            .humidity(40)
+            .try_build();
+        assert!(result.unwrap_err().to_string().contains("CO2"));
+
+        let result = CurrentReadingBuilder::for_device(DeviceType::AranetRadiation)
+            .battery(90)
+            .try_build();
+        assert!(result.unwrap_err().to_string().contains("dose rate"));
+
+        let result = CurrentReadingBuilder::for_device(DeviceType::AranetRadiation)
+            .radiation_rate(0.12)
+            .radiation_total(0.5)
+            .try_build();
+        assert!(result.is_ok());
+
+        // Without a profile, the same fields are accepted
+        let result = CurrentReading::builder().co2(800).radon(50).try_build();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_parsed_readings_match_device_profiles() {
+        let data = [0u8; types::MIN_RADIATION_READING_BYTES];
+        for device_type in [
+            DeviceType::Aranet4,
+            DeviceType::Aranet2,
+            DeviceType::AranetRadon,
+            DeviceType::AranetRadiation,
+        ] {
+            let reading = CurrentReading::from_bytes_for_device(&data, device_type).unwrap();
+            assert_eq!(
+                reading.validate_for_device(device_type),
+                Ok(()),
+                "{device_type}"
+            );
+        }
+    }
+
     #[test]
     fn test_with_captured_at() {
         use time::OffsetDateTime;
@@ -0,0 +1,80 @@
+//! Persisted state of a device manager.
+//!
+//! `aranet-core`'s `DeviceManager` saves its device roster through the
+//! [`ManagerStateStore`] trait so long-running daemons can resume after a
+//! restart without rediscovering devices. The trait lives here so storage
+//! crates (such as `aranet-store`) can implement it without depending on the
+//! BLE stack.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::DeviceType;
+
+/// Device priority levels for connection management.
+///
+/// When the connection limit is reached, lower priority devices
+/// may be disconnected to make room for higher priority devices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum DevicePriority {
+    /// Low priority - may be disconnected when at capacity.
+    Low,
+    /// Normal priority (default).
+    #[default]
+    Normal,
+    /// High priority - maintain connection, disconnect lower priorities if needed.
+    High,
+    /// Critical priority - never disconnect automatically.
+    Critical,
+}
+
+/// A managed device as persisted between runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ManagedDeviceState {
+    /// Device identifier (address or platform UUID).
+    pub id: String,
+    /// Device name, if known.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name: Option<String>,
+    /// User-assigned alias.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub alias: Option<String>,
+    /// Device type, if known.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub device_type: Option<DeviceType>,
+    /// Connection priority.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub priority: DevicePriority,
+    /// Whether the device is reconnected automatically.
+    pub auto_reconnect: bool,
+    /// Per-device minimum battery level for history syncs.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub history_sync_min_battery: Option<u8>,
+}
+
+/// The persisted state of a device manager.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ManagerState {
+    /// Managed devices, in no particular order.
+    pub devices: Vec<ManagedDeviceState>,
+    /// Current adaptive health check interval in milliseconds, if adaptive
+    /// intervals are in use.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub health_check_interval_ms: Option<u64>,
+}
+
+/// Storage for a device manager's persisted state.
+pub trait ManagerStateStore {
+    /// The error returned by the storage backend.
+    type Error;
+
+    /// Replace the saved manager state.
+    fn save_manager_state(&self, state: &ManagerState) -> Result<(), Self::Error>;
+
+    /// Load the saved manager state, or `None` if nothing was saved.
+    fn load_manager_state(&self) -> Result<Option<ManagerState>, Self::Error>;
+}
@@ -0,0 +1,113 @@
+//! Structured Aranet device names.
+//!
+//! Aranet sensors advertise names such as `"Aranet4 17C3C"`: a model followed
+//! by a short hexadecimal serial that is unique per unit. [`DeviceName`] splits
+//! such a name so scan results can be matched against stored devices (whose
+//! identifier may have changed, e.g. a new CoreBluetooth UUID on macOS) and so
+//! frontends can derive a default alias.
+
+use core::fmt;
+
+use crate::types::DeviceType;
+
+/// Shortest and longest short serial seen in advertised names.
+const SHORT_SERIAL_LEN: core::ops::RangeInclusive<usize> = 4..=6;
+
+/// An advertised Aranet device name split into model and short serial.
+///
+/// # Examples
+///
+/// ```
+/// use aranet_types::{DeviceName, DeviceType};
+///
+/// let name = DeviceName::parse("Aranet4 17C3C").unwrap();
+/// assert_eq!(name.device_type, Some(DeviceType::Aranet4));
+/// assert_eq!(name.short_serial.as_deref(), Some("17C3C"));
+/// assert_eq!(name.default_alias(), "aranet4-17c3c");
+///
+/// assert!(name.is_same_device(&DeviceName::parse("aranet4 17c3c").unwrap()));
+/// assert!(DeviceName::parse("Living Room Speaker").is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DeviceName {
+    /// Device type inferred from the model part of the name.
+    pub device_type: Option<DeviceType>,
+    /// Short serial suffix, uppercased (e.g. `"17C3C"`), if the name has one.
+    pub short_serial: Option<String>,
+}
+
+impl DeviceName {
+    /// Parse an advertised name.
+    ///
+    /// Returns `None` if the name does not look like an Aranet device: the
+    /// model part must either be a known [`DeviceType`] name or contain
+    /// "Aranet".
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().trim_end_matches('\0');
+        let (model, short_serial) = match name.rsplit_once(char::is_whitespace) {
+            Some((model, suffix)) if is_short_serial(suffix) => {
+                (model.trim_end(), Some(suffix.to_ascii_uppercase()))
+            }
+            _ => (name, None),
+        };
+
+        let device_type = DeviceType::from_name(model);
+        if device_type.is_none() && !model.to_lowercase().contains("aranet") {
+            return None;
+        }
+
+        Some(Self {
+            device_type,
+            short_serial,
+        })
+    }
+
+    /// Whether two names identify the same physical device.
+    ///
+    /// Both names need a short serial; names without one (e.g. a plain
+    /// `"Aranet4"`) never match.
+    #[must_use]
+    pub fn is_same_device(&self, other: &Self) -> bool {
+        self.short_serial.is_some()
+            && self.short_serial == other.short_serial
+            && self.device_type == other.device_type
+    }
+
+    /// A lowercase, hyphenated alias such as `"aranet4-17c3c"` or
+    /// `"aranet-radon-306b8"`.
+    #[must_use]
+    pub fn default_alias(&self) -> String {
+        let model = match self.device_type {
+            Some(DeviceType::Aranet4) => "aranet4",
+            Some(DeviceType::Aranet2) => "aranet2",
+            Some(DeviceType::AranetRadon) => "aranet-radon",
+            Some(DeviceType::AranetRadiation) => "aranet-radiation",
+            None => "aranet",
+        };
+        match &self.short_serial {
+            Some(serial) => format!("{}-{}", model, serial.to_ascii_lowercase()),
+            None => model.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for DeviceName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.device_type {
+            Some(device_type) => write!(f, "{}", device_type)?,
+            None => write!(f, "Aranet")?,
+        }
+        if let Some(serial) = &self.short_serial {
+            write!(f, " {}", serial)?;
+        }
+        Ok(())
+    }
+}
+
+fn is_short_serial(s: &str) -> bool {
+    SHORT_SERIAL_LEN.contains(&s.len())
+        && s.bytes().all(|b| b.is_ascii_hexdigit())
+        // A word made only of the letters A-F (e.g. "Cafe") is not a serial
+        && s.bytes().any(|b| b.is_ascii_digit())
+}
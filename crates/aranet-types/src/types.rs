@@ -138,6 +138,28 @@ impl DeviceType {
         matches!(self, DeviceType::Aranet4 | DeviceType::AranetRadon)
     }
 
+    /// Returns how many history records the device holds before it starts
+    /// overwriting the oldest, or `None` if unknown.
+    ///
+    /// Aranet4, Aranet2 and AranetRn+ keep a ring buffer of 2016 records
+    /// (a week at the default 5-minute interval).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aranet_types::DeviceType;
+    ///
+    /// assert_eq!(DeviceType::Aranet4.history_capacity(), Some(2016));
+    /// assert_eq!(DeviceType::AranetRadiation.history_capacity(), None);
+    /// ```
+    #[must_use]
+    pub fn history_capacity(&self) -> Option<u16> {
+        match self {
+            DeviceType::Aranet4 | DeviceType::Aranet2 | DeviceType::AranetRadon => Some(2016),
+            _ => None,
+        }
+    }
+
     /// Returns the BLE characteristic UUID for reading current sensor values.
     ///
     /// - **Aranet4**: Uses `CURRENT_READINGS_DETAIL` (f0cd3001)
@@ -687,6 +709,98 @@ impl CurrentReading {
         self
     }
 
+    /// Check that the reading only carries the fields `device_type` measures.
+    ///
+    /// | Device | Must have | Must not have |
+    /// |--------|-----------|---------------|
+    /// | Aranet4 | | radon, radiation |
+    /// | Aranet2 | | CO2, pressure, radon, radiation |
+    /// | `AranetRn+` | radon | CO2, radiation |
+    /// | Aranet Radiation | dose rate, total dose | CO2, pressure, humidity, radon |
+    ///
+    /// A field that should be absent but is set usually means bytes were
+    /// parsed with the wrong device layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError::InvalidValue`] naming the first offending field.
+    pub fn validate_for_device(&self, device_type: DeviceType) -> Result<(), ParseError> {
+        let has_radon = self.radon.is_some()
+            || self.radon_avg_24h.is_some()
+            || self.radon_avg_7d.is_some()
+            || self.radon_avg_30d.is_some();
+        let has_radiation = self.radiation_rate.is_some() || self.radiation_total.is_some();
+
+        let unexpected = |field: &str| {
+            Err(ParseError::InvalidValue(format!(
+                "{device_type} reading must not carry {field}"
+            )))
+        };
+        let missing = |field: &str| {
+            Err(ParseError::InvalidValue(format!(
+                "{device_type} reading is missing {field}"
+            )))
+        };
+
+        match device_type {
+            DeviceType::Aranet4 => {
+                if has_radon {
+                    return unexpected("radon");
+                }
+                if has_radiation {
+                    return unexpected("radiation");
+                }
+            }
+            DeviceType::Aranet2 => {
+                if self.co2 != 0 {
+                    return unexpected("CO2");
+                }
+                if self.pressure != 0.0 {
+                    return unexpected("pressure");
+                }
+                if has_radon {
+                    return unexpected("radon");
+                }
+                if has_radiation {
+                    return unexpected("radiation");
+                }
+            }
+            DeviceType::AranetRadon => {
+                if self.radon.is_none() {
+                    return missing("radon");
+                }
+                if self.co2 != 0 {
+                    return unexpected("CO2");
+                }
+                if has_radiation {
+                    return unexpected("radiation");
+                }
+            }
+            DeviceType::AranetRadiation => {
+                if self.radiation_rate.is_none() {
+                    return missing("dose rate");
+                }
+                if self.radiation_total.is_none() {
+                    return missing("total dose");
+                }
+                if self.co2 != 0 {
+                    return unexpected("CO2");
+                }
+                if self.pressure != 0.0 {
+                    return unexpected("pressure");
+                }
+                if self.humidity != 0 {
+                    return unexpected("humidity");
+                }
+                if has_radon {
+                    return unexpected("radon");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Create a builder for constructing `CurrentReading` with optional fields.
     pub fn builder() -> CurrentReadingBuilder {
         CurrentReadingBuilder::default()
@@ -696,14 +810,26 @@ impl CurrentReading {
 /// Builder for constructing `CurrentReading` with device-specific fields.
 ///
 /// Use [`build`](Self::build) for unchecked construction, or [`try_build`](Self::try_build)
-/// for validation of field values.
+/// for validation of field values. Start from [`for_device`](Self::for_device)
+/// to also check that the fields match what that device type measures.
 #[derive(Debug, Default)]
 #[must_use]
 pub struct CurrentReadingBuilder {
     reading: CurrentReading,
+    device_type: Option<DeviceType>,
 }
 
 impl CurrentReadingBuilder {
+    /// Create a builder whose [`try_build`](Self::try_build) also applies the
+    /// validation profile for `device_type`
+    /// (see [`CurrentReading::validate_for_device`]).
+    pub fn for_device(device_type: DeviceType) -> Self {
+        Self {
+            reading: CurrentReading::default(),
+            device_type: Some(device_type),
+        }
+    }
+
     /// Set CO2 concentration (Aranet4).
     pub fn co2(mut self, co2: u16) -> Self {
         self.reading.co2 = co2;
@@ -807,6 +933,8 @@ impl CurrentReadingBuilder {
     /// - `battery` is 0-100
     /// - `temperature` is within reasonable range (-40 to 100°C)
     /// - `pressure` is within reasonable range (800-1200 hPa) or 0
+    /// - the device type profile, if the builder was created with
+    ///   [`for_device`](Self::for_device)
     ///
     /// # Errors
     ///
@@ -862,6 +990,10 @@ impl CurrentReadingBuilder {
             )));
         }
 
+        if let Some(device_type) = self.device_type {
+            self.reading.validate_for_device(device_type)?;
+        }
+
         Ok(self.reading)
     }
 }